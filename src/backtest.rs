@@ -0,0 +1,560 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use anyhow::Result;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::monitor::{
+    ExitReason, PriceMonitor, SwapObservation, TakeProfitStopLossConfig, WalletMonitor,
+};
+use crate::solana_streamer_sdk::streaming::event_parser::UnifiedEvent;
+use crate::trading::common::{FillModel, MarketSnapshot};
+use crate::trading::factory::DexType;
+
+/// 回测配置，金额单位均为 lamports
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BacktestConfig {
+    pub starting_sol: u64,
+    /// 协议手续费（基点），买卖都按成交额扣除
+    pub fee_basis_points: u64,
+    /// 模拟滑点（基点），成交价相对触发成交的价格更差
+    pub slippage_basis_points: u64,
+    /// 每笔交易的固定成本，如交易费、优先费和小费
+    pub fee_lamports_per_trade: u64,
+    /// 止盈止损，买入后由 `PriceMonitor` 监控，触发时全部卖出
+    pub exits: Option<TakeProfitStopLossConfig>,
+    /// 成交模型，手续费、滑点和固定成本在模型的成交结果上再扣除
+    pub fill_model: FillModel,
+}
+
+impl Default for BacktestConfig {
+    fn default() -> Self {
+        Self {
+            starting_sol: 10_000_000_000,
+            fee_basis_points: 100,
+            slippage_basis_points: 100,
+            fee_lamports_per_trade: 1_005_000,
+            exits: None,
+            fill_model: FillModel::MidPrice,
+        }
+    }
+}
+
+/// 策略在回测中发出的订单
+#[derive(Debug, Clone, PartialEq)]
+pub enum BacktestOrder {
+    Buy {
+        dex_type: DexType,
+        mint: Pubkey,
+        sol_amount: u64,
+    },
+    /// 卖出持仓的比例（基点）
+    Sell {
+        mint: Pubkey,
+        basis_points: u64,
+    },
+}
+
+/// 模拟持仓
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulatedPosition {
+    pub dex_type: DexType,
+    pub token_amount: u64,
+    /// 剩余持仓的成本（lamports），含手续费
+    pub cost_basis: u64,
+    pub entry_slot: u64,
+}
+
+/// 回测账户状态，策略可据此决定订单
+#[derive(Debug, Clone, Default)]
+pub struct Portfolio {
+    pub cash: u64,
+    pub positions: HashMap<Pubkey, SimulatedPosition>,
+    /// 各代币最近的成交价（lamports / 代币最小单位）
+    pub prices: HashMap<Pubkey, f64>,
+}
+
+impl Portfolio {
+    /// 现金加持仓按最近成交价计算的市值
+    pub fn equity(&self) -> u64 {
+        let positions: f64 = self
+            .positions
+            .iter()
+            .map(|(mint, position)| {
+                position.token_amount as f64 * self.prices.get(mint).copied().unwrap_or_default()
+            })
+            .sum();
+        self.cash + positions as u64
+    }
+}
+
+/// 回测中的策略，每笔历史成交调用一次
+pub trait BacktestStrategy {
+    fn on_swap(&mut self, swap: &SwapObservation, portfolio: &Portfolio) -> Vec<BacktestOrder>;
+}
+
+impl<F> BacktestStrategy for F
+where
+    F: FnMut(&SwapObservation, &Portfolio) -> Vec<BacktestOrder>,
+{
+    fn on_swap(&mut self, swap: &SwapObservation, portfolio: &Portfolio) -> Vec<BacktestOrder> {
+        self(swap, portfolio)
+    }
+}
+
+/// 用历史成交回测跟单配置：目标钱包买入时按配置跟买，卖出时全部卖出，
+/// 按余额计算跟买金额时使用模拟现金
+///
+/// 模拟持仓卖出（含止盈止损）后清除该代币的敞口，与实盘清仓后调用 `exposure().close` 一致
+impl BacktestStrategy for WalletMonitor {
+    fn on_swap(&mut self, swap: &SwapObservation, portfolio: &Portfolio) -> Vec<BacktestOrder> {
+        let Some(swap) = self.observe(swap.clone()) else {
+            return vec![];
+        };
+        if !portfolio.positions.contains_key(&swap.mint) {
+            self.exposure().close(&swap.mint);
+        }
+        if !swap.is_buy {
+            return match portfolio.positions.contains_key(&swap.mint) {
+                true => vec![BacktestOrder::Sell { mint: swap.mint, basis_points: 10_000 }],
+                false => vec![],
+            };
+        }
+        self.copy_buy(&swap, portfolio.cash)
+            .map(|order| BacktestOrder::Buy {
+                dex_type: order.dex_type,
+                mint: order.mint,
+                sol_amount: order.sol_amount,
+            })
+            .into_iter()
+            .collect()
+    }
+}
+
+/// 平仓（含部分卖出）的交易记录
+#[derive(Debug, Clone, PartialEq)]
+pub struct BacktestTrade {
+    pub dex_type: DexType,
+    pub mint: Pubkey,
+    pub entry_slot: u64,
+    pub exit_slot: u64,
+    pub token_amount: u64,
+    /// 卖出部分的成本（lamports）
+    pub cost: u64,
+    /// 扣除费用后卖出所得（lamports）
+    pub proceeds: u64,
+    pub pnl: i64,
+    /// 由止盈止损触发时的原因，策略卖出时为 None
+    pub exit_reason: Option<ExitReason>,
+}
+
+/// 回测结果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BacktestReport {
+    pub starting_sol: u64,
+    /// 结束时现金加持仓市值
+    pub ending_equity: u64,
+    /// 已平仓盈亏
+    pub realized_pnl: i64,
+    /// 未平仓持仓按最近成交价计算的浮动盈亏
+    pub unrealized_pnl: i64,
+    pub trades: Vec<BacktestTrade>,
+    pub buys: usize,
+    /// 现金不足、没有价格、没有持仓或成交模型无法成交而未执行的订单
+    pub rejected_orders: usize,
+    /// 权益从最高点回撤的最大值（lamports）
+    pub max_drawdown: u64,
+    /// 最大回撤占当时最高权益的比例（基点）
+    pub max_drawdown_basis_points: u64,
+}
+
+impl BacktestReport {
+    pub fn total_pnl(&self) -> i64 {
+        self.realized_pnl + self.unrealized_pnl
+    }
+
+    pub fn wins(&self) -> usize {
+        self.trades.iter().filter(|trade| trade.pnl > 0).count()
+    }
+
+    /// 盈利交易占已平仓交易的比例，没有交易时为 0
+    pub fn win_rate(&self) -> f64 {
+        if self.trades.is_empty() {
+            return 0.0;
+        }
+        self.wins() as f64 / self.trades.len() as f64
+    }
+}
+
+/// 回测引擎：按 slot 顺序回放历史成交，策略发出的订单按配置的成交模型模拟执行
+///
+/// 订单在触发它的那笔成交之后立即执行，再扣除手续费、滑点和固定成本。
+/// `FillModel::MidPrice` 以该代币最近的成交价成交，不考虑自身交易对池子价格的影响，
+/// 大额订单的结果会偏乐观；`ReserveImpact` 按回放数据中的 SOL 储备计入价格冲击，
+/// `NextTrade` 以该代币的下一笔成交价成交
+pub struct Backtester {
+    config: BacktestConfig,
+}
+
+impl Backtester {
+    pub fn new(config: BacktestConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn run(
+        &self,
+        swaps: impl IntoIterator<Item = SwapObservation>,
+        strategy: &mut impl BacktestStrategy,
+    ) -> BacktestReport {
+        let mut swaps: Vec<SwapObservation> = swaps.into_iter().collect();
+        swaps.sort_by_key(|swap| swap.slot);
+        swaps.retain(|swap| swap.token_amount > 0);
+        let mut market = ReplayMarket::new(&swaps);
+        let monitor = self.config.exits.map(PriceMonitor::new);
+        let mut portfolio = Portfolio { cash: self.config.starting_sol, ..Default::default() };
+        let mut report =
+            BacktestReport { starting_sol: self.config.starting_sol, ..Default::default() };
+        let mut peak = self.config.starting_sol;
+
+        for (index, swap) in swaps.iter().enumerate() {
+            portfolio.prices.insert(swap.mint, swap_price(swap));
+            if let Some(sol_reserve) = swap.sol_reserve {
+                market.sol_reserves.insert(swap.mint, sol_reserve);
+            }
+
+            if let Some(signal) = monitor.as_ref().and_then(|monitor| monitor.observe(swap)) {
+                let order = BacktestOrder::Sell {
+                    mint: signal.position.mint,
+                    basis_points: (signal.sell_percent * 100.0) as u64,
+                };
+                let reason = Some(signal.reason);
+                self.execute(&order, &market, index, reason, &mut portfolio, &mut report);
+            }
+            for order in strategy.on_swap(swap, &portfolio) {
+                let opened =
+                    self.execute(&order, &market, index, None, &mut portfolio, &mut report);
+                match (&order, &monitor) {
+                    (BacktestOrder::Buy { mint, .. }, Some(monitor)) if opened => {
+                        let position = &portfolio.positions[mint];
+                        // 入场价按含费用的成本计算，与实盘按成交结果开仓一致
+                        let entry = position.cost_basis as f64 / position.token_amount as f64;
+                        if monitor.position(mint).is_none() {
+                            monitor.open_position(*mint, entry);
+                        }
+                    }
+                    (BacktestOrder::Sell { mint, .. }, Some(monitor))
+                        if !portfolio.positions.contains_key(mint) =>
+                    {
+                        monitor.close_position(mint);
+                    }
+                    _ => {}
+                }
+            }
+
+            let equity = portfolio.equity();
+            peak = peak.max(equity);
+            if peak - equity > report.max_drawdown {
+                report.max_drawdown = peak - equity;
+                report.max_drawdown_basis_points =
+                    ((peak - equity) as u128 * 10_000 / peak.max(1) as u128) as u64;
+            }
+        }
+
+        report.ending_equity = portfolio.equity();
+        report.unrealized_pnl = portfolio
+            .positions
+            .iter()
+            .map(|(mint, position)| {
+                let value = position.token_amount as f64 * portfolio.prices[mint];
+                value as i64 - position.cost_basis as i64
+            })
+            .sum();
+        report
+    }
+
+    /// 在第 `index` 笔成交之后模拟执行订单，成功时返回 true
+    fn execute(
+        &self,
+        order: &BacktestOrder,
+        market: &ReplayMarket,
+        index: usize,
+        exit_reason: Option<ExitReason>,
+        portfolio: &mut Portfolio,
+        report: &mut BacktestReport,
+    ) -> bool {
+        let haircut = 10_000u64
+            .saturating_sub(self.config.fee_basis_points + self.config.slippage_basis_points)
+            as f64
+            / 10_000.0;
+        let slot = market.swaps[index].slot;
+        match order {
+            BacktestOrder::Buy { dex_type, mint, sol_amount } => {
+                let cost = sol_amount + self.config.fee_lamports_per_trade;
+                let Some(price) = portfolio.prices.get(mint).copied() else {
+                    report.rejected_orders += 1;
+                    return false;
+                };
+                if cost > portfolio.cash || *sol_amount == 0 {
+                    report.rejected_orders += 1;
+                    return false;
+                }
+                let snapshot = market.snapshot(mint, price, index);
+                let sol_in = (*sol_amount as f64 * haircut) as u64;
+                let token_amount = match self.config.fill_model.simulate_buy(&snapshot, sol_in) {
+                    Ok(token_amount) if token_amount > 0 => token_amount,
+                    _ => {
+                        report.rejected_orders += 1;
+                        return false;
+                    }
+                };
+                portfolio.cash -= cost;
+                let position = portfolio.positions.entry(*mint).or_insert(SimulatedPosition {
+                    dex_type: dex_type.clone(),
+                    token_amount: 0,
+                    cost_basis: 0,
+                    entry_slot: slot,
+                });
+                position.token_amount += token_amount;
+                position.cost_basis += cost;
+                report.buys += 1;
+                true
+            }
+            BacktestOrder::Sell { mint, basis_points } => {
+                let (Some(position), Some(price)) =
+                    (portfolio.positions.get_mut(mint), portfolio.prices.get(mint).copied())
+                else {
+                    report.rejected_orders += 1;
+                    return false;
+                };
+                let token_amount = (position.token_amount as u128
+                    * (*basis_points).min(10_000) as u128
+                    / 10_000) as u64;
+                if token_amount == 0 {
+                    report.rejected_orders += 1;
+                    return false;
+                }
+                let snapshot = market.snapshot(mint, price, index);
+                let Ok(sol_out) = self.config.fill_model.simulate_sell(&snapshot, token_amount)
+                else {
+                    report.rejected_orders += 1;
+                    return false;
+                };
+                let cost = (position.cost_basis as u128 * token_amount as u128
+                    / position.token_amount as u128) as u64;
+                let proceeds = ((sol_out as f64 * haircut) as u64)
+                    .saturating_sub(self.config.fee_lamports_per_trade);
+                let pnl = proceeds as i64 - cost as i64;
+                report.trades.push(BacktestTrade {
+                    dex_type: position.dex_type.clone(),
+                    mint: *mint,
+                    entry_slot: position.entry_slot,
+                    exit_slot: slot,
+                    token_amount,
+                    cost,
+                    proceeds,
+                    pnl,
+                    exit_reason,
+                });
+                report.realized_pnl += pnl;
+                portfolio.cash += proceeds;
+                position.token_amount -= token_amount;
+                position.cost_basis -= cost;
+                if position.token_amount == 0 {
+                    portfolio.positions.remove(mint);
+                }
+                true
+            }
+        }
+    }
+}
+
+/// 成交价，lamports / 代币最小单位
+fn swap_price(swap: &SwapObservation) -> f64 {
+    swap.sol_amount as f64 / swap.token_amount as f64
+}
+
+/// 回放中各代币的市场状态，用于构造成交模型的快照
+struct ReplayMarket<'a> {
+    swaps: &'a [SwapObservation],
+    /// 各代币的成交在 `swaps` 中的下标，按顺序排列
+    trades_by_mint: HashMap<Pubkey, Vec<usize>>,
+    /// 各代币最近一次回放到的池子 SOL 储备
+    sol_reserves: HashMap<Pubkey, u64>,
+}
+
+impl<'a> ReplayMarket<'a> {
+    fn new(swaps: &'a [SwapObservation]) -> Self {
+        let mut trades_by_mint: HashMap<Pubkey, Vec<usize>> = HashMap::new();
+        for (index, swap) in swaps.iter().enumerate() {
+            trades_by_mint.entry(swap.mint).or_default().push(index);
+        }
+        Self { swaps, trades_by_mint, sol_reserves: HashMap::new() }
+    }
+
+    /// 第 `index` 笔成交之后 `mint` 的市场快照，代币储备按 SOL 储备和最近成交价推算，
+    /// 没有储备数据时只有按价格成交的模型可用
+    fn snapshot(&self, mint: &Pubkey, price: f64, index: usize) -> MarketSnapshot {
+        let sol_reserves = self.sol_reserves.get(mint).copied().unwrap_or_default();
+        let token_reserves = match sol_reserves {
+            0 => 0,
+            sol_reserves => (sol_reserves as f64 / price) as u64,
+        };
+        let next_trade_price = self.trades_by_mint.get(mint).and_then(|indices| {
+            let next = indices.partition_point(|trade| *trade <= index);
+            indices.get(next).map(|trade| swap_price(&self.swaps[*trade]))
+        });
+        MarketSnapshot { sol_reserves, token_reserves, spot_price: Some(price), next_trade_price }
+    }
+}
+
+/// 把流事件转换为回测用的成交，非 SOL 计价的交易和其他事件被忽略
+pub fn swaps_from_events<'a>(
+    events: impl IntoIterator<Item = &'a dyn UnifiedEvent>,
+) -> Vec<SwapObservation> {
+    events.into_iter().filter_map(SwapObservation::from_event).collect()
+}
+
+/// 按 JSON Lines 格式保存成交记录，供之后回测使用
+pub fn save_swaps(path: impl AsRef<Path>, swaps: &[SwapObservation]) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    for swap in swaps {
+        serde_json::to_writer(&mut writer, swap)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// 读取 `save_swaps` 保存的成交记录
+pub fn load_swaps(path: impl AsRef<Path>) -> Result<Vec<SwapObservation>> {
+    BufReader::new(File::open(path)?)
+        .lines()
+        .filter(|line| !line.as_ref().is_ok_and(|line| line.trim().is_empty()))
+        .map(|line| Ok(serde_json::from_str(&line?)?))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn swap(mint: Pubkey, slot: u64, price: u64) -> SwapObservation {
+        SwapObservation { mint, sol_amount: price * 1_000, slot, ..SwapObservation::for_test() }
+    }
+
+    #[test]
+    fn test_backtest_take_profit_and_drawdown() {
+        let (winner, loser) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let config = BacktestConfig {
+            starting_sol: 10_000,
+            fee_basis_points: 0,
+            slippage_basis_points: 0,
+            fee_lamports_per_trade: 0,
+            exits: Some(TakeProfitStopLossConfig {
+                take_profit_percent: Some(100.0),
+                stop_loss_percent: Some(50.0),
+                ..Default::default()
+            }),
+            fill_model: FillModel::MidPrice,
+        };
+        // 每个代币第一次出现时买入 1000 lamports
+        let mut bought = std::collections::HashSet::new();
+        let mut strategy = |swap: &SwapObservation, _: &Portfolio| {
+            if bought.insert(swap.mint) {
+                vec![BacktestOrder::Buy {
+                    dex_type: swap.dex_type.clone(),
+                    mint: swap.mint,
+                    sol_amount: 1_000,
+                }]
+            } else {
+                vec![]
+            }
+        };
+        let swaps = vec![
+            swap(winner, 1, 10),
+            swap(loser, 2, 10),
+            swap(loser, 3, 4),
+            swap(winner, 4, 25),
+            swap(loser, 5, 8),
+        ];
+
+        let report = Backtester::new(config).run(swaps, &mut strategy);
+        assert_eq!(report.buys, 2);
+        assert_eq!(
+            report
+                .trades
+                .iter()
+                .map(|trade| (trade.mint, trade.pnl, trade.exit_reason))
+                .collect::<Vec<_>>(),
+            vec![
+                (loser, -600, Some(ExitReason::StopLoss)),
+                (winner, 1_500, Some(ExitReason::TakeProfit))
+            ]
+        );
+        assert_eq!((report.realized_pnl, report.win_rate()), (900, 0.5));
+        // 亏损代币跌到 4 时权益从 10000 回撤到 9400
+        assert_eq!((report.max_drawdown, report.max_drawdown_basis_points), (600, 600));
+        assert_eq!((report.ending_equity, report.unrealized_pnl), (10_900, 0));
+    }
+
+    #[test]
+    fn test_backtest_fill_models() {
+        let mint = Pubkey::new_unique();
+        let with_reserve = |slot: u64, price: u64| SwapObservation {
+            sol_reserve: Some(100_000),
+            ..swap(mint, slot, price)
+        };
+        let run = |fill_model: FillModel, swaps: Vec<SwapObservation>| {
+            let config = BacktestConfig {
+                starting_sol: 100_000,
+                fee_basis_points: 0,
+                slippage_basis_points: 0,
+                fee_lamports_per_trade: 0,
+                exits: None,
+                fill_model,
+            };
+            // 第一笔成交时买入 10000 lamports，第二笔成交时全部卖出
+            let mut strategy = |swap: &SwapObservation, _: &Portfolio| match swap.slot {
+                1 => vec![BacktestOrder::Buy {
+                    dex_type: swap.dex_type.clone(),
+                    mint: swap.mint,
+                    sol_amount: 10_000,
+                }],
+                2 => vec![BacktestOrder::Sell { mint: swap.mint, basis_points: 10_000 }],
+                _ => vec![],
+            };
+            Backtester::new(config).run(swaps, &mut strategy)
+        };
+
+        let mid = run(FillModel::MidPrice, vec![with_reserve(1, 10), with_reserve(2, 10)]);
+        assert_eq!((mid.trades[0].token_amount, mid.realized_pnl), (1_000, 0));
+
+        // 买卖都占储备的 10%，价格冲击使得到的代币和卖出所得都更少
+        let impact = run(
+            FillModel::ReserveImpact { fee_bps: 0 },
+            vec![with_reserve(1, 10), with_reserve(2, 10)],
+        );
+        assert_eq!(impact.trades[0].token_amount, 909);
+        assert!(impact.realized_pnl < 0);
+        // 没有储备数据时无法计算价格冲击
+        let no_reserve =
+            run(FillModel::ReserveImpact { fee_bps: 0 }, vec![swap(mint, 1, 10), swap(mint, 2, 10)]);
+        assert_eq!((no_reserve.buys, no_reserve.rejected_orders), (0, 2));
+
+        // 买入按下一笔成交价 20 成交，卖出时已没有下一笔成交
+        let next = run(FillModel::NextTrade, vec![swap(mint, 1, 10), swap(mint, 2, 20)]);
+        assert_eq!((next.buys, next.rejected_orders), (1, 1));
+        assert_eq!(next.unrealized_pnl, 0);
+    }
+
+    #[test]
+    fn test_swaps_round_trip_through_file() {
+        let path = std::env::temp_dir().join(format!("swaps-{}.jsonl", Pubkey::new_unique()));
+        let swaps = vec![swap(Pubkey::new_unique(), 1, 10), swap(Pubkey::new_unique(), 2, 20)];
+        save_swaps(&path, &swaps).unwrap();
+        assert_eq!(load_swaps(&path).unwrap(), swaps);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,621 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use chrono::FixedOffset;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Keypair};
+
+use crate::common::{
+    trading_day::utc_offset, ExecutionMode, PriorityFee, PriorityFeeMode, SpendLimit, SubmissionMode, TradeConfig, WsolPolicy, DEFAULT_REGION_PROBE_INTERVAL_MS,
+};
+use crate::swqos::{
+    health::SwqosHealthConfig,
+    provider::{get_swqos_provider, register_swqos_provider, SwqosProvider},
+    rate_limit::SwqosRateLimit, tip_oracle::TipOracleConfig, SwqosConfig, SwqosType,
+};
+use crate::trading::common::{AdaptiveSlippageConfig, FillModel, TipEscalationConfig};
+use crate::trading::factory::DexType;
+use crate::SolanaTrade;
+
+/// Step-by-step construction of a [`SolanaTrade`] instance
+///
+/// Unlike `SolanaTrade::new`, `build()` validates the configuration up front and returns
+/// an error instead of failing later during a trade.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let solana_trade = SolanaTradeBuilder::new()
+///     .payer(Arc::new(payer))
+///     .rpc_url("https://api.mainnet-beta.solana.com")
+///     .swqos_config(SwqosConfig::Jito("".to_string(), SwqosRegion::Frankfurt))
+///     .priority_fee(PriorityFee::default())
+///     .build()
+///     .await?;
+/// ```
+pub struct SolanaTradeBuilder {
+    payer: Option<Arc<Keypair>>,
+    fee_payer: Option<Arc<Keypair>>,
+    rpc_url: Option<String>,
+    read_rpc_url: Option<String>,
+    commitment: CommitmentConfig,
+    swqos_configs: Vec<SwqosConfig>,
+    /// Custom providers added by `swqos_provider`, registered once `build` has validated the config
+    swqos_providers: Vec<Arc<dyn SwqosProvider>>,
+    priority_fee: PriorityFee,
+    lookup_table_key: Option<Pubkey>,
+    priority_fee_api_url: Option<String>,
+    disabled_dex_types: Vec<DexType>,
+    auto_tip: Option<TipOracleConfig>,
+    dex_priority_fees: HashMap<DexType, PriorityFee>,
+    spend_limit: SpendLimit,
+    adaptive_slippage: Option<AdaptiveSlippageConfig>,
+    submission_mode: SubmissionMode,
+    auto_route_migrated: bool,
+    wsol_policy: WsolPolicy,
+    race_submissions: bool,
+    swqos_health_check: Option<SwqosHealthConfig>,
+    swqos_rate_limits: HashMap<SwqosType, SwqosRateLimit>,
+    tip_account_refresh_ms: Option<u64>,
+    region_probe_interval_ms: u64,
+    rpc_fallback: bool,
+    tip_escalation: Option<TipEscalationConfig>,
+    execution_mode: ExecutionMode,
+    paper_fill_model: Option<FillModel>,
+    reporting_timezone: FixedOffset,
+}
+
+impl Default for SolanaTradeBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SolanaTradeBuilder {
+    pub fn new() -> Self {
+        Self {
+            payer: None,
+            fee_payer: None,
+            rpc_url: None,
+            read_rpc_url: None,
+            commitment: CommitmentConfig::confirmed(),
+            swqos_configs: vec![],
+            swqos_providers: vec![],
+            priority_fee: PriorityFee::default(),
+            lookup_table_key: None,
+            priority_fee_api_url: None,
+            disabled_dex_types: vec![],
+            auto_tip: None,
+            dex_priority_fees: HashMap::new(),
+            spend_limit: SpendLimit::default(),
+            adaptive_slippage: None,
+            submission_mode: SubmissionMode::default(),
+            auto_route_migrated: true,
+            wsol_policy: WsolPolicy::default(),
+            race_submissions: false,
+            swqos_health_check: None,
+            swqos_rate_limits: HashMap::new(),
+            tip_account_refresh_ms: None,
+            region_probe_interval_ms: DEFAULT_REGION_PROBE_INTERVAL_MS,
+            rpc_fallback: false,
+            tip_escalation: None,
+            execution_mode: ExecutionMode::default(),
+            paper_fill_model: None,
+            reporting_timezone: utc_offset(),
+        }
+    }
+
+    /// Start from an existing trade config
+    pub fn from_config(trade_config: TradeConfig) -> Self {
+        Self {
+            payer: None,
+            fee_payer: None,
+            rpc_url: Some(trade_config.rpc_url),
+            read_rpc_url: trade_config.read_rpc_url,
+            commitment: trade_config.commitment,
+            swqos_configs: trade_config.swqos_configs,
+            swqos_providers: vec![],
+            priority_fee: trade_config.priority_fee,
+            lookup_table_key: trade_config.lookup_table_key,
+            priority_fee_api_url: trade_config.priority_fee_api_url,
+            disabled_dex_types: trade_config.disabled_dex_types,
+            auto_tip: trade_config.auto_tip,
+            dex_priority_fees: trade_config.dex_priority_fees,
+            spend_limit: trade_config.spend_limit,
+            adaptive_slippage: trade_config.adaptive_slippage,
+            submission_mode: trade_config.submission_mode,
+            auto_route_migrated: trade_config.auto_route_migrated,
+            wsol_policy: trade_config.wsol_policy,
+            race_submissions: trade_config.race_submissions,
+            swqos_health_check: trade_config.swqos_health_check,
+            swqos_rate_limits: trade_config.swqos_rate_limits,
+            tip_account_refresh_ms: trade_config.tip_account_refresh_ms,
+            region_probe_interval_ms: trade_config.region_probe_interval_ms,
+            rpc_fallback: trade_config.rpc_fallback,
+            tip_escalation: trade_config.tip_escalation,
+            execution_mode: trade_config.execution_mode,
+            paper_fill_model: trade_config.paper_fill_model,
+            reporting_timezone: trade_config.reporting_timezone,
+        }
+    }
+
+    /// Keypair that signs and pays for trades
+    pub fn payer(mut self, payer: Arc<Keypair>) -> Self {
+        self.payer = Some(payer);
+        self
+    }
+
+    /// Keypair that pays network fees and tips, defaults to the payer
+    pub fn fee_payer(mut self, fee_payer: Arc<Keypair>) -> Self {
+        self.fee_payer = Some(fee_payer);
+        self
+    }
+
+    /// RPC endpoint used for queries and confirmation
+    pub fn rpc_url(mut self, rpc_url: impl Into<String>) -> Self {
+        self.rpc_url = Some(rpc_url.into());
+        self
+    }
+
+    /// Secondary RPC endpoint for heavy queries such as pool discovery
+    ///
+    /// Keeps `getProgramAccounts` off the low-latency endpoint, which then only serves
+    /// blockhashes, quotes and submission.
+    pub fn read_rpc_url(mut self, read_rpc_url: impl Into<String>) -> Self {
+        self.read_rpc_url = Some(read_rpc_url.into());
+        self
+    }
+
+    /// Commitment level for the RPC client (defaults to confirmed)
+    pub fn commitment(mut self, commitment: CommitmentConfig) -> Self {
+        self.commitment = commitment;
+        self
+    }
+
+    /// Add a SWQOS endpoint
+    pub fn swqos_config(mut self, swqos_config: SwqosConfig) -> Self {
+        self.swqos_configs.push(swqos_config);
+        self
+    }
+
+    /// Add a custom SWQOS provider as an endpoint
+    ///
+    /// The provider is registered globally by `build`, and only if the config is valid.
+    pub fn swqos_provider(mut self, provider: Arc<dyn SwqosProvider>) -> Self {
+        self.swqos_configs.push(SwqosConfig::Custom(provider.name().to_string()));
+        self.swqos_providers.push(provider);
+        self
+    }
+
+    /// Replace all SWQOS endpoints
+    pub fn swqos_configs(mut self, swqos_configs: Vec<SwqosConfig>) -> Self {
+        self.swqos_configs = swqos_configs;
+        self
+    }
+
+    pub fn priority_fee(mut self, priority_fee: PriorityFee) -> Self {
+        self.priority_fee = priority_fee;
+        self
+    }
+
+    /// Override the priority fee for a single DEX
+    pub fn dex_priority_fee(mut self, dex_type: DexType, priority_fee: PriorityFee) -> Self {
+        self.dex_priority_fees.insert(dex_type, priority_fee);
+        self
+    }
+
+    /// Cap the SOL spent per buy and per trading day, in lamports
+    ///
+    /// The day follows `reporting_timezone`, UTC by default
+    pub fn spend_limit(mut self, spend_limit: SpendLimit) -> Self {
+        self.spend_limit = spend_limit;
+        self
+    }
+
+    /// Retry slippage-exceeded trades with progressively wider slippage
+    pub fn adaptive_slippage(mut self, adaptive_slippage: AdaptiveSlippageConfig) -> Self {
+        self.adaptive_slippage = Some(adaptive_slippage);
+        self
+    }
+
+    /// Return from `buy` / `sell` before confirmation, see `SubmissionMode::FireAndTrack`
+    pub fn submission_mode(mut self, submission_mode: SubmissionMode) -> Self {
+        self.submission_mode = submission_mode;
+        self
+    }
+
+    /// Route PumpFun trades on a completed bonding curve to the migrated PumpSwap pool (default: on)
+    pub fn auto_route_migrated(mut self, auto_route_migrated: bool) -> Self {
+        self.auto_route_migrated = auto_route_migrated;
+        self
+    }
+
+    /// How trades wrap and unwrap SOL, see `WsolPolicy` (default: `UnwrapAfterTrade`)
+    pub fn wsol_policy(mut self, wsol_policy: WsolPolicy) -> Self {
+        self.wsol_policy = wsol_policy;
+        self
+    }
+
+    /// Return as soon as the first SWQOS acknowledges a trade and cancel the rest (default: off)
+    pub fn race_submissions(mut self, race_submissions: bool) -> Self {
+        self.race_submissions = race_submissions;
+        self
+    }
+
+    pub fn lookup_table_key(mut self, lookup_table_key: Pubkey) -> Self {
+        self.lookup_table_key = Some(lookup_table_key);
+        self
+    }
+
+    /// Helius API URL used by `PriorityFeeMode::Auto`
+    pub fn priority_fee_api_url(mut self, url: impl Into<String>) -> Self {
+        self.priority_fee_api_url = Some(url.into());
+        self
+    }
+
+    /// Disable trade execution on a DEX
+    pub fn disable_dex(mut self, dex_type: DexType) -> Self {
+        if !self.disabled_dex_types.contains(&dex_type) {
+            self.disabled_dex_types.push(dex_type);
+        }
+        self
+    }
+
+    /// Enable auto tips from the Jito tip oracle
+    pub fn auto_tip(mut self, auto_tip: TipOracleConfig) -> Self {
+        self.auto_tip = Some(auto_tip);
+        self
+    }
+
+    /// Probe every SWQOS endpoint periodically and skip quarantined ones when submitting
+    pub fn swqos_health_check(mut self, swqos_health_check: SwqosHealthConfig) -> Self {
+        self.swqos_health_check = Some(swqos_health_check);
+        self
+    }
+
+    /// Rate-limit submissions to every SWQOS client of `swqos_type`
+    pub fn swqos_rate_limit(mut self, swqos_type: SwqosType, rate_limit: SwqosRateLimit) -> Self {
+        self.swqos_rate_limits.insert(swqos_type, rate_limit);
+        self
+    }
+
+    /// Refresh tip accounts from the providers' APIs every `refresh_interval_ms`
+    pub fn tip_account_refresh(mut self, refresh_interval_ms: u64) -> Self {
+        self.tip_account_refresh_ms = Some(refresh_interval_ms);
+        self
+    }
+
+    /// Re-probe the regions of `SwqosRegion::Auto` providers every `probe_interval_ms`
+    pub fn region_probe_interval(mut self, probe_interval_ms: u64) -> Self {
+        self.region_probe_interval_ms = probe_interval_ms;
+        self
+    }
+
+    /// Resubmit without a tip through the RPC when every SWQOS fails before its transaction is sent
+    pub fn rpc_fallback(mut self, rpc_fallback: bool) -> Self {
+        self.rpc_fallback = rpc_fallback;
+        self
+    }
+
+    /// Resubmit tipped transactions with higher tips when they do not land in time
+    pub fn tip_escalation(mut self, tip_escalation: TipEscalationConfig) -> Self {
+        self.tip_escalation = Some(tip_escalation);
+        self
+    }
+
+    /// Simulate fills at live quotes instead of sending transactions, see `ExecutionMode::Paper`
+    pub fn execution_mode(mut self, execution_mode: ExecutionMode) -> Self {
+        self.execution_mode = execution_mode;
+        self
+    }
+
+    /// Fill paper trades with `fill_model` instead of the live venue quote
+    pub fn paper_fill_model(mut self, fill_model: FillModel) -> Self {
+        self.paper_fill_model = Some(fill_model);
+        self
+    }
+
+    /// Time zone that splits trading days for the daily spend cap, daily PnL and state reports
+    ///
+    /// Timestamps are still stored in UTC; only day boundaries and report formatting use it.
+    pub fn reporting_timezone(mut self, reporting_timezone: FixedOffset) -> Self {
+        self.reporting_timezone = reporting_timezone;
+        self
+    }
+
+    /// Validate the configuration and create the `SolanaTrade` instance
+    ///
+    /// `buy_tip_fees` shorter than the SWQOS list is padded with `buy_tip_fee`. Nothing global is
+    /// touched until validation passes: custom providers are registered, the TLS crypto provider
+    /// is installed and the config is completed only for a valid config.
+    pub async fn build(self) -> Result<SolanaTrade> {
+        let payer = self.payer.ok_or_else(|| anyhow!("Payer is required"))?;
+        let rpc_url = self.rpc_url.ok_or_else(|| anyhow!("RPC URL is required"))?;
+        let mut trade_config = TradeConfig::new(
+            rpc_url,
+            self.swqos_configs,
+            self.priority_fee,
+            self.commitment,
+            self.lookup_table_key,
+        );
+        trade_config.read_rpc_url = self.read_rpc_url;
+        trade_config.priority_fee_api_url = self.priority_fee_api_url;
+        trade_config.disabled_dex_types = self.disabled_dex_types;
+        trade_config.auto_tip = self.auto_tip;
+        trade_config.dex_priority_fees = self.dex_priority_fees;
+        trade_config.spend_limit = self.spend_limit;
+        trade_config.adaptive_slippage = self.adaptive_slippage;
+        trade_config.submission_mode = self.submission_mode;
+        trade_config.auto_route_migrated = self.auto_route_migrated;
+        trade_config.wsol_policy = self.wsol_policy;
+        trade_config.race_submissions = self.race_submissions;
+        trade_config.swqos_health_check = self.swqos_health_check;
+        trade_config.swqos_rate_limits = self.swqos_rate_limits;
+        trade_config.tip_account_refresh_ms = self.tip_account_refresh_ms;
+        trade_config.region_probe_interval_ms = self.region_probe_interval_ms;
+        trade_config.rpc_fallback = self.rpc_fallback;
+        trade_config.tip_escalation = self.tip_escalation;
+        trade_config.execution_mode = self.execution_mode;
+        trade_config.paper_fill_model = self.paper_fill_model;
+        trade_config.reporting_timezone = self.reporting_timezone;
+        validate_config(&trade_config, &self.swqos_providers)?;
+
+        for provider in self.swqos_providers {
+            register_swqos_provider(provider);
+        }
+        let solana_trade = SolanaTrade::new(payer, trade_config).await;
+        Ok(match self.fee_payer {
+            Some(fee_payer) => solana_trade.with_fee_payer(fee_payer),
+            None => solana_trade,
+        })
+    }
+}
+
+/// Check a trade config for values that would only fail once a trade is sent
+pub(crate) fn validate_trade_config(trade_config: &TradeConfig) -> Result<()> {
+    validate_config(trade_config, &[])
+}
+
+/// `pending_providers` are custom providers that will be registered if the config is valid
+fn validate_config(trade_config: &TradeConfig, pending_providers: &[Arc<dyn SwqosProvider>]) -> Result<()> {
+    validate_url(&trade_config.rpc_url).map_err(|e| anyhow!("Invalid RPC URL: {}", e))?;
+    if let Some(url) = &trade_config.read_rpc_url {
+        validate_url(url).map_err(|e| anyhow!("Invalid read RPC URL: {}", e))?;
+    }
+    let swqos_count = trade_config.swqos_configs.len();
+    for swqos_config in &trade_config.swqos_configs {
+        validate_swqos_config(swqos_config, pending_providers)?;
+    }
+    validate_priority_fee(&trade_config.priority_fee, swqos_count)?;
+    for (dex_type, priority_fee) in &trade_config.dex_priority_fees {
+        validate_priority_fee(priority_fee, swqos_count)
+            .map_err(|e| anyhow!("Invalid priority fee for {:?}: {}", dex_type, e))?;
+    }
+    if let Some(url) = &trade_config.priority_fee_api_url {
+        validate_url(url).map_err(|e| anyhow!("Invalid priority fee API URL: {}", e))?;
+    }
+    for (swqos_type, rate_limit) in &trade_config.swqos_rate_limits {
+        if !rate_limit.requests_per_second.is_finite() || rate_limit.requests_per_second <= 0.0 {
+            return Err(anyhow!(
+                "Invalid rate limit for {:?}: {} requests per second",
+                swqos_type,
+                rate_limit.requests_per_second
+            ));
+        }
+    }
+    if let Some(tip_escalation) = &trade_config.tip_escalation {
+        if !tip_escalation.multiplier.is_finite() || tip_escalation.multiplier < 1.0 {
+            return Err(anyhow!("Tip escalation multiplier must be at least 1, got {}", tip_escalation.multiplier));
+        }
+        if !tip_escalation.max_tip.is_finite() || tip_escalation.max_tip < 0.0 {
+            return Err(anyhow!("Invalid tip escalation ceiling: {}", tip_escalation.max_tip));
+        }
+    }
+    if let Some(auto_tip) = &trade_config.auto_tip {
+        if auto_tip.min_tip_fee < 0.0 || auto_tip.min_tip_fee > auto_tip.max_tip_fee {
+            return Err(anyhow!(
+                "Invalid auto tip range: min {} > max {}",
+                auto_tip.min_tip_fee,
+                auto_tip.max_tip_fee
+            ));
+        }
+    }
+    let spend_limit = &trade_config.spend_limit;
+    if let (Some(per_trade), Some(per_day)) =
+        (spend_limit.max_sol_per_trade, spend_limit.max_sol_per_day)
+    {
+        if per_trade > per_day {
+            return Err(anyhow!(
+                "Per-trade spend cap {} exceeds the daily cap {}",
+                per_trade,
+                per_day
+            ));
+        }
+    }
+    if let Some(adaptive_slippage) = &trade_config.adaptive_slippage {
+        if adaptive_slippage.step_basis_points == 0 || adaptive_slippage.max_basis_points > 10_000 {
+            return Err(anyhow!(
+                "Invalid adaptive slippage: step must be positive and max at most 10000 bps"
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn validate_url(url: &str) -> Result<()> {
+    if url.starts_with("http://") || url.starts_with("https://") {
+        Ok(())
+    } else {
+        Err(anyhow!("expected an http(s) URL, got {:?}", url))
+    }
+}
+
+fn validate_swqos_config(swqos_config: &SwqosConfig, pending_providers: &[Arc<dyn SwqosProvider>]) -> Result<()> {
+    match swqos_config {
+        SwqosConfig::Default(endpoint) => {
+            validate_url(endpoint).map_err(|e| anyhow!("Invalid SWQOS endpoint: {}", e))
+        }
+        // Jito accepts requests without an auth token
+        SwqosConfig::Jito(_, _) => Ok(()),
+        SwqosConfig::NextBlock(auth_token, _)
+        | SwqosConfig::Bloxroute(auth_token, _)
+        | SwqosConfig::Temporal(auth_token, _)
+        | SwqosConfig::ZeroSlot(auth_token, _)
+        | SwqosConfig::Node1(auth_token, _)
+        | SwqosConfig::BlockRazor(auth_token, _) => {
+            if auth_token.trim().is_empty() {
+                Err(anyhow!("Missing auth token for {:?}", swqos_config))
+            } else {
+                Ok(())
+            }
+        }
+        SwqosConfig::Custom(name) => {
+            if get_swqos_provider(name).is_some()
+                || pending_providers.iter().any(|provider| provider.name() == name)
+            {
+                Ok(())
+            } else {
+                Err(anyhow!("SWQOS provider {} is not registered", name))
+            }
+        }
+    }
+}
+
+fn validate_priority_fee(priority_fee: &PriorityFee, swqos_count: usize) -> Result<()> {
+    if priority_fee.unit_limit == 0 || priority_fee.rpc_unit_limit == 0 {
+        return Err(anyhow!("Compute unit limit must be greater than 0"));
+    }
+    let tips = [priority_fee.buy_tip_fee, priority_fee.sell_tip_fee];
+    if tips.iter().chain(priority_fee.buy_tip_fees.iter()).any(|tip| !tip.is_finite() || *tip < 0.0)
+    {
+        return Err(anyhow!("Tip fees must be non-negative"));
+    }
+    if priority_fee.buy_tip_fees.len() > swqos_count {
+        return Err(anyhow!(
+            "buy_tip_fees has {} entries but only {} SWQOS endpoints are configured",
+            priority_fee.buy_tip_fees.len(),
+            swqos_count
+        ));
+    }
+    if let PriorityFeeMode::Auto(percentile) = priority_fee.mode {
+        if percentile > 100 {
+            return Err(anyhow!("Priority fee percentile must be at most 100, got {}", percentile));
+        }
+    }
+    if let Some(margin) = priority_fee.compute_unit_margin {
+        if !margin.is_finite() || margin < 1.0 {
+            return Err(anyhow!("Compute unit margin must be at least 1.0, got {}", margin));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::swqos::SwqosRegion;
+    use reqwest::Client;
+    use solana_sdk::transaction::VersionedTransaction;
+
+    fn valid_config() -> TradeConfig {
+        TradeConfig::new(
+            "https://api.mainnet-beta.solana.com".to_string(),
+            vec![
+                SwqosConfig::Jito("".to_string(), SwqosRegion::Frankfurt),
+                SwqosConfig::Default("https://api.mainnet-beta.solana.com".to_string()),
+            ],
+            PriorityFee::default(),
+            CommitmentConfig::confirmed(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_validate_trade_config_rejects_invalid_values() {
+        assert!(validate_trade_config(&valid_config()).is_ok());
+
+        let cases: Vec<(&str, fn(&mut TradeConfig))> = vec![
+            ("rpc url", |c| c.rpc_url = "localhost:8899".to_string()),
+            ("read rpc url", |c| c.read_rpc_url = Some("ws://localhost".to_string())),
+            ("swqos endpoint", |c| c.swqos_configs.push(SwqosConfig::Default("rpc".to_string()))),
+            ("swqos auth token", |c| {
+                c.swqos_configs.push(SwqosConfig::NextBlock(" ".to_string(), SwqosRegion::Frankfurt))
+            }),
+            ("custom provider", |c| c.swqos_configs.push(SwqosConfig::Custom("unregistered".to_string()))),
+            ("unit limit", |c| c.priority_fee.unit_limit = 0),
+            ("rpc unit limit", |c| c.priority_fee.rpc_unit_limit = 0),
+            ("tip", |c| c.priority_fee.sell_tip_fee = -0.001),
+            ("buy tip fees", |c| c.priority_fee.buy_tip_fees = vec![0.001; 3]),
+            ("fee percentile", |c| c.priority_fee.mode = PriorityFeeMode::Auto(101)),
+            ("compute unit margin", |c| c.priority_fee.compute_unit_margin = Some(0.9)),
+            ("dex priority fee", |c| {
+                let priority_fee = PriorityFee { unit_limit: 0, ..PriorityFee::default() };
+                c.dex_priority_fees.insert(DexType::PumpFun, priority_fee);
+            }),
+            ("priority fee api url", |c| c.priority_fee_api_url = Some("helius".to_string())),
+            ("rate limit", |c| {
+                c.swqos_rate_limits.insert(SwqosType::Jito, SwqosRateLimit::new(0.0));
+            }),
+            ("escalation multiplier", |c| {
+                c.tip_escalation = Some(TipEscalationConfig { multiplier: 0.5, ..Default::default() })
+            }),
+            ("escalation ceiling", |c| {
+                c.tip_escalation = Some(TipEscalationConfig { max_tip: f64::NAN, ..Default::default() })
+            }),
+            ("auto tip range", |c| {
+                c.auto_tip = Some(TipOracleConfig { min_tip_fee: 0.01, max_tip_fee: 0.001, ..Default::default() })
+            }),
+            ("spend caps", |c| {
+                c.spend_limit = SpendLimit { max_sol_per_trade: Some(2), max_sol_per_day: Some(1) }
+            }),
+            ("slippage step", |c| {
+                c.adaptive_slippage = Some(AdaptiveSlippageConfig { step_basis_points: 0, ..Default::default() })
+            }),
+            ("slippage max", |c| {
+                c.adaptive_slippage = Some(AdaptiveSlippageConfig { max_basis_points: 10_001, ..Default::default() })
+            }),
+        ];
+        for (name, invalidate) in cases {
+            let mut config = valid_config();
+            invalidate(&mut config);
+            assert!(validate_trade_config(&config).is_err(), "{} was accepted", name);
+        }
+    }
+
+    struct PendingProvider;
+
+    impl SwqosProvider for PendingProvider {
+        fn name(&self) -> &str {
+            "builder-pending-relay"
+        }
+
+        fn endpoint(&self) -> String {
+            "http://127.0.0.1:1".to_string()
+        }
+
+        fn tip_accounts(&self) -> Vec<Pubkey> {
+            vec![Pubkey::new_unique()]
+        }
+
+        fn build_request(&self, http_client: &Client, _transaction: &VersionedTransaction) -> Result<reqwest::RequestBuilder> {
+            Ok(http_client.post(self.endpoint()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invalid_build_registers_nothing() {
+        let builder = SolanaTradeBuilder::new()
+            .payer(Arc::new(Keypair::new()))
+            .rpc_url("localhost:8899")
+            .swqos_provider(Arc::new(PendingProvider));
+        assert!(builder.build().await.is_err());
+        assert!(get_swqos_provider("builder-pending-relay").is_none());
+
+        // A provider waiting to be registered counts as registered during validation
+        let mut config = valid_config();
+        config.swqos_configs.push(SwqosConfig::Custom("builder-pending-relay".to_string()));
+        let pending: Vec<Arc<dyn SwqosProvider>> = vec![Arc::new(PendingProvider)];
+        assert!(validate_config(&config, &pending).is_ok());
+        assert!(validate_trade_config(&config).is_err());
+    }
+}
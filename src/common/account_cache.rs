@@ -0,0 +1,221 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock, RwLock};
+
+use anyhow::{anyhow, Result};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+use crate::common::SolanaRpcClient;
+use crate::solana_streamer_sdk::streaming::event_parser::{
+    protocols::{
+        bonk::BonkTradeEvent,
+        pumpfun::PumpFunTradeEvent,
+        pumpswap::{PumpSwapBuyEvent, PumpSwapDepositEvent, PumpSwapSellEvent, PumpSwapWithdrawEvent},
+        raydium_cpmm::{RaydiumCpmmDepositEvent, RaydiumCpmmSwapEvent, RaydiumCpmmWithdrawEvent},
+    },
+    UnifiedEvent,
+};
+
+/// 缓存的账户数据及其所在 slot
+#[derive(Debug)]
+pub struct CachedAccount<T> {
+    pub data: Arc<T>,
+    pub slot: u64,
+}
+
+impl<T> Clone for CachedAccount<T> {
+    fn clone(&self) -> Self {
+        Self { data: self.data.clone(), slot: self.slot }
+    }
+}
+
+/// 缓存命中统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AccountCacheStats {
+    pub entries: usize,
+    pub hits: u64,
+    pub misses: u64,
+    pub invalidations: u64,
+}
+
+struct CacheEntry {
+    data: Arc<dyn Any + Send + Sync>,
+    slot: u64,
+}
+
+/// AccountCache 单例，缓存解码后的账户数据（pubkey -> 数据 + slot）
+///
+/// 通过 `on_event` / `on_account_update` 接入订阅流后，账户发生变化时自动失效
+pub struct AccountCache {
+    entries: RwLock<HashMap<Pubkey, CacheEntry>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    invalidations: AtomicU64,
+}
+
+static ACCOUNT_CACHE: OnceLock<Arc<AccountCache>> = OnceLock::new();
+
+impl AccountCache {
+    fn new() -> Self {
+        Self {
+            entries: RwLock::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            invalidations: AtomicU64::new(0),
+        }
+    }
+
+    /// 获取 AccountCache 单例实例
+    pub fn get_instance() -> Arc<AccountCache> {
+        ACCOUNT_CACHE.get_or_init(|| Arc::new(AccountCache::new())).clone()
+    }
+
+    /// 获取缓存的账户数据，类型不匹配时视为未命中
+    pub fn get<T: Send + Sync + 'static>(&self, pubkey: &Pubkey) -> Option<CachedAccount<T>> {
+        let cached = self.entries.read().unwrap().get(pubkey).and_then(|entry| {
+            let data = entry.data.clone().downcast::<T>().ok()?;
+            Some(CachedAccount { data, slot: entry.slot })
+        });
+        let counter = if cached.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        cached
+    }
+
+    /// 写入账户数据，比已缓存数据更旧的 slot 会被忽略
+    pub fn insert<T: Send + Sync + 'static>(&self, pubkey: Pubkey, data: T, slot: u64) -> bool {
+        let mut entries = self.entries.write().unwrap();
+        if entries.get(&pubkey).is_some_and(|entry| entry.slot > slot) {
+            return false;
+        }
+        entries.insert(pubkey, CacheEntry { data: Arc::new(data), slot });
+        true
+    }
+
+    /// 读取缓存，未命中时通过 RPC 获取并解码
+    pub async fn get_or_fetch<T, F>(
+        &self,
+        rpc: &SolanaRpcClient,
+        pubkey: &Pubkey,
+        decode: F,
+    ) -> Result<CachedAccount<T>>
+    where
+        T: Send + Sync + 'static,
+        F: FnOnce(&Account) -> Result<T>,
+    {
+        if let Some(cached) = self.get::<T>(pubkey) {
+            return Ok(cached);
+        }
+        let response = rpc.get_account_with_commitment(pubkey, rpc.commitment()).await?;
+        let account = response.value.ok_or_else(|| anyhow!("Account {} not found", pubkey))?;
+        let data = Arc::new(decode(&account)?);
+        let slot = response.context.slot;
+        let mut entries = self.entries.write().unwrap();
+        if entries.get(pubkey).is_none_or(|entry| entry.slot <= slot) {
+            entries.insert(*pubkey, CacheEntry { data: data.clone(), slot });
+        }
+        Ok(CachedAccount { data, slot })
+    }
+
+    /// 移除指定账户
+    pub fn invalidate(&self, pubkey: &Pubkey) -> bool {
+        let removed = self.entries.write().unwrap().remove(pubkey).is_some();
+        if removed {
+            self.invalidations.fetch_add(1, Ordering::Relaxed);
+        }
+        removed
+    }
+
+    /// 账户在 `slot` 发生变化时调用，移除更早写入的缓存
+    pub fn on_account_update(&self, pubkey: &Pubkey, slot: u64) -> bool {
+        let mut entries = self.entries.write().unwrap();
+        if entries.get(pubkey).is_some_and(|entry| entry.slot < slot) {
+            entries.remove(pubkey);
+            self.invalidations.fetch_add(1, Ordering::Relaxed);
+            return true;
+        }
+        false
+    }
+
+    /// 处理流事件，使交易或流动性变化涉及的池子 / bonding curve 失效
+    pub fn on_event(&self, event: &dyn UnifiedEvent) {
+        let slot = event.slot();
+        for pubkey in touched_accounts(event) {
+            self.on_account_update(&pubkey, slot);
+        }
+    }
+
+    /// 清空缓存
+    pub fn clear(&self) {
+        self.entries.write().unwrap().clear();
+    }
+
+    /// 获取缓存统计
+    pub fn stats(&self) -> AccountCacheStats {
+        AccountCacheStats {
+            entries: self.entries.read().unwrap().len(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            invalidations: self.invalidations.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// 流事件中状态发生变化的账户
+fn touched_accounts(event: &dyn UnifiedEvent) -> Vec<Pubkey> {
+    let any = event.as_any();
+    if let Some(e) = any.downcast_ref::<PumpFunTradeEvent>() {
+        return vec![e.bonding_curve];
+    }
+    if let Some(e) = any.downcast_ref::<PumpSwapBuyEvent>() {
+        return vec![e.pool];
+    }
+    if let Some(e) = any.downcast_ref::<PumpSwapSellEvent>() {
+        return vec![e.pool];
+    }
+    if let Some(e) = any.downcast_ref::<PumpSwapDepositEvent>() {
+        return vec![e.pool];
+    }
+    if let Some(e) = any.downcast_ref::<PumpSwapWithdrawEvent>() {
+        return vec![e.pool];
+    }
+    if let Some(e) = any.downcast_ref::<BonkTradeEvent>() {
+        return vec![e.pool_state];
+    }
+    if let Some(e) = any.downcast_ref::<RaydiumCpmmSwapEvent>() {
+        return vec![e.pool_state];
+    }
+    if let Some(e) = any.downcast_ref::<RaydiumCpmmDepositEvent>() {
+        return vec![e.pool_state];
+    }
+    if let Some(e) = any.downcast_ref::<RaydiumCpmmWithdrawEvent>() {
+        return vec![e.pool_state];
+    }
+    vec![]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slot_ordering_and_invalidation() {
+        let cache = AccountCache::new();
+        let pubkey = Pubkey::new_unique();
+
+        assert!(cache.insert(pubkey, 1u64, 10));
+        // 更旧的 slot 不覆盖
+        assert!(!cache.insert(pubkey, 2u64, 9));
+        assert_eq!(*cache.get::<u64>(&pubkey).unwrap().data, 1);
+        // 类型不匹配视为未命中
+        assert!(cache.get::<String>(&pubkey).is_none());
+
+        // 同一 slot 的更新不会使缓存失效
+        assert!(!cache.on_account_update(&pubkey, 10));
+        assert!(cache.on_account_update(&pubkey, 11));
+        assert!(cache.get::<u64>(&pubkey).is_none());
+
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.hits, stats.misses, stats.invalidations), (0, 1, 2, 1));
+    }
+}
@@ -0,0 +1,78 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use solana_sdk::hash::Hash;
+
+use crate::common::SolanaRpcClient;
+
+/// 默认刷新间隔，约一个 slot
+pub const DEFAULT_BLOCKHASH_REFRESH_INTERVAL: Duration = Duration::from_millis(400);
+
+/// 缓存的 blockhash 超过该时长视为过期（blockhash 约 60 秒后失效）
+const BLOCKHASH_MAX_AGE: Duration = Duration::from_secs(30);
+
+/// BlockhashCache 单例，由后台任务定时刷新最新 blockhash
+pub struct BlockhashCache {
+    latest: RwLock<Option<(Hash, Instant)>>,
+    started: AtomicBool,
+}
+
+static BLOCKHASH_CACHE: OnceLock<Arc<BlockhashCache>> = OnceLock::new();
+
+impl BlockhashCache {
+    /// 获取 BlockhashCache 单例实例
+    pub fn get_instance() -> Arc<BlockhashCache> {
+        BLOCKHASH_CACHE
+            .get_or_init(|| {
+                Arc::new(BlockhashCache { latest: RwLock::new(None), started: AtomicBool::new(false) })
+            })
+            .clone()
+    }
+
+    /// 获取缓存的 blockhash，未初始化或已过期时返回 None
+    pub fn get(&self) -> Option<Hash> {
+        self.latest
+            .read()
+            .unwrap()
+            .filter(|(_, updated_at)| updated_at.elapsed() < BLOCKHASH_MAX_AGE)
+            .map(|(hash, _)| hash)
+    }
+
+    /// 更新 blockhash
+    pub fn update(&self, hash: Hash) {
+        *self.latest.write().unwrap() = Some((hash, Instant::now()));
+    }
+
+    /// 启动后台刷新任务，已启动时返回 false
+    pub fn start(&self, rpc: Arc<SolanaRpcClient>, interval: Duration) -> bool {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return false;
+        }
+        tokio::spawn(async move {
+            let cache = BlockhashCache::get_instance();
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                ticker.tick().await;
+                match rpc.get_latest_blockhash().await {
+                    Ok(hash) => cache.update(hash),
+                    Err(e) => println!("刷新 blockhash 失败: {}", e),
+                }
+            }
+        });
+        true
+    }
+
+    /// 获取 blockhash，缓存不可用时直接通过 RPC 获取，并确保后台刷新任务已启动
+    pub async fn get_or_fetch(&self, rpc: &Arc<SolanaRpcClient>) -> Result<Hash> {
+        if let Some(hash) = self.get() {
+            return Ok(hash);
+        }
+        self.start(rpc.clone(), DEFAULT_BLOCKHASH_REFRESH_INTERVAL);
+        let hash = rpc.get_latest_blockhash().await?;
+        self.update(hash);
+        Ok(hash)
+    }
+}
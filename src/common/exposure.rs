@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Result};
+use solana_sdk::pubkey::Pubkey;
+
+/// 持仓风险敞口上限（lamports），未设置的上限不做限制
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExposureLimit {
+    /// 所有持仓投入的 SOL 总和上限
+    pub max_total_sol: Option<u64>,
+    /// 单个代币投入的 SOL 上限
+    pub max_sol_per_token: Option<u64>,
+    /// 同时持有的代币数上限
+    pub max_positions: Option<usize>,
+}
+
+/// 按代币累计持仓投入的 SOL，超出上限的买入被拒绝
+#[derive(Debug)]
+pub struct ExposureTracker {
+    limit: ExposureLimit,
+    positions: Mutex<HashMap<Pubkey, u64>>,
+}
+
+impl ExposureTracker {
+    pub fn new(limit: ExposureLimit) -> Self {
+        Self { limit, positions: Mutex::new(HashMap::new()) }
+    }
+
+    /// 为 `mint` 预留 `sol_amount` 的敞口，超出上限时返回错误
+    ///
+    /// 买入失败时应调用 `release` 归还，卖出清仓后调用 `close`
+    pub fn reserve(&self, mint: &Pubkey, sol_amount: u64) -> Result<()> {
+        let mut positions = self.positions.lock().unwrap();
+        let current = positions.get(mint).copied();
+        if current.is_none() {
+            if let Some(max_positions) = self.limit.max_positions {
+                if positions.len() >= max_positions {
+                    return Err(anyhow!(
+                        "Opening {} would exceed the cap of {} concurrent positions",
+                        mint,
+                        max_positions
+                    ));
+                }
+            }
+        }
+        let token_total = current.unwrap_or_default().saturating_add(sol_amount);
+        if let Some(max_sol_per_token) = self.limit.max_sol_per_token {
+            if token_total > max_sol_per_token {
+                return Err(anyhow!(
+                    "Buy of {} lamports exceeds the per-token cap for {}: {} of {} lamports already deployed",
+                    sol_amount,
+                    mint,
+                    current.unwrap_or_default(),
+                    max_sol_per_token
+                ));
+            }
+        }
+        let total: u64 = positions.values().sum();
+        if let Some(max_total_sol) = self.limit.max_total_sol {
+            if total.saturating_add(sol_amount) > max_total_sol {
+                return Err(anyhow!(
+                    "Buy of {} lamports exceeds the total exposure cap: {} of {} lamports already deployed",
+                    sol_amount,
+                    total,
+                    max_total_sol
+                ));
+            }
+        }
+        positions.insert(*mint, token_total);
+        Ok(())
+    }
+
+    /// 归还 `reserve` 预留的敞口，归零时不再计为持仓
+    pub fn release(&self, mint: &Pubkey, sol_amount: u64) {
+        let mut positions = self.positions.lock().unwrap();
+        if let Some(deployed) = positions.get_mut(mint) {
+            *deployed = deployed.saturating_sub(sol_amount);
+            if *deployed == 0 {
+                positions.remove(mint);
+            }
+        }
+    }
+
+    /// 持仓清仓，移除该代币的全部敞口
+    pub fn close(&self, mint: &Pubkey) {
+        self.positions.lock().unwrap().remove(mint);
+    }
+
+    /// 所有持仓投入的 SOL 总和
+    pub fn total(&self) -> u64 {
+        self.positions.lock().unwrap().values().sum()
+    }
+
+    /// 单个代币投入的 SOL
+    pub fn deployed(&self, mint: &Pubkey) -> u64 {
+        self.positions.lock().unwrap().get(mint).copied().unwrap_or_default()
+    }
+
+    /// 当前持有的代币数
+    pub fn position_count(&self) -> usize {
+        self.positions.lock().unwrap().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exposure_caps() {
+        let tracker = ExposureTracker::new(ExposureLimit {
+            max_total_sol: Some(250),
+            max_sol_per_token: Some(150),
+            max_positions: Some(2),
+        });
+        let (a, b, c) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+
+        assert!(tracker.reserve(&a, 100).is_ok());
+        // 超出单个代币上限
+        assert!(tracker.reserve(&a, 60).is_err());
+        assert!(tracker.reserve(&b, 150).is_ok());
+        // 超出持仓数上限
+        assert!(tracker.reserve(&c, 10).is_err());
+        // 超出总敞口上限
+        assert!(tracker.reserve(&a, 10).is_err());
+        assert_eq!(tracker.total(), 250);
+
+        tracker.release(&b, 150);
+        assert_eq!(tracker.position_count(), 1);
+        assert!(tracker.reserve(&c, 100).is_ok());
+        tracker.close(&a);
+        assert_eq!((tracker.total(), tracker.deployed(&c)), (100, 100));
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use chrono::Utc;
+
+use crate::monitor::PositionStore;
+
+/// 幂等键的默认有效期
+pub const DEFAULT_IDEMPOTENCY_TTL: Duration = Duration::from_secs(60);
+
+/// 短期幂等键缓存，有效期内相同的键只允许提交一次
+pub struct IdempotencyCache {
+    ttl: Duration,
+    keys: Mutex<HashMap<String, Instant>>,
+    store: Option<Arc<dyn PositionStore>>,
+}
+
+impl Default for IdempotencyCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_IDEMPOTENCY_TTL)
+    }
+}
+
+impl IdempotencyCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl, keys: Mutex::new(HashMap::new()), store: None }
+    }
+
+    /// 从存储中恢复有效期内的键，之后的修改都写入存储；主备实例共用存储时接管的实例不会重复提交
+    pub fn with_store(ttl: Duration, store: Arc<dyn PositionStore>) -> Result<Self> {
+        let now = Instant::now();
+        let now_ms = Utc::now().timestamp_millis();
+        let keys = store
+            .load_idempotency_keys()?
+            .into_iter()
+            .filter_map(|(key, submitted_ms)| {
+                let age = Duration::from_millis(now_ms.saturating_sub(submitted_ms).max(0) as u64);
+                if age >= ttl {
+                    return None;
+                }
+                now.checked_sub(age).map(|inserted_at| (key, inserted_at))
+            })
+            .collect();
+        Ok(Self { ttl, keys: Mutex::new(keys), store: Some(store) })
+    }
+
+    /// 写入存储失败只打印错误，不影响去重
+    fn persist(&self, key: &str, submitted_ms: i64) {
+        if let Some(Err(e)) = self.store.as_ref().map(|store| store.save_idempotency_key(key, submitted_ms)) {
+            eprintln!("保存幂等键 {} 失败: {}", key, e);
+        }
+    }
+
+    /// 记录 `key`，有效期内已存在时返回 false
+    pub fn try_insert(&self, key: &str) -> bool {
+        let now = Instant::now();
+        let mut keys = self.keys.lock().unwrap();
+        let expired: Vec<String> = keys
+            .iter()
+            .filter(|(_, inserted_at)| now.duration_since(**inserted_at) >= self.ttl)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in expired {
+            keys.remove(&key);
+            self.persist(&key, 0);
+        }
+        if keys.contains_key(key) {
+            return false;
+        }
+        keys.insert(key.to_string(), now);
+        self.persist(key, Utc::now().timestamp_millis());
+        true
+    }
+
+    /// 有效期内的键
+    pub fn active_keys(&self) -> Vec<String> {
+        let now = Instant::now();
+        let keys = self.keys.lock().unwrap();
+        keys.iter()
+            .filter(|(_, inserted_at)| now.duration_since(**inserted_at) < self.ttl)
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    /// 移除 `key`，交易失败后允许使用同一个键重试
+    pub fn remove(&self, key: &str) {
+        if self.keys.lock().unwrap().remove(key).is_some() {
+            self.persist(key, 0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitor::MemoryPositionStore;
+
+    #[test]
+    fn test_try_insert() {
+        let cache = IdempotencyCache::default();
+        assert!(cache.try_insert("buy-1"));
+        assert!(!cache.try_insert("buy-1"));
+        assert!(cache.try_insert("buy-2"));
+        cache.remove("buy-1");
+        assert!(cache.try_insert("buy-1"));
+
+        // 过期后可以再次提交
+        let cache = IdempotencyCache::new(Duration::ZERO);
+        assert!(cache.try_insert("buy-1"));
+        assert!(cache.try_insert("buy-1"));
+    }
+
+    #[test]
+    fn test_keys_shared_through_store() {
+        let store: Arc<dyn PositionStore> = Arc::new(MemoryPositionStore::default());
+        let primary = IdempotencyCache::with_store(DEFAULT_IDEMPOTENCY_TTL, store.clone()).unwrap();
+        assert!(primary.try_insert("buy-1"));
+        assert!(primary.try_insert("buy-2"));
+        primary.remove("buy-2");
+
+        // 接管的实例从存储恢复，不会重复提交
+        let standby = IdempotencyCache::with_store(DEFAULT_IDEMPOTENCY_TTL, store.clone()).unwrap();
+        assert!(!standby.try_insert("buy-1"));
+        assert!(standby.try_insert("buy-2"));
+
+        // 过期的键不会恢复
+        store.save_idempotency_key("buy-3", Utc::now().timestamp_millis() - 120_000).unwrap();
+        let standby = IdempotencyCache::with_store(DEFAULT_IDEMPOTENCY_TTL, store).unwrap();
+        assert!(standby.try_insert("buy-3"));
+    }
+}
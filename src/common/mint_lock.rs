@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+/// 按 mint 串行执行交易，同一代币的买卖不会交错提交，不同代币互不影响
+#[derive(Debug, Default)]
+pub struct MintLocks {
+    locks: Mutex<HashMap<Pubkey, Arc<AsyncMutex<()>>>>,
+}
+
+impl MintLocks {
+    /// 等待 `mint` 上正在执行的交易完成并获取锁，guard 释放前同一 mint 的其他交易等待
+    pub async fn lock(&self, mint: &Pubkey) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.locks.lock().unwrap();
+            // 清理没有持有者也没有等待者的锁
+            locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+            locks.entry(*mint).or_default().clone()
+        };
+        lock.lock_owned().await
+    }
+
+    /// 当前有交易正在执行或等待的 mint 数量
+    pub fn active(&self) -> usize {
+        self.locks.lock().unwrap().values().filter(|lock| Arc::strong_count(lock) > 1).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_same_mint_is_serialized() {
+        let locks = Arc::new(MintLocks::default());
+        let mint = Pubkey::new_unique();
+        let guard = locks.lock(&mint).await;
+
+        // 其他 mint 不受影响
+        let _other = locks.lock(&Pubkey::new_unique()).await;
+
+        let waiter = {
+            let locks = locks.clone();
+            tokio::spawn(async move {
+                let _guard = locks.lock(&mint).await;
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+
+        drop(guard);
+        waiter.await.unwrap();
+    }
+}
@@ -6,5 +6,27 @@ pub mod address_lookup_cache;
 pub mod subscription_handle;
 pub mod bonding_curve;
 pub mod global;
+pub mod priority_fee_estimator;
+pub mod account_cache;
+pub mod blockhash_cache;
+pub mod spend_limit;
+pub mod exposure;
+pub mod token_metadata;
+pub mod idempotency_cache;
+pub mod trade_outcome;
+pub mod mint_lock;
+pub mod wsol;
+pub mod trading_day;
 
+pub use account_cache::{AccountCache, AccountCacheStats, CachedAccount};
+pub use blockhash_cache::BlockhashCache;
+pub use exposure::{ExposureLimit, ExposureTracker};
+pub use idempotency_cache::IdempotencyCache;
+pub use mint_lock::MintLocks;
+pub use priority_fee_estimator::PriorityFeeEstimator;
+pub use spend_limit::{SpendLimit, SpendTracker};
+pub use token_metadata::{TokenMetadata, TokenMetadataCache};
+pub use trading_day::trading_day;
+pub use trade_outcome::{PendingTrade, TradeOutcome, TradeOutcomeBus};
 pub use types::*;
+pub use wsol::WsolPolicy;
@@ -0,0 +1,123 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use reqwest::Client;
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::common::SolanaRpcClient;
+
+/// Helius 优先费等级及其对应的百分位
+const HELIUS_FEE_LEVELS: [(&str, u8); 6] = [
+    ("min", 0),
+    ("low", 25),
+    ("medium", 50),
+    ("high", 75),
+    ("veryHigh", 95),
+    ("unsafeMax", 100),
+];
+
+/// 优先费估算器，按百分位从最近的优先费样本中计算 compute unit price
+pub struct PriorityFeeEstimator {
+    rpc: Arc<SolanaRpcClient>,
+    /// Helius 优先费 API 地址，未设置时只使用 getRecentPrioritizationFees
+    helius_url: Option<String>,
+    http_client: Client,
+}
+
+impl PriorityFeeEstimator {
+    pub fn new(rpc: Arc<SolanaRpcClient>, helius_url: Option<String>) -> Self {
+        let http_client = Client::builder()
+            .timeout(Duration::from_secs(3))
+            .connect_timeout(Duration::from_secs(2))
+            .build()
+            .unwrap();
+        Self { rpc, helius_url, http_client }
+    }
+
+    /// 估算指定百分位的 compute unit price（micro-lamports）
+    ///
+    /// 配置了 Helius 时优先使用 Helius，失败后回退到 RPC 采样
+    pub async fn estimate(&self, accounts: &[Pubkey], percentile: u8) -> Result<u64> {
+        let percentile = percentile.min(100);
+        if let Some(url) = &self.helius_url {
+            match self.estimate_from_helius(url, accounts, percentile).await {
+                Ok(fee) => return Ok(fee),
+                Err(e) => println!("Helius 优先费估算失败，回退到 RPC: {}", e),
+            }
+        }
+        self.estimate_from_rpc(accounts, percentile).await
+    }
+
+    /// 基于 getRecentPrioritizationFees 采样估算
+    pub async fn estimate_from_rpc(&self, accounts: &[Pubkey], percentile: u8) -> Result<u64> {
+        let fees = self.rpc.get_recent_prioritization_fees(accounts).await?;
+        let mut samples: Vec<u64> = fees.iter().map(|fee| fee.prioritization_fee).collect();
+        if samples.is_empty() {
+            return Err(anyhow!("No recent prioritization fees"));
+        }
+        Ok(percentile_of(&mut samples, percentile))
+    }
+
+    /// 基于 Helius getPriorityFeeEstimate 估算
+    async fn estimate_from_helius(
+        &self,
+        url: &str,
+        accounts: &[Pubkey],
+        percentile: u8,
+    ) -> Result<u64> {
+        let account_keys: Vec<String> = accounts.iter().map(|account| account.to_string()).collect();
+        let request_body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getPriorityFeeEstimate",
+            "params": [{
+                "accountKeys": account_keys,
+                "options": { "includeAllPriorityFeeLevels": true }
+            }]
+        });
+        let response: Value =
+            self.http_client.post(url).json(&request_body).send().await?.json().await?;
+        let levels = response
+            .get("result")
+            .and_then(|result| result.get("priorityFeeLevels"))
+            .ok_or_else(|| anyhow!("Invalid Helius response: {}", response))?;
+
+        // 取不低于目标百分位的最小等级
+        let (level, _) = HELIUS_FEE_LEVELS
+            .iter()
+            .find(|(_, level_percentile)| *level_percentile >= percentile)
+            .unwrap_or(&HELIUS_FEE_LEVELS[HELIUS_FEE_LEVELS.len() - 1]);
+        levels
+            .get(level)
+            .and_then(|fee| fee.as_f64())
+            .map(|fee| fee.ceil() as u64)
+            .ok_or_else(|| anyhow!("Missing Helius priority fee level: {}", level))
+    }
+}
+
+/// 计算样本的百分位值（最近秩法）
+pub fn percentile_of(samples: &mut [u64], percentile: u8) -> u64 {
+    if samples.is_empty() {
+        return 0;
+    }
+    samples.sort_unstable();
+    let rank = (percentile.min(100) as usize * samples.len()).div_ceil(100);
+    samples[rank.saturating_sub(1)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile_of() {
+        let mut samples = vec![50, 10, 40, 20, 30];
+        assert_eq!(percentile_of(&mut samples, 0), 10);
+        assert_eq!(percentile_of(&mut samples, 50), 30);
+        assert_eq!(percentile_of(&mut samples, 75), 40);
+        assert_eq!(percentile_of(&mut samples, 100), 50);
+        assert_eq!(percentile_of(&mut [], 50), 0);
+    }
+}
@@ -0,0 +1,133 @@
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Result};
+use chrono::{FixedOffset, NaiveDate};
+
+use crate::common::trading_day::{today, utc_offset};
+
+/// 买入花费上限（lamports），未设置的上限不做限制
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpendLimit {
+    /// 单笔买入上限
+    pub max_sol_per_trade: Option<u64>,
+    /// 每个交易日的累计买入上限，交易日按报告时区划分（默认 UTC）
+    pub max_sol_per_day: Option<u64>,
+}
+
+/// 按交易日累计买入花费，超出上限的买入在发送前被拒绝
+#[derive(Debug)]
+pub struct SpendTracker {
+    limit: SpendLimit,
+    spent: Mutex<(NaiveDate, u64)>,
+    timezone: FixedOffset,
+}
+
+impl SpendTracker {
+    pub fn new(limit: SpendLimit) -> Self {
+        let timezone = utc_offset();
+        Self { limit, spent: Mutex::new((today(&timezone), 0)), timezone }
+    }
+
+    /// 按 `timezone` 划分交易日，默认 UTC
+    pub fn with_timezone(self, timezone: FixedOffset) -> Self {
+        Self { limit: self.limit, spent: Mutex::new((today(&timezone), 0)), timezone }
+    }
+
+    /// 预留 `sol_amount` 的额度，超出上限时返回错误
+    ///
+    /// 交易失败时应调用 `release` 归还额度
+    pub fn reserve(&self, sol_amount: u64) -> Result<()> {
+        self.reserve_on(today(&self.timezone), sol_amount)
+    }
+
+    /// 归还 `reserve` 预留的额度
+    pub fn release(&self, sol_amount: u64) {
+        let mut spent = self.spent.lock().unwrap();
+        if spent.0 == today(&self.timezone) {
+            spent.1 = spent.1.saturating_sub(sol_amount);
+        }
+    }
+
+    /// 当日已花费的 lamports
+    pub fn spent_today(&self) -> u64 {
+        let spent = self.spent.lock().unwrap();
+        if spent.0 == today(&self.timezone) {
+            spent.1
+        } else {
+            0
+        }
+    }
+
+    /// 检查 `sol_amount` 是否在上限内，不预留额度
+    ///
+    /// 用于只构建交易、不由 SDK 发送的场景，发送方仍需自行控制花费
+    pub fn check(&self, sol_amount: u64) -> Result<()> {
+        self.check_on(today(&self.timezone), sol_amount)
+    }
+
+    fn check_on(&self, today: NaiveDate, sol_amount: u64) -> Result<()> {
+        let spent = self.spent.lock().unwrap();
+        let spent_today = if spent.0 == today { spent.1 } else { 0 };
+        self.check_limit(sol_amount, spent_today)
+    }
+
+    fn check_limit(&self, sol_amount: u64, spent_today: u64) -> Result<()> {
+        if let Some(max_sol_per_trade) = self.limit.max_sol_per_trade {
+            if sol_amount > max_sol_per_trade {
+                return Err(anyhow!(
+                    "Buy of {} lamports exceeds the per-trade cap of {} lamports",
+                    sol_amount,
+                    max_sol_per_trade
+                ));
+            }
+        }
+        if let Some(max_sol_per_day) = self.limit.max_sol_per_day {
+            if spent_today.saturating_add(sol_amount) > max_sol_per_day {
+                return Err(anyhow!(
+                    "Buy of {} lamports exceeds the daily cap: {} of {} lamports already spent",
+                    sol_amount,
+                    spent_today,
+                    max_sol_per_day
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn reserve_on(&self, today: NaiveDate, sol_amount: u64) -> Result<()> {
+        let mut spent = self.spent.lock().unwrap();
+        if spent.0 != today {
+            *spent = (today, 0);
+        }
+        self.check_limit(sol_amount, spent.1)?;
+        spent.1 = spent.1.saturating_add(sol_amount);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spend_caps() {
+        let tracker = SpendTracker::new(SpendLimit {
+            max_sol_per_trade: Some(100),
+            max_sol_per_day: Some(250),
+        });
+        let day = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+
+        assert!(tracker.reserve_on(day, 101).is_err());
+        assert!(tracker.reserve_on(day, 100).is_ok());
+        // 检查不占用额度
+        assert!(tracker.check_on(day, 100).is_ok());
+        assert!(tracker.check_on(day, 101).is_err());
+        assert!(tracker.reserve_on(day, 100).is_ok());
+        assert!(tracker.check_on(day, 100).is_err());
+        // 超出当日上限
+        assert!(tracker.reserve_on(day, 100).is_err());
+        assert!(tracker.reserve_on(day, 50).is_ok());
+        // 次日重新计算
+        assert!(tracker.reserve_on(day.succ_opt().unwrap(), 100).is_ok());
+    }
+}
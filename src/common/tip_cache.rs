@@ -1,9 +1,19 @@
-use std::sync::{Arc, Mutex, OnceLock};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::Duration;
 
-/// TipCache 单例，用于存储和管理 tip 金额
+use rand::seq::IndexedRandom;
+use solana_sdk::pubkey::Pubkey;
+use tokio::task::JoinHandle;
+
+use crate::swqos::{SwqosClient, SwqosType};
+
+/// TipCache 单例，用于存储和管理 tip 金额及各服务商的小费账户
 pub struct TipCache {
     /// tip 金额
     tip_amount: Mutex<f64>,
+    /// 从服务商接口刷新得到的小费账户
+    tip_accounts: RwLock<HashMap<SwqosType, Vec<Pubkey>>>,
 }
 
 static TIP_CACHE: OnceLock<Arc<TipCache>> = OnceLock::new();
@@ -15,6 +25,7 @@ impl TipCache {
             .get_or_init(|| {
                 Arc::new(TipCache {
                     tip_amount: Mutex::new(0.001),
+                    tip_accounts: RwLock::new(HashMap::new()),
                 })
             })
             .clone()
@@ -35,4 +46,82 @@ impl TipCache {
     pub fn update_tip(&self, amount: f64) {
         *self.tip_amount.lock().unwrap() = amount;
     }
-}
\ No newline at end of file
+
+    /// 更新服务商的小费账户列表，空列表不覆盖已有数据
+    pub fn update_tip_accounts(&self, swqos_type: SwqosType, tip_accounts: Vec<Pubkey>) {
+        if !tip_accounts.is_empty() {
+            self.tip_accounts.write().unwrap().insert(swqos_type, tip_accounts);
+        }
+    }
+
+    /// 获取刷新得到的小费账户列表，未刷新过时返回 None
+    pub fn get_tip_accounts(&self, swqos_type: &SwqosType) -> Option<Vec<Pubkey>> {
+        self.tip_accounts.read().unwrap().get(swqos_type).cloned()
+    }
+
+    /// 随机选择一个小费账户，分散写锁竞争
+    ///
+    /// 优先使用刷新得到的列表，未刷新过时从内置的 `defaults` 中选择
+    pub fn random_tip_account(&self, swqos_type: &SwqosType, defaults: &[Pubkey]) -> Option<Pubkey> {
+        let tip_accounts = self.tip_accounts.read().unwrap();
+        match tip_accounts.get(swqos_type) {
+            Some(refreshed) => refreshed.choose(&mut rand::rng()).copied(),
+            None => defaults.choose(&mut rand::rng()).copied(),
+        }
+    }
+
+    /// 从各服务商接口拉取一次小费账户，同一类型只请求第一个客户端
+    pub async fn refresh_tip_accounts(&self, swqos_clients: &[Arc<SwqosClient>]) {
+        let mut refreshed: Vec<SwqosType> = vec![];
+        for swqos_client in swqos_clients {
+            let swqos_type = swqos_client.get_swqos_type();
+            if refreshed.contains(&swqos_type) {
+                continue;
+            }
+            match swqos_client.fetch_tip_accounts().await {
+                Ok(tip_accounts) => self.update_tip_accounts(swqos_type.clone(), tip_accounts),
+                Err(e) => println!("刷新 {:?} 小费账户失败: {}", swqos_type, e),
+            }
+            refreshed.push(swqos_type);
+        }
+    }
+
+    /// 启动后台任务，定时刷新小费账户
+    pub fn start_tip_account_refresh(
+        &self,
+        swqos_clients: Vec<Arc<SwqosClient>>,
+        refresh_interval_ms: u64,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let cache = TipCache::get_instance();
+            let mut interval = tokio::time::interval(Duration::from_millis(refresh_interval_ms.max(1_000)));
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                cache.refresh_tip_accounts(&swqos_clients).await;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refreshed_tip_accounts_replace_defaults() {
+        let cache = TipCache { tip_amount: Mutex::new(0.001), tip_accounts: RwLock::new(HashMap::new()) };
+        let defaults = [Pubkey::new_unique()];
+        assert_eq!(cache.random_tip_account(&SwqosType::Jito, &defaults), Some(defaults[0]));
+
+        let refreshed = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+        cache.update_tip_accounts(SwqosType::Jito, refreshed.clone());
+        cache.update_tip_accounts(SwqosType::Jito, vec![]);
+        assert_eq!(cache.get_tip_accounts(&SwqosType::Jito), Some(refreshed.clone()));
+        for _ in 0..10 {
+            let tip_account = cache.random_tip_account(&SwqosType::Jito, &defaults).unwrap();
+            assert!(refreshed.contains(&tip_account));
+        }
+        assert_eq!(cache.random_tip_account(&SwqosType::Node1, &defaults), Some(defaults[0]));
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use anyhow::{anyhow, Result};
+use borsh::BorshDeserialize;
+use solana_sdk::{account::Account, program_pack::Pack, pubkey::Pubkey};
+use spl_token_2022::{
+    extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+    state::Mint,
+};
+
+use crate::common::SolanaRpcClient;
+use crate::trading::pumpfun::common::get_metadata_pda;
+
+/// 代币名称、符号和精度
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenMetadata {
+    pub mint: Pubkey,
+    /// 没有元数据时为空字符串
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+impl TokenMetadata {
+    /// 日志中显示的名称，有符号时为符号，否则为代币地址
+    pub fn label(&self) -> String {
+        if self.symbol.is_empty() {
+            self.mint.to_string()
+        } else {
+            self.symbol.clone()
+        }
+    }
+}
+
+/// 解析代币元数据：精度取自 mint 账户，名称和符号优先取 Token-2022 的 TokenMetadata 扩展，
+/// 其次取 Metaplex 元数据账户，都没有时为空
+pub fn decode_token_metadata(
+    mint: &Pubkey,
+    mint_account: &Account,
+    metaplex_account: Option<&Account>,
+) -> Result<TokenMetadata> {
+    if mint_account.owner != spl_token::ID && mint_account.owner != spl_token_2022::ID {
+        return Err(anyhow!(
+            "Mint {} is not owned by a token program: {}",
+            mint,
+            mint_account.owner
+        ));
+    }
+    let state = StateWithExtensions::<Mint>::unpack(&mint_account.data)?;
+    let (name, symbol) = state
+        .get_extension_types()
+        .ok()
+        .filter(|extensions| extensions.contains(&ExtensionType::TokenMetadata))
+        .and_then(|_| token_2022_metadata(&mint_account.data))
+        .or_else(|| metaplex_account.and_then(|account| name_and_symbol(account.data.get(65..)?)))
+        .unwrap_or_default();
+    Ok(TokenMetadata { mint: *mint, name, symbol, decimals: state.base.decimals })
+}
+
+/// 读取 Token-2022 mint 中 TokenMetadata 扩展的名称和符号
+fn token_2022_metadata(data: &[u8]) -> Option<(String, String)> {
+    // 扩展从账户类型字节之后开始，每项为 类型(u16) + 长度(u16) + 数据
+    let mut offset = spl_token_2022::state::Account::LEN + 1;
+    while offset + 4 <= data.len() {
+        let extension_type = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let length = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        let value = data.get(offset + 4..offset + 4 + length)?;
+        if extension_type == ExtensionType::TokenMetadata as u16 {
+            // update_authority 和 mint 之后为名称和符号
+            return name_and_symbol(value.get(64..)?);
+        }
+        offset += 4 + length;
+    }
+    None
+}
+
+/// 依次读取 borsh 编码的名称和符号，去掉 Metaplex 补齐用的空字符
+fn name_and_symbol(mut data: &[u8]) -> Option<(String, String)> {
+    let name = String::deserialize(&mut data).ok()?;
+    let symbol = String::deserialize(&mut data).ok()?;
+    Some((name.trim_end_matches('\0').to_string(), symbol.trim_end_matches('\0').to_string()))
+}
+
+/// TokenMetadataCache 单例，代币元数据很少变化，获取后一直缓存
+pub struct TokenMetadataCache {
+    entries: RwLock<HashMap<Pubkey, Arc<TokenMetadata>>>,
+}
+
+static TOKEN_METADATA_CACHE: OnceLock<Arc<TokenMetadataCache>> = OnceLock::new();
+
+impl TokenMetadataCache {
+    /// 获取 TokenMetadataCache 单例实例
+    pub fn get_instance() -> Arc<TokenMetadataCache> {
+        TOKEN_METADATA_CACHE
+            .get_or_init(|| Arc::new(TokenMetadataCache { entries: RwLock::new(HashMap::new()) }))
+            .clone()
+    }
+
+    pub fn get(&self, mint: &Pubkey) -> Option<Arc<TokenMetadata>> {
+        self.entries.read().unwrap().get(mint).cloned()
+    }
+
+    pub fn insert(&self, metadata: TokenMetadata) -> Arc<TokenMetadata> {
+        let metadata = Arc::new(metadata);
+        self.entries.write().unwrap().insert(metadata.mint, metadata.clone());
+        metadata
+    }
+
+    /// 获取代币元数据，未缓存时一次请求 mint 和 Metaplex 元数据账户
+    pub async fn get_or_fetch(
+        &self,
+        rpc: &SolanaRpcClient,
+        mint: &Pubkey,
+    ) -> Result<Arc<TokenMetadata>> {
+        if let Some(metadata) = self.get(mint) {
+            return Ok(metadata);
+        }
+        let accounts = rpc.get_multiple_accounts(&[*mint, get_metadata_pda(mint)]).await?;
+        let mint_account =
+            accounts[0].as_ref().ok_or_else(|| anyhow!("Mint account {} not found", mint))?;
+        Ok(self.insert(decode_token_metadata(mint, mint_account, accounts[1].as_ref())?))
+    }
+
+    /// 日志中显示的代币名称，已缓存时为符号，否则为代币地址
+    pub fn label(&self, mint: &Pubkey) -> String {
+        self.get(mint).map_or_else(|| mint.to_string(), |metadata| metadata.label())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use borsh::BorshSerialize;
+    use solana_sdk::program_option::COption;
+
+    #[test]
+    fn test_decode_metaplex_metadata() {
+        let mint = Pubkey::new_unique();
+        let mut data = vec![0; spl_token::state::Mint::LEN];
+        spl_token::state::Mint {
+            mint_authority: COption::None,
+            supply: 1_000,
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        }
+        .pack_into_slice(&mut data);
+        let mint_account =
+            Account { lamports: 1, data, owner: spl_token::ID, executable: false, rent_epoch: 0 };
+
+        // key + update_authority + mint，之后为补齐到固定长度的名称和符号
+        let mut metadata = vec![4u8];
+        metadata.extend_from_slice(&[0; 64]);
+        "dogwifhat\0\0\0".to_string().serialize(&mut metadata).unwrap();
+        "WIF\0\0".to_string().serialize(&mut metadata).unwrap();
+        let metaplex_account =
+            Account { data: metadata, owner: Pubkey::new_unique(), ..mint_account.clone() };
+
+        let decoded = decode_token_metadata(&mint, &mint_account, Some(&metaplex_account)).unwrap();
+        assert_eq!(
+            (decoded.name.as_str(), decoded.symbol.as_str(), decoded.decimals),
+            ("dogwifhat", "WIF", 6)
+        );
+        assert_eq!(decoded.label(), "WIF");
+
+        let bare = decode_token_metadata(&mint, &mint_account, None).unwrap();
+        assert_eq!(bare.label(), mint.to_string());
+
+        let cache = TokenMetadataCache::get_instance();
+        assert_eq!(cache.label(&mint), mint.to_string());
+        cache.insert(decoded);
+        assert_eq!(cache.label(&mint), "WIF");
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::broadcast;
+
+use crate::swqos::TradeType;
+use crate::trading::factory::DexType;
+use crate::trading::SubmissionReport;
+
+/// 结果广播通道容量
+const TRADE_OUTCOME_CHANNEL_CAPACITY: usize = 1024;
+
+/// `SubmissionMode::FireAndTrack` 下后台完成的交易结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeOutcome {
+    pub dex_type: DexType,
+    pub trade_type: TradeType,
+    pub mint: Pubkey,
+    /// 买入为花费的 SOL（lamports），卖出为卖出的代币数量
+    pub amount: u64,
+    /// 提交时 `ExecutionOverrides` 中的幂等键，用于关联发起的交易
+    pub idempotency_key: Option<String>,
+    /// 交易失败（包括构建指令失败）时的错误信息
+    pub error: Option<String>,
+    /// 各 SWQOS 通道的提交耗时，失败或未走 SWQOS 时为空
+    pub submission: SubmissionReport,
+}
+
+impl TradeOutcome {
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// 已交给后台、尚未得到结果的交易
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingTrade {
+    pub dex_type: DexType,
+    pub trade_type: TradeType,
+    pub mint: Pubkey,
+    /// 买入为花费的 SOL（lamports），卖出为卖出的代币数量
+    pub amount: u64,
+    pub idempotency_key: Option<String>,
+    pub submitted_at: DateTime<Utc>,
+}
+
+/// 广播后台交易结果，订阅者按需更新持仓；同时记录尚未完成的交易
+pub struct TradeOutcomeBus {
+    sender: broadcast::Sender<TradeOutcome>,
+    pending: Mutex<HashMap<u64, PendingTrade>>,
+    next_id: AtomicU64,
+}
+
+impl Default for TradeOutcomeBus {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(TRADE_OUTCOME_CHANNEL_CAPACITY);
+        Self { sender, pending: Mutex::new(HashMap::new()), next_id: AtomicU64::new(0) }
+    }
+}
+
+impl TradeOutcomeBus {
+    /// 订阅交易结果，只能收到订阅之后完成的交易
+    pub fn subscribe(&self) -> broadcast::Receiver<TradeOutcome> {
+        self.sender.subscribe()
+    }
+
+    /// 广播交易结果，没有订阅者时丢弃
+    pub fn publish(&self, outcome: TradeOutcome) {
+        let _ = self.sender.send(outcome);
+    }
+
+    /// 记录交给后台的交易，返回的 id 用于 `complete`
+    pub fn track(&self, trade: PendingTrade) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.pending.lock().unwrap().insert(id, trade);
+        id
+    }
+
+    /// 移除 `track` 记录的交易并广播其结果
+    pub fn complete(&self, id: u64, outcome: TradeOutcome) {
+        self.pending.lock().unwrap().remove(&id);
+        self.publish(outcome);
+    }
+
+    /// 尚未完成的交易，按提交时间排序
+    pub fn pending(&self) -> Vec<PendingTrade> {
+        let mut pending: Vec<PendingTrade> =
+            self.pending.lock().unwrap().values().cloned().collect();
+        pending.sort_by_key(|trade| trade.submitted_at);
+        pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_publish_to_subscribers() {
+        let bus = TradeOutcomeBus::default();
+        // 没有订阅者时不报错
+        bus.publish(outcome(None));
+
+        let mut receiver = bus.subscribe();
+        bus.publish(outcome(Some("slippage".to_string())));
+        let received = receiver.recv().await.unwrap();
+        assert!(!received.is_success());
+        assert_eq!(received.idempotency_key.as_deref(), Some("key"));
+    }
+
+    #[test]
+    fn test_track_pending_trades() {
+        let bus = TradeOutcomeBus::default();
+        let first = bus.track(pending());
+        let second = bus.track(pending());
+        assert_eq!(bus.pending().len(), 2);
+
+        bus.complete(first, outcome(None));
+        assert_eq!(bus.pending().len(), 1);
+        bus.complete(second, outcome(None));
+        assert!(bus.pending().is_empty());
+    }
+
+    fn pending() -> PendingTrade {
+        PendingTrade {
+            dex_type: DexType::PumpFun,
+            trade_type: TradeType::Buy,
+            mint: Pubkey::new_unique(),
+            amount: 1_000,
+            idempotency_key: None,
+            submitted_at: Utc::now(),
+        }
+    }
+
+    fn outcome(error: Option<String>) -> TradeOutcome {
+        TradeOutcome {
+            dex_type: DexType::PumpFun,
+            trade_type: TradeType::Buy,
+            mint: Pubkey::new_unique(),
+            amount: 1_000,
+            idempotency_key: Some("key".to_string()),
+            error,
+            submission: SubmissionReport::default(),
+        }
+    }
+}
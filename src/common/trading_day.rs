@@ -0,0 +1,31 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, Offset, Utc};
+
+/// UTC 偏移，未配置报告时区时使用
+pub fn utc_offset() -> FixedOffset {
+    Utc.fix()
+}
+
+/// `at` 在 `timezone` 中所属的交易日，时间仍以 UTC 存储，只有日期边界按时区划分
+pub fn trading_day(timezone: &FixedOffset, at: DateTime<Utc>) -> NaiveDate {
+    at.with_timezone(timezone).date_naive()
+}
+
+/// 当前时间在 `timezone` 中所属的交易日
+pub fn today(timezone: &FixedOffset) -> NaiveDate {
+    trading_day(timezone, Utc::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trading_day_follows_timezone() {
+        // UTC 2025-01-01 20:00 在 UTC+8 已是 1 月 2 日，在 UTC-5 仍是 1 月 1 日
+        let at = DateTime::parse_from_rfc3339("2025-01-01T20:00:00Z").unwrap().with_timezone(&Utc);
+        let day = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        assert_eq!(trading_day(&utc_offset(), at), day);
+        assert_eq!(trading_day(&FixedOffset::east_opt(8 * 3600).unwrap(), at), day.succ_opt().unwrap());
+        assert_eq!(trading_day(&FixedOffset::west_opt(5 * 3600).unwrap(), at), day);
+    }
+}
@@ -1,17 +1,65 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Keypair};
 use serde::Deserialize;
-use crate::{constants::trade::trade::{DEFAULT_BUY_TIP_FEE, DEFAULT_COMPUTE_UNIT_LIMIT, DEFAULT_COMPUTE_UNIT_PRICE, DEFAULT_RPC_UNIT_LIMIT, DEFAULT_RPC_UNIT_PRICE, DEFAULT_SELL_TIP_FEE}, swqos::{SwqosClient, SwqosConfig}};
+use chrono::FixedOffset;
+use crate::common::{trading_day::utc_offset, SpendLimit, WsolPolicy};
+use crate::{constants::trade::trade::{DEFAULT_BUY_TIP_FEE, DEFAULT_COMPUTE_UNIT_LIMIT, DEFAULT_COMPUTE_UNIT_PRICE, DEFAULT_RPC_UNIT_LIMIT, DEFAULT_RPC_UNIT_PRICE, DEFAULT_SELL_TIP_FEE}, swqos::{health::SwqosHealthConfig, rate_limit::SwqosRateLimit, tip_oracle::TipOracleConfig, SwqosClient, SwqosConfig, SwqosType}, trading::{common::{AdaptiveSlippageConfig, FillModel, TipEscalationConfig}, factory::DexType}};
+
+/// `SwqosRegion::Auto` 通道默认的区域探测间隔（毫秒）
+pub const DEFAULT_REGION_PROBE_INTERVAL_MS: u64 = 300_000;
 
 #[derive(Debug, Clone)]
 pub struct TradeConfig {
     pub rpc_url: String,
+    /// 只读副本 RPC 地址，池子查找（getProgramAccounts）等重查询走该节点，
+    /// rpc_url 只用于 blockhash、报价和提交交易；未设置时全部使用 rpc_url
+    pub read_rpc_url: Option<String>,
     pub swqos_configs: Vec<SwqosConfig>,
     pub priority_fee: PriorityFee,
     pub commitment: CommitmentConfig,
     pub lookup_table_key: Option<Pubkey>,
+    /// Helius 优先费 API 地址，用于 PriorityFeeMode::Auto
+    pub priority_fee_api_url: Option<String>,
+    /// 禁止执行交易的协议，仍可用于监控
+    pub disabled_dex_types: Vec<DexType>,
+    /// 自动小费配置，设置后买入小费取自 Jito tip floor
+    pub auto_tip: Option<TipOracleConfig>,
+    /// 按协议覆盖的优先费配置，未设置的协议使用 priority_fee
+    pub dex_priority_fees: HashMap<DexType, PriorityFee>,
+    /// 买入花费上限，在 `SolanaTrade::buy` 中强制执行
+    pub spend_limit: SpendLimit,
+    /// 自适应滑点配置，设置后滑点超限失败时放宽滑点重试
+    pub adaptive_slippage: Option<AdaptiveSlippageConfig>,
+    /// 买入 / 卖出的提交方式
+    pub submission_mode: SubmissionMode,
+    /// PumpFun bonding curve 已完成时自动改走迁移后的 PumpSwap 池子
+    pub auto_route_migrated: bool,
+    /// 交易前后 wSOL 账户的处理方式，协议参数 `auto_handle_wsol` 为 false 时不生效
+    pub wsol_policy: WsolPolicy,
+    /// 为 true 时带小费的买入 / 卖出在第一个 SWQOS 成功后立即返回并取消其余提交，
+    /// 为 false 时等待所有 SWQOS 完成
+    pub race_submissions: bool,
+    /// SWQOS 健康检查配置，设置后定时探测各通道，连续失败的通道在提交时被跳过
+    pub swqos_health_check: Option<SwqosHealthConfig>,
+    /// 按通道类型设置的提交限速，同类型的每个客户端各自一个令牌桶，未设置的通道不限速
+    pub swqos_rate_limits: HashMap<SwqosType, SwqosRateLimit>,
+    /// 小费账户刷新间隔（毫秒），设置后定时从服务商接口（目前为 Jito getTipAccounts）刷新小费账户
+    pub tip_account_refresh_ms: Option<u64>,
+    /// `SwqosRegion::Auto` 通道重新探测区域延迟的间隔（毫秒）
+    pub region_probe_interval_ms: u64,
+    /// 为 true 时所有 SWQOS 都失败且没有交易已交给 SWQOS 发送时，通过 rpc_url 提交不带小费的交易（skip_preflight）
+    pub rpc_fallback: bool,
+    /// 小费递增配置，设置后带小费的交易在指定 slot 数内未上链时提高小费重新提交
+    pub tip_escalation: Option<TipEscalationConfig>,
+    /// 执行方式，`Paper` 时买入 / 卖出按实时报价模拟成交，不提交交易
+    pub execution_mode: ExecutionMode,
+    /// `Paper` 模式的成交模型，None 时按协议实时报价成交（含手续费和价格冲击）
+    pub paper_fill_model: Option<FillModel>,
+    /// 报告时区，每日花费上限、每日盈亏和状态报告按该时区划分交易日，时间戳仍以 UTC 存储
+    pub reporting_timezone: FixedOffset,
 }
 
 impl TradeConfig {
@@ -24,12 +72,74 @@ impl TradeConfig {
     ) -> Self {
         Self { 
             rpc_url, 
+            read_rpc_url: None,
             swqos_configs,
             priority_fee, 
             commitment, 
             lookup_table_key,
+            priority_fee_api_url: None,
+            disabled_dex_types: vec![],
+            auto_tip: None,
+            dex_priority_fees: HashMap::new(),
+            spend_limit: SpendLimit::default(),
+            adaptive_slippage: None,
+            submission_mode: SubmissionMode::default(),
+            auto_route_migrated: true,
+            wsol_policy: WsolPolicy::default(),
+            race_submissions: false,
+            swqos_health_check: None,
+            swqos_rate_limits: HashMap::new(),
+            tip_account_refresh_ms: None,
+            region_probe_interval_ms: DEFAULT_REGION_PROBE_INTERVAL_MS,
+            rpc_fallback: false,
+            tip_escalation: None,
+            execution_mode: ExecutionMode::default(),
+            paper_fill_model: None,
+            reporting_timezone: utc_offset(),
         }
     }
+
+    /// 指定协议是否允许执行交易
+    pub fn is_execution_enabled(&self, dex_type: &DexType) -> bool {
+        !self.disabled_dex_types.contains(dex_type)
+    }
+
+    /// 指定协议使用的优先费配置
+    pub fn priority_fee_for(&self, dex_type: &DexType) -> &PriorityFee {
+        self.dex_priority_fees.get(dex_type).unwrap_or(&self.priority_fee)
+    }
+}
+
+/// 买入 / 卖出的提交方式
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SubmissionMode {
+    /// 等待所有 SWQOS 提交及确认完成后返回
+    #[default]
+    AwaitConfirmation,
+    /// 交易签名并交给第一个提交通道后即返回该签名，确认在后台完成，结果通过
+    /// `SolanaTrade::subscribe_trade_outcomes` 广播
+    FireAndTrack,
+}
+
+/// 买入 / 卖出的执行方式
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutionMode {
+    /// 构建并提交交易
+    #[default]
+    Live,
+    /// 按实时报价模拟成交，花费上限、成交查询和监控流程与实盘一致，用于试运行新配置
+    Paper,
+}
+
+/// compute unit price 的设置方式
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum PriorityFeeMode {
+    /// 使用配置中的固定值
+    #[default]
+    Fixed,
+    /// 每笔交易按最近优先费的指定百分位动态估算
+    Auto(u8),
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -42,6 +152,11 @@ pub struct PriorityFee {
     pub buy_tip_fees: Vec<f64>,
     pub smart_buy_tip_fee: f64,
     pub sell_tip_fee: f64,
+    #[serde(default)]
+    pub mode: PriorityFeeMode,
+    /// 设置后先模拟交易，按 实际消耗 × 该系数 设置计算单元上限
+    #[serde(default)]
+    pub compute_unit_margin: Option<f64>,
 }
 
 impl Default for PriorityFee {
@@ -54,7 +169,47 @@ impl Default for PriorityFee {
             buy_tip_fee: DEFAULT_BUY_TIP_FEE, 
             buy_tip_fees: vec![],
             smart_buy_tip_fee: 0.0,
-            sell_tip_fee: DEFAULT_SELL_TIP_FEE 
+            sell_tip_fee: DEFAULT_SELL_TIP_FEE,
+            mode: PriorityFeeMode::Fixed,
+            compute_unit_margin: None,
+        }
+    }
+}
+
+impl PriorityFee {
+    /// 创建按指定百分位自动估算 compute unit price 的配置
+    pub fn auto(percentile: u8) -> Self {
+        Self { mode: PriorityFeeMode::Auto(percentile), ..Default::default() }
+    }
+}
+
+/// 单笔交易的计算预算及小费覆盖，未设置的字段使用 PriorityFee 中的值
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExecutionOverrides {
+    /// 计算单元上限，设置后不再按模拟结果调整
+    pub unit_limit: Option<u32>,
+    /// compute unit price（micro-lamports），设置后不再自动估算
+    pub unit_price: Option<u64>,
+    /// 小费（SOL），买入时覆盖 buy_tip_fee 及自动小费，卖出时覆盖 sell_tip_fee
+    pub tip_fee: Option<f64>,
+    /// 幂等键，有效期内（默认 60 秒）相同键的买入 / 卖出只提交一次，交易失败后可重试
+    pub idempotency_key: Option<String>,
+    /// 只提交到这些类型的 SWQOS 通道（如卖出只走 Jito），None 为所有通道
+    pub swqos_types: Option<Vec<SwqosType>>,
+}
+
+impl ExecutionOverrides {
+    /// 将计算单元上限和价格覆盖写入 priority_fee
+    pub fn apply_compute_budget(&self, priority_fee: &mut PriorityFee) {
+        if let Some(unit_limit) = self.unit_limit {
+            priority_fee.unit_limit = unit_limit;
+            priority_fee.rpc_unit_limit = unit_limit;
+            priority_fee.compute_unit_margin = None;
+        }
+        if let Some(unit_price) = self.unit_price {
+            priority_fee.unit_price = unit_price;
+            priority_fee.rpc_unit_price = unit_price;
+            priority_fee.mode = PriorityFeeMode::Fixed;
         }
     }
 }
@@ -75,3 +230,25 @@ impl MethodArgs {
 }
 
 pub type AnyResult<T> = anyhow::Result<T>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_execution_enabled() {
+        let mut config = TradeConfig::new(
+            "http://127.0.0.1:8899".to_string(),
+            vec![],
+            PriorityFee::default(),
+            CommitmentConfig::confirmed(),
+            None,
+        );
+        assert!(config.is_execution_enabled(&DexType::PumpFun));
+
+        // 禁止的协议仍可监控，只是不执行交易
+        config.disabled_dex_types = vec![DexType::PumpFun];
+        assert!(!config.is_execution_enabled(&DexType::PumpFun));
+        assert!(config.is_execution_enabled(&DexType::PumpSwap));
+    }
+}
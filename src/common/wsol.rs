@@ -0,0 +1,105 @@
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+use solana_system_interface::instruction::transfer;
+use spl_associated_token_account::{
+    get_associated_token_address, instruction::create_associated_token_account_idempotent,
+};
+
+/// 交易前后 wSOL 账户的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WsolPolicy {
+    /// 买入前包装 SOL，交易后保留 wSOL 账户及余额，适合连续交易省去反复开关账户
+    KeepWrapped,
+    /// 买入前包装 SOL，交易后关闭 wSOL 账户并取回 SOL 和租金
+    #[default]
+    UnwrapAfterTrade,
+    /// 不包装 SOL，直接使用 wSOL 账户中已有的余额，交易后保留账户
+    UseExistingBalance,
+}
+
+impl WsolPolicy {
+    /// 结合协议参数中的 `auto_handle_wsol`，得到实际生效的策略
+    ///
+    /// `auto_handle_wsol` 为 false 时保持原有行为：不包装也不关闭账户
+    pub fn resolve(self, auto_handle_wsol: bool) -> Self {
+        if auto_handle_wsol {
+            self
+        } else {
+            WsolPolicy::UseExistingBalance
+        }
+    }
+
+    /// 买入前是否需要把 SOL 包装成 wSOL
+    pub fn wraps_input(&self) -> bool {
+        !matches!(self, WsolPolicy::UseExistingBalance)
+    }
+
+    /// 交易后是否关闭 wSOL 账户
+    pub fn unwraps_after_trade(&self) -> bool {
+        matches!(self, WsolPolicy::UnwrapAfterTrade)
+    }
+}
+
+/// 获取 owner 的 wSOL ATA 地址
+pub fn get_wsol_account(owner: &Pubkey) -> Pubkey {
+    get_associated_token_address(owner, &spl_token::native_mint::ID)
+}
+
+/// 创建 wSOL ATA 账户，已存在时不做任何事
+pub fn create_account(owner: &Pubkey) -> Instruction {
+    create_associated_token_account_idempotent(
+        owner,
+        owner,
+        &spl_token::native_mint::ID,
+        &spl_token::ID,
+    )
+}
+
+/// 同步 wSOL 账户余额，使直接转入的 lamports 计入代币余额
+pub fn sync_native(owner: &Pubkey) -> Instruction {
+    spl_token::instruction::sync_native(&spl_token::ID, &get_wsol_account(owner)).unwrap()
+}
+
+/// 将 `amount` lamports 包装为 wSOL：创建账户、转入 SOL 并同步余额
+pub fn wrap(owner: &Pubkey, amount: u64) -> Vec<Instruction> {
+    vec![create_account(owner), transfer(owner, &get_wsol_account(owner), amount), sync_native(owner)]
+}
+
+/// 关闭 wSOL 账户，全部余额和租金退回 owner
+pub fn unwrap_all(owner: &Pubkey) -> Instruction {
+    spl_token::instruction::close_account(
+        &spl_token::ID,
+        &get_wsol_account(owner),
+        owner,
+        owner,
+        &[],
+    )
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_resolution() {
+        assert_eq!(WsolPolicy::default(), WsolPolicy::UnwrapAfterTrade);
+        assert_eq!(WsolPolicy::KeepWrapped.resolve(true), WsolPolicy::KeepWrapped);
+        assert_eq!(WsolPolicy::KeepWrapped.resolve(false), WsolPolicy::UseExistingBalance);
+
+        assert!(WsolPolicy::UnwrapAfterTrade.wraps_input());
+        assert!(WsolPolicy::UnwrapAfterTrade.unwraps_after_trade());
+        assert!(WsolPolicy::KeepWrapped.wraps_input());
+        assert!(!WsolPolicy::KeepWrapped.unwraps_after_trade());
+        assert!(!WsolPolicy::UseExistingBalance.wraps_input());
+        assert!(!WsolPolicy::UseExistingBalance.unwraps_after_trade());
+    }
+
+    #[test]
+    fn test_wrap_instructions() {
+        let owner = Pubkey::new_unique();
+        let instructions = wrap(&owner, 1_000);
+        assert_eq!(instructions.len(), 3);
+        assert_eq!(instructions[2].accounts[0].pubkey, get_wsol_account(&owner));
+        assert_eq!(unwrap_all(&owner).accounts[0].pubkey, get_wsol_account(&owner));
+    }
+}
@@ -14,6 +14,7 @@
 pub mod seeds {
     pub const POOL_SEED: &[u8] = b"pool";
     pub const POOL_VAULT_SEED: &[u8] = b"pool_vault";
+    pub const METADATA_SEED: &[u8] = b"metadata";
 }
 
 /// Constants related to program accounts and authorities
@@ -27,6 +28,9 @@ pub mod accounts {
     pub const EVENT_AUTHORITY: Pubkey = pubkey!("2DPAtwB8L12vrMRExbLuyGnC7n2J5LNoZQSejeQGpwkr");
     pub const WSOL_TOKEN_ACCOUNT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
     pub const BONK: Pubkey = pubkey!("LanMV9sAd7wArD4vJFi2qDdfnVhFxYSUg6eADduJ3uj");
+    pub const MPL_TOKEN_METADATA: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+    pub const SYSTEM_PROGRAM: Pubkey = pubkey!("11111111111111111111111111111111");
+    pub const RENT: Pubkey = pubkey!("SysvarRent111111111111111111111111111111111");
 
     pub const PLATFORM_FEE_RATE: u128 = 100; // 1%
     pub const PROTOCOL_FEE_RATE: u128 = 25; // 0.25%
@@ -35,3 +39,17 @@ pub mod accounts {
 
 pub const BUY_EXECT_IN_DISCRIMINATOR: [u8; 8] = [250, 234, 13, 123, 213, 156, 19, 236];
 pub const SELL_EXECT_IN_DISCRIMINATOR: [u8; 8] = [149, 39, 222, 155, 211, 124, 152, 26];
+pub const INITIALIZE_DISCRIMINATOR: [u8; 8] = [175, 175, 109, 31, 13, 152, 155, 237];
+
+/// letsbonk.fun 发币使用的默认参数：6 位小数、10 亿总量，募集 85 SOL 后迁移到 Raydium CPMM
+pub mod launch {
+    pub const DECIMALS: u8 = 6;
+    pub const SUPPLY: u64 = 1_000_000_000_000_000;
+    pub const TOTAL_BASE_SELL: u64 = 793_100_000_000_000;
+    pub const TOTAL_QUOTE_FUND_RAISING: u64 = 85_000_000_000;
+    /// 迁移类型：0 为 Raydium AMM v4，1 为 Raydium CPMM
+    pub const MIGRATE_TYPE_CPMM: u8 = 1;
+    /// 按以上参数新建的池子的虚拟储备
+    pub const VIRTUAL_BASE: u128 = 1073025605596382;
+    pub const VIRTUAL_QUOTE: u128 = 30000852951;
+}
@@ -0,0 +1,24 @@
+//! Constants used by the crate.
+//!
+//! This module contains various constants used throughout the crate, including:
+//!
+//! - Jupiter swap API endpoints
+//! - Program account addresses and public keys
+//!
+//! The constants are organized into submodules for better organization:
+//!
+//! - `accounts`: Contains important program account addresses
+
+/// Constants related to program accounts and authorities
+pub mod accounts {
+    use solana_sdk::{pubkey, pubkey::Pubkey};
+    pub const WSOL_TOKEN_ACCOUNT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
+    pub const COMPUTE_BUDGET_PROGRAM: Pubkey =
+        pubkey!("ComputeBudget111111111111111111111111111111");
+}
+
+/// 免费的 Jupiter swap API 地址，付费 API 需同时设置 api_key
+pub const DEFAULT_API_URL: &str = "https://lite-api.jup.ag/swap/v1";
+
+/// ComputeBudgetInstruction::SetComputeUnitLimit 的指令序号
+pub const SET_COMPUTE_UNIT_LIMIT_TAG: u8 = 2;
@@ -4,10 +4,24 @@ pub mod pumpswap;
 pub mod swqos;
 pub mod trade;
 pub mod raydium_cpmm;
+pub mod raydium_amm_v4;
+pub mod raydium_clmm;
+pub mod orca_whirlpool;
+pub mod jupiter;
+pub mod moonshot;
+pub mod boop;
+pub mod phoenix;
 
 pub mod trade_platform {
     pub const PUMPFUN: &'static str = "pumpfun";
     pub const PUMPFUN_SWAP: &'static str = "pumpswap";
     pub const BONK: &'static str = "bonk";
     pub const RAYDIUM_CPMM: &'static str = "raydium_cpmm";
+    pub const RAYDIUM_AMM_V4: &str = "raydium_amm_v4";
+    pub const RAYDIUM_CLMM: &str = "raydium_clmm";
+    pub const ORCA_WHIRLPOOL: &str = "orca_whirlpool";
+    pub const JUPITER: &str = "jupiter";
+    pub const MOONSHOT: &str = "moonshot";
+    pub const BOOP: &str = "boop";
+    pub const PHOENIX: &str = "phoenix";
 }
@@ -0,0 +1,41 @@
+//! Constants used by the crate.
+//!
+//! This module contains various constants used throughout the crate, including:
+//!
+//! - Seeds for deriving Program Derived Addresses (PDAs)
+//! - Program account addresses and public keys
+//!
+//! The constants are organized into submodules for better organization:
+//!
+//! - `seeds`: Contains seed values used for PDA derivation
+//! - `accounts`: Contains important program account addresses
+
+/// Constants used as seeds for deriving PDAs (Program Derived Addresses)
+pub mod seeds {
+    pub const TICK_ARRAY_SEED: &[u8] = b"tick_array";
+    pub const ORACLE_SEED: &[u8] = b"oracle";
+}
+
+/// Constants related to program accounts and authorities
+pub mod accounts {
+    use solana_sdk::{pubkey, pubkey::Pubkey};
+    pub const TOKEN_PROGRAM: Pubkey = spl_token::ID;
+    pub const MEMO_PROGRAM: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+    pub const WSOL_TOKEN_ACCOUNT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
+    pub const WHIRLPOOL_PROGRAM: Pubkey = pubkey!("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc");
+}
+
+/// 每个 tick array 包含的 tick 数量
+pub const TICK_ARRAY_SIZE: i32 = 88;
+/// swap 指令固定携带的 tick array 数量
+pub const SWAP_TICK_ARRAYS: usize = 3;
+
+/// sqrt 价格上下限（Q64.64）
+pub const MIN_SQRT_PRICE_X64: u128 = 4295048016;
+pub const MAX_SQRT_PRICE_X64: u128 = 79226673515401279992447579055;
+
+/// Whirlpool 账户中 token_mint_a / token_mint_b 字段的偏移量（含 8 字节 discriminator）
+pub const TOKEN_MINT_A_OFFSET: usize = 101;
+pub const TOKEN_MINT_B_OFFSET: usize = 181;
+
+pub const SWAP_V2_DISCRIMINATOR: &[u8] = &[43, 4, 237, 11, 26, 201, 30, 98];
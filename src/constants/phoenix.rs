@@ -0,0 +1,40 @@
+//! Constants used by the crate.
+//!
+//! This module contains various constants used throughout the crate, including:
+//!
+//! - Seeds for deriving Program Derived Addresses (PDAs)
+//! - Program account addresses and public keys
+//!
+//! The constants are organized into submodules for better organization:
+//!
+//! - `seeds`: Contains seed values used for PDA derivation
+//! - `accounts`: Contains important program account addresses
+
+/// Constants used as seeds for deriving PDAs (Program Derived Addresses)
+pub mod seeds {
+    pub const LOG_AUTHORITY_SEED: &[u8] = b"log";
+}
+
+/// Constants related to program accounts and authorities
+pub mod accounts {
+    use solana_sdk::{pubkey, pubkey::Pubkey};
+
+    pub const PHOENIX: Pubkey = pubkey!("PhoeNiXZ8ByJGLkxNfZRnkUfjvmuYqLR89jjFHGqdXY");
+    pub const WSOL_TOKEN_ACCOUNT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
+    pub const TOKEN_PROGRAM: Pubkey = spl_token::ID;
+}
+
+/// 市场账户头部（MarketHeader）大小
+pub const MARKET_HEADER_SIZE: usize = 576;
+/// 头部中 base / quote mint 的偏移，用于 getProgramAccounts 过滤
+pub const BASE_MINT_OFFSET: usize = 48;
+pub const QUOTE_MINT_OFFSET: usize = 128;
+
+/// Swap 指令编号
+pub const SWAP_INSTRUCTION: u8 = 0;
+/// OrderPacket::ImmediateOrCancel 的枚举编号
+pub const ORDER_PACKET_IMMEDIATE_OR_CANCEL: u8 = 2;
+pub const SIDE_BID: u8 = 0;
+pub const SIDE_ASK: u8 = 1;
+/// SelfTradeBehavior::DecrementTake
+pub const SELF_TRADE_DECREMENT_TAKE: u8 = 2;
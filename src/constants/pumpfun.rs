@@ -32,6 +32,9 @@ pub mod seeds {
 
     /// Seed for global volume accumulator PDAs
     pub const GLOBAL_VOLUME_ACCUMULATOR_SEED: &[u8] = b"global_volume_accumulator";
+
+    /// Seed for the pool authority PDA that creates the PumpSwap pool on migration
+    pub const POOL_AUTHORITY_SEED: &[u8] = b"pool-authority";
 }
 
 pub mod global_constants {
@@ -26,6 +26,12 @@ pub mod seeds {
 
     pub const USER_VOLUME_ACCUMULATOR_SEED: &[u8] = b"user_volume_accumulator";
     pub const GLOBAL_VOLUME_ACCUMULATOR_SEED: &[u8] = b"global_volume_accumulator";
+
+    /// Seed for pool PDAs
+    pub const POOL_SEED: &[u8] = b"pool";
+
+    /// Pool index used when PumpFun migrates a completed bonding curve
+    pub const CANONICAL_POOL_INDEX: u16 = 0;
 }
 
 /// Constants related to program accounts and authorities
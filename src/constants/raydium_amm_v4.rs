@@ -0,0 +1,29 @@
+//! Constants used by the crate.
+//!
+//! This module contains various constants used throughout the crate, including:
+//!
+//! - Program account addresses and public keys
+//! - Instruction tags
+//!
+//! The constants are organized into submodules for better organization:
+//!
+//! - `accounts`: Contains important program account addresses
+
+/// Constants related to program accounts and authorities
+pub mod accounts {
+    use solana_sdk::{pubkey, pubkey::Pubkey};
+    pub const AUTHORITY: Pubkey = pubkey!("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1");
+    pub const TOKEN_PROGRAM: Pubkey = spl_token::ID;
+    pub const WSOL_TOKEN_ACCOUNT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
+    pub const RAYDIUM_AMM_V4: Pubkey = pubkey!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+}
+
+/// AmmInfo 账户大小
+pub const AMM_INFO_SIZE: u64 = 752;
+/// AmmInfo 中 coin_vault_mint 字段的偏移量
+pub const COIN_MINT_OFFSET: usize = 400;
+/// AmmInfo 中 pc_vault_mint 字段的偏移量
+pub const PC_MINT_OFFSET: usize = 432;
+
+/// swap_base_in_v2 指令标识，无需 OpenBook 市场账户
+pub const SWAP_BASE_IN_V2_TAG: u8 = 16;
@@ -0,0 +1,44 @@
+//! Constants used by the crate.
+//!
+//! This module contains various constants used throughout the crate, including:
+//!
+//! - Seeds for deriving Program Derived Addresses (PDAs)
+//! - Program account addresses and public keys
+//!
+//! The constants are organized into submodules for better organization:
+//!
+//! - `seeds`: Contains seed values used for PDA derivation
+//! - `accounts`: Contains important program account addresses
+
+/// Constants used as seeds for deriving PDAs (Program Derived Addresses)
+pub mod seeds {
+    pub const TICK_ARRAY_SEED: &[u8] = b"tick_array";
+    pub const TICK_ARRAY_BITMAP_EXTENSION_SEED: &[u8] = b"pool_tick_array_bitmap_extension";
+}
+
+/// Constants related to program accounts and authorities
+pub mod accounts {
+    use solana_sdk::{pubkey, pubkey::Pubkey};
+    pub const TOKEN_PROGRAM: Pubkey = spl_token::ID;
+    pub const TOKEN_PROGRAM_2022: Pubkey = spl_token_2022::ID;
+    pub const MEMO_PROGRAM: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+    pub const WSOL_TOKEN_ACCOUNT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
+    pub const RAYDIUM_CLMM: Pubkey = pubkey!("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK");
+}
+
+/// 每个 tick array 包含的 tick 数量
+pub const TICK_ARRAY_SIZE: i32 = 60;
+/// 池子内置 tick array 位图覆盖的 tick array 数量（正负各一半）
+pub const TICK_ARRAY_BITMAP_SIZE: i32 = 1024;
+/// swap 指令最多携带的 tick array 数量
+pub const MAX_TICK_ARRAYS: usize = 3;
+
+/// sqrt 价格上下限（Q64.64）
+pub const MIN_SQRT_PRICE_X64: u128 = 4295048016;
+pub const MAX_SQRT_PRICE_X64: u128 = 79226673521066979257578248091;
+
+/// PoolState 中 token_mint_0 / token_mint_1 字段的偏移量（含 8 字节 discriminator）
+pub const TOKEN_MINT_0_OFFSET: usize = 73;
+pub const TOKEN_MINT_1_OFFSET: usize = 105;
+
+pub const SWAP_V2_DISCRIMINATOR: &[u8] = &[43, 4, 237, 11, 26, 201, 30, 98];
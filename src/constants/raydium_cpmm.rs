@@ -15,6 +15,7 @@ pub mod seeds {
     pub const POOL_SEED: &[u8] = b"pool";
     pub const POOL_VAULT_SEED: &[u8] = b"pool_vault";
     pub const OBSERVATION_STATE_SEED: &[u8] = b"observation";
+    pub const POOL_LP_MINT_SEED: &[u8] = b"pool_lp_mint";
 }
 
 /// Constants related to program accounts and authorities
@@ -25,7 +26,14 @@ pub mod accounts {
     pub const TOKEN_PROGRAM: Pubkey = spl_token::ID;
     pub const WSOL_TOKEN_ACCOUNT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
     pub const RAYDIUM_CPMM: Pubkey = pubkey!("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C");
+    pub const SYSTEM_PROGRAM: Pubkey = pubkey!("11111111111111111111111111111111");
+    pub const ASSOCIATED_TOKEN_PROGRAM: Pubkey =
+        pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+    pub const RENT: Pubkey = pubkey!("SysvarRent111111111111111111111111111111111");
+    /// 创建池子手续费的接收账户
+    pub const CREATE_POOL_FEE_RECEIVER: Pubkey = pubkey!("DNXgeM9EiiaAbaWvwjHj9fQQLAX5ZsfHyvmYUNRAdNC8");
 }
 
 pub const SWAP_BASE_IN_DISCRIMINATOR: &[u8] = &[143, 190, 90, 218, 196, 30, 51, 222];
 pub const SWAP_BASE_OUT_DISCRIMINATOR: &[u8] = &[55, 217, 98, 86, 163, 74, 180, 173];
+pub const INITIALIZE_DISCRIMINATOR: &[u8] = &[175, 175, 109, 31, 13, 152, 155, 237];
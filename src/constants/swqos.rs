@@ -59,6 +59,32 @@ pub const BLOX_TIP_ACCOUNTS: &[Pubkey] = &[
     pubkey!("FogxVNs6Mm2w9rnGL1vkARSwJxvLE8mujTv3LK8RnUhF"),
 ];
 
+pub const NODE1_TIP_ACCOUNTS: &[Pubkey] = &[
+    pubkey!("node1PqAa3BWWzUnTHVbw8NJHC874zn9ngAkXjgWEej"),
+    pubkey!("node1UzzTxAAeBTpfZkQPJXBAqixsbdth11ba1NXLBG"),
+    pubkey!("node1Qm1bV4fwYnCurP8otJ9s5yrkPq7SPZ5uhj3Tsv"),
+    pubkey!("node1PUber6SFmSQgvf2ECmXsHP5o3boRSGhvJyPMX1"),
+    pubkey!("node1AyMbeqiVN6eoQzEAwCA6Pk826hrdqdAHR7cdJ3"),
+    pubkey!("node1YtWCoTwwVYTFLfS19zquRQzYX332hs1HEuRBjC"),
+];
+
+pub const BLOCKRAZOR_TIP_ACCOUNTS: &[Pubkey] = &[
+    pubkey!("FjmZZrFvhnqqb9ThCuMVnENaM3JGVuGWNyCAxRJcFpg9"),
+    pubkey!("6No2i3aawzHsjtThw81iq1EXPJN6rh8eSJCLaYZfKDTG"),
+    pubkey!("A9cWowVAiHe9pJfKAj3TJiN9VpbzMUq6E4kEvf5mUT22"),
+    pubkey!("Gywj98ophM7GmkDdaWs4isqZnDdFCW7B46TXmKfvyqSm"),
+    pubkey!("68Pwb4jS7eZATjDfhmTXgRJjCiZmw1L7Huy4HNpnxJ3o"),
+    pubkey!("4ABhJh5rZPjv63RBJBuyWzBK3g9gWMUQdTZP2kiW31V9"),
+    pubkey!("B2M4NG5eyZp5SBQrSdtemzk5TqVuaWGQnowGaCBt8GyM"),
+    pubkey!("5jA59cXMKQqZAVdtopv8q3yyw9SYfiE3vUCbt7p8MfVf"),
+    pubkey!("5YktoWygr1Bp9wiS1xtMtUki1PeYuuzuCF98tqwYxf61"),
+    pubkey!("295Avbam4qGShBYK7E9H5Ldew4B3WyJGmgmXfiWdeeyV"),
+    pubkey!("EDi4rSy2LZgKJX74mbLTFk4mxoTgT6F7HxxzG2HBAFyK"),
+    pubkey!("BnGKHAC386n4Qmv9xtpBVbRaUTKixjBe3oagkPFKtoy6"),
+    pubkey!("Dd7K2Fp7AtoN8xCghKDRmyqr5U169t48Tw5fEd3wT9mq"),
+    pubkey!("AP6qExwrbRgBAVaehg4b5xHENX815sMabtBzUzVB4v8S"),
+];
+
 // NewYork,
 // Frankfurt,
 // Amsterdam,
@@ -123,3 +149,26 @@ pub const SWQOS_ENDPOINTS_BLOX: [&str; 8] = [
     "https://germany.solana.dex.blxrbdn.com",
 ];
 
+pub const SWQOS_ENDPOINTS_NODE1: [&str; 8] = [
+    "http://ny.node1.me",
+    "http://fra.node1.me",
+    "http://ams.node1.me",
+    "http://ams.node1.me",
+    "http://tk.node1.me",
+    "http://lon.node1.me",
+    "http://ny.node1.me",
+    "http://fra.node1.me",
+];
+
+pub const SWQOS_ENDPOINTS_BLOCKRAZOR: [&str; 8] = [
+    "http://newyork.solana.blockrazor.xyz:443",
+    "http://frankfurt.solana.blockrazor.xyz:443",
+    "http://amsterdam.solana.blockrazor.xyz:443",
+    "http://amsterdam.solana.blockrazor.xyz:443",
+    "http://tokyo.solana.blockrazor.xyz:443",
+    "http://london.solana.blockrazor.xyz:443",
+    "http://newyork.solana.blockrazor.xyz:443",
+    "http://frankfurt.solana.blockrazor.xyz:443",
+];
+
+pub const JITO_TIP_FLOOR_URL: &str = "https://bundles.jito.wtf/api/v1/bundles/tip_floor";
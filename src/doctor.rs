@@ -0,0 +1,155 @@
+use std::fmt;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use solana_program::address_lookup_table::{program::ID as ADDRESS_LOOKUP_TABLE_PROGRAM, state::AddressLookupTable};
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signer::Signer};
+use yellowstone_grpc_client::{ClientTlsConfig, GeyserGrpcClient};
+
+use crate::builder::validate_trade_config;
+use crate::SolanaTrade;
+
+const GRPC_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Result of a single preflight check
+#[derive(Debug, Clone)]
+pub struct DoctorCheck {
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+/// Pass/fail report produced by [`SolanaTrade::doctor`]
+#[derive(Debug, Clone, Default)]
+pub struct DoctorReport {
+    pub checks: Vec<DoctorCheck>,
+}
+
+impl DoctorReport {
+    /// Whether every check passed
+    pub fn is_ok(&self) -> bool {
+        self.checks.iter().all(|check| check.passed)
+    }
+
+    /// Checks that failed
+    pub fn failures(&self) -> impl Iterator<Item = &DoctorCheck> {
+        self.checks.iter().filter(|check| !check.passed)
+    }
+
+    fn record(&mut self, name: impl Into<String>, result: Result<String>) {
+        let (passed, detail) = match result {
+            Ok(detail) => (true, detail),
+            Err(e) => (false, e.to_string()),
+        };
+        self.checks.push(DoctorCheck { name: name.into(), passed, detail });
+    }
+}
+
+impl fmt::Display for DoctorReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            let status = if check.passed { "PASS" } else { "FAIL" };
+            writeln!(f, "[{}] {}: {}", status, check.name, check.detail)?;
+        }
+        let failed = self.failures().count();
+        write!(f, "{} checks, {} failed", self.checks.len(), failed)
+    }
+}
+
+impl SolanaTrade {
+    /// Run preflight diagnostics before any funds are at risk
+    ///
+    /// Checks RPC reachability and version, the gRPC endpoint when given, every configured
+    /// SWQOS provider, the payer and fee payer balances, the lookup table and the config
+    /// itself. Individual failures are recorded in the report rather than returned as errors.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let report = solana_trade.doctor(Some(grpc_url), None).await;
+    /// println!("{}", report);
+    /// if !report.is_ok() {
+    ///     return Err(anyhow::anyhow!("preflight checks failed"));
+    /// }
+    /// ```
+    pub async fn doctor(
+        &self,
+        grpc_endpoint: Option<String>,
+        grpc_x_token: Option<String>,
+    ) -> DoctorReport {
+        let mut report = DoctorReport::default();
+
+        report.record("Config", validate_trade_config(&self.trade_config).map(|_| "valid".to_string()));
+
+        let rpc_version = self.rpc.get_version().await;
+        report.record(
+            "RPC",
+            rpc_version
+                .map(|version| format!("{} (solana-core {})", self.trade_config.rpc_url, version.solana_core))
+                .map_err(|e| anyhow!("{} unreachable: {}", self.trade_config.rpc_url, e)),
+        );
+
+        if let Some(read_rpc_url) = &self.trade_config.read_rpc_url {
+            let read_rpc_version = self.read_rpc.get_version().await;
+            report.record(
+                "Read RPC",
+                read_rpc_version
+                    .map(|version| format!("{} (solana-core {})", read_rpc_url, version.solana_core))
+                    .map_err(|e| anyhow!("{} unreachable: {}", read_rpc_url, e)),
+            );
+        }
+
+        if let Some(endpoint) = grpc_endpoint {
+            report.record("gRPC", check_grpc(&endpoint, grpc_x_token).await);
+        }
+
+        for swqos_client in &self.swqos_clients {
+            let swqos_type = swqos_client.get_swqos_type();
+            report.record(
+                format!("SWQOS {:?}", swqos_type),
+                swqos_client.ping().await.map(|_| "reachable".to_string()),
+            );
+        }
+
+        report.record("Payer balance", self.check_balance(&self.payer.pubkey()).await);
+        if let Some(fee_payer) = &self.fee_payer {
+            report.record("Fee payer balance", self.check_balance(&fee_payer.pubkey()).await);
+        }
+
+        if let Some(lookup_table_key) = self.trade_config.lookup_table_key {
+            report.record("Lookup table", self.check_lookup_table(&lookup_table_key).await);
+        }
+
+        report
+    }
+
+    async fn check_balance(&self, pubkey: &Pubkey) -> Result<String> {
+        let balance = self.rpc.get_balance(pubkey).await?;
+        if balance == 0 {
+            return Err(anyhow!("{} has no SOL", pubkey));
+        }
+        Ok(format!("{} has {} SOL", pubkey, balance as f64 / LAMPORTS_PER_SOL as f64))
+    }
+
+    async fn check_lookup_table(&self, lookup_table_key: &Pubkey) -> Result<String> {
+        let account = self.rpc.get_account(lookup_table_key).await?;
+        if account.owner != ADDRESS_LOOKUP_TABLE_PROGRAM {
+            return Err(anyhow!("{} is not an address lookup table", lookup_table_key));
+        }
+        let lookup_table = AddressLookupTable::deserialize(&account.data)?;
+        Ok(format!("{} with {} addresses", lookup_table_key, lookup_table.addresses.len()))
+    }
+}
+
+/// Connect to a Yellowstone gRPC endpoint and query its version
+async fn check_grpc(endpoint: &str, x_token: Option<String>) -> Result<String> {
+    let mut client = GeyserGrpcClient::build_from_shared(endpoint.to_string())?
+        .x_token(x_token)?
+        .tls_config(ClientTlsConfig::new().with_native_roots())?
+        .connect_timeout(GRPC_TIMEOUT)
+        .timeout(GRPC_TIMEOUT)
+        .connect()
+        .await?;
+    let version = client.get_version().await?;
+    Ok(format!("{} ({})", endpoint, version.version))
+}
@@ -1,19 +1,27 @@
 use anyhow::{anyhow, Result};
-use solana_sdk::{instruction::Instruction, signer::Signer};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signer::Signer,
+};
 use solana_system_interface::instruction::transfer;
 use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
 use spl_token::instruction::close_account;
 
 use crate::{
-    constants::bonk::{accounts, BUY_EXECT_IN_DISCRIMINATOR, SELL_EXECT_IN_DISCRIMINATOR},
+    common::wsol,
+    constants::bonk::{
+        accounts, launch, BUY_EXECT_IN_DISCRIMINATOR, INITIALIZE_DISCRIMINATOR,
+        SELL_EXECT_IN_DISCRIMINATOR,
+    },
     constants::trade::trade::DEFAULT_SLIPPAGE,
     trading::bonk::{
-        common::{get_amount_out, get_pool_pda, get_vault_pda},
+        common::{get_amount_out, get_metadata_pda, get_pool_pda, get_vault_pda},
         pool::Pool,
     },
-    trading::common::utils::get_token_balance,
+    trading::common::{token_program::get_mint_token_program, utils::get_token_balance_with_program},
     trading::core::{
-        params::{BonkParams, BuyParams, SellParams},
+        params::{BonkCreateParams, BonkParams, BuyParams, SellParams},
         traits::InstructionBuilder,
     },
 };
@@ -46,31 +54,18 @@ impl BonkInstructionBuilder {
             .as_any()
             .downcast_ref::<BonkParams>()
             .ok_or_else(|| anyhow!("Invalid protocol params for Bonk"))?;
+        let rpc = params.rpc.as_ref().ok_or_else(|| anyhow!("RPC is not set"))?;
+        let token_program = get_mint_token_program(rpc, &params.mint).await?;
 
         let pool_state = get_pool_pda(&params.mint, &accounts::WSOL_TOKEN_ACCOUNT).unwrap();
 
-        // 创建用户代币账户
-        let user_base_token_account = spl_associated_token_account::get_associated_token_address(
-            &params.payer.pubkey(),
-            &params.mint,
-        );
-        let user_quote_token_account = spl_associated_token_account::get_associated_token_address(
-            &params.payer.pubkey(),
-            &accounts::WSOL_TOKEN_ACCOUNT,
-        );
-
-        // 获取池的代币账户
-        let base_vault_account = get_vault_pda(&pool_state, &params.mint).unwrap();
-        let quote_vault_account =
-            get_vault_pda(&pool_state, &accounts::WSOL_TOKEN_ACCOUNT).unwrap();
-
         let mut virtual_base = protocol_params.virtual_base.unwrap_or(0);
         let mut virtual_quote = protocol_params.virtual_quote.unwrap_or(0);
         let mut real_base = protocol_params.real_base.unwrap_or(0);
         let mut real_quote = protocol_params.real_quote.unwrap_or(0);
 
         if virtual_base == 0 || virtual_quote == 0 || real_base == 0 || real_quote == 0 {
-            let pool = Pool::fetch(params.rpc.as_ref().unwrap(), &pool_state).await?;
+            let pool = Pool::fetch(rpc, &pool_state).await?;
             virtual_base = pool.virtual_base as u128;
             virtual_quote = pool.virtual_quote as u128;
             real_base = pool.real_base as u128;
@@ -78,7 +73,6 @@ impl BonkInstructionBuilder {
         }
 
         let amount_in: u64 = params.sol_amount;
-        let share_fee_rate: u64 = 0;
         let minimum_amount_out: u64 = get_amount_out(
             amount_in,
             accounts::PROTOCOL_FEE_RATE,
@@ -91,32 +85,12 @@ impl BonkInstructionBuilder {
             params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE) as u128,
         );
 
+        let wsol_policy = params.wsol_policy.resolve(protocol_params.auto_handle_wsol);
         let mut instructions = vec![];
 
-        if protocol_params.auto_handle_wsol {
-            // 插入wsol
-            instructions.push(
-                // 创建wSOL ATA账户，如果不存在
-                create_associated_token_account_idempotent(
-                    &params.payer.pubkey(),
-                    &params.payer.pubkey(),
-                    &accounts::WSOL_TOKEN_ACCOUNT,
-                    &accounts::TOKEN_PROGRAM,
-                ),
-            );
-            instructions.push(
-                // 将SOL转入wSOL ATA账户
-                transfer(&params.payer.pubkey(), &user_quote_token_account, amount_in),
-            );
-
-            // 同步wSOL余额
-            instructions.push(
-                spl_token::instruction::sync_native(
-                    &accounts::TOKEN_PROGRAM,
-                    &user_quote_token_account,
-                )
-                .unwrap(),
-            );
+        if wsol_policy.wraps_input() {
+            // 创建wSOL ATA账户并将SOL包装为wSOL
+            instructions.extend(wsol::wrap(&params.payer.pubkey(), amount_in));
         }
 
         // 创建用户的基础代币账户
@@ -124,48 +98,21 @@ impl BonkInstructionBuilder {
             &params.payer.pubkey(),
             &params.payer.pubkey(),
             &params.mint,
-            &accounts::TOKEN_PROGRAM,
+            &token_program,
         ));
 
         // 创建买入指令
-        let accounts = vec![
-            solana_sdk::instruction::AccountMeta::new(params.payer.pubkey(), true), // Payer (signer)
-            solana_sdk::instruction::AccountMeta::new_readonly(accounts::AUTHORITY, false), // Authority (readonly)
-            solana_sdk::instruction::AccountMeta::new_readonly(accounts::GLOBAL_CONFIG, false), // Global Config (readonly)
-            solana_sdk::instruction::AccountMeta::new_readonly(accounts::PLATFORM_CONFIG, false), // Platform Config (readonly)
-            solana_sdk::instruction::AccountMeta::new(pool_state, false), // Pool State
-            solana_sdk::instruction::AccountMeta::new(user_base_token_account, false), // User Base Token
-            solana_sdk::instruction::AccountMeta::new(user_quote_token_account, false), // User Quote Token
-            solana_sdk::instruction::AccountMeta::new(base_vault_account, false), // Base Vault
-            solana_sdk::instruction::AccountMeta::new(quote_vault_account, false), // Quote Vault
-            solana_sdk::instruction::AccountMeta::new_readonly(params.mint, false), // Base Token Mint (readonly)
-            solana_sdk::instruction::AccountMeta::new_readonly(accounts::WSOL_TOKEN_ACCOUNT, false), // Quote Token Mint (readonly)
-            solana_sdk::instruction::AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false), // Base Token Program (readonly)
-            solana_sdk::instruction::AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false), // Quote Token Program (readonly)
-            solana_sdk::instruction::AccountMeta::new_readonly(accounts::EVENT_AUTHORITY, false), // Event Authority (readonly)
-            solana_sdk::instruction::AccountMeta::new_readonly(accounts::BONK, false), // Program (readonly)
-        ];
-        // 创建指令数据
-        let mut data = vec![];
-        data.extend_from_slice(&BUY_EXECT_IN_DISCRIMINATOR);
-        data.extend_from_slice(&amount_in.to_le_bytes());
-        data.extend_from_slice(&minimum_amount_out.to_le_bytes());
-        data.extend_from_slice(&share_fee_rate.to_le_bytes());
-
-        instructions.push(Instruction { program_id: accounts::BONK, accounts, data });
+        instructions.push(buy_exact_in_instruction(
+            &params.payer.pubkey(),
+            &params.mint,
+            &token_program,
+            amount_in,
+            minimum_amount_out,
+        ));
 
-        if protocol_params.auto_handle_wsol {
+        if wsol_policy.unwraps_after_trade() {
             // 关闭wSOL ATA账户，回收租金
-            instructions.push(
-                spl_token::instruction::close_account(
-                    &accounts::TOKEN_PROGRAM,
-                    &user_quote_token_account,
-                    &params.payer.pubkey(),
-                    &params.payer.pubkey(),
-                    &[],
-                )
-                .unwrap(),
-            );
+            instructions.push(wsol::unwrap_all(&params.payer.pubkey()));
         }
 
         Ok(instructions)
@@ -180,12 +127,18 @@ impl BonkInstructionBuilder {
             return Err(anyhow!("RPC is not set"));
         }
         let rpc = params.rpc.as_ref().unwrap().clone();
+        let token_program = get_mint_token_program(rpc.as_ref(), &params.mint).await?;
 
         // 获取代币余额
         let mut amount = params.token_amount;
         if params.token_amount.is_none() || params.token_amount.unwrap_or(0) == 0 {
-            let balance_u64 =
-                get_token_balance(rpc.as_ref(), &params.payer.pubkey(), &params.mint).await?;
+            let balance_u64 = get_token_balance_with_program(
+                rpc.as_ref(),
+                &params.payer.pubkey(),
+                &params.mint,
+                &token_program,
+            )
+            .await?;
             amount = Some(balance_u64);
         }
         let amount = amount.unwrap_or(0);
@@ -194,16 +147,18 @@ impl BonkInstructionBuilder {
             return Err(anyhow!("Amount cannot be zero"));
         }
 
-        // 计算预期的SOL数量
-        let minimum_amount_out: u64 = 1;
+        // 计算预期的SOL数量，未指定下限时不做限制
+        let minimum_amount_out: u64 = params.min_sol_out.unwrap_or(1);
 
         let pool_state = get_pool_pda(&params.mint, &accounts::WSOL_TOKEN_ACCOUNT).unwrap();
 
         // 创建用户代币账户
-        let user_base_token_account = spl_associated_token_account::get_associated_token_address(
-            &params.payer.pubkey(),
-            &params.mint,
-        );
+        let user_base_token_account =
+            spl_associated_token_account::get_associated_token_address_with_program_id(
+                &params.payer.pubkey(),
+                &params.mint,
+                &token_program,
+            );
         let user_quote_token_account = spl_associated_token_account::get_associated_token_address(
             &params.payer.pubkey(),
             &accounts::WSOL_TOKEN_ACCOUNT,
@@ -218,16 +173,8 @@ impl BonkInstructionBuilder {
 
         let mut instructions = vec![];
 
-        // 插入wsol
-        instructions.push(
-            // 创建wSOL ATA账户，如果不存在
-            create_associated_token_account_idempotent(
-                &params.payer.pubkey(),
-                &params.payer.pubkey(),
-                &accounts::WSOL_TOKEN_ACCOUNT,
-                &accounts::TOKEN_PROGRAM,
-            ),
-        );
+        // 创建wSOL ATA账户，如果不存在
+        instructions.push(wsol::create_account(&params.payer.pubkey()));
 
         // 创建卖出指令
         let accounts = vec![
@@ -242,7 +189,7 @@ impl BonkInstructionBuilder {
             solana_sdk::instruction::AccountMeta::new(quote_vault_account, false), // Quote Vault
             solana_sdk::instruction::AccountMeta::new_readonly(params.mint, false), // Base Token Mint (readonly)
             solana_sdk::instruction::AccountMeta::new_readonly(accounts::WSOL_TOKEN_ACCOUNT, false), // Quote Token Mint (readonly)
-            solana_sdk::instruction::AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false), // Base Token Program (readonly)
+            solana_sdk::instruction::AccountMeta::new_readonly(token_program, false), // Base Token Program (readonly)
             solana_sdk::instruction::AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false), // Quote Token Program (readonly)
             solana_sdk::instruction::AccountMeta::new_readonly(accounts::EVENT_AUTHORITY, false), // Event Authority (readonly)
             solana_sdk::instruction::AccountMeta::new_readonly(accounts::BONK, false), // Program (readonly)
@@ -263,19 +210,190 @@ impl BonkInstructionBuilder {
             .downcast_ref::<BonkParams>()
             .ok_or_else(|| anyhow!("Invalid protocol params for Bonk"))?;
 
-        if protocol_params.auto_handle_wsol {
-            instructions.push(
-                close_account(
-                    &accounts::TOKEN_PROGRAM,
-                    &user_quote_token_account,
-                    &params.payer.pubkey(),
-                    &params.payer.pubkey(),
-                    &[&params.payer.pubkey()],
-                )
-                .unwrap(),
-            );
+        if params.wsol_policy.resolve(protocol_params.auto_handle_wsol).unwraps_after_trade() {
+            instructions.push(wsol::unwrap_all(&params.payer.pubkey()));
         }
 
         Ok(instructions)
     }
 }
+
+/// 构建 buy_exact_in 指令，花费 `amount_in` 个 wSOL，至少获得 `minimum_amount_out` 个代币
+///
+/// `base_token_program` 为代币所属的代币程序，用于推导用户的代币账户
+fn buy_exact_in_instruction(
+    payer: &Pubkey,
+    mint: &Pubkey,
+    base_token_program: &Pubkey,
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Instruction {
+    let pool_state = get_pool_pda(mint, &accounts::WSOL_TOKEN_ACCOUNT).unwrap();
+    let user_base_token_account =
+        spl_associated_token_account::get_associated_token_address_with_program_id(
+            payer,
+            mint,
+            base_token_program,
+        );
+    let user_quote_token_account = spl_associated_token_account::get_associated_token_address(
+        payer,
+        &accounts::WSOL_TOKEN_ACCOUNT,
+    );
+    let base_vault_account = get_vault_pda(&pool_state, mint).unwrap();
+    let quote_vault_account = get_vault_pda(&pool_state, &accounts::WSOL_TOKEN_ACCOUNT).unwrap();
+    let share_fee_rate: u64 = 0;
+
+    let accounts = vec![
+        AccountMeta::new(*payer, true), // Payer (signer)
+        AccountMeta::new_readonly(accounts::AUTHORITY, false), // Authority (readonly)
+        AccountMeta::new_readonly(accounts::GLOBAL_CONFIG, false), // Global Config (readonly)
+        AccountMeta::new_readonly(accounts::PLATFORM_CONFIG, false), // Platform Config (readonly)
+        AccountMeta::new(pool_state, false), // Pool State
+        AccountMeta::new(user_base_token_account, false), // User Base Token
+        AccountMeta::new(user_quote_token_account, false), // User Quote Token
+        AccountMeta::new(base_vault_account, false), // Base Vault
+        AccountMeta::new(quote_vault_account, false), // Quote Vault
+        AccountMeta::new_readonly(*mint, false), // Base Token Mint (readonly)
+        AccountMeta::new_readonly(accounts::WSOL_TOKEN_ACCOUNT, false), // Quote Token Mint (readonly)
+        AccountMeta::new_readonly(*base_token_program, false), // Base Token Program (readonly)
+        AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false), // Quote Token Program (readonly)
+        AccountMeta::new_readonly(accounts::EVENT_AUTHORITY, false), // Event Authority (readonly)
+        AccountMeta::new_readonly(accounts::BONK, false), // Program (readonly)
+    ];
+    // 创建指令数据
+    let mut data = vec![];
+    data.extend_from_slice(&BUY_EXECT_IN_DISCRIMINATOR);
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&minimum_amount_out.to_le_bytes());
+    data.extend_from_slice(&share_fee_rate.to_le_bytes());
+
+    Instruction { program_id: accounts::BONK, accounts, data }
+}
+
+/// 构建在 letsbonk.fun 发币并由创建者首次买入的指令
+///
+/// 池子按 letsbonk.fun 默认的恒定乘积曲线参数创建，首次买入按新池子的虚拟储备报价，
+/// 与发币在同一笔交易中执行，`sol_amount` 为 0 时只发币。`mint` 需要签名。
+pub fn build_create_and_buy_instructions(
+    payer: &Pubkey,
+    mint: &Pubkey,
+    create_params: &BonkCreateParams,
+    sol_amount: u64,
+    slippage_basis_points: Option<u64>,
+) -> Result<Vec<Instruction>> {
+    let mut instructions = vec![initialize_instruction(payer, mint, create_params)?];
+    if sol_amount == 0 {
+        return Ok(instructions);
+    }
+
+    let minimum_amount_out = get_amount_out(
+        sol_amount,
+        accounts::PROTOCOL_FEE_RATE,
+        accounts::PLATFORM_FEE_RATE,
+        accounts::SHARE_FEE_RATE,
+        launch::VIRTUAL_BASE,
+        launch::VIRTUAL_QUOTE,
+        0,
+        0,
+        slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE) as u128,
+    );
+    if minimum_amount_out == 0 {
+        return Err(anyhow!("Buy amount is too small"));
+    }
+    let user_quote_token_account =
+        spl_associated_token_account::get_associated_token_address(payer, &accounts::WSOL_TOKEN_ACCOUNT);
+
+    // 创建wSOL ATA账户，如果不存在
+    instructions.push(create_associated_token_account_idempotent(
+        payer,
+        payer,
+        &accounts::WSOL_TOKEN_ACCOUNT,
+        &accounts::TOKEN_PROGRAM,
+    ));
+    // 将SOL转入wSOL ATA账户
+    instructions.push(transfer(payer, &user_quote_token_account, sol_amount));
+    // 同步wSOL余额
+    instructions.push(
+        spl_token::instruction::sync_native(&accounts::TOKEN_PROGRAM, &user_quote_token_account)
+            .unwrap(),
+    );
+    // 创建用户的基础代币账户，initialize 创建的是 SPL Token 代币
+    instructions.push(create_associated_token_account_idempotent(
+        payer,
+        payer,
+        mint,
+        &accounts::TOKEN_PROGRAM,
+    ));
+    instructions.push(buy_exact_in_instruction(
+        payer,
+        mint,
+        &accounts::TOKEN_PROGRAM,
+        sol_amount,
+        minimum_amount_out,
+    ));
+    // 关闭wSOL ATA账户，回收租金
+    instructions.push(
+        close_account(&accounts::TOKEN_PROGRAM, &user_quote_token_account, payer, payer, &[])
+            .unwrap(),
+    );
+
+    Ok(instructions)
+}
+
+/// 构建 initialize 指令，创建代币、元数据账户和 bonding curve 池子
+fn initialize_instruction(
+    payer: &Pubkey,
+    mint: &Pubkey,
+    create_params: &BonkCreateParams,
+) -> Result<Instruction> {
+    let pool_state = get_pool_pda(mint, &accounts::WSOL_TOKEN_ACCOUNT)
+        .ok_or_else(|| anyhow!("Failed to derive Bonk pool for {}", mint))?;
+    let base_vault = get_vault_pda(&pool_state, mint)
+        .ok_or_else(|| anyhow!("Failed to derive Bonk base vault"))?;
+    let quote_vault = get_vault_pda(&pool_state, &accounts::WSOL_TOKEN_ACCOUNT)
+        .ok_or_else(|| anyhow!("Failed to derive Bonk quote vault"))?;
+
+    let accounts = vec![
+        AccountMeta::new(*payer, true), // Payer (signer)
+        AccountMeta::new_readonly(*payer, true), // Creator (signer)
+        AccountMeta::new_readonly(accounts::GLOBAL_CONFIG, false), // Global Config (readonly)
+        AccountMeta::new_readonly(accounts::PLATFORM_CONFIG, false), // Platform Config (readonly)
+        AccountMeta::new_readonly(accounts::AUTHORITY, false), // Authority (readonly)
+        AccountMeta::new(pool_state, false), // Pool State
+        AccountMeta::new(*mint, true), // Base Mint (signer)
+        AccountMeta::new_readonly(accounts::WSOL_TOKEN_ACCOUNT, false), // Quote Mint (readonly)
+        AccountMeta::new(base_vault, false), // Base Vault
+        AccountMeta::new(quote_vault, false), // Quote Vault
+        AccountMeta::new(get_metadata_pda(mint), false), // Metadata Account
+        AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false), // Base Token Program (readonly)
+        AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false), // Quote Token Program (readonly)
+        AccountMeta::new_readonly(accounts::MPL_TOKEN_METADATA, false), // Metadata Program (readonly)
+        AccountMeta::new_readonly(accounts::SYSTEM_PROGRAM, false), // System Program (readonly)
+        AccountMeta::new_readonly(accounts::RENT, false), // Rent (readonly)
+        AccountMeta::new_readonly(accounts::EVENT_AUTHORITY, false), // Event Authority (readonly)
+        AccountMeta::new_readonly(accounts::BONK, false), // Program (readonly)
+    ];
+
+    let mut data = Vec::with_capacity(
+        8 + 1 + 12 + create_params.name.len() + create_params.symbol.len() + create_params.uri.len() + 1 + 25 + 24,
+    );
+    data.extend_from_slice(&INITIALIZE_DISCRIMINATOR);
+    // MintParams
+    data.push(create_params.decimals);
+    for field in [&create_params.name, &create_params.symbol, &create_params.uri] {
+        data.extend_from_slice(&(field.len() as u32).to_le_bytes());
+        data.extend_from_slice(field.as_bytes());
+    }
+    // CurveParams::Constant
+    data.push(0);
+    data.extend_from_slice(&launch::SUPPLY.to_le_bytes());
+    data.extend_from_slice(&launch::TOTAL_BASE_SELL.to_le_bytes());
+    data.extend_from_slice(&launch::TOTAL_QUOTE_FUND_RAISING.to_le_bytes());
+    data.push(launch::MIGRATE_TYPE_CPMM);
+    // VestingParams：不锁仓
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.extend_from_slice(&0u64.to_le_bytes());
+
+    Ok(Instruction { program_id: accounts::BONK, accounts, data })
+}
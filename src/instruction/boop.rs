@@ -0,0 +1,196 @@
+use anyhow::{anyhow, Result};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signer::Signer,
+};
+use spl_associated_token_account::{
+    get_associated_token_address, instruction::create_associated_token_account_idempotent,
+};
+use spl_token::instruction::close_account;
+
+use crate::{
+    constants::boop::{accounts, BUY_TOKEN_DISCRIMINATOR, SELL_TOKEN_DISCRIMINATOR},
+    constants::trade::trade::DEFAULT_SLIPPAGE,
+    trading::boop::{
+        common::{
+            get_bonding_curve_pda, get_bonding_curve_sol_vault_pda, get_bonding_curve_vault_pda,
+            get_buy_token_amount_from_sol_amount, get_config_pda,
+            get_sell_sol_amount_from_token_amount, get_trading_fees_vault_pda,
+            get_vault_authority_pda,
+        },
+        curve::BondingCurve,
+    },
+    trading::common::{calculate_with_slippage_sell, get_token_balance},
+    trading::core::{
+        params::{BoopParams, BuyParams, SellParams},
+        traits::InstructionBuilder,
+    },
+};
+
+/// Boop.fun协议的指令构建器
+pub struct BoopInstructionBuilder;
+
+#[async_trait::async_trait]
+impl InstructionBuilder for BoopInstructionBuilder {
+    async fn build_buy_instructions(&self, params: &BuyParams) -> Result<Vec<Instruction>> {
+        if params.sol_amount == 0 {
+            return Err(anyhow!("Amount cannot be zero"));
+        }
+        let rpc = params.rpc.as_ref().ok_or_else(|| anyhow!("RPC is not set"))?;
+        let protocol_params = boop_params(params.protocol_params.as_any())?;
+
+        let curve = match &protocol_params.bonding_curve {
+            Some(curve) => curve.clone(),
+            None => BondingCurve::fetch(rpc, &params.mint).await?,
+        };
+        if !curve.is_trading() {
+            return Err(anyhow!("Boop bonding curve for {} has graduated", params.mint));
+        }
+
+        let token_amount = get_buy_token_amount_from_sol_amount(&curve, params.sol_amount);
+        let min_token_amount = calculate_with_slippage_sell(
+            token_amount,
+            params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+        );
+        if min_token_amount == 0 {
+            return Err(anyhow!("Buy amount is too small"));
+        }
+
+        let payer = params.payer.pubkey();
+        let mut instructions = vec![create_associated_token_account_idempotent(
+            &payer,
+            &payer,
+            &params.mint,
+            &accounts::TOKEN_PROGRAM,
+        )];
+        instructions.push(buy_token(&payer, &params.mint, params.sol_amount, min_token_amount)?);
+
+        Ok(instructions)
+    }
+
+    async fn build_sell_instructions(&self, params: &SellParams) -> Result<Vec<Instruction>> {
+        let rpc = params.rpc.as_ref().ok_or_else(|| anyhow!("RPC is not set"))?;
+        let protocol_params = boop_params(params.protocol_params.as_any())?;
+        let payer = params.payer.pubkey();
+
+        // 获取代币余额
+        let balance = get_token_balance(rpc, &payer, &params.mint).await?;
+        let amount = match params.token_amount {
+            Some(amount) if amount > 0 => amount.min(balance),
+            _ => balance,
+        };
+        if amount == 0 {
+            return Err(anyhow!("Amount cannot be zero"));
+        }
+
+        let min_sol_out = match params.min_sol_out {
+            Some(min_sol_out) => min_sol_out,
+            None => {
+                let curve = match &protocol_params.bonding_curve {
+                    Some(curve) => curve.clone(),
+                    None => BondingCurve::fetch(rpc, &params.mint).await?,
+                };
+                calculate_with_slippage_sell(
+                    get_sell_sol_amount_from_token_amount(&curve, amount),
+                    params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+                )
+            }
+        };
+
+        let mut instructions = vec![sell_token(&payer, &params.mint, amount, min_sol_out)?];
+
+        // 如果卖出全部代币，关闭账户
+        if amount >= balance {
+            instructions.push(close_account(
+                &accounts::TOKEN_PROGRAM,
+                &get_associated_token_address(&payer, &params.mint),
+                &payer,
+                &payer,
+                &[&payer],
+            )?);
+        }
+
+        Ok(instructions)
+    }
+}
+
+fn boop_params(protocol_params: &dyn std::any::Any) -> Result<&BoopParams> {
+    protocol_params
+        .downcast_ref::<BoopParams>()
+        .ok_or_else(|| anyhow!("Invalid protocol params for Boop"))
+}
+
+/// bonding curve 相关的 PDA：(bonding_curve, trading_fees_vault, bonding_curve_vault, bonding_curve_sol_vault)
+fn curve_accounts(mint: &Pubkey) -> Result<(Pubkey, Pubkey, Pubkey, Pubkey)> {
+    let derive = |pda: Option<Pubkey>| pda.ok_or_else(|| anyhow!("Failed to derive Boop account"));
+    Ok((
+        derive(get_bonding_curve_pda(mint))?,
+        derive(get_trading_fees_vault_pda(mint))?,
+        derive(get_bonding_curve_vault_pda(mint))?,
+        derive(get_bonding_curve_sol_vault_pda(mint))?,
+    ))
+}
+
+fn amounts_data(discriminator: [u8; 8], amount: u64, amount_out_min: u64) -> Vec<u8> {
+    let mut data = Vec::with_capacity(8 + 8 + 8);
+    data.extend_from_slice(&discriminator);
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&amount_out_min.to_le_bytes());
+    data
+}
+
+/// 花费 `sol_amount` lamports 买入，至少获得 `min_token_amount` 个代币
+fn buy_token(payer: &Pubkey, mint: &Pubkey, sol_amount: u64, min_token_amount: u64) -> Result<Instruction> {
+    let (bonding_curve, trading_fees_vault, bonding_curve_vault, bonding_curve_sol_vault) =
+        curve_accounts(mint)?;
+    let config = get_config_pda().ok_or_else(|| anyhow!("Failed to derive Boop config"))?;
+    let vault_authority =
+        get_vault_authority_pda().ok_or_else(|| anyhow!("Failed to derive Boop vault authority"))?;
+
+    Ok(Instruction::new_with_bytes(
+        accounts::BOOP,
+        &amounts_data(BUY_TOKEN_DISCRIMINATOR, sol_amount, min_token_amount),
+        vec![
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(bonding_curve, false),
+            AccountMeta::new(trading_fees_vault, false),
+            AccountMeta::new(bonding_curve_vault, false),
+            AccountMeta::new(bonding_curve_sol_vault, false),
+            AccountMeta::new(get_associated_token_address(payer, mint), false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(config, false),
+            AccountMeta::new_readonly(vault_authority, false),
+            AccountMeta::new_readonly(accounts::WSOL_TOKEN_ACCOUNT, false),
+            AccountMeta::new_readonly(accounts::SYSTEM_PROGRAM, false),
+            AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false),
+            AccountMeta::new_readonly(accounts::ASSOCIATED_TOKEN_PROGRAM, false),
+        ],
+    ))
+}
+
+/// 卖出 `token_amount` 个代币，至少获得 `min_sol_out` lamports
+fn sell_token(payer: &Pubkey, mint: &Pubkey, token_amount: u64, min_sol_out: u64) -> Result<Instruction> {
+    let (bonding_curve, trading_fees_vault, bonding_curve_vault, bonding_curve_sol_vault) =
+        curve_accounts(mint)?;
+    let config = get_config_pda().ok_or_else(|| anyhow!("Failed to derive Boop config"))?;
+
+    Ok(Instruction::new_with_bytes(
+        accounts::BOOP,
+        &amounts_data(SELL_TOKEN_DISCRIMINATOR, token_amount, min_sol_out),
+        vec![
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(bonding_curve, false),
+            AccountMeta::new(trading_fees_vault, false),
+            AccountMeta::new(bonding_curve_vault, false),
+            AccountMeta::new(bonding_curve_sol_vault, false),
+            AccountMeta::new(get_associated_token_address(payer, mint), false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(*payer, false),
+            AccountMeta::new_readonly(config, false),
+            AccountMeta::new_readonly(accounts::SYSTEM_PROGRAM, false),
+            AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false),
+            AccountMeta::new_readonly(accounts::ASSOCIATED_TOKEN_PROGRAM, false),
+        ],
+    ))
+}
@@ -1,4 +1,11 @@
 pub mod pumpfun;
 pub mod pumpswap;
 pub mod bonk;
-pub mod raydium_cpmm;
\ No newline at end of file
+pub mod raydium_cpmm;
+pub mod raydium_amm_v4;
+pub mod raydium_clmm;
+pub mod orca_whirlpool;
+pub mod moonshot;
+pub mod boop;
+pub mod phoenix;
+
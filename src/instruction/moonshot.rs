@@ -0,0 +1,183 @@
+use anyhow::{anyhow, Result};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signer::Signer,
+};
+use spl_token_2022::instruction::close_account;
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
+};
+
+use crate::{
+    constants::moonshot::{accounts, BUY_DISCRIMINATOR, FIXED_SIDE_EXACT_IN, SELL_DISCRIMINATOR},
+    constants::trade::trade::DEFAULT_SLIPPAGE,
+    trading::common::{get_mint_token_info, get_token_balance_with_program},
+    trading::core::{
+        params::{BuyParams, MoonshotParams, SellParams},
+        traits::InstructionBuilder,
+    },
+    trading::moonshot::{
+        common::{
+            get_buy_token_amount_from_sol_amount, get_curve_pda,
+            get_sell_sol_amount_from_token_amount,
+        },
+        curve::CurveAccount,
+    },
+};
+
+/// Moonshot协议的指令构建器
+pub struct MoonshotInstructionBuilder;
+
+#[async_trait::async_trait]
+impl InstructionBuilder for MoonshotInstructionBuilder {
+    async fn build_buy_instructions(&self, params: &BuyParams) -> Result<Vec<Instruction>> {
+        if params.sol_amount == 0 {
+            return Err(anyhow!("Amount cannot be zero"));
+        }
+        let rpc = params.rpc.as_ref().ok_or_else(|| anyhow!("RPC is not set"))?;
+        let protocol_params = moonshot_params(params.protocol_params.as_any())?;
+
+        let curve = match &protocol_params.curve {
+            Some(curve) => curve.clone(),
+            None => CurveAccount::fetch(rpc, &params.mint).await?,
+        };
+        if !curve.is_trading() {
+            return Err(anyhow!("Moonshot curve for {} has migrated", params.mint));
+        }
+        let token_program = get_mint_token_info(rpc, &params.mint).await?.token_program;
+
+        // 按报价买入，由程序按滑点校验实际成交数量
+        let token_amount = get_buy_token_amount_from_sol_amount(&curve, params.sol_amount);
+        if token_amount == 0 {
+            return Err(anyhow!("Buy amount is too small"));
+        }
+
+        let payer = params.payer.pubkey();
+        let mut instructions = vec![create_associated_token_account_idempotent(
+            &payer,
+            &payer,
+            &params.mint,
+            &token_program,
+        )];
+        instructions.push(trade(
+            BUY_DISCRIMINATOR,
+            &payer,
+            &params.mint,
+            &token_program,
+            token_amount,
+            params.sol_amount,
+            params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+        )?);
+
+        Ok(instructions)
+    }
+
+    async fn build_sell_instructions(&self, params: &SellParams) -> Result<Vec<Instruction>> {
+        let rpc = params.rpc.as_ref().ok_or_else(|| anyhow!("RPC is not set"))?;
+        let protocol_params = moonshot_params(params.protocol_params.as_any())?;
+        let payer = params.payer.pubkey();
+        let token_program = get_mint_token_info(rpc, &params.mint).await?.token_program;
+
+        // 获取代币余额
+        let balance =
+            get_token_balance_with_program(rpc, &payer, &params.mint, &token_program).await?;
+        let amount = match params.token_amount {
+            Some(amount) if amount > 0 => amount.min(balance),
+            _ => balance,
+        };
+        if amount == 0 {
+            return Err(anyhow!("Amount cannot be zero"));
+        }
+
+        // 指定最少输出时以其为报价且不允许滑点，否则按曲线报价
+        let (sol_amount, slippage_basis_points) = match params.min_sol_out {
+            Some(min_sol_out) => (min_sol_out, 0),
+            None => {
+                let curve = match &protocol_params.curve {
+                    Some(curve) => curve.clone(),
+                    None => CurveAccount::fetch(rpc, &params.mint).await?,
+                };
+                (
+                    get_sell_sol_amount_from_token_amount(&curve, amount),
+                    params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+                )
+            }
+        };
+
+        let mut instructions = vec![trade(
+            SELL_DISCRIMINATOR,
+            &payer,
+            &params.mint,
+            &token_program,
+            amount,
+            sol_amount,
+            slippage_basis_points,
+        )?];
+
+        // 如果卖出全部代币，关闭账户
+        if amount >= balance {
+            instructions.push(close_account(
+                &token_program,
+                &get_associated_token_address_with_program_id(&payer, &params.mint, &token_program),
+                &payer,
+                &payer,
+                &[&payer],
+            )?);
+        }
+
+        Ok(instructions)
+    }
+}
+
+fn moonshot_params(protocol_params: &dyn std::any::Any) -> Result<&MoonshotParams> {
+    protocol_params
+        .downcast_ref::<MoonshotParams>()
+        .ok_or_else(|| anyhow!("Invalid protocol params for Moonshot"))
+}
+
+/// 构建买入 / 卖出指令，两者账户相同，输入数量固定
+fn trade(
+    discriminator: [u8; 8],
+    payer: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    token_amount: u64,
+    collateral_amount: u64,
+    slippage_basis_points: u64,
+) -> Result<Instruction> {
+    let curve_account =
+        get_curve_pda(mint).ok_or_else(|| anyhow!("Failed to derive curve account"))?;
+
+    let mut data = Vec::with_capacity(8 + 8 + 8 + 1 + 8);
+    data.extend_from_slice(&discriminator);
+    data.extend_from_slice(&token_amount.to_le_bytes());
+    data.extend_from_slice(&collateral_amount.to_le_bytes());
+    data.push(FIXED_SIDE_EXACT_IN);
+    data.extend_from_slice(&slippage_basis_points.to_le_bytes());
+
+    Ok(Instruction::new_with_bytes(
+        accounts::MOONSHOT,
+        &data,
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(
+                get_associated_token_address_with_program_id(payer, mint, token_program),
+                false,
+            ),
+            AccountMeta::new(curve_account, false),
+            AccountMeta::new(
+                get_associated_token_address_with_program_id(&curve_account, mint, token_program),
+                false,
+            ),
+            AccountMeta::new(accounts::DEX_FEE, false),
+            AccountMeta::new(accounts::HELIO_FEE, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(accounts::CONFIG_ACCOUNT, false),
+            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new_readonly(accounts::ASSOCIATED_TOKEN_PROGRAM, false),
+            AccountMeta::new_readonly(accounts::SYSTEM_PROGRAM, false),
+        ],
+    ))
+}
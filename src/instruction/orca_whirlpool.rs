@@ -0,0 +1,289 @@
+use anyhow::{anyhow, Result};
+use std::sync::Arc;
+
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signer::Signer,
+};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use crate::{
+    common::{wsol, SolanaRpcClient},
+    constants::orca_whirlpool::{
+        accounts, MAX_SQRT_PRICE_X64, MIN_SQRT_PRICE_X64, SWAP_TICK_ARRAYS, SWAP_V2_DISCRIMINATOR,
+    },
+    constants::trade::trade::DEFAULT_SLIPPAGE,
+    trading::{
+        common::{
+            concentrated_liquidity::get_amount_out,
+            token_program::{get_mint_token_info, MintTokenInfo},
+            utils::{calculate_with_slippage_sell, get_token_balance_with_program},
+        },
+        core::{
+            params::{BuyParams, OrcaWhirlpoolParams, SellParams},
+            traits::InstructionBuilder,
+        },
+        orca_whirlpool::{
+            common::{get_oracle_pda, get_tick_arrays},
+            pool::Pool,
+        },
+    },
+};
+
+/// OrcaWhirlpool协议的指令构建器
+pub struct OrcaWhirlpoolInstructionBuilder;
+
+/// 一次 swap 所需的池子信息
+struct SwapContext {
+    whirlpool: Pubkey,
+    pool: Arc<Pool>,
+    mint_info: MintTokenInfo,
+    tick_arrays: Vec<Pubkey>,
+    /// 输入为 token_a 时为 true，价格下降
+    a_to_b: bool,
+}
+
+#[async_trait::async_trait]
+impl InstructionBuilder for OrcaWhirlpoolInstructionBuilder {
+    async fn build_buy_instructions(&self, params: &BuyParams) -> Result<Vec<Instruction>> {
+        if params.sol_amount == 0 {
+            return Err(anyhow!("Amount cannot be zero"));
+        }
+        let protocol_params = params
+            .protocol_params
+            .as_any()
+            .downcast_ref::<OrcaWhirlpoolParams>()
+            .ok_or_else(|| anyhow!("Invalid protocol params for OrcaWhirlpool"))?;
+        let rpc = params.rpc.as_ref().ok_or_else(|| anyhow!("RPC is not set"))?;
+
+        let read_rpc = params.read_rpc.as_ref().unwrap_or(rpc);
+        let context = resolve_swap_context(rpc, read_rpc, &params.mint, protocol_params, true).await?;
+        let minimum_amount_out = match protocol_params.minimum_amount_out {
+            Some(minimum_amount_out) => minimum_amount_out,
+            None => {
+                let amount_out = quote(&context, params.sol_amount)?;
+                // Token-2022 转账手续费从输出中扣除
+                context.mint_info.amount_after_transfer_fee(calculate_with_slippage_sell(
+                    amount_out,
+                    params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+                ))
+            }
+        };
+
+        let wsol_token_account = spl_associated_token_account::get_associated_token_address(
+            &params.payer.pubkey(),
+            &accounts::WSOL_TOKEN_ACCOUNT,
+        );
+        let mint_token_account =
+            spl_associated_token_account::get_associated_token_address_with_program_id(
+                &params.payer.pubkey(),
+                &params.mint,
+                &context.mint_info.token_program,
+            );
+
+        let wsol_policy = params.wsol_policy.resolve(protocol_params.auto_handle_wsol);
+        let mut instructions = vec![];
+
+        if wsol_policy.wraps_input() {
+            // 创建wSOL ATA账户并将SOL包装为wSOL
+            instructions.extend(wsol::wrap(&params.payer.pubkey(), params.sol_amount));
+        }
+
+        // 创建用户的基础代币账户
+        instructions.push(create_associated_token_account_idempotent(
+            &params.payer.pubkey(),
+            &params.payer.pubkey(),
+            &params.mint,
+            &context.mint_info.token_program,
+        ));
+
+        instructions.push(swap_v2_instruction(
+            &params.payer.pubkey(),
+            &context,
+            &wsol_token_account,
+            &mint_token_account,
+            params.sol_amount,
+            minimum_amount_out,
+        )?);
+
+        if wsol_policy.unwraps_after_trade() {
+            // 关闭wSOL ATA账户，回收租金
+            instructions.push(wsol::unwrap_all(&params.payer.pubkey()));
+        }
+
+        Ok(instructions)
+    }
+
+    async fn build_sell_instructions(&self, params: &SellParams) -> Result<Vec<Instruction>> {
+        let protocol_params = params
+            .protocol_params
+            .as_any()
+            .downcast_ref::<OrcaWhirlpoolParams>()
+            .ok_or_else(|| anyhow!("Invalid protocol params for OrcaWhirlpool"))?;
+        let rpc = params.rpc.as_ref().ok_or_else(|| anyhow!("RPC is not set"))?;
+
+        let read_rpc = params.read_rpc.as_ref().unwrap_or(rpc);
+        let context = resolve_swap_context(rpc, read_rpc, &params.mint, protocol_params, false).await?;
+
+        // 获取代币余额
+        let amount = match params.token_amount {
+            Some(amount) if amount > 0 => amount,
+            _ => {
+                get_token_balance_with_program(
+                    rpc.as_ref(),
+                    &params.payer.pubkey(),
+                    &params.mint,
+                    &context.mint_info.token_program,
+                )
+                .await?
+            }
+        };
+        if amount == 0 {
+            return Err(anyhow!("Amount cannot be zero"));
+        }
+
+        let minimum_amount_out = match (params.min_sol_out, protocol_params.minimum_amount_out) {
+            // 指定了绝对下限时直接使用
+            (Some(min_sol_out), _) => min_sol_out,
+            (None, Some(minimum_amount_out)) => minimum_amount_out,
+            (None, None) => {
+                // 池子实际收到的是扣除 Token-2022 转账手续费后的数量
+                let amount_in = context.mint_info.amount_after_transfer_fee(amount);
+                calculate_with_slippage_sell(
+                    quote(&context, amount_in)?,
+                    params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+                )
+            }
+        };
+
+        let wsol_token_account = spl_associated_token_account::get_associated_token_address(
+            &params.payer.pubkey(),
+            &accounts::WSOL_TOKEN_ACCOUNT,
+        );
+        let mint_token_account =
+            spl_associated_token_account::get_associated_token_address_with_program_id(
+                &params.payer.pubkey(),
+                &params.mint,
+                &context.mint_info.token_program,
+            );
+
+        let wsol_policy = params.wsol_policy.resolve(protocol_params.auto_handle_wsol);
+        let mut instructions = vec![];
+
+        // 创建wSOL ATA账户，如果不存在
+        instructions.push(wsol::create_account(&params.payer.pubkey()));
+
+        instructions.push(swap_v2_instruction(
+            &params.payer.pubkey(),
+            &context,
+            &wsol_token_account,
+            &mint_token_account,
+            amount,
+            minimum_amount_out,
+        )?);
+
+        if wsol_policy.unwraps_after_trade() {
+            instructions.push(wsol::unwrap_all(&params.payer.pubkey()));
+        }
+
+        Ok(instructions)
+    }
+}
+
+/// 解析池子、代币程序和 tick array
+///
+/// `wsol_in` 为 true 表示买入（WSOL 为输入代币），用于确定交易方向
+async fn resolve_swap_context(
+    rpc: &Arc<SolanaRpcClient>,
+    read_rpc: &Arc<SolanaRpcClient>,
+    mint: &Pubkey,
+    protocol_params: &OrcaWhirlpoolParams,
+    wsol_in: bool,
+) -> Result<SwapContext> {
+    let (whirlpool, pool) = match protocol_params.pool {
+        Some(whirlpool) => (whirlpool, Pool::fetch_cached(rpc, &whirlpool).await?),
+        None => {
+            println!("❗️Going through RPC request, increasing instruction building time");
+            let (whirlpool, pool) = Pool::find_by_mint(read_rpc, mint).await?;
+            (whirlpool, Arc::new(pool))
+        }
+    };
+    let is_wsol_pair = (pool.token_mint_a == *mint && pool.token_mint_b == accounts::WSOL_TOKEN_ACCOUNT)
+        || (pool.token_mint_b == *mint && pool.token_mint_a == accounts::WSOL_TOKEN_ACCOUNT);
+    if !is_wsol_pair {
+        return Err(anyhow!("Orca Whirlpool {} is not a {}/WSOL pool", whirlpool, mint));
+    }
+    let a_to_b = (pool.token_mint_a == accounts::WSOL_TOKEN_ACCOUNT) == wsol_in;
+    let tick_arrays = match &protocol_params.tick_arrays {
+        Some(tick_arrays) if !tick_arrays.is_empty() => tick_arrays.clone(),
+        _ => get_tick_arrays(&whirlpool, &pool, a_to_b),
+    };
+    let mint_info = get_mint_token_info(rpc, mint).await?;
+    Ok(SwapContext { whirlpool, pool, mint_info, tick_arrays, a_to_b })
+}
+
+/// 按池子当前流动性和费率报价
+fn quote(context: &SwapContext, amount_in: u64) -> Result<u64> {
+    let pool = &context.pool;
+    get_amount_out(
+        pool.liquidity,
+        pool.sqrt_price,
+        amount_in,
+        context.a_to_b,
+        pool.fee_rate as u32,
+    )
+}
+
+/// 构建 swap_v2 指令
+fn swap_v2_instruction(
+    owner: &Pubkey,
+    context: &SwapContext,
+    wsol_token_account: &Pubkey,
+    mint_token_account: &Pubkey,
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Result<Instruction> {
+    let pool = &context.pool;
+    if context.tick_arrays.len() != SWAP_TICK_ARRAYS {
+        return Err(anyhow!("Orca Whirlpool swap requires {} tick arrays", SWAP_TICK_ARRAYS));
+    }
+    let oracle = get_oracle_pda(&context.whirlpool)
+        .ok_or_else(|| anyhow!("Failed to derive oracle for {}", context.whirlpool))?;
+    let mint_is_a = pool.token_mint_a != accounts::WSOL_TOKEN_ACCOUNT;
+    let (token_program_a, token_program_b, owner_account_a, owner_account_b) = if mint_is_a {
+        (context.mint_info.token_program, accounts::TOKEN_PROGRAM, mint_token_account, wsol_token_account)
+    } else {
+        (accounts::TOKEN_PROGRAM, context.mint_info.token_program, wsol_token_account, mint_token_account)
+    };
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(token_program_a, false),        // Token Program A (readonly)
+        AccountMeta::new_readonly(token_program_b, false),        // Token Program B (readonly)
+        AccountMeta::new_readonly(accounts::MEMO_PROGRAM, false), // Memo Program (readonly)
+        AccountMeta::new_readonly(*owner, true),                  // Token Authority (signer)
+        AccountMeta::new(context.whirlpool, false),               // Whirlpool
+        AccountMeta::new_readonly(pool.token_mint_a, false),      // Token Mint A (readonly)
+        AccountMeta::new_readonly(pool.token_mint_b, false),      // Token Mint B (readonly)
+        AccountMeta::new(*owner_account_a, false),                // Token Owner Account A
+        AccountMeta::new(pool.token_vault_a, false),              // Token Vault A
+        AccountMeta::new(*owner_account_b, false),                // Token Owner Account B
+        AccountMeta::new(pool.token_vault_b, false),              // Token Vault B
+    ];
+    accounts.extend(context.tick_arrays.iter().map(|tick_array| AccountMeta::new(*tick_array, false)));
+    accounts.push(AccountMeta::new(oracle, false)); // Oracle
+
+    // 不限制价格，由 minimum_amount_out 保护
+    let sqrt_price_limit =
+        if context.a_to_b { MIN_SQRT_PRICE_X64 } else { MAX_SQRT_PRICE_X64 };
+    let mut data = Vec::with_capacity(43);
+    data.extend_from_slice(SWAP_V2_DISCRIMINATOR);
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&minimum_amount_out.to_le_bytes());
+    data.extend_from_slice(&sqrt_price_limit.to_le_bytes());
+    data.push(1); // amount_specified_is_input
+    data.push(context.a_to_b as u8);
+    data.push(0); // remaining_accounts_info: None
+
+    Ok(Instruction { program_id: accounts::WHIRLPOOL_PROGRAM, accounts, data })
+}
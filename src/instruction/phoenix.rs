@@ -0,0 +1,265 @@
+use anyhow::{anyhow, Result};
+
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signer::Signer,
+};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use crate::{
+    common::{wsol, SolanaRpcClient},
+    constants::phoenix::{
+        accounts, ORDER_PACKET_IMMEDIATE_OR_CANCEL, SELF_TRADE_DECREMENT_TAKE, SIDE_ASK, SIDE_BID,
+        SWAP_INSTRUCTION,
+    },
+    constants::trade::trade::DEFAULT_SLIPPAGE,
+    trading::{
+        common::utils::{calculate_with_slippage_sell, get_token_balance_with_program},
+        core::{
+            params::{BuyParams, PhoenixParams, SellParams},
+            traits::InstructionBuilder,
+        },
+        phoenix::{
+            common::{get_buy_base_lots, get_log_authority_pda, get_sell_quote_lots},
+            market::Market,
+        },
+    },
+};
+
+/// Phoenix协议的指令构建器，以 IOC 吃单方式成交
+pub struct PhoenixInstructionBuilder;
+
+/// 一笔 IOC 订单的数量参数，单位均为 lot
+struct OrderSize {
+    side: u8,
+    num_base_lots: u64,
+    num_quote_lots: u64,
+    min_base_lots_to_fill: u64,
+    min_quote_lots_to_fill: u64,
+}
+
+#[async_trait::async_trait]
+impl InstructionBuilder for PhoenixInstructionBuilder {
+    async fn build_buy_instructions(&self, params: &BuyParams) -> Result<Vec<Instruction>> {
+        let protocol_params = params
+            .protocol_params
+            .as_any()
+            .downcast_ref::<PhoenixParams>()
+            .ok_or_else(|| anyhow!("Invalid protocol params for Phoenix"))?;
+        let rpc = params.rpc.as_ref().ok_or_else(|| anyhow!("RPC is not set"))?;
+
+        let read_rpc = params.read_rpc.as_ref().unwrap_or(rpc);
+        let (market_address, market) =
+            resolve_market(rpc, read_rpc, &params.mint, protocol_params).await?;
+        let num_quote_lots = params.sol_amount / market.quote_lot_size.max(1);
+        if num_quote_lots == 0 {
+            return Err(anyhow!("Amount is below the Phoenix quote lot size"));
+        }
+        let base_lots = get_buy_base_lots(&market, num_quote_lots);
+        if base_lots == 0 {
+            return Err(anyhow!("Phoenix market {} has no asks to fill", market_address));
+        }
+        let order = OrderSize {
+            side: SIDE_BID,
+            num_base_lots: 0,
+            num_quote_lots,
+            min_base_lots_to_fill: calculate_with_slippage_sell(
+                base_lots,
+                params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+            ),
+            min_quote_lots_to_fill: 0,
+        };
+
+        let wsol_token_account = spl_associated_token_account::get_associated_token_address(
+            &params.payer.pubkey(),
+            &accounts::WSOL_TOKEN_ACCOUNT,
+        );
+        let mint_token_account = spl_associated_token_account::get_associated_token_address(
+            &params.payer.pubkey(),
+            &params.mint,
+        );
+
+        let wsol_policy = params.wsol_policy.resolve(protocol_params.auto_handle_wsol);
+        let mut instructions = vec![];
+
+        if wsol_policy.wraps_input() {
+            // 创建wSOL ATA账户并将SOL包装为wSOL
+            instructions.extend(wsol::wrap(&params.payer.pubkey(), params.sol_amount));
+        }
+
+        // 创建用户的基础代币账户
+        instructions.push(create_associated_token_account_idempotent(
+            &params.payer.pubkey(),
+            &params.payer.pubkey(),
+            &params.mint,
+            &accounts::TOKEN_PROGRAM,
+        ));
+
+        instructions.push(swap_instruction(
+            &params.payer.pubkey(),
+            &market_address,
+            &market,
+            &mint_token_account,
+            &wsol_token_account,
+            &order,
+        )?);
+
+        if wsol_policy.unwraps_after_trade() {
+            // 关闭wSOL ATA账户，未成交的 SOL 一并退回
+            instructions.push(wsol::unwrap_all(&params.payer.pubkey()));
+        }
+
+        Ok(instructions)
+    }
+
+    async fn build_sell_instructions(&self, params: &SellParams) -> Result<Vec<Instruction>> {
+        let protocol_params = params
+            .protocol_params
+            .as_any()
+            .downcast_ref::<PhoenixParams>()
+            .ok_or_else(|| anyhow!("Invalid protocol params for Phoenix"))?;
+        let rpc = params.rpc.as_ref().ok_or_else(|| anyhow!("RPC is not set"))?;
+
+        let read_rpc = params.read_rpc.as_ref().unwrap_or(rpc);
+        let (market_address, market) =
+            resolve_market(rpc, read_rpc, &params.mint, protocol_params).await?;
+
+        // 获取代币余额
+        let amount = match params.token_amount {
+            Some(amount) if amount > 0 => amount,
+            _ => {
+                get_token_balance_with_program(
+                    rpc.as_ref(),
+                    &params.payer.pubkey(),
+                    &params.mint,
+                    &accounts::TOKEN_PROGRAM,
+                )
+                .await?
+            }
+        };
+        // 不足一个 lot 的零头留在账户中
+        let num_base_lots = amount / market.base_lot_size.max(1);
+        if num_base_lots == 0 {
+            return Err(anyhow!("Amount is below the Phoenix base lot size"));
+        }
+
+        let min_quote_lots_to_fill = match params.min_sol_out {
+            // 指定了绝对下限时直接使用，向上取整到 lot
+            Some(min_sol_out) => min_sol_out.div_ceil(market.quote_lot_size.max(1)),
+            None => calculate_with_slippage_sell(
+                get_sell_quote_lots(&market, num_base_lots),
+                params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+            ),
+        };
+        let order = OrderSize {
+            side: SIDE_ASK,
+            num_base_lots,
+            num_quote_lots: 0,
+            min_base_lots_to_fill: 0,
+            min_quote_lots_to_fill,
+        };
+
+        let wsol_token_account = spl_associated_token_account::get_associated_token_address(
+            &params.payer.pubkey(),
+            &accounts::WSOL_TOKEN_ACCOUNT,
+        );
+        let mint_token_account = spl_associated_token_account::get_associated_token_address(
+            &params.payer.pubkey(),
+            &params.mint,
+        );
+
+        let wsol_policy = params.wsol_policy.resolve(protocol_params.auto_handle_wsol);
+        let mut instructions = vec![];
+
+        // 创建wSOL ATA账户，如果不存在
+        instructions.push(wsol::create_account(&params.payer.pubkey()));
+
+        instructions.push(swap_instruction(
+            &params.payer.pubkey(),
+            &market_address,
+            &market,
+            &mint_token_account,
+            &wsol_token_account,
+            &order,
+        )?);
+
+        if wsol_policy.unwraps_after_trade() {
+            instructions.push(wsol::unwrap_all(&params.payer.pubkey()));
+        }
+
+        Ok(instructions)
+    }
+}
+
+/// 获取市场账户，未指定市场时按 mint 查找
+async fn resolve_market(
+    rpc: &SolanaRpcClient,
+    read_rpc: &SolanaRpcClient,
+    mint: &Pubkey,
+    protocol_params: &PhoenixParams,
+) -> Result<(Pubkey, Market)> {
+    let (market_address, market) = match protocol_params.market {
+        Some(market_address) => (market_address, Market::fetch(rpc, &market_address).await?),
+        None => {
+            println!("❗️Going through RPC request, increasing instruction building time");
+            Market::find_by_mint(read_rpc, mint).await?
+        }
+    };
+    if market.base_mint != *mint || market.quote_mint != accounts::WSOL_TOKEN_ACCOUNT {
+        return Err(anyhow!("Phoenix market {} is not a {}/WSOL market", market_address, mint));
+    }
+    Ok((market_address, market))
+}
+
+/// 构建 Swap 指令，订单为不挂单的 ImmediateOrCancel
+fn swap_instruction(
+    trader: &Pubkey,
+    market_address: &Pubkey,
+    market: &Market,
+    base_account: &Pubkey,
+    quote_account: &Pubkey,
+    order: &OrderSize,
+) -> Result<Instruction> {
+    let log_authority =
+        get_log_authority_pda().ok_or_else(|| anyhow!("Failed to derive Phoenix log authority"))?;
+
+    let accounts = vec![
+        AccountMeta::new_readonly(accounts::PHOENIX, false), // Phoenix Program (readonly)
+        AccountMeta::new_readonly(log_authority, false),     // Log Authority (readonly)
+        AccountMeta::new(*market_address, false),            // Market
+        AccountMeta::new_readonly(*trader, true),            // Trader (signer)
+        AccountMeta::new(*base_account, false),              // Base Account
+        AccountMeta::new(*quote_account, false),             // Quote Account
+        AccountMeta::new(market.base_vault, false),          // Base Vault
+        AccountMeta::new(market.quote_vault, false),         // Quote Vault
+        AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false), // Token Program (readonly)
+    ];
+
+    Ok(Instruction {
+        program_id: accounts::PHOENIX,
+        accounts,
+        data: swap_instruction_data(order),
+    })
+}
+
+/// Swap 指令数据：指令编号 + borsh 编码的 OrderPacket::ImmediateOrCancel
+fn swap_instruction_data(order: &OrderSize) -> Vec<u8> {
+    let mut data = Vec::with_capacity(64);
+    data.push(SWAP_INSTRUCTION);
+    data.push(ORDER_PACKET_IMMEDIATE_OR_CANCEL);
+    data.push(order.side);
+    data.push(0); // price_in_ticks: None，不限价，由最小成交量保护
+    data.extend_from_slice(&order.num_base_lots.to_le_bytes());
+    data.extend_from_slice(&order.num_quote_lots.to_le_bytes());
+    data.extend_from_slice(&order.min_base_lots_to_fill.to_le_bytes());
+    data.extend_from_slice(&order.min_quote_lots_to_fill.to_le_bytes());
+    data.push(SELF_TRADE_DECREMENT_TAKE);
+    data.push(0); // match_limit: None
+    data.extend_from_slice(&0u128.to_le_bytes()); // client_order_id
+    data.push(0); // use_only_deposited_funds: false
+    data.push(0); // last_valid_slot: None
+    data.push(0); // last_valid_unix_timestamp_in_seconds: None
+    data
+}
+
@@ -8,18 +8,20 @@ use spl_token::instruction::close_account;
 use crate::{
     constants,
     trading::pumpfun::common::{
-        get_bonding_curve_pda, get_global_volume_accumulator_pda, get_user_volume_accumulator_pda,
+        get_bonding_curve_pda, get_global_pda, get_global_volume_accumulator_pda,
+        get_metadata_pda, get_mint_authority_pda, get_user_volume_accumulator_pda,
     },
 };
 
 use solana_sdk::{instruction::AccountMeta, pubkey::Pubkey, signature::Keypair, signer::Signer};
 
 use crate::{
+    common::bonding_curve::BondingCurveAccount,
     constants::pumpfun::global_constants::FEE_RECIPIENT,
     constants::trade::trade::DEFAULT_SLIPPAGE,
     trading::common::utils::calculate_with_slippage_buy,
     trading::core::{
-        params::{BuyParams, PumpFunParams, SellParams},
+        params::{BuyParams, PumpFunCreateParams, PumpFunParams, SellParams},
         traits::InstructionBuilder,
     },
     trading::pumpfun::common::{get_buy_token_amount_from_sol_amount, get_creator_vault_pda},
@@ -125,7 +127,7 @@ impl InstructionBuilder for PumpFunInstructionBuilder {
             &FEE_RECIPIENT,
             Sell {
                 _amount: token_amount,
-                _min_sol_output: 1,
+                _min_sol_output: params.min_sol_out.unwrap_or(1),
             },
         )];
 
@@ -144,6 +146,84 @@ impl InstructionBuilder for PumpFunInstructionBuilder {
     }
 }
 
+/// 构建发币并由创建者首笔买入的指令，创建者为 `payer`
+///
+/// 买入按全新 bonding curve 报价，与发币在同一交易中执行，其他人无法抢在创建者之前买入。
+/// `sol_amount` 为 0 时只发币。
+pub fn build_create_and_buy_instructions(
+    payer: &Keypair,
+    mint: &Pubkey,
+    create_params: &PumpFunCreateParams,
+    sol_amount: u64,
+    slippage_basis_points: Option<u64>,
+) -> Result<Vec<Instruction>> {
+    let mut instructions = vec![create(
+        payer,
+        mint,
+        Create {
+            _name: create_params.name.clone(),
+            _symbol: create_params.symbol.clone(),
+            _uri: create_params.uri.clone(),
+            _creator: payer.pubkey(),
+        },
+    )];
+    if sol_amount == 0 {
+        return Ok(instructions);
+    }
+
+    let bonding_curve = BondingCurveAccount::from_dev_trade(mint, 0, 0, payer.pubkey());
+    let buy_token_amount = get_buy_token_amount_from_sol_amount(&bonding_curve, sol_amount);
+    if buy_token_amount == 0 {
+        return Err(anyhow!("Buy amount is too small"));
+    }
+    let max_sol_cost = calculate_with_slippage_buy(
+        sol_amount,
+        slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+    );
+
+    instructions.push(create_associated_token_account(
+        &payer.pubkey(),
+        &payer.pubkey(),
+        mint,
+        &constants::pumpfun::accounts::TOKEN_PROGRAM,
+    ));
+    instructions.push(buy(
+        payer,
+        mint,
+        &bonding_curve.account,
+        &bonding_curve.get_creator_vault_pda(),
+        &FEE_RECIPIENT,
+        Buy {
+            _amount: buy_token_amount,
+            _max_sol_cost: max_sol_cost,
+        },
+    ));
+
+    Ok(instructions)
+}
+
+pub struct Create {
+    pub _name: String,
+    pub _symbol: String,
+    pub _uri: String,
+    pub _creator: Pubkey,
+}
+
+impl Create {
+    pub fn data(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(
+            8 + 12 + self._name.len() + self._symbol.len() + self._uri.len() + 32,
+        );
+        data.extend_from_slice(&[24, 30, 200, 40, 5, 28, 7, 119]); // discriminator
+        for field in [&self._name, &self._symbol, &self._uri] {
+            data.extend_from_slice(&(field.len() as u32).to_le_bytes());
+            data.extend_from_slice(field.as_bytes());
+        }
+        data.extend_from_slice(self._creator.as_ref());
+        data
+    }
+}
+
 pub struct Buy {
     pub _amount: u64,
     pub _max_sol_cost: u64,
@@ -231,3 +311,73 @@ pub fn sell(
         ],
     )
 }
+
+pub fn create(payer: &Keypair, mint: &Pubkey, args: Create) -> Instruction {
+    let bonding_curve: Pubkey = get_bonding_curve_pda(mint).unwrap();
+    Instruction::new_with_bytes(
+        constants::pumpfun::accounts::PUMPFUN,
+        &args.data(),
+        vec![
+            AccountMeta::new(*mint, true),
+            AccountMeta::new_readonly(get_mint_authority_pda(), false),
+            AccountMeta::new(bonding_curve, false),
+            AccountMeta::new(get_associated_token_address(&bonding_curve, mint), false),
+            AccountMeta::new_readonly(get_global_pda(), false),
+            AccountMeta::new_readonly(constants::pumpfun::accounts::MPL_TOKEN_METADATA, false),
+            AccountMeta::new(get_metadata_pda(mint), false),
+            AccountMeta::new(payer.pubkey(), true),
+            AccountMeta::new_readonly(constants::pumpfun::accounts::SYSTEM_PROGRAM, false),
+            AccountMeta::new_readonly(constants::pumpfun::accounts::TOKEN_PROGRAM, false),
+            AccountMeta::new_readonly(constants::pumpfun::accounts::ASSOCIATED_TOKEN_PROGRAM, false),
+            AccountMeta::new_readonly(constants::pumpfun::accounts::RENT, false),
+            AccountMeta::new_readonly(constants::pumpfun::accounts::EVENT_AUTHORITY, false),
+            AccountMeta::new_readonly(constants::pumpfun::accounts::PUMPFUN, false),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use solana_client::rpc_request::RpcRequest;
+
+    use super::*;
+    use crate::common::SolanaRpcClient;
+
+    /// 持有 1000 个代币的 RPC
+    fn rpc() -> SolanaRpcClient {
+        let balance = serde_json::json!({
+            "context": { "slot": 1 },
+            "value": { "amount": "1000", "decimals": 6, "uiAmount": 0.001, "uiAmountString": "0.001" },
+        });
+        SolanaRpcClient::new_mock_with_mocks(
+            "succeeds".to_string(),
+            HashMap::from([(RpcRequest::GetTokenAccountBalance, balance)]),
+        )
+    }
+
+    async fn sell_min_sol_out(min_sol_out: Option<u64>) -> u64 {
+        let params = SellParams::for_test(
+            rpc(),
+            Pubkey::new_unique(),
+            400,
+            min_sol_out,
+            Box::new(PumpFunParams::default()),
+        );
+        let instructions =
+            PumpFunInstructionBuilder.build_sell_instructions(&params).await.unwrap();
+        let sell = instructions
+            .iter()
+            .find(|instruction| instruction.program_id == constants::pumpfun::accounts::PUMPFUN)
+            .unwrap();
+        assert_eq!(u64::from_le_bytes(sell.data[8..16].try_into().unwrap()), 400);
+        u64::from_le_bytes(sell.data[16..24].try_into().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_sell_min_sol_out_reaches_instruction_data() {
+        assert_eq!(sell_min_sol_out(Some(123_456)).await, 123_456);
+        assert_eq!(sell_min_sol_out(None).await, 1);
+    }
+}
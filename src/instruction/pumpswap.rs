@@ -1,17 +1,20 @@
 use anyhow::{anyhow, Result};
 use solana_sdk::{instruction::Instruction, pubkey::Pubkey, signer::Signer};
-use solana_system_interface::instruction::transfer;
 use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
-use spl_token::instruction::close_account;
 
 use crate::{
+    common::{wsol, SolanaRpcClient, WsolPolicy},
     constants::{
         pumpswap::{accounts, BUY_DISCRIMINATOR, SELL_DISCRIMINATOR},
         trade::trade::DEFAULT_SLIPPAGE,
     },
     trading::{
-        common::utils::{
-            calculate_with_slippage_buy, calculate_with_slippage_sell, get_token_balance,
+        common::{
+            token_program::get_mint_token_program,
+            utils::{
+                calculate_with_slippage_buy, calculate_with_slippage_sell,
+                get_token_balance_with_program,
+            },
         },
         core::{
             params::{BuyParams, PumpSwapParams, SellParams},
@@ -74,7 +77,7 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
                     quote_mint,
                     pool_base_token_reserves,
                     pool_quote_token_reserves,
-                    protocol_params.auto_handle_wsol,
+                    params.wsol_policy.resolve(protocol_params.auto_handle_wsol),
                 )
                 .await
             }
@@ -116,7 +119,7 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
                     quote_mint,
                     pool_base_token_reserves,
                     pool_quote_token_reserves,
-                    protocol_params.auto_handle_wsol,
+                    params.wsol_policy.resolve(protocol_params.auto_handle_wsol),
                 )
                 .await
             }
@@ -137,12 +140,11 @@ impl PumpSwapInstructionBuilder {
         println!("❗️Going through RPC request, increasing instruction building time");
         let rpc = params.rpc.as_ref().unwrap().clone();
         // Find pool
-        let pool = find_pool(rpc.as_ref(), &params.mint).await?;
-        let pool_data = pumpswap::pool::Pool::fetch(rpc.as_ref(), &pool).await?;
-        let pool_base_token_reserves =
-            get_token_balance(rpc.as_ref(), &pool, &pool_data.base_mint).await?;
-        let pool_quote_token_reserves =
-            get_token_balance(rpc.as_ref(), &pool, &pool_data.quote_mint).await?;
+        let read_rpc = params.read_rpc.as_ref().unwrap_or(&rpc);
+        let pool = find_pool(read_rpc.as_ref(), &params.mint).await?;
+        let pool_data = pumpswap::pool::Pool::fetch_cached(rpc.as_ref(), &pool).await?;
+        let (pool_base_token_reserves, pool_quote_token_reserves) =
+            get_pool_reserves(rpc.as_ref(), &pool, &pool_data).await?;
         let mut params = params.clone();
         params.creator = pool_data.coin_creator;
         self.build_buy_instructions_with_accounts(
@@ -152,7 +154,7 @@ impl PumpSwapInstructionBuilder {
             pool_data.quote_mint,
             pool_base_token_reserves,
             pool_quote_token_reserves,
-            true,
+            params.wsol_policy,
         )
         .await
     }
@@ -168,12 +170,11 @@ impl PumpSwapInstructionBuilder {
         println!("❗️Going through RPC request, increasing instruction building time");
         let rpc = params.rpc.as_ref().unwrap().clone();
         // Find pool
-        let pool = find_pool(rpc.as_ref(), &params.mint).await?;
-        let pool_data = pumpswap::pool::Pool::fetch(rpc.as_ref(), &pool).await?;
-        let pool_base_token_reserves =
-            get_token_balance(rpc.as_ref(), &pool, &pool_data.base_mint).await?;
-        let pool_quote_token_reserves =
-            get_token_balance(rpc.as_ref(), &pool, &pool_data.quote_mint).await?;
+        let read_rpc = params.read_rpc.as_ref().unwrap_or(&rpc);
+        let pool = find_pool(read_rpc.as_ref(), &params.mint).await?;
+        let pool_data = pumpswap::pool::Pool::fetch_cached(rpc.as_ref(), &pool).await?;
+        let (pool_base_token_reserves, pool_quote_token_reserves) =
+            get_pool_reserves(rpc.as_ref(), &pool, &pool_data).await?;
         let mut params = params.clone();
         params.creator = pool_data.coin_creator;
         self.build_sell_instructions_with_accounts(
@@ -183,12 +184,13 @@ impl PumpSwapInstructionBuilder {
             pool_data.quote_mint,
             pool_base_token_reserves,
             pool_quote_token_reserves,
-            true,
+            params.wsol_policy,
         )
         .await
     }
 
     /// Build buy instructions with provided account information
+    #[allow(clippy::too_many_arguments)]
     async fn build_buy_instructions_with_accounts(
         &self,
         params: &BuyParams,
@@ -197,11 +199,11 @@ impl PumpSwapInstructionBuilder {
         quote_mint: Pubkey,
         pool_base_token_reserves: u64,
         pool_quote_token_reserves: u64,
-        auto_handle_wsol: bool,
+        wsol_policy: WsolPolicy,
     ) -> Result<Vec<Instruction>> {
-        if params.rpc.is_none() {
-            return Err(anyhow!("RPC is not set"));
-        }
+        let rpc = params.rpc.as_ref().ok_or_else(|| anyhow!("RPC is not set"))?;
+        let base_token_program = get_mint_token_program(rpc, &base_mint).await?;
+        let quote_token_program = get_mint_token_program(rpc, &quote_mint).await?;
         let quote_mint_is_wsol = quote_mint == accounts::WSOL_TOKEN_ACCOUNT;
         // Calculate token amount
         let mut token_amount = get_token_amount(
@@ -237,68 +239,39 @@ impl PumpSwapInstructionBuilder {
         };
 
         // Create user token accounts
-        let user_base_token_account = spl_associated_token_account::get_associated_token_address(
-            &params.payer.pubkey(),
-            &base_mint,
-        );
-        let user_quote_token_account = spl_associated_token_account::get_associated_token_address(
-            &params.payer.pubkey(),
-            &quote_mint,
-        );
+        let user_base_token_account =
+            spl_associated_token_account::get_associated_token_address_with_program_id(
+                &params.payer.pubkey(),
+                &base_mint,
+                &base_token_program,
+            );
+        let user_quote_token_account =
+            spl_associated_token_account::get_associated_token_address_with_program_id(
+                &params.payer.pubkey(),
+                &quote_mint,
+                &quote_token_program,
+            );
 
         // Get pool token accounts
         let pool_base_token_account =
             spl_associated_token_account::get_associated_token_address_with_program_id(
                 &pool,
                 &base_mint,
-                &accounts::TOKEN_PROGRAM,
+                &base_token_program,
             );
 
         let pool_quote_token_account =
             spl_associated_token_account::get_associated_token_address_with_program_id(
                 &pool,
                 &quote_mint,
-                &accounts::TOKEN_PROGRAM,
+                &quote_token_program,
             );
 
         let mut instructions = vec![];
 
-        if auto_handle_wsol {
-            // Handle wSOL
-            instructions.push(
-                // Create wSOL ATA account if it doesn't exist
-                create_associated_token_account_idempotent(
-                    &params.payer.pubkey(),
-                    &params.payer.pubkey(),
-                    &accounts::WSOL_TOKEN_ACCOUNT,
-                    &accounts::TOKEN_PROGRAM,
-                ),
-            );
-            instructions.push(
-                // Transfer SOL to wSOL ATA account
-                transfer(
-                    &params.payer.pubkey(),
-                    if quote_mint_is_wsol {
-                        &user_quote_token_account
-                    } else {
-                        &user_base_token_account
-                    },
-                    sol_amount,
-                ),
-            );
-
-            // Sync wSOL balance
-            instructions.push(
-                spl_token::instruction::sync_native(
-                    &accounts::TOKEN_PROGRAM,
-                    if quote_mint_is_wsol {
-                        &user_quote_token_account
-                    } else {
-                        &user_base_token_account
-                    },
-                )
-                .unwrap(),
-            );
+        if wsol_policy.wraps_input() {
+            // Create the wSOL ATA account and wrap SOL into it
+            instructions.extend(wsol::wrap(&params.payer.pubkey(), sol_amount));
         }
 
         // Create user's base token account
@@ -306,12 +279,14 @@ impl PumpSwapInstructionBuilder {
             &params.payer.pubkey(),
             &params.payer.pubkey(),
             if quote_mint_is_wsol { &base_mint } else { &quote_mint },
-            &accounts::TOKEN_PROGRAM,
+            if quote_mint_is_wsol { &base_token_program } else { &quote_token_program },
         ));
 
-        let coin_creator_vault_ata = coin_creator_vault_ata(params.creator, quote_mint);
+        let coin_creator_vault_ata =
+            coin_creator_vault_ata(params.creator, quote_mint, quote_token_program);
         let coin_creator_vault_authority = coin_creator_vault_authority(params.creator);
-        let fee_recipient_ata = fee_recipient_ata(accounts::FEE_RECIPIENT, quote_mint);
+        let fee_recipient_ata =
+            fee_recipient_ata(accounts::FEE_RECIPIENT, quote_mint, quote_token_program);
 
         // Create buy instruction
         let mut accounts = vec![
@@ -326,8 +301,8 @@ impl PumpSwapInstructionBuilder {
             solana_sdk::instruction::AccountMeta::new(pool_quote_token_account, false), // pool_quote_token_account
             solana_sdk::instruction::AccountMeta::new_readonly(accounts::FEE_RECIPIENT, false), // fee_recipient (readonly)
             solana_sdk::instruction::AccountMeta::new(fee_recipient_ata, false), // fee_recipient_ata
-            solana_sdk::instruction::AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false), // TOKEN_PROGRAM_ID (readonly)
-            solana_sdk::instruction::AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false), // TOKEN_PROGRAM_ID (readonly, duplicated as in JS)
+            solana_sdk::instruction::AccountMeta::new_readonly(base_token_program, false), // base_token_program (readonly)
+            solana_sdk::instruction::AccountMeta::new_readonly(quote_token_program, false), // quote_token_program (readonly)
             solana_sdk::instruction::AccountMeta::new_readonly(accounts::SYSTEM_PROGRAM, false), // System Program (readonly)
             solana_sdk::instruction::AccountMeta::new_readonly(
                 accounts::ASSOCIATED_TOKEN_PROGRAM,
@@ -362,27 +337,15 @@ impl PumpSwapInstructionBuilder {
         }
 
         instructions.push(Instruction { program_id: accounts::AMM_PROGRAM, accounts, data });
-        if auto_handle_wsol {
+        if wsol_policy.unwraps_after_trade() {
             // Close wSOL ATA account, reclaim rent
-            instructions.push(
-                spl_token::instruction::close_account(
-                    &accounts::TOKEN_PROGRAM,
-                    if quote_mint_is_wsol {
-                        &user_quote_token_account
-                    } else {
-                        &user_base_token_account
-                    },
-                    &params.payer.pubkey(),
-                    &params.payer.pubkey(),
-                    &[&params.payer.pubkey()],
-                )
-                .unwrap(),
-            );
+            instructions.push(wsol::unwrap_all(&params.payer.pubkey()));
         }
         Ok(instructions)
     }
 
     /// Build sell instructions with provided account information
+    #[allow(clippy::too_many_arguments)]
     async fn build_sell_instructions_with_accounts(
         &self,
         params: &SellParams,
@@ -391,11 +354,11 @@ impl PumpSwapInstructionBuilder {
         quote_mint: Pubkey,
         pool_base_token_reserves: u64,
         pool_quote_token_reserves: u64,
-        auto_handle_wsol: bool,
+        wsol_policy: WsolPolicy,
     ) -> Result<Vec<Instruction>> {
-        if params.rpc.is_none() {
-            return Err(anyhow!("RPC is not set"));
-        }
+        let rpc = params.rpc.as_ref().ok_or_else(|| anyhow!("RPC is not set"))?;
+        let base_token_program = get_mint_token_program(rpc, &base_mint).await?;
+        let quote_token_program = get_mint_token_program(rpc, &quote_mint).await?;
 
         let quote_mint_is_wsol = quote_mint == accounts::WSOL_TOKEN_ACCOUNT;
         let mut sol_amount = get_wsol_amount(
@@ -416,52 +379,54 @@ impl PumpSwapInstructionBuilder {
             sol_amount,
             params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
         );
+        // An explicit floor takes precedence over the slippage-derived amount
+        if let Some(min_sol_out) = params.min_sol_out {
+            sol_amount = min_sol_out;
+        }
         let token_amount = params.token_amount.unwrap_or(0);
 
-        let coin_creator_vault_ata = coin_creator_vault_ata(params.creator, quote_mint);
+        let coin_creator_vault_ata =
+            coin_creator_vault_ata(params.creator, quote_mint, quote_token_program);
         let coin_creator_vault_authority = coin_creator_vault_authority(params.creator);
-        let fee_recipient_ata = fee_recipient_ata(accounts::FEE_RECIPIENT, quote_mint);
+        let fee_recipient_ata =
+            fee_recipient_ata(accounts::FEE_RECIPIENT, quote_mint, quote_token_program);
 
-        let user_base_token_account = spl_associated_token_account::get_associated_token_address(
-            &params.payer.pubkey(),
-            &base_mint,
-        );
-        let user_quote_token_account = spl_associated_token_account::get_associated_token_address(
-            &params.payer.pubkey(),
-            &quote_mint,
-        );
+        let user_base_token_account =
+            spl_associated_token_account::get_associated_token_address_with_program_id(
+                &params.payer.pubkey(),
+                &base_mint,
+                &base_token_program,
+            );
+        let user_quote_token_account =
+            spl_associated_token_account::get_associated_token_address_with_program_id(
+                &params.payer.pubkey(),
+                &quote_mint,
+                &quote_token_program,
+            );
         let pool_base_token_account =
             spl_associated_token_account::get_associated_token_address_with_program_id(
                 &pool,
                 &base_mint,
-                &accounts::TOKEN_PROGRAM,
+                &base_token_program,
             );
         let pool_quote_token_account =
             spl_associated_token_account::get_associated_token_address_with_program_id(
                 &pool,
                 &quote_mint,
-                &accounts::TOKEN_PROGRAM,
+                &quote_token_program,
             );
 
         let mut instructions = vec![];
 
-        // Insert wSOL
-        instructions.push(
-            // Create wSOL ATA account if it doesn't exist
-            create_associated_token_account_idempotent(
-                &params.payer.pubkey(),
-                &params.payer.pubkey(),
-                &accounts::WSOL_TOKEN_ACCOUNT,
-                &accounts::TOKEN_PROGRAM,
-            ),
-        );
+        // Create wSOL ATA account if it doesn't exist
+        instructions.push(wsol::create_account(&params.payer.pubkey()));
 
         // Create user's token account
         instructions.push(create_associated_token_account_idempotent(
             &params.payer.pubkey(),
             &params.payer.pubkey(),
             if quote_mint_is_wsol { &base_mint } else { &quote_mint },
-            &accounts::TOKEN_PROGRAM,
+            if quote_mint_is_wsol { &base_token_program } else { &quote_token_program },
         ));
 
         // Create sell instruction
@@ -477,8 +442,8 @@ impl PumpSwapInstructionBuilder {
             solana_sdk::instruction::AccountMeta::new(pool_quote_token_account, false), // pool_quote_token_account
             solana_sdk::instruction::AccountMeta::new_readonly(accounts::FEE_RECIPIENT, false), // fee_recipient (readonly)
             solana_sdk::instruction::AccountMeta::new(fee_recipient_ata, false), // fee_recipient_ata
-            solana_sdk::instruction::AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false), // TOKEN_PROGRAM_ID (readonly)
-            solana_sdk::instruction::AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false), // TOKEN_PROGRAM_ID (readonly, duplicated as in JS)
+            solana_sdk::instruction::AccountMeta::new_readonly(base_token_program, false), // base_token_program (readonly)
+            solana_sdk::instruction::AccountMeta::new_readonly(quote_token_program, false), // quote_token_program (readonly)
             solana_sdk::instruction::AccountMeta::new_readonly(accounts::SYSTEM_PROGRAM, false), // System Program (readonly)
             solana_sdk::instruction::AccountMeta::new_readonly(
                 accounts::ASSOCIATED_TOKEN_PROGRAM,
@@ -514,22 +479,86 @@ impl PumpSwapInstructionBuilder {
 
         instructions.push(Instruction { program_id: accounts::AMM_PROGRAM, accounts, data });
 
-        if auto_handle_wsol {
-            instructions.push(
-                close_account(
-                    &accounts::TOKEN_PROGRAM,
-                    if quote_mint_is_wsol {
-                        &user_quote_token_account
-                    } else {
-                        &user_base_token_account
-                    },
-                    &params.payer.pubkey(),
-                    &params.payer.pubkey(),
-                    &[&params.payer.pubkey()],
-                )
-                .unwrap(),
-            );
+        if wsol_policy.unwraps_after_trade() {
+            instructions.push(wsol::unwrap_all(&params.payer.pubkey()));
         }
         Ok(instructions)
     }
 }
+
+/// Read the pool's base and quote reserves from its token accounts, each derived under its mint's token program
+async fn get_pool_reserves(
+    rpc: &SolanaRpcClient,
+    pool: &Pubkey,
+    pool_data: &pumpswap::pool::Pool,
+) -> Result<(u64, u64)> {
+    let base_token_program = get_mint_token_program(rpc, &pool_data.base_mint).await?;
+    let quote_token_program = get_mint_token_program(rpc, &pool_data.quote_mint).await?;
+    let base_reserves =
+        get_token_balance_with_program(rpc, pool, &pool_data.base_mint, &base_token_program)
+            .await?;
+    let quote_reserves =
+        get_token_balance_with_program(rpc, pool, &pool_data.quote_mint, &quote_token_program)
+            .await?;
+    Ok((base_reserves, quote_reserves))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use solana_client::rpc_request::RpcRequest;
+
+    use super::*;
+    use crate::trading::core::params::SellParams;
+
+    /// 返回 SPL Token mint 账户的 RPC
+    fn rpc() -> SolanaRpcClient {
+        let mint_account = serde_json::json!({
+            "context": { "slot": 1 },
+            "value": {
+                "lamports": 1_461_600,
+                "data": ["", "base64"],
+                "owner": spl_token::ID.to_string(),
+                "executable": false,
+                "rentEpoch": 0,
+                "space": 0,
+            },
+        });
+        SolanaRpcClient::new_mock_with_mocks(
+            "succeeds".to_string(),
+            HashMap::from([(RpcRequest::GetAccountInfo, mint_account)]),
+        )
+    }
+
+    async fn sell_min_sol_out(mint: Pubkey, min_sol_out: Option<u64>) -> u64 {
+        let protocol_params = PumpSwapParams {
+            pool: Some(Pubkey::new_unique()),
+            base_mint: Some(mint),
+            quote_mint: Some(accounts::WSOL_TOKEN_ACCOUNT),
+            pool_base_token_reserves: Some(1_000_000_000),
+            pool_quote_token_reserves: Some(50_000_000_000),
+            auto_handle_wsol: true,
+        };
+        let params =
+            SellParams::for_test(rpc(), mint, 1_000_000, min_sol_out, Box::new(protocol_params));
+        let instructions =
+            PumpSwapInstructionBuilder.build_sell_instructions(&params).await.unwrap();
+        let sell = instructions
+            .iter()
+            .find(|instruction| instruction.program_id == accounts::AMM_PROGRAM)
+            .unwrap();
+        // wSOL 报价的池子卖出 base：base_amount_in 之后为 min_quote_amount_out
+        assert_eq!(sell.data[..8], SELL_DISCRIMINATOR);
+        assert_eq!(u64::from_le_bytes(sell.data[8..16].try_into().unwrap()), 1_000_000);
+        u64::from_le_bytes(sell.data[16..24].try_into().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_sell_min_sol_out_reaches_instruction_data() {
+        let mint = Pubkey::new_unique();
+        assert_eq!(sell_min_sol_out(mint, Some(123_456)).await, 123_456);
+        let slippage_floor = sell_min_sol_out(mint, None).await;
+        assert!(slippage_floor > 0 && slippage_floor != 123_456);
+    }
+}
@@ -0,0 +1,274 @@
+use anyhow::{anyhow, Result};
+use std::sync::Arc;
+
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signer::Signer,
+};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use crate::{
+    common::{wsol, SolanaRpcClient},
+    constants::raydium_amm_v4::{accounts, SWAP_BASE_IN_V2_TAG},
+    constants::trade::trade::DEFAULT_SLIPPAGE,
+    trading::{
+        common::{
+            token_program::get_mint_token_program,
+            utils::{calculate_with_slippage_sell, get_token_balance_with_program},
+        },
+        core::{
+            params::{BuyParams, RaydiumAmmV4Params, SellParams},
+            traits::InstructionBuilder,
+        },
+        raydium_amm_v4::{
+            common::{get_amount_out, get_pool_reserves},
+            pool::Pool,
+        },
+    },
+};
+
+/// RaydiumAmmV4协议的指令构建器
+pub struct RaydiumAmmV4InstructionBuilder;
+
+#[async_trait::async_trait]
+impl InstructionBuilder for RaydiumAmmV4InstructionBuilder {
+    async fn build_buy_instructions(&self, params: &BuyParams) -> Result<Vec<Instruction>> {
+        if params.sol_amount == 0 {
+            return Err(anyhow!("Amount cannot be zero"));
+        }
+        let protocol_params = params
+            .protocol_params
+            .as_any()
+            .downcast_ref::<RaydiumAmmV4Params>()
+            .ok_or_else(|| anyhow!("Invalid protocol params for RaydiumAmmV4"))?;
+        let rpc = params.rpc.as_ref().ok_or_else(|| anyhow!("RPC is not set"))?;
+        let read_rpc = params.read_rpc.as_ref().unwrap_or(rpc);
+        let (amm, pool) = resolve_pool(rpc, read_rpc, &params.mint, protocol_params.amm).await?;
+        let mint_is_coin = mint_is_coin(&pool, &params.mint)?;
+        let (coin_reserve, pc_reserve) = resolve_reserves(rpc, &pool, protocol_params).await?;
+        let (sol_reserve, token_reserve) =
+            if mint_is_coin { (pc_reserve, coin_reserve) } else { (coin_reserve, pc_reserve) };
+        let token_program = mint_token_program(rpc, &params.mint).await?;
+
+        let amount_out = get_amount_out(
+            params.sol_amount,
+            sol_reserve,
+            token_reserve,
+            pool.fees.swap_fee_numerator,
+            pool.fees.swap_fee_denominator,
+        )?;
+        let minimum_amount_out = calculate_with_slippage_sell(
+            amount_out,
+            params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+        );
+
+        let wsol_token_account = spl_associated_token_account::get_associated_token_address(
+            &params.payer.pubkey(),
+            &accounts::WSOL_TOKEN_ACCOUNT,
+        );
+        let mint_token_account =
+            spl_associated_token_account::get_associated_token_address_with_program_id(
+                &params.payer.pubkey(),
+                &params.mint,
+                &token_program,
+            );
+
+        let wsol_policy = params.wsol_policy.resolve(protocol_params.auto_handle_wsol);
+        let mut instructions = vec![];
+
+        if wsol_policy.wraps_input() {
+            // 创建wSOL ATA账户并将SOL包装为wSOL
+            instructions.extend(wsol::wrap(&params.payer.pubkey(), params.sol_amount));
+        }
+
+        // 创建用户的基础代币账户
+        instructions.push(create_associated_token_account_idempotent(
+            &params.payer.pubkey(),
+            &params.payer.pubkey(),
+            &params.mint,
+            &token_program,
+        ));
+
+        instructions.push(swap_base_in_instruction(
+            &params.payer.pubkey(),
+            &amm,
+            &pool,
+            &wsol_token_account,
+            &mint_token_account,
+            params.sol_amount,
+            minimum_amount_out,
+        ));
+
+        if wsol_policy.unwraps_after_trade() {
+            // 关闭wSOL ATA账户，回收租金
+            instructions.push(wsol::unwrap_all(&params.payer.pubkey()));
+        }
+
+        Ok(instructions)
+    }
+
+    async fn build_sell_instructions(&self, params: &SellParams) -> Result<Vec<Instruction>> {
+        let protocol_params = params
+            .protocol_params
+            .as_any()
+            .downcast_ref::<RaydiumAmmV4Params>()
+            .ok_or_else(|| anyhow!("Invalid protocol params for RaydiumAmmV4"))?;
+        let rpc = params.rpc.as_ref().ok_or_else(|| anyhow!("RPC is not set"))?;
+
+        let token_program = mint_token_program(rpc, &params.mint).await?;
+
+        // 获取代币余额
+        let amount = match params.token_amount {
+            Some(amount) if amount > 0 => amount,
+            _ => {
+                get_token_balance_with_program(
+                    rpc.as_ref(),
+                    &params.payer.pubkey(),
+                    &params.mint,
+                    &token_program,
+                )
+                .await?
+            }
+        };
+        if amount == 0 {
+            return Err(anyhow!("Amount cannot be zero"));
+        }
+
+        let read_rpc = params.read_rpc.as_ref().unwrap_or(rpc);
+        let (amm, pool) = resolve_pool(rpc, read_rpc, &params.mint, protocol_params.amm).await?;
+        let mint_is_coin = mint_is_coin(&pool, &params.mint)?;
+
+        // 指定了绝对下限时直接使用，无需查询储备
+        let minimum_amount_out = match params.min_sol_out {
+            Some(min_sol_out) => min_sol_out,
+            None => {
+                let (coin_reserve, pc_reserve) =
+                    resolve_reserves(rpc, &pool, protocol_params).await?;
+                let (token_reserve, sol_reserve) =
+                    if mint_is_coin { (coin_reserve, pc_reserve) } else { (pc_reserve, coin_reserve) };
+                let amount_out = get_amount_out(
+                    amount,
+                    token_reserve,
+                    sol_reserve,
+                    pool.fees.swap_fee_numerator,
+                    pool.fees.swap_fee_denominator,
+                )?;
+                calculate_with_slippage_sell(
+                    amount_out,
+                    params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+                )
+            }
+        };
+
+        let wsol_token_account = spl_associated_token_account::get_associated_token_address(
+            &params.payer.pubkey(),
+            &accounts::WSOL_TOKEN_ACCOUNT,
+        );
+        let mint_token_account =
+            spl_associated_token_account::get_associated_token_address_with_program_id(
+                &params.payer.pubkey(),
+                &params.mint,
+                &token_program,
+            );
+
+        let wsol_policy = params.wsol_policy.resolve(protocol_params.auto_handle_wsol);
+        let mut instructions = vec![];
+
+        // 创建wSOL ATA账户，如果不存在
+        instructions.push(wsol::create_account(&params.payer.pubkey()));
+
+        instructions.push(swap_base_in_instruction(
+            &params.payer.pubkey(),
+            &amm,
+            &pool,
+            &mint_token_account,
+            &wsol_token_account,
+            amount,
+            minimum_amount_out,
+        ));
+
+        if wsol_policy.unwraps_after_trade() {
+            instructions.push(wsol::unwrap_all(&params.payer.pubkey()));
+        }
+
+        Ok(instructions)
+    }
+}
+
+/// 使用指定的池子地址或通过 `read_rpc` 查找池子
+async fn resolve_pool(
+    rpc: &Arc<SolanaRpcClient>,
+    read_rpc: &Arc<SolanaRpcClient>,
+    mint: &Pubkey,
+    amm: Option<Pubkey>,
+) -> Result<(Pubkey, Arc<Pool>)> {
+    match amm {
+        Some(amm) => Ok((amm, Pool::fetch_cached(rpc, &amm).await?)),
+        None => {
+            println!("❗️Going through RPC request, increasing instruction building time");
+            let (amm, pool) = Pool::find_by_mint(read_rpc, mint).await?;
+            Ok((amm, Arc::new(pool)))
+        }
+    }
+}
+
+/// 代币所属的代币程序，Raydium AMM v4 只支持 SPL Token，Token-2022 代币直接报错，避免提交必然失败的交易
+async fn mint_token_program(rpc: &SolanaRpcClient, mint: &Pubkey) -> Result<Pubkey> {
+    let token_program = get_mint_token_program(rpc, mint).await?;
+    if token_program != accounts::TOKEN_PROGRAM {
+        return Err(anyhow!("Raydium AMM v4 does not support Token-2022 mint {}", mint));
+    }
+    Ok(token_program)
+}
+
+/// 代币是否为池子的基础代币（coin），池子必须与 WSOL 配对
+fn mint_is_coin(pool: &Pool, mint: &Pubkey) -> Result<bool> {
+    if pool.coin_vault_mint == *mint && pool.pc_vault_mint == accounts::WSOL_TOKEN_ACCOUNT {
+        Ok(true)
+    } else if pool.pc_vault_mint == *mint && pool.coin_vault_mint == accounts::WSOL_TOKEN_ACCOUNT {
+        Ok(false)
+    } else {
+        Err(anyhow!("Raydium AMM v4 pool is not a {}/WSOL pool", mint))
+    }
+}
+
+/// 优先使用参数中提供的储备，否则通过 RPC 查询
+async fn resolve_reserves(
+    rpc: &SolanaRpcClient,
+    pool: &Pool,
+    protocol_params: &RaydiumAmmV4Params,
+) -> Result<(u64, u64)> {
+    match (protocol_params.coin_reserve, protocol_params.pc_reserve) {
+        (Some(coin_reserve), Some(pc_reserve)) => Ok((coin_reserve, pc_reserve)),
+        _ => get_pool_reserves(rpc, pool).await,
+    }
+}
+
+/// 构建 swap_base_in_v2 指令
+fn swap_base_in_instruction(
+    owner: &Pubkey,
+    amm: &Pubkey,
+    pool: &Pool,
+    user_source: &Pubkey,
+    user_destination: &Pubkey,
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false), // Token Program (readonly)
+        AccountMeta::new(*amm, false),                             // Amm
+        AccountMeta::new_readonly(accounts::AUTHORITY, false),     // Amm Authority (readonly)
+        AccountMeta::new(pool.coin_vault, false),                  // Amm Coin Vault
+        AccountMeta::new(pool.pc_vault, false),                    // Amm Pc Vault
+        AccountMeta::new(*user_source, false),                     // User Source Token Account
+        AccountMeta::new(*user_destination, false),                // User Destination Token Account
+        AccountMeta::new_readonly(*owner, true),                   // User Owner (signer)
+    ];
+    let mut data = Vec::with_capacity(17);
+    data.push(SWAP_BASE_IN_V2_TAG);
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&minimum_amount_out.to_le_bytes());
+
+    Instruction { program_id: accounts::RAYDIUM_AMM_V4, accounts, data }
+}
@@ -0,0 +1,288 @@
+use anyhow::{anyhow, Result};
+use std::sync::Arc;
+
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signer::Signer,
+};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use crate::{
+    common::{wsol, SolanaRpcClient},
+    constants::raydium_clmm::{
+        accounts, MAX_SQRT_PRICE_X64, MIN_SQRT_PRICE_X64, SWAP_V2_DISCRIMINATOR,
+    },
+    constants::trade::trade::DEFAULT_SLIPPAGE,
+    trading::{
+        common::{
+            concentrated_liquidity::get_amount_out,
+            token_program::{get_mint_token_info, MintTokenInfo},
+            utils::{calculate_with_slippage_sell, get_token_balance_with_program},
+        },
+        core::{
+            params::{BuyParams, RaydiumClmmParams, SellParams},
+            traits::InstructionBuilder,
+        },
+        raydium_clmm::{
+            common::{get_tick_array_bitmap_extension_pda, get_tick_arrays},
+            pool::{AmmConfig, Pool},
+        },
+    },
+};
+
+/// RaydiumClmm协议的指令构建器
+pub struct RaydiumClmmInstructionBuilder;
+
+/// 一次 swap 所需的池子信息
+struct SwapContext {
+    pool_state: Pubkey,
+    pool: Arc<Pool>,
+    mint_info: MintTokenInfo,
+    tick_arrays: Vec<Pubkey>,
+    /// 输入为 token0 时为 true，价格下降
+    zero_for_one: bool,
+}
+
+#[async_trait::async_trait]
+impl InstructionBuilder for RaydiumClmmInstructionBuilder {
+    async fn build_buy_instructions(&self, params: &BuyParams) -> Result<Vec<Instruction>> {
+        if params.sol_amount == 0 {
+            return Err(anyhow!("Amount cannot be zero"));
+        }
+        let protocol_params = params
+            .protocol_params
+            .as_any()
+            .downcast_ref::<RaydiumClmmParams>()
+            .ok_or_else(|| anyhow!("Invalid protocol params for RaydiumClmm"))?;
+        let rpc = params.rpc.as_ref().ok_or_else(|| anyhow!("RPC is not set"))?;
+
+        let read_rpc = params.read_rpc.as_ref().unwrap_or(rpc);
+        let context = resolve_swap_context(rpc, read_rpc, &params.mint, protocol_params, true).await?;
+        let minimum_amount_out = match protocol_params.minimum_amount_out {
+            Some(minimum_amount_out) => minimum_amount_out,
+            None => {
+                let amount_out = quote(rpc, &context, params.sol_amount).await?;
+                // Token-2022 转账手续费从输出中扣除
+                context.mint_info.amount_after_transfer_fee(calculate_with_slippage_sell(
+                    amount_out,
+                    params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+                ))
+            }
+        };
+
+        let wsol_token_account = spl_associated_token_account::get_associated_token_address(
+            &params.payer.pubkey(),
+            &accounts::WSOL_TOKEN_ACCOUNT,
+        );
+        let mint_token_account =
+            spl_associated_token_account::get_associated_token_address_with_program_id(
+                &params.payer.pubkey(),
+                &params.mint,
+                &context.mint_info.token_program,
+            );
+
+        let wsol_policy = params.wsol_policy.resolve(protocol_params.auto_handle_wsol);
+        let mut instructions = vec![];
+
+        if wsol_policy.wraps_input() {
+            // 创建wSOL ATA账户并将SOL包装为wSOL
+            instructions.extend(wsol::wrap(&params.payer.pubkey(), params.sol_amount));
+        }
+
+        // 创建用户的基础代币账户
+        instructions.push(create_associated_token_account_idempotent(
+            &params.payer.pubkey(),
+            &params.payer.pubkey(),
+            &params.mint,
+            &context.mint_info.token_program,
+        ));
+
+        instructions.push(swap_v2_instruction(
+            &params.payer.pubkey(),
+            &context,
+            (&wsol_token_account, &mint_token_account),
+            (&accounts::WSOL_TOKEN_ACCOUNT, &params.mint),
+            params.sol_amount,
+            minimum_amount_out,
+        ));
+
+        if wsol_policy.unwraps_after_trade() {
+            // 关闭wSOL ATA账户，回收租金
+            instructions.push(wsol::unwrap_all(&params.payer.pubkey()));
+        }
+
+        Ok(instructions)
+    }
+
+    async fn build_sell_instructions(&self, params: &SellParams) -> Result<Vec<Instruction>> {
+        let protocol_params = params
+            .protocol_params
+            .as_any()
+            .downcast_ref::<RaydiumClmmParams>()
+            .ok_or_else(|| anyhow!("Invalid protocol params for RaydiumClmm"))?;
+        let rpc = params.rpc.as_ref().ok_or_else(|| anyhow!("RPC is not set"))?;
+
+        let read_rpc = params.read_rpc.as_ref().unwrap_or(rpc);
+        let context = resolve_swap_context(rpc, read_rpc, &params.mint, protocol_params, false).await?;
+
+        // 获取代币余额
+        let amount = match params.token_amount {
+            Some(amount) if amount > 0 => amount,
+            _ => {
+                get_token_balance_with_program(
+                    rpc.as_ref(),
+                    &params.payer.pubkey(),
+                    &params.mint,
+                    &context.mint_info.token_program,
+                )
+                .await?
+            }
+        };
+        if amount == 0 {
+            return Err(anyhow!("Amount cannot be zero"));
+        }
+
+        let minimum_amount_out = match (params.min_sol_out, protocol_params.minimum_amount_out) {
+            // 指定了绝对下限时直接使用
+            (Some(min_sol_out), _) => min_sol_out,
+            (None, Some(minimum_amount_out)) => minimum_amount_out,
+            (None, None) => {
+                // 池子实际收到的是扣除 Token-2022 转账手续费后的数量
+                let amount_in = context.mint_info.amount_after_transfer_fee(amount);
+                let amount_out = quote(rpc, &context, amount_in).await?;
+                calculate_with_slippage_sell(
+                    amount_out,
+                    params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+                )
+            }
+        };
+
+        let wsol_token_account = spl_associated_token_account::get_associated_token_address(
+            &params.payer.pubkey(),
+            &accounts::WSOL_TOKEN_ACCOUNT,
+        );
+        let mint_token_account =
+            spl_associated_token_account::get_associated_token_address_with_program_id(
+                &params.payer.pubkey(),
+                &params.mint,
+                &context.mint_info.token_program,
+            );
+
+        let wsol_policy = params.wsol_policy.resolve(protocol_params.auto_handle_wsol);
+        let mut instructions = vec![];
+
+        // 创建wSOL ATA账户，如果不存在
+        instructions.push(wsol::create_account(&params.payer.pubkey()));
+
+        instructions.push(swap_v2_instruction(
+            &params.payer.pubkey(),
+            &context,
+            (&mint_token_account, &wsol_token_account),
+            (&params.mint, &accounts::WSOL_TOKEN_ACCOUNT),
+            amount,
+            minimum_amount_out,
+        ));
+
+        if wsol_policy.unwraps_after_trade() {
+            instructions.push(wsol::unwrap_all(&params.payer.pubkey()));
+        }
+
+        Ok(instructions)
+    }
+}
+
+/// 解析池子、代币程序和 tick array
+///
+/// `wsol_in` 为 true 表示买入（WSOL 为输入代币），用于确定 tick array 的查找方向
+async fn resolve_swap_context(
+    rpc: &Arc<SolanaRpcClient>,
+    read_rpc: &Arc<SolanaRpcClient>,
+    mint: &Pubkey,
+    protocol_params: &RaydiumClmmParams,
+    wsol_in: bool,
+) -> Result<SwapContext> {
+    let (pool_state, pool) = match protocol_params.pool {
+        Some(pool_state) => (pool_state, Pool::fetch_cached(rpc, &pool_state).await?),
+        None => {
+            println!("❗️Going through RPC request, increasing instruction building time");
+            let (pool_state, pool) = Pool::find_by_mint(read_rpc, mint).await?;
+            (pool_state, Arc::new(pool))
+        }
+    };
+    let is_wsol_pair = (pool.token_mint_0 == *mint && pool.token_mint_1 == accounts::WSOL_TOKEN_ACCOUNT)
+        || (pool.token_mint_1 == *mint && pool.token_mint_0 == accounts::WSOL_TOKEN_ACCOUNT);
+    if !is_wsol_pair {
+        return Err(anyhow!("Raydium CLMM pool {} is not a {}/WSOL pool", pool_state, mint));
+    }
+    let zero_for_one = (pool.token_mint_0 == accounts::WSOL_TOKEN_ACCOUNT) == wsol_in;
+    let tick_arrays = match &protocol_params.tick_arrays {
+        Some(tick_arrays) if !tick_arrays.is_empty() => tick_arrays.clone(),
+        _ => get_tick_arrays(&pool_state, &pool, zero_for_one)?,
+    };
+    let mint_info = get_mint_token_info(rpc, mint).await?;
+    Ok(SwapContext { pool_state, pool, mint_info, tick_arrays, zero_for_one })
+}
+
+/// 按池子当前流动性和 AmmConfig 的交易费率报价
+async fn quote(rpc: &SolanaRpcClient, context: &SwapContext, amount_in: u64) -> Result<u64> {
+    let pool = &context.pool;
+    let amm_config = AmmConfig::fetch_cached(rpc, &pool.amm_config).await?;
+    get_amount_out(
+        pool.liquidity,
+        pool.sqrt_price_x64,
+        amount_in,
+        context.zero_for_one,
+        amm_config.trade_fee_rate,
+    )
+}
+
+/// 构建 swap_v2 指令，`token_accounts` 与 `mints` 均为 (输入, 输出)
+fn swap_v2_instruction(
+    owner: &Pubkey,
+    context: &SwapContext,
+    token_accounts: (&Pubkey, &Pubkey),
+    mints: (&Pubkey, &Pubkey),
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Instruction {
+    let pool = &context.pool;
+    let zero_for_one = context.zero_for_one;
+    let (input_vault, output_vault) = if zero_for_one {
+        (pool.token_vault_0, pool.token_vault_1)
+    } else {
+        (pool.token_vault_1, pool.token_vault_0)
+    };
+    let mut accounts = vec![
+        AccountMeta::new(*owner, true),                                // Payer (signer)
+        AccountMeta::new_readonly(pool.amm_config, false),             // Amm Config (readonly)
+        AccountMeta::new(context.pool_state, false),                   // Pool State
+        AccountMeta::new(*token_accounts.0, false),                    // Input Token Account
+        AccountMeta::new(*token_accounts.1, false),                    // Output Token Account
+        AccountMeta::new(input_vault, false),                          // Input Vault
+        AccountMeta::new(output_vault, false),                         // Output Vault
+        AccountMeta::new(pool.observation_key, false),                 // Observation State
+        AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false),     // Token Program (readonly)
+        AccountMeta::new_readonly(accounts::TOKEN_PROGRAM_2022, false), // Token Program 2022 (readonly)
+        AccountMeta::new_readonly(accounts::MEMO_PROGRAM, false),      // Memo Program (readonly)
+        AccountMeta::new_readonly(*mints.0, false),                    // Input Vault Mint (readonly)
+        AccountMeta::new_readonly(*mints.1, false),                    // Output Vault Mint (readonly)
+    ];
+    // remaining accounts: tick array 位图扩展账户及 tick array
+    if let Some(bitmap_extension) = get_tick_array_bitmap_extension_pda(&context.pool_state) {
+        accounts.push(AccountMeta::new_readonly(bitmap_extension, false));
+    }
+    accounts.extend(context.tick_arrays.iter().map(|tick_array| AccountMeta::new(*tick_array, false)));
+
+    // 不限制价格，由 minimum_amount_out 保护
+    let sqrt_price_limit_x64 =
+        if zero_for_one { MIN_SQRT_PRICE_X64 + 1 } else { MAX_SQRT_PRICE_X64 - 1 };
+    let mut data = Vec::with_capacity(41);
+    data.extend_from_slice(SWAP_V2_DISCRIMINATOR);
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&minimum_amount_out.to_le_bytes());
+    data.extend_from_slice(&sqrt_price_limit_x64.to_le_bytes());
+    data.push(1); // is_base_input
+
+    Instruction { program_id: accounts::RAYDIUM_CLMM, accounts, data }
+}
@@ -1,19 +1,25 @@
 use anyhow::{anyhow, Result};
-use solana_sdk::{instruction::Instruction, signer::Signer};
+use std::sync::Arc;
+
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey, signer::Signer};
 use solana_system_interface::instruction::transfer;
 use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
 use spl_token::instruction::close_account;
 
 use crate::{
-    constants::raydium_cpmm::{accounts, SWAP_BASE_IN_DISCRIMINATOR},
+    common::{wsol, SolanaRpcClient},
+    constants::raydium_cpmm::{accounts, INITIALIZE_DISCRIMINATOR, SWAP_BASE_IN_DISCRIMINATOR},
     constants::trade::trade::DEFAULT_SLIPPAGE,
-    trading::common::utils::get_token_balance,
+    trading::common::{
+        token_program::{get_mint_token_info, MintTokenInfo},
+        utils::get_token_balance_with_program,
+    },
     trading::core::{
         params::{BuyParams, RaydiumCpmmParams, SellParams},
         traits::InstructionBuilder,
     },
     trading::raydium_cpmm::{
-        common::{get_observation_state_pda, get_pool_pda, get_vault_pda},
+        common::{get_observation_state_pda, get_pool_pda, get_vault_pda, CpmmPoolAddresses},
         // pool::Pool,
     },
 };
@@ -46,6 +52,12 @@ impl RaydiumCpmmInstructionBuilder {
             .as_any()
             .downcast_ref::<RaydiumCpmmParams>()
             .ok_or_else(|| anyhow!("Invalid protocol params for RaydiumCpmm"))?;
+        let mint_info = resolve_mint_token_info(
+            params.rpc.as_ref(),
+            &params.mint,
+            protocol_params.mint_token_program,
+        )
+        .await?;
 
         let pool_state = if protocol_params.pool_state.is_some() {
             protocol_params.pool_state.unwrap()
@@ -72,10 +84,12 @@ impl RaydiumCpmmInstructionBuilder {
             &params.payer.pubkey(),
             &accounts::WSOL_TOKEN_ACCOUNT,
         );
-        let mint_token_account = spl_associated_token_account::get_associated_token_address(
-            &params.payer.pubkey(),
-            &params.mint,
-        );
+        let mint_token_account =
+            spl_associated_token_account::get_associated_token_address_with_program_id(
+                &params.payer.pubkey(),
+                &params.mint,
+                &mint_info.token_program,
+            );
 
         // 获取池的代币账户
         let wsol_vault_account = get_vault_pda(&pool_state, &accounts::WSOL_TOKEN_ACCOUNT).unwrap();
@@ -98,32 +112,17 @@ impl RaydiumCpmmInstructionBuilder {
             } as u64;
             minimum_amount_out = minimum_amount_out * (10000 - slippage_basis_points) / 10000;
             println!("slippage_basis_points: {}", slippage_basis_points);
+            // Token-2022 转账手续费从输出中扣除
+            minimum_amount_out = mint_info.amount_after_transfer_fee(minimum_amount_out);
         }
         println!("minimum_amount_out: {}", minimum_amount_out);
 
+        let wsol_policy = params.wsol_policy.resolve(protocol_params.auto_handle_wsol);
         let mut instructions = vec![];
 
-        if protocol_params.auto_handle_wsol {
-            // 插入wsol
-            instructions.push(
-                // 创建wSOL ATA账户，如果不存在
-                create_associated_token_account_idempotent(
-                    &params.payer.pubkey(),
-                    &params.payer.pubkey(),
-                    &accounts::WSOL_TOKEN_ACCOUNT,
-                    &accounts::TOKEN_PROGRAM,
-                ),
-            );
-            instructions.push(
-                // 将SOL转入wSOL ATA账户
-                transfer(&params.payer.pubkey(), &wsol_token_account, amount_in),
-            );
-
-            // 同步wSOL余额
-            instructions.push(
-                spl_token::instruction::sync_native(&accounts::TOKEN_PROGRAM, &wsol_token_account)
-                    .unwrap(),
-            );
+        if wsol_policy.wraps_input() {
+            // 创建wSOL ATA账户并将SOL包装为wSOL
+            instructions.extend(wsol::wrap(&params.payer.pubkey(), amount_in));
         }
 
         // 创建用户的基础代币账户
@@ -131,7 +130,7 @@ impl RaydiumCpmmInstructionBuilder {
             &params.payer.pubkey(),
             &params.payer.pubkey(),
             &params.mint,
-            &protocol_params.mint_token_program.unwrap_or(accounts::TOKEN_PROGRAM),
+            &mint_info.token_program,
         ));
 
         // 创建买入指令
@@ -146,7 +145,7 @@ impl RaydiumCpmmInstructionBuilder {
             solana_sdk::instruction::AccountMeta::new(mint_vault_account, false), // Output Vault Account
             solana_sdk::instruction::AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false), // Input Token Program (readonly)
             solana_sdk::instruction::AccountMeta::new_readonly(
-                protocol_params.mint_token_program.unwrap_or(accounts::TOKEN_PROGRAM),
+                mint_info.token_program,
                 false,
             ), // Output Token Program (readonly)
             solana_sdk::instruction::AccountMeta::new_readonly(accounts::WSOL_TOKEN_ACCOUNT, false), // Input token mint (readonly)
@@ -161,18 +160,9 @@ impl RaydiumCpmmInstructionBuilder {
 
         instructions.push(Instruction { program_id: accounts::RAYDIUM_CPMM, accounts, data });
 
-        if protocol_params.auto_handle_wsol {
+        if wsol_policy.unwraps_after_trade() {
             // 关闭wSOL ATA账户，回收租金
-            instructions.push(
-                spl_token::instruction::close_account(
-                    &accounts::TOKEN_PROGRAM,
-                    &wsol_token_account,
-                    &params.payer.pubkey(),
-                    &params.payer.pubkey(),
-                    &[],
-                )
-                .unwrap(),
-            );
+            instructions.push(wsol::unwrap_all(&params.payer.pubkey()));
         }
 
         Ok(instructions)
@@ -193,12 +183,20 @@ impl RaydiumCpmmInstructionBuilder {
             return Err(anyhow!("RPC is not set"));
         }
         let rpc = params.rpc.as_ref().unwrap().clone();
+        let mint_info =
+            resolve_mint_token_info(Some(&rpc), &params.mint, protocol_params.mint_token_program)
+                .await?;
 
         // 获取代币余额
         let mut amount = params.token_amount;
         if params.token_amount.is_none() || params.token_amount.unwrap_or(0) == 0 {
-            let balance_u64 =
-                get_token_balance(rpc.as_ref(), &params.payer.pubkey(), &params.mint).await?;
+            let balance_u64 = get_token_balance_with_program(
+                rpc.as_ref(),
+                &params.payer.pubkey(),
+                &params.mint,
+                &mint_info.token_program,
+            )
+            .await?;
             amount = Some(balance_u64);
         }
         let amount = amount.unwrap_or(0);
@@ -221,6 +219,14 @@ impl RaydiumCpmmInstructionBuilder {
             } as u64;
             minimum_amount_out = minimum_amount_out * (10000 - slippage_basis_points) / 10000;
             println!("slippage_basis_points: {}", slippage_basis_points);
+            // 池子实际收到的是扣除 Token-2022 转账手续费后的数量
+            let amount_after_fee = mint_info.amount_after_transfer_fee(amount);
+            minimum_amount_out =
+                (minimum_amount_out as u128 * amount_after_fee as u128 / amount as u128) as u64;
+        }
+        // 指定了绝对下限时直接使用
+        if let Some(min_sol_out) = params.min_sol_out {
+            minimum_amount_out = min_sol_out;
         }
         println!("minimum_amount_out: {}", minimum_amount_out);
 
@@ -249,10 +255,12 @@ impl RaydiumCpmmInstructionBuilder {
             &params.payer.pubkey(),
             &accounts::WSOL_TOKEN_ACCOUNT,
         );
-        let mint_token_account = spl_associated_token_account::get_associated_token_address(
-            &params.payer.pubkey(),
-            &params.mint,
-        );
+        let mint_token_account =
+            spl_associated_token_account::get_associated_token_address_with_program_id(
+                &params.payer.pubkey(),
+                &params.mint,
+                &mint_info.token_program,
+            );
 
         // 获取池的代币账户
         let wsol_vault_account = get_vault_pda(&pool_state, &accounts::WSOL_TOKEN_ACCOUNT).unwrap();
@@ -260,18 +268,11 @@ impl RaydiumCpmmInstructionBuilder {
 
         let observation_state_account = get_observation_state_pda(&pool_state).unwrap();
 
+        let wsol_policy = params.wsol_policy.resolve(protocol_params.auto_handle_wsol);
         let mut instructions = vec![];
 
-        // 插入wsol
-        instructions.push(
-            // 创建wSOL ATA账户，如果不存在
-            create_associated_token_account_idempotent(
-                &params.payer.pubkey(),
-                &params.payer.pubkey(),
-                &accounts::WSOL_TOKEN_ACCOUNT,
-                &protocol_params.mint_token_program.unwrap(),
-            ),
-        );
+        // 创建wSOL ATA账户，如果不存在
+        instructions.push(wsol::create_account(&params.payer.pubkey()));
 
         // 创建卖出指令
         let accounts = vec![
@@ -284,7 +285,7 @@ impl RaydiumCpmmInstructionBuilder {
             solana_sdk::instruction::AccountMeta::new(mint_vault_account, false), // Input Vault Account
             solana_sdk::instruction::AccountMeta::new(wsol_vault_account, false), // Output Vault Account
             solana_sdk::instruction::AccountMeta::new_readonly(
-                protocol_params.mint_token_program.unwrap_or(accounts::TOKEN_PROGRAM),
+                mint_info.token_program,
                 false,
             ), // Input Token Program (readonly)
             solana_sdk::instruction::AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false), // Output Token Program (readonly)
@@ -300,19 +301,161 @@ impl RaydiumCpmmInstructionBuilder {
 
         instructions.push(Instruction { program_id: accounts::RAYDIUM_CPMM, accounts, data });
 
-        if protocol_params.auto_handle_wsol {
-            instructions.push(
-                close_account(
-                    &accounts::TOKEN_PROGRAM,
-                    &wsol_token_account,
-                    &params.payer.pubkey(),
-                    &params.payer.pubkey(),
-                    &[&params.payer.pubkey()],
-                )
-                .unwrap(),
-            );
+        if wsol_policy.unwraps_after_trade() {
+            instructions.push(wsol::unwrap_all(&params.payer.pubkey()));
         }
 
         Ok(instructions)
     }
 }
+
+/// 确定代币程序，未指定或为 Token-2022 时通过 RPC 查询（需读取转账手续费配置）
+async fn resolve_mint_token_info(
+    rpc: Option<&Arc<SolanaRpcClient>>,
+    mint: &Pubkey,
+    mint_token_program: Option<Pubkey>,
+) -> Result<MintTokenInfo> {
+    match (mint_token_program, rpc) {
+        (Some(token_program), _) if token_program == accounts::TOKEN_PROGRAM => {
+            Ok(MintTokenInfo::spl_token())
+        }
+        (_, Some(rpc)) => get_mint_token_info(rpc, mint).await,
+        (Some(token_program), None) => {
+            Ok(MintTokenInfo { token_program, transfer_fee_config: None, epoch: 0 })
+        }
+        (None, None) => Err(anyhow!("RPC is required to detect the mint token program")),
+    }
+}
+
+/// 构建创建 `mint`/WSOL 池子并注入初始流动性的指令
+///
+/// `token_amount` 个代币从创建者的 ATA 转入，`sol_amount` 先包装为 wSOL 再转入，
+/// 初始价格即两者之比。创建者获得 LP 代币，交易在 `open_time`（unix 秒，0 表示立即）之后开放。
+pub fn build_create_pool_instructions(
+    creator: &Pubkey,
+    mint: &Pubkey,
+    mint_token_program: &Pubkey,
+    token_amount: u64,
+    sol_amount: u64,
+    open_time: u64,
+) -> Result<(Vec<Instruction>, CpmmPoolAddresses)> {
+    if token_amount == 0 || sol_amount == 0 {
+        return Err(anyhow!("Initial liquidity cannot be zero"));
+    }
+    let addresses =
+        CpmmPoolAddresses::derive(&accounts::AMM_CONFIG, mint, &accounts::WSOL_TOKEN_ACCOUNT)?;
+    let wsol_token_account = spl_associated_token_account::get_associated_token_address(
+        creator,
+        &accounts::WSOL_TOKEN_ACCOUNT,
+    );
+    let mint_token_account = spl_associated_token_account::get_associated_token_address_with_program_id(
+        creator,
+        mint,
+        mint_token_program,
+    );
+    let creator_lp_token =
+        spl_associated_token_account::get_associated_token_address(creator, &addresses.lp_mint);
+    let wsol_is_token_0 = addresses.token_0_mint == accounts::WSOL_TOKEN_ACCOUNT;
+    let (creator_token_0, creator_token_1) = if wsol_is_token_0 {
+        (wsol_token_account, mint_token_account)
+    } else {
+        (mint_token_account, wsol_token_account)
+    };
+    let (token_0_program, token_1_program) = if wsol_is_token_0 {
+        (accounts::TOKEN_PROGRAM, *mint_token_program)
+    } else {
+        (*mint_token_program, accounts::TOKEN_PROGRAM)
+    };
+    let (init_amount_0, init_amount_1) =
+        if wsol_is_token_0 { (sol_amount, token_amount) } else { (token_amount, sol_amount) };
+
+    let mut instructions = vec![];
+    // 创建wSOL ATA账户，如果不存在
+    instructions.push(create_associated_token_account_idempotent(
+        creator,
+        creator,
+        &accounts::WSOL_TOKEN_ACCOUNT,
+        &accounts::TOKEN_PROGRAM,
+    ));
+    // 将SOL转入wSOL ATA账户
+    instructions.push(transfer(creator, &wsol_token_account, sol_amount));
+    // 同步wSOL余额
+    instructions.push(
+        spl_token::instruction::sync_native(&accounts::TOKEN_PROGRAM, &wsol_token_account).unwrap(),
+    );
+
+    let accounts = vec![
+        solana_sdk::instruction::AccountMeta::new(*creator, true), // Creator (signer)
+        solana_sdk::instruction::AccountMeta::new_readonly(addresses.amm_config, false), // Amm Config (readonly)
+        solana_sdk::instruction::AccountMeta::new_readonly(accounts::AUTHORITY, false), // Authority (readonly)
+        solana_sdk::instruction::AccountMeta::new(addresses.pool_state, false), // Pool State
+        solana_sdk::instruction::AccountMeta::new_readonly(addresses.token_0_mint, false), // Token 0 Mint (readonly)
+        solana_sdk::instruction::AccountMeta::new_readonly(addresses.token_1_mint, false), // Token 1 Mint (readonly)
+        solana_sdk::instruction::AccountMeta::new(addresses.lp_mint, false), // LP Mint
+        solana_sdk::instruction::AccountMeta::new(creator_token_0, false), // Creator Token 0
+        solana_sdk::instruction::AccountMeta::new(creator_token_1, false), // Creator Token 1
+        solana_sdk::instruction::AccountMeta::new(creator_lp_token, false), // Creator LP Token
+        solana_sdk::instruction::AccountMeta::new(addresses.token_0_vault, false), // Token 0 Vault
+        solana_sdk::instruction::AccountMeta::new(addresses.token_1_vault, false), // Token 1 Vault
+        solana_sdk::instruction::AccountMeta::new(accounts::CREATE_POOL_FEE_RECEIVER, false), // Create Pool Fee
+        solana_sdk::instruction::AccountMeta::new(addresses.observation_state, false), // Observation State
+        solana_sdk::instruction::AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false), // Token Program (readonly)
+        solana_sdk::instruction::AccountMeta::new_readonly(token_0_program, false), // Token 0 Program (readonly)
+        solana_sdk::instruction::AccountMeta::new_readonly(token_1_program, false), // Token 1 Program (readonly)
+        solana_sdk::instruction::AccountMeta::new_readonly(accounts::ASSOCIATED_TOKEN_PROGRAM, false), // Associated Token Program (readonly)
+        solana_sdk::instruction::AccountMeta::new_readonly(accounts::SYSTEM_PROGRAM, false), // System Program (readonly)
+        solana_sdk::instruction::AccountMeta::new_readonly(accounts::RENT, false), // Rent (readonly)
+    ];
+    let mut data = vec![];
+    data.extend_from_slice(INITIALIZE_DISCRIMINATOR);
+    data.extend_from_slice(&init_amount_0.to_le_bytes());
+    data.extend_from_slice(&init_amount_1.to_le_bytes());
+    data.extend_from_slice(&open_time.to_le_bytes());
+    instructions.push(Instruction { program_id: accounts::RAYDIUM_CPMM, accounts, data });
+
+    // 关闭wSOL ATA账户，回收租金
+    instructions.push(
+        close_account(&accounts::TOKEN_PROGRAM, &wsol_token_account, creator, creator, &[creator]).unwrap(),
+    );
+
+    Ok((instructions, addresses))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::core::params::SellParams;
+
+    async fn sell_min_sol_out(min_sol_out: Option<u64>) -> u64 {
+        let protocol_params = RaydiumCpmmParams {
+            pool_state: Some(Pubkey::new_unique()),
+            mint_token_program: Some(accounts::TOKEN_PROGRAM),
+            minimum_amount_out: Some(1_000_000),
+            ..RaydiumCpmmParams::default()
+        };
+        let rpc = SolanaRpcClient::new_mock("succeeds".to_string());
+        let params = SellParams::for_test(
+            rpc,
+            Pubkey::new_unique(),
+            5_000,
+            min_sol_out,
+            Box::new(protocol_params),
+        );
+        let instructions =
+            RaydiumCpmmInstructionBuilder.build_sell_instructions(&params).await.unwrap();
+        let swap = instructions
+            .iter()
+            .find(|instruction| instruction.program_id == accounts::RAYDIUM_CPMM)
+            .unwrap();
+        assert_eq!(swap.data[..8], SWAP_BASE_IN_DISCRIMINATOR[..]);
+        assert_eq!(u64::from_le_bytes(swap.data[8..16].try_into().unwrap()), 5_000);
+        u64::from_le_bytes(swap.data[16..24].try_into().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_sell_min_sol_out_reaches_instruction_data() {
+        assert_eq!(sell_min_sol_out(Some(123_456)).await, 123_456);
+        // 未指定时按 minimum_amount_out 扣除 10% 滑点
+        assert_eq!(sell_min_sol_out(None).await, 900_000);
+    }
+}
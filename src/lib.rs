@@ -1,36 +1,143 @@
+pub mod backtest;
+pub mod builder;
 pub mod common;
 pub mod constants;
+pub mod doctor;
 pub mod instruction;
+pub mod monitor;
+pub mod pending_orders;
 pub mod protos;
+pub mod state_report;
+pub mod strategy;
+pub mod support;
 pub mod swqos;
 pub mod trading;
 pub mod utils;
 pub use solana_streamer_sdk;
+pub use builder::SolanaTradeBuilder;
+pub use doctor::{DoctorCheck, DoctorReport};
+pub use pending_orders::{PendingOrder, PendingOrderId};
+pub use state_report::StateReport;
+pub use support::{SanitizedConfig, SupportSnapshot};
 
+use crate::monitor::CopyBuyOrder;
+use crate::monitor::CopySellOrder;
+use crate::monitor::MomentumOrder;
+use crate::monitor::SnipeOrder;
+use crate::monitor::{DustSweepConfig, DustSweepPlan, DustSweepReport, PriceMonitor};
+use crate::monitor::{PnlStatistics, PnlTracker};
+use crate::strategy::{FailoverCoordinator, StrategyEvent, StrategyEventBus};
 use crate::swqos::SwqosConfig;
+use crate::trading::core::params::BonkCreateParams;
 use crate::trading::core::params::BonkParams;
+use crate::trading::core::params::BoopParams;
+use crate::trading::core::params::JupiterParams;
+use crate::trading::core::params::MoonshotParams;
+use crate::trading::core::params::OrcaWhirlpoolParams;
+use crate::trading::core::params::PhoenixParams;
+use crate::trading::core::params::PumpFunCreateParams;
 use crate::trading::core::params::PumpFunParams;
 use crate::trading::core::params::PumpSwapParams;
+use crate::trading::core::params::RaydiumAmmV4Params;
+use crate::trading::core::params::RaydiumClmmParams;
 use crate::trading::core::params::RaydiumCpmmParams;
 use crate::trading::core::traits::ProtocolParams;
 use crate::trading::factory::DexType;
+use crate::constants::trade::trade::DEFAULT_SLIPPAGE;
+use crate::trading::arbitrage::{ArbitrageConfig, ArbitrageDetector, ArbitrageSignal};
+use crate::trading::raydium_cpmm::common::CpmmPoolAddresses;
+use crate::trading::common::token_program::get_mint_token_info;
+use crate::trading::router::{Route, Router, RouterConfig};
+use crate::trading::twap::{plan_twap, TwapConfig, TwapOrders, TwapSliceOutcome};
+use crate::trading::paper::PaperBroker;
+use crate::instruction::bonk::build_create_and_buy_instructions as build_bonk_create_and_buy_instructions;
+use crate::instruction::pumpfun::build_create_and_buy_instructions;
+use crate::instruction::raydium_cpmm::build_create_pool_instructions;
+use crate::trading::pumpfun::common::fetch_bonding_curve;
+use crate::trading::pumpfun::migration::migrated_pumpswap_params;
+use crate::trading::common::{
+    build_versioned_transaction_with_signers, get_address_lookup_table_accounts, FillReport,
+    SlippageManager,
+};
+use crate::trading::common::nonce_manager::{get_transaction_blockhash, is_using_nonce};
+use crate::trading::common::tip_escalation::{tips, wait_for_expiry, wait_for_landing, TipEscalationAttempt};
 use crate::trading::BuyParams;
+use crate::trading::BuyWithTipParams;
 use crate::trading::SellParams;
+use crate::trading::SellWithTipParams;
+use crate::trading::core::parallel::{
+    next_trade_id, select_swqos_types, swqos_tips, SentNotifier, SubmissionAttempt, SubmissionStatus,
+    SubmissionTiming,
+};
+use crate::trading::SubmissionReport;
 use crate::trading::TradeFactory;
-use common::{PriorityFee, SolanaRpcClient, TradeConfig};
+use common::blockhash_cache::BlockhashCache;
+use common::nonce_cache::NonceCache;
+use common::tip_cache::TipCache;
+use common::{
+    ExecutionMode, ExecutionOverrides, IdempotencyCache, MintLocks, PendingTrade, PriorityFee, PriorityFeeEstimator, PriorityFeeMode,
+    SolanaRpcClient, SpendTracker, SubmissionMode, TokenMetadata, TokenMetadataCache, TradeConfig, TradeOutcome,
+    TradeOutcomeBus, wsol,
+};
 use rustls::crypto::{ring::default_provider, CryptoProvider};
 use solana_sdk::hash::Hash;
-use solana_sdk::{pubkey::Pubkey, signature::Keypair};
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction,
+    instruction::Instruction,
+    native_token::sol_str_to_lamports,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::VersionedTransaction,
+};
+use solana_system_interface::instruction::transfer;
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+use swqos::tip_oracle::JitoTipOracle;
+use swqos::health::{SwqosHealthEvent, SwqosHealthMonitor};
+use swqos::rate_limit::RateLimitedClient;
+use swqos::region::AutoRegionClient;
+use swqos::jito::{JitoBundleStatus, JitoClient};
+use swqos::metrics::{track_winner, SwqosMetrics, SwqosStats};
+use swqos::solana_rpc::SolRpcClient;
 use swqos::SwqosClient;
+use swqos::SwqosType;
+use swqos::TradeType;
+use tokio::sync::broadcast;
 
 pub struct SolanaTrade {
     pub payer: Arc<Keypair>,
+    /// Keypair that pays network fees and tips, `payer` when unset
+    pub fee_payer: Option<Arc<Keypair>>,
     pub rpc: Arc<SolanaRpcClient>,
+    /// Client for heavy queries such as pool discovery, `rpc` when no read RPC URL is set
+    pub read_rpc: Arc<SolanaRpcClient>,
     pub swqos_clients: Vec<Arc<SwqosClient>>,
     pub priority_fee: PriorityFee,
     pub trade_config: TradeConfig,
+    pub priority_fee_estimator: Arc<PriorityFeeEstimator>,
+    pub spend_tracker: Arc<SpendTracker>,
+    pub idempotency_cache: Arc<IdempotencyCache>,
+    /// Set when `adaptive_slippage` is configured; record realized slippage through it
+    pub slippage_manager: Option<Arc<SlippageManager>>,
+    /// Outcomes of trades completed in the background under `SubmissionMode::FireAndTrack`
+    pub trade_outcomes: Arc<TradeOutcomeBus>,
+    /// Serializes `buy` / `sell` per mint so conflicting trades on one token never interleave
+    pub mint_locks: Arc<MintLocks>,
+    /// Health of each SWQOS client; probed in the background when `swqos_health_check` is set
+    pub swqos_health: Arc<SwqosHealthMonitor>,
+    /// Set under `ExecutionMode::Paper`; buys and sells fill here at live quotes instead of on chain
+    pub paper_broker: Option<Arc<PaperBroker>>,
+    /// Realized and unrealized PnL of the fills passed to `record_fill`
+    pub pnl_tracker: Arc<PnlTracker>,
+    /// Receives `copy_buy` results; share it with monitors via their `with_event_bus`
+    pub event_bus: Arc<StrategyEventBus>,
+    /// Set by `with_failover`; trades are rejected while this instance is the standby
+    pub failover: Option<Arc<FailoverCoordinator>>,
+    /// TWAP orders with slices still to run, see `list_pending_orders`
+    pub twap_orders: Arc<TwapOrders>,
 }
 
 static INSTANCE: Mutex<Option<Arc<SolanaTrade>>> = Mutex::new(None);
@@ -39,10 +146,24 @@ impl Clone for SolanaTrade {
     fn clone(&self) -> Self {
         Self {
             payer: self.payer.clone(),
+            fee_payer: self.fee_payer.clone(),
             rpc: self.rpc.clone(),
+            read_rpc: self.read_rpc.clone(),
             swqos_clients: self.swqos_clients.clone(),
             priority_fee: self.priority_fee.clone(),
             trade_config: self.trade_config.clone(),
+            priority_fee_estimator: self.priority_fee_estimator.clone(),
+            spend_tracker: self.spend_tracker.clone(),
+            idempotency_cache: self.idempotency_cache.clone(),
+            slippage_manager: self.slippage_manager.clone(),
+            trade_outcomes: self.trade_outcomes.clone(),
+            mint_locks: self.mint_locks.clone(),
+            swqos_health: self.swqos_health.clone(),
+            paper_broker: self.paper_broker.clone(),
+            pnl_tracker: self.pnl_tracker.clone(),
+            event_bus: self.event_bus.clone(),
+            failover: self.failover.clone(),
+            twap_orders: self.twap_orders.clone(),
         }
     }
 }
@@ -59,7 +180,7 @@ impl SolanaTrade {
         let rpc_url = trade_config.rpc_url.clone();
         let swqos_configs = trade_config.swqos_configs.clone();
         let mut priority_fee = trade_config.priority_fee.clone();
-        let commitment = trade_config.commitment.clone();
+        let commitment = trade_config.commitment;
         if priority_fee.buy_tip_fees.len() < swqos_configs.len() {
             // 补齐数组,只补齐缺少的
             let mut buy_tip_fees = priority_fee.buy_tip_fees.clone();
@@ -74,26 +195,122 @@ impl SolanaTrade {
             priority_fee.buy_tip_fees = buy_tip_fees;
             trade_config.priority_fee = priority_fee.clone();
         }
+        for dex_priority_fee in trade_config.dex_priority_fees.values_mut() {
+            if dex_priority_fee.buy_tip_fees.len() < swqos_configs.len() {
+                let default_fee = dex_priority_fee.buy_tip_fee;
+                dex_priority_fee.buy_tip_fees.resize(swqos_configs.len(), default_fee);
+            }
+        }
 
         let mut swqos_clients: Vec<Arc<SwqosClient>> = vec![];
 
-        for swqos in swqos_configs {
-            let swqos_client =
-                SwqosConfig::get_swqos_client(rpc_url.clone(), commitment.clone(), swqos.clone());
+        let mut auto_region_clients: Vec<Arc<AutoRegionClient>> = vec![];
+
+        // 无法创建客户端的配置（如未注册的自定义服务商）跳过，并移除其小费以保持下标对齐
+        let mut skipped_swqos: Vec<usize> = vec![];
+
+        for (index, swqos) in swqos_configs.into_iter().enumerate() {
+            let swqos_client: Result<Arc<SwqosClient>, anyhow::Error> = match SwqosConfig::get_auto_region_client(rpc_url.clone(), &swqos) {
+                Ok(Some(auto_region_client)) => {
+                    auto_region_clients.push(auto_region_client.clone());
+                    Ok(auto_region_client)
+                }
+                Ok(None) => SwqosConfig::get_swqos_client(rpc_url.clone(), commitment, swqos.clone()),
+                Err(e) => Err(e),
+            };
+            let mut swqos_client = match swqos_client {
+                Ok(swqos_client) => swqos_client,
+                Err(e) => {
+                    println!("警告: 跳过 SWQOS 配置: {}", e);
+                    skipped_swqos.push(index);
+                    continue;
+                }
+            };
+            if let Some(rate_limit) = trade_config.swqos_rate_limits.get(&swqos_client.get_swqos_type()) {
+                swqos_client = Arc::new(RateLimitedClient::new(swqos_client, *rate_limit));
+            }
             swqos_clients.push(swqos_client);
         }
+        for index in skipped_swqos.into_iter().rev() {
+            trade_config.swqos_configs.remove(index);
+            if index < priority_fee.buy_tip_fees.len() {
+                priority_fee.buy_tip_fees.remove(index);
+            }
+            for dex_priority_fee in trade_config.dex_priority_fees.values_mut() {
+                if index < dex_priority_fee.buy_tip_fees.len() {
+                    dex_priority_fee.buy_tip_fees.remove(index);
+                }
+            }
+        }
+        trade_config.priority_fee.buy_tip_fees = priority_fee.buy_tip_fees.clone();
+
+        // SwqosRegion::Auto 的通道先探测一次选出最快的区域，再定时重新探测
+        futures::future::join_all(auto_region_clients.iter().map(|auto_region_client| auto_region_client.probe()))
+            .await;
+        for auto_region_client in &auto_region_clients {
+            auto_region_client.start(trade_config.region_probe_interval_ms);
+        }
 
         let rpc = Arc::new(SolanaRpcClient::new_with_commitment(
             rpc_url.clone(),
             commitment,
         ));
+        let read_rpc = match &trade_config.read_rpc_url {
+            Some(read_rpc_url) => Arc::new(SolanaRpcClient::new_with_commitment(
+                read_rpc_url.clone(),
+                commitment,
+            )),
+            None => rpc.clone(),
+        };
+
+        if let Some(auto_tip) = trade_config.auto_tip.clone() {
+            TipCache::get_instance().init(Some(priority_fee.buy_tip_fee));
+            JitoTipOracle::new(auto_tip).start();
+        }
+
+        if let Some(tip_account_refresh_ms) = trade_config.tip_account_refresh_ms {
+            TipCache::get_instance().start_tip_account_refresh(swqos_clients.clone(), tip_account_refresh_ms);
+        }
+
+        let swqos_health = Arc::new(SwqosHealthMonitor::new(&swqos_clients));
+        if let Some(swqos_health_check) = trade_config.swqos_health_check.clone() {
+            swqos_health.start(swqos_clients.clone(), swqos_health_check);
+        }
+
+        let priority_fee_estimator = Arc::new(PriorityFeeEstimator::new(
+            rpc.clone(),
+            trade_config.priority_fee_api_url.clone(),
+        ));
 
         let instance = Self {
             payer,
+            fee_payer: None,
             rpc,
+            read_rpc,
             swqos_clients,
             priority_fee,
             trade_config: trade_config.clone(),
+            priority_fee_estimator,
+            spend_tracker: Arc::new(
+                SpendTracker::new(trade_config.spend_limit).with_timezone(trade_config.reporting_timezone),
+            ),
+            idempotency_cache: Arc::new(IdempotencyCache::default()),
+            slippage_manager: trade_config
+                .adaptive_slippage
+                .map(|config| Arc::new(SlippageManager::new(config))),
+            trade_outcomes: Arc::new(TradeOutcomeBus::default()),
+            mint_locks: Arc::new(MintLocks::default()),
+            swqos_health,
+            paper_broker: match trade_config.execution_mode {
+                ExecutionMode::Live => None,
+                ExecutionMode::Paper => Some(Arc::new(
+                    PaperBroker::new().with_fill_model(trade_config.paper_fill_model),
+                )),
+            },
+            pnl_tracker: Arc::new(PnlTracker::new()),
+            event_bus: Arc::new(StrategyEventBus::new()),
+            failover: None,
+            twap_orders: Arc::new(TwapOrders::default()),
         };
 
         let mut current = INSTANCE.lock().unwrap();
@@ -102,11 +319,247 @@ impl SolanaTrade {
         instance
     }
 
+    /// Pay network fees and tips from `fee_payer` while `payer` keeps custody of the tokens
+    ///
+    /// Both keypairs sign every transaction built by this instance.
+    pub fn with_fee_payer(mut self, fee_payer: Arc<Keypair>) -> Self {
+        self.fee_payer = Some(fee_payer);
+        let mut current = INSTANCE.lock().unwrap();
+        *current = Some(Arc::new(self.clone()));
+        drop(current);
+        self
+    }
+
+    /// Track PnL with `pnl_tracker`, e.g. one loaded from a `PositionStore` so history survives restarts
+    pub fn with_pnl_tracker(mut self, pnl_tracker: Arc<PnlTracker>) -> Self {
+        self.pnl_tracker = pnl_tracker;
+        let mut current = INSTANCE.lock().unwrap();
+        *current = Some(Arc::new(self.clone()));
+        drop(current);
+        self
+    }
+
+    /// Publish `copy_buy` results to `event_bus` instead of a bus owned by this instance
+    pub fn with_event_bus(mut self, event_bus: Arc<StrategyEventBus>) -> Self {
+        self.event_bus = event_bus;
+        let mut current = INSTANCE.lock().unwrap();
+        *current = Some(Arc::new(self.clone()));
+        drop(current);
+        self
+    }
+
+    /// Replace the idempotency cache, e.g. with `IdempotencyCache::with_store` to share submitted
+    /// idempotency keys with a failover standby
+    pub fn with_idempotency_cache(mut self, idempotency_cache: Arc<IdempotencyCache>) -> Self {
+        self.idempotency_cache = idempotency_cache;
+        let mut current = INSTANCE.lock().unwrap();
+        *current = Some(Arc::new(self.clone()));
+        drop(current);
+        self
+    }
+
+    /// Only trade while `failover` holds the lease shared with a standby instance
+    ///
+    /// Buys, sells, pool creation, arbitrage, WSOL helpers and dust sweeps return an error while
+    /// this instance is the standby, so a primary that lost its lease never trades alongside the
+    /// instance that took over.
+    pub fn with_failover(mut self, failover: Arc<FailoverCoordinator>) -> Self {
+        self.failover = Some(failover);
+        let mut current = INSTANCE.lock().unwrap();
+        *current = Some(Arc::new(self.clone()));
+        drop(current);
+        self
+    }
+
+    fn check_active(&self) -> Result<(), anyhow::Error> {
+        match &self.failover {
+            Some(failover) if !failover.is_active() => {
+                Err(anyhow::anyhow!("Trading is disabled while this instance is the failover standby"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// 协议被禁止执行时发布 `StrategyEvent::ExecutionSkipped` 并返回错误
+    fn check_execution_enabled(&self, dex_type: &DexType, mint: &Pubkey) -> Result<(), anyhow::Error> {
+        if self.trade_config.is_execution_enabled(dex_type) {
+            return Ok(());
+        }
+        let reason = format!("Execution is disabled for {} by trade config", dex_type);
+        println!("跳过 {} 的 {} 交易: {}", mint, dex_type, reason);
+        self.event_bus.publish(StrategyEvent::ExecutionSkipped {
+            dex_type: dex_type.clone(),
+            mint: *mint,
+            reason: reason.clone(),
+        });
+        Err(anyhow::anyhow!(reason))
+    }
+
+    /// Subscribe to strategy events published to `event_bus`
+    pub fn subscribe_strategy_events(&self) -> broadcast::Receiver<StrategyEvent> {
+        self.event_bus.subscribe()
+    }
+
     /// Get the RPC client instance
     pub fn get_rpc(&self) -> &Arc<SolanaRpcClient> {
         &self.rpc
     }
 
+    /// Resolve the priority fee for a trade on `mint`
+    ///
+    /// Uses the per-DEX override from the trade config when one is set. In `PriorityFeeMode::Auto` the compute unit price is estimated from recent
+    /// prioritization fees; the configured values are kept if estimation fails.
+    async fn resolve_priority_fee(
+        &self,
+        dex_type: &DexType,
+        mint: &Pubkey,
+        execution_overrides: Option<&ExecutionOverrides>,
+    ) -> PriorityFee {
+        let mut priority_fee = self.trade_config.priority_fee_for(dex_type).clone();
+        if let Some(execution_overrides) = execution_overrides {
+            execution_overrides.apply_compute_budget(&mut priority_fee);
+        }
+        if let PriorityFeeMode::Auto(percentile) = priority_fee.mode {
+            match self.priority_fee_estimator.estimate(&[*mint], percentile).await {
+                Ok(unit_price) => {
+                    priority_fee.unit_price = unit_price;
+                    priority_fee.rpc_unit_price = unit_price;
+                }
+                Err(e) => println!("优先费估算失败，使用配置值: {}", e),
+            }
+        }
+        priority_fee
+    }
+
+    /// Detect which DEX `mint` currently trades on
+    ///
+    /// Checks, in order: an incomplete PumpFun bonding curve, a PumpSwap pool, an unmigrated
+    /// Bonk pool, a live Moonshot bonding curve, a live Boop.fun bonding curve, a Raydium CPMM
+    /// WSOL pool, and a Raydium AMM v4 WSOL pool. Pass the result to `buy` / `sell` with `extension_params: None` so the
+    /// protocol defaults are used. Each check costs RPC round trips on the read RPC, so cache the
+    /// result on latency-sensitive paths.
+    pub async fn resolve_dex(&self, mint: &Pubkey) -> Result<DexType, anyhow::Error> {
+        trading::resolve_dex(&self.read_rpc, mint).await
+    }
+
+    /// Look up the name, symbol and decimals of `mint`
+    ///
+    /// Results are cached in `TokenMetadataCache` for the life of the process, and SDK logs show
+    /// the symbol of cached mints instead of the address.
+    pub async fn token_metadata(&self, mint: &Pubkey) -> Result<Arc<TokenMetadata>, anyhow::Error> {
+        TokenMetadataCache::get_instance().get_or_fetch(&self.read_rpc, mint).await
+    }
+
+    /// Report what a confirmed trade actually filled for the payer
+    ///
+    /// Parses the pre/post SOL and token balances in the transaction meta. Amounts are positive
+    /// for buys and negative for sells; `sol_spent` excludes the network fee, which is reported
+    /// separately in `fee_lamports`. For Token-2022 mints with a transfer fee, the withheld fee
+    /// and its rate are reported in `transfer_fee` and `transfer_fee_basis_points`.
+    pub async fn get_fill_report(
+        &self,
+        signature: &Signature,
+        mint: &Pubkey,
+    ) -> Result<FillReport, anyhow::Error> {
+        if let Some(fill) = self.paper_broker.as_ref().and_then(|paper| paper.fill(signature)) {
+            return Ok(fill);
+        }
+        trading::common::get_fill_report(&self.rpc, signature, &self.payer.pubkey(), mint).await
+    }
+
+    /// Report what a submitted trade actually filled, whichever of its transactions landed
+    ///
+    /// Checks every signature in `report`, including earlier tip escalation attempts, and parses
+    /// the first one that confirmed without error. Use the result instead of estimates to record
+    /// copied positions and cost basis, e.g. `CopySellMirror::record_fill` and
+    /// `PriceMonitor::open_position_from_fill`. Fails if none of the transactions has landed yet.
+    pub async fn get_submission_fill_report(
+        &self,
+        report: &SubmissionReport,
+        mint: &Pubkey,
+    ) -> Result<FillReport, anyhow::Error> {
+        let mut signatures = report.signatures();
+        for attempt in &report.tip_escalations {
+            for signature in &attempt.signatures {
+                if !signatures.contains(signature) {
+                    signatures.push(*signature);
+                }
+            }
+        }
+        if let Some(paper_broker) = &self.paper_broker {
+            if let Some(fill) = signatures.iter().find_map(|signature| paper_broker.fill(signature)) {
+                return Ok(fill);
+            }
+        }
+        let statuses = self.rpc.get_signature_statuses(&signatures).await?.value;
+        let landed = signatures
+            .iter()
+            .zip(statuses)
+            .find(|(_, status)| status.as_ref().is_some_and(|status| status.err.is_none()))
+            .map(|(signature, _)| *signature)
+            .ok_or_else(|| anyhow::anyhow!("None of the {} submitted transactions has landed", signatures.len()))?;
+        self.get_fill_report(&landed, mint).await
+    }
+
+    /// Record an entry or exit fill in the PnL tracker
+    ///
+    /// Pass the result of `get_fill_report` / `get_submission_fill_report` after each buy and
+    /// sell. Cost basis is the weighted average of the buys including network fees; selling more
+    /// than the recorded position counts the excess at zero cost.
+    pub fn record_fill(&self, mint: Pubkey, fill: &FillReport) -> Result<(), anyhow::Error> {
+        self.pnl_tracker.record_fill(mint, fill)
+    }
+
+    /// Realized PnL per token, unrealized PnL at the latest prices and the portfolio totals
+    ///
+    /// Prices come from the tracker's `observe` / `update_price`, or from registering
+    /// `pnl_tracker` with a `TradingStrategyService`. Tokens without a price have no unrealized PnL.
+    pub fn get_strategy_statistics(&self) -> PnlStatistics {
+        self.pnl_tracker.statistics()
+    }
+
+    /// Realized PnL in lamports of the sells filled on `day`
+    ///
+    /// Days follow `TradeConfig::reporting_timezone`; pass `None` for the current trading day.
+    pub fn get_daily_pnl(&self, day: Option<chrono::NaiveDate>) -> i64 {
+        let timezone = self.trade_config.reporting_timezone;
+        let day = day.unwrap_or_else(|| common::trading_day::today(&timezone));
+        self.pnl_tracker.daily_realized_pnl(day, &timezone)
+    }
+
+    /// Subscribe to the outcomes of trades submitted under `SubmissionMode::FireAndTrack`
+    ///
+    /// Each `buy` / `sell` that returned early publishes one outcome once its submissions and
+    /// confirmation polling finish, including failures to build the instructions. Only trades
+    /// that finish after subscribing are received.
+    pub fn subscribe_trade_outcomes(&self) -> broadcast::Receiver<TradeOutcome> {
+        self.trade_outcomes.subscribe()
+    }
+
+    /// Submission latency, acceptance and landing counts per SWQOS provider
+    ///
+    /// Counted since process start (or the last `reset_swqos_stats`) across all instances.
+    /// A submission is accepted when the provider answers without an error; it has landed when
+    /// confirmation polling sees it confirmed before timing out. Use `acceptance_rate` and
+    /// `land_rate` to spot endpoints worth dropping from `swqos_configs`.
+    pub fn get_swqos_stats(&self) -> Vec<SwqosStats> {
+        SwqosMetrics::get_instance().stats()
+    }
+
+    /// Clear the counters behind `get_swqos_stats`
+    pub fn reset_swqos_stats(&self) {
+        SwqosMetrics::get_instance().reset()
+    }
+
+    /// Subscribe to SWQOS quarantine and recovery events from the health checks
+    ///
+    /// With `swqos_health_check` set, an endpoint that fails `failure_threshold` consecutive pings
+    /// is quarantined and skipped by `buy` / `sell` until a ping succeeds again. If every endpoint
+    /// is quarantined, trades are still sent to all of them. Events are also logged.
+    pub fn subscribe_swqos_health(&self) -> broadcast::Receiver<SwqosHealthEvent> {
+        self.swqos_health.subscribe()
+    }
+
     /// Get the current instance
     pub fn get_instance() -> Arc<Self> {
         let instance = INSTANCE.lock().unwrap();
@@ -120,22 +573,36 @@ impl SolanaTrade {
     ///
     /// # Arguments
     ///
-    /// * `dex_type` - The trading protocol to use (PumpFun, PumpSwap, or Bonk)
+    /// * `dex_type` - The trading protocol to use (PumpFun, PumpSwap, or Bonk). PumpFun trades on a
+    ///   completed bonding curve go to the migrated PumpSwap pool unless `auto_route_migrated` is off
     /// * `mint` - The public key of the token mint to buy
     /// * `creator` - Optional creator public key for the token (defaults to Pubkey::default() if None)
     /// * `sol_amount` - Amount of SOL to spend on the purchase (in lamports)
     /// * `slippage_basis_points` - Optional slippage tolerance in basis points (e.g., 100 = 1%)
-    /// * `recent_blockhash` - Recent blockhash for transaction validity
-    /// * `custom_buy_tip_fee` - Optional custom tip fee for priority processing (in SOL)
+    /// * `recent_blockhash` - Recent blockhash for transaction validity; if None, the blockhash
+    ///   kept fresh by the background `BlockhashCache` is used
+    /// * `execution_overrides` - Optional compute unit limit, unit price and tip (in SOL) for this trade only;
+    ///   the tip overrides the auto tip. An idempotency key rejects resubmissions of the same trade
     /// * `extension_params` - Optional protocol-specific parameters (uses defaults if None)
     ///
     /// # Returns
     ///
-    /// Returns `Ok(())` if the buy order is successfully executed, or an error if the transaction fails.
+    /// Returns a `SubmissionReport` with the per-SWQOS timings if the buy order is successfully
+    /// executed, or an error if the transaction fails. With `race_submissions` on, it returns as
+    /// soon as one SWQOS succeeds and the others are reported as cancelled.
+    /// Under `SubmissionMode::FireAndTrack` it returns once the signed transaction is handed to the
+    /// first provider, with that signature in the report; confirmation continues in the background
+    /// and the result is then published through `subscribe_trade_outcomes`.
+    /// Under `ExecutionMode::Paper` nothing is sent: the buy fills at the live quote and the report
+    /// carries a simulated signature that `get_fill_report` resolves.
     ///
     /// # Errors
     ///
     /// This function will return an error if:
+    /// - This instance is the failover standby (see `with_failover`)
+    /// - Execution is disabled for `dex_type` in the trade config
+    /// - `sol_amount` would exceed the per-trade or daily spend cap in the trade config
+    /// - The idempotency key was already used by a trade submitted within the last minute
     /// - Invalid protocol parameters are provided
     /// - The transaction fails to execute
     /// - Network or RPC errors occur
@@ -151,7 +618,7 @@ impl SolanaTrade {
     /// let mint = Pubkey::new_unique();
     /// let sol_amount = 1_000_000_000; // 1 SOL in lamports
     /// let slippage = Some(500); // 5% slippage
-    /// let recent_blockhash = Hash::default();
+    /// let recent_blockhash = Some(Hash::default());
     ///
     /// solana_trade.buy(
     ///     DexType::PumpFun,
@@ -171,95 +638,137 @@ impl SolanaTrade {
         creator: Option<Pubkey>,
         sol_amount: u64,
         slippage_basis_points: Option<u64>,
-        recent_blockhash: Hash,
-        custom_buy_tip_fee: Option<f64>,
+        recent_blockhash: Option<Hash>,
+        execution_overrides: Option<ExecutionOverrides>,
         extension_params: Option<Box<dyn ProtocolParams>>,
-    ) -> Result<(), anyhow::Error> {
-        let executor = TradeFactory::create_executor(dex_type.clone());
-        let protocol_params = if let Some(params) = extension_params {
-            params
-        } else {
-            match dex_type {
-                DexType::PumpFun => Box::new(PumpFunParams::default()) as Box<dyn ProtocolParams>,
-                DexType::PumpSwap => Box::new(PumpSwapParams::default()) as Box<dyn ProtocolParams>,
-                DexType::Bonk => Box::new(BonkParams::default()) as Box<dyn ProtocolParams>,
-                DexType::RaydiumCpmm => {
-                    Box::new(RaydiumCpmmParams::default()) as Box<dyn ProtocolParams>
-                }
-            }
-        };
-        let buy_params = BuyParams {
-            rpc: Some(self.rpc.clone()),
-            payer: self.payer.clone(),
-            mint: mint,
-            creator: creator.unwrap_or(Pubkey::default()),
-            sol_amount: sol_amount,
-            slippage_basis_points: slippage_basis_points,
-            priority_fee: self.trade_config.priority_fee.clone(),
-            lookup_table_key: self.trade_config.lookup_table_key,
-            recent_blockhash,
-            data_size_limit: 0,
-            protocol_params: protocol_params.clone(),
-        };
-        let mut priority_fee = buy_params.priority_fee.clone();
-        if custom_buy_tip_fee.is_some() {
-            priority_fee.buy_tip_fee = custom_buy_tip_fee.unwrap();
-            priority_fee.buy_tip_fees = priority_fee
-                .buy_tip_fees
-                .iter()
-                .map(|_| custom_buy_tip_fee.unwrap())
-                .collect();
-        }
-        let buy_with_tip_params = buy_params.clone().with_tip(self.swqos_clients.clone());
-
-        // Validate protocol params
-        let is_valid_params = match dex_type {
-            DexType::PumpFun => protocol_params
-                .as_any()
-                .downcast_ref::<PumpFunParams>()
-                .is_some(),
-            DexType::PumpSwap => protocol_params
-                .as_any()
-                .downcast_ref::<PumpSwapParams>()
-                .is_some(),
-            DexType::Bonk => protocol_params
-                .as_any()
-                .downcast_ref::<BonkParams>()
-                .is_some(),
-            DexType::RaydiumCpmm => protocol_params
-                .as_any()
-                .downcast_ref::<RaydiumCpmmParams>()
-                .is_some(),
+    ) -> Result<SubmissionReport, anyhow::Error> {
+        self.check_active()?;
+        let (dex_type, creator, extension_params) =
+            self.route_migrated(dex_type, &mint, creator, extension_params).await?;
+        self.check_execution_enabled(&dex_type, &mint)?;
+        if let Some(paper_broker) = &self.paper_broker {
+            let idempotency_key = execution_overrides.and_then(|o| o.idempotency_key);
+            return self.paper_buy(paper_broker, dex_type, mint, sol_amount, idempotency_key).await;
+        }
+        // Trades on one mint run one at a time; fire-and-track trades wait in their background task
+        let _mint_lock = match self.trade_config.submission_mode {
+            SubmissionMode::AwaitConfirmation => Some(self.mint_locks.lock(&mint).await),
+            SubmissionMode::FireAndTrack => None,
         };
+        let idempotency_key = execution_overrides.as_ref().and_then(|o| o.idempotency_key.clone());
+        let mut buy_params = self
+            .make_buy_params(
+                &dex_type,
+                mint,
+                creator,
+                sol_amount,
+                slippage_basis_points,
+                recent_blockhash,
+                execution_overrides,
+                extension_params,
+            )
+            .await?;
+        let swqos_clients =
+            self.select_swqos_clients(buy_params.swqos_types.as_deref(), &mut buy_params.priority_fee)?;
+        let mut buy_with_tip_params = buy_params.with_tip(
+            swqos_clients,
+            self.trade_config.race_submissions,
+            self.rpc_fallback_client(),
+        );
 
-        if !is_valid_params {
-            return Err(anyhow::anyhow!("Invalid protocol params for Trade"));
+        self.claim_idempotency_key(idempotency_key.as_deref())?;
+        if let Err(e) = self.spend_tracker.reserve(sol_amount) {
+            self.release_idempotency_key(idempotency_key.as_deref());
+            return Err(e);
+        }
+        match self.trade_config.submission_mode {
+            SubmissionMode::AwaitConfirmation => {
+                let mut trail = SubmissionReport { trade_id: next_trade_id(), ..Default::default() };
+                let result =
+                    self.execute_buy(&dex_type, buy_with_tip_params, idempotency_key.as_deref(), &mut trail).await;
+                if let Err(e) = &result {
+                    println!("交易 {} 失败: {}，提交记录: {:?}", trail.trade_id, e, trail.attempts);
+                }
+                result
+            }
+            SubmissionMode::FireAndTrack => {
+                let solana_trade = self.clone();
+                let trade_id = next_trade_id();
+                let pending_id = self.trade_outcomes.track(PendingTrade {
+                    dex_type: dex_type.clone(),
+                    trade_type: TradeType::Buy,
+                    mint,
+                    amount: sol_amount,
+                    idempotency_key: idempotency_key.clone(),
+                    submitted_at: chrono::Utc::now(),
+                });
+                let planned = swqos_tips(
+                    &buy_with_tip_params.swqos_clients,
+                    &buy_with_tip_params.priority_fee,
+                    TradeType::Buy,
+                );
+                let (on_sent, sent) = SentNotifier::new();
+                buy_with_tip_params.on_sent = Some(on_sent);
+                let started = Instant::now();
+                let task = tokio::spawn(async move {
+                    let _mint_lock = solana_trade.mint_locks.lock(&mint).await;
+                    let mut trail = SubmissionReport { trade_id, ..Default::default() };
+                    let result = solana_trade
+                        .execute_buy(&dex_type, buy_with_tip_params, idempotency_key.as_deref(), &mut trail)
+                        .await;
+                    let (submission, error) = match result {
+                        Ok(submission) => (submission, None),
+                        Err(e) => (trail, Some(e.to_string())),
+                    };
+                    solana_trade.trade_outcomes.complete(pending_id, TradeOutcome {
+                        dex_type,
+                        trade_type: TradeType::Buy,
+                        mint,
+                        amount: sol_amount,
+                        idempotency_key,
+                        error: error.clone(),
+                        submission: submission.clone(),
+                    });
+                    error.map_or(Ok(submission), Err)
+                });
+                first_submission(trade_id, planned, started, sent, task).await
+            }
         }
-
-        executor.buy_with_tip(buy_with_tip_params).await
     }
 
     /// Execute a sell order for a specified token
     ///
     /// # Arguments
     ///
-    /// * `dex_type` - The trading protocol to use (PumpFun, PumpSwap, or Bonk)
+    /// * `dex_type` - The trading protocol to use (PumpFun, PumpSwap, or Bonk). PumpFun trades on a
+    ///   completed bonding curve go to the migrated PumpSwap pool unless `auto_route_migrated` is off
     /// * `mint` - The public key of the token mint to sell
     /// * `creator` - Optional creator public key for the token (defaults to Pubkey::default() if None)
     /// * `token_amount` - Amount of tokens to sell (in smallest token units)
     /// * `slippage_basis_points` - Optional slippage tolerance in basis points (e.g., 100 = 1%)
-    /// * `recent_blockhash` - Recent blockhash for transaction validity
-    /// * `custom_buy_tip_fee` - Optional custom tip fee for priority processing (in SOL)
+    /// * `min_sol_out` - Optional absolute minimum SOL to receive (in lamports), overrides the slippage-derived floor
+    /// * `recent_blockhash` - Recent blockhash for transaction validity; if None, the blockhash
+    ///   kept fresh by the background `BlockhashCache` is used
+    /// * `execution_overrides` - Optional compute unit limit, unit price and sell tip (in SOL) for this trade only;
+    ///   an idempotency key rejects resubmissions of the same trade
     /// * `with_tip` - Optional boolean to indicate if the transaction should be sent with tip
     /// * `extension_params` - Optional protocol-specific parameters (uses defaults if None)
     ///
     /// # Returns
     ///
-    /// Returns `Ok(())` if the sell order is successfully executed, or an error if the transaction fails.
+    /// Returns a `SubmissionReport` with the per-SWQOS timings if the sell order is successfully
+    /// executed, or an error if the transaction fails. With `race_submissions` on, it returns as
+    /// soon as one SWQOS succeeds and the others are reported as cancelled.
+    /// Under `SubmissionMode::FireAndTrack` it returns once the signed transaction is handed to the
+    /// first provider, with that signature in the report; confirmation continues in the background
+    /// and the result is then published through `subscribe_trade_outcomes`.
+    /// Under `ExecutionMode::Paper` the sell fills at the live quote against the paper balance.
     ///
     /// # Errors
     ///
     /// This function will return an error if:
+    /// - This instance is the failover standby (see `with_failover`)
+    /// - Execution is disabled for `dex_type` in the trade config
     /// - Invalid protocol parameters are provided
     /// - The transaction fails to execute
     /// - Network or RPC errors occur
@@ -276,7 +785,7 @@ impl SolanaTrade {
     /// let mint = Pubkey::new_unique();
     /// let token_amount = 1_000_000; // Amount of tokens to sell
     /// let slippage = Some(500); // 5% slippage
-    /// let recent_blockhash = Hash::default();
+    /// let recent_blockhash = Some(Hash::default());
     ///
     /// solana_trade.sell(
     ///     DexType::PumpFun,
@@ -284,6 +793,7 @@ impl SolanaTrade {
     ///     None,
     ///     token_amount,
     ///     slippage,
+    ///     None,
     ///     recent_blockhash,
     ///     None,
     ///     false,
@@ -297,76 +807,99 @@ impl SolanaTrade {
         creator: Option<Pubkey>,
         token_amount: u64,
         slippage_basis_points: Option<u64>,
-        recent_blockhash: Hash,
-        custom_buy_tip_fee: Option<f64>,
+        min_sol_out: Option<u64>,
+        recent_blockhash: Option<Hash>,
+        execution_overrides: Option<ExecutionOverrides>,
         with_tip: bool,
         extension_params: Option<Box<dyn ProtocolParams>>,
-    ) -> Result<(), anyhow::Error> {
-        let executor = TradeFactory::create_executor(dex_type.clone());
-        let protocol_params = if let Some(params) = extension_params {
-            params
-        } else {
-            match dex_type {
-                DexType::PumpFun => Box::new(PumpFunParams::default()) as Box<dyn ProtocolParams>,
-                DexType::PumpSwap => Box::new(PumpSwapParams::default()) as Box<dyn ProtocolParams>,
-                DexType::Bonk => Box::new(BonkParams::default()) as Box<dyn ProtocolParams>,
-                DexType::RaydiumCpmm => {
-                    Box::new(RaydiumCpmmParams::default()) as Box<dyn ProtocolParams>
+    ) -> Result<SubmissionReport, anyhow::Error> {
+        self.check_active()?;
+        let (dex_type, creator, extension_params) =
+            self.route_migrated(dex_type, &mint, creator, extension_params).await?;
+        self.check_execution_enabled(&dex_type, &mint)?;
+        if let Some(paper_broker) = &self.paper_broker {
+            let idempotency_key = execution_overrides.and_then(|o| o.idempotency_key);
+            return self
+                .paper_sell(paper_broker, dex_type, mint, token_amount, min_sol_out, idempotency_key)
+                .await;
+        }
+        // Trades on one mint run one at a time; fire-and-track trades wait in their background task
+        let _mint_lock = match self.trade_config.submission_mode {
+            SubmissionMode::AwaitConfirmation => Some(self.mint_locks.lock(&mint).await),
+            SubmissionMode::FireAndTrack => None,
+        };
+        let idempotency_key = execution_overrides.as_ref().and_then(|o| o.idempotency_key.clone());
+        let sell_params = self
+            .make_sell_params(
+                &dex_type,
+                mint,
+                creator,
+                token_amount,
+                slippage_basis_points,
+                min_sol_out,
+                recent_blockhash,
+                execution_overrides,
+                extension_params,
+            )
+            .await?;
+        self.claim_idempotency_key(idempotency_key.as_deref())?;
+        match self.trade_config.submission_mode {
+            SubmissionMode::AwaitConfirmation => {
+                let mut trail = SubmissionReport { trade_id: next_trade_id(), ..Default::default() };
+                let result = self
+                    .execute_sell(&dex_type, sell_params, with_tip, idempotency_key.as_deref(), &mut trail)
+                    .await;
+                if let Err(e) = &result {
+                    println!("交易 {} 失败: {}，提交记录: {:?}", trail.trade_id, e, trail.attempts);
                 }
+                result
+            }
+            SubmissionMode::FireAndTrack => {
+                let solana_trade = self.clone();
+                let trade_id = next_trade_id();
+                let pending_id = self.trade_outcomes.track(PendingTrade {
+                    dex_type: dex_type.clone(),
+                    trade_type: TradeType::Sell,
+                    mint,
+                    amount: token_amount,
+                    idempotency_key: idempotency_key.clone(),
+                    submitted_at: chrono::Utc::now(),
+                });
+                let planned = if with_tip {
+                    let mut priority_fee = sell_params.priority_fee.clone();
+                    self.select_swqos_clients(sell_params.swqos_types.as_deref(), &mut priority_fee)
+                        .map(|swqos_clients| swqos_tips(&swqos_clients, &priority_fee, TradeType::Sell))
+                        .unwrap_or_default()
+                } else {
+                    vec![(SwqosType::Default, 0.0)]
+                };
+                let (on_sent, sent) = SentNotifier::new();
+                let mut sell_params = sell_params;
+                sell_params.on_sent = Some(on_sent);
+                let started = Instant::now();
+                let task = tokio::spawn(async move {
+                    let _mint_lock = solana_trade.mint_locks.lock(&mint).await;
+                    let mut trail = SubmissionReport { trade_id, ..Default::default() };
+                    let result = solana_trade
+                        .execute_sell(&dex_type, sell_params, with_tip, idempotency_key.as_deref(), &mut trail)
+                        .await;
+                    let (submission, error) = match result {
+                        Ok(submission) => (submission, None),
+                        Err(e) => (trail, Some(e.to_string())),
+                    };
+                    solana_trade.trade_outcomes.complete(pending_id, TradeOutcome {
+                        dex_type,
+                        trade_type: TradeType::Sell,
+                        mint,
+                        amount: token_amount,
+                        idempotency_key,
+                        error: error.clone(),
+                        submission: submission.clone(),
+                    });
+                    error.map_or(Ok(submission), Err)
+                });
+                first_submission(trade_id, planned, started, sent, task).await
             }
-        };
-        let sell_params = SellParams {
-            rpc: Some(self.rpc.clone()),
-            payer: self.payer.clone(),
-            mint: mint,
-            creator: creator.unwrap_or(Pubkey::default()),
-            token_amount: Some(token_amount),
-            slippage_basis_points: slippage_basis_points,
-            priority_fee: self.trade_config.priority_fee.clone(),
-            lookup_table_key: self.trade_config.lookup_table_key,
-            recent_blockhash,
-            protocol_params: protocol_params.clone(),
-        };
-        let mut priority_fee = sell_params.priority_fee.clone();
-        if custom_buy_tip_fee.is_some() {
-            priority_fee.buy_tip_fee = custom_buy_tip_fee.unwrap();
-            priority_fee.buy_tip_fees = priority_fee
-                .buy_tip_fees
-                .iter()
-                .map(|_| custom_buy_tip_fee.unwrap())
-                .collect();
-        }
-        let sell_with_tip_params = sell_params.clone().with_tip(self.swqos_clients.clone());
-
-        // Validate protocol params
-        let is_valid_params = match dex_type {
-            DexType::PumpFun => protocol_params
-                .as_any()
-                .downcast_ref::<PumpFunParams>()
-                .is_some(),
-            DexType::PumpSwap => protocol_params
-                .as_any()
-                .downcast_ref::<PumpSwapParams>()
-                .is_some(),
-            DexType::Bonk => protocol_params
-                .as_any()
-                .downcast_ref::<BonkParams>()
-                .is_some(),
-            DexType::RaydiumCpmm => protocol_params
-                .as_any()
-                .downcast_ref::<RaydiumCpmmParams>()
-                .is_some(),
-        };
-
-        if !is_valid_params {
-            return Err(anyhow::anyhow!("Invalid protocol params for Trade"));
-        }
-
-        // Execute sell based on tip preference
-        if with_tip {
-            executor.sell_with_tip(sell_with_tip_params).await
-        } else {
-            executor.sell(sell_params).await
         }
     }
 
@@ -383,18 +916,28 @@ impl SolanaTrade {
     /// * `amount_token` - Total amount of tokens available (in smallest token units)
     /// * `percent` - Percentage of tokens to sell (1-100, where 100 = 100%)
     /// * `slippage_basis_points` - Optional slippage tolerance in basis points (e.g., 100 = 1%)
-    /// * `recent_blockhash` - Recent blockhash for transaction validity
-    /// * `custom_buy_tip_fee` - Optional custom tip fee for priority processing (in SOL)
+    /// * `min_sol_out` - Optional absolute minimum SOL to receive (in lamports), overrides the slippage-derived floor
+    /// * `recent_blockhash` - Recent blockhash for transaction validity; if None, the blockhash
+    ///   kept fresh by the background `BlockhashCache` is used
+    /// * `execution_overrides` - Optional compute unit limit, unit price and sell tip (in SOL) for this trade only;
+    ///   an idempotency key rejects resubmissions of the same trade
     /// * `extension_params` - Optional protocol-specific parameters (uses defaults if None)
     ///
     /// # Returns
     ///
-    /// Returns `Ok(())` if the sell order is successfully executed, or an error if the transaction fails.
+    /// Returns a `SubmissionReport` with the per-SWQOS timings if the sell order is successfully
+    /// executed, or an error if the transaction fails. With `race_submissions` on, it returns as
+    /// soon as one SWQOS succeeds and the others are reported as cancelled.
+    /// Under `SubmissionMode::FireAndTrack` it returns once the signed transaction is handed to the
+    /// first provider, with that signature in the report; confirmation continues in the background
+    /// and the result is then published through `subscribe_trade_outcomes`.
     ///
     /// # Errors
     ///
     /// This function will return an error if:
     /// - `percent` is 0 or greater than 100
+    /// - This instance is the failover standby (see `with_failover`)
+    /// - Execution is disabled for `dex_type` in the trade config
     /// - Invalid protocol parameters are provided
     /// - The transaction fails to execute
     /// - Network or RPC errors occur
@@ -412,7 +955,7 @@ impl SolanaTrade {
     /// let total_tokens = 10_000_000; // Total tokens available
     /// let percent = 50; // Sell 50% of tokens
     /// let slippage = Some(500); // 5% slippage
-    /// let recent_blockhash = Hash::default();
+    /// let recent_blockhash = Some(Hash::default());
     ///
     /// // This will sell 5_000_000 tokens (50% of 10_000_000)
     /// solana_trade.sell_by_percent(
@@ -422,6 +965,7 @@ impl SolanaTrade {
     ///     total_tokens,
     ///     percent,
     ///     slippage,
+    ///     None,
     ///     recent_blockhash,
     ///     None,
     ///     None,
@@ -435,11 +979,12 @@ impl SolanaTrade {
         amount_token: u64,
         percent: u64,
         slippage_basis_points: Option<u64>,
-        recent_blockhash: Hash,
-        custom_buy_tip_fee: Option<f64>,
+        min_sol_out: Option<u64>,
+        recent_blockhash: Option<Hash>,
+        execution_overrides: Option<ExecutionOverrides>,
         with_tip: bool,
         extension_params: Option<Box<dyn ProtocolParams>>,
-    ) -> Result<(), anyhow::Error> {
+    ) -> Result<SubmissionReport, anyhow::Error> {
         if percent == 0 || percent > 100 {
             return Err(anyhow::anyhow!("Percentage must be between 1 and 100"));
         }
@@ -450,11 +995,1734 @@ impl SolanaTrade {
             creator,
             amount,
             slippage_basis_points,
+            min_sol_out,
             recent_blockhash,
-            custom_buy_tip_fee,
+            execution_overrides,
             with_tip,
             extension_params,
         )
         .await
     }
+
+    /// Execute a copy buy produced by `WalletMonitor::copy_buy`
+    ///
+    /// The buy runs on the protocol the target wallet traded on. When the order came from an
+    /// event handled by `WalletMonitor::on_event`, it reuses that pool and its post-trade
+    /// reserves, so no pool lookup is needed. If the buy fails, call
+    /// `WalletMonitor::release_copy_buy`; if it succeeds and an execution timeout is configured,
+    /// call `WalletMonitor::complete_copy_buy`.
+    ///
+    /// Publishes `StrategyEvent::CopyExecuted` or `StrategyEvent::Error` to `event_bus`.
+    pub async fn copy_buy(
+        &self,
+        order: &CopyBuyOrder,
+        slippage_basis_points: Option<u64>,
+        execution_overrides: Option<ExecutionOverrides>,
+    ) -> Result<SubmissionReport, anyhow::Error> {
+        let result = self
+            .buy(
+                order.dex_type.clone(),
+                order.mint,
+                order.creator,
+                order.sol_amount,
+                slippage_basis_points,
+                None,
+                execution_overrides,
+                order.protocol_params(),
+            )
+            .await;
+        match &result {
+            Ok(submission) => self.event_bus.publish(StrategyEvent::CopyExecuted {
+                order: order.clone(),
+                submission: submission.clone(),
+            }),
+            Err(e) => self.event_bus.publish(StrategyEvent::Error {
+                source: "copy_buy".to_string(),
+                message: format!("{}: {}", order.mint, e),
+            }),
+        }
+        result
+    }
+
+    /// Execute a copy sell produced by `CopySellMirror::on_event` or `CopySellMirror::on_swap`
+    ///
+    /// Sells `order.token_amount` with `order.min_sol_out` as the floor. Orders from `on_event`
+    /// carry the creator and pool of the target's sell; for orders from `on_swap` on PumpFun,
+    /// the bonding curve and creator are fetched first. If the sell fails, the amount is not
+    /// returned to the mirror; call `CopySellMirror::record_buy` to restore it. When the order
+    /// sells the whole copied position and the mirror was given `with_exposure`, a successful
+    /// sell closes the mint's copy exposure.
+    pub async fn copy_sell(
+        &self,
+        order: &CopySellOrder,
+        execution_overrides: Option<ExecutionOverrides>,
+        with_tip: bool,
+    ) -> Result<SubmissionReport, anyhow::Error> {
+        let (creator, extension_params) = self
+            .resolve_pumpfun_params(&order.dex_type, &order.mint, order.creator, order.protocol_params())
+            .await?;
+        let submission = self
+            .sell(
+                order.dex_type.clone(),
+                order.mint,
+                creator,
+                order.token_amount,
+                None,
+                Some(order.min_sol_out),
+                None,
+                execution_overrides,
+                with_tip,
+                extension_params,
+            )
+            .await?;
+        order.close_exposure();
+        Ok(submission)
+    }
+
+    /// Execute a snipe produced by `SniperStrategy`
+    ///
+    /// The protocol params were built from the launch event, so the buy needs no RPC lookups
+    /// beyond what the trade config itself requires and can usually land in the launch slot.
+    pub async fn snipe(
+        &self,
+        order: &SnipeOrder,
+        execution_overrides: Option<ExecutionOverrides>,
+    ) -> Result<SubmissionReport, anyhow::Error> {
+        self.buy(
+            order.launch.dex_type.clone(),
+            order.launch.mint,
+            Some(order.launch.creator),
+            order.sol_amount,
+            order.slippage_basis_points,
+            None,
+            execution_overrides,
+            Some(order.launch.protocol_params()),
+        )
+        .await
+    }
+
+    /// Execute a buy produced by `EmaCrossStrategy`
+    ///
+    /// Goes through `buy`, so spend limits, idempotency and the other trade-config checks
+    /// apply as for any other buy. PumpFun buys fetch the bonding curve and its creator first;
+    /// other protocols use the default protocol params for `order.dex_type`, as `buy` does
+    /// without extension params.
+    pub async fn momentum_buy(
+        &self,
+        order: &MomentumOrder,
+        execution_overrides: Option<ExecutionOverrides>,
+    ) -> Result<SubmissionReport, anyhow::Error> {
+        let (creator, extension_params) =
+            self.resolve_pumpfun_params(&order.dex_type, &order.mint, None, None).await?;
+        self.buy(
+            order.dex_type.clone(),
+            order.mint,
+            creator,
+            order.sol_amount,
+            order.slippage_basis_points,
+            None,
+            execution_overrides,
+            extension_params,
+        )
+        .await
+    }
+
+    /// Build a buy transaction without sending it
+    ///
+    /// Returns the fully constructed `VersionedTransaction` so it can be inspected, bundled,
+    /// or sent through custom infrastructure. Takes the same arguments as `buy`, plus:
+    ///
+    /// * `tip_account` - Optional tip account; when set a transfer of the buy tip fee is appended
+    /// * `sign` - Whether to sign with the payer; when false the signatures are left empty
+    ///
+    /// Applies the same routing and guards as `buy`: PumpFun trades on a completed bonding curve
+    /// are built against the migrated PumpSwap pool unless `auto_route_migrated` is off, and it
+    /// fails on a failover standby or a DEX disabled by `disabled_dex_types` (publishing
+    /// `StrategyEvent::ExecutionSkipped`), and when `sol_amount` would exceed the spend limit.
+    /// The spend limit is only checked, not reserved, since the SDK does not send the transaction.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn build_buy_transaction(
+        &self,
+        dex_type: DexType,
+        mint: Pubkey,
+        creator: Option<Pubkey>,
+        sol_amount: u64,
+        slippage_basis_points: Option<u64>,
+        recent_blockhash: Option<Hash>,
+        execution_overrides: Option<ExecutionOverrides>,
+        tip_account: Option<Pubkey>,
+        sign: bool,
+        extension_params: Option<Box<dyn ProtocolParams>>,
+    ) -> Result<VersionedTransaction, anyhow::Error> {
+        self.check_active()?;
+        let (dex_type, creator, extension_params) =
+            self.route_migrated(dex_type, &mint, creator, extension_params).await?;
+        self.check_execution_enabled(&dex_type, &mint)?;
+        self.spend_tracker.check(sol_amount)?;
+        let executor = TradeFactory::create_executor(dex_type.clone());
+        let buy_params = self
+            .make_buy_params(
+                &dex_type,
+                mint,
+                creator,
+                sol_amount,
+                slippage_basis_points,
+                recent_blockhash,
+                execution_overrides,
+                extension_params,
+            )
+            .await?;
+        let transaction = executor.build_buy_transaction(buy_params, tip_account).await?;
+        Ok(if sign { transaction } else { clear_signatures(transaction) })
+    }
+
+    /// Build a sell transaction without sending it
+    ///
+    /// Returns the fully constructed `VersionedTransaction` so it can be inspected, bundled,
+    /// or sent through custom infrastructure. Takes the same arguments as `sell`, plus:
+    ///
+    /// * `tip_account` - Optional tip account; when set a transfer of the sell tip fee is appended
+    /// * `sign` - Whether to sign with the payer; when false the signatures are left empty
+    ///
+    /// Applies the same routing and guards as `sell`: PumpFun trades on a completed bonding curve
+    /// are built against the migrated PumpSwap pool unless `auto_route_migrated` is off, and it
+    /// fails on a failover standby or a DEX disabled by `disabled_dex_types` (publishing
+    /// `StrategyEvent::ExecutionSkipped`).
+    #[allow(clippy::too_many_arguments)]
+    pub async fn build_sell_transaction(
+        &self,
+        dex_type: DexType,
+        mint: Pubkey,
+        creator: Option<Pubkey>,
+        token_amount: u64,
+        slippage_basis_points: Option<u64>,
+        min_sol_out: Option<u64>,
+        recent_blockhash: Option<Hash>,
+        execution_overrides: Option<ExecutionOverrides>,
+        tip_account: Option<Pubkey>,
+        sign: bool,
+        extension_params: Option<Box<dyn ProtocolParams>>,
+    ) -> Result<VersionedTransaction, anyhow::Error> {
+        self.check_active()?;
+        let (dex_type, creator, extension_params) =
+            self.route_migrated(dex_type, &mint, creator, extension_params).await?;
+        self.check_execution_enabled(&dex_type, &mint)?;
+        let executor = TradeFactory::create_executor(dex_type.clone());
+        let sell_params = self
+            .make_sell_params(
+                &dex_type,
+                mint,
+                creator,
+                token_amount,
+                slippage_basis_points,
+                min_sol_out,
+                recent_blockhash,
+                execution_overrides,
+                extension_params,
+            )
+            .await?;
+        let transaction = executor.build_sell_transaction(sell_params, tip_account).await?;
+        Ok(if sign { transaction } else { clear_signatures(transaction) })
+    }
+
+    /// Create a PumpFun token and buy it from the payer in the same transaction
+    ///
+    /// Builds the create instruction with the token metadata, the payer's token account and an
+    /// initial buy of `sol_amount` quoted from the fresh bonding curve, so nobody can buy ahead
+    /// of the creator. The payer becomes the token creator and the spend cap applies to the buy.
+    ///
+    /// # Arguments
+    ///
+    /// * `create_params` - Token name, symbol and metadata URI
+    /// * `mint` - Keypair of the new mint, e.g. a vanity address; a new one is generated if None
+    /// * `sol_amount` - SOL to spend on the initial buy (in lamports), 0 to only create the token
+    /// * `slippage_basis_points` - Optional slippage tolerance for the initial buy
+    /// * `recent_blockhash` - Recent blockhash; if None, the cached blockhash is used
+    /// * `bundle` - Send as a Jito bundle carrying the buy tip (requires a `SwqosConfig::Jito`
+    ///   entry); otherwise the transaction is sent and confirmed through the RPC
+    ///
+    /// # Returns
+    ///
+    /// Returns the mint keypair and the transaction signature. For a bundle the signature is
+    /// returned once the bundle is accepted; poll it to confirm the launch landed.
+    pub async fn create_and_buy(
+        &self,
+        create_params: PumpFunCreateParams,
+        mint: Option<Keypair>,
+        sol_amount: u64,
+        slippage_basis_points: Option<u64>,
+        recent_blockhash: Option<Hash>,
+        bundle: bool,
+    ) -> Result<(Arc<Keypair>, Signature), anyhow::Error> {
+        let mint = Arc::new(mint.unwrap_or_else(Keypair::new));
+        let instructions = build_create_and_buy_instructions(
+            &self.payer,
+            &mint.pubkey(),
+            &create_params,
+            sol_amount,
+            slippage_basis_points,
+        )?;
+        let signature = self
+            .send_launch(&DexType::PumpFun, &mint, instructions, sol_amount, recent_blockhash, bundle)
+            .await?;
+        Ok((mint, signature))
+    }
+
+    /// Create a Bonk (letsbonk.fun) token and buy it from the payer in the same transaction
+    ///
+    /// The Bonk counterpart of [`SolanaTrade::create_and_buy`]. The pool uses the letsbonk.fun
+    /// default curve (1B supply, migrating to Raydium CPMM after raising 85 SOL) and the initial
+    /// buy is quoted from the fresh pool. Upload the metadata JSON first and pass its URI in
+    /// `create_params`. The arguments and return value match `create_and_buy`.
+    pub async fn create_and_buy_bonk(
+        &self,
+        create_params: BonkCreateParams,
+        mint: Option<Keypair>,
+        sol_amount: u64,
+        slippage_basis_points: Option<u64>,
+        recent_blockhash: Option<Hash>,
+        bundle: bool,
+    ) -> Result<(Arc<Keypair>, Signature), anyhow::Error> {
+        let mint = Arc::new(mint.unwrap_or_else(Keypair::new));
+        let instructions = build_bonk_create_and_buy_instructions(
+            &self.payer.pubkey(),
+            &mint.pubkey(),
+            &create_params,
+            sol_amount,
+            slippage_basis_points,
+        )?;
+        let signature = self
+            .send_launch(&DexType::Bonk, &mint, instructions, sol_amount, recent_blockhash, bundle)
+            .await?;
+        Ok((mint, signature))
+    }
+
+    /// Sign a token launch with the mint keypair and send it through the RPC or as a Jito bundle
+    async fn send_launch(
+        &self,
+        dex_type: &DexType,
+        mint: &Arc<Keypair>,
+        launch_instructions: Vec<Instruction>,
+        sol_amount: u64,
+        recent_blockhash: Option<Hash>,
+        bundle: bool,
+    ) -> Result<Signature, anyhow::Error> {
+        self.check_active()?;
+        self.check_execution_enabled(dex_type, &mint.pubkey())?;
+        self.ensure_live_execution()?;
+        let recent_blockhash = match recent_blockhash {
+            Some(recent_blockhash) => recent_blockhash,
+            None => BlockhashCache::get_instance().get_or_fetch(&self.rpc).await?,
+        };
+        let priority_fee = self.resolve_priority_fee(dex_type, &mint.pubkey(), None).await;
+
+        let mut instructions = if bundle {
+            vec![
+                ComputeBudgetInstruction::set_compute_unit_price(priority_fee.unit_price),
+                ComputeBudgetInstruction::set_compute_unit_limit(priority_fee.unit_limit),
+            ]
+        } else {
+            vec![
+                ComputeBudgetInstruction::set_compute_unit_price(priority_fee.rpc_unit_price),
+                ComputeBudgetInstruction::set_compute_unit_limit(priority_fee.rpc_unit_limit),
+            ]
+        };
+        instructions.extend(launch_instructions);
+        if bundle {
+            let tip_payer = self.fee_payer.as_ref().unwrap_or(&self.payer).pubkey();
+            instructions.push(transfer(
+                &tip_payer,
+                &swqos::jito::random_tip_account()?,
+                sol_str_to_lamports(priority_fee.buy_tip_fee.to_string().as_str()).unwrap_or(0),
+            ));
+        }
+
+        let transaction = build_versioned_transaction_with_signers(
+            self.payer.clone(),
+            self.fee_payer.clone(),
+            std::slice::from_ref(mint),
+            instructions,
+            get_address_lookup_table_accounts(self.trade_config.lookup_table_key).await,
+            recent_blockhash,
+        )
+        .await?;
+        let signature = transaction.signatures[0];
+
+        self.spend_tracker.reserve(sol_amount)?;
+        let result = if bundle {
+            self.send_bundle(&[transaction]).await.map(|_| ())
+        } else {
+            self.rpc.send_and_confirm_transaction(&transaction).await.map(|_| ()).map_err(Into::into)
+        };
+        if let Err(e) = result {
+            self.spend_tracker.release(sol_amount);
+            return Err(e);
+        }
+        Ok(signature)
+    }
+
+    /// Create a Raydium CPMM pool pairing `mint` with WSOL and seed its initial liquidity
+    ///
+    /// Intended for tokens migrated by hand, e.g. from a PumpFun launch. `token_amount` tokens
+    /// come from the payer's token account and `sol_amount` is wrapped into WSOL; their ratio
+    /// sets the opening price. The pool uses the default AMM config, the payer receives the LP
+    /// tokens, and the spend cap applies to `sol_amount`. Raydium charges a pool creation fee
+    /// on top.
+    ///
+    /// # Arguments
+    ///
+    /// * `mint` - Token to pair with WSOL; SPL Token and Token-2022 mints are supported
+    /// * `token_amount` - Initial token liquidity (in smallest token units)
+    /// * `sol_amount` - Initial SOL liquidity (in lamports)
+    /// * `open_time` - Unix timestamp at which swaps open; None opens the pool immediately
+    /// * `recent_blockhash` - Recent blockhash; if None, the cached blockhash is used
+    ///
+    /// # Returns
+    ///
+    /// Returns every derived address of the new pool and the confirmed transaction signature
+    pub async fn create_raydium_cpmm_pool(
+        &self,
+        mint: Pubkey,
+        token_amount: u64,
+        sol_amount: u64,
+        open_time: Option<u64>,
+        recent_blockhash: Option<Hash>,
+    ) -> Result<(CpmmPoolAddresses, Signature), anyhow::Error> {
+        self.check_active()?;
+        self.check_execution_enabled(&DexType::RaydiumCpmm, &mint)?;
+        self.ensure_live_execution()?;
+        let recent_blockhash = match recent_blockhash {
+            Some(recent_blockhash) => recent_blockhash,
+            None => BlockhashCache::get_instance().get_or_fetch(&self.rpc).await?,
+        };
+        let mint_info = get_mint_token_info(&self.rpc, &mint).await?;
+        let priority_fee = self.resolve_priority_fee(&DexType::RaydiumCpmm, &mint, None).await;
+
+        let mut instructions = vec![
+            ComputeBudgetInstruction::set_compute_unit_price(priority_fee.rpc_unit_price),
+            ComputeBudgetInstruction::set_compute_unit_limit(priority_fee.rpc_unit_limit),
+        ];
+        let (pool_instructions, addresses) = build_create_pool_instructions(
+            &self.payer.pubkey(),
+            &mint,
+            &mint_info.token_program,
+            token_amount,
+            sol_amount,
+            open_time.unwrap_or(0),
+        )?;
+        instructions.extend(pool_instructions);
+
+        let transaction = build_versioned_transaction_with_signers(
+            self.payer.clone(),
+            self.fee_payer.clone(),
+            &[],
+            instructions,
+            get_address_lookup_table_accounts(self.trade_config.lookup_table_key).await,
+            recent_blockhash,
+        )
+        .await?;
+
+        self.spend_tracker.reserve(sol_amount)?;
+        match self.rpc.send_and_confirm_transaction(&transaction).await {
+            Ok(signature) => Ok((addresses, signature)),
+            Err(e) => {
+                self.spend_tracker.release(sol_amount);
+                Err(e.into())
+            }
+        }
+    }
+
+    /// Wrap `amount` lamports into the payer's WSOL account, creating it if needed
+    ///
+    /// Pairs with `WsolPolicy::UseExistingBalance` to wrap once and trade from the WSOL
+    /// balance, or tops up an account kept by `WsolPolicy::KeepWrapped`.
+    pub async fn wrap_sol(
+        &self,
+        amount: u64,
+        recent_blockhash: Option<Hash>,
+    ) -> Result<Signature, anyhow::Error> {
+        let instructions = wsol::wrap(&self.payer.pubkey(), amount);
+        self.send_instructions(instructions, recent_blockhash).await
+    }
+
+    /// Close the payer's WSOL account, returning its whole balance and rent as SOL
+    pub async fn unwrap_all_sol(
+        &self,
+        recent_blockhash: Option<Hash>,
+    ) -> Result<Signature, anyhow::Error> {
+        let instructions = vec![wsol::unwrap_all(&self.payer.pubkey())];
+        self.send_instructions(instructions, recent_blockhash).await
+    }
+
+    /// Sync the payer's WSOL balance after lamports were sent to the account directly
+    pub async fn sync_wsol(&self, recent_blockhash: Option<Hash>) -> Result<Signature, anyhow::Error> {
+        let instructions = vec![wsol::sync_native(&self.payer.pubkey())];
+        self.send_instructions(instructions, recent_blockhash).await
+    }
+
+    /// Send instructions signed by the payer through the RPC; rejected under `ExecutionMode::Paper`
+    /// and while this instance is the failover standby
+    async fn send_instructions(
+        &self,
+        payer_instructions: Vec<Instruction>,
+        recent_blockhash: Option<Hash>,
+    ) -> Result<Signature, anyhow::Error> {
+        self.check_active()?;
+        self.ensure_live_execution()?;
+        let recent_blockhash = match recent_blockhash {
+            Some(recent_blockhash) => recent_blockhash,
+            None => BlockhashCache::get_instance().get_or_fetch(&self.rpc).await?,
+        };
+        let mut instructions = vec![
+            ComputeBudgetInstruction::set_compute_unit_price(self.priority_fee.rpc_unit_price),
+            ComputeBudgetInstruction::set_compute_unit_limit(self.priority_fee.rpc_unit_limit),
+        ];
+        instructions.extend(payer_instructions);
+        let transaction = build_versioned_transaction_with_signers(
+            self.payer.clone(),
+            self.fee_payer.clone(),
+            &[],
+            instructions,
+            vec![],
+            recent_blockhash,
+        )
+        .await?;
+        Ok(self.rpc.send_and_confirm_transaction(&transaction).await?)
+    }
+
+    /// Clear out the dust positions in `price_monitor` and the payer's empty token accounts
+    ///
+    /// A position is dust when its value at the last price is below `price_monitor.min_exit_lamports()`.
+    /// With `config.sell` each dust position is sold and its account is closed by a later sweep
+    /// once empty; otherwise the remaining tokens are burned and the account closed, which only
+    /// reclaims the rent. PumpFun dust sells use the creator and bonding curve read from the
+    /// curve account. Accounts are cleaned in batches of `max_accounts_per_transaction`,
+    /// and swept positions stop being monitored. With `close_empty_accounts`, empty token accounts
+    /// for mints that are not monitored are closed as well. WSOL is never touched.
+    /// Under `ExecutionMode::Paper` dust sells are simulated, while burning and closing accounts
+    /// is not and those batches are reported as failed.
+    pub async fn sweep_dust(
+        &self,
+        price_monitor: &PriceMonitor,
+        config: &DustSweepConfig,
+    ) -> Result<DustSweepReport, anyhow::Error> {
+        self.check_active()?;
+        let owner = self.payer.pubkey();
+        let accounts = monitor::dust_sweeper::token_accounts(&self.rpc, &owner).await?;
+        let tracked = price_monitor.positions().iter().map(|position| position.mint).collect();
+        let plan = DustSweepPlan::new(&price_monitor.dust_positions(), &accounts, &tracked, config);
+        let mut report = DustSweepReport::default();
+
+        for (position, amount) in plan.sell {
+            let dex_type = match position.dex_type {
+                Some(dex_type) => dex_type,
+                None => match self.resolve_dex(&position.mint).await {
+                    Ok(dex_type) => dex_type,
+                    Err(e) => {
+                        report.failed.push((position.mint, e.to_string()));
+                        continue;
+                    }
+                },
+            };
+            let sell = async {
+                let (creator, extension_params) =
+                    self.resolve_pumpfun_params(&dex_type, &position.mint, None, None).await?;
+                self.sell(
+                    dex_type.clone(),
+                    position.mint,
+                    creator,
+                    amount,
+                    config.slippage_basis_points,
+                    None,
+                    None,
+                    None,
+                    false,
+                    extension_params,
+                )
+                .await
+            }
+            .await;
+            match sell {
+                Ok(_) => {
+                    price_monitor.close_position(&position.mint);
+                    report.sold.push(position.mint);
+                }
+                Err(e) => report.failed.push((position.mint, e.to_string())),
+            }
+        }
+
+        for batch in plan.close.chunks(config.max_accounts_per_transaction.max(1)) {
+            let mut instructions = vec![];
+            for account in batch {
+                instructions.extend(account.close_instructions(&owner)?);
+            }
+            match self.send_instructions(instructions, None).await {
+                Ok(signature) => {
+                    report.signatures.push(signature);
+                    for account in batch {
+                        if price_monitor.close_position(&account.mint).is_some() {
+                            report.burned.push(account.mint);
+                        }
+                        report.closed_accounts.push(account.address);
+                    }
+                }
+                Err(e) => {
+                    report.failed.extend(batch.iter().map(|account| (account.mint, e.to_string())))
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    /// Run `sweep_dust` every `interval_ms` in the background; failures are only printed
+    pub fn start_dust_sweeper(
+        self: &Arc<Self>,
+        price_monitor: Arc<PriceMonitor>,
+        config: DustSweepConfig,
+        interval_ms: u64,
+    ) -> tokio::task::JoinHandle<()> {
+        let client = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_millis(interval_ms.max(1_000)));
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                match client.sweep_dust(&price_monitor, &config).await {
+                    Ok(report) => {
+                        for (mint, error) in &report.failed {
+                            println!("警告: 清理粉尘 {} 失败: {}", mint, error);
+                        }
+                    }
+                    Err(e) => println!("警告: 粉尘清理失败: {}", e),
+                }
+            }
+        })
+    }
+
+    /// Submit several signed transactions as a single Jito bundle
+    ///
+    /// The transactions execute in order and land atomically. Only one of them needs to
+    /// carry the tip: build it with `tip_account: Some(swqos::jito::random_tip_account()?)`
+    /// and the others with `None`. Requires a `SwqosConfig::Jito` entry in the trade config.
+    ///
+    /// # Returns
+    ///
+    /// Returns the bundle id, which can be passed to `wait_for_bundle`
+    pub async fn send_bundle(
+        &self,
+        transactions: &[VersionedTransaction],
+    ) -> Result<String, anyhow::Error> {
+        self.ensure_live_execution()?;
+        self.jito_bundle_client()?.send_bundle(transactions).await
+    }
+
+    /// Check whether `mint` trades at diverging prices on PumpSwap and Raydium CPMM
+    ///
+    /// Compares the spot prices of both WSOL pools and returns a signal when the spread exceeds
+    /// both venues' fees by at least `config.min_profit_basis_points`. Returns an error when the
+    /// mint has no pool on either venue.
+    pub async fn check_arbitrage(
+        &self,
+        mint: &Pubkey,
+        config: ArbitrageConfig,
+    ) -> Result<Option<ArbitrageSignal>, anyhow::Error> {
+        ArbitrageDetector::new(config).check(&self.rpc, mint).await
+    }
+
+    /// Execute both legs of an arbitrage signal as a single Jito bundle
+    ///
+    /// Buys with `sol_amount` on the cheaper venue, then sells the tokens the buy venue's quote
+    /// returns for `sol_amount` (after its fee, price impact and any transfer fee), less
+    /// `slippage_basis_points`, on the other venue with `min_sol_out` set to `sol_amount`, so the
+    /// bundle only lands when the round trip does not lose SOL (before tip and network fees).
+    /// The sell transaction carries the bundle tip. Both venues must have execution enabled, and
+    /// `sol_amount` counts against the spend limit like a regular buy.
+    ///
+    /// # Returns
+    ///
+    /// Returns the bundle id, which can be passed to `wait_for_bundle`
+    pub async fn execute_arbitrage(
+        &self,
+        signal: &ArbitrageSignal,
+        sol_amount: u64,
+        slippage_basis_points: u64,
+        recent_blockhash: Option<Hash>,
+    ) -> Result<String, anyhow::Error> {
+        self.check_active()?;
+        for dex_type in [&signal.buy_venue.dex_type, &signal.sell_venue.dex_type] {
+            self.check_execution_enabled(dex_type, &signal.mint)?;
+        }
+        self.ensure_live_execution()?;
+        let expected_tokens = signal.buy_venue.buy_amount_out(sol_amount);
+        let token_amount = (expected_tokens as u128 * (10_000 - slippage_basis_points.min(10_000)) as u128
+            / 10_000) as u64;
+        let buy = self
+            .build_buy_transaction(
+                signal.buy_venue.dex_type.clone(),
+                signal.mint,
+                None,
+                sol_amount,
+                Some(slippage_basis_points),
+                recent_blockhash,
+                None,
+                None,
+                true,
+                None,
+            )
+            .await?;
+        let sell = self
+            .build_sell_transaction(
+                signal.sell_venue.dex_type.clone(),
+                signal.mint,
+                None,
+                token_amount,
+                Some(slippage_basis_points),
+                Some(sol_amount),
+                recent_blockhash,
+                None,
+                Some(swqos::jito::random_tip_account()?),
+                true,
+                None,
+            )
+            .await?;
+        self.spend_tracker.reserve(sol_amount)?;
+        let result = self.send_bundle(&[buy, sell]).await;
+        if result.is_err() {
+            self.spend_tracker.release(sol_amount);
+        }
+        result
+    }
+
+    /// Poll the status of a Jito bundle until it lands, fails, or `timeout` elapses
+    pub async fn wait_for_bundle(
+        &self,
+        bundle_id: &str,
+        timeout: Duration,
+    ) -> Result<JitoBundleStatus, anyhow::Error> {
+        self.jito_bundle_client()?.poll_bundle_status(bundle_id, timeout).await
+    }
+
+    /// Buy `mint` on the venue that returns the most tokens for `sol_amount`
+    ///
+    /// Quotes every venue in `config.venues` where the mint has liquidity, including fees and
+    /// price impact, then buys through the best one. With `config.max_splits` above 1 the amount
+    /// may be split across venues when that returns more tokens; each leg is a separate
+    /// transaction, so a split route can land partially.
+    ///
+    /// # Returns
+    ///
+    /// Returns the executed route. Legs before a failed leg have already been submitted.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let route = solana_trade.route_buy(mint, sol_amount, Some(500), None, RouterConfig::default()).await?;
+    /// println!("bought on {}", route.legs[0].venue.dex_type);
+    /// ```
+    pub async fn route_buy(
+        &self,
+        mint: Pubkey,
+        sol_amount: u64,
+        slippage_basis_points: Option<u64>,
+        recent_blockhash: Option<Hash>,
+        config: RouterConfig,
+    ) -> Result<Route, anyhow::Error> {
+        let route = Router::new(config).route_buy(&self.read_rpc, &mint, sol_amount).await?;
+        for leg in &route.legs {
+            self.buy(
+                leg.venue.dex_type.clone(),
+                mint,
+                leg.venue.creator(),
+                leg.amount_in,
+                slippage_basis_points,
+                recent_blockhash,
+                None,
+                Some(leg.venue.protocol_params()),
+            )
+            .await?;
+        }
+        Ok(route)
+    }
+
+    /// Sell `token_amount` of `mint` on the venue that returns the most SOL
+    ///
+    /// The counterpart of [`SolanaTrade::route_buy`]: quotes every venue in `config.venues`,
+    /// sells through the best one and optionally splits across venues.
+    pub async fn route_sell(
+        &self,
+        mint: Pubkey,
+        token_amount: u64,
+        slippage_basis_points: Option<u64>,
+        recent_blockhash: Option<Hash>,
+        with_tip: bool,
+        config: RouterConfig,
+    ) -> Result<Route, anyhow::Error> {
+        let route = Router::new(config).route_sell(&self.read_rpc, &mint, token_amount).await?;
+        for leg in &route.legs {
+            self.sell(
+                leg.venue.dex_type.clone(),
+                mint,
+                leg.venue.creator(),
+                leg.amount_in,
+                slippage_basis_points,
+                None,
+                recent_blockhash,
+                None,
+                with_tip,
+                Some(leg.venue.protocol_params()),
+            )
+            .await?;
+        }
+        Ok(route)
+    }
+
+    /// Buy `sol_amount` of `mint` in slices spread over time (TWAP)
+    ///
+    /// The amount is split into `config.slices` equal buys spread across `config.duration_ms` with
+    /// random jitter. Each slice is a regular [`SolanaTrade::buy`] with
+    /// `config.slice_slippage_basis_points`, so spend caps and execution toggles apply per slice.
+    /// Failed slices are recorded in the outcome; with `config.stop_on_failure` the remaining
+    /// slices are skipped. PumpFun slices carry the token creator, resolved once before the first
+    /// slice, and a bonding curve fetched just before each slice. While it runs the order is listed
+    /// by `list_pending_orders`; `cancel_order` skips the slices that have not started yet.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let config = TwapConfig { slices: 10, duration_ms: 120_000, ..Default::default() };
+    /// let outcomes = solana_trade.twap_buy(DexType::RaydiumCpmm, mint, 20 * LAMPORTS_PER_SOL, config).await?;
+    /// ```
+    pub async fn twap_buy(
+        &self,
+        dex_type: DexType,
+        mint: Pubkey,
+        sol_amount: u64,
+        config: TwapConfig,
+    ) -> Result<Vec<TwapSliceOutcome>, anyhow::Error> {
+        let slices = plan_twap(sol_amount, &config)?;
+        // 创建者在切片前解析一次；bonding curve 储备随每笔切片变化，发送前重新获取
+        let (creator, _) = self.resolve_pumpfun_params(&dex_type, &mint, None, None).await?;
+        let (order_id, mut cancelled) = self.twap_orders.start(dex_type.clone(), mint, TradeType::Buy, &slices);
+        let started = tokio::time::Instant::now();
+        let mut outcomes = vec![];
+        for slice in slices {
+            tokio::select! {
+                biased;
+                _ = &mut cancelled => break,
+                _ = tokio::time::sleep_until(started + Duration::from_millis(slice.delay_ms)) => {}
+            }
+            self.twap_orders.record_slice(order_id, &slice);
+            let result = async {
+                let (creator, extension_params) =
+                    self.resolve_pumpfun_params(&dex_type, &mint, creator, None).await?;
+                self.buy(
+                    dex_type.clone(),
+                    mint,
+                    creator,
+                    slice.amount,
+                    config.slice_slippage_basis_points,
+                    None,
+                    None,
+                    extension_params,
+                )
+                .await
+            }
+            .await;
+            if record_twap_slice(&mut outcomes, slice, result) && config.stop_on_failure {
+                break;
+            }
+        }
+        self.twap_orders.finish(order_id);
+        Ok(outcomes)
+    }
+
+    /// Sell `token_amount` of `mint` in slices spread over time (TWAP)
+    ///
+    /// The sell counterpart of [`SolanaTrade::twap_buy`], meant for exiting large positions
+    /// without draining a thin pool in one transaction. PumpFun slices resolve the creator and
+    /// bonding curve the same way.
+    pub async fn twap_sell(
+        &self,
+        dex_type: DexType,
+        mint: Pubkey,
+        token_amount: u64,
+        config: TwapConfig,
+        with_tip: bool,
+    ) -> Result<Vec<TwapSliceOutcome>, anyhow::Error> {
+        let slices = plan_twap(token_amount, &config)?;
+        // 创建者在切片前解析一次；bonding curve 储备随每笔切片变化，发送前重新获取
+        let (creator, _) = self.resolve_pumpfun_params(&dex_type, &mint, None, None).await?;
+        let (order_id, mut cancelled) = self.twap_orders.start(dex_type.clone(), mint, TradeType::Sell, &slices);
+        let started = tokio::time::Instant::now();
+        let mut outcomes = vec![];
+        for slice in slices {
+            tokio::select! {
+                biased;
+                _ = &mut cancelled => break,
+                _ = tokio::time::sleep_until(started + Duration::from_millis(slice.delay_ms)) => {}
+            }
+            self.twap_orders.record_slice(order_id, &slice);
+            let result = async {
+                let (creator, extension_params) =
+                    self.resolve_pumpfun_params(&dex_type, &mint, creator, None).await?;
+                self.sell(
+                    dex_type.clone(),
+                    mint,
+                    creator,
+                    slice.amount,
+                    config.slice_slippage_basis_points,
+                    None,
+                    None,
+                    None,
+                    with_tip,
+                    extension_params,
+                )
+                .await
+            }
+            .await;
+            if record_twap_slice(&mut outcomes, slice, result) && config.stop_on_failure {
+                break;
+            }
+        }
+        self.twap_orders.finish(order_id);
+        Ok(outcomes)
+    }
+
+    fn jito_bundle_client(&self) -> Result<JitoClient, anyhow::Error> {
+        self.trade_config
+            .swqos_configs
+            .iter()
+            .find_map(|swqos_config| match swqos_config {
+                SwqosConfig::Jito(auth_token, region) => Some(JitoClient::new(
+                    self.trade_config.rpc_url.clone(),
+                    SwqosConfig::get_endpoint(SwqosType::Jito, region.clone()),
+                    auth_token.clone(),
+                )),
+                _ => None,
+            })
+            .ok_or_else(|| anyhow::anyhow!("Bundles require a Jito SWQOS config"))
+    }
+
+    /// Healthy SWQOS clients for a trade, limited to `swqos_types` when given
+    ///
+    /// `buy_tip_fees` is filtered to stay aligned with the returned clients.
+    fn select_swqos_clients(
+        &self,
+        swqos_types: Option<&[SwqosType]>,
+        priority_fee: &mut PriorityFee,
+    ) -> Result<Vec<Arc<SwqosClient>>, anyhow::Error> {
+        let swqos_clients = self.swqos_health.select(&self.swqos_clients, priority_fee);
+        match swqos_types {
+            Some(swqos_types) => select_swqos_types(swqos_clients, swqos_types, priority_fee),
+            None => Ok(swqos_clients),
+        }
+    }
+
+    /// Attach the selected SWQOS clients to a sell
+    fn sell_with_tip_params(&self, mut sell_params: SellParams) -> Result<SellWithTipParams, anyhow::Error> {
+        let swqos_clients =
+            self.select_swqos_clients(sell_params.swqos_types.as_deref(), &mut sell_params.priority_fee)?;
+        Ok(sell_params.with_tip(swqos_clients, self.trade_config.race_submissions, self.rpc_fallback_client()))
+    }
+
+    /// RPC channel for tip-less resubmission when every SWQOS fails, if enabled
+    fn rpc_fallback_client(&self) -> Option<Arc<SolRpcClient>> {
+        self.trade_config
+            .rpc_fallback
+            .then(|| Arc::new(SolRpcClient::new(self.rpc.clone())))
+    }
+
+    /// Submit a buy, retrying with wider slippage when configured
+    ///
+    /// Every submission is recorded in `trail.attempts`, which is kept when the buy fails.
+    /// Releases the spend reservation and the idempotency key if the buy fails.
+    async fn execute_buy(
+        &self,
+        dex_type: &DexType,
+        mut buy_with_tip_params: BuyWithTipParams,
+        idempotency_key: Option<&str>,
+        trail: &mut SubmissionReport,
+    ) -> Result<SubmissionReport, anyhow::Error> {
+        let executor = TradeFactory::create_executor(dex_type.clone());
+        let sol_amount = buy_with_tip_params.sol_amount;
+        let mint = buy_with_tip_params.mint;
+        let expected_tokens = self.slippage_quote(dex_type, &mint, sol_amount).await;
+        let mut attempt = 0;
+        let result = loop {
+            let result = executor.buy_with_tip(buy_with_tip_params.clone()).await;
+            trail.attempts.push(SubmissionAttempt::new(
+                get_transaction_blockhash(buy_with_tip_params.recent_blockhash),
+                swqos_tips(&buy_with_tip_params.swqos_clients, &buy_with_tip_params.priority_fee, TradeType::Buy),
+                buy_with_tip_params.slippage_basis_points,
+                &result,
+            ));
+            let Err(e) = &result else { break result };
+            let slippage = buy_with_tip_params.slippage_basis_points;
+            let Some(slippage) = self.next_slippage_after(dex_type, slippage, attempt, e) else {
+                break result;
+            };
+            buy_with_tip_params.slippage_basis_points = Some(slippage);
+            attempt += 1;
+        };
+        let result = match result {
+            Ok(report) => {
+                let priority_fee = buy_with_tip_params.priority_fee.clone();
+                let recent_blockhash = buy_with_tip_params.recent_blockhash;
+                let swqos_clients = buy_with_tip_params.swqos_clients.clone();
+                Ok(self
+                    .escalate_tips(
+                        TradeType::Buy,
+                        priority_fee,
+                        recent_blockhash,
+                        report,
+                        trail,
+                        |priority_fee| swqos_tips(&swqos_clients, priority_fee, TradeType::Buy),
+                        |priority_fee, recent_blockhash| {
+                            let executor = executor.clone();
+                            let buy_with_tip_params =
+                                BuyWithTipParams { priority_fee, recent_blockhash, ..buy_with_tip_params.clone() };
+                            async move { executor.buy_with_tip(buy_with_tip_params).await }
+                        },
+                    )
+                    .await)
+            }
+            Err(e) => Err(e),
+        };
+        let result = result.map(|report| SubmissionReport {
+            trade_id: trail.trade_id,
+            attempts: trail.attempts.clone(),
+            ..report
+        });
+        if let Ok(report) = &result {
+            track_winner(self.rpc.clone(), report.submitted());
+            if let Some(expected_tokens) = expected_tokens {
+                self.record_realized_slippage(dex_type.clone(), mint, expected_tokens, report.clone());
+            }
+        }
+        if result.is_err() {
+            self.spend_tracker.release(sol_amount);
+            self.release_idempotency_key(idempotency_key);
+        }
+        result
+    }
+
+    /// Tokens `sol_amount` buys of `mint` on `dex_type`, quoted before the buy is submitted as
+    /// the baseline for its realized slippage; None without adaptive slippage or if the quote fails
+    async fn slippage_quote(&self, dex_type: &DexType, mint: &Pubkey, sol_amount: u64) -> Option<u64> {
+        self.slippage_manager.as_ref()?;
+        match Router::venue(&self.read_rpc, mint, dex_type).await {
+            Ok(venue) => venue.map(|venue| venue.buy_amount_out(sol_amount)),
+            Err(e) => {
+                println!("警告: {} 报价失败，本笔买入不记录实际滑点: {}", mint, e);
+                None
+            }
+        }
+    }
+
+    /// Record the realized slippage of a confirmed buy in the background: the tokens received
+    /// per its fill report against `expected_tokens`, quoted before the buy was submitted
+    ///
+    /// Only buys are sampled. A sell's SOL balance change also carries its tip and any closed
+    /// account's rent, so it does not measure slippage.
+    fn record_realized_slippage(
+        &self,
+        dex_type: DexType,
+        mint: Pubkey,
+        expected_tokens: u64,
+        report: SubmissionReport,
+    ) {
+        let Some(slippage_manager) = self.slippage_manager.clone() else {
+            return;
+        };
+        let solana_trade = self.clone();
+        tokio::spawn(async move {
+            match solana_trade.get_submission_fill_report(&report, &mint).await {
+                Ok(fill) => {
+                    let actual = fill.tokens_received.clamp(0, u64::MAX as i128) as u64;
+                    slippage_manager.record_realized(dex_type, expected_tokens, actual);
+                }
+                Err(e) => println!("警告: 获取交易 {} 的成交结果失败，未记录实际滑点: {}", report.trade_id, e),
+            }
+        });
+    }
+
+    /// Submit a sell, retrying with wider slippage when configured
+    ///
+    /// Every submission is recorded in `trail.attempts`, which is kept when the sell fails.
+    /// Releases the idempotency key if the sell fails.
+    async fn execute_sell(
+        &self,
+        dex_type: &DexType,
+        mut sell_params: SellParams,
+        with_tip: bool,
+        idempotency_key: Option<&str>,
+        trail: &mut SubmissionReport,
+    ) -> Result<SubmissionReport, anyhow::Error> {
+        let executor = TradeFactory::create_executor(dex_type.clone());
+        let mut attempt = 0;
+        let result = loop {
+            // Execute sell based on tip preference
+            let mut planned = vec![];
+            let result = if with_tip {
+                match self.sell_with_tip_params(sell_params.clone()) {
+                    Ok(sell_with_tip_params) => {
+                        planned = swqos_tips(
+                            &sell_with_tip_params.swqos_clients,
+                            &sell_with_tip_params.priority_fee,
+                            TradeType::Sell,
+                        );
+                        executor.sell_with_tip(sell_with_tip_params).await
+                    }
+                    Err(e) => Err(e),
+                }
+            } else {
+                executor.sell(sell_params.clone()).await
+            };
+            trail.attempts.push(SubmissionAttempt::new(
+                get_transaction_blockhash(sell_params.recent_blockhash),
+                planned,
+                sell_params.slippage_basis_points,
+                &result,
+            ));
+            let Err(e) = &result else { break result };
+            let slippage = sell_params.slippage_basis_points;
+            let Some(slippage) = self.next_slippage_after(dex_type, slippage, attempt, e) else {
+                break result;
+            };
+            sell_params.slippage_basis_points = Some(slippage);
+            attempt += 1;
+        };
+        let result = match result {
+            Ok(report) if with_tip => {
+                let priority_fee = sell_params.priority_fee.clone();
+                let recent_blockhash = sell_params.recent_blockhash;
+                let swqos_types: Vec<SwqosType> =
+                    report.timings.iter().map(|timing| timing.swqos_type.clone()).collect();
+                Ok(self
+                    .escalate_tips(
+                        TradeType::Sell,
+                        priority_fee,
+                        recent_blockhash,
+                        report,
+                        trail,
+                        |priority_fee| {
+                            let tip = |swqos_type: &SwqosType| match swqos_type {
+                                SwqosType::Default => 0.0,
+                                _ => priority_fee.sell_tip_fee,
+                            };
+                            swqos_types.iter().map(|swqos_type| (swqos_type.clone(), tip(swqos_type))).collect()
+                        },
+                        |priority_fee, recent_blockhash| {
+                            let executor = executor.clone();
+                            let sell_with_tip_params = self.sell_with_tip_params(SellParams {
+                                priority_fee,
+                                recent_blockhash,
+                                ..sell_params.clone()
+                            });
+                            async move { executor.sell_with_tip(sell_with_tip_params?).await }
+                        },
+                    )
+                    .await)
+            }
+            result => result,
+        };
+        let result = result.map(|report| SubmissionReport {
+            trade_id: trail.trade_id,
+            attempts: trail.attempts.clone(),
+            ..report
+        });
+        if let Ok(report) = &result {
+            track_winner(self.rpc.clone(), report.submitted());
+        }
+        if result.is_err() {
+            self.release_idempotency_key(idempotency_key);
+        }
+        result
+    }
+
+    /// Resubmit with escalated tips while no submission so far has landed within
+    /// `TipEscalationConfig::max_slots`, recording every attempt in the returned report
+    ///
+    /// Only one attempt can land: with a durable nonce every attempt consumes the same nonce,
+    /// otherwise a resubmission waits until the previous blockhash has expired with none of the
+    /// earlier transactions landed, then uses a fresh blockhash. Stops at the tip ceiling, after
+    /// `max_escalations`, when the nonce changes, or when a resubmission fails; the report of the
+    /// last successful submission is returned. Each resubmission is appended to `trail.attempts`,
+    /// with the SWQOS channels and tips from `planned` if it fails.
+    #[allow(clippy::too_many_arguments)]
+    async fn escalate_tips<F, Fut>(
+        &self,
+        trade_type: TradeType,
+        mut priority_fee: PriorityFee,
+        mut recent_blockhash: Hash,
+        mut report: SubmissionReport,
+        trail: &mut SubmissionReport,
+        planned: impl Fn(&PriorityFee) -> Vec<(SwqosType, f64)>,
+        mut resubmit: F,
+    ) -> SubmissionReport
+    where
+        F: FnMut(PriorityFee, Hash) -> Fut,
+        Fut: std::future::Future<Output = Result<SubmissionReport, anyhow::Error>>,
+    {
+        let Some(config) = self.trade_config.tip_escalation else { return report };
+        let nonce = is_using_nonce().then(|| NonceCache::get_instance().get_nonce_info().current_nonce);
+        let mut attempts: Vec<TipEscalationAttempt> = vec![];
+        // 之前所有提交的签名，它们在 blockhash 过期前仍可能上链
+        let mut all_signatures = vec![];
+        loop {
+            let signatures = report.signatures();
+            all_signatures.extend(signatures.iter().copied());
+            // RPC 回退的交易不带小费，无需递增
+            let landed = if report.rpc_fallback || signatures.is_empty() {
+                true
+            } else {
+                match wait_for_landing(&self.rpc, &all_signatures, config.max_slots).await {
+                    Ok(landed) => {
+                        if let Some(attempt) = trail.attempts.last_mut() {
+                            attempt.landed = Some(landed);
+                        }
+                        landed
+                    }
+                    Err(e) => {
+                        println!("查询上链状态失败，停止递增小费: {}", e);
+                        true
+                    }
+                }
+            };
+            attempts.push(TipEscalationAttempt { tips: tips(&priority_fee, trade_type), signatures, landed });
+            if landed
+                || attempts.len() > config.max_escalations as usize
+                || !config.escalate_priority_fee(&mut priority_fee, trade_type)
+            {
+                break;
+            }
+            match nonce {
+                // 各次提交消费同一个 nonce，nonce 被推进说明其他交易已使用，不能再提交
+                Some(nonce) => {
+                    if NonceCache::get_instance().get_nonce_info().current_nonce != nonce {
+                        println!("nonce 已变化，停止递增小费");
+                        break;
+                    }
+                }
+                None => {
+                    println!("交易 {} 个 slot 内未上链，等待 blockhash 过期后再提高小费", config.max_slots);
+                    match wait_for_expiry(&self.rpc, &all_signatures, &recent_blockhash).await {
+                        Ok(false) => {}
+                        Ok(true) => {
+                            if let Some(attempt) = attempts.last_mut() {
+                                attempt.landed = true;
+                            }
+                            if let Some(attempt) = trail.attempts.last_mut() {
+                                attempt.landed = Some(true);
+                            }
+                            break;
+                        }
+                        Err(e) => {
+                            println!("等待 blockhash 过期失败，停止递增小费: {}", e);
+                            break;
+                        }
+                    }
+                    recent_blockhash = match self.rpc.get_latest_blockhash().await {
+                        Ok(blockhash) => blockhash,
+                        Err(e) => {
+                            println!("获取 blockhash 失败，停止递增小费: {}", e);
+                            break;
+                        }
+                    };
+                }
+            }
+            println!("小费提高到 {:?} 后重新提交", tips(&priority_fee, trade_type));
+            let result = resubmit(priority_fee.clone(), recent_blockhash).await;
+            let slippage_basis_points = trail.attempts.last().and_then(|attempt| attempt.slippage_basis_points);
+            trail.attempts.push(SubmissionAttempt::new(
+                get_transaction_blockhash(recent_blockhash),
+                planned(&priority_fee),
+                slippage_basis_points,
+                &result,
+            ));
+            match result {
+                Ok(next) => report = next,
+                Err(e) => {
+                    println!("提高小费后重新提交失败: {}", e);
+                    break;
+                }
+            }
+        }
+        report.tip_escalations = attempts;
+        report
+    }
+
+    /// Fill a buy at the live quote under `ExecutionMode::Paper`
+    ///
+    /// Spend caps and idempotency keys apply as for live trades. Fills are immediate, so under
+    /// `SubmissionMode::FireAndTrack` the outcome is published before returning.
+    async fn paper_buy(
+        &self,
+        paper_broker: &PaperBroker,
+        dex_type: DexType,
+        mint: Pubkey,
+        sol_amount: u64,
+        idempotency_key: Option<String>,
+    ) -> Result<SubmissionReport, anyhow::Error> {
+        self.claim_idempotency_key(idempotency_key.as_deref())?;
+        if let Err(e) = self.spend_tracker.reserve(sol_amount) {
+            self.release_idempotency_key(idempotency_key.as_deref());
+            return Err(e);
+        }
+        let result = async {
+            let _mint_lock = self.mint_locks.lock(&mint).await;
+            let venue = self.paper_venue(&dex_type, &mint).await?;
+            let decimals = self.token_metadata(&mint).await?.decimals;
+            paper_broker.fill_buy(&venue, sol_amount, decimals)
+        }
+        .await;
+        if result.is_err() {
+            self.spend_tracker.release(sol_amount);
+            self.release_idempotency_key(idempotency_key.as_deref());
+        }
+        self.publish_paper_outcome(dex_type, TradeType::Buy, mint, sol_amount, idempotency_key, result)
+    }
+
+    /// Fill a sell at the live quote under `ExecutionMode::Paper`, up to the paper balance
+    async fn paper_sell(
+        &self,
+        paper_broker: &PaperBroker,
+        dex_type: DexType,
+        mint: Pubkey,
+        token_amount: u64,
+        min_sol_out: Option<u64>,
+        idempotency_key: Option<String>,
+    ) -> Result<SubmissionReport, anyhow::Error> {
+        self.claim_idempotency_key(idempotency_key.as_deref())?;
+        let result = async {
+            let _mint_lock = self.mint_locks.lock(&mint).await;
+            let venue = self.paper_venue(&dex_type, &mint).await?;
+            let decimals = self.token_metadata(&mint).await?.decimals;
+            paper_broker.fill_sell(&venue, token_amount, min_sol_out, decimals)
+        }
+        .await;
+        if result.is_err() {
+            self.release_idempotency_key(idempotency_key.as_deref());
+        }
+        self.publish_paper_outcome(dex_type, TradeType::Sell, mint, token_amount, idempotency_key, result)
+    }
+
+    /// Live liquidity of `mint` on `dex_type` used to price a paper fill
+    async fn paper_venue(&self, dex_type: &DexType, mint: &Pubkey) -> Result<trading::Venue, anyhow::Error> {
+        Router::venue(&self.rpc, mint, dex_type)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("No live {} quote for {} to fill a paper trade", dex_type, mint))
+    }
+
+    fn publish_paper_outcome(
+        &self,
+        dex_type: DexType,
+        trade_type: TradeType,
+        mint: Pubkey,
+        amount: u64,
+        idempotency_key: Option<String>,
+        result: Result<(SubmissionReport, FillReport), anyhow::Error>,
+    ) -> Result<SubmissionReport, anyhow::Error> {
+        let result = result.map(|(submission, _)| submission);
+        if self.trade_config.submission_mode == SubmissionMode::FireAndTrack {
+            let (submission, error) = match &result {
+                Ok(submission) => (submission.clone(), None),
+                Err(e) => (SubmissionReport::default(), Some(e.to_string())),
+            };
+            self.trade_outcomes.publish(TradeOutcome {
+                dex_type,
+                trade_type,
+                mint,
+                amount,
+                idempotency_key,
+                error,
+                submission,
+            });
+        }
+        result
+    }
+
+    /// Reject transactions that bypass `buy` / `sell` under `ExecutionMode::Paper`
+    fn ensure_live_execution(&self) -> Result<(), anyhow::Error> {
+        if self.paper_broker.is_some() {
+            return Err(anyhow::anyhow!("Only buy and sell can be simulated in paper execution mode"));
+        }
+        Ok(())
+    }
+
+    /// Reject a trade whose idempotency key was already submitted within the TTL
+    fn claim_idempotency_key(&self, idempotency_key: Option<&str>) -> Result<(), anyhow::Error> {
+        match idempotency_key {
+            Some(key) if !self.idempotency_cache.try_insert(key) => {
+                Err(anyhow::anyhow!("Duplicate trade submission for idempotency key {}", key))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Wider slippage for the next attempt after a slippage-exceeded failure, None to give up
+    fn next_slippage_after(
+        &self,
+        dex_type: &DexType,
+        slippage_basis_points: Option<u64>,
+        attempt: u32,
+        error: &anyhow::Error,
+    ) -> Option<u64> {
+        let slippage_manager = self.slippage_manager.as_ref()?;
+        if attempt >= slippage_manager.config().max_retries
+            || !SlippageManager::is_slippage_error(dex_type, error)
+        {
+            return None;
+        }
+        let next = slippage_manager.next_slippage(slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE))?;
+        println!("滑点超限，使用 {} 基点重试", next);
+        Some(next)
+    }
+
+    /// Let a failed trade be retried with the same idempotency key
+    fn release_idempotency_key(&self, idempotency_key: Option<&str>) {
+        if let Some(key) = idempotency_key {
+            self.idempotency_cache.remove(key);
+        }
+    }
+
+    /// Fetch the PumpFun bonding curve, and the creator when not given, for trades made without
+    /// PumpFun params; other protocols and trades with a bonding curve are returned unchanged
+    async fn resolve_pumpfun_params(
+        &self,
+        dex_type: &DexType,
+        mint: &Pubkey,
+        creator: Option<Pubkey>,
+        extension_params: Option<Box<dyn ProtocolParams>>,
+    ) -> Result<(Option<Pubkey>, Option<Box<dyn ProtocolParams>>), anyhow::Error> {
+        if *dex_type != DexType::PumpFun {
+            return Ok((creator, extension_params));
+        }
+        let bonding_curve = match &extension_params {
+            Some(params) => match params.as_any().downcast_ref::<PumpFunParams>() {
+                Some(PumpFunParams { bonding_curve: Some(bonding_curve) }) => bonding_curve.clone(),
+                Some(_) => Arc::new(fetch_bonding_curve(&self.rpc, mint).await?),
+                None => return Ok((creator, extension_params)),
+            },
+            None => Arc::new(fetch_bonding_curve(&self.rpc, mint).await?),
+        };
+        let creator = creator.or(Some(bonding_curve.creator));
+        Ok((creator, Some(Box::new(PumpFunParams { bonding_curve: Some(bonding_curve) }))))
+    }
+
+    /// Route PumpFun trades on a completed bonding curve to the migrated PumpSwap pool
+    ///
+    /// A routed trade uses the pool's coin creator, which the PumpSwap creator vault is derived from
+    async fn route_migrated(
+        &self,
+        dex_type: DexType,
+        mint: &Pubkey,
+        creator: Option<Pubkey>,
+        extension_params: Option<Box<dyn ProtocolParams>>,
+    ) -> Result<(DexType, Option<Pubkey>, Option<Box<dyn ProtocolParams>>), anyhow::Error> {
+        if dex_type != DexType::PumpFun || !self.trade_config.auto_route_migrated {
+            return Ok((dex_type, creator, extension_params));
+        }
+        let bonding_curve = extension_params
+            .as_ref()
+            .and_then(|params| params.as_any().downcast_ref::<PumpFunParams>())
+            .and_then(|params| params.bonding_curve.clone());
+        match migrated_pumpswap_params(&self.rpc, mint, bonding_curve.as_deref()).await? {
+            Some((pumpswap_params, coin_creator)) => {
+                println!(
+                    "{} 已迁移到 PumpSwap，改走池子 {:?}",
+                    TokenMetadataCache::get_instance().label(mint),
+                    pumpswap_params.pool
+                );
+                Ok((DexType::PumpSwap, Some(coin_creator), Some(Box::new(pumpswap_params))))
+            }
+            None => Ok((dex_type, creator, extension_params)),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn make_buy_params(
+        &self,
+        dex_type: &DexType,
+        mint: Pubkey,
+        creator: Option<Pubkey>,
+        sol_amount: u64,
+        slippage_basis_points: Option<u64>,
+        recent_blockhash: Option<Hash>,
+        execution_overrides: Option<ExecutionOverrides>,
+        extension_params: Option<Box<dyn ProtocolParams>>,
+    ) -> Result<BuyParams, anyhow::Error> {
+        let recent_blockhash = match recent_blockhash {
+            Some(recent_blockhash) => recent_blockhash,
+            None => BlockhashCache::get_instance().get_or_fetch(&self.rpc).await?,
+        };
+        let protocol_params = resolve_protocol_params(dex_type, extension_params)?;
+        // Without an explicit slippage, fall back to one informed by realized slippage
+        let slippage_basis_points = slippage_basis_points.or_else(|| {
+            self.slippage_manager.as_ref().and_then(|manager| manager.suggested_slippage(dex_type))
+        });
+        let priority_fee =
+            self.resolve_priority_fee(dex_type, &mint, execution_overrides.as_ref()).await;
+        let mut buy_params = BuyParams {
+            rpc: Some(self.rpc.clone()),
+            read_rpc: Some(self.read_rpc.clone()),
+            payer: self.payer.clone(),
+            fee_payer: self.fee_payer.clone(),
+            mint,
+            creator: creator.unwrap_or_default(),
+            sol_amount,
+            slippage_basis_points,
+            priority_fee,
+            lookup_table_key: self.trade_config.lookup_table_key,
+            recent_blockhash,
+            data_size_limit: 0,
+            wsol_policy: self.trade_config.wsol_policy,
+            swqos_types: execution_overrides.as_ref().and_then(|overrides| overrides.swqos_types.clone()),
+            protocol_params,
+        };
+        // A per-trade tip takes precedence over the auto tip from the Jito tip oracle; the auto
+        // tip follows Jito's tip floor, so it only replaces the tips paid to Jito
+        let priority_fee = &mut buy_params.priority_fee;
+        if let Some(buy_tip_fee) = execution_overrides.and_then(|overrides| overrides.tip_fee) {
+            priority_fee.buy_tip_fee = buy_tip_fee;
+            priority_fee.buy_tip_fees =
+                priority_fee.buy_tip_fees.iter().map(|_| buy_tip_fee).collect();
+        } else if self.trade_config.auto_tip.is_some() {
+            let buy_tip_fee = TipCache::get_instance().get_tip();
+            priority_fee.buy_tip_fee = buy_tip_fee;
+            for (tip_fee, swqos_client) in priority_fee.buy_tip_fees.iter_mut().zip(&self.swqos_clients) {
+                if swqos_client.get_swqos_type() == SwqosType::Jito {
+                    *tip_fee = buy_tip_fee;
+                }
+            }
+        }
+        Ok(buy_params)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn make_sell_params(
+        &self,
+        dex_type: &DexType,
+        mint: Pubkey,
+        creator: Option<Pubkey>,
+        token_amount: u64,
+        slippage_basis_points: Option<u64>,
+        min_sol_out: Option<u64>,
+        recent_blockhash: Option<Hash>,
+        execution_overrides: Option<ExecutionOverrides>,
+        extension_params: Option<Box<dyn ProtocolParams>>,
+    ) -> Result<SellParams, anyhow::Error> {
+        let recent_blockhash = match recent_blockhash {
+            Some(recent_blockhash) => recent_blockhash,
+            None => BlockhashCache::get_instance().get_or_fetch(&self.rpc).await?,
+        };
+        let protocol_params = resolve_protocol_params(dex_type, extension_params)?;
+        // Without an explicit slippage, fall back to one informed by realized slippage
+        let slippage_basis_points = slippage_basis_points.or_else(|| {
+            self.slippage_manager.as_ref().and_then(|manager| manager.suggested_slippage(dex_type))
+        });
+        let mut priority_fee =
+            self.resolve_priority_fee(dex_type, &mint, execution_overrides.as_ref()).await;
+        let swqos_types = execution_overrides.as_ref().and_then(|overrides| overrides.swqos_types.clone());
+        if let Some(sell_tip_fee) = execution_overrides.and_then(|overrides| overrides.tip_fee) {
+            priority_fee.sell_tip_fee = sell_tip_fee;
+        }
+        Ok(SellParams {
+            rpc: Some(self.rpc.clone()),
+            read_rpc: Some(self.read_rpc.clone()),
+            payer: self.payer.clone(),
+            fee_payer: self.fee_payer.clone(),
+            mint,
+            creator: creator.unwrap_or_default(),
+            token_amount: Some(token_amount),
+            slippage_basis_points,
+            min_sol_out,
+            priority_fee,
+            lookup_table_key: self.trade_config.lookup_table_key,
+            recent_blockhash,
+            wsol_policy: self.trade_config.wsol_policy,
+            swqos_types,
+            protocol_params,
+            on_sent: None,
+        })
+    }
+}
+
+/// Use the given protocol params, or the protocol defaults, and check they match `dex_type`
+fn resolve_protocol_params(
+    dex_type: &DexType,
+    extension_params: Option<Box<dyn ProtocolParams>>,
+) -> Result<Box<dyn ProtocolParams>, anyhow::Error> {
+    let protocol_params = if let Some(params) = extension_params {
+        params
+    } else {
+        match dex_type {
+            DexType::PumpFun => Box::new(PumpFunParams::default()) as Box<dyn ProtocolParams>,
+            DexType::PumpSwap => Box::new(PumpSwapParams::default()) as Box<dyn ProtocolParams>,
+            DexType::Bonk => Box::new(BonkParams::default()) as Box<dyn ProtocolParams>,
+            DexType::RaydiumCpmm => {
+                Box::new(RaydiumCpmmParams::default()) as Box<dyn ProtocolParams>
+            }
+            DexType::RaydiumAmmV4 => {
+                Box::new(RaydiumAmmV4Params::default()) as Box<dyn ProtocolParams>
+            }
+            DexType::RaydiumClmm => {
+                Box::new(RaydiumClmmParams::default()) as Box<dyn ProtocolParams>
+            }
+            DexType::OrcaWhirlpool => {
+                Box::new(OrcaWhirlpoolParams::default()) as Box<dyn ProtocolParams>
+            }
+            DexType::Jupiter => Box::new(JupiterParams::default()) as Box<dyn ProtocolParams>,
+            DexType::Moonshot => Box::new(MoonshotParams::default()) as Box<dyn ProtocolParams>,
+            DexType::Boop => Box::new(BoopParams::default()) as Box<dyn ProtocolParams>,
+            DexType::Phoenix => Box::new(PhoenixParams::default()) as Box<dyn ProtocolParams>,
+        }
+    };
+
+    // Validate protocol params
+    let is_valid_params = match dex_type {
+        DexType::PumpFun => protocol_params.as_any().downcast_ref::<PumpFunParams>().is_some(),
+        DexType::PumpSwap => protocol_params.as_any().downcast_ref::<PumpSwapParams>().is_some(),
+        DexType::Bonk => protocol_params.as_any().downcast_ref::<BonkParams>().is_some(),
+        DexType::RaydiumCpmm => {
+            protocol_params.as_any().downcast_ref::<RaydiumCpmmParams>().is_some()
+        }
+        DexType::RaydiumAmmV4 => {
+            protocol_params.as_any().downcast_ref::<RaydiumAmmV4Params>().is_some()
+        }
+        DexType::RaydiumClmm => {
+            protocol_params.as_any().downcast_ref::<RaydiumClmmParams>().is_some()
+        }
+        DexType::OrcaWhirlpool => {
+            protocol_params.as_any().downcast_ref::<OrcaWhirlpoolParams>().is_some()
+        }
+        DexType::Jupiter => protocol_params.as_any().downcast_ref::<JupiterParams>().is_some(),
+        DexType::Moonshot => protocol_params.as_any().downcast_ref::<MoonshotParams>().is_some(),
+        DexType::Boop => protocol_params.as_any().downcast_ref::<BoopParams>().is_some(),
+        DexType::Phoenix => protocol_params.as_any().downcast_ref::<PhoenixParams>().is_some(),
+    };
+
+    if !is_valid_params {
+        return Err(anyhow::anyhow!("Invalid protocol params for Trade"));
+    }
+
+    Ok(protocol_params)
+}
+
+/// Wait until a FireAndTrack trade is handed to its first provider and report that signature
+///
+/// If the background task ends before anything was sent, its own report or error is returned instead
+async fn first_submission(
+    trade_id: u64,
+    planned: Vec<(SwqosType, f64)>,
+    started: Instant,
+    sent: tokio::sync::oneshot::Receiver<(SwqosType, Signature)>,
+    task: tokio::task::JoinHandle<Result<SubmissionReport, String>>,
+) -> Result<SubmissionReport, anyhow::Error> {
+    match sent.await {
+        Ok((swqos_type, signature)) => {
+            let tip = planned
+                .iter()
+                .find(|(planned_type, _)| *planned_type == swqos_type)
+                .map_or(0.0, |(_, tip)| *tip);
+            Ok(SubmissionReport {
+                trade_id,
+                timings: vec![SubmissionTiming {
+                    swqos_type,
+                    elapsed: started.elapsed(),
+                    status: SubmissionStatus::Succeeded,
+                    signature: Some(signature),
+                    tip,
+                }],
+                ..Default::default()
+            })
+        }
+        Err(_) => match task.await {
+            Ok(Ok(submission)) => Ok(submission),
+            Ok(Err(e)) => Err(anyhow::anyhow!(e)),
+            Err(e) => Err(anyhow::anyhow!("交易 {} 后台任务异常: {}", trade_id, e)),
+        },
+    }
+}
+
+/// Replace the signatures of a transaction with empty placeholders
+fn clear_signatures(mut transaction: VersionedTransaction) -> VersionedTransaction {
+    transaction.signatures = vec![Signature::default(); transaction.signatures.len()];
+    transaction
+}
+
+/// Record the result of one TWAP slice, returning whether it failed
+fn record_twap_slice(
+    outcomes: &mut Vec<TwapSliceOutcome>,
+    slice: trading::TwapSlice,
+    result: Result<SubmissionReport, anyhow::Error>,
+) -> bool {
+    let (submission, error) = match result {
+        Ok(submission) => (submission, None),
+        Err(e) => {
+            println!("TWAP slice {} of {} lamports/tokens failed: {}", slice.index, slice.amount, e);
+            (SubmissionReport::default(), Some(e.to_string()))
+        }
+    };
+    let failed = error.is_some();
+    outcomes.push(TwapSliceOutcome { slice, submission, error });
+    failed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::bonding_curve::BondingCurveAccount;
+    use solana_sdk::commitment_config::CommitmentConfig;
+
+    #[tokio::test]
+    async fn test_built_transactions_route_completed_curves() {
+        let trade_config = TradeConfig::new(
+            "http://127.0.0.1:1".to_string(),
+            vec![],
+            PriorityFee::default(),
+            CommitmentConfig::confirmed(),
+            None,
+        );
+        let client = SolanaTrade::new(Arc::new(Keypair::new()), trade_config).await;
+        let mint = Pubkey::new_unique();
+        let mut bonding_curve = BondingCurveAccount::from_dev_trade(&mint, 0, 0, Pubkey::new_unique());
+        bonding_curve.complete = true;
+        let params = || -> Option<Box<dyn ProtocolParams>> {
+            Some(Box::new(PumpFunParams { bonding_curve: Some(Arc::new(bonding_curve.clone())) }))
+        };
+
+        // 已完成的 bonding curve 改走 PumpSwap 池子，而不是构建必然失败的 PumpFun 交易
+        let buy = client
+            .build_buy_transaction(
+                DexType::PumpFun,
+                mint,
+                None,
+                1_000_000,
+                None,
+                Some(Hash::default()),
+                None,
+                None,
+                false,
+                params(),
+            )
+            .await;
+        assert!(buy.unwrap_err().to_string().contains("is complete but PumpSwap pool"));
+        let sell = client
+            .build_sell_transaction(
+                DexType::PumpFun,
+                mint,
+                None,
+                1_000_000,
+                None,
+                None,
+                Some(Hash::default()),
+                None,
+                None,
+                false,
+                params(),
+            )
+            .await;
+        assert!(sell.unwrap_err().to_string().contains("is complete but PumpSwap pool"));
+    }
 }
@@ -1,7 +1,7 @@
-use std::{str::FromStr, sync::Arc};
+use std::{collections::HashMap, str::FromStr, sync::Arc};
 
 use sol_trade_sdk::{
-    common::{bonding_curve::BondingCurveAccount, AnyResult, PriorityFee, TradeConfig},
+    common::{bonding_curve::BondingCurveAccount, AnyResult, ExecutionMode, PriorityFee, SpendLimit, SubmissionMode, TradeConfig, WsolPolicy},
     swqos::{SwqosConfig, SwqosRegion},
     trading::{core::params::{BonkParams, PumpFunParams, PumpSwapParams, RaydiumCpmmParams}, factory::DexType, raydium_cpmm::common::{get_buy_token_amount, get_sell_sol_amount}},
     SolanaTrade,
@@ -60,6 +60,8 @@ fn create_swqos_configs(rpc_url: &str) -> Vec<SwqosConfig> {
         SwqosConfig::Bloxroute("your api_token".to_string(), SwqosRegion::Frankfurt), 
         SwqosConfig::ZeroSlot("your api_token".to_string(), SwqosRegion::Frankfurt),
         SwqosConfig::Temporal("your api_token".to_string(), SwqosRegion::Frankfurt),
+        SwqosConfig::Node1("your api_key".to_string(), SwqosRegion::Frankfurt),
+        SwqosConfig::BlockRazor("your api_key".to_string(), SwqosRegion::Frankfurt),
         SwqosConfig::Default(rpc_url.to_string()),
     ]
 }
@@ -67,10 +69,30 @@ fn create_swqos_configs(rpc_url: &str) -> Vec<SwqosConfig> {
 fn create_trade_config(rpc_url: String, swqos_configs: Vec<SwqosConfig>) -> TradeConfig {
     TradeConfig {
         rpc_url,
+        read_rpc_url: None,
         commitment: CommitmentConfig::confirmed(),
         priority_fee: PriorityFee::default(),
         swqos_configs,
         lookup_table_key: None,
+        priority_fee_api_url: None,
+        disabled_dex_types: vec![],
+        auto_tip: None,
+        dex_priority_fees: HashMap::new(),
+        spend_limit: SpendLimit::default(),
+        adaptive_slippage: None,
+        submission_mode: SubmissionMode::default(),
+        auto_route_migrated: true,
+        wsol_policy: WsolPolicy::default(),
+        race_submissions: false,
+        swqos_health_check: None,
+        swqos_rate_limits: HashMap::new(),
+        tip_account_refresh_ms: None,
+        region_probe_interval_ms: 300_000,
+        rpc_fallback: false,
+        tip_escalation: None,
+        execution_mode: ExecutionMode::default(),
+        paper_fill_model: None,
+        reporting_timezone: sol_trade_sdk::common::trading_day::utc_offset(),
     }
 }
 
@@ -82,7 +104,7 @@ async fn test_pumpfun_copy_trade_with_grpc(trade_info: PumpFunTradeEvent) -> Any
     let mint_pubkey = Pubkey::from_str("xxxxxx")?;
     let buy_sol_cost = 100_000;
     let slippage_basis_points = Some(100);
-    let recent_blockhash = client.rpc.get_latest_blockhash().await?;
+    let recent_blockhash = Some(client.rpc.get_latest_blockhash().await?);
     let bonding_curve = BondingCurveAccount::from_trade(&trade_info);
 
     // Buy tokens
@@ -109,6 +131,7 @@ async fn test_pumpfun_copy_trade_with_grpc(trade_info: PumpFunTradeEvent) -> Any
         Some(creator),
         amount_token,
         slippage_basis_points,
+        None,
         recent_blockhash,
         None,
         false,
@@ -129,7 +152,7 @@ async fn test_pumpfun_sniper_trade_with_shreds(trade_info: PumpFunTradeEvent) ->
     let mint_pubkey = trade_info.mint;
     let creator = trade_info.creator;
     let slippage_basis_points = Some(100);
-    let recent_blockhash = client.rpc.get_latest_blockhash().await?;
+    let recent_blockhash = Some(client.rpc.get_latest_blockhash().await?);
 
     let bonding_curve = BondingCurveAccount::from_dev_trade(
         &mint_pubkey,
@@ -163,6 +186,7 @@ async fn test_pumpfun_sniper_trade_with_shreds(trade_info: PumpFunTradeEvent) ->
         Some(creator),
         amount_token,
         slippage_basis_points,
+        None,
         recent_blockhash,
         None,
         false,
@@ -180,7 +204,7 @@ async fn test_pumpswap() -> AnyResult<()> {
     let mint_pubkey = Pubkey::from_str("2zMMhcVQEXDtdE6vsFS7S7D5oUodfJHE8vd1gnBouauv")?;
     let buy_sol_cost = 100_000;
     let slippage_basis_points = Some(100);
-    let recent_blockhash = client.rpc.get_latest_blockhash().await?;
+    let recent_blockhash = Some(client.rpc.get_latest_blockhash().await?);
     let pool_address = Pubkey::from_str("xxxxxxx")?;
     let base_mint = Pubkey::from_str("2zMMhcVQEXDtdE6vsFS7S7D5oUodfJHE8vd1gnBouauv")?;
     let quote_mint = Pubkey::from_str("So11111111111111111111111111111111111111112")?;
@@ -216,6 +240,7 @@ async fn test_pumpswap() -> AnyResult<()> {
         Some(creator),
         amount_token,
         slippage_basis_points,
+        None,
         recent_blockhash,
         None,
         false,
@@ -241,7 +266,7 @@ async fn test_bonk_copy_trade_with_grpc(trade_info: BonkTradeEvent) -> AnyResult
     let mint_pubkey = Pubkey::from_str("xxxxxxx")?;
     let buy_sol_cost = 100_000;
     let slippage_basis_points = Some(100);
-    let recent_blockhash = client.rpc.get_latest_blockhash().await?;
+    let recent_blockhash = Some(client.rpc.get_latest_blockhash().await?);
 
     // Buy tokens
     println!("Buying tokens from letsbonk.fun...");
@@ -265,6 +290,7 @@ async fn test_bonk_copy_trade_with_grpc(trade_info: BonkTradeEvent) -> AnyResult
         None,
         amount_token,
         slippage_basis_points,
+        None,
         recent_blockhash,
         None,
         false,
@@ -285,7 +311,7 @@ async fn test_bonk_sniper_trade_with_shreds(trade_info: BonkTradeEvent) -> AnyRe
     let mint_pubkey = Pubkey::from_str("xxxxxxx")?;
     let buy_sol_cost = 100_000;
     let slippage_basis_points = Some(100);
-    let recent_blockhash = client.rpc.get_latest_blockhash().await?;
+    let recent_blockhash = Some(client.rpc.get_latest_blockhash().await?);
 
     // Buy tokens
     println!("Buying tokens from letsbonk.fun...");
@@ -309,6 +335,7 @@ async fn test_bonk_sniper_trade_with_shreds(trade_info: BonkTradeEvent) -> AnyRe
         None,
         amount_token,
         slippage_basis_points,
+        None,
         recent_blockhash,
         None,
         false,
@@ -326,7 +353,7 @@ async fn test_bonk() -> Result<(), Box<dyn std::error::Error>> {
     let mint_pubkey = Pubkey::from_str("xxxxxxx")?;
     let buy_sol_cost = 100_000;
     let slippage_basis_points = Some(100);
-    let recent_blockhash = client.rpc.get_latest_blockhash().await?;
+    let recent_blockhash = Some(client.rpc.get_latest_blockhash().await?);
 
     // Buy tokens
     println!("Buying tokens from letsbonk.fun...");
@@ -350,6 +377,7 @@ async fn test_bonk() -> Result<(), Box<dyn std::error::Error>> {
         None,
         amount_token,
         slippage_basis_points,
+        None,
         recent_blockhash,
         None,
         false,
@@ -367,7 +395,7 @@ async fn test_raydium_cpmm() -> Result<(), Box<dyn std::error::Error>> {
     let mint_pubkey = Pubkey::from_str("xxxxxxxx")?;
     let buy_sol_cost = 100_000;
     let slippage_basis_points = Some(100);
-    let recent_blockhash = client.rpc.get_latest_blockhash().await?;
+    let recent_blockhash = Some(client.rpc.get_latest_blockhash().await?);
     let pool_state = Pubkey::from_str("xxxxxxx")?;
     let buy_amount_out = get_buy_token_amount(&client.rpc, &pool_state, buy_sol_cost).await?;
     // Buy tokens
@@ -399,6 +427,7 @@ async fn test_raydium_cpmm() -> Result<(), Box<dyn std::error::Error>> {
         None,
         amount_token,
         slippage_basis_points,
+        None,
         recent_blockhash,
         None,
         false,
@@ -0,0 +1,225 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+/// 地址列表文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListFormat {
+    /// 地址字符串数组，或带 `address`、`name`、`group` 字段的对象数组，两种可混用
+    Json,
+    /// 每行 `address,name,group`，首行表头可选，名称和分组可以为空
+    Csv,
+}
+
+impl ListFormat {
+    /// 按扩展名（`.json` / `.csv`）判断格式
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("json") => Ok(Self::Json),
+            Some(extension) if extension.eq_ignore_ascii_case("csv") => Ok(Self::Csv),
+            _ => Err(anyhow!("Cannot tell the list format of {}, use a .json or .csv file", path.display())),
+        }
+    }
+}
+
+/// 地址列表中的一项，用于导入导出监控钱包、狙击黑名单等列表
+///
+/// 名称和分组只对监控钱包生效，其他列表忽略
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressEntry {
+    pub address: Pubkey,
+    pub name: Option<String>,
+    pub group: Option<String>,
+}
+
+impl AddressEntry {
+    pub fn new(address: Pubkey) -> Self {
+        Self { address, name: None, group: None }
+    }
+}
+
+/// JSON 中的一项：地址字符串或对象
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum JsonEntry {
+    Address(String),
+    Entry {
+        address: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        group: Option<String>,
+    },
+}
+
+/// 解析地址列表，地址无效时返回错误并指出所在位置
+pub fn parse_address_list(text: &str, format: ListFormat) -> Result<Vec<AddressEntry>> {
+    match format {
+        ListFormat::Json => {
+            let entries: Vec<JsonEntry> = serde_json::from_str(text)?;
+            entries
+                .into_iter()
+                .enumerate()
+                .map(|(index, entry)| {
+                    let (address, name, group) = match entry {
+                        JsonEntry::Address(address) => (address, None, None),
+                        JsonEntry::Entry { address, name, group } => (address, name, group),
+                    };
+                    let address = address
+                        .trim()
+                        .parse()
+                        .map_err(|e| anyhow!("Entry {}: invalid address {}: {}", index, address, e))?;
+                    Ok(AddressEntry { address, name, group })
+                })
+                .collect()
+        }
+        ListFormat::Csv => {
+            let mut entries = vec![];
+            for (index, line) in text.lines().enumerate() {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                let fields = csv_fields(line).map_err(|e| anyhow!("Line {}: {}", index + 1, e))?;
+                if entries.is_empty() && fields[0].eq_ignore_ascii_case("address") {
+                    continue;
+                }
+                let address = fields[0]
+                    .parse()
+                    .map_err(|e| anyhow!("Line {}: invalid address {}: {}", index + 1, fields[0], e))?;
+                let field = |i: usize| fields.get(i).filter(|value| !value.is_empty()).cloned();
+                entries.push(AddressEntry { address, name: field(1), group: field(2) });
+            }
+            Ok(entries)
+        }
+    }
+}
+
+/// 将地址列表格式化为文本，按给定顺序输出
+pub fn format_address_list(entries: &[AddressEntry], format: ListFormat) -> Result<String> {
+    match format {
+        ListFormat::Json => {
+            let entries: Vec<JsonEntry> = entries
+                .iter()
+                .map(|entry| match (&entry.name, &entry.group) {
+                    (None, None) => JsonEntry::Address(entry.address.to_string()),
+                    _ => JsonEntry::Entry {
+                        address: entry.address.to_string(),
+                        name: entry.name.clone(),
+                        group: entry.group.clone(),
+                    },
+                })
+                .collect();
+            Ok(serde_json::to_string_pretty(&entries)? + "\n")
+        }
+        ListFormat::Csv => {
+            let mut text = String::from("address,name,group\n");
+            for entry in entries {
+                text.push_str(&format!(
+                    "{},{},{}\n",
+                    entry.address,
+                    csv_field(entry.name.as_deref().unwrap_or_default()),
+                    csv_field(entry.group.as_deref().unwrap_or_default()),
+                ));
+            }
+            Ok(text)
+        }
+    }
+}
+
+/// 从文件导入地址列表，格式按扩展名判断
+pub fn import_address_list(path: impl AsRef<Path>) -> Result<Vec<AddressEntry>> {
+    let path = path.as_ref();
+    parse_address_list(&fs::read_to_string(path)?, ListFormat::from_path(path)?)
+}
+
+/// 导出地址列表到文件，格式按扩展名判断
+pub fn export_address_list(path: impl AsRef<Path>, entries: &[AddressEntry]) -> Result<()> {
+    let path = path.as_ref();
+    fs::write(path, format_address_list(entries, ListFormat::from_path(path)?)?)?;
+    Ok(())
+}
+
+/// 列表中的地址，用于设置 `SniperFilter` 等按地址集合过滤的规则
+pub fn addresses(entries: &[AddressEntry]) -> HashSet<Pubkey> {
+    entries.iter().map(|entry| entry.address).collect()
+}
+
+/// 地址集合转为列表，按地址排序，导出的文件在版本管理中保持稳定
+pub fn entries(addresses: &HashSet<Pubkey>) -> Vec<AddressEntry> {
+    let mut entries: Vec<AddressEntry> = addresses.iter().copied().map(AddressEntry::new).collect();
+    entries.sort_by_key(|entry| entry.address.to_string());
+    entries
+}
+
+/// 拆分一行 CSV，支持双引号包裹的字段及其中的 `""` 转义
+fn csv_fields(line: &str) -> Result<Vec<String>> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut chars = line.chars().peekable();
+    let mut quoted = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(anyhow!("Unterminated quote"));
+    }
+    fields.push(field.trim().to_string());
+    Ok(fields)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_address_list_round_trip() {
+        let entries = vec![
+            AddressEntry {
+                address: Pubkey::new_unique(),
+                name: Some("whale, \"big\"".to_string()),
+                group: Some("KOLs".to_string()),
+            },
+            AddressEntry::new(Pubkey::new_unique()),
+        ];
+        for format in [ListFormat::Json, ListFormat::Csv] {
+            let text = format_address_list(&entries, format).unwrap();
+            assert_eq!(parse_address_list(&text, format).unwrap(), entries, "{}", text);
+        }
+
+        // 表头、注释和空行可省略，JSON 可混用字符串和对象
+        let (first, second) = (entries[0].address, entries[1].address);
+        let csv = format!("# blacklist\n{}\n\n{},,insiders\n", first, second);
+        let parsed = parse_address_list(&csv, ListFormat::Csv).unwrap();
+        assert_eq!(parsed[1].group.as_deref(), Some("insiders"));
+        assert_eq!(parsed[1].name, None);
+        let json = format!("[\"{}\", {{\"address\": \"{}\", \"name\": \"dev\"}}]", first, second);
+        let parsed = parse_address_list(&json, ListFormat::Json).unwrap();
+        assert_eq!((parsed[0].address, parsed[1].name.as_deref()), (first, Some("dev")));
+
+        assert!(parse_address_list("not-a-key\n", ListFormat::Csv).is_err());
+        assert!(parse_address_list("[\"not-a-key\"]", ListFormat::Json).is_err());
+        assert!(ListFormat::from_path(Path::new("list.txt")).is_err());
+    }
+}
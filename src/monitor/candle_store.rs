@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use tokio::task::JoinHandle;
+
+use crate::monitor::candles::{Candle, CandleInterval};
+
+/// 写入失败时缓冲中最多保留的 K 线数量，超出时丢弃最早的
+const MAX_PENDING_ROWS: usize = 100_000;
+
+/// K 线持久化，`CandleAggregator` 在 K 线结束时写入，启动时加载最近的历史，
+/// 均线等按 K 线计算的指标重启后不需要重新积累
+#[async_trait::async_trait]
+pub trait CandleStore: Send + Sync {
+    /// 保存一根 K 线，同一代币、周期和开始时间以最后保存的为准；在处理成交时调用，不应阻塞
+    fn save(&self, mint: &Pubkey, interval: CandleInterval, candle: &Candle) -> Result<()>;
+
+    /// 该周期开始时间不早于 `since_ms` 的 K 线，按开始时间从早到晚排列
+    async fn load(&self, interval: CandleInterval, since_ms: i64) -> Result<Vec<(Pubkey, Candle)>>;
+}
+
+/// 内存存储，不跨进程保留，用于测试
+#[derive(Default)]
+pub struct MemoryCandleStore {
+    candles: Mutex<HashMap<(Pubkey, CandleInterval, i64), Candle>>,
+}
+
+#[async_trait::async_trait]
+impl CandleStore for MemoryCandleStore {
+    fn save(&self, mint: &Pubkey, interval: CandleInterval, candle: &Candle) -> Result<()> {
+        self.candles.lock().unwrap().insert((*mint, interval, candle.open_time_ms), *candle);
+        Ok(())
+    }
+
+    async fn load(&self, interval: CandleInterval, since_ms: i64) -> Result<Vec<(Pubkey, Candle)>> {
+        let mut candles: Vec<(Pubkey, Candle)> = self
+            .candles
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|((_, candle_interval, open_time_ms), _)| {
+                *candle_interval == interval && *open_time_ms >= since_ms
+            })
+            .map(|((mint, _, _), candle)| (*mint, *candle))
+            .collect();
+        candles.sort_by_key(|(_, candle)| candle.open_time_ms);
+        Ok(candles)
+    }
+}
+
+/// ClickHouse 连接配置
+#[derive(Debug, Clone)]
+pub struct ClickHouseConfig {
+    /// HTTP 接口地址，如 http://localhost:8123
+    pub url: String,
+    pub database: String,
+    pub table: String,
+    pub user: String,
+    pub password: String,
+}
+
+impl Default for ClickHouseConfig {
+    fn default() -> Self {
+        Self {
+            url: "http://localhost:8123".to_string(),
+            database: "default".to_string(),
+            table: "candles".to_string(),
+            user: "default".to_string(),
+            password: String::new(),
+        }
+    }
+}
+
+/// ClickHouse 中的一行 K 线
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct CandleRow {
+    mint: String,
+    interval_ms: i64,
+    open_time_ms: i64,
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+    volume: u64,
+    trades: u32,
+}
+
+impl CandleRow {
+    fn new(mint: &Pubkey, interval: CandleInterval, candle: &Candle) -> Self {
+        Self {
+            mint: mint.to_string(),
+            interval_ms: interval.millis(),
+            open_time_ms: candle.open_time_ms,
+            open: candle.open,
+            high: candle.high,
+            low: candle.low,
+            close: candle.close,
+            volume: candle.volume,
+            trades: candle.trades,
+        }
+    }
+
+    fn candle(&self) -> Result<(Pubkey, Candle)> {
+        let candle = Candle {
+            open_time_ms: self.open_time_ms,
+            open: self.open,
+            high: self.high,
+            low: self.low,
+            close: self.close,
+            volume: self.volume,
+            trades: self.trades,
+        };
+        Ok((self.mint.parse()?, candle))
+    }
+}
+
+/// ClickHouse 存储，通过 HTTP 接口读写
+///
+/// `save` 只写入内存缓冲，由 `flush` 或 `spawn_flush` 启动的后台任务批量插入。
+/// 表使用 ReplacingMergeTree，同一根 K 线保存多次时读取最后一次
+pub struct ClickHouseCandleStore {
+    config: ClickHouseConfig,
+    http_client: Client,
+    pending: Mutex<Vec<CandleRow>>,
+}
+
+impl ClickHouseCandleStore {
+    pub fn new(config: ClickHouseConfig) -> Self {
+        let http_client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .connect_timeout(Duration::from_secs(3))
+            .build()
+            .unwrap();
+        Self { config, http_client, pending: Mutex::new(Vec::new()) }
+    }
+
+    fn table(&self) -> String {
+        format!("{}.{}", self.config.database, self.config.table)
+    }
+
+    async fn query(&self, query: &str, body: String) -> Result<String> {
+        let response = self
+            .http_client
+            .post(&self.config.url)
+            .query(&[("query", query), ("output_format_json_quote_64bit_integers", "0")])
+            .header("X-ClickHouse-User", &self.config.user)
+            .header("X-ClickHouse-Key", &self.config.password)
+            .body(body)
+            .send()
+            .await?;
+        let status = response.status();
+        let text = response.text().await?;
+        if !status.is_success() {
+            return Err(anyhow!("ClickHouse 请求失败 ({}): {}", status, text.trim()));
+        }
+        Ok(text)
+    }
+
+    /// 创建 K 线表，已存在时不修改
+    pub async fn create_table(&self) -> Result<()> {
+        let query = format!(
+            "CREATE TABLE IF NOT EXISTS {} (mint String, interval_ms Int64, open_time_ms Int64, \
+             open Float64, high Float64, low Float64, close Float64, volume UInt64, trades UInt32) \
+             ENGINE = ReplacingMergeTree ORDER BY (interval_ms, mint, open_time_ms)",
+            self.table()
+        );
+        self.query(&query, String::new()).await.map(|_| ())
+    }
+
+    /// 插入缓冲中的 K 线，失败时放回缓冲，下次重试
+    pub async fn flush(&self) -> Result<()> {
+        let rows = std::mem::take(&mut *self.pending.lock().unwrap());
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let body =
+            rows.iter().map(serde_json::to_string).collect::<Result<Vec<_>, _>>()?.join("\n");
+        let query = format!("INSERT INTO {} FORMAT JSONEachRow", self.table());
+        if let Err(e) = self.query(&query, body).await {
+            let mut pending = self.pending.lock().unwrap();
+            let newer = std::mem::replace(&mut *pending, rows);
+            pending.extend(newer);
+            let overflow = pending.len().saturating_sub(MAX_PENDING_ROWS);
+            pending.drain(..overflow);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// 启动后台任务，每隔 `interval_ms` 毫秒插入缓冲中的 K 线，失败时打印错误
+    pub fn spawn_flush(self: &Arc<Self>, interval_ms: u64) -> JoinHandle<()> {
+        let store = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_millis(interval_ms.max(1)));
+            loop {
+                interval.tick().await;
+                if let Err(e) = store.flush().await {
+                    println!("警告: 写入 K 线失败: {}", e);
+                }
+            }
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl CandleStore for ClickHouseCandleStore {
+    fn save(&self, mint: &Pubkey, interval: CandleInterval, candle: &Candle) -> Result<()> {
+        let mut pending = self.pending.lock().unwrap();
+        if pending.len() >= MAX_PENDING_ROWS {
+            pending.remove(0);
+        }
+        pending.push(CandleRow::new(mint, interval, candle));
+        Ok(())
+    }
+
+    async fn load(&self, interval: CandleInterval, since_ms: i64) -> Result<Vec<(Pubkey, Candle)>> {
+        let query = format!(
+            "SELECT mint, interval_ms, open_time_ms, open, high, low, close, volume, trades FROM {} FINAL \
+             WHERE interval_ms = {} AND open_time_ms >= {} ORDER BY open_time_ms FORMAT JSONEachRow",
+            self.table(),
+            interval.millis(),
+            since_ms
+        );
+        let text = self.query(&query, String::new()).await?;
+        text.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str::<CandleRow>(line)?.candle())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clickhouse_row_round_trip() {
+        let mint = Pubkey::new_unique();
+        let candle = Candle {
+            open_time_ms: 60_000,
+            open: 1.0,
+            high: 2.0,
+            low: 0.5,
+            close: 1.5,
+            volume: u64::MAX,
+            trades: 3,
+        };
+        let row = CandleRow::new(&mint, CandleInterval::OneMinute, &candle);
+        assert_eq!(row.interval_ms, 60_000);
+
+        // 查询时关闭了 64 位整数加引号，volume 按数字读取
+        let line = serde_json::to_string(&row).unwrap();
+        assert!(line.contains(&format!("\"volume\":{}", u64::MAX)));
+        let parsed: CandleRow = serde_json::from_str(&line).unwrap();
+        assert_eq!(parsed.candle().unwrap(), (mint, candle));
+    }
+}
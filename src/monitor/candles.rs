@@ -0,0 +1,351 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, RwLock};
+
+use anyhow::Result;
+use chrono::Utc;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::monitor::candle_store::CandleStore;
+use crate::monitor::large_swap::SwapObservation;
+use crate::solana_streamer_sdk::streaming::event_parser::UnifiedEvent;
+
+/// K 线周期
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CandleInterval {
+    OneSecond,
+    FifteenSeconds,
+    OneMinute,
+}
+
+impl CandleInterval {
+    pub const ALL: [CandleInterval; 3] = [Self::OneSecond, Self::FifteenSeconds, Self::OneMinute];
+
+    pub fn millis(&self) -> i64 {
+        match self {
+            Self::OneSecond => 1_000,
+            Self::FifteenSeconds => 15_000,
+            Self::OneMinute => 60_000,
+        }
+    }
+
+    /// 时间戳所在 K 线的开始时间（毫秒）
+    pub fn open_time(&self, timestamp_ms: i64) -> i64 {
+        timestamp_ms.div_euclid(self.millis()) * self.millis()
+    }
+}
+
+/// 一根 K 线，价格为 lamports / 代币最小单位，与 `PriceMonitor::observe` 一致
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Candle {
+    /// 开始时间（Unix 毫秒）
+    pub open_time_ms: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    /// 成交量（lamports），买卖合计
+    pub volume: u64,
+    pub trades: u32,
+}
+
+impl Candle {
+    fn new(open_time_ms: i64, price: f64, volume: u64) -> Self {
+        Self { open_time_ms, open: price, high: price, low: price, close: price, volume, trades: 1 }
+    }
+
+    /// 迟到的成交不改变收盘价
+    fn update(&mut self, price: f64, volume: u64, late: bool) {
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        if !late {
+            self.close = price;
+        }
+        self.volume += volume;
+        self.trades += 1;
+    }
+}
+
+/// K 线聚合配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct CandleConfig {
+    pub intervals: Vec<CandleInterval>,
+    /// 每个代币每个周期保留的 K 线数量，超出时丢弃最早的
+    pub capacity: usize,
+    /// 最多统计的代币数量，超出时丢弃最久没有成交的代币
+    pub max_tokens: usize,
+}
+
+impl Default for CandleConfig {
+    fn default() -> Self {
+        Self { intervals: CandleInterval::ALL.to_vec(), capacity: 500, max_tokens: 1_000 }
+    }
+}
+
+/// 单个代币各周期的 K 线
+#[derive(Debug, Default)]
+struct TokenCandles {
+    series: HashMap<CandleInterval, VecDeque<Candle>>,
+    /// 最近一次成交的时间，用于淘汰代币
+    last_update_ms: i64,
+}
+
+/// 把流事件中的成交聚合为各代币的 K 线，内存占用有上限，可在此基础上计算均线、RSI 等指标
+///
+/// 没有成交的周期不生成 K 线，计算指标时需自行处理缺口。
+/// 早于该代币最近一笔成交的迟到成交计入对应的已有 K 线但不改变收盘价，早于保留范围的丢弃
+pub struct CandleAggregator {
+    config: CandleConfig,
+    tokens: RwLock<HashMap<Pubkey, TokenCandles>>,
+    store: Option<Arc<dyn CandleStore>>,
+}
+
+impl CandleAggregator {
+    pub fn new(config: CandleConfig) -> Self {
+        Self { config, tokens: RwLock::new(HashMap::new()), store: None }
+    }
+
+    /// 从存储加载各周期最近 `capacity` 根 K 线，之后每根 K 线结束时写入存储，
+    /// 迟到成交更新已结束的 K 线时再次写入
+    pub async fn with_store(config: CandleConfig, store: Arc<dyn CandleStore>) -> Result<Self> {
+        let now = Utc::now().timestamp_millis();
+        let mut history = Vec::new();
+        for interval in &config.intervals {
+            let since = interval.open_time(now) - interval.millis() * config.capacity as i64;
+            history.push((*interval, store.load(*interval, since).await?));
+        }
+        let aggregator = Self { config, tokens: RwLock::new(HashMap::new()), store: Some(store) };
+        let capacity = aggregator.config.capacity;
+        let mut tokens = aggregator.tokens.write().unwrap();
+        for (interval, candles) in history {
+            for (mint, candle) in candles {
+                let token = tokens.entry(mint).or_default();
+                token.last_update_ms = token.last_update_ms.max(candle.open_time_ms);
+                let series = token.series.entry(interval).or_default();
+                if series.back().is_some_and(|last| last.open_time_ms >= candle.open_time_ms) {
+                    continue;
+                }
+                series.push_back(candle);
+                if series.len() > capacity {
+                    series.pop_front();
+                }
+            }
+        }
+        while tokens.len() > aggregator.config.max_tokens {
+            match stale_token(&tokens) {
+                Some(stale) => tokens.remove(&stale),
+                None => break,
+            };
+        }
+        drop(tokens);
+        Ok(aggregator)
+    }
+
+    /// 处理流事件，按程序收到事件的时间归入 K 线
+    pub fn on_event(&self, event: &dyn UnifiedEvent) {
+        if let Some(swap) = SwapObservation::from_event(event) {
+            self.observe_at(&swap, event.program_received_time_ms());
+        }
+    }
+
+    /// 按当前时间记录一笔成交
+    pub fn observe(&self, swap: &SwapObservation) {
+        self.observe_at(swap, Utc::now().timestamp_millis());
+    }
+
+    /// 按 `timestamp_ms`（Unix 毫秒）记录一笔成交
+    pub fn observe_at(&self, swap: &SwapObservation, timestamp_ms: i64) {
+        if swap.token_amount == 0 || self.config.capacity == 0 {
+            return;
+        }
+        let price = swap.sol_amount as f64 / swap.token_amount as f64;
+        // 已结束或被迟到成交更新的 K 线，释放锁后写入存储
+        let mut finished = Vec::new();
+        let mut tokens = self.tokens.write().unwrap();
+        if !tokens.contains_key(&swap.mint) && tokens.len() >= self.config.max_tokens {
+            if let Some(stale) = stale_token(&tokens) {
+                tokens.remove(&stale);
+            }
+            if tokens.len() >= self.config.max_tokens {
+                return;
+            }
+        }
+        let token = tokens.entry(swap.mint).or_default();
+        let late = timestamp_ms < token.last_update_ms;
+        token.last_update_ms = token.last_update_ms.max(timestamp_ms);
+        for interval in &self.config.intervals {
+            let open_time = interval.open_time(timestamp_ms);
+            let candles = token.series.entry(*interval).or_default();
+            match candles.back() {
+                Some(last) if last.open_time_ms >= open_time => {
+                    let last_open_time = last.open_time_ms;
+                    if let Some(candle) =
+                        candles.iter_mut().rev().find(|candle| candle.open_time_ms == open_time)
+                    {
+                        candle.update(price, swap.sol_amount, late);
+                        if open_time < last_open_time {
+                            finished.push((*interval, *candle));
+                        }
+                    }
+                }
+                Some(last) => {
+                    finished.push((*interval, *last));
+                    candles.push_back(Candle::new(open_time, price, swap.sol_amount));
+                    if candles.len() > self.config.capacity {
+                        candles.pop_front();
+                    }
+                }
+                None => {
+                    candles.push_back(Candle::new(open_time, price, swap.sol_amount));
+                    if candles.len() > self.config.capacity {
+                        candles.pop_front();
+                    }
+                }
+            }
+        }
+        drop(tokens);
+        for (interval, candle) in finished {
+            self.save(&swap.mint, interval, &candle);
+        }
+    }
+
+    fn save(&self, mint: &Pubkey, interval: CandleInterval, candle: &Candle) {
+        if let Some(Err(e)) = self.store.as_ref().map(|store| store.save(mint, interval, candle)) {
+            eprintln!("保存 K 线 {} 失败: {}", mint, e);
+        }
+    }
+
+    /// 把各代币尚未结束的 K 线写入存储，停止前调用，重启后可恢复到最新一根
+    pub fn save_open_candles(&self) {
+        let open: Vec<(Pubkey, CandleInterval, Candle)> =
+            self.tokens
+                .read()
+                .unwrap()
+                .iter()
+                .flat_map(|(mint, token)| {
+                    token.series.iter().filter_map(|(interval, candles)| {
+                        Some((*mint, *interval, *candles.back()?))
+                    })
+                })
+                .collect();
+        for (mint, interval, candle) in open {
+            self.save(&mint, interval, &candle);
+        }
+    }
+
+    /// 代币在该周期的 K 线，按时间从早到晚排列，最后一根可能尚未结束
+    pub fn candles(&self, mint: &Pubkey, interval: CandleInterval) -> Vec<Candle> {
+        self.tokens
+            .read()
+            .unwrap()
+            .get(mint)
+            .and_then(|token| token.series.get(&interval))
+            .map_or_else(Vec::new, |candles| candles.iter().copied().collect())
+    }
+
+    /// 最近 `count` 根 K 线的收盘价，按时间从早到晚排列
+    pub fn closes(&self, mint: &Pubkey, interval: CandleInterval, count: usize) -> Vec<f64> {
+        let candles = self.candles(mint, interval);
+        candles[candles.len().saturating_sub(count)..].iter().map(|candle| candle.close).collect()
+    }
+
+    /// 最新一根 K 线
+    pub fn latest(&self, mint: &Pubkey, interval: CandleInterval) -> Option<Candle> {
+        self.tokens.read().unwrap().get(mint)?.series.get(&interval)?.back().copied()
+    }
+
+    /// 停止统计代币并清除其 K 线
+    pub fn remove(&self, mint: &Pubkey) {
+        self.tokens.write().unwrap().remove(mint);
+    }
+}
+
+/// 最久没有成交的代币
+fn stale_token(tokens: &HashMap<Pubkey, TokenCandles>) -> Option<Pubkey> {
+    tokens.iter().min_by_key(|(_, token)| token.last_update_ms).map(|(mint, _)| *mint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn swap(mint: Pubkey, sol_amount: u64) -> SwapObservation {
+        SwapObservation { mint, sol_amount, token_amount: 100, ..SwapObservation::for_test() }
+    }
+
+    #[test]
+    fn test_candles_aggregate_per_interval() {
+        let aggregator = CandleAggregator::new(CandleConfig {
+            intervals: vec![CandleInterval::OneSecond, CandleInterval::OneMinute],
+            capacity: 2,
+            max_tokens: 1,
+        });
+        let (mint, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+        aggregator.observe_at(&swap(mint, 100), 60_000);
+        aggregator.observe_at(&swap(mint, 300), 60_400);
+        aggregator.observe_at(&swap(mint, 200), 60_900);
+        aggregator.observe_at(&swap(mint, 400), 61_000);
+        // 迟到的成交更新对应 K 线的最高价和成交量，不改变收盘价
+        aggregator.observe_at(&swap(mint, 500), 60_950);
+
+        assert_eq!(
+            aggregator.candles(&mint, CandleInterval::OneSecond),
+            vec![
+                Candle {
+                    open_time_ms: 60_000,
+                    open: 1.0,
+                    high: 5.0,
+                    low: 1.0,
+                    close: 2.0,
+                    volume: 1_100,
+                    trades: 4,
+                },
+                Candle::new(61_000, 4.0, 400),
+            ]
+        );
+        let minute = aggregator.latest(&mint, CandleInterval::OneMinute).unwrap();
+        assert_eq!((minute.open, minute.high, minute.close, minute.volume), (1.0, 5.0, 4.0, 1_500));
+        assert!(aggregator.candles(&mint, CandleInterval::FifteenSeconds).is_empty());
+
+        // 超出容量时丢弃最早的 K 线
+        aggregator.observe_at(&swap(mint, 600), 62_000);
+        assert_eq!(aggregator.closes(&mint, CandleInterval::OneSecond, 5), vec![4.0, 6.0]);
+        assert_eq!(aggregator.closes(&mint, CandleInterval::OneSecond, 1), vec![6.0]);
+
+        // 超出代币数量时丢弃最久没有成交的代币
+        aggregator.observe_at(&swap(other, 100), 63_000);
+        assert!(aggregator.latest(&mint, CandleInterval::OneSecond).is_none());
+        assert!(aggregator.latest(&other, CandleInterval::OneSecond).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_candles_persisted_and_restored() {
+        use crate::monitor::candle_store::MemoryCandleStore;
+
+        let store: Arc<dyn CandleStore> = Arc::new(MemoryCandleStore::default());
+        let config = CandleConfig {
+            intervals: vec![CandleInterval::OneSecond],
+            capacity: 3,
+            max_tokens: 10,
+        };
+        let now = CandleInterval::OneSecond.open_time(Utc::now().timestamp_millis());
+        let mint = Pubkey::new_unique();
+
+        let aggregator = CandleAggregator::with_store(config.clone(), store.clone()).await.unwrap();
+        aggregator.observe_at(&swap(mint, 100), now - 3_000);
+        aggregator.observe_at(&swap(mint, 200), now - 2_000);
+        // 只有已结束的 K 线写入存储，迟到成交更新已结束的 K 线时再次写入
+        assert_eq!(store.load(CandleInterval::OneSecond, 0).await.unwrap().len(), 1);
+        aggregator.observe_at(&swap(mint, 500), now - 2_500);
+        aggregator.observe_at(&swap(mint, 300), now - 1_000);
+        aggregator.save_open_candles();
+        let saved = store.load(CandleInterval::OneSecond, 0).await.unwrap();
+        assert_eq!(saved.len(), 3);
+        assert_eq!(saved[0].1.high, 5.0);
+
+        // 重启后恢复最近 capacity 根 K 线，新的成交接在其后
+        let restored = CandleAggregator::with_store(config, store).await.unwrap();
+        assert_eq!(restored.closes(&mint, CandleInterval::OneSecond, 10), vec![1.0, 2.0, 3.0]);
+        restored.observe_at(&swap(mint, 400), now);
+        assert_eq!(restored.closes(&mint, CandleInterval::OneSecond, 10), vec![2.0, 3.0, 4.0]);
+    }
+}
@@ -0,0 +1,343 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::common::{ExposureTracker, TokenMetadataCache};
+use crate::monitor::large_swap::SwapObservation;
+use crate::monitor::position_store::PositionStore;
+use crate::monitor::wallet_history::WalletStats;
+use crate::monitor::wallet_monitor::copy_protocol_params;
+use crate::solana_streamer_sdk::streaming::event_parser::UnifiedEvent;
+use crate::trading::common::FillReport;
+use crate::trading::core::traits::{ProtocolParams, ProtocolParamsBox};
+use crate::trading::factory::DexType;
+
+/// 跟卖配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CopySellConfig {
+    /// 为 false 时只更新持仓，不生成跟卖
+    pub enabled: bool,
+    /// 按目标钱包成交价估算的所得低于该值（lamports）时不跟卖
+    pub min_proceeds: u64,
+    /// 相对目标钱包成交价允许的滑点（基点），用于计算 `min_sol_out`
+    pub slippage_basis_points: u64,
+}
+
+impl Default for CopySellConfig {
+    fn default() -> Self {
+        Self { enabled: true, min_proceeds: 1_000_000, slippage_basis_points: 1_000 }
+    }
+}
+
+/// 跟卖指令，由 `SolanaTrade::copy_sell` 执行
+#[derive(Debug, Clone)]
+pub struct CopySellOrder {
+    pub dex_type: DexType,
+    pub mint: Pubkey,
+    /// 卖出的代币数量
+    pub token_amount: u64,
+    /// 目标钱包卖出的持仓比例（基点）
+    pub fraction_basis_points: u64,
+    /// 最少获得的 SOL（lamports）
+    pub min_sol_out: u64,
+    /// 代币创建者，PumpFun / PumpSwap 用于计算创建者费用账户，事件不含创建者时为 None
+    pub creator: Option<Pubkey>,
+    /// 触发跟卖的目标钱包交易
+    pub source: SwapObservation,
+    /// 由目标钱包卖出事件构造的协议参数，包含池子地址和交易后的储备
+    protocol_params: Option<ProtocolParamsBox>,
+    /// 卖出全部跟单持仓时为跟买敞口，卖出成功后清除该代币的敞口
+    exposure: Option<Arc<ExposureTracker>>,
+}
+
+impl CopySellOrder {
+    /// 跟卖使用的协议参数，由 `CopySellMirror::on_event` 处理的事件构造；
+    /// 只经过 `on_swap` 的交易没有参数，卖出时通过 RPC 查询
+    pub fn protocol_params(&self) -> Option<Box<dyn ProtocolParams>> {
+        self.protocol_params.as_ref().map(|params| params.0.clone())
+    }
+
+    /// 卖出成功后调用，卖出全部跟单持仓时清除该代币的跟买敞口
+    pub fn close_exposure(&self) {
+        if let Some(exposure) = &self.exposure {
+            exposure.close(&self.mint);
+        }
+    }
+}
+
+/// 跟卖：目标钱包卖出某代币持仓的 X% 时，按相同比例卖出跟单买入的持仓
+///
+/// 只跟踪 `init_target` / `add_target` 添加的目标钱包，其他钱包的交易被忽略；
+/// 目标钱包持仓由流事件（及回填）维护，跟单持仓由 `record_buy` 维护；
+/// 生成跟卖指令时立即扣减跟单持仓，卖出失败时用 `record_buy` 加回
+pub struct CopySellMirror {
+    config: CopySellConfig,
+    targets: Mutex<HashMap<Pubkey, WalletStats>>,
+    positions: Mutex<HashMap<Pubkey, u64>>,
+    store: Option<Arc<dyn PositionStore>>,
+    exposure: Option<Arc<ExposureTracker>>,
+}
+
+impl CopySellMirror {
+    pub fn new(config: CopySellConfig) -> Self {
+        Self {
+            config,
+            targets: Mutex::new(HashMap::new()),
+            positions: Mutex::new(HashMap::new()),
+            store: None,
+            exposure: None,
+        }
+    }
+
+    /// 从存储中恢复跟单持仓，之后的修改都写入存储；目标钱包持仓需重新用 `init_target` 初始化
+    pub fn with_store(config: CopySellConfig, store: Arc<dyn PositionStore>) -> Result<Self> {
+        Ok(Self {
+            config,
+            targets: Mutex::new(HashMap::new()),
+            positions: Mutex::new(store.load_copied()?),
+            store: Some(store),
+            exposure: None,
+        })
+    }
+
+    /// 卖出全部跟单持仓的跟卖指令带上 `exposure`，`SolanaTrade::copy_sell` 成功后清除该代币的敞口，
+    /// 通常传入 `WalletMonitor::shared_exposure`
+    pub fn with_exposure(mut self, exposure: Arc<ExposureTracker>) -> Self {
+        self.exposure = Some(exposure);
+        self
+    }
+
+    /// 写入存储失败只打印错误，不影响跟卖
+    fn persist(&self, mint: &Pubkey, token_amount: u64) {
+        if let Some(Err(e)) = self.store.as_ref().map(|store| store.save_copied(mint, token_amount)) {
+            eprintln!("保存跟单持仓 {} 失败: {}", mint, e);
+        }
+    }
+
+    /// 用回填得到的统计初始化目标钱包持仓
+    pub fn init_target(&self, wallet: Pubkey, stats: WalletStats) {
+        self.targets.lock().unwrap().insert(wallet, stats);
+    }
+
+    /// 添加目标钱包，持仓从之后的交易开始累计；已添加的钱包保留原有持仓
+    pub fn add_target(&self, wallet: Pubkey) {
+        self.targets.lock().unwrap().entry(wallet).or_default();
+    }
+
+    /// 移除目标钱包及其持仓，之后该钱包的卖出不再跟卖
+    pub fn remove_target(&self, wallet: &Pubkey) {
+        self.targets.lock().unwrap().remove(wallet);
+    }
+
+    /// 记录跟单买入的代币数量
+    pub fn record_buy(&self, mint: Pubkey, token_amount: u64) {
+        let mut positions = self.positions.lock().unwrap();
+        let position = positions.entry(mint).or_default();
+        *position += token_amount;
+        self.persist(&mint, *position);
+    }
+
+    /// 按跟买的实际成交结果记录持仓，卖出的成交结果会被忽略
+    pub fn record_fill(&self, mint: Pubkey, fill: &FillReport) {
+        if fill.tokens_received > 0 {
+            self.record_buy(mint, fill.tokens_received as u64);
+        }
+    }
+
+    /// 跟单持仓
+    pub fn position(&self, mint: &Pubkey) -> u64 {
+        self.positions.lock().unwrap().get(mint).copied().unwrap_or_default()
+    }
+
+    /// 处理流事件，需要跟卖时返回跟卖指令，指令带有事件中的创建者和协议参数，可直接在订阅回调中调用
+    pub fn on_event(&self, event: &dyn UnifiedEvent) -> Option<CopySellOrder> {
+        let swap = SwapObservation::from_event(event)?;
+        let mut order = self.on_swap(&swap)?;
+        if let Some((creator, protocol_params)) = copy_protocol_params(event, &swap.mint) {
+            order.creator = creator;
+            order.protocol_params = Some(ProtocolParamsBox(protocol_params));
+        }
+        Some(order)
+    }
+
+    /// 处理目标钱包的一笔交易，需要跟卖时返回跟卖指令，非目标钱包的交易直接返回 None
+    pub fn on_swap(&self, swap: &SwapObservation) -> Option<CopySellOrder> {
+        let target_position = {
+            let mut targets = self.targets.lock().unwrap();
+            let stats = targets.get_mut(&swap.user)?;
+            let target_position = stats.positions.get(&swap.mint).copied().unwrap_or_default();
+            stats.record(swap);
+            target_position
+        };
+        if swap.is_buy || !self.config.enabled || target_position == 0 || swap.token_amount == 0 {
+            return None;
+        }
+
+        let mut positions = self.positions.lock().unwrap();
+        let position = positions.get(&swap.mint).copied().unwrap_or_default();
+        if position == 0 {
+            return None;
+        }
+        let fraction_basis_points = (swap.token_amount.min(target_position) as u128 * 10_000
+            / target_position as u128) as u64;
+        // 目标钱包清仓时跟单持仓也全部卖出，避免取整留下零头
+        let token_amount = if fraction_basis_points >= 10_000 {
+            position
+        } else {
+            (position as u128 * fraction_basis_points as u128 / 10_000) as u64
+        };
+        let expected = (token_amount as u128 * swap.sol_amount as u128 / swap.token_amount as u128) as u64;
+        if token_amount == 0 || expected < self.config.min_proceeds {
+            println!(
+                "跟卖 {} 预计所得 {} lamports 低于下限，跳过",
+                TokenMetadataCache::get_instance().label(&swap.mint),
+                expected
+            );
+            return None;
+        }
+
+        let remaining = position - token_amount;
+        if remaining == 0 {
+            positions.remove(&swap.mint);
+        } else {
+            positions.insert(swap.mint, remaining);
+        }
+        self.persist(&swap.mint, remaining);
+        Some(CopySellOrder {
+            dex_type: swap.dex_type.clone(),
+            mint: swap.mint,
+            token_amount,
+            fraction_basis_points,
+            min_sol_out: (expected as u128 * 10_000u128.saturating_sub(self.config.slippage_basis_points as u128)
+                / 10_000) as u64,
+            creator: None,
+            source: swap.clone(),
+            protocol_params: None,
+            exposure: if remaining == 0 { self.exposure.clone() } else { None },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn swap(user: Pubkey, mint: Pubkey, is_buy: bool, token_amount: u64, sol_amount: u64) -> SwapObservation {
+        SwapObservation {
+            dex_type: DexType::PumpFun,
+            mint,
+            user,
+            is_buy,
+            sol_amount,
+            token_amount,
+            signature: "sig".to_string(),
+            ..SwapObservation::for_test()
+        }
+    }
+
+    #[test]
+    fn test_mirrors_sell_proportion() {
+        let (target, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let exposure = Arc::new(ExposureTracker::new(Default::default()));
+        exposure.reserve(&mint, 1_000_000_000).unwrap();
+        let mirror = CopySellMirror::new(CopySellConfig::default()).with_exposure(exposure.clone());
+        mirror.add_target(target);
+        assert!(mirror.on_swap(&swap(target, mint, true, 1_000, 1_000_000_000)).is_none());
+        mirror.record_buy(mint, 500);
+
+        // 目标卖出 40%，跟卖 200，按目标成交价估算所得 0.2 SOL
+        let order = mirror.on_swap(&swap(target, mint, false, 400, 400_000_000)).unwrap();
+        assert_eq!(order.fraction_basis_points, 4_000);
+        assert_eq!(order.token_amount, 200);
+        assert_eq!(order.min_sol_out, 180_000_000);
+        assert_eq!(mirror.position(&mint), 300);
+        // 部分卖出不清除敞口
+        order.close_exposure();
+        assert_eq!(exposure.deployed(&mint), 1_000_000_000);
+
+        // 目标清仓，跟单持仓全部卖出，成功后清除敞口
+        let order = mirror.on_swap(&swap(target, mint, false, 600, 600_000_000)).unwrap();
+        assert_eq!(order.token_amount, 300);
+        assert_eq!(mirror.position(&mint), 0);
+        assert_eq!(exposure.deployed(&mint), 1_000_000_000);
+        order.close_exposure();
+        assert_eq!(exposure.deployed(&mint), 0);
+    }
+
+    #[test]
+    fn test_order_from_event_carries_creator_and_params() {
+        use crate::solana_streamer_sdk::streaming::event_parser::protocols::pumpfun::PumpFunTradeEvent;
+        use crate::trading::core::params::PumpFunParams;
+
+        let (target, mint, creator) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mirror = CopySellMirror::new(CopySellConfig::default());
+        mirror.add_target(target);
+        mirror.record_buy(mint, 500);
+        let event = |is_buy: bool| PumpFunTradeEvent {
+            mint,
+            creator,
+            user: target,
+            is_buy,
+            sol_amount: 1_000_000_000,
+            token_amount: 1_000,
+            virtual_sol_reserves: 40_000_000_000,
+            virtual_token_reserves: 1_000_000_000_000,
+            ..Default::default()
+        };
+        assert!(mirror.on_event(&event(true)).is_none());
+
+        let order = mirror.on_event(&event(false)).unwrap();
+        assert_eq!(order.creator, Some(creator));
+        let params = order.protocol_params().unwrap();
+        let bonding_curve = params.as_any().downcast_ref::<PumpFunParams>().unwrap().bonding_curve.clone();
+        assert_eq!(bonding_curve.unwrap().creator, creator);
+        // on_swap 不带协议参数，卖出时再查询
+        assert!(mirror.on_swap(&swap(target, mint, true, 1_000, 1_000_000_000)).is_none());
+        mirror.record_buy(mint, 500);
+        let order = mirror.on_swap(&swap(target, mint, false, 1_000, 1_000_000_000)).unwrap();
+        assert!(order.creator.is_none() && order.protocol_params().is_none());
+    }
+
+    #[test]
+    fn test_guards() {
+        let (target, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mirror = CopySellMirror::new(CopySellConfig { min_proceeds: 100_000_000, ..Default::default() });
+        mirror.add_target(target);
+        mirror.record_buy(mint, 500);
+
+        // 目标钱包没有已知持仓
+        assert!(mirror.on_swap(&swap(target, mint, false, 400, 400_000_000)).is_none());
+
+        mirror.on_swap(&swap(target, mint, true, 1_000, 1_000_000_000));
+        // 预计所得 0.05 SOL 低于下限
+        assert!(mirror.on_swap(&swap(target, mint, false, 100, 100_000_000)).is_none());
+        assert_eq!(mirror.position(&mint), 500);
+
+        let disabled = CopySellMirror::new(CopySellConfig { enabled: false, ..Default::default() });
+        disabled.add_target(target);
+        disabled.record_buy(mint, 500);
+        disabled.on_swap(&swap(target, mint, true, 1_000, 1_000_000_000));
+        assert!(disabled.on_swap(&swap(target, mint, false, 1_000, 1_000_000_000)).is_none());
+    }
+
+    #[test]
+    fn test_ignores_wallets_that_are_not_targets() {
+        let (target, other, mint) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mirror = CopySellMirror::new(CopySellConfig::default());
+        mirror.init_target(target, WalletStats::default());
+        mirror.record_buy(mint, 500);
+
+        // 流中其他钱包的交易不记录持仓，也不触发跟卖
+        mirror.on_swap(&swap(other, mint, true, 1_000, 1_000_000_000));
+        assert!(mirror.on_swap(&swap(other, mint, false, 1_000, 1_000_000_000)).is_none());
+        assert_eq!(mirror.targets.lock().unwrap().len(), 1);
+
+        mirror.on_swap(&swap(target, mint, true, 1_000, 1_000_000_000));
+        mirror.remove_target(&target);
+        assert!(mirror.on_swap(&swap(target, mint, false, 1_000, 1_000_000_000)).is_none());
+        assert!(mirror.targets.lock().unwrap().is_empty());
+        assert_eq!(mirror.position(&mint), 500);
+    }
+}
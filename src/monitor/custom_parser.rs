@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use chrono::Utc;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+
+use crate::monitor::large_swap::SwapObservation;
+use crate::solana_streamer_sdk::streaming::event_parser::UnifiedEvent;
+
+/// 自定义程序的事件解析器，用于 streamer 尚未支持的发射台等程序
+///
+/// 事件类型实现 `UnifiedEvent` 即可，可使用 `solana_streamer_sdk::impl_unified_event!`，
+/// `event_type` 取 `EventType::Unknown`
+pub trait CustomEventParser: Send + Sync {
+    /// 解析器名称，如发射台名称
+    fn name(&self) -> &str;
+
+    /// 解析的程序
+    fn program_id(&self) -> Pubkey;
+
+    /// 解析一条该程序的指令，`accounts` 为指令按顺序引用的账户
+    fn parse_instruction(
+        &self,
+        accounts: &[Pubkey],
+        data: &[u8],
+        signature: &str,
+        slot: u64,
+        program_received_time_ms: i64,
+    ) -> Vec<Box<dyn UnifiedEvent>>;
+
+    /// 把本解析器产生的交易事件转换为 `SwapObservation`，使各监控的 `on_event` 能够处理；
+    /// 不是交易或不是本解析器的事件返回 None。
+    /// SDK 无法交易的程序，`dex_type` 可取代币毕业后迁移到的 DEX
+    fn swap(&self, _event: &dyn UnifiedEvent) -> Option<SwapObservation> {
+        None
+    }
+}
+
+static CUSTOM_EVENT_PARSERS: OnceLock<Arc<CustomEventParsers>> = OnceLock::new();
+
+/// 自定义事件解析器注册表
+///
+/// 注册后，`parse_transaction` 把原始交易中这些程序的顶层指令解析为流事件，
+/// 交给与 streamer 事件相同的回调处理；`SwapObservation::from_event` 也会查询已注册的解析器，
+/// 因此 `LargeSwapDetector`、`WalletMonitor`、`PriceMonitor`、`CandleAggregator` 等
+/// 无需改动即可处理自定义程序的交易，`backfill_wallet` 也会用这些解析器解析历史交易。
+///
+/// streamer 的订阅回调（包括 `WalletMonitor::start_shredstream`）只交付已解析的事件，
+/// 不含原始交易；需要实时解析自定义程序时，应自行订阅原始交易（gRPC、ShredStream 或 RPC），
+/// 把 `parse_transaction` 的结果交给各监控的 `on_event`
+pub struct CustomEventParsers {
+    parsers: RwLock<HashMap<Pubkey, Arc<dyn CustomEventParser>>>,
+}
+
+impl CustomEventParsers {
+    /// 获取 CustomEventParsers 单例实例
+    pub fn get_instance() -> Arc<CustomEventParsers> {
+        CUSTOM_EVENT_PARSERS
+            .get_or_init(|| Arc::new(CustomEventParsers { parsers: RwLock::new(HashMap::new()) }))
+            .clone()
+    }
+
+    /// 注册解析器，同一程序已有解析器时替换并返回旧的
+    pub fn register(
+        &self,
+        parser: Arc<dyn CustomEventParser>,
+    ) -> Option<Arc<dyn CustomEventParser>> {
+        self.parsers.write().unwrap().insert(parser.program_id(), parser)
+    }
+
+    /// 移除程序的解析器
+    pub fn unregister(&self, program_id: &Pubkey) -> Option<Arc<dyn CustomEventParser>> {
+        self.parsers.write().unwrap().remove(program_id)
+    }
+
+    /// 已注册的程序
+    pub fn programs(&self) -> Vec<Pubkey> {
+        self.parsers.read().unwrap().keys().copied().collect()
+    }
+
+    /// 解析交易中已注册程序的所有顶层指令，`program_received_time_ms` 取当前时间
+    ///
+    /// 只使用交易的静态账户，引用地址查找表账户的指令会被跳过
+    pub fn parse_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+        slot: u64,
+    ) -> Vec<Box<dyn UnifiedEvent>> {
+        let parsers = self.parsers.read().unwrap();
+        if parsers.is_empty() {
+            return Vec::new();
+        }
+        let signature = transaction.signatures.first().map(|s| s.to_string()).unwrap_or_default();
+        let received_ms = Utc::now().timestamp_millis();
+        let account_keys = transaction.message.static_account_keys();
+
+        let mut events = Vec::new();
+        for ix in transaction.message.instructions() {
+            let Some(parser) = account_keys
+                .get(ix.program_id_index as usize)
+                .and_then(|program_id| parsers.get(program_id))
+            else {
+                continue;
+            };
+            let accounts: Option<Vec<Pubkey>> = ix
+                .accounts
+                .iter()
+                .map(|index| account_keys.get(*index as usize).copied())
+                .collect();
+            if let Some(accounts) = accounts {
+                events.extend(parser.parse_instruction(
+                    &accounts,
+                    &ix.data,
+                    &signature,
+                    slot,
+                    received_ms,
+                ));
+            }
+        }
+        events
+    }
+
+    /// 由已注册的解析器把事件转换为 `SwapObservation`
+    pub fn swap(&self, event: &dyn UnifiedEvent) -> Option<SwapObservation> {
+        self.parsers.read().unwrap().values().find_map(|parser| parser.swap(event))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+    use solana_sdk::message::{Message, VersionedMessage};
+
+    use crate::solana_streamer_sdk::streaming::event_parser::common::{EventMetadata, EventType};
+    use crate::trading::factory::DexType;
+
+    #[derive(Debug, Clone, Default)]
+    struct LaunchpadBuyEvent {
+        metadata: EventMetadata,
+        mint: Pubkey,
+        user: Pubkey,
+        sol_amount: u64,
+    }
+
+    crate::solana_streamer_sdk::impl_unified_event!(LaunchpadBuyEvent,);
+
+    struct LaunchpadParser {
+        program_id: Pubkey,
+    }
+
+    impl CustomEventParser for LaunchpadParser {
+        fn name(&self) -> &str {
+            "launchpad"
+        }
+
+        fn program_id(&self) -> Pubkey {
+            self.program_id
+        }
+
+        fn parse_instruction(
+            &self,
+            accounts: &[Pubkey],
+            data: &[u8],
+            signature: &str,
+            slot: u64,
+            program_received_time_ms: i64,
+        ) -> Vec<Box<dyn UnifiedEvent>> {
+            let Some(sol_amount) = data.try_into().ok().map(u64::from_le_bytes) else {
+                return Vec::new();
+            };
+            let metadata = EventMetadata {
+                signature: signature.to_string(),
+                slot,
+                program_received_time_ms,
+                event_type: EventType::Unknown,
+                program_id: self.program_id,
+                ..Default::default()
+            };
+            vec![Box::new(LaunchpadBuyEvent {
+                metadata,
+                mint: accounts[1],
+                user: accounts[0],
+                sol_amount,
+            })]
+        }
+
+        fn swap(&self, event: &dyn UnifiedEvent) -> Option<SwapObservation> {
+            let e = event.as_any().downcast_ref::<LaunchpadBuyEvent>()?;
+            Some(SwapObservation {
+                dex_type: DexType::PumpSwap,
+                pool: Pubkey::default(),
+                mint: e.mint,
+                user: e.user,
+                is_buy: true,
+                sol_amount: e.sol_amount,
+                token_amount: 1_000,
+                sol_reserve: None,
+                signature: e.metadata.signature.clone(),
+                slot: e.metadata.slot,
+            })
+        }
+    }
+
+    #[test]
+    fn test_custom_parser_feeds_swap_observation() {
+        let parsers = CustomEventParsers::get_instance();
+        let program_id = Pubkey::new_unique();
+        assert!(parsers.register(Arc::new(LaunchpadParser { program_id })).is_none());
+
+        let (user, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let instruction = Instruction::new_with_bytes(
+            program_id,
+            &500u64.to_le_bytes(),
+            vec![AccountMeta::new(user, true), AccountMeta::new_readonly(mint, false)],
+        );
+        let transaction = VersionedTransaction {
+            signatures: vec![Default::default()],
+            message: VersionedMessage::Legacy(Message::new(&[instruction], Some(&user))),
+        };
+
+        let events = parsers.parse_transaction(&transaction, 9);
+        assert_eq!(events.len(), 1);
+        assert!(events[0].program_received_time_ms() > 0);
+        let swap = SwapObservation::from_event(events[0].as_ref()).unwrap();
+        assert_eq!((swap.mint, swap.user, swap.sol_amount, swap.slot), (mint, user, 500, 9));
+
+        assert!(parsers.unregister(&program_id).is_some());
+        assert!(parsers.parse_transaction(&transaction, 9).is_empty());
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::common::TokenMetadataCache;
+use crate::constants::pumpfun::global_constants::TOKEN_TOTAL_SUPPLY;
+use crate::monitor::large_swap::SwapObservation;
+use crate::monitor::price_monitor::{ExitReason, ExitSignal, PriceMonitor};
+use crate::solana_streamer_sdk::streaming::event_parser::{
+    protocols::pumpfun::PumpFunTradeEvent, UnifiedEvent,
+};
+
+/// 开发者卖出检测配置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DevSellConfig {
+    /// 创建者和开发者钱包累计卖出超过总供应量的该比例（基点）时卖出持仓
+    pub max_sold_basis_points: u64,
+    /// 所有代币都关注的开发者钱包，单个代币的开发者钱包用 `add_dev_wallet` 设置
+    pub dev_wallets: HashSet<Pubkey>,
+    /// 未知总供应量时使用的供应量（代币最小单位），默认为 PumpFun 的 10 亿枚
+    pub default_supply: u64,
+}
+
+impl Default for DevSellConfig {
+    fn default() -> Self {
+        Self {
+            max_sold_basis_points: 500,
+            dev_wallets: HashSet::new(),
+            default_supply: TOKEN_TOTAL_SUPPLY,
+        }
+    }
+}
+
+/// 单个代币的创建者、开发者钱包和累计卖出
+#[derive(Debug, Default)]
+struct TokenDevs {
+    creator: Option<Pubkey>,
+    dev_wallets: HashSet<Pubkey>,
+    supply: Option<u64>,
+    /// 开始持仓后创建者和开发者钱包累计卖出的数量
+    sold: u64,
+}
+
+/// 开发者卖出检测：创建者或开发者钱包在我们持仓期间累计卖出超过阈值时，
+/// 通过 `PriceMonitor::exit_position` 立即以 `ExitReason::CreatorSell` 卖出持仓
+///
+/// 创建者取自 PumpFun 交易事件，迁移后的池子交易不含创建者，需提前收到 PumpFun 交易
+/// 或用 `set_creator` 设置。只统计 `PriceMonitor` 中有持仓的代币，持仓关闭后累计卖出清零
+pub struct DevSellMonitor {
+    config: DevSellConfig,
+    price_monitor: Arc<PriceMonitor>,
+    tokens: RwLock<HashMap<Pubkey, TokenDevs>>,
+}
+
+impl DevSellMonitor {
+    pub fn new(config: DevSellConfig, price_monitor: Arc<PriceMonitor>) -> Self {
+        Self { config, price_monitor, tokens: RwLock::new(HashMap::new()) }
+    }
+
+    /// 设置代币的创建者，用于事件中不含创建者的协议
+    pub fn set_creator(&self, mint: Pubkey, creator: Pubkey) {
+        self.tokens.write().unwrap().entry(mint).or_default().creator = Some(creator);
+    }
+
+    /// 设置代币的总供应量（代币最小单位）
+    pub fn set_supply(&self, mint: Pubkey, supply: u64) {
+        self.tokens.write().unwrap().entry(mint).or_default().supply = Some(supply);
+    }
+
+    /// 添加单个代币的开发者钱包
+    pub fn add_dev_wallet(&self, mint: Pubkey, wallet: Pubkey) {
+        self.tokens.write().unwrap().entry(mint).or_default().dev_wallets.insert(wallet);
+    }
+
+    /// 代币的创建者
+    pub fn creator(&self, mint: &Pubkey) -> Option<Pubkey> {
+        self.tokens.read().unwrap().get(mint)?.creator
+    }
+
+    /// 持仓期间创建者和开发者钱包累计卖出的数量
+    pub fn sold(&self, mint: &Pubkey) -> u64 {
+        self.tokens.read().unwrap().get(mint).map_or(0, |token| token.sold)
+    }
+
+    /// 处理流事件，可直接在订阅回调中调用，触发卖出时返回卖出信号
+    pub fn on_event(&self, event: &dyn UnifiedEvent) -> Option<ExitSignal> {
+        if let Some(e) = event.as_any().downcast_ref::<PumpFunTradeEvent>() {
+            if self.price_monitor.position(&e.mint).is_some() {
+                let mut tokens = self.tokens.write().unwrap();
+                let token = tokens.entry(e.mint).or_default();
+                token.creator.get_or_insert(e.creator);
+            }
+        }
+        self.observe(&SwapObservation::from_event(event)?)
+    }
+
+    /// 处理一笔交易，创建者或开发者钱包卖出使累计卖出超过阈值时卖出持仓
+    pub fn observe(&self, swap: &SwapObservation) -> Option<ExitSignal> {
+        if self.price_monitor.position(&swap.mint).is_none() {
+            // 持仓已关闭，下次开仓重新统计；手动设置的创建者和开发者钱包保留
+            if let Some(token) = self.tokens.write().unwrap().get_mut(&swap.mint) {
+                token.sold = 0;
+            }
+            return None;
+        }
+        if swap.is_buy {
+            return None;
+        }
+        let (sold, supply) = {
+            let mut tokens = self.tokens.write().unwrap();
+            let token = tokens.entry(swap.mint).or_default();
+            let is_dev = token.creator == Some(swap.user)
+                || token.dev_wallets.contains(&swap.user)
+                || self.config.dev_wallets.contains(&swap.user);
+            if !is_dev {
+                return None;
+            }
+            token.sold = token.sold.saturating_add(swap.token_amount);
+            (token.sold, token.supply.unwrap_or(self.config.default_supply))
+        };
+        let sold_basis_points = sold as u128 * 10_000 / supply.max(1) as u128;
+        if sold_basis_points <= self.config.max_sold_basis_points as u128 {
+            return None;
+        }
+        println!(
+            "开发者钱包 {} 已卖出 {} 的 {} 基点供应量，卖出持仓",
+            swap.user,
+            TokenMetadataCache::get_instance().label(&swap.mint),
+            sold_basis_points
+        );
+        let signal = self.price_monitor.exit_position(&swap.mint, ExitReason::CreatorSell)?;
+        if let Some(token) = self.tokens.write().unwrap().get_mut(&swap.mint) {
+            token.sold = 0;
+        }
+        Some(signal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitor::price_monitor::TakeProfitStopLossConfig;
+
+    fn sell(mint: Pubkey, user: Pubkey, token_amount: u64) -> SwapObservation {
+        SwapObservation { mint, user, is_buy: false, token_amount, ..SwapObservation::for_test() }
+    }
+
+    #[test]
+    fn test_creator_dump_exits_position() {
+        let price_monitor = Arc::new(PriceMonitor::new(TakeProfitStopLossConfig::default()));
+        let mut signals = price_monitor.subscribe();
+        let monitor = DevSellMonitor::new(
+            DevSellConfig { max_sold_basis_points: 500, ..Default::default() },
+            price_monitor.clone(),
+        );
+        let (mint, creator, dev, other) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        monitor.set_supply(mint, 1_000_000);
+        monitor.add_dev_wallet(mint, dev);
+        let creator_sell = PumpFunTradeEvent {
+            mint,
+            creator,
+            user: creator,
+            is_buy: false,
+            sol_amount: 1_000,
+            token_amount: 30_000,
+            ..Default::default()
+        };
+
+        // 没有持仓时不统计
+        assert!(monitor.on_event(&creator_sell).is_none());
+        assert_eq!(monitor.creator(&mint), None);
+        price_monitor.open_position(mint, 1.0);
+        assert!(monitor.observe(&sell(mint, other, 100_000)).is_none());
+        // 从 PumpFun 交易中得到创建者
+        assert!(monitor.on_event(&creator_sell).is_none());
+        assert_eq!((monitor.creator(&mint), monitor.sold(&mint)), (Some(creator), 30_000));
+        // 创建者和开发者钱包合计卖出 5.5%，超过 5%
+        let signal = monitor.observe(&sell(mint, dev, 25_000)).unwrap();
+        assert_eq!(signal.reason, ExitReason::CreatorSell);
+        assert_eq!(signals.try_recv().unwrap(), signal);
+        assert!(price_monitor.position(&mint).is_none());
+        assert_eq!(monitor.sold(&mint), 0);
+    }
+}
@@ -0,0 +1,208 @@
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use anyhow::Result;
+use solana_client::rpc_request::TokenAccountsFilter;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+use crate::common::SolanaRpcClient;
+use crate::constants::pumpswap::accounts::WSOL_TOKEN_ACCOUNT;
+use crate::monitor::price_monitor::MonitoredPosition;
+use crate::monitor::reconcile::parse_token_balance;
+
+/// 粉尘清理配置，粉尘的标准为 `PriceMonitor::set_min_exit_lamports`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DustSweepConfig {
+    /// 为 true 时卖出粉尘持仓，代币账户在卖出成交、余额为 0 后的下一轮关闭；
+    /// 为 false 时销毁剩余代币并关闭代币账户，只回收租金
+    pub sell: bool,
+    /// 卖出的滑点（基点），None 时使用默认滑点
+    pub slippage_basis_points: Option<u64>,
+    /// 关闭钱包中余额为 0 的其他代币账户，监控中的持仓和 WSOL 账户除外
+    pub close_empty_accounts: bool,
+    /// 每笔清理交易最多处理的代币账户数
+    pub max_accounts_per_transaction: usize,
+}
+
+impl Default for DustSweepConfig {
+    fn default() -> Self {
+        Self {
+            sell: false,
+            slippage_basis_points: None,
+            close_empty_accounts: true,
+            max_accounts_per_transaction: 8,
+        }
+    }
+}
+
+/// 一次粉尘清理的结果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DustSweepReport {
+    /// 已卖出并停止监控的粉尘持仓
+    pub sold: Vec<Pubkey>,
+    /// 销毁剩余代币并关闭账户、停止监控的粉尘持仓
+    pub burned: Vec<Pubkey>,
+    /// 关闭的代币账户
+    pub closed_accounts: Vec<Pubkey>,
+    /// 清理交易的签名
+    pub signatures: Vec<Signature>,
+    /// 卖出或清理失败的代币及原因
+    pub failed: Vec<(Pubkey, String)>,
+}
+
+/// 钱包的一个代币账户
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenAccountBalance {
+    pub address: Pubkey,
+    /// 代币账户所属的 Token 或 Token-2022 程序
+    pub program: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+impl TokenAccountBalance {
+    /// 销毁余额（不为 0 时）并关闭账户的指令，租金退回 `owner`
+    pub fn close_instructions(&self, owner: &Pubkey) -> Result<Vec<Instruction>> {
+        let mut instructions = vec![];
+        if self.amount > 0 {
+            instructions.push(spl_token_2022::instruction::burn(
+                &self.program,
+                &self.address,
+                &self.mint,
+                owner,
+                &[],
+                self.amount,
+            )?);
+        }
+        instructions.push(spl_token_2022::instruction::close_account(
+            &self.program,
+            &self.address,
+            owner,
+            owner,
+            &[],
+        )?);
+        Ok(instructions)
+    }
+}
+
+/// 查询钱包在 Token 和 Token-2022 程序下的全部代币账户
+pub async fn token_accounts(
+    rpc: &SolanaRpcClient,
+    owner: &Pubkey,
+) -> Result<Vec<TokenAccountBalance>> {
+    let mut balances = vec![];
+    for program in [spl_token::ID, spl_token_2022::ID] {
+        let accounts =
+            rpc.get_token_accounts_by_owner(owner, TokenAccountsFilter::ProgramId(program)).await?;
+        for account in accounts {
+            if let Some((mint, amount)) = parse_token_balance(&account.account.data) {
+                let address = Pubkey::from_str(&account.pubkey)?;
+                balances.push(TokenAccountBalance { address, program, mint, amount });
+            }
+        }
+    }
+    Ok(balances)
+}
+
+/// 一轮清理要做的操作
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DustSweepPlan {
+    /// 要卖出的粉尘持仓及链上余额
+    pub sell: Vec<(MonitoredPosition, u64)>,
+    /// 要销毁余额并关闭的代币账户，余额为 0 时只关闭
+    pub close: Vec<TokenAccountBalance>,
+}
+
+impl DustSweepPlan {
+    /// 按粉尘持仓、钱包的代币账户和监控中的代币生成清理计划
+    pub fn new(
+        dust: &[MonitoredPosition],
+        accounts: &[TokenAccountBalance],
+        tracked: &HashSet<Pubkey>,
+        config: &DustSweepConfig,
+    ) -> Self {
+        let dust: HashMap<Pubkey, &MonitoredPosition> =
+            dust.iter().map(|position| (position.mint, position)).collect();
+        let mut plan = Self::default();
+        let mut sell: HashMap<Pubkey, u64> = HashMap::new();
+        for account in accounts {
+            if account.mint == WSOL_TOKEN_ACCOUNT {
+                continue;
+            }
+            if dust.contains_key(&account.mint) {
+                if config.sell && account.amount > 0 {
+                    *sell.entry(account.mint).or_default() += account.amount;
+                } else {
+                    plan.close.push(account.clone());
+                }
+            } else if account.amount == 0
+                && config.close_empty_accounts
+                && !tracked.contains(&account.mint)
+            {
+                plan.close.push(account.clone());
+            }
+        }
+        plan.sell = sell.into_iter().map(|(mint, amount)| (dust[&mint].clone(), amount)).collect();
+        plan
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(mint: Pubkey, amount: u64) -> TokenAccountBalance {
+        TokenAccountBalance { address: Pubkey::new_unique(), program: spl_token::ID, mint, amount }
+    }
+
+    fn position(mint: Pubkey) -> MonitoredPosition {
+        MonitoredPosition {
+            mint,
+            entry_price: 1.0,
+            peak_price: 1.0,
+            last_price: 1.0,
+            config: None,
+            dex_type: None,
+            token_amount: 10,
+            cost_basis: 0,
+        }
+    }
+
+    #[test]
+    fn test_dust_sweep_plan() {
+        let (dust, sold, held, empty) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let accounts = vec![
+            account(dust, 10),
+            account(held, 0),
+            account(empty, 0),
+            account(Pubkey::new_unique(), 5),
+            account(WSOL_TOKEN_ACCOUNT, 0),
+        ];
+        let tracked = HashSet::from([dust, held]);
+
+        // 销毁并关闭粉尘账户，关闭不在监控中的空账户，监控中的持仓、有余额的账户和 WSOL 保留
+        let plan =
+            DustSweepPlan::new(&[position(dust)], &accounts, &tracked, &DustSweepConfig::default());
+        assert!(plan.sell.is_empty());
+        assert_eq!(plan.close, vec![accounts[0].clone(), accounts[2].clone()]);
+        let instructions = plan.close[0].close_instructions(&Pubkey::new_unique()).unwrap();
+        assert_eq!(instructions.len(), 2);
+        assert_eq!(plan.close[1].close_instructions(&Pubkey::new_unique()).unwrap().len(), 1);
+
+        // 卖出模式先卖出，卖出后余额为 0 的粉尘账户直接关闭
+        let config =
+            DustSweepConfig { sell: true, close_empty_accounts: false, ..Default::default() };
+        let accounts = vec![account(dust, 10), account(sold, 0), account(empty, 0)];
+        let plan =
+            DustSweepPlan::new(&[position(dust), position(sold)], &accounts, &tracked, &config);
+        assert_eq!(plan.sell, vec![(position(dust), 10)]);
+        assert_eq!(plan.close, vec![accounts[1].clone()]);
+    }
+}
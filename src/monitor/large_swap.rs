@@ -0,0 +1,411 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::broadcast;
+
+use crate::constants::pumpswap::accounts::WSOL_TOKEN_ACCOUNT;
+use crate::monitor::custom_parser::CustomEventParsers;
+use crate::solana_streamer_sdk::streaming::event_parser::{
+    protocols::{
+        bonk::{BonkTradeEvent, TradeDirection},
+        pumpfun::PumpFunTradeEvent,
+        pumpswap::{PumpSwapBuyEvent, PumpSwapSellEvent},
+        raydium_cpmm::RaydiumCpmmSwapEvent,
+    },
+    UnifiedEvent,
+};
+use crate::trading::factory::DexType;
+use crate::trading::moonshot::event::MoonshotTradeEvent;
+
+/// 告警广播通道容量
+const ALERT_CHANNEL_CAPACITY: usize = 1024;
+
+/// 大额交易检测配置
+#[derive(Debug, Clone)]
+pub struct LargeSwapConfig {
+    /// 绝对金额阈值（lamports），0 表示不启用
+    pub min_sol_amount: u64,
+    /// 相对滚动中位数的倍数阈值，0 表示不启用
+    pub median_multiple: f64,
+    /// 每个池子保留的滚动窗口大小
+    pub window_size: usize,
+    /// 中位数判断所需的最少样本数
+    pub min_samples: usize,
+    /// 关注的池子或代币，为空时监控全部
+    pub watched: HashSet<Pubkey>,
+}
+
+impl Default for LargeSwapConfig {
+    fn default() -> Self {
+        Self {
+            min_sol_amount: 10_000_000_000,
+            median_multiple: 10.0,
+            window_size: 100,
+            min_samples: 20,
+            watched: HashSet::new(),
+        }
+    }
+}
+
+/// 从流事件中提取的统一交易数据
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SwapObservation {
+    pub dex_type: DexType,
+    pub pool: Pubkey,
+    pub mint: Pubkey,
+    pub user: Pubkey,
+    pub is_buy: bool,
+    /// SOL 数量（lamports）
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    /// 池子 SOL 一侧的真实储备（lamports），事件不含储备时为 None
+    #[serde(default)]
+    pub sol_reserve: Option<u64>,
+    pub signature: String,
+    pub slot: u64,
+}
+
+impl SwapObservation {
+    /// 从流事件中解析交易数据，非 SOL 计价的交易返回 None；
+    /// streamer 不支持的事件交给 `CustomEventParsers` 中注册的解析器
+    pub fn from_event(event: &dyn UnifiedEvent) -> Option<Self> {
+        let any = event.as_any();
+        let signature = event.signature().to_string();
+        let slot = event.slot();
+
+        if let Some(e) = any.downcast_ref::<PumpFunTradeEvent>() {
+            return Some(Self {
+                dex_type: DexType::PumpFun,
+                pool: e.bonding_curve,
+                mint: e.mint,
+                user: e.user,
+                is_buy: e.is_buy,
+                sol_amount: e.sol_amount,
+                token_amount: e.token_amount,
+                sol_reserve: Some(e.real_sol_reserves),
+                signature,
+                slot,
+            });
+        }
+
+        if let Some(e) = any.downcast_ref::<PumpSwapBuyEvent>() {
+            // 买入 base，支付 quote，储备为交易前的数量
+            let (mint, is_buy, sol_amount, token_amount, sol_reserve) =
+                if e.quote_mint == WSOL_TOKEN_ACCOUNT {
+                    (
+                        e.base_mint,
+                        true,
+                        e.quote_amount_in,
+                        e.base_amount_out,
+                        e.pool_quote_token_reserves,
+                    )
+                } else if e.base_mint == WSOL_TOKEN_ACCOUNT {
+                    (
+                        e.quote_mint,
+                        false,
+                        e.base_amount_out,
+                        e.quote_amount_in,
+                        e.pool_base_token_reserves,
+                    )
+                } else {
+                    return None;
+                };
+            return Some(Self {
+                dex_type: DexType::PumpSwap,
+                pool: e.pool,
+                mint,
+                user: e.user,
+                is_buy,
+                sol_amount,
+                token_amount,
+                sol_reserve: Some(sol_reserve),
+                signature,
+                slot,
+            });
+        }
+
+        if let Some(e) = any.downcast_ref::<PumpSwapSellEvent>() {
+            // 卖出 base，获得 quote
+            let (mint, is_buy, sol_amount, token_amount, sol_reserve) =
+                if e.quote_mint == WSOL_TOKEN_ACCOUNT {
+                    (
+                        e.base_mint,
+                        false,
+                        e.quote_amount_out,
+                        e.base_amount_in,
+                        e.pool_quote_token_reserves,
+                    )
+                } else if e.base_mint == WSOL_TOKEN_ACCOUNT {
+                    (
+                        e.quote_mint,
+                        true,
+                        e.base_amount_in,
+                        e.quote_amount_out,
+                        e.pool_base_token_reserves,
+                    )
+                } else {
+                    return None;
+                };
+            return Some(Self {
+                dex_type: DexType::PumpSwap,
+                pool: e.pool,
+                mint,
+                user: e.user,
+                is_buy,
+                sol_amount,
+                token_amount,
+                sol_reserve: Some(sol_reserve),
+                signature,
+                slot,
+            });
+        }
+
+        if let Some(e) = any.downcast_ref::<BonkTradeEvent>() {
+            if e.quote_token_mint != WSOL_TOKEN_ACCOUNT {
+                return None;
+            }
+            let is_buy = e.trade_direction == TradeDirection::Buy;
+            let (sol_amount, token_amount) =
+                if is_buy { (e.amount_in, e.amount_out) } else { (e.amount_out, e.amount_in) };
+            return Some(Self {
+                dex_type: DexType::Bonk,
+                pool: e.pool_state,
+                mint: e.base_token_mint,
+                user: e.payer,
+                is_buy,
+                sol_amount,
+                token_amount,
+                sol_reserve: Some(e.real_quote_after),
+                signature,
+                slot,
+            });
+        }
+
+        if let Some(e) = any.downcast_ref::<RaydiumCpmmSwapEvent>() {
+            // 指令数据中只有一侧是精确值，另一侧取限价
+            let amount_in = if e.amount_in > 0 { e.amount_in } else { e.max_amount_in };
+            let amount_out = if e.amount_out > 0 { e.amount_out } else { e.minimum_amount_out };
+            let (mint, is_buy, sol_amount, token_amount) =
+                if e.input_token_mint == WSOL_TOKEN_ACCOUNT {
+                    (e.output_token_mint, true, amount_in, amount_out)
+                } else if e.output_token_mint == WSOL_TOKEN_ACCOUNT {
+                    (e.input_token_mint, false, amount_out, amount_in)
+                } else {
+                    return None;
+                };
+            return Some(Self {
+                dex_type: DexType::RaydiumCpmm,
+                pool: e.pool_state,
+                mint,
+                user: e.payer,
+                is_buy,
+                sol_amount,
+                token_amount,
+                sol_reserve: None,
+                signature,
+                slot,
+            });
+        }
+
+        CustomEventParsers::get_instance().swap(event)
+    }
+}
+
+/// Moonshot 交易不在流事件中，由 `trading::moonshot::event::parse_transaction` 解析后转换
+impl From<MoonshotTradeEvent> for SwapObservation {
+    fn from(e: MoonshotTradeEvent) -> Self {
+        Self {
+            dex_type: DexType::Moonshot,
+            pool: e.curve_account,
+            mint: e.mint,
+            user: e.user,
+            is_buy: e.is_buy,
+            sol_amount: e.collateral_amount,
+            token_amount: e.token_amount,
+            sol_reserve: None,
+            signature: e.signature,
+            slot: e.slot,
+        }
+    }
+}
+
+#[cfg(test)]
+impl SwapObservation {
+    /// 测试用的交易：新地址上的一笔 PumpSwap 买入，其余字段用结构体更新语法按需覆盖
+    pub(crate) fn for_test() -> Self {
+        Self {
+            dex_type: DexType::PumpSwap,
+            pool: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            user: Pubkey::new_unique(),
+            is_buy: true,
+            sol_amount: 1_000,
+            token_amount: 1_000,
+            sol_reserve: None,
+            signature: String::new(),
+            slot: 1,
+        }
+    }
+}
+
+/// 触发告警的原因
+#[derive(Debug, Clone, PartialEq)]
+pub enum LargeSwapReason {
+    /// 超过绝对金额阈值
+    AbsoluteSize { threshold: u64 },
+    /// 超过滚动中位数的指定倍数
+    MedianMultiple { median: u64, multiple: f64 },
+}
+
+/// 大额交易告警
+#[derive(Debug, Clone, PartialEq)]
+pub struct LargeSwapAlert {
+    pub swap: SwapObservation,
+    pub reason: LargeSwapReason,
+}
+
+/// 大额交易检测器，按池子维护滚动窗口
+pub struct LargeSwapDetector {
+    config: LargeSwapConfig,
+    windows: Mutex<HashMap<Pubkey, VecDeque<u64>>>,
+    sender: broadcast::Sender<LargeSwapAlert>,
+}
+
+impl LargeSwapDetector {
+    pub fn new(config: LargeSwapConfig) -> Self {
+        let (sender, _) = broadcast::channel(ALERT_CHANNEL_CAPACITY);
+        Self { config, windows: Mutex::new(HashMap::new()), sender }
+    }
+
+    /// 订阅告警事件
+    pub fn subscribe(&self) -> broadcast::Receiver<LargeSwapAlert> {
+        self.sender.subscribe()
+    }
+
+    /// 处理流事件，可直接在订阅回调中调用
+    pub fn on_event(&self, event: &dyn UnifiedEvent) -> Option<LargeSwapAlert> {
+        let swap = SwapObservation::from_event(event)?;
+        self.observe(swap)
+    }
+
+    /// 处理一笔交易，触发时返回告警并广播给订阅者
+    pub fn observe(&self, swap: SwapObservation) -> Option<LargeSwapAlert> {
+        if !self.is_watched(&swap) {
+            return None;
+        }
+
+        let median = {
+            let mut windows = self.windows.lock().unwrap();
+            let window = windows.entry(swap.pool).or_default();
+            let median = if window.len() >= self.config.min_samples.max(1) {
+                Some(median(window))
+            } else {
+                None
+            };
+            window.push_back(swap.sol_amount);
+            while window.len() > self.config.window_size.max(1) {
+                window.pop_front();
+            }
+            median
+        };
+
+        let reason = self.check(swap.sol_amount, median)?;
+        let alert = LargeSwapAlert { swap, reason };
+        // 没有订阅者时发送失败，忽略即可
+        let _ = self.sender.send(alert.clone());
+        Some(alert)
+    }
+
+    /// 清除指定池子的历史窗口
+    pub fn reset(&self, pool: &Pubkey) {
+        self.windows.lock().unwrap().remove(pool);
+    }
+
+    fn is_watched(&self, swap: &SwapObservation) -> bool {
+        self.config.watched.is_empty()
+            || self.config.watched.contains(&swap.pool)
+            || self.config.watched.contains(&swap.mint)
+    }
+
+    fn check(&self, sol_amount: u64, median: Option<u64>) -> Option<LargeSwapReason> {
+        if self.config.min_sol_amount > 0 && sol_amount >= self.config.min_sol_amount {
+            return Some(LargeSwapReason::AbsoluteSize { threshold: self.config.min_sol_amount });
+        }
+        let multiple = self.config.median_multiple;
+        match median {
+            Some(median) if multiple > 0.0 && median > 0 => {
+                if sol_amount as f64 >= median as f64 * multiple {
+                    Some(LargeSwapReason::MedianMultiple { median, multiple })
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+fn median(window: &VecDeque<u64>) -> u64 {
+    let mut values: Vec<u64> = window.iter().copied().collect();
+    values.sort_unstable();
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        ((values[mid - 1] as u128 + values[mid] as u128) / 2) as u64
+    } else {
+        values[mid]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn swap(pool: Pubkey, sol_amount: u64) -> SwapObservation {
+        SwapObservation { pool, sol_amount, token_amount: 0, slot: 0, ..SwapObservation::for_test() }
+    }
+
+    #[test]
+    fn test_median_multiple_alert() {
+        let config = LargeSwapConfig {
+            min_sol_amount: 0,
+            median_multiple: 5.0,
+            window_size: 10,
+            min_samples: 3,
+            watched: HashSet::new(),
+        };
+        let detector = LargeSwapDetector::new(config);
+        let pool = Pubkey::new_unique();
+
+        // 样本不足时不触发
+        assert!(detector.observe(swap(pool, 100)).is_none());
+        assert!(detector.observe(swap(pool, 110)).is_none());
+        assert!(detector.observe(swap(pool, 120)).is_none());
+
+        // 中位数为 110，550 达到 5 倍
+        let alert = detector.observe(swap(pool, 550)).unwrap();
+        assert_eq!(alert.reason, LargeSwapReason::MedianMultiple { median: 110, multiple: 5.0 });
+    }
+
+    #[test]
+    fn test_absolute_alert_and_watch_list() {
+        let pool = Pubkey::new_unique();
+        let config = LargeSwapConfig {
+            min_sol_amount: 1_000,
+            watched: HashSet::from([pool]),
+            ..Default::default()
+        };
+        let detector = LargeSwapDetector::new(config);
+
+        assert!(detector.observe(swap(Pubkey::new_unique(), 5_000)).is_none());
+        let alert = detector.observe(swap(pool, 5_000)).unwrap();
+        assert_eq!(alert.reason, LargeSwapReason::AbsoluteSize { threshold: 1_000 });
+    }
+
+    #[test]
+    fn test_median_of_large_values() {
+        let window = VecDeque::from([u64::MAX - 1, u64::MAX - 3]);
+        assert_eq!(median(&window), u64::MAX - 2);
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, RwLock};
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::common::TokenMetadataCache;
+use crate::monitor::large_swap::SwapObservation;
+use crate::monitor::pool_events::{PoolEvent, PoolEventKind};
+use crate::monitor::price_monitor::{ExitReason, ExitSignal, PriceMonitor};
+use crate::solana_streamer_sdk::streaming::event_parser::UnifiedEvent;
+
+/// 流动性保护配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LiquidityGuardConfig {
+    /// 窗口内池子 SOL 储备较窗口内最高值减少超过该比例（基点）时卖出持仓
+    pub max_drop_basis_points: u64,
+    /// 统计窗口（slot），约 400ms 一个 slot
+    pub window_slots: u64,
+}
+
+impl Default for LiquidityGuardConfig {
+    fn default() -> Self {
+        Self { max_drop_basis_points: 3_000, window_slots: 150 }
+    }
+}
+
+/// 单个池子窗口内的 SOL 储备
+#[derive(Debug)]
+struct PoolReserves {
+    mint: Pubkey,
+    /// (slot, 储备)，按到达顺序排列
+    samples: VecDeque<(u64, u64)>,
+}
+
+/// 流动性保护：持仓代币所在池子的 SOL 储备在短时间内大幅减少时，
+/// 通过 `PriceMonitor::exit_position` 立即以 `ExitReason::LiquidityRemoved` 卖出持仓
+///
+/// 储备取自交易事件和移除流动性事件，移除流动性和大额卖出抽干池子都会触发，
+/// 能在价格止损之前发现撤池。每个池子分别统计，只统计 `PriceMonitor` 中有持仓的代币
+pub struct LiquidityGuard {
+    config: LiquidityGuardConfig,
+    price_monitor: Arc<PriceMonitor>,
+    pools: RwLock<HashMap<Pubkey, PoolReserves>>,
+}
+
+impl LiquidityGuard {
+    pub fn new(config: LiquidityGuardConfig, price_monitor: Arc<PriceMonitor>) -> Self {
+        Self { config, price_monitor, pools: RwLock::new(HashMap::new()) }
+    }
+
+    /// 池子最近一次记录的 SOL 储备（lamports）
+    pub fn reserve(&self, pool: &Pubkey) -> Option<u64> {
+        self.pools.read().unwrap().get(pool)?.samples.back().map(|(_, reserve)| *reserve)
+    }
+
+    /// 处理流事件，可直接在订阅回调中调用，触发卖出时返回卖出信号
+    pub fn on_event(&self, event: &dyn UnifiedEvent) -> Option<ExitSignal> {
+        if let Some(pool_event) = PoolEvent::from_event(event) {
+            return self.on_pool_event(&pool_event);
+        }
+        self.observe(&SwapObservation::from_event(event)?)
+    }
+
+    /// 处理一笔交易，用交易后的池子储备更新窗口
+    pub fn observe(&self, swap: &SwapObservation) -> Option<ExitSignal> {
+        self.record(swap.pool, swap.mint, swap.slot, swap.sol_reserve?)
+    }
+
+    /// 处理池子事件，移除流动性事件不含储备时按最近一次储备减去取出的 SOL 估算
+    pub fn on_pool_event(&self, event: &PoolEvent) -> Option<ExitSignal> {
+        let reserve = match (event.sol_reserve, event.kind) {
+            (Some(reserve), _) => reserve,
+            (None, PoolEventKind::LiquidityRemoved) => {
+                self.reserve(&event.pool)?.saturating_sub(event.sol_amount)
+            }
+            (None, _) => return None,
+        };
+        self.record(event.pool, event.base_mint, event.slot, reserve)
+    }
+
+    fn record(&self, pool: Pubkey, mint: Pubkey, slot: u64, reserve: u64) -> Option<ExitSignal> {
+        if self.price_monitor.position(&mint).is_none() {
+            // 持仓已关闭，下次开仓重新统计
+            self.pools.write().unwrap().remove(&pool);
+            return None;
+        }
+        let peak = {
+            let mut pools = self.pools.write().unwrap();
+            let reserves = pools
+                .entry(pool)
+                .or_insert_with(|| PoolReserves { mint, samples: VecDeque::new() });
+            let window_start = slot.saturating_sub(self.config.window_slots);
+            while reserves
+                .samples
+                .front()
+                .is_some_and(|(sample_slot, _)| *sample_slot < window_start)
+            {
+                reserves.samples.pop_front();
+            }
+            reserves.samples.push_back((slot, reserve));
+            reserves.samples.iter().map(|(_, reserve)| *reserve).max().unwrap_or(reserve)
+        };
+        if peak == 0 {
+            return None;
+        }
+        let drop_basis_points = (peak - reserve) as u128 * 10_000 / peak as u128;
+        if drop_basis_points <= self.config.max_drop_basis_points as u128 {
+            return None;
+        }
+        println!(
+            "{} 池子 {} 的 SOL 储备从 {} 降至 {}，卖出持仓",
+            TokenMetadataCache::get_instance().label(&mint),
+            pool,
+            peak,
+            reserve
+        );
+        let signal = self.price_monitor.exit_position(&mint, ExitReason::LiquidityRemoved)?;
+        self.pools.write().unwrap().retain(|_, reserves| reserves.mint != mint);
+        Some(signal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::pumpswap::accounts::WSOL_TOKEN_ACCOUNT;
+    use crate::monitor::price_monitor::TakeProfitStopLossConfig;
+    use crate::solana_streamer_sdk::streaming::event_parser::protocols::pumpswap::PumpSwapWithdrawEvent;
+
+    #[test]
+    fn test_liquidity_removal_exits_position() {
+        let price_monitor = Arc::new(PriceMonitor::new(TakeProfitStopLossConfig::default()));
+        let guard = LiquidityGuard::new(
+            LiquidityGuardConfig { max_drop_basis_points: 3_000, window_slots: 100 },
+            price_monitor.clone(),
+        );
+        let (mint, pool) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let swap = |slot: u64, sol_reserve: u64| SwapObservation {
+            pool,
+            mint,
+            is_buy: false,
+            sol_reserve: Some(sol_reserve),
+            slot,
+            ..SwapObservation::for_test()
+        };
+
+        // 没有持仓时不统计
+        assert!(guard.observe(&swap(1, 100_000_000_000)).is_none());
+        assert_eq!(guard.reserve(&pool), None);
+        price_monitor.open_position(mint, 1.0);
+        assert!(guard.observe(&swap(10, 100_000_000_000)).is_none());
+        // 窗口外的最高值不计入：100 -> 65 跨越 200 个 slot
+        assert!(guard.observe(&swap(211, 65_000_000_000)).is_none());
+
+        // 撤出 30 SOL，65 -> 35 超过 30%
+        let withdraw = PumpSwapWithdrawEvent {
+            pool,
+            base_mint: mint,
+            quote_mint: WSOL_TOKEN_ACCOUNT,
+            pool_base_token_reserves: 1_000_000,
+            pool_quote_token_reserves: 65_000_000_000,
+            base_amount_out: 400_000,
+            quote_amount_out: 30_000_000_000,
+            ..Default::default()
+        };
+        let signal = guard.on_event(&withdraw).unwrap();
+        assert_eq!(signal.reason, ExitReason::LiquidityRemoved);
+        assert!(price_monitor.position(&mint).is_none());
+        assert_eq!(guard.reserve(&pool), None);
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::monitor::large_swap::SwapObservation;
+
+/// 单个代币的近期成交和池子储备
+#[derive(Debug, Default)]
+struct Market {
+    /// 窗口内每笔成交的 slot 和 SOL 数量
+    swaps: VecDeque<(u64, u64)>,
+    sol_reserve: Option<u64>,
+}
+
+/// 按代币统计流事件中的近期成交量和池子 SOL 储备
+///
+/// 成交量按 slot 窗口统计，以收到的最新 slot 为准，长时间没有成交的代币成交量为 0
+#[derive(Debug)]
+pub struct MarketStats {
+    window_slots: u64,
+    state: Mutex<(u64, HashMap<Pubkey, Market>)>,
+}
+
+impl MarketStats {
+    /// `window_slots` 为成交量统计窗口，150 个 slot 约为 1 分钟
+    pub fn new(window_slots: u64) -> Self {
+        Self { window_slots, state: Mutex::new((0, HashMap::new())) }
+    }
+
+    /// 记录一笔成交，储备以最新一笔包含储备的成交为准
+    pub fn observe(&self, swap: &SwapObservation) {
+        let mut state = self.state.lock().unwrap();
+        let (latest_slot, markets) = &mut *state;
+        *latest_slot = (*latest_slot).max(swap.slot);
+        let market = markets.entry(swap.mint).or_default();
+        market.swaps.push_back((swap.slot, swap.sol_amount));
+        let window_start = latest_slot.saturating_sub(self.window_slots);
+        while market.swaps.front().is_some_and(|(slot, _)| *slot <= window_start) {
+            market.swaps.pop_front();
+        }
+        if swap.sol_reserve.is_some() {
+            market.sol_reserve = swap.sol_reserve;
+        }
+    }
+
+    /// 窗口内的成交量（lamports），买卖合计
+    pub fn volume(&self, mint: &Pubkey) -> u64 {
+        let state = self.state.lock().unwrap();
+        let window_start = state.0.saturating_sub(self.window_slots);
+        state.1.get(mint).map_or(0, |market| {
+            market.swaps.iter().filter(|(slot, _)| *slot > window_start).map(|(_, sol)| sol).sum()
+        })
+    }
+
+    /// 最近一次看到的池子 SOL 储备（lamports），没有包含储备的成交时为 None
+    pub fn sol_reserve(&self, mint: &Pubkey) -> Option<u64> {
+        self.state.lock().unwrap().1.get(mint)?.sol_reserve
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_volume_window_and_reserve() {
+        let stats = MarketStats::new(100);
+        let (mint, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let swap = |mint: Pubkey, slot: u64, sol_amount: u64, sol_reserve: Option<u64>| SwapObservation {
+            mint,
+            sol_amount,
+            token_amount: 1,
+            sol_reserve,
+            slot,
+            ..SwapObservation::for_test()
+        };
+
+        stats.observe(&swap(mint, 10, 300, Some(5_000)));
+        stats.observe(&swap(mint, 50, 200, None));
+        assert_eq!((stats.volume(&mint), stats.sol_reserve(&mint)), (500, Some(5_000)));
+
+        // 其他代币的成交推进 slot，slot 10 的成交移出窗口
+        stats.observe(&swap(other, 120, 1, None));
+        assert_eq!(stats.volume(&mint), 200);
+        assert_eq!((stats.volume(&other), stats.sol_reserve(&other)), (1, None));
+        assert_eq!(stats.volume(&Pubkey::new_unique()), 0);
+    }
+}
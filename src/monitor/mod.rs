@@ -0,0 +1,48 @@
+pub mod address_list;
+pub mod candle_store;
+pub mod candles;
+pub mod copy_sell;
+pub mod custom_parser;
+pub mod dev_sell;
+pub mod dust_sweeper;
+pub mod large_swap;
+pub mod liquidity_guard;
+pub mod market_stats;
+pub mod momentum;
+pub mod pnl;
+pub mod pool_events;
+pub mod position_store;
+pub mod price_monitor;
+pub mod reconcile;
+pub mod sniper;
+pub mod wallet_history;
+pub mod wallet_monitor;
+
+pub use address_list::{
+    export_address_list, format_address_list, import_address_list, parse_address_list, AddressEntry, ListFormat,
+};
+pub use candle_store::{CandleStore, ClickHouseCandleStore, ClickHouseConfig, MemoryCandleStore};
+pub use candles::{Candle, CandleAggregator, CandleConfig, CandleInterval};
+pub use copy_sell::{CopySellConfig, CopySellMirror, CopySellOrder};
+pub use custom_parser::{CustomEventParser, CustomEventParsers};
+pub use dev_sell::{DevSellConfig, DevSellMonitor};
+pub use dust_sweeper::{DustSweepConfig, DustSweepPlan, DustSweepReport, TokenAccountBalance};
+pub use large_swap::{
+    LargeSwapAlert, LargeSwapConfig, LargeSwapDetector, LargeSwapReason, SwapObservation,
+};
+pub use liquidity_guard::{LiquidityGuard, LiquidityGuardConfig};
+pub use market_stats::MarketStats;
+pub use momentum::{EmaCrossConfig, EmaCrossStrategy, MomentumOrder};
+pub use pnl::{PnlFill, PnlStatistics, PnlTracker, TokenPnl};
+pub use pool_events::{PoolEvent, PoolEventConfig, PoolEventKind, PoolEventMonitor};
+pub use position_store::{JsonFilePositionStore, MemoryPositionStore, PositionStore};
+pub use price_monitor::{
+    EntryReason, EntrySignal, ExitReason, ExitSignal, MonitoredPosition, PendingEntry,
+    PriceMonitor, TakeProfitStopLossConfig, TrailingBuyConfig, TrailingBuyTimeout,
+};
+pub use reconcile::{PositionReconciler, ReconcileReport};
+pub use sniper::{Launch, SnipeOrder, SniperConfig, SniperFilter, SniperStrategy};
+pub use wallet_history::{backfill_wallet, WalletBackfillConfig, WalletStats};
+pub use wallet_monitor::{
+    CopyBuyOrder, CopyMarketFilter, CopySizing, WalletCopyConfig, WalletLabel, WalletMonitor, WalletMonitorConfig,
+};
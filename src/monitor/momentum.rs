@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::broadcast;
+
+use crate::monitor::candles::{CandleAggregator, CandleInterval};
+use crate::monitor::large_swap::SwapObservation;
+use crate::monitor::price_monitor::{ExitReason, ExitSignal, PriceMonitor};
+use crate::solana_streamer_sdk::streaming::event_parser::UnifiedEvent;
+use crate::trading::factory::DexType;
+
+/// 均线订单广播通道容量
+const MOMENTUM_ORDER_CHANNEL_CAPACITY: usize = 1024;
+
+/// 均线交叉策略配置
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EmaCrossConfig {
+    pub interval: CandleInterval,
+    /// 快线周期（K 线数量）
+    pub fast_period: usize,
+    /// 慢线周期（K 线数量），需大于快线周期
+    pub slow_period: usize,
+    /// 金叉所在 K 线的成交量（lamports）低于该值时不买入，0 表示不限制
+    pub min_candle_volume: u64,
+    /// 每次买入的 SOL 数量（lamports）
+    pub sol_amount: u64,
+    /// 滑点（基点），None 时使用默认滑点
+    pub slippage_basis_points: Option<u64>,
+}
+
+impl Default for EmaCrossConfig {
+    fn default() -> Self {
+        Self {
+            interval: CandleInterval::OneMinute,
+            fast_period: 9,
+            slow_period: 21,
+            min_candle_volume: 0,
+            sol_amount: 100_000_000,
+            slippage_basis_points: Some(1_000),
+        }
+    }
+}
+
+/// 金叉买入订单，可直接交给 `SolanaTrade::momentum_buy`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MomentumOrder {
+    pub dex_type: DexType,
+    pub mint: Pubkey,
+    /// 金叉所在 K 线的收盘价（lamports / 代币最小单位）
+    pub price: f64,
+    pub fast_ema: f64,
+    pub slow_ema: f64,
+    pub sol_amount: u64,
+    pub slippage_basis_points: Option<u64>,
+}
+
+/// 单个代币的均线状态
+#[derive(Debug, Default)]
+struct EmaState {
+    /// 最近一次计算时最新 K 线的开始时间，每根 K 线结束后只计算一次
+    evaluated_open_time: Option<i64>,
+}
+
+/// 均线交叉策略：快线上穿慢线（金叉）时广播买入订单，下穿（死叉）时卖出持仓
+///
+/// 均线按已结束的 K 线收盘价计算，K 线由 `CandleAggregator` 提供，需另外喂入成交，
+/// 例如 `PriceMonitor::with_candles`。某根 K 线在该代币的下一笔成交到达时视为结束。
+/// 设置 `PriceMonitor` 后，已有持仓的代币不再买入，死叉时通过
+/// `PriceMonitor::exit_position` 以 `ExitReason::TrendReversal` 卖出，
+/// 与止盈止损共用同一卖出通道；买入成交后用 `open_position_from_fill` 开始监控即可
+pub struct EmaCrossStrategy {
+    config: EmaCrossConfig,
+    candles: Arc<CandleAggregator>,
+    price_monitor: Option<Arc<PriceMonitor>>,
+    states: Mutex<HashMap<Pubkey, EmaState>>,
+    sender: broadcast::Sender<MomentumOrder>,
+}
+
+impl EmaCrossStrategy {
+    pub fn new(config: EmaCrossConfig, candles: Arc<CandleAggregator>) -> Self {
+        let (sender, _) = broadcast::channel(MOMENTUM_ORDER_CHANNEL_CAPACITY);
+        Self { config, candles, price_monitor: None, states: Mutex::new(HashMap::new()), sender }
+    }
+
+    /// 死叉时卖出 `price_monitor` 中的持仓，已有持仓时不再买入
+    pub fn with_price_monitor(mut self, price_monitor: Arc<PriceMonitor>) -> Self {
+        self.price_monitor = Some(price_monitor);
+        self
+    }
+
+    /// 订阅买入订单
+    pub fn subscribe(&self) -> broadcast::Receiver<MomentumOrder> {
+        self.sender.subscribe()
+    }
+
+    /// 处理流事件，可直接在订阅回调中调用，返回金叉买入订单
+    pub fn on_event(&self, event: &dyn UnifiedEvent) -> Option<MomentumOrder> {
+        self.observe(&SwapObservation::from_event(event)?)
+    }
+
+    /// 代币有新成交时检查上一根 K 线结束后是否出现交叉
+    pub fn observe(&self, swap: &SwapObservation) -> Option<MomentumOrder> {
+        let candles = self.candles.candles(&swap.mint, self.config.interval);
+        let latest = candles.last()?;
+        {
+            let mut states = self.states.lock().unwrap();
+            let state = states.entry(swap.mint).or_default();
+            if state.evaluated_open_time == Some(latest.open_time_ms) {
+                return None;
+            }
+            state.evaluated_open_time = Some(latest.open_time_ms);
+        }
+        // 最后一根 K 线尚未结束，不参与计算
+        let closed = &candles[..candles.len() - 1];
+        let closes: Vec<f64> = closed.iter().map(|candle| candle.close).collect();
+        let previous = self.emas(&closes[..closes.len().saturating_sub(1)])?;
+        let current = self.emas(&closes)?;
+        let held = self.price_monitor.as_ref().and_then(|monitor| monitor.position(&swap.mint));
+
+        if previous.0 <= previous.1 && current.0 > current.1 {
+            let candle = closed.last()?;
+            if held.is_some() || candle.volume < self.config.min_candle_volume {
+                return None;
+            }
+            let order = MomentumOrder {
+                dex_type: swap.dex_type.clone(),
+                mint: swap.mint,
+                price: candle.close,
+                fast_ema: current.0,
+                slow_ema: current.1,
+                sol_amount: self.config.sol_amount,
+                slippage_basis_points: self.config.slippage_basis_points,
+            };
+            // 没有订阅者时发送失败，忽略即可
+            let _ = self.sender.send(order.clone());
+            return Some(order);
+        }
+        if previous.0 >= previous.1 && current.0 < current.1 && held.is_some() {
+            self.exit(&swap.mint);
+        }
+        None
+    }
+
+    fn exit(&self, mint: &Pubkey) -> Option<ExitSignal> {
+        self.price_monitor.as_ref()?.exit_position(mint, ExitReason::TrendReversal)
+    }
+
+    /// 快线和慢线，K 线不足慢线周期时为 None
+    fn emas(&self, closes: &[f64]) -> Option<(f64, f64)> {
+        Some((ema(closes, self.config.fast_period)?, ema(closes, self.config.slow_period)?))
+    }
+
+    /// 停止跟踪代币的均线状态
+    pub fn remove(&self, mint: &Pubkey) {
+        self.states.lock().unwrap().remove(mint);
+    }
+}
+
+/// 指数移动平均，以前 `period` 个值的简单平均为初始值
+pub fn ema(values: &[f64], period: usize) -> Option<f64> {
+    if period == 0 || values.len() < period {
+        return None;
+    }
+    let k = 2.0 / (period as f64 + 1.0);
+    let seed = values[..period].iter().sum::<f64>() / period as f64;
+    Some(values[period..].iter().fold(seed, |ema, value| value * k + ema * (1.0 - k)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitor::candles::CandleConfig;
+    use crate::monitor::price_monitor::TakeProfitStopLossConfig;
+
+    #[test]
+    fn test_ema_cross_buys_and_exits() {
+        let candles = Arc::new(CandleAggregator::new(CandleConfig {
+            intervals: vec![CandleInterval::OneSecond],
+            ..Default::default()
+        }));
+        let price_monitor = Arc::new(PriceMonitor::new(TakeProfitStopLossConfig::default()));
+        let strategy = EmaCrossStrategy::new(
+            EmaCrossConfig {
+                interval: CandleInterval::OneSecond,
+                fast_period: 2,
+                slow_period: 4,
+                ..Default::default()
+            },
+            candles.clone(),
+        )
+        .with_price_monitor(price_monitor.clone());
+        let mint = Pubkey::new_unique();
+        let mut second = 0;
+        let mut trade = |price: u64| {
+            let swap = SwapObservation { mint, sol_amount: price, token_amount: 1, ..SwapObservation::for_test() };
+            second += 1;
+            candles.observe_at(&swap, second * 1_000);
+            strategy.observe(&swap)
+        };
+
+        // 下跌中快线在慢线下方，不买入
+        for price in [10, 9, 8, 7, 6] {
+            assert!(trade(price).is_none());
+        }
+        assert!(trade(9).is_none());
+        // 上一根 K 线收盘 9 使快线上穿慢线
+        let order = trade(12).unwrap();
+        assert_eq!((order.mint, order.price), (mint, 9.0));
+        assert!(order.fast_ema > order.slow_ema);
+
+        price_monitor.open_position(mint, order.price);
+        let mut exits = price_monitor.subscribe();
+        for price in [13, 14] {
+            assert!(trade(price).is_none());
+        }
+        for price in [6, 5] {
+            assert!(trade(price).is_none());
+        }
+        assert_eq!(exits.try_recv().unwrap().reason, ExitReason::TrendReversal);
+        assert!(price_monitor.position(&mint).is_none());
+    }
+}
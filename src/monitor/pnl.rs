@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::common::trading_day::trading_day;
+use crate::monitor::large_swap::SwapObservation;
+use crate::monitor::position_store::PositionStore;
+use crate::solana_streamer_sdk::streaming::event_parser::UnifiedEvent;
+use crate::trading::common::FillReport;
+
+/// 一笔买入或卖出的成交记录
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PnlFill {
+    /// 代币数量变化（最小单位），买入为正，卖出为负
+    pub tokens: i128,
+    /// SOL 变化（lamports），买入为正表示花费，卖出为负表示收到，与 `FillReport::sol_spent` 一致
+    pub sol_spent: i128,
+    pub fee_lamports: u64,
+    /// 成交时间（Unix 秒）
+    pub timestamp: i64,
+}
+
+/// 单个代币的盈亏，成本按加权平均计算
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TokenPnl {
+    /// 当前持仓（代币最小单位）
+    pub token_amount: u64,
+    /// 当前持仓的成本（lamports），含网络费用
+    pub cost_basis: u64,
+    /// 已实现盈亏（lamports），已扣除卖出的网络费用
+    pub realized_pnl: i64,
+    /// 最新价格（lamports / 代币最小单位），没有价格时不计算浮动盈亏
+    pub last_price: Option<f64>,
+    pub fills: Vec<PnlFill>,
+}
+
+impl TokenPnl {
+    /// 按最新价格计算的浮动盈亏（lamports），没有持仓或价格时为 0
+    pub fn unrealized_pnl(&self) -> i64 {
+        match self.last_price {
+            Some(price) if self.token_amount > 0 => {
+                (self.token_amount as f64 * price) as i64 - self.cost_basis as i64
+            }
+            _ => 0,
+        }
+    }
+
+    pub fn total_pnl(&self) -> i64 {
+        self.realized_pnl + self.unrealized_pnl()
+    }
+
+    /// `day` 当天（按 `timezone` 划分）卖出实现的盈亏（lamports），成本按当时的加权平均计算
+    pub fn realized_pnl_on(&self, day: NaiveDate, timezone: &FixedOffset) -> i64 {
+        let mut replay = TokenPnl::default();
+        let mut realized = 0;
+        for fill in &self.fills {
+            let before = replay.realized_pnl;
+            replay.apply(*fill);
+            let filled_on = DateTime::from_timestamp(fill.timestamp, 0)
+                .map(|at| trading_day(timezone, at));
+            if filled_on == Some(day) {
+                realized += replay.realized_pnl - before;
+            }
+        }
+        realized
+    }
+
+    fn apply(&mut self, fill: PnlFill) {
+        if fill.tokens > 0 {
+            self.token_amount += fill.tokens as u64;
+            self.cost_basis += (fill.sol_spent.max(0) as u64) + fill.fee_lamports;
+        } else {
+            let sold = fill.tokens.unsigned_abs() as u64;
+            // 卖出超过记录持仓的部分（如未记录的买入）按零成本计算
+            let matched = sold.min(self.token_amount);
+            let cost = if self.token_amount == 0 {
+                0
+            } else {
+                (self.cost_basis as u128 * matched as u128 / self.token_amount as u128) as u64
+            };
+            let proceeds = (-fill.sol_spent).max(0) as i64 - fill.fee_lamports as i64;
+            self.realized_pnl += proceeds - cost as i64;
+            self.token_amount -= matched;
+            self.cost_basis -= cost;
+        }
+        self.fills.push(fill);
+    }
+}
+
+/// 全部代币的盈亏汇总
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PnlStatistics {
+    pub realized_pnl: i64,
+    pub unrealized_pnl: i64,
+    pub total_pnl: i64,
+    /// 仍有持仓的代币数量
+    pub open_positions: usize,
+    /// 已全部卖出的代币中盈利和亏损的数量
+    pub winning_tokens: usize,
+    pub losing_tokens: usize,
+    pub tokens: HashMap<Pubkey, TokenPnl>,
+}
+
+/// 盈亏跟踪：记录每笔买入和卖出的实际成交，计算每个代币的已实现和浮动盈亏
+///
+/// 成交来自 `SolanaTrade::get_fill_report` 等链上成交结果，价格可由流事件或链上刷新更新。
+/// 设置存储时每次变化都会保存，重启后从存储恢复
+pub struct PnlTracker {
+    tokens: RwLock<HashMap<Pubkey, TokenPnl>>,
+    store: Option<Arc<dyn PositionStore>>,
+}
+
+impl Default for PnlTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PnlTracker {
+    pub fn new() -> Self {
+        Self { tokens: RwLock::new(HashMap::new()), store: None }
+    }
+
+    /// 从存储加载已记录的盈亏，之后的变化都写入存储
+    pub fn with_store(store: Arc<dyn PositionStore>) -> Result<Self> {
+        Ok(Self { tokens: RwLock::new(store.load_pnl()?), store: Some(store) })
+    }
+
+    /// 记录一笔成交，`tokens_received` 为正时为买入，为负时为卖出
+    pub fn record_fill(&self, mint: Pubkey, fill: &FillReport) -> Result<()> {
+        if fill.tokens_received == 0 {
+            return Err(anyhow!("Fill for {} moved no tokens", mint));
+        }
+        let fill = PnlFill {
+            tokens: fill.tokens_received,
+            sol_spent: fill.sol_spent,
+            fee_lamports: fill.fee_lamports,
+            timestamp: chrono::Utc::now().timestamp(),
+        };
+        let mut tokens = self.tokens.write().unwrap();
+        let token = tokens.entry(mint).or_default();
+        token.apply(fill);
+        self.persist(&mint, token);
+        Ok(())
+    }
+
+    /// 更新代币价格（lamports / 代币最小单位），只更新已记录的代币，不写入存储
+    pub fn update_price(&self, mint: &Pubkey, price: f64) {
+        if let Some(token) = self.tokens.write().unwrap().get_mut(mint) {
+            token.last_price = Some(price);
+        }
+    }
+
+    /// 用一笔交易的成交价更新价格
+    pub fn observe(&self, swap: &SwapObservation) {
+        if swap.token_amount > 0 {
+            self.update_price(&swap.mint, swap.sol_amount as f64 / swap.token_amount as f64);
+        }
+    }
+
+    /// 处理流事件，可直接在订阅回调中调用
+    pub fn on_event(&self, event: &dyn UnifiedEvent) {
+        if let Some(swap) = SwapObservation::from_event(event) {
+            self.observe(&swap);
+        }
+    }
+
+    pub fn token(&self, mint: &Pubkey) -> Option<TokenPnl> {
+        self.tokens.read().unwrap().get(mint).cloned()
+    }
+
+    pub fn statistics(&self) -> PnlStatistics {
+        let tokens = self.tokens.read().unwrap().clone();
+        let mut statistics = PnlStatistics::default();
+        for token in tokens.values() {
+            statistics.realized_pnl += token.realized_pnl;
+            statistics.unrealized_pnl += token.unrealized_pnl();
+            if token.token_amount > 0 {
+                statistics.open_positions += 1;
+            } else if token.realized_pnl > 0 {
+                statistics.winning_tokens += 1;
+            } else {
+                statistics.losing_tokens += 1;
+            }
+        }
+        statistics.total_pnl = statistics.realized_pnl + statistics.unrealized_pnl;
+        statistics.tokens = tokens;
+        statistics
+    }
+
+    /// 所有代币在 `day` 当天（按 `timezone` 划分）的已实现盈亏合计（lamports）
+    pub fn daily_realized_pnl(&self, day: NaiveDate, timezone: &FixedOffset) -> i64 {
+        self.tokens.read().unwrap().values().map(|token| token.realized_pnl_on(day, timezone)).sum()
+    }
+
+    fn persist(&self, mint: &Pubkey, token: &TokenPnl) {
+        if let Some(Err(e)) = self.store.as_ref().map(|store| store.save_pnl(mint, token)) {
+            eprintln!("保存 {} 的盈亏失败: {}", mint, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitor::position_store::MemoryPositionStore;
+
+    fn fill(tokens_received: i128, sol_spent: i128) -> FillReport {
+        FillReport {
+            tokens_received,
+            sol_spent,
+            price_paid: 0.0,
+            fee_lamports: 5_000,
+            decimals: 6,
+            transfer_fee: 0,
+            transfer_fee_basis_points: 0,
+        }
+    }
+
+    #[test]
+    fn test_realized_and_unrealized_pnl() {
+        let store: Arc<dyn PositionStore> = Arc::new(MemoryPositionStore::default());
+        let tracker = PnlTracker::with_store(store.clone()).unwrap();
+        let (mint, closed) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        tracker.record_fill(mint, &fill(1_000, 995_000)).unwrap();
+        tracker.record_fill(mint, &fill(1_000, 1_995_000)).unwrap();
+        // 平均成本 1500 / 代币，卖出一半收到 2005000
+        tracker.record_fill(mint, &fill(-1_000, -2_005_000)).unwrap();
+        let token = tracker.token(&mint).unwrap();
+        assert_eq!((token.token_amount, token.cost_basis), (1_000, 1_500_000));
+        assert_eq!(token.realized_pnl, 500_000);
+        assert_eq!(token.unrealized_pnl(), 0);
+
+        tracker.update_price(&mint, 1_000.0);
+        tracker.record_fill(closed, &fill(500, 495_000)).unwrap();
+        tracker.record_fill(closed, &fill(-500, -405_000)).unwrap();
+        let statistics = tracker.statistics();
+        assert_eq!((statistics.realized_pnl, statistics.unrealized_pnl), (400_000, -500_000));
+        assert_eq!(statistics.total_pnl, -100_000);
+        assert_eq!(
+            (statistics.open_positions, statistics.winning_tokens, statistics.losing_tokens),
+            (1, 0, 1)
+        );
+
+        // 重启后从存储恢复
+        let reopened = PnlTracker::with_store(store).unwrap();
+        assert_eq!(reopened.token(&closed), tracker.token(&closed));
+        assert_eq!(reopened.token(&mint).unwrap().fills.len(), 3);
+    }
+
+    #[test]
+    fn test_realized_pnl_by_trading_day() {
+        let at = |rfc3339: &str| DateTime::parse_from_rfc3339(rfc3339).unwrap().timestamp();
+        let mut token = TokenPnl::default();
+        for (tokens, sol_spent, timestamp) in [
+            (2_000, 2_000_000, at("2025-01-01T01:00:00Z")),
+            // UTC 1 月 1 日 20:00，UTC+8 已是 1 月 2 日
+            (-1_000, -1_500_000, at("2025-01-01T20:00:00Z")),
+            (-1_000, -800_000, at("2025-01-02T10:00:00Z")),
+        ] {
+            token.apply(PnlFill { tokens, sol_spent, fee_lamports: 0, timestamp });
+        }
+        let (jan1, jan2) = (NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(), NaiveDate::from_ymd_opt(2025, 1, 2).unwrap());
+        let utc = FixedOffset::east_opt(0).unwrap();
+        let utc8 = FixedOffset::east_opt(8 * 3600).unwrap();
+        assert_eq!((token.realized_pnl_on(jan1, &utc), token.realized_pnl_on(jan2, &utc)), (500_000, -200_000));
+        assert_eq!((token.realized_pnl_on(jan1, &utc8), token.realized_pnl_on(jan2, &utc8)), (0, 300_000));
+    }
+}
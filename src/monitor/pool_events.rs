@@ -0,0 +1,434 @@
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::broadcast;
+
+use crate::constants::pumpswap::accounts::WSOL_TOKEN_ACCOUNT;
+use crate::solana_streamer_sdk::streaming::event_parser::{
+    protocols::{
+        pumpswap::{PumpSwapCreatePoolEvent, PumpSwapDepositEvent, PumpSwapWithdrawEvent},
+        raydium_cpmm::{
+            RaydiumCpmmDepositEvent, RaydiumCpmmInitializeEvent, RaydiumCpmmWithdrawEvent,
+        },
+    },
+    UnifiedEvent,
+};
+use crate::trading::factory::DexType;
+use crate::trading::pumpfun::common::get_pool_authority_pda;
+
+/// 事件广播通道容量
+const POOL_EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// 池子事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoolEventKind {
+    /// 新池子创建
+    Created,
+    /// 添加流动性
+    LiquidityAdded,
+    /// 移除流动性
+    LiquidityRemoved,
+}
+
+/// 统一的池子创建 / 添加 / 移除流动性事件
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolEvent {
+    pub kind: PoolEventKind,
+    pub dex_type: DexType,
+    pub pool: Pubkey,
+    /// 非 SOL 一侧的代币，池子不含 WSOL 时为 token0 / base
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub base_amount: u64,
+    pub quote_amount: u64,
+    /// SOL 一侧的数量（lamports），池子不含 WSOL 时为 0
+    pub sol_amount: u64,
+    /// 事件后池子 SOL 一侧的储备（lamports），事件不含储备或池子不含 WSOL 时为 None
+    pub sol_reserve: Option<u64>,
+    pub user: Pubkey,
+    /// 是否为 PumpFun 迁移创建的 PumpSwap 池子
+    pub is_migration: bool,
+    pub signature: String,
+    pub slot: u64,
+}
+
+impl PoolEvent {
+    /// 从流事件中解析池子事件
+    pub fn from_event(event: &dyn UnifiedEvent) -> Option<Self> {
+        let any = event.as_any();
+        let signature = event.signature().to_string();
+        let slot = event.slot();
+
+        if let Some(e) = any.downcast_ref::<PumpSwapCreatePoolEvent>() {
+            // 迁移池由 PumpFun 的 pool-authority PDA 以 index 0 创建
+            let is_migration = e.index == 0
+                && get_pool_authority_pda(&e.base_mint).is_some_and(|pda| pda == e.creator);
+            return Some(Self::build(
+                PoolEventKind::Created,
+                DexType::PumpSwap,
+                e.pool,
+                (e.base_mint, e.base_amount_in),
+                (e.quote_mint, e.quote_amount_in),
+                Some((e.base_amount_in, e.quote_amount_in)),
+                e.creator,
+                is_migration,
+                signature,
+                slot,
+            ));
+        }
+
+        if let Some(e) = any.downcast_ref::<PumpSwapDepositEvent>() {
+            // 事件中的池子储备为操作前的数量
+            return Some(Self::build(
+                PoolEventKind::LiquidityAdded,
+                DexType::PumpSwap,
+                e.pool,
+                (e.base_mint, e.base_amount_in),
+                (e.quote_mint, e.quote_amount_in),
+                Some((
+                    e.pool_base_token_reserves.saturating_add(e.base_amount_in),
+                    e.pool_quote_token_reserves.saturating_add(e.quote_amount_in),
+                )),
+                e.user,
+                false,
+                signature,
+                slot,
+            ));
+        }
+
+        if let Some(e) = any.downcast_ref::<PumpSwapWithdrawEvent>() {
+            return Some(Self::build(
+                PoolEventKind::LiquidityRemoved,
+                DexType::PumpSwap,
+                e.pool,
+                (e.base_mint, e.base_amount_out),
+                (e.quote_mint, e.quote_amount_out),
+                Some((
+                    e.pool_base_token_reserves.saturating_sub(e.base_amount_out),
+                    e.pool_quote_token_reserves.saturating_sub(e.quote_amount_out),
+                )),
+                e.user,
+                false,
+                signature,
+                slot,
+            ));
+        }
+
+        if let Some(e) = any.downcast_ref::<RaydiumCpmmInitializeEvent>() {
+            return Some(Self::build(
+                PoolEventKind::Created,
+                DexType::RaydiumCpmm,
+                e.pool_state,
+                (e.token0_mint, e.init_amount0),
+                (e.token1_mint, e.init_amount1),
+                Some((e.init_amount0, e.init_amount1)),
+                e.creator,
+                false,
+                signature,
+                slot,
+            ));
+        }
+
+        if let Some(e) = any.downcast_ref::<RaydiumCpmmDepositEvent>() {
+            // 指令中只有最大投入量，实际数量以链上为准
+            return Some(Self::build(
+                PoolEventKind::LiquidityAdded,
+                DexType::RaydiumCpmm,
+                e.pool_state,
+                (e.vault0_mint, e.maximum_token0_amount),
+                (e.vault1_mint, e.maximum_token1_amount),
+                None,
+                e.owner,
+                false,
+                signature,
+                slot,
+            ));
+        }
+
+        if let Some(e) = any.downcast_ref::<RaydiumCpmmWithdrawEvent>() {
+            // 指令中只有最小取出量，实际取出不少于该数量
+            return Some(Self::build(
+                PoolEventKind::LiquidityRemoved,
+                DexType::RaydiumCpmm,
+                e.pool_state,
+                (e.vault0_mint, e.minimum_token0_amount),
+                (e.vault1_mint, e.minimum_token1_amount),
+                None,
+                e.owner,
+                false,
+                signature,
+                slot,
+            ));
+        }
+
+        None
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn build(
+        kind: PoolEventKind,
+        dex_type: DexType,
+        pool: Pubkey,
+        side0: (Pubkey, u64),
+        side1: (Pubkey, u64),
+        reserves: Option<(u64, u64)>,
+        user: Pubkey,
+        is_migration: bool,
+        signature: String,
+        slot: u64,
+    ) -> Self {
+        // 统一把 WSOL 放到 quote 一侧
+        let swapped = side0.0 == WSOL_TOKEN_ACCOUNT;
+        let ((base_mint, base_amount), (quote_mint, quote_amount)) =
+            if swapped { (side1, side0) } else { (side0, side1) };
+        let is_sol_pool = quote_mint == WSOL_TOKEN_ACCOUNT;
+        let sol_amount = if is_sol_pool { quote_amount } else { 0 };
+        let sol_reserve =
+            reserves
+                .filter(|_| is_sol_pool)
+                .map(|(reserve0, reserve1)| if swapped { reserve0 } else { reserve1 });
+        Self {
+            kind,
+            dex_type,
+            pool,
+            base_mint,
+            quote_mint,
+            base_amount,
+            quote_amount,
+            sol_amount,
+            sol_reserve,
+            user,
+            is_migration,
+            signature,
+            slot,
+        }
+    }
+}
+
+/// 池子事件监控配置
+#[derive(Debug, Clone)]
+pub struct PoolEventConfig {
+    /// 是否推送新池子创建事件
+    pub include_created: bool,
+    /// 添加流动性事件的最小 SOL 数量（lamports），0 表示全部推送
+    pub min_liquidity_sol: u64,
+    /// 只推送 PumpFun 迁移创建的池子
+    pub migration_only: bool,
+    /// 是否推送移除流动性事件，不受 `min_liquidity_sol` 限制
+    pub include_removed: bool,
+}
+
+impl Default for PoolEventConfig {
+    fn default() -> Self {
+        Self {
+            include_created: true,
+            min_liquidity_sol: 10_000_000_000,
+            migration_only: false,
+            include_removed: false,
+        }
+    }
+}
+
+/// 池子事件监控，过滤后广播给策略
+pub struct PoolEventMonitor {
+    config: PoolEventConfig,
+    sender: broadcast::Sender<PoolEvent>,
+}
+
+impl PoolEventMonitor {
+    pub fn new(config: PoolEventConfig) -> Self {
+        let (sender, _) = broadcast::channel(POOL_EVENT_CHANNEL_CAPACITY);
+        Self { config, sender }
+    }
+
+    /// 订阅池子事件
+    pub fn subscribe(&self) -> broadcast::Receiver<PoolEvent> {
+        self.sender.subscribe()
+    }
+
+    /// 处理流事件，命中过滤条件时返回并广播
+    pub fn on_event(&self, event: &dyn UnifiedEvent) -> Option<PoolEvent> {
+        let pool_event = PoolEvent::from_event(event)?;
+        if !self.accept(&pool_event) {
+            return None;
+        }
+        // 没有订阅者时发送失败，忽略即可
+        let _ = self.sender.send(pool_event.clone());
+        Some(pool_event)
+    }
+
+    fn accept(&self, event: &PoolEvent) -> bool {
+        match event.kind {
+            PoolEventKind::Created => {
+                self.config.include_created && (!self.config.migration_only || event.is_migration)
+            }
+            PoolEventKind::LiquidityAdded => {
+                !self.config.migration_only && event.sol_amount >= self.config.min_liquidity_sol
+            }
+            PoolEventKind::LiquidityRemoved => {
+                self.config.include_removed && !self.config.migration_only
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool_event(kind: PoolEventKind, sol_amount: u64, is_migration: bool) -> PoolEvent {
+        PoolEvent::build(
+            kind,
+            DexType::PumpSwap,
+            Pubkey::new_unique(),
+            (Pubkey::new_unique(), 1_000),
+            (WSOL_TOKEN_ACCOUNT, sol_amount),
+            None,
+            Pubkey::new_unique(),
+            is_migration,
+            String::new(),
+            1,
+        )
+    }
+
+    #[test]
+    fn test_build_puts_wsol_on_quote_side() {
+        let mint = Pubkey::new_unique();
+
+        // WSOL 在 token0 一侧时交换，储备也取 token0 一侧
+        let event = PoolEvent::build(
+            PoolEventKind::Created,
+            DexType::RaydiumCpmm,
+            Pubkey::new_unique(),
+            (WSOL_TOKEN_ACCOUNT, 5_000),
+            (mint, 9_000),
+            Some((50_000, 90_000)),
+            Pubkey::new_unique(),
+            false,
+            String::new(),
+            1,
+        );
+        assert_eq!((event.base_mint, event.base_amount), (mint, 9_000));
+        assert_eq!((event.quote_mint, event.quote_amount), (WSOL_TOKEN_ACCOUNT, 5_000));
+        assert_eq!((event.sol_amount, event.sol_reserve), (5_000, Some(50_000)));
+
+        let event = PoolEvent::build(
+            PoolEventKind::Created,
+            DexType::RaydiumCpmm,
+            Pubkey::new_unique(),
+            (mint, 9_000),
+            (WSOL_TOKEN_ACCOUNT, 5_000),
+            Some((90_000, 50_000)),
+            Pubkey::new_unique(),
+            false,
+            String::new(),
+            1,
+        );
+        assert_eq!((event.base_mint, event.quote_mint), (mint, WSOL_TOKEN_ACCOUNT));
+        assert_eq!((event.sol_amount, event.sol_reserve), (5_000, Some(50_000)));
+
+        // 不含 WSOL 的池子保持原顺序，没有 SOL 数量和储备
+        let quote = Pubkey::new_unique();
+        let event = PoolEvent::build(
+            PoolEventKind::Created,
+            DexType::RaydiumCpmm,
+            Pubkey::new_unique(),
+            (mint, 9_000),
+            (quote, 5_000),
+            Some((90_000, 50_000)),
+            Pubkey::new_unique(),
+            false,
+            String::new(),
+            1,
+        );
+        assert_eq!((event.base_mint, event.quote_mint), (mint, quote));
+        assert_eq!((event.sol_amount, event.sol_reserve), (0, None));
+    }
+
+    #[test]
+    fn test_migration_detected_from_pool_authority() {
+        let mint = Pubkey::new_unique();
+        let pool_authority = get_pool_authority_pda(&mint).unwrap();
+        let create = |creator: Pubkey, index: u16| PumpSwapCreatePoolEvent {
+            index,
+            creator,
+            base_mint: mint,
+            quote_mint: WSOL_TOKEN_ACCOUNT,
+            base_amount_in: 1_000,
+            quote_amount_in: 2_000,
+            pool: Pubkey::new_unique(),
+            ..Default::default()
+        };
+
+        let event = PoolEvent::from_event(&create(pool_authority, 0)).unwrap();
+        assert_eq!(event.kind, PoolEventKind::Created);
+        assert!(event.is_migration);
+        assert_eq!((event.sol_amount, event.sol_reserve), (2_000, Some(2_000)));
+
+        // 非 index 0 或非 pool-authority 创建的池子不是迁移池
+        assert!(!PoolEvent::from_event(&create(pool_authority, 1)).unwrap().is_migration);
+        assert!(!PoolEvent::from_event(&create(Pubkey::new_unique(), 0)).unwrap().is_migration);
+    }
+
+    #[test]
+    fn test_pumpswap_liquidity_reserves_after_event() {
+        let mint = Pubkey::new_unique();
+        let deposit = PumpSwapDepositEvent {
+            base_mint: mint,
+            quote_mint: WSOL_TOKEN_ACCOUNT,
+            base_amount_in: 100,
+            quote_amount_in: 10,
+            pool_base_token_reserves: 1_000,
+            pool_quote_token_reserves: 100,
+            ..Default::default()
+        };
+        let event = PoolEvent::from_event(&deposit).unwrap();
+        assert_eq!(event.kind, PoolEventKind::LiquidityAdded);
+        assert_eq!((event.sol_amount, event.sol_reserve), (10, Some(110)));
+
+        let withdraw = PumpSwapWithdrawEvent {
+            base_mint: mint,
+            quote_mint: WSOL_TOKEN_ACCOUNT,
+            base_amount_out: 100,
+            quote_amount_out: 10,
+            pool_base_token_reserves: 1_000,
+            pool_quote_token_reserves: 100,
+            ..Default::default()
+        };
+        let event = PoolEvent::from_event(&withdraw).unwrap();
+        assert_eq!(event.kind, PoolEventKind::LiquidityRemoved);
+        assert_eq!((event.sol_amount, event.sol_reserve), (10, Some(90)));
+
+        // 取出量超过储备时不会下溢
+        let withdraw = PumpSwapWithdrawEvent { quote_amount_out: 500, ..withdraw };
+        assert_eq!(PoolEvent::from_event(&withdraw).unwrap().sol_reserve, Some(0));
+    }
+
+    #[test]
+    fn test_accept_filter_matrix() {
+        let created = pool_event(PoolEventKind::Created, 0, false);
+        let migration = pool_event(PoolEventKind::Created, 0, true);
+        let small_add = pool_event(PoolEventKind::LiquidityAdded, 1_000_000_000, false);
+        let large_add = pool_event(PoolEventKind::LiquidityAdded, 10_000_000_000, false);
+        let removed = pool_event(PoolEventKind::LiquidityRemoved, 1, false);
+        let events = [&created, &migration, &small_add, &large_add, &removed];
+
+        let cases = [
+            (PoolEventConfig::default(), [true, true, false, true, false]),
+            (PoolEventConfig { include_created: false, ..Default::default() }, [false, false, false, true, false]),
+            (PoolEventConfig { min_liquidity_sol: 0, ..Default::default() }, [true, true, true, true, false]),
+            (PoolEventConfig { include_removed: true, ..Default::default() }, [true, true, false, true, true]),
+            (
+                PoolEventConfig { migration_only: true, include_removed: true, ..Default::default() },
+                [false, true, false, false, false],
+            ),
+            (
+                PoolEventConfig { migration_only: true, include_created: false, ..Default::default() },
+                [false, false, false, false, false],
+            ),
+        ];
+        for (config, expected) in cases {
+            let monitor = PoolEventMonitor::new(config.clone());
+            let accepted: Vec<bool> = events.iter().map(|event| monitor.accept(event)).collect();
+            assert_eq!(accepted, expected, "{:?}", config);
+        }
+    }
+}
@@ -0,0 +1,293 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::monitor::pnl::TokenPnl;
+use crate::monitor::price_monitor::MonitoredPosition;
+
+/// 持仓持久化，`PriceMonitor`、`CopySellMirror` 和 `PnlTracker` 启动时加载，每次修改时写入
+pub trait PositionStore: Send + Sync {
+    /// 价格监控中的持仓
+    fn load_monitored(&self) -> Result<Vec<MonitoredPosition>>;
+
+    fn save_monitored(&self, position: &MonitoredPosition) -> Result<()>;
+
+    fn remove_monitored(&self, mint: &Pubkey) -> Result<()>;
+
+    /// 跟单买入的持仓（代币数量）
+    fn load_copied(&self) -> Result<HashMap<Pubkey, u64>>;
+
+    /// 保存跟单持仓，数量为 0 时删除
+    fn save_copied(&self, mint: &Pubkey, token_amount: u64) -> Result<()>;
+
+    /// 每个代币的成交记录和盈亏
+    fn load_pnl(&self) -> Result<HashMap<Pubkey, TokenPnl>>;
+
+    fn save_pnl(&self, mint: &Pubkey, pnl: &TokenPnl) -> Result<()>;
+
+    /// 每个代币最近一次跟买的目标钱包交易 slot，用于跟买冷却和去重，主备实例共用存储时共享
+    fn load_copy_cooldowns(&self) -> Result<HashMap<Pubkey, u64>> {
+        Ok(HashMap::new())
+    }
+
+    /// 保存跟买冷却，slot 为 0 时删除
+    fn save_copy_cooldown(&self, _mint: &Pubkey, _slot: u64) -> Result<()> {
+        Ok(())
+    }
+
+    /// 已提交交易的幂等键及提交时间（Unix 毫秒），主备实例共用存储时接管的实例不会重复提交
+    fn load_idempotency_keys(&self) -> Result<HashMap<String, i64>> {
+        Ok(HashMap::new())
+    }
+
+    /// 保存幂等键，提交时间为 0 时删除
+    fn save_idempotency_key(&self, _key: &str, _submitted_ms: i64) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// 存储的全部持仓，以代币地址字符串为键
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct StoredPositions {
+    monitored: HashMap<String, MonitoredPosition>,
+    copied: HashMap<String, u64>,
+    #[serde(default)]
+    pnl: HashMap<String, TokenPnl>,
+    #[serde(default)]
+    copy_cooldowns: HashMap<String, u64>,
+    #[serde(default)]
+    idempotency_keys: HashMap<String, i64>,
+}
+
+impl StoredPositions {
+    fn save_monitored(&mut self, position: &MonitoredPosition) {
+        self.monitored.insert(position.mint.to_string(), position.clone());
+    }
+
+    fn remove_monitored(&mut self, mint: &Pubkey) {
+        self.monitored.remove(&mint.to_string());
+    }
+
+    fn save_copied(&mut self, mint: &Pubkey, token_amount: u64) {
+        if token_amount == 0 {
+            self.copied.remove(&mint.to_string());
+        } else {
+            self.copied.insert(mint.to_string(), token_amount);
+        }
+    }
+
+    fn copied(&self) -> Result<HashMap<Pubkey, u64>> {
+        self.copied.iter().map(|(mint, amount)| Ok((mint.parse()?, *amount))).collect()
+    }
+
+    fn save_pnl(&mut self, mint: &Pubkey, pnl: &TokenPnl) {
+        self.pnl.insert(mint.to_string(), pnl.clone());
+    }
+
+    fn pnl(&self) -> Result<HashMap<Pubkey, TokenPnl>> {
+        self.pnl.iter().map(|(mint, pnl)| Ok((mint.parse()?, pnl.clone()))).collect()
+    }
+
+    fn save_copy_cooldown(&mut self, mint: &Pubkey, slot: u64) {
+        if slot == 0 {
+            self.copy_cooldowns.remove(&mint.to_string());
+        } else {
+            self.copy_cooldowns.insert(mint.to_string(), slot);
+        }
+    }
+
+    fn copy_cooldowns(&self) -> Result<HashMap<Pubkey, u64>> {
+        self.copy_cooldowns.iter().map(|(mint, slot)| Ok((mint.parse()?, *slot))).collect()
+    }
+
+    fn save_idempotency_key(&mut self, key: &str, submitted_ms: i64) {
+        if submitted_ms == 0 {
+            self.idempotency_keys.remove(key);
+        } else {
+            self.idempotency_keys.insert(key.to_string(), submitted_ms);
+        }
+    }
+}
+
+/// 内存存储，不跨进程保留，用于测试或不需要恢复的场景
+#[derive(Default)]
+pub struct MemoryPositionStore {
+    positions: Mutex<StoredPositions>,
+}
+
+impl PositionStore for MemoryPositionStore {
+    fn load_monitored(&self) -> Result<Vec<MonitoredPosition>> {
+        Ok(self.positions.lock().unwrap().monitored.values().cloned().collect())
+    }
+
+    fn save_monitored(&self, position: &MonitoredPosition) -> Result<()> {
+        self.positions.lock().unwrap().save_monitored(position);
+        Ok(())
+    }
+
+    fn remove_monitored(&self, mint: &Pubkey) -> Result<()> {
+        self.positions.lock().unwrap().remove_monitored(mint);
+        Ok(())
+    }
+
+    fn load_copied(&self) -> Result<HashMap<Pubkey, u64>> {
+        self.positions.lock().unwrap().copied()
+    }
+
+    fn save_copied(&self, mint: &Pubkey, token_amount: u64) -> Result<()> {
+        self.positions.lock().unwrap().save_copied(mint, token_amount);
+        Ok(())
+    }
+
+    fn load_pnl(&self) -> Result<HashMap<Pubkey, TokenPnl>> {
+        self.positions.lock().unwrap().pnl()
+    }
+
+    fn save_pnl(&self, mint: &Pubkey, pnl: &TokenPnl) -> Result<()> {
+        self.positions.lock().unwrap().save_pnl(mint, pnl);
+        Ok(())
+    }
+
+    fn load_copy_cooldowns(&self) -> Result<HashMap<Pubkey, u64>> {
+        self.positions.lock().unwrap().copy_cooldowns()
+    }
+
+    fn save_copy_cooldown(&self, mint: &Pubkey, slot: u64) -> Result<()> {
+        self.positions.lock().unwrap().save_copy_cooldown(mint, slot);
+        Ok(())
+    }
+
+    fn load_idempotency_keys(&self) -> Result<HashMap<String, i64>> {
+        Ok(self.positions.lock().unwrap().idempotency_keys.clone())
+    }
+
+    fn save_idempotency_key(&self, key: &str, submitted_ms: i64) -> Result<()> {
+        self.positions.lock().unwrap().save_idempotency_key(key, submitted_ms);
+        Ok(())
+    }
+}
+
+/// JSON 文件存储，每次修改重写整个文件
+///
+/// 先写临时文件再重命名，进程中途退出时不会留下写了一半的文件
+pub struct JsonFilePositionStore {
+    path: PathBuf,
+    positions: Mutex<StoredPositions>,
+}
+
+impl JsonFilePositionStore {
+    /// 打开存储文件，文件不存在时从空持仓开始
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let positions = match File::open(&path) {
+            Ok(file) => serde_json::from_reader(file)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => StoredPositions::default(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self { path, positions: Mutex::new(positions) })
+    }
+
+    fn update(&self, f: impl FnOnce(&mut StoredPositions)) -> Result<()> {
+        let mut positions = self.positions.lock().unwrap();
+        f(&mut positions);
+        let tmp_path = self.path.with_extension("tmp");
+        let writer = BufWriter::new(File::create(&tmp_path)?);
+        serde_json::to_writer(writer, &*positions)?;
+        fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+impl PositionStore for JsonFilePositionStore {
+    fn load_monitored(&self) -> Result<Vec<MonitoredPosition>> {
+        Ok(self.positions.lock().unwrap().monitored.values().cloned().collect())
+    }
+
+    fn save_monitored(&self, position: &MonitoredPosition) -> Result<()> {
+        self.update(|positions| positions.save_monitored(position))
+    }
+
+    fn remove_monitored(&self, mint: &Pubkey) -> Result<()> {
+        self.update(|positions| positions.remove_monitored(mint))
+    }
+
+    fn load_copied(&self) -> Result<HashMap<Pubkey, u64>> {
+        self.positions.lock().unwrap().copied()
+    }
+
+    fn save_copied(&self, mint: &Pubkey, token_amount: u64) -> Result<()> {
+        self.update(|positions| positions.save_copied(mint, token_amount))
+    }
+
+    fn load_pnl(&self) -> Result<HashMap<Pubkey, TokenPnl>> {
+        self.positions.lock().unwrap().pnl()
+    }
+
+    fn save_pnl(&self, mint: &Pubkey, pnl: &TokenPnl) -> Result<()> {
+        self.update(|positions| positions.save_pnl(mint, pnl))
+    }
+
+    fn load_copy_cooldowns(&self) -> Result<HashMap<Pubkey, u64>> {
+        self.positions.lock().unwrap().copy_cooldowns()
+    }
+
+    fn save_copy_cooldown(&self, mint: &Pubkey, slot: u64) -> Result<()> {
+        self.update(|positions| positions.save_copy_cooldown(mint, slot))
+    }
+
+    fn load_idempotency_keys(&self) -> Result<HashMap<String, i64>> {
+        Ok(self.positions.lock().unwrap().idempotency_keys.clone())
+    }
+
+    fn save_idempotency_key(&self, key: &str, submitted_ms: i64) -> Result<()> {
+        self.update(|positions| positions.save_idempotency_key(key, submitted_ms))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::factory::DexType;
+
+    #[test]
+    fn test_json_file_store_survives_reopen() {
+        let path = std::env::temp_dir().join(format!("positions-{}.json", Pubkey::new_unique()));
+        let (monitored, copied) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let position = MonitoredPosition {
+            mint: monitored,
+            entry_price: 1.0,
+            peak_price: 1.5,
+            last_price: 1.2,
+            config: None,
+            dex_type: Some(DexType::PumpSwap),
+            token_amount: 1_000,
+            cost_basis: 0,
+        };
+
+        let store = JsonFilePositionStore::open(&path).unwrap();
+        store.save_monitored(&position).unwrap();
+        store.save_copied(&copied, 500).unwrap();
+        store.save_copied(&Pubkey::new_unique(), 0).unwrap();
+        store.save_copy_cooldown(&copied, 42).unwrap();
+        store.save_idempotency_key("buy-1", 1_000).unwrap();
+
+        let reopened = JsonFilePositionStore::open(&path).unwrap();
+        assert_eq!(reopened.load_monitored().unwrap(), vec![position]);
+        assert_eq!(reopened.load_copied().unwrap(), HashMap::from([(copied, 500)]));
+        assert_eq!(reopened.load_copy_cooldowns().unwrap(), HashMap::from([(copied, 42)]));
+        assert_eq!(reopened.load_idempotency_keys().unwrap(), HashMap::from([("buy-1".to_string(), 1_000)]));
+
+        reopened.remove_monitored(&monitored).unwrap();
+        reopened.save_copied(&copied, 0).unwrap();
+        let reopened = JsonFilePositionStore::open(&path).unwrap();
+        assert!(reopened.load_monitored().unwrap().is_empty());
+        assert!(reopened.load_copied().unwrap().is_empty());
+        fs::remove_file(path).unwrap();
+    }
+}
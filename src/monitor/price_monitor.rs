@@ -0,0 +1,1062 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+use crate::common::priority_fee_estimator::PriorityFeeEstimator;
+use crate::common::{ExposureTracker, PriorityFee, SolanaRpcClient};
+use crate::monitor::candles::{Candle, CandleAggregator, CandleInterval};
+use crate::monitor::large_swap::SwapObservation;
+use crate::monitor::position_store::PositionStore;
+use crate::solana_streamer_sdk::streaming::event_parser::protocols::pumpfun::PumpFunTradeEvent;
+use crate::solana_streamer_sdk::streaming::event_parser::UnifiedEvent;
+use crate::strategy::{StrategyEvent, StrategyEventBus};
+use crate::trading::common::FillReport;
+use crate::trading::factory::DexType;
+use crate::trading::pumpfun::common::get_spot_price;
+use crate::trading::{resolve_dex, Router};
+
+/// 止盈止损和回调买入信号广播通道容量
+const EXIT_SIGNAL_CHANNEL_CAPACITY: usize = 1024;
+
+/// 止盈止损配置，百分比均相对入场价（移动止损相对最高价），None 表示不启用
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct TakeProfitStopLossConfig {
+    /// 价格较入场价上涨该百分比时止盈
+    pub take_profit_percent: Option<f64>,
+    /// 价格较入场价下跌该百分比时止损
+    pub stop_loss_percent: Option<f64>,
+    /// 移动止损：价格从入场后的最高价回撤该百分比时卖出
+    pub trailing_stop_percent: Option<f64>,
+    /// 止盈时只卖出持仓的该百分比，剩余部分以触发价为新的入场价继续监控，
+    /// 价格再涨 `take_profit_percent` 时再次卖出；None 或 100 时全部卖出
+    #[serde(default)]
+    pub take_profit_sell_percent: Option<f64>,
+    /// 止盈按扣除预计卖出费用后的净收益计算：持仓按当前价卖出所得减去 `exit_fee_lamports`，
+    /// 超过成本 `take_profit_percent` 时才止盈，避免小仓位扣费后亏损。
+    /// 价格须为 lamports / 代币最小单位；持仓数量未知时仍按价格涨幅计算
+    #[serde(default)]
+    pub net_take_profit: bool,
+}
+
+/// 触发卖出的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitReason {
+    TakeProfit,
+    StopLoss,
+    TrailingStop,
+    /// 代币创建者或开发者钱包卖出超过阈值，由 `DevSellMonitor` 触发
+    CreatorSell,
+    /// 池子 SOL 储备短时间内大幅减少，由 `LiquidityGuard` 触发
+    LiquidityRemoved,
+    /// 快线下穿慢线，由 `EmaCrossStrategy` 触发
+    TrendReversal,
+}
+
+/// 监控中的持仓，价格单位由调用方决定，但需与入场价一致
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitoredPosition {
+    pub mint: Pubkey,
+    pub entry_price: f64,
+    /// 入场后的最高价
+    pub peak_price: f64,
+    pub last_price: f64,
+    /// 该持仓单独的止盈止损配置，None 时使用全局配置
+    pub config: Option<TakeProfitStopLossConfig>,
+    /// 买入时使用的协议，链上刷新价格时使用，None 时自动识别
+    #[serde(default)]
+    pub dex_type: Option<DexType>,
+    /// 持有的代币数量（最小单位），部分卖出后减少，0 表示未知
+    #[serde(default)]
+    pub token_amount: u64,
+    /// 持仓成本（lamports），含买入的小费和网络费用，0 表示未知，此时按入场价乘以持仓数量计算
+    #[serde(default)]
+    pub cost_basis: u64,
+}
+
+impl MonitoredPosition {
+    /// 持仓成本（lamports），持仓数量未知时为 None
+    pub fn cost_lamports(&self) -> Option<f64> {
+        match (self.token_amount, self.cost_basis) {
+            (0, _) => None,
+            (_, 0) => Some(self.entry_price * self.token_amount as f64),
+            (_, cost_basis) => Some(cost_basis as f64),
+        }
+    }
+}
+
+/// 止盈止损信号
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExitSignal {
+    pub reason: ExitReason,
+    /// 卖出前的持仓
+    pub position: MonitoredPosition,
+    /// 卖出持仓的百分比，100 表示全部卖出，小于 100 时剩余部分仍在监控中
+    pub sell_percent: f64,
+}
+
+impl ExitSignal {
+    fn full(reason: ExitReason, position: MonitoredPosition) -> Self {
+        Self { reason, position, sell_percent: 100.0 }
+    }
+
+    pub fn is_partial(&self) -> bool {
+        self.sell_percent < 100.0
+    }
+
+    /// 按持仓数量计算应卖出的代币数量，持仓数量未知时为 0，
+    /// 可改用 `SolanaTrade::sell_by_percent` 按钱包余额卖出 `sell_percent`
+    pub fn token_amount(&self) -> u64 {
+        if !self.is_partial() {
+            return self.position.token_amount;
+        }
+        (self.position.token_amount as f64 * self.sell_percent / 100.0) as u64
+    }
+}
+
+/// 等待回调超时后的处理
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrailingBuyTimeout {
+    /// 按当前价格市价买入
+    MarketBuy,
+    /// 放弃买入
+    #[default]
+    Cancel,
+}
+
+/// 回调买入配置：收到信号后等待价格较信号价下跌 `dip_percent` 再买入
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrailingBuyConfig {
+    pub dip_percent: f64,
+    /// 等待时间（毫秒）
+    pub timeout_ms: u64,
+    pub on_timeout: TrailingBuyTimeout,
+}
+
+/// 买入信号的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryReason {
+    /// 价格回调到目标价
+    Dip,
+    /// 超时，按配置市价买入
+    TimeoutMarketBuy,
+    /// 超时，按配置放弃买入
+    TimeoutCancel,
+}
+
+impl EntryReason {
+    /// 是否应当买入
+    pub fn is_buy(&self) -> bool {
+        !matches!(self, Self::TimeoutCancel)
+    }
+}
+
+/// 等待回调的买入
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingEntry {
+    pub mint: Pubkey,
+    /// 收到信号时的价格
+    pub signal_price: f64,
+    /// 等待期间的最新价，未更新时为信号价
+    pub last_price: f64,
+    pub deadline: Instant,
+    pub config: TrailingBuyConfig,
+    /// 链上刷新价格时使用的协议，None 时自动识别
+    pub dex_type: Option<DexType>,
+}
+
+impl PendingEntry {
+    /// 触发买入的目标价
+    pub fn target_price(&self) -> f64 {
+        self.signal_price * (1.0 - self.config.dip_percent / 100.0)
+    }
+}
+
+/// 回调买入信号
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntrySignal {
+    pub reason: EntryReason,
+    pub entry: PendingEntry,
+}
+
+/// 持仓价格监控，价格触及止盈止损条件时广播卖出信号
+///
+/// 触发后持仓即被移除，同一持仓只会发出一次信号；配置了 `take_profit_sell_percent` 时
+/// 止盈只卖出部分持仓，剩余部分继续监控。同时跟踪等待回调的买入，
+/// 价格回调到目标价或超时时广播买入信号。等待中的买入不写入存储
+pub struct PriceMonitor {
+    config: TakeProfitStopLossConfig,
+    positions: RwLock<HashMap<Pubkey, MonitoredPosition>>,
+    sender: broadcast::Sender<ExitSignal>,
+    entries: RwLock<HashMap<Pubkey, PendingEntry>>,
+    entry_sender: broadcast::Sender<EntrySignal>,
+    store: Option<Arc<dyn PositionStore>>,
+    event_bus: Option<Arc<StrategyEventBus>>,
+    candles: Option<Arc<CandleAggregator>>,
+    exposure: Option<Arc<ExposureTracker>>,
+    /// 预计卖出一次的费用（lamports），`net_take_profit` 使用
+    exit_fee_lamports: AtomicU64,
+    /// 卖出所得下限（lamports），低于该值的持仓视为粉尘
+    min_exit_lamports: AtomicU64,
+}
+
+impl PriceMonitor {
+    pub fn new(config: TakeProfitStopLossConfig) -> Self {
+        Self::from_positions(config, HashMap::new(), None)
+    }
+
+    fn from_positions(
+        config: TakeProfitStopLossConfig,
+        positions: HashMap<Pubkey, MonitoredPosition>,
+        store: Option<Arc<dyn PositionStore>>,
+    ) -> Self {
+        let (sender, _) = broadcast::channel(EXIT_SIGNAL_CHANNEL_CAPACITY);
+        let (entry_sender, _) = broadcast::channel(EXIT_SIGNAL_CHANNEL_CAPACITY);
+        Self {
+            config,
+            positions: RwLock::new(positions),
+            sender,
+            entries: RwLock::new(HashMap::new()),
+            entry_sender,
+            store,
+            event_bus: None,
+            candles: None,
+            exposure: None,
+            exit_fee_lamports: AtomicU64::new(0),
+            min_exit_lamports: AtomicU64::new(0),
+        }
+    }
+
+    /// 预计卖出一次的费用（lamports）
+    pub fn exit_fee_lamports(&self) -> u64 {
+        self.exit_fee_lamports.load(Ordering::Relaxed)
+    }
+
+    /// 设置预计卖出一次的费用（lamports），包括网络费用、优先费和小费
+    pub fn set_exit_fee_lamports(&self, lamports: u64) {
+        self.exit_fee_lamports.store(lamports, Ordering::Relaxed);
+    }
+
+    /// 卖出所得下限（lamports），0 表示不检查
+    pub fn min_exit_lamports(&self) -> u64 {
+        self.min_exit_lamports.load(Ordering::Relaxed)
+    }
+
+    /// 设置卖出所得下限（lamports）：止盈止损要卖出的部分按当前价计算所得低于该值时不发出信号，
+    /// 持仓作为粉尘继续保留，由 `SolanaTrade::sweep_dust` 清理。
+    /// 价格须为 lamports / 代币最小单位，持仓数量未知时不检查
+    pub fn set_min_exit_lamports(&self, lamports: u64) {
+        self.min_exit_lamports.store(lamports, Ordering::Relaxed);
+    }
+
+    /// 按当前价计算价值低于卖出所得下限的持仓，持仓数量未知的不计入
+    pub fn dust_positions(&self) -> Vec<MonitoredPosition> {
+        self.positions
+            .read()
+            .unwrap()
+            .values()
+            .filter(|position| self.is_dust(position.token_amount, position.last_price))
+            .cloned()
+            .collect()
+    }
+
+    fn is_dust(&self, token_amount: u64, price: f64) -> bool {
+        let min_exit_lamports = self.min_exit_lamports();
+        min_exit_lamports > 0
+            && token_amount > 0
+            && token_amount as f64 * price < min_exit_lamports as f64
+    }
+
+    /// 按当前优先费估算卖出费用并更新：签名费 + 估算的 compute unit price × `unit_limit`
+    /// + `sell_tip_fee`，返回新的费用
+    pub async fn refresh_exit_fee(
+        &self,
+        estimator: &PriorityFeeEstimator,
+        accounts: &[Pubkey],
+        percentile: u8,
+        priority_fee: &PriorityFee,
+    ) -> Result<u64> {
+        let unit_price = estimator.estimate(accounts, percentile).await?;
+        let lamports =
+            estimate_exit_fee(unit_price, priority_fee.unit_limit, priority_fee.sell_tip_fee);
+        self.set_exit_fee_lamports(lamports);
+        Ok(lamports)
+    }
+
+    /// 开仓和止盈止损同时发布到策略事件总线
+    pub fn with_event_bus(mut self, event_bus: Arc<StrategyEventBus>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    /// `on_event` 和 `observe` 收到的所有成交同时聚合为 K 线，不限于持仓中的代币
+    pub fn with_candles(mut self, candles: Arc<CandleAggregator>) -> Self {
+        self.candles = Some(candles);
+        self
+    }
+
+    /// 持仓全部卖出或停止监控时清除该代币在 `exposure` 中的敞口，
+    /// 通常传入 `WalletMonitor::shared_exposure`；部分止盈不清除
+    pub fn with_exposure(mut self, exposure: Arc<ExposureTracker>) -> Self {
+        self.exposure = Some(exposure);
+        self
+    }
+
+    fn close_exposure(&self, mint: &Pubkey) {
+        if let Some(exposure) = &self.exposure {
+            exposure.close(mint);
+        }
+    }
+
+    /// 代币在该周期的 K 线，按时间从早到晚排列，未设置 `with_candles` 时为空
+    pub fn candles(&self, mint: &Pubkey, interval: CandleInterval) -> Vec<Candle> {
+        self.candles.as_ref().map_or_else(Vec::new, |candles| candles.candles(mint, interval))
+    }
+
+    /// 从存储中恢复持仓，之后的修改都写入存储
+    pub fn with_store(
+        config: TakeProfitStopLossConfig,
+        store: Arc<dyn PositionStore>,
+    ) -> Result<Self> {
+        let positions =
+            store.load_monitored()?.into_iter().map(|position| (position.mint, position)).collect();
+        Ok(Self::from_positions(config, positions, Some(store)))
+    }
+
+    /// 写入存储失败只打印错误，不影响监控
+    fn persist(&self, position: &MonitoredPosition) {
+        if let Some(Err(e)) = self.store.as_ref().map(|store| store.save_monitored(position)) {
+            eprintln!("保存持仓 {} 失败: {}", position.mint, e);
+        }
+    }
+
+    fn unpersist(&self, mint: &Pubkey) {
+        if let Some(Err(e)) = self.store.as_ref().map(|store| store.remove_monitored(mint)) {
+            eprintln!("删除持仓 {} 失败: {}", mint, e);
+        }
+    }
+
+    /// 订阅止盈止损信号
+    pub fn subscribe(&self) -> broadcast::Receiver<ExitSignal> {
+        self.sender.subscribe()
+    }
+
+    /// 订阅回调买入信号
+    pub fn subscribe_entries(&self) -> broadcast::Receiver<EntrySignal> {
+        self.entry_sender.subscribe()
+    }
+
+    /// 收到买入信号后开始等待回调，已在等待时覆盖
+    pub fn watch_entry(&self, mint: Pubkey, signal_price: f64, config: TrailingBuyConfig) {
+        self.watch_entry_with_dex_type(mint, signal_price, config, None);
+    }
+
+    /// 开始等待回调并指定链上刷新价格时使用的协议
+    pub fn watch_entry_with_dex_type(
+        &self,
+        mint: Pubkey,
+        signal_price: f64,
+        config: TrailingBuyConfig,
+        dex_type: Option<DexType>,
+    ) {
+        let entry = PendingEntry {
+            mint,
+            signal_price,
+            last_price: signal_price,
+            deadline: Instant::now() + Duration::from_millis(config.timeout_ms),
+            config,
+            dex_type,
+        };
+        self.entries.write().unwrap().insert(mint, entry);
+    }
+
+    /// 放弃等待中的买入，不发出信号
+    pub fn cancel_entry(&self, mint: &Pubkey) -> Option<PendingEntry> {
+        self.entries.write().unwrap().remove(mint)
+    }
+
+    pub fn pending_entry(&self, mint: &Pubkey) -> Option<PendingEntry> {
+        self.entries.read().unwrap().get(mint).cloned()
+    }
+
+    /// 所有等待回调的买入
+    pub fn pending_entries(&self) -> Vec<PendingEntry> {
+        self.entries.read().unwrap().values().cloned().collect()
+    }
+
+    /// 更新等待中买入的价格，回调到目标价时移除、广播买入信号并返回
+    pub fn check_entry_price(&self, mint: &Pubkey, price: f64) -> Option<EntrySignal> {
+        let mut entries = self.entries.write().unwrap();
+        let entry = entries.get_mut(mint)?;
+        entry.last_price = price;
+        if price > entry.target_price() {
+            return None;
+        }
+        let signal = EntrySignal { reason: EntryReason::Dip, entry: entries.remove(mint)? };
+        let _ = self.entry_sender.send(signal.clone());
+        Some(signal)
+    }
+
+    /// 处理已超时的买入，按配置发出市价买入或放弃的信号
+    pub fn expire_entries(&self) -> Vec<EntrySignal> {
+        self.expire_entries_at(Instant::now())
+    }
+
+    fn expire_entries_at(&self, now: Instant) -> Vec<EntrySignal> {
+        let mut entries = self.entries.write().unwrap();
+        let expired: Vec<Pubkey> = entries
+            .values()
+            .filter(|entry| entry.deadline <= now)
+            .map(|entry| entry.mint)
+            .collect();
+        expired
+            .into_iter()
+            .filter_map(|mint| entries.remove(&mint))
+            .map(|entry| {
+                let reason = match entry.config.on_timeout {
+                    TrailingBuyTimeout::MarketBuy => EntryReason::TimeoutMarketBuy,
+                    TrailingBuyTimeout::Cancel => EntryReason::TimeoutCancel,
+                };
+                let signal = EntrySignal { reason, entry };
+                let _ = self.entry_sender.send(signal.clone());
+                signal
+            })
+            .collect()
+    }
+
+    /// 开始监控持仓，使用全局配置，已有持仓时覆盖
+    pub fn open_position(&self, mint: Pubkey, entry_price: f64) {
+        self.open_position_with_config(mint, entry_price, None);
+    }
+
+    /// 开始监控持仓并指定该持仓的止盈止损配置，已有持仓时覆盖
+    pub fn open_position_with_config(
+        &self,
+        mint: Pubkey,
+        entry_price: f64,
+        config: Option<TakeProfitStopLossConfig>,
+    ) {
+        let position = MonitoredPosition {
+            mint,
+            entry_price,
+            peak_price: entry_price,
+            last_price: entry_price,
+            config,
+            dex_type: None,
+            token_amount: 0,
+            cost_basis: 0,
+        };
+        self.persist(&position);
+        if let Some(event_bus) = &self.event_bus {
+            event_bus.publish(StrategyEvent::PositionOpened(position.clone()));
+        }
+        self.positions.write().unwrap().insert(mint, position);
+    }
+
+    /// 按实际成交结果开始监控持仓，入场价为 lamports / 代币最小单位，与 `observe` 一致；
+    /// 持仓成本为花费的 SOL 加网络费用
+    pub fn open_position_from_fill(
+        &self,
+        mint: Pubkey,
+        fill: &FillReport,
+        config: Option<TakeProfitStopLossConfig>,
+    ) -> Result<()> {
+        if fill.tokens_received <= 0 {
+            return Err(anyhow!("Fill for {} received no tokens", mint));
+        }
+        self.open_position_with_config(
+            mint,
+            fill.sol_spent as f64 / fill.tokens_received as f64,
+            config,
+        );
+        if let Some(position) = self.positions.write().unwrap().get_mut(&mint) {
+            position.token_amount = fill.tokens_received as u64;
+            position.cost_basis = fill.sol_spent.max(0) as u64 + fill.fee_lamports;
+            self.persist(position);
+        }
+        Ok(())
+    }
+
+    /// 设置持仓的代币数量，部分卖出信号按该数量计算卖出数量，持仓不存在时返回 false
+    pub fn set_position_token_amount(&self, mint: &Pubkey, token_amount: u64) -> bool {
+        match self.positions.write().unwrap().get_mut(mint) {
+            Some(position) => {
+                position.token_amount = token_amount;
+                self.persist(position);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 修改持仓的止盈止损配置，None 恢复使用全局配置，持仓不存在时返回 false
+    pub fn set_position_config(
+        &self,
+        mint: &Pubkey,
+        config: Option<TakeProfitStopLossConfig>,
+    ) -> bool {
+        match self.positions.write().unwrap().get_mut(mint) {
+            Some(position) => {
+                position.config = config;
+                self.persist(position);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 设置持仓的协议，链上刷新价格时直接读取该协议的池子，持仓不存在时返回 false
+    pub fn set_position_dex_type(&self, mint: &Pubkey, dex_type: Option<DexType>) -> bool {
+        match self.positions.write().unwrap().get_mut(mint) {
+            Some(position) => {
+                position.dex_type = dex_type;
+                self.persist(position);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 停止监控持仓
+    pub fn close_position(&self, mint: &Pubkey) -> Option<MonitoredPosition> {
+        let position = self.positions.write().unwrap().remove(mint)?;
+        self.unpersist(mint);
+        self.close_exposure(mint);
+        Some(position)
+    }
+
+    pub fn position(&self, mint: &Pubkey) -> Option<MonitoredPosition> {
+        self.positions.read().unwrap().get(mint).cloned()
+    }
+
+    /// 所有监控中的持仓
+    pub fn positions(&self) -> Vec<MonitoredPosition> {
+        self.positions.read().unwrap().values().cloned().collect()
+    }
+
+    /// 更新价格和最高价，触发止盈止损时移除持仓、广播信号并返回
+    ///
+    /// 要卖出的部分低于 `min_exit_lamports` 时不触发，持仓留作粉尘。
+    /// 部分止盈时不移除持仓：扣减代币数量，并以当前价作为剩余部分的入场价（成本价），
+    /// 之后的止盈止损都相对该价格计算。
+    /// 同时更新等待回调的买入，触发时在买入信号通道广播。
+    /// 只有最高价变化时才写入存储，恢复后的最新价以重启后的第一次更新为准
+    pub fn update_price(&self, mint: &Pubkey, price: f64) -> Option<ExitSignal> {
+        self.check_entry_price(mint, price);
+        let mut positions = self.positions.write().unwrap();
+        let position = positions.get_mut(mint)?;
+        position.last_price = price;
+        let new_peak = price > position.peak_price;
+        if new_peak {
+            position.peak_price = price;
+        }
+        let Some(reason) = self.check_take_profit_stop_loss(position) else {
+            if new_peak {
+                self.persist(position);
+            }
+            return None;
+        };
+        let sell_percent = match reason {
+            ExitReason::TakeProfit => position
+                .config
+                .unwrap_or(self.config)
+                .take_profit_sell_percent
+                .map_or(100.0, |percent| percent.clamp(0.0, 100.0)),
+            _ => 100.0,
+        };
+        let signal = ExitSignal { reason, position: position.clone(), sell_percent };
+        if self.is_dust(signal.token_amount(), price) {
+            if new_peak {
+                self.persist(position);
+            }
+            return None;
+        }
+        if signal.is_partial() && signal.token_amount() < position.token_amount.max(1) {
+            position.token_amount -= signal.token_amount();
+            position.entry_price = price;
+            position.cost_basis = 0;
+            self.persist(position);
+        } else {
+            let signal = ExitSignal::full(reason, positions.remove(mint)?);
+            self.unpersist(mint);
+            self.close_exposure(mint);
+            self.send_exit(&signal);
+            return Some(signal);
+        }
+        drop(positions);
+        self.send_exit(&signal);
+        Some(signal)
+    }
+
+    /// 立即以 `reason` 卖出持仓：移除持仓并广播卖出信号，持仓不存在时返回 None
+    pub fn exit_position(&self, mint: &Pubkey, reason: ExitReason) -> Option<ExitSignal> {
+        let signal = ExitSignal::full(reason, self.positions.write().unwrap().remove(mint)?);
+        self.unpersist(mint);
+        self.close_exposure(mint);
+        self.send_exit(&signal);
+        Some(signal)
+    }
+
+    fn send_exit(&self, signal: &ExitSignal) {
+        // 没有订阅者时发送失败，忽略即可
+        let _ = self.sender.send(signal.clone());
+        if let Some(event_bus) = &self.event_bus {
+            event_bus.publish(signal.clone());
+        }
+    }
+
+    /// 处理流事件，可直接在订阅回调中调用，K 线按程序收到事件的时间归入
+    ///
+    /// 协议为 PumpFun 的持仓按交易后 bonding curve 的虚拟储备计算现价，不受单笔成交滑点影响；
+    /// 其他持仓按成交价计算
+    pub fn on_event(&self, event: &dyn UnifiedEvent) -> Option<ExitSignal> {
+        let swap = SwapObservation::from_event(event)?;
+        if let Some(candles) = &self.candles {
+            candles.observe_at(&swap, event.program_received_time_ms());
+        }
+        match self.bonding_curve_price(event, &swap.mint) {
+            Some(price) => self.update_price(&swap.mint, price),
+            None => self.observe_price(&swap),
+        }
+    }
+
+    /// 持仓协议为 PumpFun 且事件为 PumpFun 交易时，交易后的 bonding curve 现价
+    fn bonding_curve_price(&self, event: &dyn UnifiedEvent, mint: &Pubkey) -> Option<f64> {
+        let trade = event.as_any().downcast_ref::<PumpFunTradeEvent>()?;
+        let dex_type = self.positions.read().unwrap().get(mint)?.dex_type.clone();
+        if dex_type != Some(DexType::PumpFun) || trade.virtual_token_reserves == 0 {
+            return None;
+        }
+        Some(get_spot_price(trade.virtual_sol_reserves, trade.virtual_token_reserves))
+    }
+
+    /// 用一笔交易的成交价（lamports / 代币最小单位）更新价格，K 线按当前时间归入
+    pub fn observe(&self, swap: &SwapObservation) -> Option<ExitSignal> {
+        if let Some(candles) = &self.candles {
+            candles.observe(swap);
+        }
+        self.observe_price(swap)
+    }
+
+    fn observe_price(&self, swap: &SwapObservation) -> Option<ExitSignal> {
+        if swap.token_amount == 0 {
+            return None;
+        }
+        self.update_price(&swap.mint, swap.sol_amount as f64 / swap.token_amount as f64)
+    }
+
+    fn set_entry_dex_type(&self, mint: &Pubkey, dex_type: Option<DexType>) {
+        if let Some(entry) = self.entries.write().unwrap().get_mut(mint) {
+            entry.dex_type = dex_type;
+        }
+    }
+
+    /// 从链上读取所有持仓和等待回调买入的池子或 bonding curve 价格并更新，返回触发的卖出信号
+    ///
+    /// 没有协议的持仓先自动识别协议并保存；池子不再有流动性（如 PumpFun 已迁移）时清除协议，
+    /// 下一轮重新识别。单个持仓失败只打印警告
+    pub async fn refresh_prices(&self, rpc: &SolanaRpcClient) -> Vec<ExitSignal> {
+        let mut mints: HashMap<Pubkey, Option<DexType>> = self
+            .entries
+            .read()
+            .unwrap()
+            .values()
+            .map(|entry| (entry.mint, entry.dex_type.clone()))
+            .collect();
+        mints.extend(
+            self.positions
+                .read()
+                .unwrap()
+                .values()
+                .map(|position| (position.mint, position.dex_type.clone())),
+        );
+        let mut signals = vec![];
+        for (mint, dex_type) in mints {
+            let dex_type = match dex_type {
+                Some(dex_type) => dex_type,
+                None => match resolve_dex(rpc, &mint).await {
+                    Ok(dex_type) => {
+                        self.set_position_dex_type(&mint, Some(dex_type.clone()));
+                        self.set_entry_dex_type(&mint, Some(dex_type.clone()));
+                        dex_type
+                    }
+                    Err(e) => {
+                        println!("警告: 识别 {} 的协议失败: {}", mint, e);
+                        continue;
+                    }
+                },
+            };
+            match Router::venue(rpc, &mint, &dex_type).await {
+                Ok(Some(venue)) if venue.price() > 0.0 => {
+                    signals.extend(self.update_price(&mint, venue.price()));
+                }
+                Ok(_) => {
+                    println!("警告: {} 在 {} 上没有流动性，重新识别协议", mint, dex_type);
+                    self.set_position_dex_type(&mint, None);
+                    self.set_entry_dex_type(&mint, None);
+                }
+                Err(e) => println!("警告: 查询 {} 在 {} 的价格失败: {}", mint, dex_type, e),
+            }
+        }
+        signals
+    }
+
+    /// 启动后台任务，定时从链上刷新所有持仓的价格并处理超时的回调买入
+    pub fn start_price_refresh(
+        self: &Arc<Self>,
+        rpc: Arc<SolanaRpcClient>,
+        interval_ms: u64,
+    ) -> JoinHandle<()> {
+        let monitor = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_millis(interval_ms.max(100)));
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                monitor.refresh_prices(&rpc).await;
+                monitor.expire_entries();
+            }
+        })
+    }
+
+    /// 检查持仓是否触发止盈、止损或移动止损，优先使用持仓单独的配置
+    pub fn check_take_profit_stop_loss(&self, position: &MonitoredPosition) -> Option<ExitReason> {
+        let config = position.config.unwrap_or(self.config);
+        let price = position.last_price;
+        if config
+            .stop_loss_percent
+            .is_some_and(|percent| price <= position.entry_price * (1.0 - percent / 100.0))
+        {
+            return Some(ExitReason::StopLoss);
+        }
+        if config
+            .trailing_stop_percent
+            .is_some_and(|percent| price <= position.peak_price * (1.0 - percent / 100.0))
+        {
+            return Some(ExitReason::TrailingStop);
+        }
+        let net_cost = position.cost_lamports().filter(|_| config.net_take_profit);
+        if config.take_profit_percent.is_some_and(|percent| match net_cost {
+            Some(cost) => {
+                let proceeds =
+                    price * position.token_amount as f64 - self.exit_fee_lamports() as f64;
+                proceeds >= cost * (1.0 + percent / 100.0)
+            }
+            None => price >= position.entry_price * (1.0 + percent / 100.0),
+        }) {
+            return Some(ExitReason::TakeProfit);
+        }
+        None
+    }
+}
+
+/// 卖出一次的费用（lamports）：签名费 + `unit_price`（micro-lamports）× `unit_limit` + 小费（SOL）
+pub fn estimate_exit_fee(unit_price: u64, unit_limit: u32, tip_fee: f64) -> u64 {
+    const SIGNATURE_FEE_LAMPORTS: u64 = 5_000;
+    let priority_fee = (unit_price as u128 * unit_limit as u128 / 1_000_000) as u64;
+    SIGNATURE_FEE_LAMPORTS + priority_fee + (tip_fee * LAMPORTS_PER_SOL as f64).round() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trailing_stop_follows_peak() {
+        let monitor = PriceMonitor::new(TakeProfitStopLossConfig {
+            stop_loss_percent: Some(30.0),
+            trailing_stop_percent: Some(20.0),
+            ..Default::default()
+        });
+        let mut signals = monitor.subscribe();
+        let mint = Pubkey::new_unique();
+        monitor.open_position(mint, 1.0);
+
+        assert!(monitor.update_price(&mint, 0.85).is_none());
+        assert!(monitor.update_price(&mint, 2.0).is_none());
+        // 从最高价 2.0 回撤 15%，未触发
+        assert!(monitor.update_price(&mint, 1.7).is_none());
+        assert_eq!(monitor.position(&mint).unwrap().peak_price, 2.0);
+
+        let signal = monitor.update_price(&mint, 1.6).unwrap();
+        assert_eq!(signal.reason, ExitReason::TrailingStop);
+        assert_eq!(signals.try_recv().unwrap(), signal);
+        assert!(monitor.position(&mint).is_none());
+        assert!(monitor.update_price(&mint, 1.0).is_none());
+    }
+
+    #[test]
+    fn test_take_profit_and_stop_loss() {
+        let monitor = PriceMonitor::new(TakeProfitStopLossConfig {
+            take_profit_percent: Some(100.0),
+            stop_loss_percent: Some(50.0),
+            trailing_stop_percent: None,
+            take_profit_sell_percent: None,
+            net_take_profit: false,
+        });
+        let (winner, loser) = (Pubkey::new_unique(), Pubkey::new_unique());
+        monitor.open_position(winner, 1.0);
+        monitor.open_position(loser, 1.0);
+
+        assert!(monitor.update_price(&winner, 1.9).is_none());
+        assert!(monitor.update_price(&winner, 1.2).is_none());
+        assert_eq!(monitor.update_price(&winner, 2.0).unwrap().reason, ExitReason::TakeProfit);
+        assert_eq!(monitor.update_price(&loser, 0.5).unwrap().reason, ExitReason::StopLoss);
+    }
+
+    #[test]
+    fn test_position_config_overrides_global() {
+        let monitor = PriceMonitor::new(TakeProfitStopLossConfig {
+            take_profit_percent: Some(50.0),
+            ..Default::default()
+        });
+        let (conviction, regular) = (Pubkey::new_unique(), Pubkey::new_unique());
+        monitor.open_position_with_config(
+            conviction,
+            1.0,
+            Some(TakeProfitStopLossConfig {
+                take_profit_percent: Some(400.0),
+                ..Default::default()
+            }),
+        );
+        monitor.open_position(regular, 1.0);
+
+        assert!(monitor.update_price(&conviction, 2.0).is_none());
+        assert_eq!(monitor.update_price(&regular, 2.0).unwrap().reason, ExitReason::TakeProfit);
+
+        assert!(monitor.set_position_config(&conviction, None));
+        assert_eq!(monitor.update_price(&conviction, 2.0).unwrap().reason, ExitReason::TakeProfit);
+        assert!(!monitor.set_position_config(&conviction, None));
+    }
+
+    #[test]
+    fn test_positions_recovered_from_store() {
+        use crate::monitor::position_store::MemoryPositionStore;
+
+        let store: Arc<dyn PositionStore> = Arc::new(MemoryPositionStore::default());
+        let config =
+            TakeProfitStopLossConfig { trailing_stop_percent: Some(20.0), ..Default::default() };
+        let (open, closed) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        let monitor = PriceMonitor::with_store(config, store.clone()).unwrap();
+        monitor.open_position(open, 1.0);
+        monitor.open_position(closed, 1.0);
+        monitor.update_price(&open, 2.0);
+        monitor.close_position(&closed);
+        drop(monitor);
+
+        // 重启后最高价保留，移动止损仍按重启前的最高价计算
+        let monitor = PriceMonitor::with_store(config, store).unwrap();
+        assert!(monitor.position(&closed).is_none());
+        assert_eq!(monitor.position(&open).unwrap().peak_price, 2.0);
+        assert_eq!(monitor.update_price(&open, 1.6).unwrap().reason, ExitReason::TrailingStop);
+    }
+
+    #[test]
+    fn test_trailing_buy_waits_for_dip() {
+        let monitor = PriceMonitor::new(TakeProfitStopLossConfig::default());
+        let mut entries = monitor.subscribe_entries();
+        let config = TrailingBuyConfig {
+            dip_percent: 10.0,
+            timeout_ms: 60_000,
+            on_timeout: TrailingBuyTimeout::MarketBuy,
+        };
+        let (dip, stale, cancelled) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        monitor.watch_entry(dip, 1.0, config);
+        monitor.watch_entry(stale, 1.0, config);
+        monitor.watch_entry(
+            cancelled,
+            1.0,
+            TrailingBuyConfig { on_timeout: TrailingBuyTimeout::Cancel, ..config },
+        );
+
+        assert!(monitor.check_entry_price(&dip, 0.95).is_none());
+        monitor.update_price(&dip, 0.9);
+        let signal = entries.try_recv().unwrap();
+        assert_eq!((signal.reason, signal.entry.last_price), (EntryReason::Dip, 0.9));
+        assert!(monitor.pending_entry(&dip).is_none());
+
+        assert!(monitor.expire_entries().is_empty());
+        let mut expired = monitor.expire_entries_at(Instant::now() + Duration::from_secs(61));
+        expired.sort_by_key(|signal| signal.reason.is_buy());
+        assert_eq!(
+            expired.iter().map(|signal| (signal.entry.mint, signal.reason)).collect::<Vec<_>>(),
+            vec![(cancelled, EntryReason::TimeoutCancel), (stale, EntryReason::TimeoutMarketBuy)]
+        );
+        assert!(monitor.pending_entry(&stale).is_none());
+    }
+
+    #[test]
+    fn test_open_position_from_fill() {
+        let monitor = PriceMonitor::new(TakeProfitStopLossConfig::default());
+        let mint = Pubkey::new_unique();
+        let fill = FillReport {
+            tokens_received: 2_000_000,
+            sol_spent: 1_000_000_000,
+            price_paid: 0.0,
+            fee_lamports: 5_000,
+            decimals: 6,
+            transfer_fee: 0,
+            transfer_fee_basis_points: 0,
+        };
+        monitor.open_position_from_fill(mint, &fill, None).unwrap();
+        assert_eq!(monitor.position(&mint).unwrap().entry_price, 500.0);
+        assert_eq!(monitor.position(&mint).unwrap().token_amount, 2_000_000);
+        assert_eq!(monitor.position(&mint).unwrap().cost_basis, 1_000_005_000);
+
+        let sell = FillReport { tokens_received: -2_000_000, ..fill };
+        assert!(monitor.open_position_from_fill(Pubkey::new_unique(), &sell, None).is_err());
+    }
+
+    #[test]
+    fn test_net_take_profit_after_fees() {
+        let config = TakeProfitStopLossConfig {
+            take_profit_percent: Some(10.0),
+            net_take_profit: true,
+            ..Default::default()
+        };
+        let monitor = PriceMonitor::new(config);
+        // 0.01 SOL 的小仓位，卖出费用 0.001 SOL
+        monitor.set_exit_fee_lamports(estimate_exit_fee(100_000, 200_000, 0.000975));
+        assert_eq!(monitor.exit_fee_lamports(), 1_000_000);
+        let mint = Pubkey::new_unique();
+        let fill = FillReport {
+            tokens_received: 1_000_000,
+            sol_spent: 10_000_000,
+            price_paid: 0.0,
+            fee_lamports: 0,
+            decimals: 6,
+            transfer_fee: 0,
+            transfer_fee_basis_points: 0,
+        };
+        monitor.open_position_from_fill(mint, &fill, None).unwrap();
+
+        // 价格上涨 15%，扣除费用后净收益只有 5%
+        assert!(monitor.update_price(&mint, 11.5).is_none());
+        let signal = monitor.update_price(&mint, 12.0).unwrap();
+        assert_eq!(signal.reason, ExitReason::TakeProfit);
+
+        // 持仓数量未知时按价格涨幅计算
+        let unknown = Pubkey::new_unique();
+        monitor.open_position(unknown, 10.0);
+        assert_eq!(monitor.update_price(&unknown, 11.0).unwrap().reason, ExitReason::TakeProfit);
+    }
+
+    #[test]
+    fn test_dust_exits_are_skipped() {
+        let monitor = PriceMonitor::new(TakeProfitStopLossConfig {
+            stop_loss_percent: Some(20.0),
+            ..Default::default()
+        });
+        monitor.set_min_exit_lamports(1_000_000);
+        let (dust, position, unknown) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        monitor.open_position(dust, 1.0);
+        monitor.set_position_token_amount(&dust, 1_000_000);
+        monitor.open_position(position, 1.0);
+        monitor.set_position_token_amount(&position, 10_000_000);
+        monitor.open_position(unknown, 1.0);
+
+        // 止损卖出所得 0.7 × 1_000_000 = 700_000 lamports，低于下限，留作粉尘
+        assert!(monitor.update_price(&dust, 0.7).is_none());
+        assert_eq!(monitor.update_price(&position, 0.7).unwrap().reason, ExitReason::StopLoss);
+        // 持仓数量未知时不检查
+        assert!(monitor.update_price(&unknown, 0.7).is_some());
+        let dust_positions = monitor.dust_positions();
+        assert_eq!(dust_positions.len(), 1);
+        assert_eq!(dust_positions[0].mint, dust);
+
+        monitor.set_min_exit_lamports(0);
+        assert!(monitor.dust_positions().is_empty());
+        assert!(monitor.update_price(&dust, 0.7).is_some());
+    }
+
+    #[test]
+    fn test_partial_take_profit_keeps_remainder() {
+        let monitor = PriceMonitor::new(TakeProfitStopLossConfig {
+            take_profit_percent: Some(50.0),
+            stop_loss_percent: Some(20.0),
+            take_profit_sell_percent: Some(40.0),
+            ..Default::default()
+        });
+        let mut signals = monitor.subscribe();
+        let mint = Pubkey::new_unique();
+        monitor.open_position(mint, 1.0);
+        monitor.set_position_token_amount(&mint, 1_000);
+
+        let signal = monitor.update_price(&mint, 1.5).unwrap();
+        assert_eq!((signal.reason, signal.sell_percent), (ExitReason::TakeProfit, 40.0));
+        assert_eq!(signal.token_amount(), 400);
+        assert_eq!(signals.try_recv().unwrap(), signal);
+        let position = monitor.position(&mint).unwrap();
+        assert_eq!((position.token_amount, position.entry_price), (600, 1.5));
+
+        // 剩余部分相对新的入场价 1.5 计算，1.5 * 1.5 = 2.25 时再次止盈
+        assert!(monitor.update_price(&mint, 2.0).is_none());
+        assert_eq!(monitor.update_price(&mint, 2.25).unwrap().token_amount(), 240);
+        // 止损总是全部卖出
+        let signal = monitor.update_price(&mint, 1.7).unwrap();
+        assert_eq!((signal.reason, signal.token_amount()), (ExitReason::StopLoss, 360));
+        assert!(!signal.is_partial());
+        assert!(monitor.position(&mint).is_none());
+    }
+
+    #[test]
+    fn test_exits_close_exposure() {
+        let exposure = Arc::new(ExposureTracker::new(Default::default()));
+        let monitor = PriceMonitor::new(TakeProfitStopLossConfig {
+            take_profit_percent: Some(50.0),
+            stop_loss_percent: Some(20.0),
+            take_profit_sell_percent: Some(40.0),
+            ..Default::default()
+        })
+        .with_exposure(exposure.clone());
+        let (exited, closed) = (Pubkey::new_unique(), Pubkey::new_unique());
+        for mint in [exited, closed] {
+            exposure.reserve(&mint, 1_000).unwrap();
+            monitor.open_position(mint, 1.0);
+            monitor.set_position_token_amount(&mint, 1_000);
+        }
+
+        // 部分止盈保留敞口，全部卖出后清除
+        monitor.update_price(&exited, 1.5).unwrap();
+        assert_eq!(exposure.deployed(&exited), 1_000);
+        monitor.update_price(&exited, 1.0).unwrap();
+        assert_eq!(exposure.deployed(&exited), 0);
+
+        // 停止监控（如对账发现已没有余额）同样清除
+        monitor.close_position(&closed);
+        assert_eq!(exposure.position_count(), 0);
+    }
+
+    #[test]
+    fn test_pumpfun_positions_use_bonding_curve_price() {
+        let monitor = PriceMonitor::new(TakeProfitStopLossConfig::default());
+        let (curve, fill) = (Pubkey::new_unique(), Pubkey::new_unique());
+        monitor.open_position(curve, 0.02);
+        monitor.open_position(fill, 0.02);
+        assert!(monitor.set_position_dex_type(&curve, Some(DexType::PumpFun)));
+        let trade = |mint: Pubkey| PumpFunTradeEvent {
+            mint,
+            is_buy: true,
+            sol_amount: 3_000,
+            token_amount: 100_000,
+            virtual_sol_reserves: 40_000_000_000,
+            virtual_token_reserves: 1_000_000_000_000,
+            ..Default::default()
+        };
+
+        monitor.on_event(&trade(curve));
+        monitor.on_event(&trade(fill));
+        assert_eq!(monitor.position(&curve).unwrap().last_price, 0.04);
+        assert_eq!(monitor.position(&fill).unwrap().last_price, 0.03);
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use solana_account_decoder::UiAccountData;
+use solana_client::rpc_request::TokenAccountsFilter;
+use solana_sdk::pubkey::Pubkey;
+use tokio::task::JoinHandle;
+
+use crate::common::{SolanaRpcClient, TokenMetadataCache};
+use crate::constants::pumpswap::accounts::WSOL_TOKEN_ACCOUNT;
+use crate::monitor::price_monitor::PriceMonitor;
+
+/// 一次对账的结果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReconcileReport {
+    /// 链上已没有余额而停止监控的持仓，如手动卖出或买入失败
+    pub closed: Vec<Pubkey>,
+    /// 代币数量按链上余额更新的持仓：(代币, 原数量, 链上余额)
+    pub adjusted: Vec<(Pubkey, u64, u64)>,
+    /// 钱包持有但不在监控中的代币及余额，如空投或监控外买入
+    pub untracked: Vec<(Pubkey, u64)>,
+}
+
+/// 持仓对账：定时比较 `PriceMonitor` 中的持仓与钱包的链上代币余额，修正两者的偏差
+///
+/// 持仓的代币数量按链上余额更新；链上余额为 0 的持仓连续两次对账都没有余额时才停止监控，
+/// 避免刚成交、RPC 尚未同步的持仓被误关闭；钱包中不在监控中的代币只打印警告，不自动监控。
+/// 对账不会广播卖出信号；关闭持仓通过 `PriceMonitor::close_position`，
+/// 设置了 `PriceMonitor::with_exposure` 时同时清除该代币的跟买敞口
+pub struct PositionReconciler {
+    price_monitor: Arc<PriceMonitor>,
+    owner: Pubkey,
+    /// 上次对账时链上没有余额的持仓
+    missing: Mutex<HashSet<Pubkey>>,
+}
+
+impl PositionReconciler {
+    /// `owner` 为持有代币的钱包，即交易的 payer
+    pub fn new(price_monitor: Arc<PriceMonitor>, owner: Pubkey) -> Self {
+        Self { price_monitor, owner, missing: Mutex::new(HashSet::new()) }
+    }
+
+    /// 查询钱包在 Token 和 Token-2022 程序下的全部代币余额并对账，WSOL 不计入
+    pub async fn reconcile(&self, rpc: &SolanaRpcClient) -> Result<ReconcileReport> {
+        let mut balances: HashMap<Pubkey, u64> = HashMap::new();
+        for program in [spl_token::ID, spl_token_2022::ID] {
+            let accounts = rpc
+                .get_token_accounts_by_owner(&self.owner, TokenAccountsFilter::ProgramId(program))
+                .await?;
+            for account in accounts {
+                if let Some((mint, amount)) = parse_token_balance(&account.account.data) {
+                    *balances.entry(mint).or_default() += amount;
+                }
+            }
+        }
+        balances.remove(&WSOL_TOKEN_ACCOUNT);
+        Ok(self.reconcile_balances(&balances))
+    }
+
+    /// 按给定的链上余额对账
+    pub fn reconcile_balances(&self, balances: &HashMap<Pubkey, u64>) -> ReconcileReport {
+        let mut report = ReconcileReport::default();
+        let mut missing = self.missing.lock().unwrap();
+        let mut still_missing = HashSet::new();
+        let mut tracked = HashSet::new();
+        for position in self.price_monitor.positions() {
+            tracked.insert(position.mint);
+            let balance = balances.get(&position.mint).copied().unwrap_or(0);
+            if balance == 0 {
+                if !missing.contains(&position.mint) {
+                    still_missing.insert(position.mint);
+                    continue;
+                }
+                println!(
+                    "警告: 钱包已没有 {}，停止监控该持仓",
+                    TokenMetadataCache::get_instance().label(&position.mint)
+                );
+                self.price_monitor.close_position(&position.mint);
+                report.closed.push(position.mint);
+            } else if balance != position.token_amount {
+                self.price_monitor.set_position_token_amount(&position.mint, balance);
+                report.adjusted.push((position.mint, position.token_amount, balance));
+            }
+        }
+        *missing = still_missing;
+        for (mint, balance) in balances {
+            if *balance > 0 && !tracked.contains(mint) {
+                println!(
+                    "警告: 钱包持有未监控的代币 {}，余额 {}",
+                    TokenMetadataCache::get_instance().label(mint),
+                    balance
+                );
+                report.untracked.push((*mint, *balance));
+            }
+        }
+        report
+    }
+
+    /// 启动后台任务，定时对账，单次失败只打印警告
+    pub fn start(self: &Arc<Self>, rpc: Arc<SolanaRpcClient>, interval_ms: u64) -> JoinHandle<()> {
+        let reconciler = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_millis(interval_ms.max(1_000)));
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                if let Err(e) = reconciler.reconcile(&rpc).await {
+                    println!("警告: 持仓对账失败: {}", e);
+                }
+            }
+        })
+    }
+}
+
+/// 从 jsonParsed 编码的代币账户中读取代币和余额
+pub(crate) fn parse_token_balance(data: &UiAccountData) -> Option<(Pubkey, u64)> {
+    let UiAccountData::Json(account) = data else {
+        return None;
+    };
+    let info = account.parsed.get("info")?;
+    let mint = Pubkey::from_str(info.get("mint")?.as_str()?).ok()?;
+    let amount = info.get("tokenAmount")?.get("amount")?.as_str()?.parse().ok()?;
+    Some((mint, amount))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitor::price_monitor::TakeProfitStopLossConfig;
+
+    #[test]
+    fn test_reconcile_fixes_drift() {
+        let price_monitor = Arc::new(PriceMonitor::new(TakeProfitStopLossConfig::default()));
+        let reconciler = PositionReconciler::new(price_monitor.clone(), Pubkey::new_unique());
+        let (held, sold, airdrop) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        price_monitor.open_position(held, 1.0);
+        price_monitor.set_position_token_amount(&held, 1_000);
+        price_monitor.open_position(sold, 1.0);
+        let balances = HashMap::from([(held, 600), (airdrop, 50)]);
+
+        let report = reconciler.reconcile_balances(&balances);
+        assert_eq!(report.adjusted, vec![(held, 1_000, 600)]);
+        assert_eq!(report.untracked, vec![(airdrop, 50)]);
+        // 第一次没有余额时保留，可能是 RPC 尚未同步
+        assert!(report.closed.is_empty());
+        assert!(price_monitor.position(&sold).is_some());
+
+        let report = reconciler.reconcile_balances(&balances);
+        assert_eq!(report.closed, vec![sold]);
+        assert!(report.adjusted.is_empty());
+        assert!(price_monitor.position(&sold).is_none());
+        assert_eq!(price_monitor.position(&held).unwrap().token_amount, 600);
+
+        let account = serde_json::json!({
+            "program": "spl-token",
+            "parsed": { "info": { "mint": held.to_string(), "tokenAmount": { "amount": "600" } } },
+            "space": 165
+        });
+        let data = UiAccountData::Json(serde_json::from_value(account).unwrap());
+        assert_eq!(parse_token_balance(&data), Some((held, 600)));
+    }
+}
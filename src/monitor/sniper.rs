@@ -0,0 +1,305 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use regex::Regex;
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::broadcast;
+
+use crate::common::bonding_curve::BondingCurveAccount;
+use crate::constants::bonk::launch;
+use crate::constants::pumpswap::accounts::WSOL_TOKEN_ACCOUNT;
+use crate::solana_streamer_sdk::streaming::event_parser::{
+    protocols::{
+        bonk::BonkPoolCreateEvent, pumpfun::PumpFunCreateTokenEvent,
+        raydium_cpmm::RaydiumCpmmInitializeEvent,
+    },
+    UnifiedEvent,
+};
+use crate::trading::core::params::{BonkParams, PumpFunParams, RaydiumCpmmParams};
+use crate::trading::core::traits::{ProtocolParams, ProtocolParamsBox};
+use crate::trading::factory::DexType;
+
+/// 狙击信号广播通道容量
+const SNIPE_CHANNEL_CAPACITY: usize = 1024;
+
+/// 从流事件中解析的新发行代币或新池子
+#[derive(Debug, Clone)]
+pub struct Launch {
+    pub dex_type: DexType,
+    pub mint: Pubkey,
+    /// bonding curve 或池子地址
+    pub pool: Pubkey,
+    pub creator: Pubkey,
+    /// 池子事件不含元数据，名称和符号为空
+    pub name: String,
+    pub symbol: String,
+    /// 池子初始的 SOL 数量（lamports），bonding curve 发行时为 0
+    pub sol_liquidity: u64,
+    pub signature: String,
+    pub slot: u64,
+    /// 买入所需的协议参数，由事件数据直接构造，买入时不需要再查询链上状态
+    protocol_params: ProtocolParamsBox,
+}
+
+impl Launch {
+    /// 从流事件中解析，支持 PumpFun 发币、Raydium CPMM 建池（需含 WSOL）和 Bonk 发币
+    pub fn from_event(event: &dyn UnifiedEvent) -> Option<Self> {
+        let any = event.as_any();
+        let signature = event.signature().to_string();
+        let slot = event.slot();
+
+        if let Some(e) = any.downcast_ref::<PumpFunCreateTokenEvent>() {
+            let bonding_curve = BondingCurveAccount {
+                discriminator: 0,
+                account: e.bonding_curve,
+                virtual_token_reserves: e.virtual_token_reserves,
+                virtual_sol_reserves: e.virtual_sol_reserves,
+                real_token_reserves: e.real_token_reserves,
+                real_sol_reserves: 0,
+                token_total_supply: e.token_total_supply,
+                complete: false,
+                creator: e.creator,
+            };
+            return Some(Self {
+                dex_type: DexType::PumpFun,
+                mint: e.mint,
+                pool: e.bonding_curve,
+                creator: e.creator,
+                name: e.name.clone(),
+                symbol: e.symbol.clone(),
+                sol_liquidity: 0,
+                signature,
+                slot,
+                protocol_params: ProtocolParamsBox(Box::new(PumpFunParams {
+                    bonding_curve: Some(Arc::new(bonding_curve)),
+                })),
+            });
+        }
+
+        if let Some(e) = any.downcast_ref::<BonkPoolCreateEvent>() {
+            // 按 letsbonk.fun 默认参数新建的池子，储备与 `launch` 常量一致
+            return Some(Self {
+                dex_type: DexType::Bonk,
+                mint: e.base_mint,
+                pool: e.pool_state,
+                creator: e.creator,
+                name: e.base_mint_param.name.clone(),
+                symbol: e.base_mint_param.symbol.clone(),
+                sol_liquidity: 0,
+                signature,
+                slot,
+                protocol_params: ProtocolParamsBox(Box::new(BonkParams {
+                    virtual_base: Some(launch::VIRTUAL_BASE),
+                    virtual_quote: Some(launch::VIRTUAL_QUOTE),
+                    real_base: Some(0),
+                    real_quote: Some(0),
+                    auto_handle_wsol: true,
+                })),
+            });
+        }
+
+        if let Some(e) = any.downcast_ref::<RaydiumCpmmInitializeEvent>() {
+            let (mint, index, token_program, sol_liquidity) = if e.token0_mint == WSOL_TOKEN_ACCOUNT
+            {
+                (e.token1_mint, 1, e.token1_program, e.init_amount0)
+            } else if e.token1_mint == WSOL_TOKEN_ACCOUNT {
+                (e.token0_mint, 0, e.token0_program, e.init_amount1)
+            } else {
+                return None;
+            };
+            return Some(Self {
+                dex_type: DexType::RaydiumCpmm,
+                mint,
+                pool: e.pool_state,
+                creator: e.creator,
+                name: String::new(),
+                symbol: String::new(),
+                sol_liquidity,
+                signature,
+                slot,
+                protocol_params: ProtocolParamsBox(Box::new(RaydiumCpmmParams {
+                    pool_state: Some(e.pool_state),
+                    mint_token_program: Some(token_program),
+                    mint_token_in_pool_state_index: Some(index),
+                    minimum_amount_out: None,
+                    auto_handle_wsol: true,
+                })),
+            });
+        }
+
+        None
+    }
+
+    /// 买入所需的协议参数
+    pub fn protocol_params(&self) -> Box<dyn ProtocolParams> {
+        self.protocol_params.0.clone()
+    }
+}
+
+/// 狙击过滤规则，未设置的规则不做限制
+#[derive(Debug, Clone, Default)]
+pub struct SniperFilter {
+    /// 参与狙击的协议，为空时全部参与
+    pub dex_types: Vec<DexType>,
+    /// 只狙击这些创建者，为空时不限制
+    pub creators: HashSet<Pubkey>,
+    /// 不狙击这些创建者
+    pub blocked_creators: HashSet<Pubkey>,
+    /// 不狙击这些代币
+    pub blocked_mints: HashSet<Pubkey>,
+    /// 池子初始 SOL 下限（lamports），只对 Raydium CPMM 建池生效
+    pub min_sol_liquidity: u64,
+    /// 名称或符号需匹配的正则，池子事件没有名称，设置后不会命中
+    pub name_pattern: Option<Regex>,
+}
+
+impl SniperFilter {
+    pub fn accept(&self, launch: &Launch) -> bool {
+        if !self.dex_types.is_empty() && !self.dex_types.contains(&launch.dex_type) {
+            return false;
+        }
+        if !self.creators.is_empty() && !self.creators.contains(&launch.creator) {
+            return false;
+        }
+        if self.blocked_creators.contains(&launch.creator) || self.blocked_mints.contains(&launch.mint) {
+            return false;
+        }
+        if launch.dex_type == DexType::RaydiumCpmm && launch.sol_liquidity < self.min_sol_liquidity
+        {
+            return false;
+        }
+        self.name_pattern.as_ref().is_none_or(|pattern| {
+            pattern.is_match(&launch.name) || pattern.is_match(&launch.symbol)
+        })
+    }
+}
+
+/// 狙击配置
+#[derive(Debug, Clone)]
+pub struct SniperConfig {
+    pub filter: SniperFilter,
+    /// 每次狙击买入的 SOL 数量（lamports）
+    pub sol_amount: u64,
+    /// 滑点（基点），None 时使用默认滑点
+    pub slippage_basis_points: Option<u64>,
+}
+
+impl Default for SniperConfig {
+    fn default() -> Self {
+        Self {
+            filter: SniperFilter::default(),
+            sol_amount: 100_000_000,
+            slippage_basis_points: Some(3_000),
+        }
+    }
+}
+
+/// 狙击买入订单，协议参数已构造好，可直接交给 `SolanaTrade::snipe`
+#[derive(Debug, Clone)]
+pub struct SnipeOrder {
+    pub launch: Launch,
+    pub sol_amount: u64,
+    pub slippage_basis_points: Option<u64>,
+}
+
+/// 新池子狙击策略：解析发币和建池事件，命中过滤规则时广播买入订单
+///
+/// 同一代币只发出一次订单，多个数据源重复推送同一事件时不会重复买入
+pub struct SniperStrategy {
+    config: SniperConfig,
+    sniped: Mutex<HashSet<Pubkey>>,
+    sender: broadcast::Sender<SnipeOrder>,
+}
+
+impl SniperStrategy {
+    pub fn new(config: SniperConfig) -> Self {
+        let (sender, _) = broadcast::channel(SNIPE_CHANNEL_CAPACITY);
+        Self { config, sniped: Mutex::new(HashSet::new()), sender }
+    }
+
+    /// 订阅狙击订单
+    pub fn subscribe(&self) -> broadcast::Receiver<SnipeOrder> {
+        self.sender.subscribe()
+    }
+
+    /// 处理流事件，可直接在订阅回调中调用
+    pub fn on_event(&self, event: &dyn UnifiedEvent) -> Option<SnipeOrder> {
+        self.observe(Launch::from_event(event)?)
+    }
+
+    /// 命中过滤规则且未狙击过时返回订单并广播
+    pub fn observe(&self, launch: Launch) -> Option<SnipeOrder> {
+        if !self.config.filter.accept(&launch) || !self.sniped.lock().unwrap().insert(launch.mint) {
+            return None;
+        }
+        let order = SnipeOrder {
+            launch,
+            sol_amount: self.config.sol_amount,
+            slippage_basis_points: self.config.slippage_basis_points,
+        };
+        // 没有订阅者时发送失败，忽略即可
+        let _ = self.sender.send(order.clone());
+        Some(order)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniper_filters_and_dedupes() {
+        let creator = Pubkey::new_unique();
+        let strategy = SniperStrategy::new(SniperConfig {
+            filter: SniperFilter {
+                blocked_creators: HashSet::from([creator]),
+                min_sol_liquidity: 5_000_000_000,
+                name_pattern: Some(Regex::new("(?i)cat").unwrap()),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let create = PumpFunCreateTokenEvent {
+            name: "Cat Coin".to_string(),
+            symbol: "CAT".to_string(),
+            mint: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+            virtual_sol_reserves: 30_000_000_000,
+            ..Default::default()
+        };
+
+        let order = strategy.on_event(&create).unwrap();
+        assert_eq!((&order.launch.dex_type, order.launch.mint), (&DexType::PumpFun, create.mint));
+        let params = order.launch.protocol_params();
+        let curve = params.as_any().downcast_ref::<PumpFunParams>().unwrap().bonding_curve.clone();
+        assert_eq!(curve.unwrap().virtual_sol_reserves, 30_000_000_000);
+        // 同一代币只狙击一次
+        assert!(strategy.on_event(&create).is_none());
+
+        let dog = PumpFunCreateTokenEvent {
+            name: "Dog".to_string(),
+            symbol: "DOG".to_string(),
+            ..create.clone()
+        };
+        assert!(strategy
+            .on_event(&PumpFunCreateTokenEvent { mint: Pubkey::new_unique(), ..dog })
+            .is_none());
+        let blocked = PumpFunCreateTokenEvent { mint: Pubkey::new_unique(), creator, ..create };
+        assert!(strategy.on_event(&blocked).is_none());
+
+        let pool = RaydiumCpmmInitializeEvent {
+            token0_mint: WSOL_TOKEN_ACCOUNT,
+            token1_mint: Pubkey::new_unique(),
+            init_amount0: 1_000_000_000,
+            ..Default::default()
+        };
+        let launch = Launch::from_event(&pool).unwrap();
+        assert_eq!((launch.mint, launch.sol_liquidity), (pool.token1_mint, 1_000_000_000));
+        let pool_filter = SniperFilter { min_sol_liquidity: 5_000_000_000, ..Default::default() };
+        // 池子初始 SOL 不足
+        assert!(!pool_filter.accept(&launch));
+        let blocked_mint = SniperFilter { blocked_mints: HashSet::from([launch.mint]), ..Default::default() };
+        assert!(!blocked_mint.accept(&launch));
+        assert!(SniperFilter::default().accept(&launch));
+    }
+}
@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::Result;
+use prost_types::Timestamp;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_rpc_client_api::config::RpcTransactionConfig;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::UiTransactionEncoding;
+use tokio::time::MissedTickBehavior;
+
+use crate::common::SolanaRpcClient;
+use crate::monitor::custom_parser::CustomEventParsers;
+use crate::monitor::large_swap::SwapObservation;
+use crate::solana_streamer_sdk::streaming::event_parser::{EventParserFactory, Protocol};
+use crate::trading::moonshot::event::{parse_transaction as parse_moonshot_transaction, MoonshotEvent};
+
+/// getSignaturesForAddress 单次请求的签名数量上限
+const MAX_SIGNATURES_PER_REQUEST: usize = 1000;
+
+/// 钱包历史回填配置
+#[derive(Debug, Clone)]
+pub struct WalletBackfillConfig {
+    /// 回填的最近签名数量
+    pub max_signatures: usize,
+    /// 每秒最多拉取的交易数，避免触发 RPC 限流
+    pub requests_per_second: u32,
+}
+
+impl Default for WalletBackfillConfig {
+    fn default() -> Self {
+        Self { max_signatures: 100, requests_per_second: 5 }
+    }
+}
+
+/// 单个钱包的交易统计，新增钱包时由回填初始化，之后按流事件更新
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WalletStats {
+    pub buys: u64,
+    pub sells: u64,
+    /// 买入花费的 SOL（lamports）
+    pub sol_bought: u64,
+    /// 卖出获得的 SOL（lamports）
+    pub sol_sold: u64,
+    /// 各代币的净持仓（买入减卖出的代币数量）
+    pub positions: HashMap<Pubkey, u64>,
+    pub last_slot: u64,
+}
+
+impl WalletStats {
+    /// 由按时间排序的交易初始化
+    pub fn from_swaps<'a>(swaps: impl IntoIterator<Item = &'a SwapObservation>) -> Self {
+        let mut stats = Self::default();
+        for swap in swaps {
+            stats.record(swap);
+        }
+        stats
+    }
+
+    pub fn record(&mut self, swap: &SwapObservation) {
+        if swap.is_buy {
+            self.buys += 1;
+            self.sol_bought += swap.sol_amount;
+            *self.positions.entry(swap.mint).or_default() += swap.token_amount;
+        } else {
+            self.sells += 1;
+            self.sol_sold += swap.sol_amount;
+            if let Some(position) = self.positions.get_mut(&swap.mint) {
+                *position = position.saturating_sub(swap.token_amount);
+                if *position == 0 {
+                    self.positions.remove(&swap.mint);
+                }
+            }
+        }
+        self.last_slot = self.last_slot.max(swap.slot);
+    }
+
+    /// 钱包在回填或监控期间买入过且仍持有该代币，卖出属于常规平仓而非新信号
+    pub fn holds(&self, mint: &Pubkey) -> bool {
+        self.positions.contains_key(mint)
+    }
+}
+
+/// 拉取钱包最近的交易并解析出其本人发起的 swap，按时间从旧到新返回
+///
+/// 除 streamer 支持的协议和 Moonshot 外，也使用 `CustomEventParsers` 中已注册的解析器。
+/// 失败的交易会被跳过，单笔交易拉取失败只打印警告。拉取速度受 `requests_per_second` 限制，
+/// 回填 N 笔交易大约需要 N / requests_per_second 秒。
+pub async fn backfill_wallet(
+    rpc: &SolanaRpcClient,
+    wallet: &Pubkey,
+    config: &WalletBackfillConfig,
+) -> Result<Vec<SwapObservation>> {
+    let mut signatures = rpc
+        .get_signatures_for_address_with_config(
+            wallet,
+            GetConfirmedSignaturesForAddress2Config {
+                limit: Some(config.max_signatures.min(MAX_SIGNATURES_PER_REQUEST)),
+                ..Default::default()
+            },
+        )
+        .await?;
+    signatures.retain(|status| status.err.is_none());
+    // 接口按从新到旧返回
+    signatures.reverse();
+
+    let parsers: Vec<_> =
+        [Protocol::PumpFun, Protocol::PumpSwap, Protocol::Bonk, Protocol::RaydiumCpmm]
+            .into_iter()
+            .map(EventParserFactory::create_parser)
+            .collect();
+    let transaction_config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0),
+    };
+    let mut interval = tokio::time::interval(Duration::from_secs_f64(
+        1.0 / config.requests_per_second.max(1) as f64,
+    ));
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    let mut swaps = Vec::new();
+    for status in signatures {
+        interval.tick().await;
+        let Ok(signature) = Signature::from_str(&status.signature) else {
+            continue;
+        };
+        let transaction = match rpc.get_transaction_with_config(&signature, transaction_config).await {
+            Ok(transaction) => transaction,
+            Err(e) => {
+                println!("警告: 回填 {} 时获取交易 {} 失败: {}", wallet, status.signature, e);
+                continue;
+            }
+        };
+        let slot = transaction.slot;
+        let block_time = transaction.block_time.map(|seconds| Timestamp { seconds, nanos: 0 });
+
+        for parser in &parsers {
+            let events = parser
+                .parse_transaction(
+                    transaction.transaction.clone(),
+                    &status.signature,
+                    Some(slot),
+                    block_time,
+                    0,
+                    None,
+                )
+                .await
+                .unwrap_or_default();
+            swaps.extend(
+                events
+                    .iter()
+                    .filter_map(|event| SwapObservation::from_event(event.as_ref()))
+                    .filter(|swap| swap.user == *wallet),
+            );
+        }
+        if let Some(versioned_transaction) = transaction.transaction.transaction.decode() {
+            swaps.extend(
+                parse_moonshot_transaction(&versioned_transaction, slot)
+                    .into_iter()
+                    .filter_map(|event| match event {
+                        MoonshotEvent::Trade(trade) => Some(SwapObservation::from(trade)),
+                        _ => None,
+                    })
+                    .filter(|swap| swap.user == *wallet),
+            );
+            swaps.extend(
+                CustomEventParsers::get_instance()
+                    .parse_transaction(&versioned_transaction, slot)
+                    .iter()
+                    .filter_map(|event| SwapObservation::from_event(event.as_ref()))
+                    .filter(|swap| swap.user == *wallet),
+            );
+        }
+    }
+    Ok(swaps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::factory::DexType;
+
+    fn swap(mint: Pubkey, is_buy: bool, token_amount: u64, slot: u64) -> SwapObservation {
+        SwapObservation { dex_type: DexType::PumpFun, mint, is_buy, token_amount, slot, ..SwapObservation::for_test() }
+    }
+
+    #[test]
+    fn test_wallet_stats_track_positions() {
+        let mint = Pubkey::new_unique();
+        let swaps = [swap(mint, true, 100, 1), swap(mint, false, 40, 2)];
+        let mut stats = WalletStats::from_swaps(&swaps);
+        assert_eq!((stats.buys, stats.sells, stats.last_slot), (1, 1, 2));
+        assert!(stats.holds(&mint));
+
+        stats.record(&swap(mint, false, 60, 3));
+        assert!(!stats.holds(&mint));
+        // 回填窗口之前买入的代币没有持仓记录
+        stats.record(&swap(Pubkey::new_unique(), false, 10, 4));
+        assert!(stats.positions.is_empty());
+    }
+}
@@ -0,0 +1,1156 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use chrono::{FixedOffset, NaiveDate, Utc};
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+use crate::common::trading_day::{today, utc_offset};
+use crate::common::{
+    AnyResult, ExposureLimit, ExposureTracker, SpendLimit, TokenMetadataCache,
+};
+use crate::monitor::address_list::AddressEntry;
+use crate::monitor::large_swap::SwapObservation;
+use crate::monitor::market_stats::MarketStats;
+use crate::monitor::position_store::PositionStore;
+use crate::solana_streamer_sdk::streaming::{
+    event_parser::{
+        protocols::{
+            bonk::BonkTradeEvent,
+            pumpfun::PumpFunTradeEvent,
+            pumpswap::{PumpSwapBuyEvent, PumpSwapSellEvent},
+            raydium_cpmm::RaydiumCpmmSwapEvent,
+        },
+        Protocol, UnifiedEvent,
+    },
+    ShredStreamGrpc,
+};
+use crate::strategy::{StrategyEvent, StrategyEventBus};
+use crate::trading::core::params::{BonkParams, PumpFunParams, PumpSwapParams, RaydiumCpmmParams};
+use crate::trading::core::traits::{ProtocolParams, ProtocolParamsBox};
+use crate::trading::factory::DexType;
+
+/// 目标钱包交易广播通道容量
+const WALLET_SWAP_CHANNEL_CAPACITY: usize = 1024;
+
+/// 跟买金额的计算方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CopySizing {
+    /// 目标钱包买入金额的比例，0 表示不跟买
+    Ratio(f64),
+    /// 固定金额（lamports）
+    FixedSol(u64),
+    /// 跟买时钱包 SOL 余额的百分比
+    WalletPercent(f64),
+    /// 按单笔风险计算：止损时亏损钱包余额的 `risk_percent`，
+    /// 金额为余额 × `risk_percent` / `stop_loss_percent`，止损比例应与持仓的止损配置一致
+    RiskPerTrade { risk_percent: f64, stop_loss_percent: f64 },
+}
+
+impl Default for CopySizing {
+    fn default() -> Self {
+        Self::Ratio(1.0)
+    }
+}
+
+impl CopySizing {
+    /// 跟买金额（lamports），`target_sol_amount` 为目标钱包买入金额，`wallet_balance` 为当前钱包余额
+    /// 按余额计算的金额不超过余额，止损比例不大于 0 时不跟买
+    pub fn sol_amount(&self, target_sol_amount: u64, wallet_balance: u64) -> u64 {
+        match *self {
+            Self::Ratio(ratio) => (target_sol_amount as f64 * ratio) as u64,
+            Self::FixedSol(sol_amount) => sol_amount,
+            Self::WalletPercent(percent) => {
+                ((wallet_balance as f64 * percent / 100.0) as u64).min(wallet_balance)
+            }
+            Self::RiskPerTrade { risk_percent, stop_loss_percent } => {
+                if stop_loss_percent <= 0.0 {
+                    return 0;
+                }
+                ((wallet_balance as f64 * risk_percent / stop_loss_percent) as u64).min(wallet_balance)
+            }
+        }
+    }
+}
+
+/// 跟买配置
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WalletCopyConfig {
+    /// 跟买金额的计算方式
+    pub sizing: CopySizing,
+    /// 跟买花费上限，超过单笔上限时按上限跟买，超过当日上限时不跟买
+    pub spend_limit: SpendLimit,
+    /// 跟买的协议，为空时全部跟买
+    pub dex_types: Vec<DexType>,
+}
+
+/// 目标钱包的名称和分组
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WalletLabel {
+    /// 名称，日志和跟买指令中代替钱包地址
+    pub name: String,
+    /// 所属分组，如 "insiders"、"KOLs"，分组的跟买配置在 `group_copy` 中设置
+    pub group: Option<String>,
+}
+
+/// 跟买前对池子流动性和近期成交量的要求，0 表示不检查
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CopyMarketFilter {
+    /// 池子 SOL 储备下限（lamports），事件不含储备的协议（如 Raydium CPMM）不检查
+    pub min_sol_reserve: u64,
+    /// 窗口内成交量下限（lamports），包含目标钱包本次买入
+    pub min_volume: u64,
+    /// 成交量统计窗口（slot）
+    pub volume_window_slots: u64,
+}
+
+impl Default for CopyMarketFilter {
+    fn default() -> Self {
+        Self { min_sol_reserve: 0, min_volume: 0, volume_window_slots: 150 }
+    }
+}
+
+/// 钱包监控配置
+#[derive(Debug, Clone)]
+pub struct WalletMonitorConfig {
+    /// 目标钱包
+    pub wallets: HashSet<Pubkey>,
+    /// 订阅的协议
+    pub protocols: Vec<Protocol>,
+    /// 没有单独配置的钱包使用的跟买配置
+    pub copy: WalletCopyConfig,
+    /// 单个钱包的跟买配置
+    pub wallet_copy: HashMap<Pubkey, WalletCopyConfig>,
+    /// 目标钱包的名称和分组
+    pub wallet_labels: HashMap<Pubkey, WalletLabel>,
+    /// 分组的跟买配置，钱包没有单独配置时使用所在分组的配置，跟买上限仍按钱包分别计算
+    pub group_copy: HashMap<String, WalletCopyConfig>,
+    /// 所有跟买持仓合计的敞口上限
+    pub exposure_limit: ExposureLimit,
+    /// 跟买前的流动性和成交量要求，按订阅到的全部交易统计
+    pub market_filter: CopyMarketFilter,
+    /// 同一代币两次跟买之间至少间隔的 slot 数，按目标钱包交易的 slot 计算，0 表示不限制；
+    /// 多个目标钱包买入同一代币或重复处理同一笔交易时只跟买一次
+    pub mint_cooldown_slots: u64,
+    /// 已持有（敞口未清零）的代币不再跟买
+    pub skip_held_mints: bool,
+    /// 目标钱包交易的 slot 落后于已观察到的最新 slot 超过该值时不跟买，
+    /// 例如重连后处理积压的事件，0 表示不检查
+    pub max_signal_age_slots: u64,
+    /// 目标钱包交易的区块时间到跟买超过该毫秒数时不跟买，只对经过 `on_event` 的交易生效；
+    /// shred 中的交易没有区块时间，按程序收到事件的时间计算，0 表示不检查
+    pub max_signal_age_ms: u64,
+    /// 跟买执行超时（毫秒）：`copy_buy` 返回的订单超过该时间仍未调用 `complete_copy_buy` 或
+    /// `release_copy_buy` 时，`expire_copy_buys` 视为失败并归还额度，0 表示不跟踪执行中的跟买。
+    /// 超时的交易仍可能上链，因此保留该代币的冷却，冷却期应不短于 blockhash 有效期（约 150 slot）
+    pub copy_execution_timeout_ms: u64,
+    /// 划分跟买每日上限的时区，通常与 `TradeConfig::reporting_timezone` 相同
+    pub reporting_timezone: FixedOffset,
+}
+
+impl Default for WalletMonitorConfig {
+    fn default() -> Self {
+        Self {
+            wallets: HashSet::new(),
+            protocols: vec![Protocol::PumpFun, Protocol::PumpSwap, Protocol::Bonk, Protocol::RaydiumCpmm],
+            copy: WalletCopyConfig::default(),
+            wallet_copy: HashMap::new(),
+            wallet_labels: HashMap::new(),
+            group_copy: HashMap::new(),
+            exposure_limit: ExposureLimit::default(),
+            market_filter: CopyMarketFilter::default(),
+            mint_cooldown_slots: 150,
+            skip_held_mints: true,
+            max_signal_age_slots: 0,
+            max_signal_age_ms: 0,
+            copy_execution_timeout_ms: 0,
+            reporting_timezone: utc_offset(),
+        }
+    }
+}
+
+/// 跟买指令
+#[derive(Debug, Clone)]
+pub struct CopyBuyOrder {
+    /// 目标钱包交易所在的协议，跟买在同一协议上执行
+    pub dex_type: DexType,
+    pub mint: Pubkey,
+    /// 跟买金额（lamports）
+    pub sol_amount: u64,
+    /// 代币创建者，PumpFun / PumpSwap 用于计算创建者费用账户，事件不含创建者时为 None
+    pub creator: Option<Pubkey>,
+    /// 触发跟买的目标钱包交易
+    pub source: SwapObservation,
+    /// 目标钱包的名称和分组，没有设置时为 None
+    pub wallet_label: Option<Arc<WalletLabel>>,
+    /// 由目标钱包交易事件构造的协议参数，包含池子地址和交易后的储备
+    protocol_params: Option<ProtocolParamsBox>,
+}
+
+impl CopyBuyOrder {
+    /// 跟买使用的协议参数，由 `WalletMonitor::on_event` 处理的事件构造；
+    /// 只经过 `observe` 的交易没有参数，买入时通过 RPC 查询
+    pub fn protocol_params(&self) -> Option<Box<dyn ProtocolParams>> {
+        self.protocol_params.as_ref().map(|params| params.0.clone())
+    }
+}
+
+/// 目标钱包一笔买入事件的时间和其中的协议参数
+struct CopyTradeParams {
+    signature: String,
+    /// 交易的区块时间（Unix 毫秒），没有区块时间时为程序收到事件的时间
+    event_time_ms: i64,
+    creator: Option<Pubkey>,
+    protocol_params: Option<ProtocolParamsBox>,
+}
+
+/// 目标钱包交易监控，用于跟单
+///
+/// 通过 ShredStream 在区块确认前从 shred 中解析交易，比 gRPC 订阅确认后的交易延迟更低，
+/// 但交易此时尚未确认，仍可能执行失败
+pub struct WalletMonitor {
+    wallets: RwLock<HashSet<Pubkey>>,
+    protocols: Vec<Protocol>,
+    sender: broadcast::Sender<SwapObservation>,
+    copy: WalletCopyConfig,
+    wallet_copy: RwLock<HashMap<Pubkey, WalletCopyConfig>>,
+    wallet_labels: RwLock<HashMap<Pubkey, WalletLabel>>,
+    group_copy: RwLock<HashMap<String, WalletCopyConfig>>,
+    /// 各钱包当日已跟买的金额（lamports）
+    spent: Mutex<HashMap<Pubkey, (NaiveDate, u64)>>,
+    timezone: FixedOffset,
+    exposure: Arc<ExposureTracker>,
+    market_filter: CopyMarketFilter,
+    market: MarketStats,
+    mint_cooldown_slots: u64,
+    skip_held_mints: bool,
+    /// 各代币最近一次跟买的目标钱包交易 slot
+    last_copied: Mutex<HashMap<Pubkey, u64>>,
+    /// 目标钱包最近一笔买入事件中的协议参数，按代币保存，`copy_buy` 时取出
+    copy_params: Mutex<HashMap<Pubkey, CopyTradeParams>>,
+    event_bus: Option<Arc<StrategyEventBus>>,
+    max_signal_age_slots: u64,
+    max_signal_age_ms: u64,
+    /// 已观察到的最新 slot
+    latest_slot: AtomicU64,
+    copy_execution_timeout: Option<Duration>,
+    /// 执行中的跟买及其开始时间，按代币和目标钱包交易签名区分
+    executing: Mutex<HashMap<(Pubkey, String), (CopyBuyOrder, Instant)>>,
+    store: Option<Arc<dyn PositionStore>>,
+}
+
+impl WalletMonitor {
+    pub fn new(config: WalletMonitorConfig) -> Self {
+        let (sender, _) = broadcast::channel(WALLET_SWAP_CHANNEL_CAPACITY);
+        Self {
+            wallets: RwLock::new(config.wallets),
+            protocols: config.protocols,
+            sender,
+            copy: config.copy,
+            wallet_copy: RwLock::new(config.wallet_copy),
+            wallet_labels: RwLock::new(config.wallet_labels),
+            group_copy: RwLock::new(config.group_copy),
+            spent: Mutex::new(HashMap::new()),
+            timezone: config.reporting_timezone,
+            exposure: Arc::new(ExposureTracker::new(config.exposure_limit)),
+            market_filter: config.market_filter,
+            market: MarketStats::new(config.market_filter.volume_window_slots),
+            mint_cooldown_slots: config.mint_cooldown_slots,
+            skip_held_mints: config.skip_held_mints,
+            last_copied: Mutex::new(HashMap::new()),
+            copy_params: Mutex::new(HashMap::new()),
+            event_bus: None,
+            max_signal_age_slots: config.max_signal_age_slots,
+            max_signal_age_ms: config.max_signal_age_ms,
+            latest_slot: AtomicU64::new(0),
+            copy_execution_timeout: (config.copy_execution_timeout_ms > 0)
+                .then(|| Duration::from_millis(config.copy_execution_timeout_ms)),
+            executing: Mutex::new(HashMap::new()),
+            store: None,
+        }
+    }
+
+    /// 从存储加载各代币的跟买冷却，之后每次跟买时写入，重启或主备切换后不会重复跟买冷却期内的代币
+    pub fn with_store(mut self, store: Arc<dyn PositionStore>) -> AnyResult<Self> {
+        self.last_copied.lock().unwrap().extend(store.load_copy_cooldowns()?);
+        self.store = Some(store);
+        Ok(self)
+    }
+
+    /// 目标钱包的交易同时发布到策略事件总线
+    pub fn with_event_bus(mut self, event_bus: Arc<StrategyEventBus>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    /// 订阅目标钱包的交易
+    pub fn subscribe(&self) -> broadcast::Receiver<SwapObservation> {
+        self.sender.subscribe()
+    }
+
+    /// 添加目标钱包，运行中即时生效
+    pub fn add_wallet(&self, wallet: Pubkey) {
+        self.wallets.write().unwrap().insert(wallet);
+    }
+
+    /// 导入目标钱包列表，带名称或分组的钱包同时设置标签，只有分组时以地址为名称
+    ///
+    /// 已有的钱包和标签保留，返回新增的钱包数量
+    pub fn import_wallets(&self, entries: &[AddressEntry]) -> usize {
+        let mut wallets = self.wallets.write().unwrap();
+        let mut wallet_labels = self.wallet_labels.write().unwrap();
+        let mut added = 0;
+        for entry in entries {
+            if wallets.insert(entry.address) {
+                added += 1;
+            }
+            if entry.name.is_some() || entry.group.is_some() {
+                let name = entry.name.clone().unwrap_or_else(|| entry.address.to_string());
+                wallet_labels.insert(entry.address, WalletLabel { name, group: entry.group.clone() });
+            }
+        }
+        added
+    }
+
+    /// 导出目标钱包及其名称和分组，按地址排序；指定分组时只导出该分组
+    pub fn export_wallets(&self, group: Option<&str>) -> Vec<AddressEntry> {
+        let wallets = self.wallets.read().unwrap();
+        let wallet_labels = self.wallet_labels.read().unwrap();
+        let mut entries: Vec<AddressEntry> = wallets
+            .iter()
+            .map(|wallet| {
+                let label = wallet_labels.get(wallet);
+                AddressEntry {
+                    address: *wallet,
+                    name: label.map(|label| label.name.clone()),
+                    group: label.and_then(|label| label.group.clone()),
+                }
+            })
+            .filter(|entry| group.is_none() || entry.group.as_deref() == group)
+            .collect();
+        entries.sort_by_key(|entry| entry.address.to_string());
+        entries
+    }
+
+    /// 移除目标钱包
+    pub fn remove_wallet(&self, wallet: &Pubkey) {
+        self.wallets.write().unwrap().remove(wallet);
+    }
+
+    /// 设置单个钱包的跟买配置，None 时恢复使用全局配置，运行中即时生效
+    pub fn set_wallet_copy_config(&self, wallet: Pubkey, config: Option<WalletCopyConfig>) {
+        let mut wallet_copy = self.wallet_copy.write().unwrap();
+        match config {
+            Some(config) => wallet_copy.insert(wallet, config),
+            None => wallet_copy.remove(&wallet),
+        };
+    }
+
+    /// 钱包生效的跟买配置：依次使用钱包的配置、所在分组的配置和全局配置
+    pub fn wallet_copy_config(&self, wallet: &Pubkey) -> WalletCopyConfig {
+        if let Some(config) = self.wallet_copy.read().unwrap().get(wallet) {
+            return config.clone();
+        }
+        let group = self.wallet_label(wallet).and_then(|label| label.group);
+        group
+            .and_then(|group| self.group_copy.read().unwrap().get(&group).cloned())
+            .unwrap_or_else(|| self.copy.clone())
+    }
+
+    /// 设置分组的跟买配置，None 时分组内的钱包恢复使用全局配置，运行中即时生效
+    pub fn set_group_copy_config(&self, group: &str, config: Option<WalletCopyConfig>) {
+        let mut group_copy = self.group_copy.write().unwrap();
+        match config {
+            Some(config) => group_copy.insert(group.to_string(), config),
+            None => group_copy.remove(group),
+        };
+    }
+
+    /// 设置钱包的名称和分组，None 时移除，运行中即时生效
+    pub fn set_wallet_label(&self, wallet: Pubkey, label: Option<WalletLabel>) {
+        let mut wallet_labels = self.wallet_labels.write().unwrap();
+        match label {
+            Some(label) => wallet_labels.insert(wallet, label),
+            None => wallet_labels.remove(&wallet),
+        };
+    }
+
+    /// 钱包的名称和分组
+    pub fn wallet_label(&self, wallet: &Pubkey) -> Option<WalletLabel> {
+        self.wallet_labels.read().unwrap().get(wallet).cloned()
+    }
+
+    /// 钱包在日志中显示的名称，没有名称时为地址
+    pub fn wallet_name(&self, wallet: &Pubkey) -> String {
+        match self.wallet_labels.read().unwrap().get(wallet) {
+            Some(label) => format!("{} ({})", label.name, wallet),
+            None => wallet.to_string(),
+        }
+    }
+
+    /// 分组内的目标钱包
+    pub fn group_wallets(&self, group: &str) -> Vec<Pubkey> {
+        let wallets = self.wallets.read().unwrap();
+        self.wallet_labels
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(wallet, label)| label.group.as_deref() == Some(group) && wallets.contains(*wallet))
+            .map(|(wallet, _)| *wallet)
+            .collect()
+    }
+
+    /// 钱包当日已跟买的金额（lamports）
+    pub fn spent_today(&self, wallet: &Pubkey) -> u64 {
+        self.spent_on(today(&self.timezone), wallet)
+    }
+
+    /// 分组内所有钱包当日已跟买的金额合计（lamports）
+    pub fn group_spent_today(&self, group: &str) -> u64 {
+        let today = today(&self.timezone);
+        self.group_wallets(group).iter().map(|wallet| self.spent_on(today, wallet)).sum()
+    }
+
+    fn spent_on(&self, today: NaiveDate, wallet: &Pubkey) -> u64 {
+        match self.spent.lock().unwrap().get(wallet) {
+            Some((day, spent)) if *day == today => *spent,
+            _ => 0,
+        }
+    }
+
+    /// 跟买持仓的敞口，跟单持仓清仓后应调用 `exposure().close(&mint)`
+    pub fn exposure(&self) -> &ExposureTracker {
+        &self.exposure
+    }
+
+    /// 跟买敞口的共享句柄，交给 `PriceMonitor::with_exposure` 和 `CopySellMirror::with_exposure`
+    /// 后，持仓清仓时自动调用 `close`
+    pub fn shared_exposure(&self) -> Arc<ExposureTracker> {
+        self.exposure.clone()
+    }
+
+    /// 订阅到的全部交易的成交量和池子储备
+    pub fn market_stats(&self) -> &MarketStats {
+        &self.market
+    }
+
+    /// 池子储备或近期成交量低于要求时返回原因
+    fn check_market(&self, mint: &Pubkey) -> Option<String> {
+        let filter = &self.market_filter;
+        if let Some(sol_reserve) = self.market.sol_reserve(mint) {
+            if sol_reserve < filter.min_sol_reserve {
+                return Some(format!(
+                    "pool holds {} lamports, below {}",
+                    sol_reserve, filter.min_sol_reserve
+                ));
+            }
+        }
+        let volume = self.market.volume(mint);
+        if volume < filter.min_volume {
+            return Some(format!(
+                "volume of {} lamports in the last {} slots, below {}",
+                volume, filter.volume_window_slots, filter.min_volume
+            ));
+        }
+        None
+    }
+
+    /// 目标钱包交易已过时返回原因
+    fn check_stale(&self, swap: &SwapObservation, params: Option<&CopyTradeParams>) -> Option<String> {
+        let latest_slot = self.latest_slot.load(Ordering::Relaxed);
+        if self.max_signal_age_slots > 0
+            && swap.slot.saturating_add(self.max_signal_age_slots) < latest_slot
+        {
+            return Some(format!(
+                "signal from slot {} is {} slots behind",
+                swap.slot,
+                latest_slot - swap.slot
+            ));
+        }
+        let event_time_ms = params?.event_time_ms;
+        let age_ms = Utc::now().timestamp_millis() - event_time_ms;
+        if self.max_signal_age_ms > 0 && event_time_ms > 0 && age_ms > self.max_signal_age_ms as i64 {
+            return Some(format!("signal is {} ms old", age_ms));
+        }
+        None
+    }
+
+    /// 代币在冷却期内或已持有时返回原因
+    fn check_duplicate(
+        &self,
+        last_copied: &HashMap<Pubkey, u64>,
+        swap: &SwapObservation,
+    ) -> Option<String> {
+        if self.skip_held_mints && self.exposure.deployed(&swap.mint) > 0 {
+            return Some("already holding".to_string());
+        }
+        let last_slot = *last_copied.get(&swap.mint)?;
+        // 重复处理的旧交易 slot 不晚于上次跟买，同样视为冷却期内
+        if self.mint_cooldown_slots > 0
+            && swap.slot < last_slot.saturating_add(self.mint_cooldown_slots)
+        {
+            return Some(format!(
+                "copied at slot {}, cooling down for {} slots",
+                last_slot, self.mint_cooldown_slots
+            ));
+        }
+        None
+    }
+
+    /// 按钱包的跟买配置计算目标钱包买入对应的跟买，并计入当日花费和敞口
+    ///
+    /// `wallet_balance` 为跟买时钱包的 SOL 余额（lamports），按余额计算金额的方式使用，
+    /// 可由 `SolanaTrade::get_payer_sol_balance` 获取。
+    /// 卖出、非目标钱包、不跟买的协议、流动性或成交量不足、代币在冷却期内或已持有、
+    /// 超出当日上限或敞口上限、目标钱包交易已过时返回 None，跟买失败时应调用 `release_copy_buy`。
+    /// 设置了执行超时时，跟买成功后应调用 `complete_copy_buy`
+    pub fn copy_buy(&self, swap: &SwapObservation, wallet_balance: u64) -> Option<CopyBuyOrder> {
+        self.copy_buy_on(today(&self.timezone), swap, wallet_balance)
+    }
+
+    /// 跟买已成交，不再按执行超时处理
+    pub fn complete_copy_buy(&self, order: &CopyBuyOrder) {
+        self.executing.lock().unwrap().remove(&(order.mint, order.source.signature.clone()));
+    }
+
+    /// 执行中（未调用 `complete_copy_buy` 或 `release_copy_buy`）的跟买，未设置执行超时时为空
+    pub fn executing_copy_buys(&self) -> Vec<CopyBuyOrder> {
+        self.executing.lock().unwrap().values().map(|(order, _)| order.clone()).collect()
+    }
+
+    /// 把执行超时的跟买视为失败：归还额度、发布 `StrategyEvent::Error` 并返回这些订单。
+    /// 超时的交易仍可能上链，因此不解除该代币的冷却
+    pub fn expire_copy_buys(&self) -> Vec<CopyBuyOrder> {
+        self.expire_copy_buys_at(Instant::now())
+    }
+
+    fn expire_copy_buys_at(&self, now: Instant) -> Vec<CopyBuyOrder> {
+        let Some(timeout) = self.copy_execution_timeout else {
+            return vec![];
+        };
+        let expired: Vec<CopyBuyOrder> = {
+            let mut executing = self.executing.lock().unwrap();
+            let keys: Vec<(Pubkey, String)> = executing
+                .iter()
+                .filter(|(_, (_, started))| now.saturating_duration_since(*started) >= timeout)
+                .map(|(key, _)| key.clone())
+                .collect();
+            keys.iter().filter_map(|key| executing.remove(key)).map(|(order, _)| order).collect()
+        };
+        for order in &expired {
+            println!(
+                "跟买 {} 执行超过 {} ms，视为失败",
+                TokenMetadataCache::get_instance().label(&order.mint),
+                timeout.as_millis()
+            );
+            self.release_reservation(order);
+            if let Some(event_bus) = &self.event_bus {
+                event_bus.publish(StrategyEvent::Error {
+                    source: "copy_buy".to_string(),
+                    message: format!("{}: timed out after {} ms", order.mint, timeout.as_millis()),
+                });
+            }
+        }
+        expired
+    }
+
+    /// 启动后台任务，定时处理执行超时的跟买
+    pub fn start_copy_buy_expiry(self: &Arc<Self>, interval_ms: u64) -> JoinHandle<()> {
+        let monitor = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_millis(interval_ms.max(100)));
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                monitor.expire_copy_buys();
+            }
+        })
+    }
+
+    /// 归还 `copy_buy` 计入的当日花费和敞口，并解除该代币的冷却
+    pub fn release_copy_buy(&self, order: &CopyBuyOrder) {
+        self.release_reservation(order);
+        let mut last_copied = self.last_copied.lock().unwrap();
+        if last_copied.get(&order.mint) == Some(&order.source.slot) {
+            last_copied.remove(&order.mint);
+            self.save_cooldown(&order.mint, 0);
+        }
+    }
+
+    fn save_cooldown(&self, mint: &Pubkey, slot: u64) {
+        if let Some(Err(e)) = self.store.as_ref().map(|store| store.save_copy_cooldown(mint, slot)) {
+            eprintln!("保存跟买冷却 {} 失败: {}", mint, e);
+        }
+    }
+
+    /// 归还 `copy_buy` 计入的当日花费和敞口，保留冷却
+    fn release_reservation(&self, order: &CopyBuyOrder) {
+        self.complete_copy_buy(order);
+        self.exposure.release(&order.mint, order.sol_amount);
+        if let Some((day, spent)) = self.spent.lock().unwrap().get_mut(&order.source.user) {
+            if *day == today(&self.timezone) {
+                *spent = spent.saturating_sub(order.sol_amount);
+            }
+        }
+    }
+
+    fn copy_buy_on(
+        &self,
+        today: NaiveDate,
+        swap: &SwapObservation,
+        wallet_balance: u64,
+    ) -> Option<CopyBuyOrder> {
+        if !swap.is_buy || !self.wallets.read().unwrap().contains(&swap.user) {
+            return None;
+        }
+        // 无论是否跟买都取出，参数只对应这一笔交易
+        let params = self
+            .copy_params
+            .lock()
+            .unwrap()
+            .remove(&swap.mint)
+            .filter(|params| params.signature == swap.signature);
+        let config = self.wallet_copy_config(&swap.user);
+        if !config.dex_types.is_empty() && !config.dex_types.contains(&swap.dex_type) {
+            return None;
+        }
+        if let Some(reason) = self.check_stale(swap, params.as_ref()) {
+            println!("跳过跟买 {}: {}", TokenMetadataCache::get_instance().label(&swap.mint), reason);
+            return None;
+        }
+        if let Some(reason) = self.check_market(&swap.mint) {
+            println!("跳过跟买 {}: {}", TokenMetadataCache::get_instance().label(&swap.mint), reason);
+            return None;
+        }
+        // 持有锁直到跟买确定，并发处理的多笔买入只有一笔通过
+        let mut last_copied = self.last_copied.lock().unwrap();
+        if let Some(reason) = self.check_duplicate(&last_copied, swap) {
+            println!("跳过跟买 {}: {}", TokenMetadataCache::get_instance().label(&swap.mint), reason);
+            return None;
+        }
+        let mut sol_amount = config.sizing.sol_amount(swap.sol_amount, wallet_balance);
+        if let Some(max_sol_per_trade) = config.spend_limit.max_sol_per_trade {
+            sol_amount = sol_amount.min(max_sol_per_trade);
+        }
+        if sol_amount == 0 {
+            return None;
+        }
+
+        let mut spent = self.spent.lock().unwrap();
+        let spent = spent.entry(swap.user).or_insert((today, 0));
+        if spent.0 != today {
+            *spent = (today, 0);
+        }
+        let total = spent.1.saturating_add(sol_amount);
+        if config.spend_limit.max_sol_per_day.is_some_and(|max_sol_per_day| total > max_sol_per_day) {
+            println!(
+                "钱包 {} 当日跟买已达上限，跳过 {}",
+                self.wallet_name(&swap.user),
+                TokenMetadataCache::get_instance().label(&swap.mint)
+            );
+            return None;
+        }
+        if let Err(e) = self.exposure.reserve(&swap.mint, sol_amount) {
+            println!("跳过跟买 {}: {}", TokenMetadataCache::get_instance().label(&swap.mint), e);
+            return None;
+        }
+        spent.1 = total;
+        last_copied.insert(swap.mint, swap.slot);
+        self.save_cooldown(&swap.mint, swap.slot);
+        let order = CopyBuyOrder {
+            dex_type: swap.dex_type.clone(),
+            mint: swap.mint,
+            sol_amount,
+            creator: params.as_ref().and_then(|params| params.creator),
+            source: swap.clone(),
+            wallet_label: self.wallet_label(&swap.user).map(Arc::new),
+            protocol_params: params.and_then(|params| params.protocol_params),
+        };
+        if self.copy_execution_timeout.is_some() {
+            self.executing
+                .lock()
+                .unwrap()
+                .insert((order.mint, swap.signature.clone()), (order.clone(), Instant::now()));
+        }
+        Some(order)
+    }
+
+    /// 处理流事件，可直接在订阅回调中调用
+    ///
+    /// 目标钱包买入时保存事件的时间和其中的协议参数，随后的 `copy_buy` 在同一池子上跟买
+    pub fn on_event(&self, event: &dyn UnifiedEvent) -> Option<SwapObservation> {
+        let swap = self.observe(SwapObservation::from_event(event)?)?;
+        if swap.is_buy {
+            let (creator, protocol_params) = match copy_protocol_params(event, &swap.mint) {
+                Some((creator, protocol_params)) => (creator, Some(ProtocolParamsBox(protocol_params))),
+                None => (None, None),
+            };
+            self.copy_params.lock().unwrap().insert(
+                swap.mint,
+                CopyTradeParams {
+                    signature: swap.signature.clone(),
+                    event_time_ms: signal_time_ms(event),
+                    creator,
+                    protocol_params,
+                },
+            );
+        }
+        Some(swap)
+    }
+
+    /// 处理一笔交易，计入成交量统计，属于目标钱包时广播给订阅者并返回
+    pub fn observe(&self, swap: SwapObservation) -> Option<SwapObservation> {
+        self.market.observe(&swap);
+        self.latest_slot.fetch_max(swap.slot, Ordering::Relaxed);
+        if !self.wallets.read().unwrap().contains(&swap.user) {
+            return None;
+        }
+        // 没有订阅者时发送失败，忽略即可
+        let _ = self.sender.send(swap.clone());
+        if let Some(event_bus) = &self.event_bus {
+            event_bus.publish(StrategyEvent::TradeDetected(swap.clone()));
+        }
+        Some(swap)
+    }
+
+    /// 启动 ShredStream 订阅，连接失败或订阅结束时任务返回
+    ///
+    /// streamer 只解析其支持的协议，回调中拿不到原始交易，`CustomEventParsers` 中注册的解析器
+    /// 不会在这里生效；自定义程序的交易需自行订阅原始交易，用 `parse_transaction` 解析后调用 `on_event`
+    pub fn start_shredstream(self: &Arc<Self>, shredstream_url: String) -> JoinHandle<AnyResult<()>> {
+        let monitor = self.clone();
+        tokio::spawn(async move {
+            let shred_stream = ShredStreamGrpc::new(shredstream_url).await?;
+            let protocols = monitor.protocols.clone();
+            let callback = move |event: Box<dyn UnifiedEvent>| {
+                monitor.on_event(event.as_ref());
+            };
+            shred_stream.shredstream_subscribe(protocols, None, callback).await
+        })
+    }
+}
+
+/// 事件中交易的区块时间（Unix 毫秒）
+///
+/// 重连后补发的积压事件收到时间是新的，只有区块时间能反映交易的实际时间；
+/// shred 中的交易尚未出块，没有区块时间，此时使用程序收到事件的时间
+fn signal_time_ms(event: &dyn UnifiedEvent) -> i64 {
+    let any = event.as_any();
+    let block_time_ms = if let Some(e) = any.downcast_ref::<PumpFunTradeEvent>() {
+        e.metadata.block_time_ms
+    } else if let Some(e) = any.downcast_ref::<PumpSwapBuyEvent>() {
+        e.metadata.block_time_ms
+    } else if let Some(e) = any.downcast_ref::<PumpSwapSellEvent>() {
+        e.metadata.block_time_ms
+    } else if let Some(e) = any.downcast_ref::<BonkTradeEvent>() {
+        e.metadata.block_time_ms
+    } else if let Some(e) = any.downcast_ref::<RaydiumCpmmSwapEvent>() {
+        e.metadata.block_time_ms
+    } else {
+        0
+    };
+    if block_time_ms > 0 {
+        block_time_ms
+    } else {
+        event.program_received_time_ms()
+    }
+}
+
+/// 从目标钱包的交易事件构造同一协议、同一池子的买入参数和代币创建者
+pub(crate) fn copy_protocol_params(
+    event: &dyn UnifiedEvent,
+    mint: &Pubkey,
+) -> Option<(Option<Pubkey>, Box<dyn ProtocolParams>)> {
+    let any = event.as_any();
+    if let Some(e) = any.downcast_ref::<PumpFunTradeEvent>() {
+        return Some((Some(e.creator), Box::new(PumpFunParams::from_trade(e))));
+    }
+    if let Some(e) = any.downcast_ref::<PumpSwapBuyEvent>() {
+        return Some((Some(e.coin_creator), Box::new(PumpSwapParams::from_buy_trade(e))));
+    }
+    if let Some(e) = any.downcast_ref::<PumpSwapSellEvent>() {
+        return Some((Some(e.coin_creator), Box::new(PumpSwapParams::from_sell_trade(e))));
+    }
+    if let Some(e) = any.downcast_ref::<BonkTradeEvent>() {
+        return Some((None, Box::new(BonkParams::from_trade(e.clone()))));
+    }
+    if let Some(e) = any.downcast_ref::<RaydiumCpmmSwapEvent>() {
+        return Some((None, Box::new(RaydiumCpmmParams::from_trade(e, mint))));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试用的钱包余额，100 SOL
+    const BALANCE: u64 = 100_000_000_000;
+
+    fn swap(user: Pubkey) -> SwapObservation {
+        SwapObservation {
+            dex_type: DexType::PumpFun,
+            user,
+            sol_amount: 1_000_000_000,
+            sol_reserve: Some(100_000_000_000),
+            signature: "sig".to_string(),
+            ..SwapObservation::for_test()
+        }
+    }
+
+    #[test]
+    fn test_only_target_wallets_are_broadcast() {
+        let target = Pubkey::new_unique();
+        let monitor = WalletMonitor::new(WalletMonitorConfig {
+            wallets: HashSet::from([target]),
+            ..Default::default()
+        });
+        let mut swaps = monitor.subscribe();
+
+        assert!(monitor.observe(swap(Pubkey::new_unique())).is_none());
+        assert!(monitor.observe(swap(target)).is_some());
+        assert_eq!(swaps.try_recv().unwrap().user, target);
+        assert!(swaps.try_recv().is_err());
+
+        let other = Pubkey::new_unique();
+        monitor.add_wallet(other);
+        monitor.remove_wallet(&target);
+        assert!(monitor.observe(swap(other)).is_some());
+        assert!(monitor.observe(swap(target)).is_none());
+    }
+
+    #[test]
+    fn test_copy_buy_per_wallet_limits() {
+        let (regular, capped) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let monitor = WalletMonitor::new(WalletMonitorConfig {
+            wallets: HashSet::from([regular, capped]),
+            copy: WalletCopyConfig { sizing: CopySizing::Ratio(0.5), ..Default::default() },
+            wallet_copy: HashMap::from([(
+                capped,
+                WalletCopyConfig {
+                    sizing: CopySizing::Ratio(1.0),
+                    spend_limit: SpendLimit {
+                        max_sol_per_trade: Some(400_000_000),
+                        max_sol_per_day: Some(1_000_000_000),
+                    },
+                    dex_types: vec![DexType::PumpFun],
+                },
+            )]),
+            ..Default::default()
+        });
+        let day = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+
+        assert_eq!(monitor.copy_buy_on(day, &swap(regular), BALANCE).unwrap().sol_amount, 500_000_000);
+        assert!(monitor.copy_buy_on(day, &swap(Pubkey::new_unique()), BALANCE).is_none());
+        assert!(monitor.copy_buy_on(day, &SwapObservation { is_buy: false, ..swap(regular) }, BALANCE).is_none());
+
+        // 单笔按上限跟买，第三笔超出当日上限
+        assert_eq!(monitor.copy_buy_on(day, &swap(capped), BALANCE).unwrap().sol_amount, 400_000_000);
+        assert!(monitor.copy_buy_on(day, &swap(capped), BALANCE).is_some());
+        assert!(monitor.copy_buy_on(day, &swap(capped), BALANCE).is_none());
+        assert!(monitor.copy_buy_on(day.succ_opt().unwrap(), &swap(capped), BALANCE).is_some());
+        let pumpswap = SwapObservation { dex_type: DexType::PumpSwap, ..swap(capped) };
+        assert!(monitor.copy_buy_on(day.succ_opt().unwrap(), &pumpswap, BALANCE).is_none());
+
+        monitor.set_wallet_copy_config(capped, None);
+        assert_eq!(monitor.copy_buy_on(day, &pumpswap, BALANCE).unwrap().sol_amount, 500_000_000);
+    }
+
+    #[test]
+    fn test_copy_buy_group_config_and_labels() {
+        let (insider, kol, solo) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let label = |name: &str| WalletLabel { name: name.to_string(), group: Some("insiders".to_string()) };
+        let monitor = WalletMonitor::new(WalletMonitorConfig {
+            wallets: HashSet::from([insider, kol, solo]),
+            copy: WalletCopyConfig { sizing: CopySizing::Ratio(0.5), ..Default::default() },
+            wallet_copy: HashMap::from([(
+                solo,
+                WalletCopyConfig { sizing: CopySizing::FixedSol(100_000_000), ..Default::default() },
+            )]),
+            wallet_labels: HashMap::from([(insider, label("dev")), (solo, label("solo"))]),
+            group_copy: HashMap::from([(
+                "insiders".to_string(),
+                WalletCopyConfig { sizing: CopySizing::Ratio(0.2), ..Default::default() },
+            )]),
+            mint_cooldown_slots: 0,
+            ..Default::default()
+        });
+        let day = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+
+        // 分组配置优先于全局配置，钱包自己的配置优先于分组配置
+        let order = monitor.copy_buy_on(day, &swap(insider), BALANCE).unwrap();
+        assert_eq!(order.sol_amount, 200_000_000);
+        assert_eq!(order.wallet_label.unwrap().name, "dev");
+        assert_eq!(monitor.copy_buy_on(day, &swap(solo), BALANCE).unwrap().sol_amount, 100_000_000);
+        assert_eq!(monitor.copy_buy_on(day, &swap(kol), BALANCE).unwrap().sol_amount, 500_000_000);
+
+        let mut wallets = monitor.group_wallets("insiders");
+        wallets.sort();
+        let mut expected = vec![insider, solo];
+        expected.sort();
+        assert_eq!(wallets, expected);
+        assert_eq!(monitor.wallet_name(&kol), kol.to_string());
+        assert!(monitor.wallet_name(&insider).starts_with("dev ("));
+
+        let kol_label = WalletLabel { name: "kol".to_string(), group: Some("KOLs".to_string()) };
+        monitor.set_wallet_label(kol, Some(kol_label));
+        monitor.set_group_copy_config(
+            "KOLs",
+            Some(WalletCopyConfig { sizing: CopySizing::FixedSol(50_000_000), ..Default::default() }),
+        );
+        assert_eq!(monitor.copy_buy_on(day, &swap(kol), BALANCE).unwrap().sol_amount, 50_000_000);
+        monitor.set_group_copy_config("KOLs", None);
+        assert_eq!(monitor.copy_buy_on(day, &swap(kol), BALANCE).unwrap().sol_amount, 500_000_000);
+        assert_eq!(monitor.spent_on(day, &kol), 1_050_000_000);
+
+        // 导出分组后导入另一个实例，名称和分组保留
+        let exported = monitor.export_wallets(Some("KOLs"));
+        assert_eq!(exported.len(), 1);
+        assert_eq!((exported[0].address, exported[0].name.as_deref()), (kol, Some("kol")));
+        let other = WalletMonitor::new(WalletMonitorConfig::default());
+        assert_eq!(other.import_wallets(&monitor.export_wallets(None)), 3);
+        assert_eq!(other.export_wallets(None), monitor.export_wallets(None));
+        assert_eq!(other.import_wallets(&exported), 0);
+    }
+
+    #[test]
+    fn test_copy_sizing_modes() {
+        let target = 1_000_000_000;
+        assert_eq!(CopySizing::Ratio(0.5).sol_amount(target, BALANCE), 500_000_000);
+        assert_eq!(CopySizing::FixedSol(200_000_000).sol_amount(target, 0), 200_000_000);
+        assert_eq!(CopySizing::WalletPercent(2.0).sol_amount(target, BALANCE), 2_000_000_000);
+        assert_eq!(CopySizing::WalletPercent(150.0).sol_amount(target, BALANCE), BALANCE);
+        // 止损 20% 时亏损 1% 的余额：100 SOL × 1% / 20% = 5 SOL
+        let risk = CopySizing::RiskPerTrade { risk_percent: 1.0, stop_loss_percent: 20.0 };
+        assert_eq!(risk.sol_amount(target, BALANCE), 5_000_000_000);
+        let no_stop = CopySizing::RiskPerTrade { risk_percent: 1.0, stop_loss_percent: 0.0 };
+        assert_eq!(no_stop.sol_amount(target, BALANCE), 0);
+
+        // 跟买时按传入的余额计算，仍受单笔上限限制
+        let wallet = Pubkey::new_unique();
+        let monitor = WalletMonitor::new(WalletMonitorConfig {
+            wallets: HashSet::from([wallet]),
+            copy: WalletCopyConfig {
+                sizing: risk,
+                spend_limit: SpendLimit { max_sol_per_trade: Some(3_000_000_000), max_sol_per_day: None },
+                dex_types: vec![],
+            },
+            mint_cooldown_slots: 0,
+            ..Default::default()
+        });
+        let day = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        assert_eq!(monitor.copy_buy_on(day, &swap(wallet), 20_000_000_000).unwrap().sol_amount, 1_000_000_000);
+        assert_eq!(monitor.copy_buy_on(day, &swap(wallet), BALANCE).unwrap().sol_amount, 3_000_000_000);
+        assert!(monitor.copy_buy_on(day, &swap(wallet), 0).is_none());
+    }
+
+    #[test]
+    fn test_copy_buy_respects_exposure() {
+        let target = Pubkey::new_unique();
+        let monitor = WalletMonitor::new(WalletMonitorConfig {
+            wallets: HashSet::from([target]),
+            exposure_limit: ExposureLimit { max_positions: Some(1), ..Default::default() },
+            ..Default::default()
+        });
+        let day = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+
+        let order = monitor.copy_buy_on(day, &swap(target), BALANCE).unwrap();
+        // swap() 每次生成新代币，第二个持仓超出上限
+        assert!(monitor.copy_buy_on(day, &swap(target), BALANCE).is_none());
+        assert_eq!(monitor.exposure().total(), order.sol_amount);
+
+        monitor.release_copy_buy(&order);
+        assert_eq!(monitor.exposure().position_count(), 0);
+        assert!(monitor.copy_buy_on(day, &swap(target), BALANCE).is_some());
+    }
+
+    #[test]
+    fn test_copy_buy_market_filter() {
+        let target = Pubkey::new_unique();
+        let monitor = WalletMonitor::new(WalletMonitorConfig {
+            wallets: HashSet::from([target]),
+            market_filter: CopyMarketFilter {
+                min_sol_reserve: 50_000_000_000,
+                min_volume: 1_500_000_000,
+                volume_window_slots: 150,
+            },
+            ..Default::default()
+        });
+        let day = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+
+        // 成交量只有目标钱包本次买入的 1 SOL
+        let buy = swap(target);
+        monitor.observe(buy.clone());
+        assert!(monitor.copy_buy_on(day, &buy, BALANCE).is_none());
+        monitor.observe(SwapObservation { user: Pubkey::new_unique(), ..buy.clone() });
+        assert!(monitor.copy_buy_on(day, &buy, BALANCE).is_some());
+
+        // 池子储备不足
+        let dust = SwapObservation { sol_reserve: Some(1_000_000_000), ..swap(target) };
+        monitor.observe(dust.clone());
+        monitor.observe(SwapObservation { user: Pubkey::new_unique(), ..dust.clone() });
+        assert!(monitor.copy_buy_on(day, &dust, BALANCE).is_none());
+    }
+
+    #[test]
+    fn test_copy_buy_mint_cooldown_and_held() {
+        let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let monitor = WalletMonitor::new(WalletMonitorConfig {
+            wallets: HashSet::from([first, second]),
+            mint_cooldown_slots: 100,
+            ..Default::default()
+        });
+        let day = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let buy = SwapObservation { slot: 1_000, ..swap(first) };
+
+        assert!(monitor.copy_buy_on(day, &buy, BALANCE).is_some());
+        // 另一个目标钱包买入同一代币、重复处理同一笔交易都不再跟买
+        assert!(monitor.copy_buy_on(day, &SwapObservation { user: second, ..buy.clone() }, BALANCE).is_none());
+        assert!(monitor.copy_buy_on(day, &buy, BALANCE).is_none());
+
+        // 清仓后仍在冷却期内
+        monitor.exposure().close(&buy.mint);
+        assert!(monitor.copy_buy_on(day, &SwapObservation { slot: 1_050, ..buy.clone() }, BALANCE).is_none());
+        assert!(monitor.copy_buy_on(day, &SwapObservation { slot: 1_100, ..buy.clone() }, BALANCE).is_some());
+
+        // 跟买失败归还后可立即重试
+        let retry = SwapObservation { mint: Pubkey::new_unique(), ..buy.clone() };
+        let failed = monitor.copy_buy_on(day, &retry, BALANCE).unwrap();
+        monitor.release_copy_buy(&failed);
+        assert!(monitor.copy_buy_on(day, &retry, BALANCE).is_some());
+    }
+
+    #[test]
+    fn test_copy_cooldowns_shared_through_store() {
+        use crate::monitor::position_store::MemoryPositionStore;
+
+        let target = Pubkey::new_unique();
+        let store: Arc<dyn PositionStore> = Arc::new(MemoryPositionStore::default());
+        let config = WalletMonitorConfig {
+            wallets: HashSet::from([target]),
+            mint_cooldown_slots: 100,
+            ..Default::default()
+        };
+        let day = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let primary = WalletMonitor::new(config.clone()).with_store(store.clone()).unwrap();
+        let buy = SwapObservation { slot: 1_000, ..swap(target) };
+        assert!(primary.copy_buy_on(day, &buy, BALANCE).is_some());
+        let failed = SwapObservation { mint: Pubkey::new_unique(), ..buy.clone() };
+        let order = primary.copy_buy_on(day, &failed, BALANCE).unwrap();
+        primary.release_copy_buy(&order);
+
+        // 接管的实例从存储加载冷却，不重复跟买主实例已跟买的代币，归还的跟买可以重试
+        let standby = WalletMonitor::new(config).with_store(store).unwrap();
+        assert!(standby.copy_buy_on(day, &buy, BALANCE).is_none());
+        assert!(standby.copy_buy_on(day, &failed, BALANCE).is_some());
+    }
+
+    #[test]
+    fn test_copy_buy_on_target_pool() {
+        use crate::constants::pumpswap::accounts::WSOL_TOKEN_ACCOUNT;
+
+        let target = Pubkey::new_unique();
+        let monitor = WalletMonitor::new(WalletMonitorConfig {
+            wallets: HashSet::from([target]),
+            ..Default::default()
+        });
+        let event = PumpSwapBuyEvent {
+            pool: Pubkey::new_unique(),
+            user: target,
+            base_mint: Pubkey::new_unique(),
+            quote_mint: WSOL_TOKEN_ACCOUNT,
+            coin_creator: Pubkey::new_unique(),
+            pool_base_token_reserves: 1_000_000,
+            pool_quote_token_reserves: 50_000_000_000,
+            base_amount_out: 10_000,
+            quote_amount_in: 500_000_000,
+            quote_amount_in_with_lp_fee: 501_000_000,
+            ..Default::default()
+        };
+        let observed = monitor.on_event(&event).unwrap();
+        let order = monitor.copy_buy(&observed, BALANCE).unwrap();
+        assert_eq!((order.dex_type.clone(), order.creator), (DexType::PumpSwap, Some(event.coin_creator)));
+        let params = order.protocol_params().unwrap();
+        let params = params.as_any().downcast_ref::<PumpSwapParams>().unwrap();
+        assert_eq!((params.pool, params.base_mint), (Some(event.pool), Some(event.base_mint)));
+        assert_eq!(params.pool_base_token_reserves, Some(990_000));
+        assert_eq!(params.pool_quote_token_reserves, Some(50_501_000_000));
+
+        // 只经过 observe 的交易没有协议参数
+        let order = monitor.copy_buy(&swap(target), BALANCE).unwrap();
+        assert!(order.protocol_params().is_none());
+    }
+
+    #[test]
+    fn test_stale_signals_are_skipped() {
+        use crate::solana_streamer_sdk::streaming::event_parser::common::EventMetadata;
+
+        let target = Pubkey::new_unique();
+        let monitor = WalletMonitor::new(WalletMonitorConfig {
+            wallets: HashSet::from([target]),
+            max_signal_age_slots: 50,
+            max_signal_age_ms: 1_000,
+            ..Default::default()
+        });
+        let day = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        monitor.observe(SwapObservation { slot: 1_000, ..swap(Pubkey::new_unique()) });
+
+        assert!(monitor.copy_buy_on(day, &SwapObservation { slot: 940, ..swap(target) }, BALANCE).is_none());
+        assert!(monitor.copy_buy_on(day, &SwapObservation { slot: 950, ..swap(target) }, BALANCE).is_some());
+
+        let event = |block_time_ms: i64, received_ms: i64| PumpFunTradeEvent {
+            metadata: EventMetadata {
+                slot: 1_000,
+                block_time_ms,
+                program_received_time_ms: received_ms,
+                ..Default::default()
+            },
+            mint: Pubkey::new_unique(),
+            user: target,
+            is_buy: true,
+            sol_amount: 1_000_000_000,
+            token_amount: 1_000,
+            ..Default::default()
+        };
+        let now = Utc::now().timestamp_millis();
+        let late_shred = monitor.on_event(&event(0, now - 5_000)).unwrap();
+        assert!(monitor.copy_buy(&late_shred, BALANCE).is_none());
+        // 重连后补发的积压事件收到时间是新的，按区块时间判断
+        let backlog = monitor.on_event(&event(now - 5_000, now)).unwrap();
+        assert!(monitor.copy_buy(&backlog, BALANCE).is_none());
+        let fresh = monitor.on_event(&event(now, now)).unwrap();
+        assert!(monitor.copy_buy(&fresh, BALANCE).is_some());
+    }
+
+    #[test]
+    fn test_executing_copy_buys_time_out() {
+        let target = Pubkey::new_unique();
+        let monitor = WalletMonitor::new(WalletMonitorConfig {
+            wallets: HashSet::from([target]),
+            copy_execution_timeout_ms: 1_000,
+            ..Default::default()
+        });
+        let day = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let (landed, stuck) = (swap(target), swap(target));
+
+        let order = monitor.copy_buy_on(day, &landed, BALANCE).unwrap();
+        monitor.complete_copy_buy(&order);
+        let order = monitor.copy_buy_on(day, &stuck, BALANCE).unwrap();
+        assert_eq!(monitor.executing_copy_buys().len(), 1);
+        assert!(monitor.expire_copy_buys().is_empty());
+
+        let expired = monitor.expire_copy_buys_at(Instant::now() + Duration::from_secs(2));
+        assert_eq!(expired.iter().map(|order| order.mint).collect::<Vec<_>>(), vec![order.mint]);
+        assert!(monitor.executing_copy_buys().is_empty());
+        // 超时的跟买归还额度，已成交的保留
+        assert_eq!(monitor.exposure().deployed(&stuck.mint), 0);
+        assert_eq!(monitor.exposure().deployed(&landed.mint), order.sol_amount);
+        // 超时的交易仍可能上链，冷却期内不再跟买同一代币
+        assert!(monitor.copy_buy_on(day, &stuck, BALANCE).is_none());
+        let later = SwapObservation { slot: stuck.slot + 150, ..stuck.clone() };
+        assert!(monitor.copy_buy_on(day, &later, BALANCE).is_some());
+    }
+}
@@ -0,0 +1,111 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::monitor::{PendingEntry, PriceMonitor};
+use crate::trading::TwapOrder;
+use crate::SolanaTrade;
+
+/// An order that has not fully executed yet and can still be cancelled
+#[derive(Debug, Clone, PartialEq)]
+pub enum PendingOrder {
+    /// A TWAP buy or sell with slices left to run
+    Twap(TwapOrder),
+    /// A buy waiting in a [`PriceMonitor`] for the price to dip
+    DipEntry(PendingEntry),
+}
+
+impl PendingOrder {
+    pub fn id(&self) -> PendingOrderId {
+        match self {
+            Self::Twap(order) => PendingOrderId::Twap(order.id),
+            Self::DipEntry(entry) => PendingOrderId::DipEntry(entry.mint),
+        }
+    }
+
+    pub fn mint(&self) -> Pubkey {
+        match self {
+            Self::Twap(order) => order.mint,
+            Self::DipEntry(entry) => entry.mint,
+        }
+    }
+}
+
+/// Identifies a [`PendingOrder`]; prints as `twap:<id>` or `entry:<mint>` and parses back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PendingOrderId {
+    Twap(u64),
+    /// A price monitor keeps at most one pending dip entry per mint
+    DipEntry(Pubkey),
+}
+
+impl fmt::Display for PendingOrderId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Twap(id) => write!(f, "twap:{}", id),
+            Self::DipEntry(mint) => write!(f, "entry:{}", mint),
+        }
+    }
+}
+
+impl FromStr for PendingOrderId {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.split_once(':') {
+            Some(("twap", id)) => Ok(Self::Twap(id.parse()?)),
+            Some(("entry", mint)) => Ok(Self::DipEntry(mint.parse()?)),
+            _ => Err(anyhow!("Invalid order id {}, expected twap:<id> or entry:<mint>", s)),
+        }
+    }
+}
+
+impl SolanaTrade {
+    /// Orders that are waiting to execute: running TWAP orders and, when `price_monitor` is given,
+    /// its pending dip entries
+    ///
+    /// Use it with [`SolanaTrade::cancel_order`] to intervene before the remaining parts execute.
+    pub fn list_pending_orders(&self, price_monitor: Option<&PriceMonitor>) -> Vec<PendingOrder> {
+        let mut orders: Vec<PendingOrder> =
+            self.twap_orders.list().into_iter().map(PendingOrder::Twap).collect();
+        if let Some(price_monitor) = price_monitor {
+            let mut entries = price_monitor.pending_entries();
+            entries.sort_by_key(|entry| entry.deadline);
+            orders.extend(entries.into_iter().map(PendingOrder::DipEntry));
+        }
+        orders
+    }
+
+    /// Cancel a pending order and return it as it was when cancelled
+    ///
+    /// A TWAP order stops before its next slice; a slice already submitting is not recalled.
+    /// A dip entry is dropped without an entry signal. Fails if the order already finished or,
+    /// for a dip entry, no `price_monitor` is given.
+    pub fn cancel_order(&self, id: PendingOrderId, price_monitor: Option<&PriceMonitor>) -> Result<PendingOrder> {
+        let order = match id {
+            PendingOrderId::Twap(order_id) => self.twap_orders.cancel(order_id).map(PendingOrder::Twap),
+            PendingOrderId::DipEntry(mint) => {
+                let price_monitor =
+                    price_monitor.ok_or_else(|| anyhow!("Cancelling {} needs the price monitor", id))?;
+                price_monitor.cancel_entry(&mint).map(PendingOrder::DipEntry)
+            }
+        };
+        order.ok_or_else(|| anyhow!("No pending order {}", id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pending_order_id_round_trip() {
+        for id in [PendingOrderId::Twap(7), PendingOrderId::DipEntry(Pubkey::new_unique())] {
+            assert_eq!(id.to_string().parse::<PendingOrderId>().unwrap(), id);
+        }
+        assert!("twap:x".parse::<PendingOrderId>().is_err());
+        assert!("limit:1".parse::<PendingOrderId>().is_err());
+    }
+}
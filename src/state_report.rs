@@ -0,0 +1,93 @@
+use std::fmt;
+use std::time::Duration;
+
+use chrono::{DateTime, FixedOffset, Utc};
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signer::Signer};
+
+use crate::common::PendingTrade;
+use crate::SolanaTrade;
+
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Snapshot of the in-memory trading state produced by [`SolanaTrade::state_report`]
+#[derive(Debug, Clone)]
+pub struct StateReport {
+    pub generated_at: DateTime<Utc>,
+    /// Time zone the report is printed in, from `TradeConfig::reporting_timezone`
+    pub timezone: FixedOffset,
+    pub payer: Pubkey,
+    /// Lamports reserved against the daily spend limit on the current trading day
+    pub spent_today: u64,
+    /// Fire-and-track trades whose outcome has not been published yet
+    pub pending_trades: Vec<PendingTrade>,
+    /// Idempotency keys still inside their deduplication window
+    pub active_idempotency_keys: Vec<String>,
+}
+
+impl StateReport {
+    /// Whether any trade may have landed without its outcome being observed
+    pub fn needs_attention(&self) -> bool {
+        !self.pending_trades.is_empty()
+    }
+}
+
+impl fmt::Display for StateReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "State report at {} for {}", self.generated_at.with_timezone(&self.timezone).to_rfc3339(), self.payer)?;
+        writeln!(f, "Spent today: {:.9} SOL", self.spent_today as f64 / LAMPORTS_PER_SOL as f64)?;
+        for trade in &self.pending_trades {
+            writeln!(
+                f,
+                "[PENDING] {} {:?} {} amount={} key={} submitted_at={}",
+                trade.dex_type,
+                trade.trade_type,
+                trade.mint,
+                trade.amount,
+                trade.idempotency_key.as_deref().unwrap_or("-"),
+                trade.submitted_at.with_timezone(&self.timezone).to_rfc3339(),
+            )?;
+        }
+        if !self.active_idempotency_keys.is_empty() {
+            writeln!(f, "Active idempotency keys: {}", self.active_idempotency_keys.join(", "))?;
+        }
+        write!(f, "{} pending trades", self.pending_trades.len())
+    }
+}
+
+impl SolanaTrade {
+    /// Snapshot of what is in flight right now
+    ///
+    /// Log it on startup and shutdown so operators can see which trades were submitted
+    /// without a confirmed outcome and need to be checked on chain.
+    pub fn state_report(&self) -> StateReport {
+        let mut active_idempotency_keys = self.idempotency_cache.active_keys();
+        active_idempotency_keys.sort();
+        StateReport {
+            generated_at: Utc::now(),
+            timezone: self.trade_config.reporting_timezone,
+            payer: self.payer.pubkey(),
+            spent_today: self.spend_tracker.spent_today(),
+            pending_trades: self.trade_outcomes.pending(),
+            active_idempotency_keys,
+        }
+    }
+
+    /// Wait up to `timeout` for fire-and-track trades to finish, then report what is left
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let report = solana_trade.shutdown(Duration::from_secs(30)).await;
+    /// println!("{}", report);
+    /// if report.needs_attention() {
+    ///     // check the pending trades on chain before restarting
+    /// }
+    /// ```
+    pub async fn shutdown(&self, timeout: Duration) -> StateReport {
+        let deadline = tokio::time::Instant::now() + timeout;
+        while !self.trade_outcomes.pending().is_empty() && tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(SHUTDOWN_POLL_INTERVAL).await;
+        }
+        self.state_report()
+    }
+}
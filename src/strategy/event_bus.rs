@@ -0,0 +1,122 @@
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::broadcast;
+
+use crate::monitor::{CopyBuyOrder, ExitReason, ExitSignal, MonitoredPosition, SwapObservation};
+use crate::trading::factory::DexType;
+use crate::trading::SubmissionReport;
+
+/// 事件广播通道容量
+const STRATEGY_EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// 策略运行中发生的事件
+#[derive(Debug, Clone)]
+pub enum StrategyEvent {
+    /// 目标钱包的交易，由 `WalletMonitor` 发布
+    TradeDetected(SwapObservation),
+    /// 跟买已提交，由 `SolanaTrade::copy_buy` 发布
+    CopyExecuted { order: CopyBuyOrder, submission: SubmissionReport },
+    /// 开始监控持仓，由 `PriceMonitor` 发布
+    PositionOpened(MonitoredPosition),
+    /// 触发止盈，由 `PriceMonitor` 发布
+    TpTriggered(ExitSignal),
+    /// 触发止损、移动止损或保护性卖出（如开发者抛售、撤池），由 `PriceMonitor` 发布
+    SlTriggered(ExitSignal),
+    /// 协议被 `TradeConfig::disabled_dex_types` 禁止执行，交易在构建或发送前被跳过，由 `SolanaTrade` 发布
+    ExecutionSkipped { dex_type: DexType, mint: Pubkey, reason: String },
+    /// 策略处理事件或执行跟买失败，`source` 为策略名称或出错的操作
+    Error { source: String, message: String },
+}
+
+impl From<ExitSignal> for StrategyEvent {
+    fn from(signal: ExitSignal) -> Self {
+        match signal.reason {
+            ExitReason::TakeProfit => Self::TpTriggered(signal),
+            _ => Self::SlTriggered(signal),
+        }
+    }
+}
+
+/// 策略事件总线：钱包监控、价格监控、策略服务和跟买执行把事件发布到同一通道，
+/// 嵌入 SDK 的应用订阅即可收到，不需要轮询各组件的状态
+pub struct StrategyEventBus {
+    sender: broadcast::Sender<StrategyEvent>,
+}
+
+impl Default for StrategyEventBus {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(STRATEGY_EVENT_CHANNEL_CAPACITY);
+        Self { sender }
+    }
+}
+
+impl StrategyEventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 订阅事件，只能收到订阅之后发布的事件
+    pub fn subscribe(&self) -> broadcast::Receiver<StrategyEvent> {
+        self.sender.subscribe()
+    }
+
+    /// 发布事件，没有订阅者时丢弃
+    pub fn publish(&self, event: impl Into<StrategyEvent>) {
+        let _ = self.sender.send(event.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    use crate::monitor::{
+        PriceMonitor, TakeProfitStopLossConfig, WalletMonitor, WalletMonitorConfig,
+    };
+
+    #[test]
+    fn test_monitors_publish_to_bus() {
+        let bus = Arc::new(StrategyEventBus::new());
+        // 没有订阅者时不报错
+        bus.publish(StrategyEvent::Error { source: "test".to_string(), message: String::new() });
+        let mut events = bus.subscribe();
+
+        let target = Pubkey::new_unique();
+        let wallets = WalletMonitor::new(WalletMonitorConfig {
+            wallets: HashSet::from([target]),
+            ..Default::default()
+        })
+        .with_event_bus(bus.clone());
+        let prices = PriceMonitor::new(TakeProfitStopLossConfig {
+            take_profit_percent: Some(50.0),
+            stop_loss_percent: Some(20.0),
+            ..Default::default()
+        })
+        .with_event_bus(bus.clone());
+
+        let swap = SwapObservation { dex_type: DexType::PumpFun, user: target, ..SwapObservation::for_test() };
+        wallets.observe(SwapObservation { user: Pubkey::new_unique(), ..swap.clone() });
+        wallets.observe(swap.clone());
+        assert!(matches!(events.try_recv().unwrap(), StrategyEvent::TradeDetected(s) if s == swap));
+
+        let (winner, loser) = (Pubkey::new_unique(), Pubkey::new_unique());
+        prices.open_position(winner, 1.0);
+        prices.open_position(loser, 1.0);
+        prices.update_price(&winner, 1.6);
+        prices.update_price(&loser, 0.7);
+        assert!(
+            matches!(events.try_recv().unwrap(), StrategyEvent::PositionOpened(p) if p.mint == winner)
+        );
+        assert!(
+            matches!(events.try_recv().unwrap(), StrategyEvent::PositionOpened(p) if p.mint == loser)
+        );
+        assert!(
+            matches!(events.try_recv().unwrap(), StrategyEvent::TpTriggered(s) if s.position.mint == winner)
+        );
+        assert!(
+            matches!(events.try_recv().unwrap(), StrategyEvent::SlTriggered(s) if s.position.mint == loser)
+        );
+        assert!(events.try_recv().is_err());
+    }
+}
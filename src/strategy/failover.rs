@@ -0,0 +1,363 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+/// 主备切换通知通道容量
+const FAILOVER_CHANNEL_CAPACITY: usize = 16;
+
+/// 等待租约文件锁的次数和间隔
+const LEASE_LOCK_RETRIES: u32 = 20;
+const LEASE_LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(5);
+
+/// 锁文件超过该时间未删除时视为持有者已退出
+const STALE_LEASE_LOCK: Duration = Duration::from_secs(10);
+
+/// 当前持有交易权的实例及其最近一次心跳
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lease {
+    pub holder: String,
+    /// 最近一次心跳的时间（Unix 毫秒）
+    pub heartbeat_ms: i64,
+    /// 每次写入加 1，用于比较并交换
+    #[serde(default)]
+    pub version: u64,
+}
+
+/// 主备实例共享的租约存储，两个实例应指向同一份存储
+pub trait LeaseStore: Send + Sync {
+    fn load_lease(&self) -> Result<Option<Lease>>;
+
+    /// 当前租约与 `expected` 相同时写入 `lease` 并返回 true，否则不写入并返回 false；
+    /// 比较和写入必须是原子的，两个实例同时接管过期租约时只有一个成功
+    fn save_lease(&self, expected: Option<&Lease>, lease: &Lease) -> Result<bool>;
+}
+
+/// 内存存储，用于测试或同一进程内的多个实例
+#[derive(Default)]
+pub struct MemoryLeaseStore {
+    lease: Mutex<Option<Lease>>,
+}
+
+impl LeaseStore for MemoryLeaseStore {
+    fn load_lease(&self) -> Result<Option<Lease>> {
+        Ok(self.lease.lock().unwrap().clone())
+    }
+
+    fn save_lease(&self, expected: Option<&Lease>, lease: &Lease) -> Result<bool> {
+        let mut current = self.lease.lock().unwrap();
+        if current.as_ref() != expected {
+            return Ok(false);
+        }
+        *current = Some(lease.clone());
+        Ok(true)
+    }
+}
+
+/// JSON 文件存储，文件放在两台主机都能访问的共享存储上
+///
+/// 每次读取都从文件读取，先写临时文件再重命名，另一实例不会读到写了一半的文件。
+/// 写入时以独占方式创建 `.lock` 锁文件，在锁内比较并写入
+pub struct JsonFileLeaseStore {
+    path: PathBuf,
+}
+
+impl JsonFileLeaseStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// 创建锁文件，已存在时短暂等待；锁文件过旧时视为持有者已退出，删除后重试
+    fn lock(&self) -> Result<LeaseFileLock> {
+        let lock_path = self.path.with_extension("lock");
+        for _ in 0..LEASE_LOCK_RETRIES {
+            match OpenOptions::new().write(true).create_new(true).open(&lock_path) {
+                Ok(_) => return Ok(LeaseFileLock { path: lock_path }),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    let stale = fs::metadata(&lock_path)
+                        .and_then(|metadata| metadata.modified())
+                        .ok()
+                        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+                        .is_some_and(|age| age > STALE_LEASE_LOCK);
+                    if stale {
+                        let _ = fs::remove_file(&lock_path);
+                    } else {
+                        std::thread::sleep(LEASE_LOCK_RETRY_INTERVAL);
+                    }
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Err(anyhow::anyhow!("租约文件 {} 被占用", lock_path.display()))
+    }
+}
+
+/// 租约文件锁，释放时删除锁文件
+struct LeaseFileLock {
+    path: PathBuf,
+}
+
+impl Drop for LeaseFileLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+impl LeaseStore for JsonFileLeaseStore {
+    fn load_lease(&self) -> Result<Option<Lease>> {
+        match File::open(&self.path) {
+            Ok(file) => Ok(Some(serde_json::from_reader(file)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn save_lease(&self, expected: Option<&Lease>, lease: &Lease) -> Result<bool> {
+        let _lock = self.lock()?;
+        if self.load_lease()?.as_ref() != expected {
+            return Ok(false);
+        }
+        let tmp_path = self.path.with_extension(format!("{}.tmp", std::process::id()));
+        let writer = BufWriter::new(File::create(&tmp_path)?);
+        serde_json::to_writer(writer, lease)?;
+        fs::rename(&tmp_path, &self.path)?;
+        Ok(true)
+    }
+}
+
+/// 主备切换配置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailoverConfig {
+    /// 实例名称，主备实例必须不同
+    pub instance_id: String,
+    /// 心跳间隔（毫秒）
+    pub heartbeat_interval_ms: u64,
+    /// 持有租约的实例超过该毫秒数没有心跳时，备用实例接管，应为心跳间隔的数倍
+    pub takeover_timeout_ms: u64,
+}
+
+impl Default for FailoverConfig {
+    fn default() -> Self {
+        Self {
+            instance_id: String::new(),
+            heartbeat_interval_ms: 1_000,
+            takeover_timeout_ms: 5_000,
+        }
+    }
+}
+
+/// 主备切换：主实例定时写入心跳，备用实例在心跳停止超过 `takeover_timeout_ms` 后接管交易
+///
+/// 同一时间只有持有租约的实例处于活跃状态，`SolanaTrade::with_failover` 后备用实例的买卖直接返回错误。
+/// 持仓、跟买冷却等状态通过两个实例共用的 `PositionStore` 共享，备用实例应在
+/// `wait_until_active` 返回后再从存储加载持仓和创建监控，得到主实例最后写入的状态
+pub struct FailoverCoordinator {
+    config: FailoverConfig,
+    store: Arc<dyn LeaseStore>,
+    active: AtomicBool,
+    /// 本实例最近一次成功写入心跳的时间（Unix 毫秒）
+    last_heartbeat_ms: AtomicI64,
+    sender: broadcast::Sender<bool>,
+}
+
+impl FailoverCoordinator {
+    pub fn new(config: FailoverConfig, store: Arc<dyn LeaseStore>) -> Self {
+        let (sender, _) = broadcast::channel(FAILOVER_CHANNEL_CAPACITY);
+        Self {
+            config,
+            store,
+            active: AtomicBool::new(false),
+            last_heartbeat_ms: AtomicI64::new(0),
+            sender,
+        }
+    }
+
+    /// 当前是否持有租约，只有活跃实例可以交易
+    ///
+    /// 距上次成功写入心跳超过 `takeover_timeout_ms` 时备用实例可能已接管，即使尚未检查租约也视为不活跃
+    pub fn is_active(&self) -> bool {
+        self.is_active_at(Utc::now().timestamp_millis())
+    }
+
+    fn is_active_at(&self, now_ms: i64) -> bool {
+        self.active.load(Ordering::SeqCst)
+            && now_ms - self.last_heartbeat_ms.load(Ordering::SeqCst)
+                <= self.config.takeover_timeout_ms as i64
+    }
+
+    /// 订阅活跃状态的变化，true 为接管交易，false 为转为备用
+    pub fn subscribe(&self) -> broadcast::Receiver<bool> {
+        self.sender.subscribe()
+    }
+
+    /// 检查一次租约：租约为空、属于本实例或已超时时写入心跳并成为活跃实例，否则转为备用，
+    /// 返回检查后是否活跃
+    pub fn heartbeat(&self) -> Result<bool> {
+        self.heartbeat_at(Utc::now().timestamp_millis())
+    }
+
+    fn heartbeat_at(&self, now_ms: i64) -> Result<bool> {
+        let result = self.acquire(now_ms);
+        // 读写租约失败时无法确认仍持有租约，停止交易
+        let active = *result.as_ref().unwrap_or(&false);
+        if active {
+            self.last_heartbeat_ms.store(now_ms, Ordering::SeqCst);
+        }
+        self.set_active(active);
+        result
+    }
+
+    fn acquire(&self, now_ms: i64) -> Result<bool> {
+        let current = self.store.load_lease()?;
+        let acquire = current.as_ref().is_none_or(|lease| {
+            lease.holder == self.config.instance_id
+                || now_ms - lease.heartbeat_ms > self.config.takeover_timeout_ms as i64
+        });
+        if !acquire {
+            return Ok(false);
+        }
+        let lease = Lease {
+            holder: self.config.instance_id.clone(),
+            heartbeat_ms: now_ms,
+            version: current.as_ref().map_or(0, |lease| lease.version) + 1,
+        };
+        // 读取后租约已被另一实例写入时放弃，由下一次心跳重新判断
+        if !self.store.save_lease(current.as_ref(), &lease)? {
+            return Ok(false);
+        }
+        // 写入后再读取一次，确认租约仍属于本实例
+        Ok(self.store.load_lease()?.as_ref() == Some(&lease))
+    }
+
+    fn set_active(&self, active: bool) {
+        if self.active.swap(active, Ordering::SeqCst) != active {
+            // 没有订阅者时发送失败，忽略即可
+            let _ = self.sender.send(active);
+        }
+    }
+
+    /// 启动后台心跳任务，读写租约失败时打印错误并转为备用
+    pub fn spawn(self: &Arc<Self>) -> JoinHandle<()> {
+        let coordinator = self.clone();
+        tokio::spawn(async move {
+            let period = Duration::from_millis(coordinator.config.heartbeat_interval_ms.max(1));
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                if let Err(e) = coordinator.heartbeat() {
+                    println!("警告: 主备租约读写失败: {}", e);
+                }
+            }
+        })
+    }
+
+    /// 等待本实例成为活跃实例，需先调用 `spawn`
+    pub async fn wait_until_active(&self) {
+        let mut receiver = self.sender.subscribe();
+        while !self.is_active() {
+            match receiver.recv().await {
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => return,
+            }
+        }
+    }
+
+    /// 主动释放租约（如计划内停机），应先停止 `spawn` 启动的心跳任务，备用实例在下一次心跳时接管
+    pub fn release(&self) -> Result<()> {
+        self.set_active(false);
+        match self.store.load_lease()? {
+            Some(current) if current.holder == self.config.instance_id => {
+                let lease = Lease { holder: String::new(), heartbeat_ms: 0, version: current.version + 1 };
+                // 租约已被接管时不需要释放
+                self.store.save_lease(Some(&current), &lease).map(|_| ())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coordinator(instance_id: &str, store: &Arc<dyn LeaseStore>) -> FailoverCoordinator {
+        FailoverCoordinator::new(
+            FailoverConfig { instance_id: instance_id.to_string(), ..Default::default() },
+            store.clone(),
+        )
+    }
+
+    #[test]
+    fn test_standby_takes_over_after_heartbeat_stops() {
+        let store: Arc<dyn LeaseStore> = Arc::new(MemoryLeaseStore::default());
+        let (primary, standby) = (coordinator("primary", &store), coordinator("standby", &store));
+        let mut changes = standby.subscribe();
+
+        assert!(primary.heartbeat_at(1_000).unwrap());
+        assert!(!standby.heartbeat_at(1_500).unwrap());
+        assert!(primary.heartbeat_at(3_000).unwrap());
+        // 主实例心跳在 3000 停止，超过 5000 毫秒后备用实例接管
+        assert!(!standby.heartbeat_at(8_000).unwrap());
+        assert!(standby.heartbeat_at(8_001).unwrap());
+        assert!(standby.is_active_at(8_001));
+        assert!(changes.try_recv().unwrap());
+        // 心跳停止后超过接管时间即视为不活跃，不等下一次检查租约
+        assert!(!standby.is_active_at(13_002));
+
+        // 原主实例恢复后发现租约已被接管，转为备用
+        assert!(!primary.heartbeat_at(8_500).unwrap());
+        assert!(!primary.is_active_at(8_500));
+
+        standby.release().unwrap();
+        assert!(!standby.is_active_at(8_600));
+        assert!(primary.heartbeat_at(8_600).unwrap());
+    }
+
+    #[test]
+    fn test_json_file_lease_shared_between_instances() {
+        let path = std::env::temp_dir().join(format!("lease-{}.json", std::process::id()));
+        let store: Arc<dyn LeaseStore> = Arc::new(JsonFileLeaseStore::new(&path));
+        assert!(store.load_lease().unwrap().is_none());
+
+        let primary = coordinator("primary", &store);
+        assert!(primary.heartbeat().unwrap());
+        let other: Arc<dyn LeaseStore> = Arc::new(JsonFileLeaseStore::new(&path));
+        assert_eq!(other.load_lease().unwrap().unwrap().holder, "primary");
+        assert!(!coordinator("standby", &other).heartbeat().unwrap());
+
+        // 比较并交换：以旧租约为预期的写入失败，不覆盖另一实例写入的租约
+        let current = other.load_lease().unwrap().unwrap();
+        let taken = Lease { holder: "standby".to_string(), heartbeat_ms: 0, version: current.version + 1 };
+        assert!(other.save_lease(Some(&current), &taken).unwrap());
+        let stale = Lease { holder: "primary".to_string(), ..taken.clone() };
+        assert!(!store.save_lease(Some(&current), &stale).unwrap());
+        assert_eq!(store.load_lease().unwrap(), Some(taken));
+        assert!(!path.with_extension("lock").exists());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_only_one_instance_takes_expired_lease() {
+        let store: Arc<dyn LeaseStore> = Arc::new(MemoryLeaseStore::default());
+        let (a, b) = (coordinator("a", &store), coordinator("b", &store));
+        assert!(coordinator("old", &store).heartbeat_at(1_000).unwrap());
+
+        // 两个实例读到同一份过期租约，只有先写入的实例接管
+        let expired = store.load_lease().unwrap();
+        let lease = |holder: &str| Lease { holder: holder.to_string(), heartbeat_ms: 7_000, version: 2 };
+        assert!(store.save_lease(expired.as_ref(), &lease("a")).unwrap());
+        assert!(!store.save_lease(expired.as_ref(), &lease("b")).unwrap());
+        assert!(a.heartbeat_at(7_500).unwrap());
+        assert!(!b.heartbeat_at(7_500).unwrap());
+        assert_eq!(store.load_lease().unwrap().unwrap().version, 3);
+    }
+}
@@ -0,0 +1,9 @@
+pub mod event_bus;
+pub mod failover;
+pub mod service;
+pub mod trading_strategy;
+
+pub use event_bus::{StrategyEvent, StrategyEventBus};
+pub use failover::{FailoverConfig, FailoverCoordinator, JsonFileLeaseStore, Lease, LeaseStore, MemoryLeaseStore};
+pub use service::{StrategyState, TradingStrategyService};
+pub use trading_strategy::TradingStrategy;
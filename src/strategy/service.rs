@@ -0,0 +1,277 @@
+use std::sync::{Arc, RwLock};
+
+use anyhow::{anyhow, Result};
+use solana_sdk::pubkey::Pubkey;
+use tokio::task::JoinHandle;
+
+use crate::solana_streamer_sdk::streaming::event_parser::UnifiedEvent;
+use crate::strategy::event_bus::{StrategyEvent, StrategyEventBus};
+use crate::strategy::trading_strategy::TradingStrategy;
+
+/// 策略运行状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrategyState {
+    /// 已注册但未启动，或已停止
+    Stopped,
+    /// 接收事件和价格
+    Running,
+    /// 已启动但暂不接收事件和价格，恢复时不再调用 `start`
+    Paused,
+}
+
+struct RegisteredStrategy {
+    strategy: Arc<dyn TradingStrategy>,
+    state: StrategyState,
+}
+
+/// 策略服务：注册策略、统一管理启动 / 暂停 / 停止，并把流事件和价格分发给运行中的策略
+///
+/// 每个事件并发分发给所有运行中的策略，单个策略出错只打印错误（设置事件总线时同时发布），
+/// 不影响其他策略
+#[derive(Default)]
+pub struct TradingStrategyService {
+    strategies: RwLock<Vec<RegisteredStrategy>>,
+    event_bus: Option<Arc<StrategyEventBus>>,
+}
+
+impl TradingStrategyService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 策略处理事件或价格出错时发布 `StrategyEvent::Error`
+    pub fn with_event_bus(mut self, event_bus: Arc<StrategyEventBus>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    /// 注册策略，初始状态为 `Stopped`，名称重复时返回错误
+    pub fn register(&self, strategy: Arc<dyn TradingStrategy>) -> Result<()> {
+        let mut strategies = self.strategies.write().unwrap();
+        if strategies.iter().any(|registered| registered.strategy.name() == strategy.name()) {
+            return Err(anyhow!("Strategy {} is already registered", strategy.name()));
+        }
+        strategies.push(RegisteredStrategy { strategy, state: StrategyState::Stopped });
+        Ok(())
+    }
+
+    /// 移除已停止的策略
+    pub fn unregister(&self, name: &str) -> Result<Arc<dyn TradingStrategy>> {
+        let mut strategies = self.strategies.write().unwrap();
+        let index = strategies
+            .iter()
+            .position(|registered| registered.strategy.name() == name)
+            .ok_or_else(|| anyhow!("Strategy {} is not registered", name))?;
+        if strategies[index].state != StrategyState::Stopped {
+            return Err(anyhow!("Strategy {} must be stopped before it is unregistered", name));
+        }
+        Ok(strategies.remove(index).strategy)
+    }
+
+    /// 已注册的策略名称和状态
+    pub fn strategies(&self) -> Vec<(String, StrategyState)> {
+        self.strategies
+            .read()
+            .unwrap()
+            .iter()
+            .map(|registered| (registered.strategy.name().to_string(), registered.state))
+            .collect()
+    }
+
+    pub fn state(&self, name: &str) -> Option<StrategyState> {
+        self.strategies
+            .read()
+            .unwrap()
+            .iter()
+            .find(|registered| registered.strategy.name() == name)
+            .map(|registered| registered.state)
+    }
+
+    /// 启动已停止的策略，调用其 `start` 成功后开始接收事件
+    pub async fn start(&self, name: &str) -> Result<()> {
+        let strategy = self.transition(name, StrategyState::Stopped, None)?;
+        strategy.start().await?;
+        self.transition(name, StrategyState::Stopped, Some(StrategyState::Running))?;
+        Ok(())
+    }
+
+    /// 暂停运行中的策略
+    pub fn pause(&self, name: &str) -> Result<()> {
+        self.transition(name, StrategyState::Running, Some(StrategyState::Paused)).map(|_| ())
+    }
+
+    /// 恢复已暂停的策略
+    pub fn resume(&self, name: &str) -> Result<()> {
+        self.transition(name, StrategyState::Paused, Some(StrategyState::Running)).map(|_| ())
+    }
+
+    /// 停止运行中或已暂停的策略，先停止接收事件再调用其 `stop`
+    pub async fn stop(&self, name: &str) -> Result<()> {
+        let strategy = {
+            let mut strategies = self.strategies.write().unwrap();
+            let registered = strategies
+                .iter_mut()
+                .find(|registered| registered.strategy.name() == name)
+                .ok_or_else(|| anyhow!("Strategy {} is not registered", name))?;
+            if registered.state == StrategyState::Stopped {
+                return Err(anyhow!("Strategy {} is not running", name));
+            }
+            registered.state = StrategyState::Stopped;
+            registered.strategy.clone()
+        };
+        strategy.stop().await
+    }
+
+    /// 启动所有已停止的策略，返回启动失败的策略及错误
+    pub async fn start_all(&self) -> Vec<(String, anyhow::Error)> {
+        let mut failures = vec![];
+        for name in self.names_in(StrategyState::Stopped) {
+            if let Err(e) = self.start(&name).await {
+                failures.push((name, e));
+            }
+        }
+        failures
+    }
+
+    /// 停止所有运行中或已暂停的策略，返回停止时出错的策略及错误
+    pub async fn stop_all(&self) -> Vec<(String, anyhow::Error)> {
+        let mut failures = vec![];
+        for (name, state) in self.strategies() {
+            if state == StrategyState::Stopped {
+                continue;
+            }
+            if let Err(e) = self.stop(&name).await {
+                failures.push((name, e));
+            }
+        }
+        failures
+    }
+
+    /// 把流事件分发给所有运行中的策略
+    pub async fn on_event(&self, event: &dyn UnifiedEvent) {
+        let running = self.running();
+        let results = futures::future::join_all(
+            running
+                .iter()
+                .map(|strategy| async move { (strategy.name(), strategy.on_event(event).await) }),
+        )
+        .await;
+        for (name, result) in results {
+            if let Err(e) = result {
+                eprintln!("策略 {} 处理事件 {} 失败: {}", name, event.signature(), e);
+                self.publish_error(name, format!("event {}: {}", event.signature(), e));
+            }
+        }
+    }
+
+    /// 在后台任务中分发流事件，可直接在同步的订阅回调中调用
+    pub fn spawn_event(self: &Arc<Self>, event: Box<dyn UnifiedEvent>) -> JoinHandle<()> {
+        let service = self.clone();
+        tokio::spawn(async move { service.on_event(event.as_ref()).await })
+    }
+
+    /// 把代币价格分发给所有运行中的策略
+    pub async fn on_price(&self, mint: &Pubkey, price: f64) {
+        let running = self.running();
+        let results = futures::future::join_all(running.iter().map(|strategy| async move {
+            (strategy.name(), strategy.on_price(mint, price).await)
+        }))
+        .await;
+        for (name, result) in results {
+            if let Err(e) = result {
+                eprintln!("策略 {} 处理 {} 的价格失败: {}", name, mint, e);
+                self.publish_error(name, format!("price of {}: {}", mint, e));
+            }
+        }
+    }
+
+    fn publish_error(&self, name: &str, message: String) {
+        if let Some(event_bus) = &self.event_bus {
+            event_bus.publish(StrategyEvent::Error { source: name.to_string(), message });
+        }
+    }
+
+    fn running(&self) -> Vec<Arc<dyn TradingStrategy>> {
+        self.strategies
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|registered| registered.state == StrategyState::Running)
+            .map(|registered| registered.strategy.clone())
+            .collect()
+    }
+
+    fn names_in(&self, state: StrategyState) -> Vec<String> {
+        self.strategies()
+            .into_iter()
+            .filter(|(_, current)| *current == state)
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// 检查策略处于 `from` 状态，`to` 不为 None 时切换状态
+    fn transition(
+        &self,
+        name: &str,
+        from: StrategyState,
+        to: Option<StrategyState>,
+    ) -> Result<Arc<dyn TradingStrategy>> {
+        let mut strategies = self.strategies.write().unwrap();
+        let registered = strategies
+            .iter_mut()
+            .find(|registered| registered.strategy.name() == name)
+            .ok_or_else(|| anyhow!("Strategy {} is not registered", name))?;
+        if registered.state != from {
+            return Err(anyhow!(
+                "Strategy {} is {:?}, expected {:?}",
+                name,
+                registered.state,
+                from
+            ));
+        }
+        if let Some(to) = to {
+            registered.state = to;
+        }
+        Ok(registered.strategy.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitor::{PriceMonitor, TakeProfitStopLossConfig};
+
+    #[tokio::test]
+    async fn test_strategy_lifecycle_and_routing() {
+        let service = TradingStrategyService::new();
+        let monitor = Arc::new(PriceMonitor::new(TakeProfitStopLossConfig {
+            take_profit_percent: Some(100.0),
+            ..Default::default()
+        }));
+        let mut signals = monitor.subscribe();
+        let mint = Pubkey::new_unique();
+        monitor.open_position(mint, 1.0);
+
+        service.register(monitor.clone()).unwrap();
+        assert!(service.register(monitor.clone()).is_err());
+        assert_eq!(service.state("price_monitor"), Some(StrategyState::Stopped));
+
+        // 未启动和暂停时不接收价格
+        service.on_price(&mint, 1.5).await;
+        assert_eq!(monitor.position(&mint).unwrap().last_price, 1.0);
+        service.start("price_monitor").await.unwrap();
+        assert!(service.start("price_monitor").await.is_err());
+        service.pause("price_monitor").unwrap();
+        service.on_price(&mint, 1.5).await;
+        assert_eq!(monitor.position(&mint).unwrap().last_price, 1.0);
+
+        service.resume("price_monitor").unwrap();
+        service.on_price(&mint, 2.0).await;
+        assert!(signals.try_recv().is_ok());
+
+        assert!(service.unregister("price_monitor").is_err());
+        assert!(service.stop_all().await.is_empty());
+        assert!(service.unregister("price_monitor").is_ok());
+        assert!(service.strategies().is_empty());
+    }
+}
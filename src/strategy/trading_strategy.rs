@@ -0,0 +1,153 @@
+use anyhow::Result;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::monitor::{
+    DevSellMonitor, EmaCrossStrategy, LargeSwapDetector, LiquidityGuard, PnlTracker,
+    PoolEventMonitor, PriceMonitor, SniperStrategy, WalletMonitor,
+};
+use crate::solana_streamer_sdk::streaming::event_parser::UnifiedEvent;
+
+/// 交易策略，由 `TradingStrategyService` 统一管理生命周期并分发事件和价格
+///
+/// 除 `name` 和 `on_event` 外都有默认实现
+#[async_trait::async_trait]
+pub trait TradingStrategy: Send + Sync {
+    /// 策略名称，在同一个服务中唯一
+    fn name(&self) -> &str;
+
+    /// 启动时调用，可在此建立连接或加载状态
+    async fn start(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// 停止时调用，可在此释放资源或保存状态
+    async fn stop(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// 处理解析后的流事件
+    async fn on_event(&self, event: &dyn UnifiedEvent) -> Result<()>;
+
+    /// 处理代币的最新价格（lamports / 代币最小单位）
+    async fn on_price(&self, _mint: &Pubkey, _price: f64) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl TradingStrategy for PriceMonitor {
+    fn name(&self) -> &str {
+        "price_monitor"
+    }
+
+    async fn on_event(&self, event: &dyn UnifiedEvent) -> Result<()> {
+        PriceMonitor::on_event(self, event);
+        Ok(())
+    }
+
+    async fn on_price(&self, mint: &Pubkey, price: f64) -> Result<()> {
+        self.update_price(mint, price);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl TradingStrategy for SniperStrategy {
+    fn name(&self) -> &str {
+        "sniper"
+    }
+
+    async fn on_event(&self, event: &dyn UnifiedEvent) -> Result<()> {
+        SniperStrategy::on_event(self, event);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl TradingStrategy for WalletMonitor {
+    fn name(&self) -> &str {
+        "wallet_monitor"
+    }
+
+    async fn on_event(&self, event: &dyn UnifiedEvent) -> Result<()> {
+        WalletMonitor::on_event(self, event);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl TradingStrategy for LargeSwapDetector {
+    fn name(&self) -> &str {
+        "large_swap"
+    }
+
+    async fn on_event(&self, event: &dyn UnifiedEvent) -> Result<()> {
+        LargeSwapDetector::on_event(self, event);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl TradingStrategy for PoolEventMonitor {
+    fn name(&self) -> &str {
+        "pool_events"
+    }
+
+    async fn on_event(&self, event: &dyn UnifiedEvent) -> Result<()> {
+        PoolEventMonitor::on_event(self, event);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl TradingStrategy for PnlTracker {
+    fn name(&self) -> &str {
+        "pnl"
+    }
+
+    async fn on_event(&self, event: &dyn UnifiedEvent) -> Result<()> {
+        PnlTracker::on_event(self, event);
+        Ok(())
+    }
+
+    async fn on_price(&self, mint: &Pubkey, price: f64) -> Result<()> {
+        self.update_price(mint, price);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl TradingStrategy for DevSellMonitor {
+    fn name(&self) -> &str {
+        "dev_sell"
+    }
+
+    async fn on_event(&self, event: &dyn UnifiedEvent) -> Result<()> {
+        DevSellMonitor::on_event(self, event);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl TradingStrategy for LiquidityGuard {
+    fn name(&self) -> &str {
+        "liquidity_guard"
+    }
+
+    async fn on_event(&self, event: &dyn UnifiedEvent) -> Result<()> {
+        LiquidityGuard::on_event(self, event);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl TradingStrategy for EmaCrossStrategy {
+    fn name(&self) -> &str {
+        "ema_cross"
+    }
+
+    async fn on_event(&self, event: &dyn UnifiedEvent) -> Result<()> {
+        EmaCrossStrategy::on_event(self, event);
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use crate::swqos::metrics::{send_and_record, SwqosMetrics};
+use crate::swqos::common::{poll_transaction_confirmation, serialize_transaction_and_encode, ping_endpoint};
+use reqwest::Client;
+use serde_json::json;
+use std::{sync::Arc, time::Instant};
+
+use std::time::Duration;
+use solana_transaction_status::UiTransactionEncoding;
+
+use anyhow::Result;
+use solana_sdk::transaction::VersionedTransaction;
+use crate::swqos::{SwqosType, TradeType};
+use crate::swqos::SwqosClientTrait;
+
+use crate::{common::{tip_cache::TipCache, SolanaRpcClient}, constants::swqos::BLOCKRAZOR_TIP_ACCOUNTS};
+
+#[derive(Clone)]
+pub struct BlockRazorClient {
+    pub endpoint: String,
+    pub auth_token: String,
+    pub rpc_client: Arc<SolanaRpcClient>,
+    pub http_client: Client,
+}
+
+#[async_trait::async_trait]
+impl SwqosClientTrait for BlockRazorClient {
+    async fn send_transaction(&self, trade_type: TradeType, transaction: &VersionedTransaction) -> Result<()> {
+        self.send_transaction(trade_type, transaction).await
+    }
+
+    async fn send_transactions(&self, trade_type: TradeType, transactions: &Vec<VersionedTransaction>) -> Result<()> {
+        self.send_transactions(trade_type, transactions).await
+    }
+
+    fn get_tip_account(&self) -> Result<String> {
+        let tip_account = TipCache::get_instance()
+            .random_tip_account(&SwqosType::BlockRazor, BLOCKRAZOR_TIP_ACCOUNTS)
+            .ok_or_else(|| anyhow::anyhow!("no valid tip accounts found"))?;
+        Ok(tip_account.to_string())
+    }
+
+    fn get_swqos_type(&self) -> SwqosType {
+        SwqosType::BlockRazor
+    }
+
+    async fn ping(&self) -> Result<()> {
+        let url = format!("{}/health", self.endpoint);
+        ping_endpoint(self.http_client.get(&url).header("apikey", &self.auth_token)).await
+    }
+}
+
+impl BlockRazorClient {
+    pub fn new(rpc_url: String, endpoint: String, auth_token: String) -> Self {
+        let rpc_client = SolanaRpcClient::new(rpc_url);
+        let http_client = Client::builder()
+            .pool_idle_timeout(Duration::from_secs(60))
+            .pool_max_idle_per_host(64)
+            .tcp_keepalive(Some(Duration::from_secs(1200)))
+            .http2_keep_alive_interval(Duration::from_secs(15))
+            .timeout(Duration::from_secs(10))
+            .connect_timeout(Duration::from_secs(5))
+            .build()
+            .unwrap();
+        Self { rpc_client: Arc::new(rpc_client), endpoint, auth_token, http_client }
+    }
+
+    pub async fn send_transaction(&self, trade_type: TradeType, transaction: &VersionedTransaction) -> Result<()> {
+        let start_time = Instant::now();
+        let (content, signature) = serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64).await?;
+        println!(" 交易编码base64: {:?}", start_time.elapsed());
+
+        let request_body = serde_json::to_string(&json!({
+            "transaction": content,
+            "mode": "fast"
+        }))?;
+
+        let url = format!("{}/sendTransaction", self.endpoint);
+        let request = self.http_client.post(&url)
+            .body(request_body)
+            .header("apikey", &self.auth_token)
+            .header("Content-Type", "application/json");
+        let response_text = send_and_record(SwqosType::BlockRazor, request).await?;
+
+        if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
+            if let Some(error) = response_json.get("error") {
+                return Err(anyhow::anyhow!("blockrazor{}提交失败: {}", trade_type, error));
+            } else {
+                println!(" blockrazor{}提交: {:?}", trade_type, start_time.elapsed());
+            }
+        }
+
+        let start_time: Instant = Instant::now();
+        let landed = poll_transaction_confirmation(&self.rpc_client, signature).await.is_ok();
+        SwqosMetrics::get_instance().record_landing(SwqosType::BlockRazor, landed);
+
+        println!(" blockrazor{}确认: {:?}", trade_type, start_time.elapsed());
+
+        Ok(())
+    }
+
+    pub async fn send_transactions(&self, trade_type: TradeType, transactions: &Vec<VersionedTransaction>) -> Result<()> {
+        for transaction in transactions {
+            self.send_transaction(trade_type, transaction).await?;
+        }
+        Ok(())
+    }
+}
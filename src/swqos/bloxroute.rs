@@ -1,5 +1,5 @@
-use crate::swqos::common::{poll_transaction_confirmation, serialize_transaction_and_encode, FormatBase64VersionedTransaction};
-use rand::seq::IndexedRandom;
+use crate::swqos::metrics::{send_and_record, SwqosMetrics};
+use crate::swqos::common::{poll_transaction_confirmation, serialize_transaction_and_encode, ping_endpoint, FormatBase64VersionedTransaction};
 use reqwest::Client;
 use std::{sync::Arc, time::Instant};
 
@@ -11,7 +11,7 @@ use solana_sdk::transaction::VersionedTransaction;
 use crate::swqos::{SwqosType, TradeType};
 use crate::swqos::SwqosClientTrait;
 
-use crate::{common::SolanaRpcClient, constants::swqos::BLOX_TIP_ACCOUNTS};
+use crate::{common::{tip_cache::TipCache, SolanaRpcClient}, constants::swqos::BLOX_TIP_ACCOUNTS};
 
 
 #[derive(Clone)]
@@ -33,13 +33,20 @@ impl SwqosClientTrait for BloxrouteClient {
     }
 
     fn get_tip_account(&self) -> Result<String> {
-        let tip_account = *BLOX_TIP_ACCOUNTS.choose(&mut rand::rng()).or_else(|| BLOX_TIP_ACCOUNTS.first()).unwrap();
+        let tip_account = TipCache::get_instance()
+            .random_tip_account(&SwqosType::Bloxroute, BLOX_TIP_ACCOUNTS)
+            .ok_or_else(|| anyhow::anyhow!("no valid tip accounts found"))?;
         Ok(tip_account.to_string())
     }
 
     fn get_swqos_type(&self) -> SwqosType {
         SwqosType::Bloxroute
     }
+
+    async fn ping(&self) -> Result<()> {
+        let endpoint = format!("{}/api/v2/submit", self.endpoint);
+        ping_endpoint(self.http_client.post(&endpoint).header("Authorization", self.auth_token.clone()).body("{}")).await
+    }
 }
 
 impl BloxrouteClient {
@@ -71,14 +78,11 @@ impl BloxrouteClient {
         });
 
         let endpoint = format!("{}/api/v2/submit", self.endpoint);
-        let response_text = self.http_client.post(&endpoint)
+        let request = self.http_client.post(&endpoint)
             .body(body.to_string())
             .header("Content-Type", "application/json")
-            .header("Authorization", self.auth_token.clone())
-            .send()
-            .await?
-            .text()
-            .await?;
+            .header("Authorization", self.auth_token.clone());
+        let response_text = send_and_record(SwqosType::Bloxroute, request).await?;
 
         // 5. 用 `serde_json::from_str()` 解析 JSON，减少 `.json().await?` 额外等待
         if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
@@ -90,10 +94,8 @@ impl BloxrouteClient {
         }
 
         let start_time: Instant = Instant::now();
-        match poll_transaction_confirmation(&self.rpc_client, signature).await {
-            Ok(_) => (),
-            Err(_) => (),
-        }
+        let landed = poll_transaction_confirmation(&self.rpc_client, signature).await.is_ok();
+        SwqosMetrics::get_instance().record_landing(SwqosType::Bloxroute, landed);
 
         println!(" bloxroute{}确认: {:?}", trade_type, start_time.elapsed());
 
@@ -97,6 +97,24 @@ pub async fn send_nb_transaction(client: Client, endpoint: &str, auth_token: &st
     Ok(signature)
 }
 
+/// 发送不含交易的探测请求，检查端点可达且鉴权通过
+///
+/// 401 / 403 视为鉴权失败，5xx 视为服务不可用，其余响应（包括请求体无效）均视为通过
+pub async fn ping_endpoint(request: reqwest::RequestBuilder) -> Result<()> {
+    let status = request
+        .header("Content-Type", "application/json")
+        .send()
+        .await?
+        .status();
+    if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+        return Err(anyhow::anyhow!("鉴权失败: {}", status));
+    }
+    if status.is_server_error() {
+        return Err(anyhow::anyhow!("服务不可用: {}", status));
+    }
+    Ok(())
+}
+
 pub async fn serialize_and_encode(
     transaction: &Vec<u8>,
     encoding: UiTransactionEncoding,
@@ -0,0 +1,222 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+use crate::common::PriorityFee;
+use crate::swqos::{SwqosClient, SwqosType};
+
+/// 状态变化广播通道容量
+const HEALTH_EVENT_CHANNEL_CAPACITY: usize = 64;
+
+/// SWQOS 健康检查配置
+#[derive(Debug, Clone)]
+pub struct SwqosHealthConfig {
+    /// 探测间隔（毫秒）
+    pub interval_ms: u64,
+    /// 连续探测失败多少次后隔离该通道
+    pub failure_threshold: u32,
+}
+
+impl Default for SwqosHealthConfig {
+    fn default() -> Self {
+        Self { interval_ms: 10_000, failure_threshold: 3 }
+    }
+}
+
+/// SWQOS 通道健康状态变化
+#[derive(Debug, Clone, PartialEq)]
+pub enum SwqosHealthEvent {
+    /// 连续探测失败，提交时跳过该通道
+    Quarantined { index: usize, swqos_type: SwqosType, error: String },
+    /// 探测恢复成功，重新参与提交
+    Recovered { index: usize, swqos_type: SwqosType },
+}
+
+struct EndpointHealth {
+    swqos_type: SwqosType,
+    consecutive_failures: AtomicU32,
+    quarantined: AtomicBool,
+}
+
+/// 定时 ping 每个 SWQOS 通道，连续失败的通道在提交时被跳过
+///
+/// 通道按 `swqos_configs` 中的位置编号，与 `buy_tip_fees` 一一对应
+pub struct SwqosHealthMonitor {
+    endpoints: Vec<EndpointHealth>,
+    failure_threshold: AtomicU32,
+    sender: broadcast::Sender<SwqosHealthEvent>,
+}
+
+impl SwqosHealthMonitor {
+    pub fn new(swqos_clients: &[Arc<SwqosClient>]) -> Self {
+        let (sender, _) = broadcast::channel(HEALTH_EVENT_CHANNEL_CAPACITY);
+        Self {
+            endpoints: swqos_clients
+                .iter()
+                .map(|swqos_client| EndpointHealth {
+                    swqos_type: swqos_client.get_swqos_type(),
+                    consecutive_failures: AtomicU32::new(0),
+                    quarantined: AtomicBool::new(false),
+                })
+                .collect(),
+            failure_threshold: AtomicU32::new(SwqosHealthConfig::default().failure_threshold),
+            sender,
+        }
+    }
+
+    /// 订阅通道隔离 / 恢复事件，只能收到订阅之后的变化
+    pub fn subscribe(&self) -> broadcast::Receiver<SwqosHealthEvent> {
+        self.sender.subscribe()
+    }
+
+    /// 第 `index` 个通道当前是否参与提交
+    pub fn is_healthy(&self, index: usize) -> bool {
+        self.endpoints.get(index).is_none_or(|endpoint| !endpoint.quarantined.load(Ordering::Relaxed))
+    }
+
+    /// 被隔离的通道编号
+    pub fn quarantined(&self) -> Vec<usize> {
+        (0..self.endpoints.len()).filter(|index| !self.is_healthy(*index)).collect()
+    }
+
+    /// 记录一次探测结果，状态发生变化时打印日志、广播并返回事件
+    pub fn record(&self, index: usize, result: &Result<()>) -> Option<SwqosHealthEvent> {
+        let endpoint = self.endpoints.get(index)?;
+        let event = match result {
+            Ok(()) => {
+                endpoint.consecutive_failures.store(0, Ordering::Relaxed);
+                endpoint.quarantined.swap(false, Ordering::Relaxed).then(|| SwqosHealthEvent::Recovered {
+                    index,
+                    swqos_type: endpoint.swqos_type.clone(),
+                })
+            }
+            Err(e) => {
+                let failures = endpoint.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+                let threshold = self.failure_threshold.load(Ordering::Relaxed).max(1);
+                (failures >= threshold && !endpoint.quarantined.swap(true, Ordering::Relaxed)).then(|| {
+                    SwqosHealthEvent::Quarantined {
+                        index,
+                        swqos_type: endpoint.swqos_type.clone(),
+                        error: e.to_string(),
+                    }
+                })
+            }
+        }?;
+        match &event {
+            SwqosHealthEvent::Quarantined { swqos_type, error, .. } => {
+                println!("SWQOS {:?} 连续探测失败，已隔离: {}", swqos_type, error)
+            }
+            SwqosHealthEvent::Recovered { swqos_type, .. } => println!("SWQOS {:?} 已恢复", swqos_type),
+        }
+        let _ = self.sender.send(event.clone());
+        Some(event)
+    }
+
+    /// 并发 ping 所有通道并记录结果
+    pub async fn probe(&self, swqos_clients: &[Arc<SwqosClient>]) {
+        let results =
+            futures::future::join_all(swqos_clients.iter().map(|swqos_client| swqos_client.ping())).await;
+        for (index, result) in results.iter().enumerate() {
+            self.record(index, result);
+        }
+    }
+
+    /// 启动后台探测任务
+    pub fn start(self: &Arc<Self>, swqos_clients: Vec<Arc<SwqosClient>>, config: SwqosHealthConfig) -> JoinHandle<()> {
+        self.failure_threshold.store(config.failure_threshold, Ordering::Relaxed);
+        let monitor = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_millis(config.interval_ms.max(1_000)));
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                monitor.probe(&swqos_clients).await;
+            }
+        })
+    }
+
+    /// 去掉被隔离的通道，`buy_tip_fees` 同步过滤以保持与通道对应
+    ///
+    /// 所有通道都被隔离时不做过滤，仍然全部提交
+    pub fn select(
+        &self,
+        swqos_clients: &[Arc<SwqosClient>],
+        priority_fee: &mut PriorityFee,
+    ) -> Vec<Arc<SwqosClient>> {
+        let healthy: Vec<usize> = (0..swqos_clients.len()).filter(|index| self.is_healthy(*index)).collect();
+        if healthy.is_empty() || healthy.len() == swqos_clients.len() {
+            return swqos_clients.to_vec();
+        }
+        if !priority_fee.buy_tip_fees.is_empty() {
+            priority_fee.buy_tip_fees = healthy
+                .iter()
+                .map(|index| priority_fee.buy_tip_fees.get(*index).copied().unwrap_or(priority_fee.buy_tip_fee))
+                .collect();
+        }
+        healthy.iter().map(|index| swqos_clients[*index].clone()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    use crate::common::SolanaRpcClient;
+    use crate::swqos::solana_rpc::SolRpcClient;
+
+    fn clients(count: usize) -> Vec<Arc<SwqosClient>> {
+        let rpc = Arc::new(SolanaRpcClient::new("http://127.0.0.1:8899".to_string()));
+        (0..count).map(|_| Arc::new(SolRpcClient::new(rpc.clone())) as Arc<SwqosClient>).collect()
+    }
+
+    #[test]
+    fn test_quarantine_and_recover() {
+        let monitor = SwqosHealthMonitor::new(&clients(2));
+        let mut events = monitor.subscribe();
+        let failure: Result<()> = Err(anyhow!("timeout"));
+
+        assert!(monitor.record(0, &failure).is_none());
+        assert!(monitor.record(0, &failure).is_none());
+        assert!(matches!(monitor.record(0, &failure), Some(SwqosHealthEvent::Quarantined { index: 0, .. })));
+        assert!(monitor.record(0, &failure).is_none());
+        assert_eq!(monitor.quarantined(), vec![0]);
+        assert!(matches!(events.try_recv(), Ok(SwqosHealthEvent::Quarantined { index: 0, .. })));
+
+        assert_eq!(
+            monitor.record(0, &Ok(())),
+            Some(SwqosHealthEvent::Recovered { index: 0, swqos_type: SwqosType::Default })
+        );
+        assert!(monitor.is_healthy(0));
+        assert!(monitor.record(1, &Ok(())).is_none());
+    }
+
+    #[test]
+    fn test_select_skips_quarantined() {
+        let swqos_clients = clients(3);
+        let monitor = SwqosHealthMonitor::new(&swqos_clients);
+        let failure: Result<()> = Err(anyhow!("timeout"));
+        for _ in 0..3 {
+            monitor.record(1, &failure);
+        }
+
+        let mut priority_fee = PriorityFee { buy_tip_fees: vec![0.1, 0.2, 0.3], ..Default::default() };
+        let selected = monitor.select(&swqos_clients, &mut priority_fee);
+        assert_eq!(selected.len(), 2);
+        assert_eq!(priority_fee.buy_tip_fees, vec![0.1, 0.3]);
+
+        // 全部隔离时仍然全部提交
+        for index in [0, 2] {
+            for _ in 0..3 {
+                monitor.record(index, &failure);
+            }
+        }
+        let mut priority_fee = PriorityFee { buy_tip_fees: vec![0.1, 0.2, 0.3], ..Default::default() };
+        assert_eq!(monitor.select(&swqos_clients, &mut priority_fee).len(), 3);
+        assert_eq!(priority_fee.buy_tip_fees.len(), 3);
+    }
+}
@@ -1,20 +1,49 @@
 
+use crate::swqos::metrics::{send_and_record, SwqosMetrics};
 use crate::swqos::common::{poll_transaction_confirmation, serialize_transaction_and_encode, FormatBase64VersionedTransaction};
-use rand::seq::IndexedRandom;
 use reqwest::Client;
 use serde_json::json;
-use std::{sync::Arc, time::Instant};
+use std::{str::FromStr, sync::Arc, time::Instant};
 
 use std::time::Duration;
 use solana_transaction_status::UiTransactionEncoding;
 
-use anyhow::Result;
-use solana_sdk::transaction::VersionedTransaction;
+use anyhow::{anyhow, Result};
+use solana_sdk::{pubkey::Pubkey, transaction::VersionedTransaction};
 use crate::swqos::{SwqosType, TradeType};
 use crate::swqos::SwqosClientTrait;
 
-use crate::{common::SolanaRpcClient, constants::swqos::JITO_TIP_ACCOUNTS};
+use crate::{common::{tip_cache::TipCache, SolanaRpcClient}, constants::swqos::JITO_TIP_ACCOUNTS};
 
+/// 单个 bundle 最多包含的交易数
+pub const MAX_BUNDLE_TRANSACTIONS: usize = 5;
+
+/// Jito bundle 状态
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JitoBundleStatus {
+    /// 已上链
+    Landed { slot: u64 },
+    /// 等待处理中
+    Pending,
+    /// 执行失败
+    Failed,
+    /// bundle 不存在或已超出查询窗口
+    Invalid,
+}
+
+impl JitoBundleStatus {
+    /// 是否为最终状态
+    pub fn is_final(&self) -> bool {
+        !matches!(self, JitoBundleStatus::Pending)
+    }
+}
+
+/// 随机选择一个 Jito 小费账户，优先使用 TipCache 中刷新得到的列表
+pub fn random_tip_account() -> Result<Pubkey> {
+    TipCache::get_instance()
+        .random_tip_account(&SwqosType::Jito, JITO_TIP_ACCOUNTS)
+        .ok_or_else(|| anyhow!("no valid tip accounts found"))
+}
 
 pub struct JitoClient {
     pub endpoint: String,
@@ -34,16 +63,27 @@ impl SwqosClientTrait for JitoClient {
     }
 
     fn get_tip_account(&self) -> Result<String> {
-        if let Some(acc) = JITO_TIP_ACCOUNTS.choose(&mut rand::rng()) {
-            Ok(acc.to_string())
-        } else {
-            Err(anyhow::anyhow!("no valid tip accounts found"))
-        }
+        Ok(random_tip_account()?.to_string())
     }
 
     fn get_swqos_type(&self) -> SwqosType {
         SwqosType::Jito
     }
+
+    async fn ping(&self) -> Result<()> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": "getTipAccounts", "params": [] });
+        self.post_bundle_api("bundles", body).await.map(|_| ())
+    }
+
+    async fn fetch_tip_accounts(&self) -> Result<Vec<Pubkey>> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": "getTipAccounts", "params": [] });
+        let result = self.post_bundle_api("bundles", body).await?;
+        let tip_accounts: Vec<String> = serde_json::from_value(result)?;
+        tip_accounts
+            .iter()
+            .map(|tip_account| Pubkey::from_str(tip_account).map_err(|e| anyhow!(e)))
+            .collect()
+    }
 }
 
 impl JitoClient {
@@ -89,13 +129,10 @@ impl JitoClient {
             self.http_client.post(&endpoint)
                 .header("x-jito-auth", &self.auth_token)
         };
-        let response_text = response
+        let request = response
             .body(request_body)
-            .header("Content-Type", "application/json")
-            .send()
-            .await?
-            .text()
-            .await?;
+            .header("Content-Type", "application/json");
+        let response_text = send_and_record(SwqosType::Jito, request).await?;
 
         if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
             if response_json.get("result").is_some() {
@@ -106,10 +143,8 @@ impl JitoClient {
         }
 
         let start_time: Instant = Instant::now();
-        match poll_transaction_confirmation(&self.rpc_client, signature).await {
-            Ok(_) => (),
-            Err(_) => (),
-        }
+        let landed = poll_transaction_confirmation(&self.rpc_client, signature).await.is_ok();
+        SwqosMetrics::get_instance().record_landing(SwqosType::Jito, landed);
 
         println!(" jito{}确认: {:?}", trade_type, start_time.elapsed());
 
@@ -158,4 +193,139 @@ impl JitoClient {
 
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// 提交 bundle，返回 bundle id
+    ///
+    /// bundle 中的交易按顺序原子执行，只需其中一笔交易包含小费
+    pub async fn send_bundle(&self, transactions: &[VersionedTransaction]) -> Result<String> {
+        if transactions.is_empty() || transactions.len() > MAX_BUNDLE_TRANSACTIONS {
+            return Err(anyhow!(
+                "Bundle must contain 1 to {} transactions, got {}",
+                MAX_BUNDLE_TRANSACTIONS,
+                transactions.len()
+            ));
+        }
+        let txs_base64 = transactions.iter().map(|tx| tx.to_base64_string()).collect::<Vec<String>>();
+        let body = json!({
+            "jsonrpc": "2.0",
+            "method": "sendBundle",
+            "params": [
+                txs_base64,
+                { "encoding": "base64" }
+            ],
+            "id": 1,
+        });
+        let result = self.post_bundle_api("bundles", body).await?;
+        result
+            .as_str()
+            .map(|bundle_id| bundle_id.to_string())
+            .ok_or_else(|| anyhow!("Invalid sendBundle result: {}", result))
+    }
+
+    /// 查询 bundle 状态（getInflightBundleStatuses，仅覆盖最近 5 分钟）
+    pub async fn get_bundle_status(&self, bundle_id: &str) -> Result<JitoBundleStatus> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "method": "getInflightBundleStatuses",
+            "params": [[bundle_id]],
+            "id": 1,
+        });
+        let result = self.post_bundle_api("getInflightBundleStatuses", body).await?;
+        parse_bundle_status(&result)
+    }
+
+    /// 轮询 bundle 状态直到最终状态或超时
+    pub async fn poll_bundle_status(&self, bundle_id: &str, timeout: Duration) -> Result<JitoBundleStatus> {
+        let interval = Duration::from_millis(500);
+        let start = Instant::now();
+        loop {
+            let status = self.get_bundle_status(bundle_id).await?;
+            // 刚提交的 bundle 可能尚未被索引，先按 Pending 处理
+            let status = if status == JitoBundleStatus::Invalid && start.elapsed() < Duration::from_secs(2) {
+                JitoBundleStatus::Pending
+            } else {
+                status
+            };
+            if status.is_final() {
+                return Ok(status);
+            }
+            if start.elapsed() >= timeout {
+                return Err(anyhow!("Bundle {}'s status polling timed out", bundle_id));
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    async fn post_bundle_api(&self, path: &str, body: serde_json::Value) -> Result<serde_json::Value> {
+        let endpoint = if self.auth_token.is_empty() {
+            format!("{}/api/v1/{}", self.endpoint, path)
+        } else {
+            format!("{}/api/v1/{}?uuid={}", self.endpoint, path, self.auth_token)
+        };
+        let request = if self.auth_token.is_empty() {
+            self.http_client.post(&endpoint)
+        } else {
+            self.http_client.post(&endpoint)
+                .header("x-jito-auth", &self.auth_token)
+        };
+        let response_json: serde_json::Value = request
+            .body(body.to_string())
+            .header("Content-Type", "application/json")
+            .send()
+            .await?
+            .json()
+            .await?;
+        if let Some(error) = response_json.get("error") {
+            return Err(anyhow!("Jito {} failed: {}", path, error));
+        }
+        response_json
+            .get("result")
+            .cloned()
+            .ok_or_else(|| anyhow!("Invalid Jito response: {}", response_json))
+    }
+}
+
+/// 解析 getInflightBundleStatuses 的 result，查询窗口内找不到 bundle 时为 Invalid
+fn parse_bundle_status(result: &serde_json::Value) -> Result<JitoBundleStatus> {
+    let status = result
+        .get("value")
+        .and_then(|value| value.as_array())
+        .and_then(|value| value.first());
+    let Some(status) = status else {
+        return Ok(JitoBundleStatus::Invalid);
+    };
+    match status.get("status").and_then(|s| s.as_str()) {
+        Some("Landed") => Ok(JitoBundleStatus::Landed {
+            slot: status.get("landed_slot").and_then(|s| s.as_u64()).unwrap_or_default(),
+        }),
+        Some("Pending") => Ok(JitoBundleStatus::Pending),
+        Some("Failed") => Ok(JitoBundleStatus::Failed),
+        Some("Invalid") => Ok(JitoBundleStatus::Invalid),
+        _ => Err(anyhow!("Unknown bundle status: {}", status)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bundle_status() {
+        let status = |value: serde_json::Value| {
+            parse_bundle_status(&json!({ "context": { "slot": 1 }, "value": value }))
+        };
+        assert_eq!(
+            status(json!([{ "bundle_id": "b", "status": "Landed", "landed_slot": 42 }])).unwrap(),
+            JitoBundleStatus::Landed { slot: 42 }
+        );
+        assert_eq!(
+            status(json!([{ "status": "Pending", "landed_slot": null }])).unwrap(),
+            JitoBundleStatus::Pending
+        );
+        assert_eq!(status(json!([{ "status": "Failed" }])).unwrap(), JitoBundleStatus::Failed);
+        assert_eq!(status(json!([{ "status": "Invalid" }])).unwrap(), JitoBundleStatus::Invalid);
+        // 超出查询窗口的 bundle 不在结果中
+        assert_eq!(status(json!([])).unwrap(), JitoBundleStatus::Invalid);
+        assert!(status(json!([{ "status": "Unknown" }])).is_err());
+    }
+}
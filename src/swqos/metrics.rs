@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::TransactionStatus;
+use tokio::task::JoinHandle;
+
+use crate::common::SolanaRpcClient;
+use crate::swqos::SwqosType;
+
+/// 等待胜出交易上链的最长时间
+const WIN_TRACKING_TIMEOUT: Duration = Duration::from_secs(30);
+/// 查询签名状态的间隔
+const WIN_TRACKING_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 单个 SWQOS 通道的提交统计
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwqosStats {
+    pub swqos_type: SwqosType,
+    /// 发出的提交请求数，包括网络错误
+    pub submissions: u64,
+    /// 服务端接受（响应中没有 error）的提交数
+    pub accepted: u64,
+    /// 已检查上链结果的提交数
+    pub confirmations: u64,
+    /// 在确认超时前上链成功的提交数
+    pub landed: u64,
+    /// 并行提交的交易中，最终上链的那笔由该通道提交的次数
+    pub wins: u64,
+    /// 胜出交易支付的小费合计（SOL）
+    pub tip_spent: f64,
+    /// 提交请求的平均往返耗时
+    pub avg_latency: Duration,
+    /// 提交请求的最大往返耗时
+    pub max_latency: Duration,
+}
+
+impl SwqosStats {
+    /// 接受率，没有提交时为 0
+    pub fn acceptance_rate(&self) -> f64 {
+        ratio(self.accepted, self.submissions)
+    }
+
+    /// 上链率（上链数 / 已检查数），没有检查时为 0
+    pub fn land_rate(&self) -> f64 {
+        ratio(self.landed, self.confirmations)
+    }
+
+    /// 胜出率（胜出数 / 提交数），没有提交时为 0
+    pub fn win_rate(&self) -> f64 {
+        ratio(self.wins, self.submissions)
+    }
+
+    /// 每笔胜出交易的平均小费（SOL），没有胜出时为 0
+    pub fn tip_per_win(&self) -> f64 {
+        if self.wins == 0 {
+            0.0
+        } else {
+            self.tip_spent / self.wins as f64
+        }
+    }
+}
+
+fn ratio(numerator: u64, denominator: u64) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+#[derive(Default)]
+struct Counters {
+    submissions: u64,
+    accepted: u64,
+    confirmations: u64,
+    landed: u64,
+    wins: u64,
+    tip_spent: f64,
+    total_latency: Duration,
+    max_latency: Duration,
+}
+
+/// SwqosMetrics 单例，按通道记录提交延迟、接受率和上链率
+pub struct SwqosMetrics {
+    counters: Mutex<HashMap<SwqosType, Counters>>,
+}
+
+static SWQOS_METRICS: OnceLock<Arc<SwqosMetrics>> = OnceLock::new();
+
+impl SwqosMetrics {
+    fn new() -> Self {
+        Self { counters: Mutex::new(HashMap::new()) }
+    }
+
+    /// 获取 SwqosMetrics 单例实例
+    pub fn get_instance() -> Arc<SwqosMetrics> {
+        SWQOS_METRICS.get_or_init(|| Arc::new(SwqosMetrics::new())).clone()
+    }
+
+    /// 记录一次提交请求
+    pub fn record_submission(&self, swqos_type: SwqosType, latency: Duration, accepted: bool) {
+        let mut counters = self.counters.lock().unwrap();
+        let counters = counters.entry(swqos_type).or_default();
+        counters.submissions += 1;
+        if accepted {
+            counters.accepted += 1;
+        }
+        counters.total_latency += latency;
+        counters.max_latency = counters.max_latency.max(latency);
+    }
+
+    /// 记录一次上链检查的结果
+    pub fn record_landing(&self, swqos_type: SwqosType, landed: bool) {
+        let mut counters = self.counters.lock().unwrap();
+        let counters = counters.entry(swqos_type).or_default();
+        counters.confirmations += 1;
+        if landed {
+            counters.landed += 1;
+        }
+    }
+
+    /// 记录上链交易由哪个通道提交及其小费
+    pub fn record_win(&self, swqos_type: SwqosType, tip: f64) {
+        let mut counters = self.counters.lock().unwrap();
+        let counters = counters.entry(swqos_type).or_default();
+        counters.wins += 1;
+        counters.tip_spent += tip;
+    }
+
+    /// 各通道的统计，按提交数从多到少排序
+    pub fn stats(&self) -> Vec<SwqosStats> {
+        let counters = self.counters.lock().unwrap();
+        let mut stats: Vec<SwqosStats> = counters
+            .iter()
+            .map(|(swqos_type, counters)| SwqosStats {
+                swqos_type: swqos_type.clone(),
+                submissions: counters.submissions,
+                accepted: counters.accepted,
+                confirmations: counters.confirmations,
+                landed: counters.landed,
+                wins: counters.wins,
+                tip_spent: counters.tip_spent,
+                avg_latency: match counters.submissions {
+                    0 => Duration::ZERO,
+                    n => counters.total_latency / n as u32,
+                },
+                max_latency: counters.max_latency,
+            })
+            .collect();
+        stats.sort_by_key(|stats| std::cmp::Reverse(stats.submissions));
+        stats
+    }
+
+    /// 清空统计
+    pub fn reset(&self) {
+        self.counters.lock().unwrap().clear();
+    }
+}
+
+/// 后台等待并行提交的交易上链，记录由哪个通道胜出
+///
+/// `candidates` 为各通道提交的（类型, 签名, 小费），超过 `WIN_TRACKING_TIMEOUT` 仍未上链时不记录，
+/// 执行失败的交易不计为胜出
+pub fn track_winner(rpc: Arc<SolanaRpcClient>, candidates: Vec<(SwqosType, Signature, f64)>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        if candidates.is_empty() {
+            return;
+        }
+        let signatures: Vec<Signature> = candidates.iter().map(|(_, signature, _)| *signature).collect();
+        let start_time = Instant::now();
+        while start_time.elapsed() < WIN_TRACKING_TIMEOUT {
+            if let Ok(statuses) = rpc.get_signature_statuses(&signatures).await {
+                if let Some((index, succeeded)) = first_landed(&statuses.value) {
+                    if succeeded {
+                        let (swqos_type, _, tip) = &candidates[index];
+                        SwqosMetrics::get_instance().record_win(swqos_type.clone(), *tip);
+                    }
+                    return;
+                }
+            }
+            tokio::time::sleep(WIN_TRACKING_INTERVAL).await;
+        }
+    })
+}
+
+/// 第一笔已上链交易的下标及其是否执行成功，均未上链时为 None
+fn first_landed(statuses: &[Option<TransactionStatus>]) -> Option<(usize, bool)> {
+    let index = statuses.iter().position(|status| status.is_some())?;
+    Some((index, statuses[index].as_ref().is_some_and(|status| status.err.is_none())))
+}
+
+/// 发送提交请求并记录往返耗时及是否被接受，返回响应内容
+///
+/// 网络错误和响应中带 error 字段都记为未接受
+pub async fn send_and_record(swqos_type: SwqosType, request: reqwest::RequestBuilder) -> Result<String> {
+    let start_time = Instant::now();
+    let response_text = match request.send().await {
+        Ok(response) => response.text().await,
+        Err(e) => Err(e),
+    };
+    let accepted = match &response_text {
+        Ok(text) => serde_json::from_str::<serde_json::Value>(text)
+            .map(|json| json.get("error").is_none())
+            .unwrap_or(false),
+        Err(_) => false,
+    };
+    SwqosMetrics::get_instance().record_submission(swqos_type, start_time.elapsed(), accepted);
+    Ok(response_text?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_per_provider() {
+        let metrics = SwqosMetrics::new();
+        metrics.record_submission(SwqosType::Jito, Duration::from_millis(10), true);
+        metrics.record_submission(SwqosType::Jito, Duration::from_millis(30), false);
+        metrics.record_landing(SwqosType::Jito, true);
+        metrics.record_win(SwqosType::Jito, 0.002);
+        metrics.record_win(SwqosType::Jito, 0.004);
+        metrics.record_submission(SwqosType::Node1, Duration::from_millis(5), true);
+
+        let stats = metrics.stats();
+        assert_eq!(stats.len(), 2);
+        let jito = &stats[0];
+        assert_eq!(jito.swqos_type, SwqosType::Jito);
+        assert_eq!(jito.submissions, 2);
+        assert_eq!(jito.avg_latency, Duration::from_millis(20));
+        assert_eq!(jito.max_latency, Duration::from_millis(30));
+        assert_eq!(jito.acceptance_rate(), 0.5);
+        assert_eq!(jito.land_rate(), 1.0);
+        assert_eq!(jito.wins, 2);
+        assert_eq!(jito.win_rate(), 1.0);
+        assert!((jito.tip_per_win() - 0.003).abs() < 1e-12);
+        assert_eq!(stats[1].land_rate(), 0.0);
+        assert_eq!(stats[1].tip_per_win(), 0.0);
+
+        metrics.reset();
+        assert!(metrics.stats().is_empty());
+    }
+
+    #[test]
+    fn test_first_landed() {
+        use solana_sdk::transaction::TransactionError;
+
+        let status = |err: Option<TransactionError>| {
+            Some(TransactionStatus {
+                slot: 1,
+                confirmations: Some(0),
+                status: err.clone().map_or(Ok(()), Err),
+                err,
+                confirmation_status: None,
+            })
+        };
+        assert_eq!(first_landed(&[None, None]), None);
+        assert_eq!(first_landed(&[None, status(None), status(None)]), Some((1, true)));
+        // 先上链的交易执行失败，不计为胜出
+        let failed = status(Some(TransactionError::AccountInUse));
+        assert_eq!(first_landed(&[failed, status(None)]), Some((0, false)));
+    }
+}
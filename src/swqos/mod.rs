@@ -5,21 +5,24 @@ pub mod nextblock;
 pub mod zeroslot;
 pub mod temporal;
 pub mod bloxroute;
+pub mod node1;
+pub mod blockrazor;
+pub mod tip_oracle;
+pub mod metrics;
+pub mod health;
+pub mod rate_limit;
+pub mod region;
+pub mod provider;
 
 use std::sync::Arc;
 
-use solana_sdk::{commitment_config::CommitmentConfig, transaction::VersionedTransaction};
-use tokio::sync::RwLock;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, transaction::VersionedTransaction};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
-use crate::{common::SolanaRpcClient, constants::swqos::{SWQOS_ENDPOINTS_BLOX, SWQOS_ENDPOINTS_JITO, SWQOS_ENDPOINTS_NEXTBLOCK, SWQOS_ENDPOINTS_TEMPORAL, SWQOS_ENDPOINTS_ZERO_SLOT}, swqos::{bloxroute::BloxrouteClient, jito::JitoClient, nextblock::NextBlockClient, solana_rpc::SolRpcClient, temporal::TemporalClient, zeroslot::ZeroSlotClient}};
+use crate::{common::SolanaRpcClient, constants::swqos::{SWQOS_ENDPOINTS_BLOCKRAZOR, SWQOS_ENDPOINTS_BLOX, SWQOS_ENDPOINTS_JITO, SWQOS_ENDPOINTS_NEXTBLOCK, SWQOS_ENDPOINTS_NODE1, SWQOS_ENDPOINTS_TEMPORAL, SWQOS_ENDPOINTS_ZERO_SLOT}, swqos::{blockrazor::BlockRazorClient, bloxroute::BloxrouteClient, jito::JitoClient, nextblock::NextBlockClient, node1::Node1Client, provider::{get_swqos_provider, ProviderClient}, region::{region_endpoints, AutoRegionClient}, solana_rpc::SolRpcClient, temporal::TemporalClient, zeroslot::ZeroSlotClient}};
 
-lazy_static::lazy_static! {
-    static ref TIP_ACCOUNT_CACHE: RwLock<Vec<String>> = RwLock::new(Vec::new());
-}
-
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TradeType {
     Create,
     CreateAndBuy,
@@ -39,13 +42,17 @@ impl std::fmt::Display for TradeType {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum SwqosType {
     Jito,
     NextBlock,
     ZeroSlot,
     Temporal,
     Bloxroute,
+    Node1,
+    BlockRazor,
+    /// 通过 `register_swqos_provider` 注册的自定义服务商，内容为服务商名称
+    Custom(String),
     Default,
 }
 
@@ -57,6 +64,12 @@ pub trait SwqosClientTrait {
     async fn send_transactions(&self, trade_type: TradeType, transactions: &Vec<VersionedTransaction>) -> Result<()>;
     fn get_tip_account(&self) -> Result<String>;
     fn get_swqos_type(&self) -> SwqosType;
+    /// 检查服务可达且鉴权通过，不提交任何交易
+    async fn ping(&self) -> Result<()>;
+    /// 从服务商接口拉取当前的小费账户，没有该接口的服务商返回空列表
+    async fn fetch_tip_accounts(&self) -> Result<Vec<Pubkey>> {
+        Ok(vec![])
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -69,6 +82,22 @@ pub enum SwqosRegion {
     London,
     LosAngeles,
     Default,
+    /// 启动时（及定时）探测所有区域，自动选用延迟最低的区域
+    Auto,
+}
+
+impl SwqosRegion {
+    /// 所有固定区域，不含 Auto
+    pub const ALL: [SwqosRegion; 8] = [
+        SwqosRegion::NewYork,
+        SwqosRegion::Frankfurt,
+        SwqosRegion::Amsterdam,
+        SwqosRegion::SLC,
+        SwqosRegion::Tokyo,
+        SwqosRegion::London,
+        SwqosRegion::LosAngeles,
+        SwqosRegion::Default,
+    ];
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -79,75 +108,101 @@ pub enum SwqosConfig {
     Bloxroute(String, SwqosRegion),
     Temporal(String, SwqosRegion),
     ZeroSlot(String, SwqosRegion),
+    /// Node1.me，第一个参数为 API Key
+    Node1(String, SwqosRegion),
+    /// BlockRazor，第一个参数为 API Key
+    BlockRazor(String, SwqosRegion),
+    /// 已注册的自定义服务商，参数为服务商名称
+    Custom(String),
 }
 
 impl SwqosConfig {
     pub fn get_endpoint(swqos_type: SwqosType, region: SwqosRegion) -> String {
+        // Auto 未探测时使用默认端点
+        let region = if region == SwqosRegion::Auto { SwqosRegion::Default } else { region };
         match swqos_type {
             SwqosType::Jito => SWQOS_ENDPOINTS_JITO[region as usize].to_string(),
             SwqosType::NextBlock => SWQOS_ENDPOINTS_NEXTBLOCK[region as usize].to_string(),
             SwqosType::ZeroSlot => SWQOS_ENDPOINTS_ZERO_SLOT[region as usize].to_string(),
             SwqosType::Temporal => SWQOS_ENDPOINTS_TEMPORAL[region as usize].to_string(),
             SwqosType::Bloxroute => SWQOS_ENDPOINTS_BLOX[region as usize].to_string(),
+            SwqosType::Node1 => SWQOS_ENDPOINTS_NODE1[region as usize].to_string(),
+            SwqosType::BlockRazor => SWQOS_ENDPOINTS_BLOCKRAZOR[region as usize].to_string(),
+            SwqosType::Custom(name) => get_swqos_provider(&name).map(|provider| provider.endpoint()).unwrap_or_default(),
             SwqosType::Default => "".to_string(),
         }
     }
 
-    pub fn get_swqos_client(rpc_url: String, commitment: CommitmentConfig, swqos_config: SwqosConfig) -> Arc<SwqosClient> {
-        match swqos_config {
-            SwqosConfig::Jito(auth_token, region) => {
-                let endpoint = SwqosConfig::get_endpoint(SwqosType::Jito, region);
-                let jito_client = JitoClient::new(
-                    rpc_url.clone(),
-                    endpoint,
-                    auth_token
-                );
-                Arc::new(jito_client)
-            }
-            SwqosConfig::NextBlock(auth_token, region) => {
-                let endpoint = SwqosConfig::get_endpoint(SwqosType::NextBlock, region);
-                let nextblock_client = NextBlockClient::new(
-                    rpc_url.clone(),
-                    endpoint.to_string(),
-                    auth_token
-                );
-                Arc::new(nextblock_client)
-            },
-            SwqosConfig::ZeroSlot(auth_token, region) => {
-                let endpoint = SwqosConfig::get_endpoint(SwqosType::ZeroSlot, region);
-                let zeroslot_client = ZeroSlotClient::new(
-                    rpc_url.clone(),
-                    endpoint.to_string(),
-                    auth_token
-                );
-                Arc::new(zeroslot_client)
-            },
-            SwqosConfig::Temporal(auth_token, region) => {  
-                let endpoint = SwqosConfig::get_endpoint(SwqosType::Temporal, region);
-                let temporal_client = TemporalClient::new(
-                    rpc_url.clone(),
-                    endpoint.to_string(),
-                    auth_token
-                );
-                Arc::new(temporal_client)
-            },
-            SwqosConfig::Bloxroute(auth_token, region) => { 
-                let endpoint = SwqosConfig::get_endpoint(SwqosType::Bloxroute, region);
-                let bloxroute_client = BloxrouteClient::new(
-                    rpc_url.clone(),
-                    endpoint.to_string(),
-                    auth_token
-                );
-                Arc::new(bloxroute_client)
-            },
-            SwqosConfig::Default(endpoint) => {
-                let rpc = SolanaRpcClient::new_with_commitment(
-                    endpoint,
-                    commitment
-                );   
-                let rpc_client = SolRpcClient::new(Arc::new(rpc));
-                Arc::new(rpc_client)
-            }
+    /// 服务商类型、鉴权 token 及区域，Default 返回 None
+    pub fn parts(&self) -> Option<(SwqosType, String, SwqosRegion)> {
+        match self.clone() {
+            SwqosConfig::Jito(auth_token, region) => Some((SwqosType::Jito, auth_token, region)),
+            SwqosConfig::NextBlock(auth_token, region) => Some((SwqosType::NextBlock, auth_token, region)),
+            SwqosConfig::ZeroSlot(auth_token, region) => Some((SwqosType::ZeroSlot, auth_token, region)),
+            SwqosConfig::Temporal(auth_token, region) => Some((SwqosType::Temporal, auth_token, region)),
+            SwqosConfig::Bloxroute(auth_token, region) => Some((SwqosType::Bloxroute, auth_token, region)),
+            SwqosConfig::Node1(auth_token, region) => Some((SwqosType::Node1, auth_token, region)),
+            SwqosConfig::BlockRazor(auth_token, region) => Some((SwqosType::BlockRazor, auth_token, region)),
+            // 鉴权由服务商自己处理，区域固定为 Default
+            SwqosConfig::Custom(name) => Some((SwqosType::Custom(name), String::new(), SwqosRegion::Default)),
+            SwqosConfig::Default(_) => None,
         }
     }
+
+    /// 区域为 `SwqosRegion::Auto` 时为每个区域各建一个客户端，返回尚未探测的自动选区客户端
+    pub fn get_auto_region_client(rpc_url: String, swqos_config: &SwqosConfig) -> Result<Option<Arc<AutoRegionClient>>> {
+        let Some((swqos_type, auth_token, region)) = swqos_config.parts() else {
+            return Ok(None);
+        };
+        if region != SwqosRegion::Auto {
+            return Ok(None);
+        }
+        let regions = region_endpoints(&swqos_type)
+            .into_iter()
+            .map(|(region, endpoint)| {
+                let swqos_client =
+                    SwqosConfig::new_client(rpc_url.clone(), swqos_type.clone(), endpoint, auth_token.clone())?;
+                Ok((region, swqos_client))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Some(Arc::new(AutoRegionClient::new(swqos_type, regions))))
+    }
+
+    /// 自定义服务商未注册时返回错误
+    pub fn get_swqos_client(rpc_url: String, commitment: CommitmentConfig, swqos_config: SwqosConfig) -> Result<Arc<SwqosClient>> {
+        if let Some(auto_region_client) = SwqosConfig::get_auto_region_client(rpc_url.clone(), &swqos_config)? {
+            return Ok(auto_region_client);
+        }
+        let Some((swqos_type, auth_token, region)) = swqos_config.parts() else {
+            let SwqosConfig::Default(endpoint) = swqos_config else {
+                return Err(anyhow!("SWQOS config has no provider type"));
+            };
+            let rpc = SolanaRpcClient::new_with_commitment(
+                endpoint,
+                commitment
+            );
+            let rpc_client = SolRpcClient::new(Arc::new(rpc));
+            return Ok(Arc::new(rpc_client));
+        };
+        let endpoint = SwqosConfig::get_endpoint(swqos_type.clone(), region);
+        SwqosConfig::new_client(rpc_url, swqos_type, endpoint, auth_token)
+    }
+
+    fn new_client(rpc_url: String, swqos_type: SwqosType, endpoint: String, auth_token: String) -> Result<Arc<SwqosClient>> {
+        Ok(match swqos_type {
+            SwqosType::Jito => Arc::new(JitoClient::new(rpc_url, endpoint, auth_token)),
+            SwqosType::NextBlock => Arc::new(NextBlockClient::new(rpc_url, endpoint, auth_token)),
+            SwqosType::ZeroSlot => Arc::new(ZeroSlotClient::new(rpc_url, endpoint, auth_token)),
+            SwqosType::Temporal => Arc::new(TemporalClient::new(rpc_url, endpoint, auth_token)),
+            SwqosType::Bloxroute => Arc::new(BloxrouteClient::new(rpc_url, endpoint, auth_token)),
+            SwqosType::Node1 => Arc::new(Node1Client::new(rpc_url, endpoint, auth_token)),
+            SwqosType::BlockRazor => Arc::new(BlockRazorClient::new(rpc_url, endpoint, auth_token)),
+            SwqosType::Custom(name) => {
+                let provider = get_swqos_provider(&name)
+                    .ok_or_else(|| anyhow!("SWQOS provider {} is not registered", name))?;
+                Arc::new(ProviderClient::new(rpc_url, provider))
+            }
+            SwqosType::Default => Arc::new(SolRpcClient::new(Arc::new(SolanaRpcClient::new(endpoint)))),
+        })
+    }
 }
\ No newline at end of file
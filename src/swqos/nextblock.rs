@@ -1,5 +1,5 @@
-use crate::swqos::common::{poll_transaction_confirmation, serialize_transaction_and_encode};
-use rand::seq::IndexedRandom;
+use crate::swqos::metrics::{send_and_record, SwqosMetrics};
+use crate::swqos::common::{poll_transaction_confirmation, serialize_transaction_and_encode, ping_endpoint};
 use reqwest::Client;
 use serde_json::json;
 use std::{sync::Arc, time::Instant};
@@ -12,7 +12,7 @@ use solana_sdk::transaction::VersionedTransaction;
 use crate::swqos::{SwqosType, TradeType};
 use crate::swqos::SwqosClientTrait;
 
-use crate::{common::SolanaRpcClient, constants::swqos::NEXTBLOCK_TIP_ACCOUNTS};
+use crate::{common::{tip_cache::TipCache, SolanaRpcClient}, constants::swqos::NEXTBLOCK_TIP_ACCOUNTS};
 
 #[derive(Clone)]
 pub struct NextBlockClient {
@@ -33,13 +33,19 @@ impl SwqosClientTrait for NextBlockClient {
     }
 
     fn get_tip_account(&self) -> Result<String> {
-        let tip_account = *NEXTBLOCK_TIP_ACCOUNTS.choose(&mut rand::rng()).or_else(|| NEXTBLOCK_TIP_ACCOUNTS.first()).unwrap();
+        let tip_account = TipCache::get_instance()
+            .random_tip_account(&SwqosType::NextBlock, NEXTBLOCK_TIP_ACCOUNTS)
+            .ok_or_else(|| anyhow::anyhow!("no valid tip accounts found"))?;
         Ok(tip_account.to_string())
     }
 
     fn get_swqos_type(&self) -> SwqosType {
         SwqosType::NextBlock
     }
+
+    async fn ping(&self) -> Result<()> {
+        ping_endpoint(self.http_client.post(&self.endpoint).header("Authorization", &self.auth_token).body("{}")).await
+    }
 }
 
 impl NextBlockClient {
@@ -69,14 +75,11 @@ impl NextBlockClient {
             "frontRunningProtection": false
         }))?;
 
-        let response_text = self.http_client.post(&self.endpoint)
+        let request = self.http_client.post(&self.endpoint)
             .body(request_body)
             .header("Authorization", &self.auth_token)
-            .header("Content-Type", "application/json")
-            .send()
-            .await?
-            .text()
-            .await?;
+            .header("Content-Type", "application/json");
+        let response_text = send_and_record(SwqosType::NextBlock, request).await?;
 
         if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
             if response_json.get("result").is_some() {
@@ -87,10 +90,8 @@ impl NextBlockClient {
         }
 
         let start_time: Instant = Instant::now();
-        match poll_transaction_confirmation(&self.rpc_client, signature).await {
-            Ok(_) => (),
-            Err(_) => (),
-        }
+        let landed = poll_transaction_confirmation(&self.rpc_client, signature).await.is_ok();
+        SwqosMetrics::get_instance().record_landing(SwqosType::NextBlock, landed);
 
         println!(" nextblock{}确认: {:?}", trade_type, start_time.elapsed());
 
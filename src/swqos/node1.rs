@@ -0,0 +1,112 @@
+use crate::swqos::metrics::{send_and_record, SwqosMetrics};
+use crate::swqos::common::{poll_transaction_confirmation, serialize_transaction_and_encode, ping_endpoint};
+use reqwest::Client;
+use serde_json::json;
+use std::{sync::Arc, time::Instant};
+
+use std::time::Duration;
+use solana_transaction_status::UiTransactionEncoding;
+
+use anyhow::Result;
+use solana_sdk::transaction::VersionedTransaction;
+use crate::swqos::{SwqosType, TradeType};
+use crate::swqos::SwqosClientTrait;
+
+use crate::{common::{tip_cache::TipCache, SolanaRpcClient}, constants::swqos::NODE1_TIP_ACCOUNTS};
+
+#[derive(Clone)]
+pub struct Node1Client {
+    pub endpoint: String,
+    pub auth_token: String,
+    pub rpc_client: Arc<SolanaRpcClient>,
+    pub http_client: Client,
+}
+
+#[async_trait::async_trait]
+impl SwqosClientTrait for Node1Client {
+    async fn send_transaction(&self, trade_type: TradeType, transaction: &VersionedTransaction) -> Result<()> {
+        self.send_transaction(trade_type, transaction).await
+    }
+
+    async fn send_transactions(&self, trade_type: TradeType, transactions: &Vec<VersionedTransaction>) -> Result<()> {
+        self.send_transactions(trade_type, transactions).await
+    }
+
+    fn get_tip_account(&self) -> Result<String> {
+        let tip_account = TipCache::get_instance()
+            .random_tip_account(&SwqosType::Node1, NODE1_TIP_ACCOUNTS)
+            .ok_or_else(|| anyhow::anyhow!("no valid tip accounts found"))?;
+        Ok(tip_account.to_string())
+    }
+
+    fn get_swqos_type(&self) -> SwqosType {
+        SwqosType::Node1
+    }
+
+    async fn ping(&self) -> Result<()> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": "getHealth" });
+        ping_endpoint(self.http_client.post(&self.endpoint).header("api-key", &self.auth_token).body(body.to_string())).await
+    }
+}
+
+impl Node1Client {
+    pub fn new(rpc_url: String, endpoint: String, auth_token: String) -> Self {
+        let rpc_client = SolanaRpcClient::new(rpc_url);
+        let http_client = Client::builder()
+            .pool_idle_timeout(Duration::from_secs(60))
+            .pool_max_idle_per_host(64)
+            .tcp_keepalive(Some(Duration::from_secs(1200)))
+            .http2_keep_alive_interval(Duration::from_secs(15))
+            .timeout(Duration::from_secs(10))
+            .connect_timeout(Duration::from_secs(5))
+            .build()
+            .unwrap();
+        Self { rpc_client: Arc::new(rpc_client), endpoint, auth_token, http_client }
+    }
+
+    pub async fn send_transaction(&self, trade_type: TradeType, transaction: &VersionedTransaction) -> Result<()> {
+        let start_time = Instant::now();
+        let (content, signature) = serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64).await?;
+        println!(" 交易编码base64: {:?}", start_time.elapsed());
+
+        let request_body = serde_json::to_string(&json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "sendTransaction",
+            "params": [
+                content,
+                { "encoding": "base64", "skipPreflight": true }
+            ]
+        }))?;
+
+        // API Key 通过请求头传递
+        let request = self.http_client.post(&self.endpoint)
+            .body(request_body)
+            .header("api-key", &self.auth_token)
+            .header("Content-Type", "application/json");
+        let response_text = send_and_record(SwqosType::Node1, request).await?;
+
+        if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
+            if response_json.get("result").is_some() {
+                println!(" node1{}提交: {:?}", trade_type, start_time.elapsed());
+            } else if let Some(error) = response_json.get("error") {
+                return Err(anyhow::anyhow!("node1{}提交失败: {}", trade_type, error));
+            }
+        }
+
+        let start_time: Instant = Instant::now();
+        let landed = poll_transaction_confirmation(&self.rpc_client, signature).await.is_ok();
+        SwqosMetrics::get_instance().record_landing(SwqosType::Node1, landed);
+
+        println!(" node1{}确认: {:?}", trade_type, start_time.elapsed());
+
+        Ok(())
+    }
+
+    pub async fn send_transactions(&self, trade_type: TradeType, transactions: &Vec<VersionedTransaction>) -> Result<()> {
+        for transaction in transactions {
+            self.send_transaction(trade_type, transaction).await?;
+        }
+        Ok(())
+    }
+}
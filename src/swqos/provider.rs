@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use reqwest::Client;
+use solana_sdk::{pubkey::Pubkey, signature::Signature, transaction::VersionedTransaction};
+
+use crate::common::{tip_cache::TipCache, SolanaRpcClient};
+use crate::swqos::common::{ping_endpoint, poll_transaction_confirmation};
+use crate::swqos::metrics::{send_and_record, SwqosMetrics};
+use crate::swqos::{SwqosClientTrait, SwqosType, TradeType};
+
+/// 自定义 SWQOS 服务商，实现后通过 `register_swqos_provider` 注册，
+/// 再用 `SwqosConfig::Custom(name)` 引用，无需修改 `SwqosConfig`
+pub trait SwqosProvider: Send + Sync {
+    /// 服务商名称，注册和 `SwqosConfig::Custom` 均使用该名称，也是 `SwqosType::Custom` 的内容
+    fn name(&self) -> &str;
+
+    /// 服务商端点
+    fn endpoint(&self) -> String;
+
+    /// 小费账户，每笔交易随机选择一个
+    fn tip_accounts(&self) -> Vec<Pubkey>;
+
+    /// 构造提交交易的请求，响应中带 error 字段视为未被接受
+    fn build_request(&self, http_client: &Client, transaction: &VersionedTransaction) -> Result<reqwest::RequestBuilder>;
+
+    /// 构造健康检查请求，默认 GET 端点
+    fn build_ping_request(&self, http_client: &Client) -> reqwest::RequestBuilder {
+        http_client.get(self.endpoint())
+    }
+}
+
+static SWQOS_PROVIDERS: OnceLock<RwLock<HashMap<String, Arc<dyn SwqosProvider>>>> = OnceLock::new();
+
+fn providers() -> &'static RwLock<HashMap<String, Arc<dyn SwqosProvider>>> {
+    SWQOS_PROVIDERS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// 注册自定义服务商，同名的服务商会被替换
+pub fn register_swqos_provider(provider: Arc<dyn SwqosProvider>) {
+    providers().write().unwrap().insert(provider.name().to_string(), provider);
+}
+
+/// 按名称获取已注册的服务商
+pub fn get_swqos_provider(name: &str) -> Option<Arc<dyn SwqosProvider>> {
+    providers().read().unwrap().get(name).cloned()
+}
+
+/// 自定义服务商的 SWQOS 客户端，提交、统计和上链确认与内置服务商一致
+pub struct ProviderClient {
+    pub provider: Arc<dyn SwqosProvider>,
+    pub rpc_client: Arc<SolanaRpcClient>,
+    pub http_client: Client,
+}
+
+impl ProviderClient {
+    pub fn new(rpc_url: String, provider: Arc<dyn SwqosProvider>) -> Self {
+        let rpc_client = SolanaRpcClient::new(rpc_url);
+        let http_client = Client::builder()
+            .pool_idle_timeout(Duration::from_secs(60))
+            .pool_max_idle_per_host(64)
+            .tcp_keepalive(Some(Duration::from_secs(1200)))
+            .http2_keep_alive_interval(Duration::from_secs(15))
+            .timeout(Duration::from_secs(10))
+            .connect_timeout(Duration::from_secs(5))
+            .build()
+            .unwrap();
+        Self { provider, rpc_client: Arc::new(rpc_client), http_client }
+    }
+
+    fn swqos_type(&self) -> SwqosType {
+        SwqosType::Custom(self.provider.name().to_string())
+    }
+}
+
+#[async_trait::async_trait]
+impl SwqosClientTrait for ProviderClient {
+    async fn send_transaction(&self, trade_type: TradeType, transaction: &VersionedTransaction) -> Result<()> {
+        let start_time = Instant::now();
+        let name = self.provider.name();
+        let signature: Signature =
+            *transaction.signatures.first().ok_or_else(|| anyhow!("transaction has no signature"))?;
+        let request = self.provider.build_request(&self.http_client, transaction)?;
+        let response_text = send_and_record(self.swqos_type(), request).await?;
+
+        match serde_json::from_str::<serde_json::Value>(&response_text) {
+            Ok(response_json) if response_json.get("error").is_some() => {
+                eprintln!(" {}{}提交失败: {:?}", name, trade_type, response_json["error"]);
+            }
+            _ => println!(" {}{}提交: {:?}", name, trade_type, start_time.elapsed()),
+        }
+
+        let start_time: Instant = Instant::now();
+        let landed = poll_transaction_confirmation(&self.rpc_client, signature).await.is_ok();
+        SwqosMetrics::get_instance().record_landing(self.swqos_type(), landed);
+
+        println!(" {}{}确认: {:?}", name, trade_type, start_time.elapsed());
+
+        Ok(())
+    }
+
+    async fn send_transactions(&self, trade_type: TradeType, transactions: &Vec<VersionedTransaction>) -> Result<()> {
+        for transaction in transactions {
+            self.send_transaction(trade_type, transaction).await?;
+        }
+        Ok(())
+    }
+
+    fn get_tip_account(&self) -> Result<String> {
+        let tip_account = TipCache::get_instance()
+            .random_tip_account(&self.swqos_type(), &self.provider.tip_accounts())
+            .ok_or_else(|| anyhow!("no valid tip accounts found"))?;
+        Ok(tip_account.to_string())
+    }
+
+    fn get_swqos_type(&self) -> SwqosType {
+        self.swqos_type()
+    }
+
+    async fn ping(&self) -> Result<()> {
+        ping_endpoint(self.provider.build_ping_request(&self.http_client)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestProvider;
+
+    impl SwqosProvider for TestProvider {
+        fn name(&self) -> &str {
+            "test-relay"
+        }
+
+        fn endpoint(&self) -> String {
+            "http://127.0.0.1:1".to_string()
+        }
+
+        fn tip_accounts(&self) -> Vec<Pubkey> {
+            vec![Pubkey::new_from_array([7; 32])]
+        }
+
+        fn build_request(&self, http_client: &Client, _transaction: &VersionedTransaction) -> Result<reqwest::RequestBuilder> {
+            Ok(http_client.post(self.endpoint()))
+        }
+    }
+
+    #[test]
+    fn test_registered_provider_client() {
+        register_swqos_provider(Arc::new(TestProvider));
+        let provider = get_swqos_provider("test-relay").unwrap();
+        assert!(get_swqos_provider("missing").is_none());
+
+        let client = ProviderClient::new("http://127.0.0.1:8899".to_string(), provider);
+        assert_eq!(client.get_swqos_type(), SwqosType::Custom("test-relay".to_string()));
+        assert_eq!(client.get_tip_account().unwrap(), Pubkey::new_from_array([7; 32]).to_string());
+
+        // 未注册的服务商返回错误而不是 panic
+        let missing = crate::swqos::SwqosConfig::get_swqos_client(
+            "http://127.0.0.1:8899".to_string(),
+            solana_sdk::commitment_config::CommitmentConfig::processed(),
+            crate::swqos::SwqosConfig::Custom("missing".to_string()),
+        );
+        assert!(missing.is_err());
+    }
+}
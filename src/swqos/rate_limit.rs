@@ -0,0 +1,160 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use solana_sdk::{pubkey::Pubkey, transaction::VersionedTransaction};
+
+use crate::swqos::{SwqosClient, SwqosClientTrait, SwqosType, TradeType};
+
+/// 超出速率限制时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RateLimitOverflow {
+    /// 等待令牌后再提交
+    #[default]
+    Queue,
+    /// 直接放弃本次提交并返回错误
+    Shed,
+}
+
+/// 单个 SWQOS 通道的令牌桶限速配置
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SwqosRateLimit {
+    /// 每秒补充的令牌数
+    pub requests_per_second: f64,
+    /// 桶容量，即允许的突发请求数
+    pub burst: u32,
+    pub overflow: RateLimitOverflow,
+}
+
+impl SwqosRateLimit {
+    pub fn new(requests_per_second: f64) -> Self {
+        Self { requests_per_second, burst: 1, overflow: RateLimitOverflow::default() }
+    }
+}
+
+/// 令牌桶
+pub struct TokenBucket {
+    limit: SwqosRateLimit,
+    /// 当前令牌数及上次补充时间
+    state: Mutex<(f64, Instant)>,
+}
+
+impl TokenBucket {
+    pub fn new(limit: SwqosRateLimit) -> Self {
+        Self { limit, state: Mutex::new((limit.burst as f64, Instant::now())) }
+    }
+
+    /// 尝试取一个令牌，令牌不足时返回需要等待的时长
+    pub fn try_acquire(&self) -> Result<(), Duration> {
+        let mut state = self.state.lock().unwrap();
+        let (tokens, last_refill) = &mut *state;
+        let now = Instant::now();
+        let capacity = self.limit.burst.max(1) as f64;
+        *tokens = (*tokens + now.duration_since(*last_refill).as_secs_f64() * self.limit.requests_per_second)
+            .min(capacity);
+        *last_refill = now;
+        if *tokens >= 1.0 {
+            *tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - *tokens) / self.limit.requests_per_second))
+        }
+    }
+
+    /// 按配置的方式取令牌：排队等待或直接拒绝
+    pub async fn acquire(&self) -> Result<()> {
+        loop {
+            match self.try_acquire() {
+                Ok(()) => return Ok(()),
+                Err(wait) => match self.limit.overflow {
+                    RateLimitOverflow::Queue => tokio::time::sleep(wait).await,
+                    RateLimitOverflow::Shed => {
+                        return Err(anyhow!("Rate limit exceeded, retry in {:?}", wait))
+                    }
+                },
+            }
+        }
+    }
+}
+
+/// 为 SWQOS 客户端加上令牌桶限速，只限制交易提交，不限制 ping
+pub struct RateLimitedClient {
+    inner: Arc<SwqosClient>,
+    bucket: TokenBucket,
+}
+
+impl RateLimitedClient {
+    pub fn new(inner: Arc<SwqosClient>, limit: SwqosRateLimit) -> Self {
+        Self { inner, bucket: TokenBucket::new(limit) }
+    }
+}
+
+#[async_trait::async_trait]
+impl SwqosClientTrait for RateLimitedClient {
+    async fn send_transaction(&self, trade_type: TradeType, transaction: &VersionedTransaction) -> Result<()> {
+        self.bucket
+            .acquire()
+            .await
+            .map_err(|e| anyhow!("{:?}: {}", self.inner.get_swqos_type(), e))?;
+        self.inner.send_transaction(trade_type, transaction).await
+    }
+
+    async fn send_transactions(&self, trade_type: TradeType, transactions: &Vec<VersionedTransaction>) -> Result<()> {
+        self.bucket
+            .acquire()
+            .await
+            .map_err(|e| anyhow!("{:?}: {}", self.inner.get_swqos_type(), e))?;
+        self.inner.send_transactions(trade_type, transactions).await
+    }
+
+    fn get_tip_account(&self) -> Result<String> {
+        self.inner.get_tip_account()
+    }
+
+    fn get_swqos_type(&self) -> SwqosType {
+        self.inner.get_swqos_type()
+    }
+
+    async fn ping(&self) -> Result<()> {
+        self.inner.ping().await
+    }
+
+    async fn fetch_tip_accounts(&self) -> Result<Vec<Pubkey>> {
+        self.inner.fetch_tip_accounts().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket_burst_and_refill() {
+        let bucket = TokenBucket::new(SwqosRateLimit {
+            requests_per_second: 10.0,
+            burst: 2,
+            overflow: RateLimitOverflow::Shed,
+        });
+        assert!(bucket.try_acquire().is_ok());
+        assert!(bucket.try_acquire().is_ok());
+        let wait = bucket.try_acquire().unwrap_err();
+        assert!(wait <= Duration::from_millis(100));
+
+        std::thread::sleep(Duration::from_millis(120));
+        assert!(bucket.try_acquire().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_queue_waits_and_shed_fails() {
+        let bucket = TokenBucket::new(SwqosRateLimit::new(20.0));
+        bucket.acquire().await.unwrap();
+        let start = Instant::now();
+        bucket.acquire().await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(40));
+
+        let bucket =
+            TokenBucket::new(SwqosRateLimit { overflow: RateLimitOverflow::Shed, ..SwqosRateLimit::new(1.0) });
+        bucket.acquire().await.unwrap();
+        assert!(bucket.acquire().await.is_err());
+    }
+}
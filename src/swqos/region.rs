@@ -0,0 +1,161 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use solana_sdk::{pubkey::Pubkey, transaction::VersionedTransaction};
+use tokio::task::JoinHandle;
+
+use crate::swqos::{SwqosClient, SwqosClientTrait, SwqosConfig, SwqosRegion, SwqosType, TradeType};
+
+/// 单个区域探测的超时时间
+const REGION_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 服务商所有区域及其端点，端点相同的区域只保留第一个
+pub fn region_endpoints(swqos_type: &SwqosType) -> Vec<(SwqosRegion, String)> {
+    let mut endpoints: Vec<(SwqosRegion, String)> = vec![];
+    for region in SwqosRegion::ALL {
+        let endpoint = SwqosConfig::get_endpoint(swqos_type.clone(), region.clone());
+        if !endpoint.is_empty() && endpoints.iter().all(|(_, existing)| *existing != endpoint) {
+            endpoints.push((region, endpoint));
+        }
+    }
+    endpoints
+}
+
+/// 从探测结果中选出延迟最低的区域编号，全部失败时返回 None
+pub fn fastest_region(latencies: &[Result<Duration>]) -> Option<usize> {
+    latencies
+        .iter()
+        .enumerate()
+        .filter_map(|(index, latency)| latency.as_ref().ok().map(|latency| (index, *latency)))
+        .min_by_key(|(_, latency)| *latency)
+        .map(|(index, _)| index)
+}
+
+/// 为服务商的每个区域各建一个客户端，按探测延迟自动选用最快的区域
+///
+/// 由 `SwqosRegion::Auto` 创建，未探测前使用第一个区域
+pub struct AutoRegionClient {
+    swqos_type: SwqosType,
+    regions: Vec<(SwqosRegion, Arc<SwqosClient>)>,
+    selected: AtomicUsize,
+    /// 最近一次探测各区域的延迟，失败的区域为 None
+    latencies: Mutex<Vec<Option<Duration>>>,
+}
+
+impl AutoRegionClient {
+    pub fn new(swqos_type: SwqosType, regions: Vec<(SwqosRegion, Arc<SwqosClient>)>) -> Self {
+        let latencies = Mutex::new(vec![None; regions.len()]);
+        Self { swqos_type, regions, selected: AtomicUsize::new(0), latencies }
+    }
+
+    fn current(&self) -> &Arc<SwqosClient> {
+        &self.regions[self.selected.load(Ordering::Relaxed)].1
+    }
+
+    /// 当前选用的区域
+    pub fn selected_region(&self) -> SwqosRegion {
+        self.regions[self.selected.load(Ordering::Relaxed)].0.clone()
+    }
+
+    /// 最近一次探测各区域的延迟
+    pub fn latencies(&self) -> Vec<(SwqosRegion, Option<Duration>)> {
+        let latencies = self.latencies.lock().unwrap();
+        self.regions.iter().map(|(region, _)| region.clone()).zip(latencies.iter().copied()).collect()
+    }
+
+    /// 并发 ping 所有区域并切换到最快的区域，全部失败时保持当前区域
+    pub async fn probe(&self) -> SwqosRegion {
+        let results: Vec<Result<Duration>> =
+            futures::future::join_all(self.regions.iter().map(|(_, swqos_client)| async move {
+                let start_time = Instant::now();
+                tokio::time::timeout(REGION_PROBE_TIMEOUT, swqos_client.ping())
+                    .await
+                    .map_err(|_| anyhow::anyhow!("探测超时"))??;
+                Ok(start_time.elapsed())
+            }))
+            .await;
+        *self.latencies.lock().unwrap() = results.iter().map(|result| result.as_ref().ok().copied()).collect();
+
+        if let Some(fastest) = fastest_region(&results) {
+            let previous = self.selected.swap(fastest, Ordering::Relaxed);
+            if previous != fastest {
+                println!(
+                    "SWQOS {:?} 切换区域 {:?} -> {:?} ({:?})",
+                    self.swqos_type, self.regions[previous].0, self.regions[fastest].0, results[fastest].as_ref().ok()
+                );
+            }
+        }
+        self.selected_region()
+    }
+
+    /// 启动后台任务，定时重新探测
+    pub fn start(self: &Arc<Self>, probe_interval_ms: u64) -> JoinHandle<()> {
+        let client = self.clone();
+        tokio::spawn(async move {
+            let period = Duration::from_millis(probe_interval_ms.max(1_000));
+            let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                client.probe().await;
+            }
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl SwqosClientTrait for AutoRegionClient {
+    async fn send_transaction(&self, trade_type: TradeType, transaction: &VersionedTransaction) -> Result<()> {
+        self.current().send_transaction(trade_type, transaction).await
+    }
+
+    async fn send_transactions(&self, trade_type: TradeType, transactions: &Vec<VersionedTransaction>) -> Result<()> {
+        self.current().send_transactions(trade_type, transactions).await
+    }
+
+    fn get_tip_account(&self) -> Result<String> {
+        self.current().get_tip_account()
+    }
+
+    fn get_swqos_type(&self) -> SwqosType {
+        self.swqos_type.clone()
+    }
+
+    async fn ping(&self) -> Result<()> {
+        self.current().ping().await
+    }
+
+    async fn fetch_tip_accounts(&self) -> Result<Vec<Pubkey>> {
+        self.current().fetch_tip_accounts().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    #[test]
+    fn test_fastest_region_skips_failures() {
+        let latencies = vec![
+            Ok(Duration::from_millis(40)),
+            Err(anyhow!("timeout")),
+            Ok(Duration::from_millis(15)),
+            Ok(Duration::from_millis(90)),
+        ];
+        assert_eq!(fastest_region(&latencies), Some(2));
+        assert_eq!(fastest_region(&[Err(anyhow!("timeout"))]), None);
+    }
+
+    #[test]
+    fn test_region_endpoints_are_distinct() {
+        let endpoints = region_endpoints(&SwqosType::Jito);
+        assert!(!endpoints.is_empty());
+        for (index, (_, endpoint)) in endpoints.iter().enumerate() {
+            assert!(endpoints[index + 1..].iter().all(|(_, other)| other != endpoint));
+        }
+        assert!(region_endpoints(&SwqosType::Default).is_empty());
+    }
+}
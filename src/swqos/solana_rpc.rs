@@ -7,7 +7,7 @@ use solana_sdk::{
 };
 use solana_transaction_status::UiTransactionEncoding;
 
-use crate::{common::SolanaRpcClient, swqos::{common::poll_transaction_confirmation, SwqosType, TradeType}};
+use crate::{common::SolanaRpcClient, swqos::{common::poll_transaction_confirmation, metrics::SwqosMetrics, SwqosType, TradeType}};
 use crate::swqos::SwqosClientTrait;
 use anyhow::Result;
 
@@ -19,19 +19,21 @@ pub struct SolRpcClient {
 #[async_trait::async_trait]
 impl SwqosClientTrait for SolRpcClient {
     async fn send_transaction(&self, trade_type: TradeType, transaction: &VersionedTransaction) -> Result<()> {
-        let signature = self.rpc_client.send_transaction_with_config(transaction, RpcSendTransactionConfig{
+        let start_time = Instant::now();
+        let result = self.rpc_client.send_transaction_with_config(transaction, RpcSendTransactionConfig{
             skip_preflight: true,
             preflight_commitment: Some(CommitmentLevel::Processed),
             encoding: Some(UiTransactionEncoding::Base64),
             max_retries: Some(3),
             min_context_slot: Some(0),
-        }).await?;
+        }).await;
+        let metrics = SwqosMetrics::get_instance();
+        metrics.record_submission(SwqosType::Default, start_time.elapsed(), result.is_ok());
+        let signature = result?;
 
         let start_time = Instant::now();
-        match poll_transaction_confirmation(&self.rpc_client, signature).await {
-            Ok(_) => (),
-            Err(_) => (),
-        }
+        let landed = poll_transaction_confirmation(&self.rpc_client, signature).await.is_ok();
+        metrics.record_landing(SwqosType::Default, landed);
         println!(" signature: {:?}", signature);
         println!(" rpc{}确认: {:?}", trade_type, start_time.elapsed());
 
@@ -52,6 +54,11 @@ impl SwqosClientTrait for SolRpcClient {
     fn get_swqos_type(&self) -> SwqosType {
         SwqosType::Default
     }
+
+    async fn ping(&self) -> Result<()> {
+        self.rpc_client.get_version().await?;
+        Ok(())
+    }
 }
 
 impl SolRpcClient {
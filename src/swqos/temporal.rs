@@ -1,6 +1,6 @@
 
-use crate::swqos::common::{poll_transaction_confirmation, serialize_transaction_and_encode};
-use rand::seq::IndexedRandom;
+use crate::swqos::metrics::{send_and_record, SwqosMetrics};
+use crate::swqos::common::{poll_transaction_confirmation, serialize_transaction_and_encode, ping_endpoint};
 use reqwest::Client;
 use serde_json::json;
 use std::{sync::Arc, time::Instant};
@@ -12,7 +12,7 @@ use solana_sdk::transaction::VersionedTransaction;
 use crate::swqos::{SwqosType, TradeType};
 use crate::swqos::SwqosClientTrait;
 
-use crate::{common::SolanaRpcClient, constants::swqos::NOZOMI_TIP_ACCOUNTS};
+use crate::{common::{tip_cache::TipCache, SolanaRpcClient}, constants::swqos::NOZOMI_TIP_ACCOUNTS};
 
 
 #[derive(Clone)]
@@ -34,13 +34,21 @@ impl SwqosClientTrait for TemporalClient {
     }
 
     fn get_tip_account(&self) -> Result<String> {
-        let tip_account = *NOZOMI_TIP_ACCOUNTS.choose(&mut rand::rng()).or_else(|| NOZOMI_TIP_ACCOUNTS.first()).unwrap();
+        let tip_account = TipCache::get_instance()
+            .random_tip_account(&SwqosType::Temporal, NOZOMI_TIP_ACCOUNTS)
+            .ok_or_else(|| anyhow::anyhow!("no valid tip accounts found"))?;
         Ok(tip_account.to_string())
     }
 
     fn get_swqos_type(&self) -> SwqosType {
         SwqosType::Temporal
     }
+
+    async fn ping(&self) -> Result<()> {
+        let url = format!("{}/?c={}", self.endpoint, self.auth_token);
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": "getHealth" });
+        ping_endpoint(self.http_client.post(&url).body(body.to_string())).await
+    }
 }
 
 impl TemporalClient {
@@ -79,13 +87,10 @@ impl TemporalClient {
         url.push_str("/?c=");
         url.push_str(&self.auth_token);
 
-        let response_text = self.http_client.post(&url)
+        let request = self.http_client.post(&url)
             .body(request_body)
-            .header("Content-Type", "application/json")
-            .send()
-            .await?
-            .text()
-            .await?;
+            .header("Content-Type", "application/json");
+        let response_text = send_and_record(SwqosType::Temporal, request).await?;
 
         if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
             if response_json.get("result").is_some() {
@@ -96,10 +101,8 @@ impl TemporalClient {
         }
 
         let start_time: Instant = Instant::now();
-        match poll_transaction_confirmation(&self.rpc_client, signature).await {
-            Ok(_) => (),
-            Err(_) => (),
-        }
+        let landed = poll_transaction_confirmation(&self.rpc_client, signature).await.is_ok();
+        SwqosMetrics::get_instance().record_landing(SwqosType::Temporal, landed);
 
         println!(" nozomi{}确认: {:?}", trade_type, start_time.elapsed());
 
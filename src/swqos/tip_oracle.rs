@@ -0,0 +1,165 @@
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use reqwest::Client;
+use serde::Deserialize;
+use tokio::task::JoinHandle;
+
+use crate::{common::tip_cache::TipCache, constants::swqos::JITO_TIP_FLOOR_URL};
+
+/// 小费百分位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TipPercentile {
+    P25,
+    P50,
+    P75,
+    P95,
+    P99,
+    /// 50 百分位的指数移动平均
+    Ema50,
+}
+
+/// Jito tip floor 接口返回的最近落地小费分布（单位 SOL）
+#[derive(Debug, Clone, Deserialize)]
+pub struct TipFloor {
+    pub landed_tips_25th_percentile: f64,
+    pub landed_tips_50th_percentile: f64,
+    pub landed_tips_75th_percentile: f64,
+    pub landed_tips_95th_percentile: f64,
+    pub landed_tips_99th_percentile: f64,
+    pub ema_landed_tips_50th_percentile: f64,
+}
+
+impl TipFloor {
+    pub fn get(&self, percentile: TipPercentile) -> f64 {
+        match percentile {
+            TipPercentile::P25 => self.landed_tips_25th_percentile,
+            TipPercentile::P50 => self.landed_tips_50th_percentile,
+            TipPercentile::P75 => self.landed_tips_75th_percentile,
+            TipPercentile::P95 => self.landed_tips_95th_percentile,
+            TipPercentile::P99 => self.landed_tips_99th_percentile,
+            TipPercentile::Ema50 => self.ema_landed_tips_50th_percentile,
+        }
+    }
+}
+
+/// 自动小费配置
+#[derive(Debug, Clone)]
+pub struct TipOracleConfig {
+    pub percentile: TipPercentile,
+    /// 刷新间隔（毫秒）
+    pub refresh_interval_ms: u64,
+    /// 小费下限（SOL）
+    pub min_tip_fee: f64,
+    /// 小费上限（SOL）
+    pub max_tip_fee: f64,
+}
+
+impl Default for TipOracleConfig {
+    fn default() -> Self {
+        Self { percentile: TipPercentile::P75, refresh_interval_ms: 2_000, min_tip_fee: 0.0001, max_tip_fee: 0.01 }
+    }
+}
+
+/// 后台刷新任务，进程内只保留一个
+static TIP_ORACLE_TASK: OnceLock<Mutex<Option<JoinHandle<()>>>> = OnceLock::new();
+
+/// Jito 小费预言机，定时拉取 tip floor 并写入 TipCache
+pub struct JitoTipOracle {
+    config: TipOracleConfig,
+    http_client: Client,
+}
+
+impl JitoTipOracle {
+    pub fn new(config: TipOracleConfig) -> Self {
+        let http_client = Client::builder()
+            .timeout(Duration::from_secs(5))
+            .connect_timeout(Duration::from_secs(3))
+            .build()
+            .unwrap();
+        Self { config, http_client }
+    }
+
+    /// 拉取最新的 tip floor
+    pub async fn fetch_tip_floor(&self) -> Result<TipFloor> {
+        let floors: Vec<TipFloor> =
+            self.http_client.get(JITO_TIP_FLOOR_URL).send().await?.json().await?;
+        floors.into_iter().next().ok_or_else(|| anyhow!("Empty tip floor response"))
+    }
+
+    /// 按配置的百分位给出建议小费（SOL），已按上下限截断
+    pub async fn suggest_tip(&self) -> Result<f64> {
+        let tip_floor = self.fetch_tip_floor().await?;
+        Ok(self.tip_from_floor(&tip_floor))
+    }
+
+    /// 从 tip floor 中取配置的百分位，并按上下限截断
+    pub fn tip_from_floor(&self, tip_floor: &TipFloor) -> f64 {
+        tip_floor.get(self.config.percentile).max(self.config.min_tip_fee).min(self.config.max_tip_fee)
+    }
+
+    /// 刷新一次 TipCache
+    pub async fn refresh(&self) -> Result<f64> {
+        let tip = self.suggest_tip().await?;
+        TipCache::get_instance().update_tip(tip);
+        Ok(tip)
+    }
+
+    /// 启动后台任务，定时刷新 TipCache
+    ///
+    /// TipCache 是进程内共享的，因此同时只运行一个刷新任务：再次启动时先停止之前的任务，
+    /// 以最新的配置为准，重复创建 `SolanaTrade` 不会累积任务
+    pub fn start(self) {
+        let task = tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(Duration::from_millis(self.config.refresh_interval_ms.max(100)));
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.refresh().await {
+                    println!("刷新 Jito 小费失败: {}", e);
+                }
+            }
+        });
+        let mut current = TIP_ORACLE_TASK.get_or_init(|| Mutex::new(None)).lock().unwrap();
+        if let Some(previous) = current.replace(task) {
+            previous.abort();
+        }
+    }
+
+    /// 停止后台刷新任务，TipCache 保留最后一次的小费
+    pub fn stop() {
+        if let Some(task) = TIP_ORACLE_TASK.get().and_then(|task| task.lock().unwrap().take()) {
+            task.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tip_from_floor() {
+        let body = r#"[{
+            "time": "2025-01-01T00:00:00Z",
+            "landed_tips_25th_percentile": 0.00001,
+            "landed_tips_50th_percentile": 0.00005,
+            "landed_tips_75th_percentile": 0.0004,
+            "landed_tips_95th_percentile": 0.003,
+            "landed_tips_99th_percentile": 0.05,
+            "ema_landed_tips_50th_percentile": 0.00006
+        }]"#;
+        let floors: Vec<TipFloor> = serde_json::from_str(body).unwrap();
+        let tip_floor = &floors[0];
+        assert_eq!(tip_floor.get(TipPercentile::Ema50), 0.00006);
+
+        let oracle = |percentile| JitoTipOracle::new(TipOracleConfig { percentile, ..Default::default() });
+        assert_eq!(oracle(TipPercentile::P75).tip_from_floor(tip_floor), 0.0004);
+        assert_eq!(oracle(TipPercentile::P95).tip_from_floor(tip_floor), 0.003);
+        // 截断到 [min_tip_fee, max_tip_fee]
+        assert_eq!(oracle(TipPercentile::P25).tip_from_floor(tip_floor), 0.0001);
+        assert_eq!(oracle(TipPercentile::P99).tip_from_floor(tip_floor), 0.01);
+    }
+}
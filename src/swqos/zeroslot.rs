@@ -1,5 +1,5 @@
-use crate::swqos::common::{poll_transaction_confirmation, serialize_transaction_and_encode};
-use rand::seq::IndexedRandom;
+use crate::swqos::metrics::{send_and_record, SwqosMetrics};
+use crate::swqos::common::{poll_transaction_confirmation, serialize_transaction_and_encode, ping_endpoint};
 use reqwest::Client;
 use serde_json::json;
 use std::{sync::Arc, time::Instant};
@@ -12,7 +12,7 @@ use solana_sdk::transaction::VersionedTransaction;
 use crate::swqos::{SwqosType, TradeType};
 use crate::swqos::SwqosClientTrait;
 
-use crate::{common::SolanaRpcClient, constants::swqos::ZEROSLOT_TIP_ACCOUNTS};
+use crate::{common::{tip_cache::TipCache, SolanaRpcClient}, constants::swqos::ZEROSLOT_TIP_ACCOUNTS};
 
 
 #[derive(Clone)]
@@ -34,13 +34,21 @@ impl SwqosClientTrait for ZeroSlotClient {
     }
 
     fn get_tip_account(&self) -> Result<String> {
-        let tip_account = *ZEROSLOT_TIP_ACCOUNTS.choose(&mut rand::rng()).or_else(|| ZEROSLOT_TIP_ACCOUNTS.first()).unwrap();
+        let tip_account = TipCache::get_instance()
+            .random_tip_account(&SwqosType::ZeroSlot, ZEROSLOT_TIP_ACCOUNTS)
+            .ok_or_else(|| anyhow::anyhow!("no valid tip accounts found"))?;
         Ok(tip_account.to_string())
     }
 
     fn get_swqos_type(&self) -> SwqosType {
         SwqosType::ZeroSlot
     }
+
+    async fn ping(&self) -> Result<()> {
+        let url = format!("{}/?api-key={}", self.endpoint, self.auth_token);
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": "getHealth" });
+        ping_endpoint(self.http_client.post(&url).body(body.to_string())).await
+    }
 }
 
 impl ZeroSlotClient {
@@ -79,13 +87,10 @@ impl ZeroSlotClient {
         url.push_str(&self.auth_token);
 
         // 4. 直接使用 `text().await?`，避免 `json().await?` 的异步 JSON 解析
-        let response_text = self.http_client.post(&url)
+        let request = self.http_client.post(&url)
             .body(request_body) // 直接传字符串，避免 `json()` 开销
-            .header("Content-Type", "application/json") // 显式指定 JSON 头
-            .send()
-            .await?
-            .text()
-            .await?;
+            .header("Content-Type", "application/json"); // 显式指定 JSON 头
+        let response_text = send_and_record(SwqosType::ZeroSlot, request).await?;
 
         // 5. 用 `serde_json::from_str()` 解析 JSON，减少 `.json().await?` 额外等待
         if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
@@ -97,10 +102,8 @@ impl ZeroSlotClient {
         }
 
         let start_time: Instant = Instant::now();
-        match poll_transaction_confirmation(&self.rpc_client, signature).await {
-            Ok(_) => (),
-            Err(_) => (),
-        }
+        let landed = poll_transaction_confirmation(&self.rpc_client, signature).await.is_ok();
+        SwqosMetrics::get_instance().record_landing(SwqosType::ZeroSlot, landed);
 
         println!(" 0slot{}确认: {:?}", trade_type, start_time.elapsed());
 
@@ -0,0 +1,242 @@
+use anyhow::{anyhow, Result};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    common::SolanaRpcClient,
+    constants::{pumpswap, raydium_cpmm},
+    trading::{
+        common::token_program::get_mint_token_info,
+        factory::DexType,
+        pumpswap::pool::Pool as PumpSwapPool,
+        raydium_cpmm::{
+            common::{get_pool_pda as get_cpmm_pool_pda, get_pool_token_balances},
+            pool::Pool as CpmmPool,
+        },
+    },
+};
+
+/// PumpSwap 单边手续费（基点）：LP + 协议 + 创作者
+pub const PUMPSWAP_FEE_BASIS_POINTS: u64 = pumpswap::accounts::LP_FEE_BASIS_POINTS
+    + pumpswap::accounts::PROTOCOL_FEE_BASIS_POINTS
+    + pumpswap::accounts::COIN_CREATOR_FEE_BASIS_POINTS;
+/// Raydium CPMM 默认 AMM 配置的单边手续费（基点），即 0.25%
+pub const RAYDIUM_CPMM_FEE_BASIS_POINTS: u64 = 25;
+
+/// 套利检测配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArbitrageConfig {
+    /// 扣除两边手续费后的最小价差（基点），低于该值不产生信号
+    pub min_profit_basis_points: u64,
+}
+
+impl Default for ArbitrageConfig {
+    fn default() -> Self {
+        Self { min_profit_basis_points: 50 }
+    }
+}
+
+/// 单个协议上的 WSOL 池子报价
+#[derive(Debug, Clone, PartialEq)]
+pub struct VenueQuote {
+    pub dex_type: DexType,
+    pub pool: Pubkey,
+    /// 池子中的 SOL 储备（lamports）
+    pub sol_reserve: u64,
+    /// 池子中的代币储备（最小单位）
+    pub token_reserve: u64,
+    /// 单边手续费（基点）
+    pub fee_basis_points: u64,
+    /// 代币的 Token-2022 转账手续费率（基点），每次转入或转出代币时收取
+    pub transfer_fee_basis_points: u16,
+}
+
+impl VenueQuote {
+    /// 现价，lamports / 代币最小单位
+    pub fn price(&self) -> f64 {
+        if self.token_reserve == 0 {
+            return 0.0;
+        }
+        self.sol_reserve as f64 / self.token_reserve as f64
+    }
+
+    /// 花费 `sol_amount` lamports 可获得的代币数量，已扣除池子手续费、价格冲击和转账手续费
+    pub fn buy_amount_out(&self, sol_amount: u64) -> u64 {
+        let amount_in = sol_amount as u128 * (10_000 - self.fee_basis_points.min(10_000)) as u128 / 10_000;
+        let denominator = self.sol_reserve as u128 + amount_in;
+        if denominator == 0 {
+            return 0;
+        }
+        let amount_out = self.token_reserve as u128 * amount_in / denominator;
+        (amount_out * (10_000 - self.transfer_fee_basis_points.min(10_000)) as u128 / 10_000) as u64
+    }
+}
+
+/// 跨协议价差信号：在 `buy_venue` 买入、在 `sell_venue` 卖出
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArbitrageSignal {
+    pub mint: Pubkey,
+    pub buy_venue: VenueQuote,
+    pub sell_venue: VenueQuote,
+    /// 两边现价的价差（基点）
+    pub spread_basis_points: f64,
+    /// 扣除两边手续费后的价差（基点）
+    pub net_basis_points: f64,
+}
+
+/// 比较同一代币在 PumpSwap 与 Raydium CPMM 上的价格，价差超过手续费时产生信号
+#[derive(Debug, Clone, Default)]
+pub struct ArbitrageDetector {
+    config: ArbitrageConfig,
+}
+
+impl ArbitrageDetector {
+    pub fn new(config: ArbitrageConfig) -> Self {
+        Self { config }
+    }
+
+    /// 查询两边池子并检测价差，任一池子不存在时返回错误
+    pub async fn check(
+        &self,
+        rpc: &SolanaRpcClient,
+        mint: &Pubkey,
+    ) -> Result<Option<ArbitrageSignal>> {
+        let pumpswap = quote_pumpswap(rpc, mint).await?;
+        let raydium_cpmm = quote_raydium_cpmm(rpc, mint).await?;
+        Ok(self.detect(mint, &pumpswap, &raydium_cpmm))
+    }
+
+    /// 根据两边报价检测价差
+    ///
+    /// 价差按现价计算，未考虑成交数量带来的价格冲击，执行前应按实际数量重新报价
+    pub fn detect(&self, mint: &Pubkey, a: &VenueQuote, b: &VenueQuote) -> Option<ArbitrageSignal> {
+        let (buy_venue, sell_venue) = if a.price() <= b.price() { (a, b) } else { (b, a) };
+        if buy_venue.price() == 0.0 {
+            return None;
+        }
+        let spread_basis_points = (sell_venue.price() / buy_venue.price() - 1.0) * 10_000.0;
+        // 买入到账和卖出转入池子时各收取一次转账手续费
+        let net_basis_points = spread_basis_points
+            - (buy_venue.fee_basis_points + sell_venue.fee_basis_points) as f64
+            - (buy_venue.transfer_fee_basis_points + sell_venue.transfer_fee_basis_points) as f64;
+        if net_basis_points < self.config.min_profit_basis_points as f64 {
+            return None;
+        }
+        Some(ArbitrageSignal {
+            mint: *mint,
+            buy_venue: buy_venue.clone(),
+            sell_venue: sell_venue.clone(),
+            spread_basis_points,
+            net_basis_points,
+        })
+    }
+}
+
+/// PumpSwap 上 `mint` 与 WSOL 池子的报价
+pub async fn quote_pumpswap(rpc: &SolanaRpcClient, mint: &Pubkey) -> Result<VenueQuote> {
+    let (pool_address, pool) = PumpSwapPool::find_by_mint(rpc, mint).await?;
+    let (base_amount, quote_amount) = pool.get_token_balances(rpc).await?;
+    let (sol_reserve, token_reserve) = if pool.quote_mint == pumpswap::accounts::WSOL_TOKEN_ACCOUNT {
+        (quote_amount, base_amount)
+    } else if pool.base_mint == pumpswap::accounts::WSOL_TOKEN_ACCOUNT {
+        (base_amount, quote_amount)
+    } else {
+        return Err(anyhow!("PumpSwap pool {} is not paired with WSOL", pool_address));
+    };
+    let mint_info = get_mint_token_info(rpc, mint).await?;
+    Ok(VenueQuote {
+        dex_type: DexType::PumpSwap,
+        pool: pool_address,
+        sol_reserve,
+        token_reserve,
+        fee_basis_points: PUMPSWAP_FEE_BASIS_POINTS,
+        transfer_fee_basis_points: mint_info.transfer_fee_basis_points(),
+    })
+}
+
+/// 默认 AMM 配置下 Raydium CPMM 上 `mint` 与 WSOL 池子的报价
+pub async fn quote_raydium_cpmm(rpc: &SolanaRpcClient, mint: &Pubkey) -> Result<VenueQuote> {
+    let wsol = raydium_cpmm::accounts::WSOL_TOKEN_ACCOUNT;
+    for (mint0, mint1) in [(&wsol, mint), (mint, &wsol)] {
+        let Some(pool_address) =
+            get_cpmm_pool_pda(&raydium_cpmm::accounts::AMM_CONFIG, mint0, mint1)
+        else {
+            continue;
+        };
+        let Ok(pool) = CpmmPool::fetch_cached(rpc, &pool_address).await else {
+            continue;
+        };
+        let (token0_amount, token1_amount) =
+            get_pool_token_balances(rpc, &pool_address, &pool.token0_mint, &pool.token1_mint)
+                .await?;
+        let (sol_reserve, token_reserve) = if pool.token0_mint == wsol {
+            (token0_amount, token1_amount)
+        } else {
+            (token1_amount, token0_amount)
+        };
+        let mint_info = get_mint_token_info(rpc, mint).await?;
+        return Ok(VenueQuote {
+            dex_type: DexType::RaydiumCpmm,
+            pool: pool_address,
+            sol_reserve,
+            token_reserve,
+            fee_basis_points: RAYDIUM_CPMM_FEE_BASIS_POINTS,
+            transfer_fee_basis_points: mint_info.transfer_fee_basis_points(),
+        });
+    }
+    Err(anyhow!("No Raydium CPMM WSOL pool found for mint {}", mint))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(dex_type: DexType, sol_reserve: u64, token_reserve: u64, fee_basis_points: u64) -> VenueQuote {
+        VenueQuote {
+            dex_type,
+            pool: Pubkey::new_unique(),
+            sol_reserve,
+            token_reserve,
+            fee_basis_points,
+            transfer_fee_basis_points: 0,
+        }
+    }
+
+    #[test]
+    fn test_detect() {
+        let detector = ArbitrageDetector::new(ArbitrageConfig { min_profit_basis_points: 50 });
+        let mint = Pubkey::new_unique();
+        let pumpswap = quote(DexType::PumpSwap, 100_000, 1_000_000, PUMPSWAP_FEE_BASIS_POINTS);
+
+        // 1% 价差扣除 55 基点手续费后为 45 基点，低于阈值
+        let raydium_cpmm = quote(DexType::RaydiumCpmm, 101_000, 1_000_000, RAYDIUM_CPMM_FEE_BASIS_POINTS);
+        assert!(detector.detect(&mint, &pumpswap, &raydium_cpmm).is_none());
+
+        // 2% 价差：在较便宜的 Raydium CPMM 买入，在 PumpSwap 卖出
+        let raydium_cpmm = quote(DexType::RaydiumCpmm, 98_000, 1_000_000, RAYDIUM_CPMM_FEE_BASIS_POINTS);
+        let signal = detector.detect(&mint, &pumpswap, &raydium_cpmm).unwrap();
+        assert_eq!(signal.buy_venue.dex_type, DexType::RaydiumCpmm);
+        assert_eq!(signal.sell_venue.dex_type, DexType::PumpSwap);
+        assert!((signal.net_basis_points - (signal.spread_basis_points - 55.0)).abs() < 1e-9);
+        assert!(signal.net_basis_points > 50.0);
+
+        // 1% 转账手续费在买卖两端各收一次，吃掉价差
+        let mut pumpswap = pumpswap;
+        let mut raydium_cpmm = raydium_cpmm;
+        pumpswap.transfer_fee_basis_points = 100;
+        raydium_cpmm.transfer_fee_basis_points = 100;
+        assert!(detector.detect(&mint, &pumpswap, &raydium_cpmm).is_none());
+    }
+
+    #[test]
+    fn test_buy_amount_out() {
+        let mut venue = quote(DexType::RaydiumCpmm, 100_000, 1_000_000, 0);
+        // 无手续费时只有价格冲击：1_000_000 * 10_000 / 110_000
+        assert_eq!(venue.buy_amount_out(10_000), 90_909);
+        venue.fee_basis_points = RAYDIUM_CPMM_FEE_BASIS_POINTS;
+        assert_eq!(venue.buy_amount_out(10_000), 90_702);
+        venue.transfer_fee_basis_points = 100;
+        assert_eq!(venue.buy_amount_out(10_000), 89_794);
+        // 按现价估算会高估到账数量
+        assert!(venue.buy_amount_out(10_000) < (10_000.0 / venue.price()) as u64);
+    }
+}
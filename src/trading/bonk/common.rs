@@ -108,6 +108,18 @@ pub fn get_vault_pda(pool_state: &Pubkey, mint: &Pubkey) -> Option<Pubkey> {
     pda.map(|pubkey| pubkey.0)
 }
 
+pub fn get_metadata_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            constants::bonk::seeds::METADATA_SEED,
+            constants::bonk::accounts::MPL_TOKEN_METADATA.as_ref(),
+            mint.as_ref(),
+        ],
+        &constants::bonk::accounts::MPL_TOKEN_METADATA,
+    )
+    .0
+}
+
 pub fn get_token_price(
     virtual_base: u128,
     virtual_quote: u128,
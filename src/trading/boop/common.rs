@@ -0,0 +1,106 @@
+use solana_sdk::pubkey::Pubkey;
+
+use crate::constants::boop::{accounts, seeds};
+
+use super::curve::BondingCurve;
+
+fn find_pda(seeds: &[&[u8]]) -> Option<Pubkey> {
+    let program_id: &Pubkey = &accounts::BOOP;
+    let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
+    pda.map(|pubkey| pubkey.0)
+}
+
+pub fn get_config_pda() -> Option<Pubkey> {
+    find_pda(&[seeds::CONFIG_SEED])
+}
+
+pub fn get_vault_authority_pda() -> Option<Pubkey> {
+    find_pda(&[seeds::VAULT_AUTHORITY_SEED])
+}
+
+pub fn get_bonding_curve_pda(mint: &Pubkey) -> Option<Pubkey> {
+    find_pda(&[seeds::BONDING_CURVE_SEED, mint.as_ref()])
+}
+
+pub fn get_bonding_curve_vault_pda(mint: &Pubkey) -> Option<Pubkey> {
+    find_pda(&[seeds::BONDING_CURVE_VAULT_SEED, mint.as_ref()])
+}
+
+pub fn get_bonding_curve_sol_vault_pda(mint: &Pubkey) -> Option<Pubkey> {
+    find_pda(&[seeds::BONDING_CURVE_SOL_VAULT_SEED, mint.as_ref()])
+}
+
+pub fn get_trading_fees_vault_pda(mint: &Pubkey) -> Option<Pubkey> {
+    find_pda(&[seeds::TRADING_FEES_VAULT_SEED, mint.as_ref()])
+}
+
+/// 当前储备 (代币, SOL)，SOL 一侧包含虚拟储备
+fn reserves(curve: &BondingCurve) -> (u128, u128) {
+    (
+        curve.token_reserves as u128,
+        curve.virtual_sol_reserves as u128 + curve.sol_reserves as u128,
+    )
+}
+
+/// 用 `sol_amount` lamports 可买入的代币数量，手续费从输入中扣除
+pub fn get_buy_token_amount_from_sol_amount(curve: &BondingCurve, sol_amount: u64) -> u64 {
+    let (token_reserves, sol_reserves) = reserves(curve);
+    if token_reserves == 0 {
+        return 0;
+    }
+    let amount_in =
+        sol_amount as u128 * (10_000 - curve.swap_fee_basis_points as u128) / 10_000;
+    let new_token_reserves = token_reserves * sol_reserves / (sol_reserves + amount_in);
+    (token_reserves - new_token_reserves) as u64
+}
+
+/// 卖出 `token_amount` 个代币可获得的 lamports，已扣除手续费，不超过实际 SOL 储备
+pub fn get_sell_sol_amount_from_token_amount(curve: &BondingCurve, token_amount: u64) -> u64 {
+    let (token_reserves, sol_reserves) = reserves(curve);
+    let new_sol_reserves = token_reserves * sol_reserves / (token_reserves + token_amount as u128);
+    let amount_out = (sol_reserves - new_sol_reserves).min(curve.sol_reserves as u128);
+    (amount_out * (10_000 - curve.swap_fee_basis_points as u128) / 10_000) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve(sol_reserves: u64, token_reserves: u64) -> BondingCurve {
+        BondingCurve {
+            creator: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            virtual_sol_reserves: 30_000_000_000,
+            virtual_token_reserves: 1_073_000_000_000_000,
+            graduation_target: 85_000_000_000,
+            graduation_fee: 0,
+            sol_reserves,
+            token_reserves,
+            damping_term: 0,
+            swap_fee_basis_points: 100,
+            token_for_stakers_basis_points: 0,
+            status: 0,
+        }
+    }
+
+    #[test]
+    fn test_buy_then_sell_loses_only_fees() {
+        let curve = curve(0, 1_000_000_000_000_000);
+        let tokens = get_buy_token_amount_from_sol_amount(&curve, 1_000_000_000);
+        assert!(tokens > 0);
+
+        let mut after_buy = curve.clone();
+        after_buy.token_reserves -= tokens;
+        after_buy.sol_reserves += 990_000_000;
+        let sol_out = get_sell_sol_amount_from_token_amount(&after_buy, tokens);
+        // 两次 1% 手续费
+        assert!(sol_out < 1_000_000_000 * 99 / 100);
+        assert!(sol_out > 1_000_000_000 * 97 / 100);
+    }
+
+    #[test]
+    fn test_sell_is_capped_by_sol_reserves() {
+        let curve = curve(1_000, 1_000_000_000_000_000);
+        assert!(get_sell_sol_amount_from_token_amount(&curve, 500_000_000_000_000) <= 1_000);
+    }
+}
@@ -0,0 +1,55 @@
+use crate::{
+    common::SolanaRpcClient,
+    constants::boop::{accounts, BONDING_CURVE_DISCRIMINATOR, STATUS_TRADING},
+};
+use anyhow::anyhow;
+use borsh::BorshDeserialize;
+use solana_sdk::pubkey::Pubkey;
+
+use super::common::get_bonding_curve_pda;
+
+/// Boop.fun bonding curve 账户
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct BondingCurve {
+    pub creator: Pubkey,
+    pub mint: Pubkey,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    /// 毕业所需的 SOL 数量（lamports）
+    pub graduation_target: u64,
+    pub graduation_fee: u64,
+    pub sol_reserves: u64,
+    pub token_reserves: u64,
+    pub damping_term: u8,
+    pub swap_fee_basis_points: u16,
+    pub token_for_stakers_basis_points: u16,
+    pub status: u8,
+}
+
+impl BondingCurve {
+    pub fn from_bytes(data: &[u8]) -> Result<Self, anyhow::Error> {
+        if data.len() < 8 || data[..8] != BONDING_CURVE_DISCRIMINATOR {
+            return Err(anyhow!("Invalid Boop bonding curve account"));
+        }
+        // 账户末尾可能有预留空间，只解析已知字段
+        let curve = BondingCurve::deserialize(&mut &data[8..])?;
+        Ok(curve)
+    }
+
+    pub async fn fetch(rpc: &SolanaRpcClient, mint: &Pubkey) -> Result<Self, anyhow::Error> {
+        let curve_address = get_bonding_curve_pda(mint)
+            .ok_or_else(|| anyhow!("Failed to derive bonding curve account"))?;
+        let account = rpc.get_account(&curve_address).await?;
+
+        if account.owner != accounts::BOOP {
+            return Err(anyhow!("Account is not owned by Boop program"));
+        }
+
+        Self::from_bytes(&account.data)
+    }
+
+    /// 曲线是否仍可交易（未毕业）
+    pub fn is_trading(&self) -> bool {
+        self.status == STATUS_TRADING
+    }
+}
@@ -1,9 +1,17 @@
+use anyhow::Result;
+use std::collections::HashSet;
+use solana_hash::Hash;
 use solana_sdk::{
-    message::AddressLookupTableAccount,
+    instruction::Instruction,
+    message::{v0, AddressLookupTableAccount, VersionedMessage},
+    packet::PACKET_DATA_SIZE,
     pubkey::Pubkey,
+    signature::SIGNATURE_BYTES,
 };
 
-use crate::common::address_lookup_cache::get_address_lookup_table_account;
+use crate::common::address_lookup_cache::{
+    get_address_lookup_table_account, AddressLookupTableCache,
+};
 
 /// 获取地址查找表账户列表
 /// 如果提供了lookup_table_key，则获取对应的账户，否则返回空列表
@@ -18,4 +26,146 @@ pub async fn get_address_lookup_table_accounts(
     }
     
     address_lookup_table_accounts
-}
\ No newline at end of file
+}
+
+/// 获取 `AddressLookupTableCache` 中所有已加载内容的地址表
+pub fn get_cached_address_lookup_table_accounts() -> Vec<AddressLookupTableAccount> {
+    let cache = AddressLookupTableCache::get_instance();
+    cache
+        .get_all_table_addresses()
+        .iter()
+        .map(|key| cache.get_table_content(key))
+        .filter(|table| !table.addresses.is_empty())
+        .collect()
+}
+
+/// 从候选地址表中选出使交易序列化大小最小的组合并编译消息
+///
+/// 每轮尝试加入一个剩余的地址表，保留使消息最小的一个，直到不再变小。
+/// 引用地址表本身需要 34 字节，只覆盖一两个账户的表不会被选中。
+/// 编译后的交易超过数据包上限时打印警告。
+pub fn compile_message_with_optimal_lookup_tables(
+    payer: &Pubkey,
+    instructions: &[Instruction],
+    candidates: &[AddressLookupTableAccount],
+    blockhash: Hash,
+) -> Result<v0::Message> {
+    let mut candidates: Vec<&AddressLookupTableAccount> =
+        candidates.iter().filter(|table| !table.addresses.is_empty()).collect();
+    let mut seen = HashSet::new();
+    candidates.retain(|table| seen.insert(table.key));
+
+    let mut selected: Vec<AddressLookupTableAccount> = vec![];
+    let mut message = v0::Message::try_compile(payer, instructions, &selected, blockhash)?;
+    let mut size = message_size(&message)?;
+
+    while !candidates.is_empty() {
+        let mut best: Option<(usize, v0::Message, usize)> = None;
+        for (i, table) in candidates.iter().enumerate() {
+            let mut tables = selected.clone();
+            tables.push((*table).clone());
+            let candidate = v0::Message::try_compile(payer, instructions, &tables, blockhash)?;
+            let candidate_size = message_size(&candidate)?;
+            if candidate_size < best.as_ref().map_or(size, |(_, _, best_size)| *best_size) {
+                best = Some((i, candidate, candidate_size));
+            }
+        }
+        let Some((i, best_message, best_size)) = best else {
+            break;
+        };
+        selected.push(candidates.remove(i).clone());
+        message = best_message;
+        size = best_size;
+    }
+
+    let transaction_size = transaction_size(&message, size);
+    if transaction_size > PACKET_DATA_SIZE {
+        if selected.is_empty() {
+            println!(
+                "警告: 交易大小 {} 字节超过数据包上限 {} 字节，且没有可用的地址查找表",
+                transaction_size, PACKET_DATA_SIZE
+            );
+        } else {
+            println!(
+                "警告: 使用 {} 个地址查找表后交易大小 {} 字节仍超过数据包上限 {} 字节",
+                selected.len(),
+                transaction_size,
+                PACKET_DATA_SIZE
+            );
+        }
+    }
+
+    Ok(message)
+}
+
+fn message_size(message: &v0::Message) -> Result<usize> {
+    Ok(bincode::serialized_size(&VersionedMessage::V0(message.clone()))? as usize)
+}
+
+/// 签名后的交易大小，签名数量按 compact-u16 编码
+fn transaction_size(message: &v0::Message, message_size: usize) -> usize {
+    let num_signatures = message.header.num_required_signatures as usize;
+    let length_prefix = if num_signatures < 0x80 { 1 } else { 2 };
+    length_prefix + num_signatures * SIGNATURE_BYTES + message_size
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::instruction::AccountMeta;
+
+    fn instruction(accounts: &[Pubkey]) -> Instruction {
+        Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[0],
+            accounts.iter().map(|key| AccountMeta::new(*key, false)).collect(),
+        )
+    }
+
+    #[test]
+    fn test_selects_only_useful_tables() {
+        let payer = Pubkey::new_unique();
+        let accounts: Vec<Pubkey> = (0..6).map(|_| Pubkey::new_unique()).collect();
+        let instructions = vec![instruction(&accounts)];
+        let useful = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: accounts.clone(),
+        };
+        let single = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: vec![Pubkey::new_unique(), accounts[0]],
+        };
+        let unrelated = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: (0..10).map(|_| Pubkey::new_unique()).collect(),
+        };
+
+        let message = compile_message_with_optimal_lookup_tables(
+            &payer,
+            &instructions,
+            &[single, unrelated, useful.clone()],
+            Hash::default(),
+        )
+        .unwrap();
+
+        assert_eq!(message.address_table_lookups.len(), 1);
+        assert_eq!(message.address_table_lookups[0].account_key, useful.key);
+    }
+
+    #[test]
+    fn test_skips_table_that_does_not_save_space() {
+        let payer = Pubkey::new_unique();
+        let account = Pubkey::new_unique();
+        let table = AddressLookupTableAccount { key: Pubkey::new_unique(), addresses: vec![account] };
+
+        let message = compile_message_with_optimal_lookup_tables(
+            &payer,
+            &[instruction(&[account])],
+            &[table],
+            Hash::default(),
+        )
+        .unwrap();
+
+        assert!(message.address_table_lookups.is_empty());
+    }
+}
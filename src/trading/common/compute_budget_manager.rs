@@ -1,6 +1,17 @@
-use solana_sdk::{compute_budget::ComputeBudgetInstruction, instruction::Instruction};
+use anyhow::anyhow;
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_hash::Hash;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, compute_budget::ComputeBudgetInstruction,
+    instruction::Instruction, pubkey::Pubkey, signature::Keypair,
+};
+use std::sync::Arc;
 
-use crate::common::PriorityFee;
+use super::transaction_builder::build_rpc_transaction;
+use crate::common::{PriorityFee, SolanaRpcClient};
+
+/// 单笔交易允许的最大计算单元
+const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
 
 /// 为RPC交易添加计算预算指令
 pub fn add_rpc_compute_budget_instructions(
@@ -71,3 +82,64 @@ pub fn add_sell_tip_compute_budget_instructions(
         priority_fee.unit_limit,
     ));
 }
+
+/// 模拟交易获取实际消耗的计算单元，返回 消耗量 × 安全系数
+#[allow(clippy::too_many_arguments)]
+pub async fn simulate_compute_unit_limit(
+    rpc: &SolanaRpcClient,
+    payer: Arc<Keypair>,
+    fee_payer: Option<Arc<Keypair>>,
+    priority_fee: &PriorityFee,
+    business_instructions: Vec<Instruction>,
+    lookup_table_key: Option<Pubkey>,
+    recent_blockhash: Hash,
+    data_size_limit: u32,
+    margin: f64,
+) -> Result<u32, anyhow::Error> {
+    // 模拟时使用最大上限，避免静态上限过低导致模拟失败
+    let mut simulate_fee = priority_fee.clone();
+    simulate_fee.rpc_unit_limit = MAX_COMPUTE_UNIT_LIMIT;
+    let transaction = build_rpc_transaction(
+        payer,
+        fee_payer,
+        &simulate_fee,
+        business_instructions,
+        lookup_table_key,
+        recent_blockhash,
+        data_size_limit,
+    )
+    .await?;
+
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        commitment: Some(CommitmentConfig::processed()),
+        ..Default::default()
+    };
+    let result = rpc.simulate_transaction_with_config(&transaction, config).await?.value;
+    if let Some(err) = result.err {
+        return Err(anyhow!("Simulation failed: {:?}, logs: {:?}", err, result.logs));
+    }
+    let units_consumed =
+        result.units_consumed.ok_or_else(|| anyhow!("Simulation returned no units consumed"))?;
+
+    Ok(compute_unit_limit_with_margin(units_consumed, margin))
+}
+
+/// 按 消耗量 × 安全系数 计算计算单元上限，向上取整且不超过单笔交易的最大值
+pub fn compute_unit_limit_with_margin(units_consumed: u64, margin: f64) -> u32 {
+    ((units_consumed as f64 * margin).ceil() as u64).min(MAX_COMPUTE_UNIT_LIMIT as u64) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_unit_limit_with_margin() {
+        assert_eq!(compute_unit_limit_with_margin(100_000, 1.2), 120_000);
+        // 向上取整，保证上限不低于 消耗量 × 系数
+        assert_eq!(compute_unit_limit_with_margin(12_345, 1.1), 13_580);
+        assert_eq!(compute_unit_limit_with_margin(80_000, 1.0), 80_000);
+        assert_eq!(compute_unit_limit_with_margin(1_300_000, 1.5), MAX_COMPUTE_UNIT_LIMIT);
+    }
+}
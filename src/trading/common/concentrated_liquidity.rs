@@ -0,0 +1,62 @@
+use anyhow::anyhow;
+
+/// 费率分母，Raydium CLMM 与 Orca Whirlpool 的费率单位均为百万分之一
+pub const FEE_RATE_DENOMINATOR: u64 = 1_000_000;
+
+const Q64: f64 = 18_446_744_073_709_551_616.0;
+
+/// 将 Q64.64 格式的 sqrt 价格转换为 token0 以 token1 计价的价格（已考虑精度）
+pub fn sqrt_price_x64_to_price(sqrt_price_x64: u128, mint_decimals_0: u8, mint_decimals_1: u8) -> f64 {
+    let sqrt_price = sqrt_price_x64 as f64 / Q64;
+    sqrt_price * sqrt_price * 10f64.powi(mint_decimals_0 as i32 - mint_decimals_1 as i32)
+}
+
+/// 按当前价格区间的流动性估算输出数量，输入数量先扣除交易手续费
+///
+/// 假设成交不跨越已初始化的 tick。跨越 tick 时流动性会变化，大额交易应自行设置最小输出。
+pub fn get_amount_out(
+    liquidity: u128,
+    sqrt_price_x64: u128,
+    amount_in: u64,
+    zero_for_one: bool,
+    fee_rate: u32,
+) -> Result<u64, anyhow::Error> {
+    if liquidity == 0 || sqrt_price_x64 == 0 {
+        return Err(anyhow!("池子流动性为零，无法进行交换"));
+    }
+    let fee = (amount_in as u128 * fee_rate as u128).div_ceil(FEE_RATE_DENOMINATOR as u128);
+    let amount_in = (amount_in as u128).saturating_sub(fee) as f64;
+    let liquidity = liquidity as f64;
+    let sqrt_price = sqrt_price_x64 as f64 / Q64;
+
+    let amount_out = if zero_for_one {
+        // token0 换 token1: sqrt_p' = L * sqrt_p / (L + dx * sqrt_p), dy = L * (sqrt_p - sqrt_p')
+        let next_sqrt_price = liquidity * sqrt_price / (liquidity + amount_in * sqrt_price);
+        liquidity * (sqrt_price - next_sqrt_price)
+    } else {
+        // token1 换 token0: sqrt_p' = sqrt_p + dy / L, dx = L * (1 / sqrt_p - 1 / sqrt_p')
+        let next_sqrt_price = sqrt_price + amount_in / liquidity;
+        liquidity * (1.0 / sqrt_price - 1.0 / next_sqrt_price)
+    };
+    Ok(amount_out.max(0.0).floor() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_amount_out() {
+        // sqrt 价格为 1 时两个方向对称，无手续费的小额交易约等于 1:1
+        let sqrt_price_x64 = 1u128 << 64;
+        let liquidity = 1_000_000_000_000u128;
+        for zero_for_one in [true, false] {
+            let out = get_amount_out(liquidity, sqrt_price_x64, 1_000, zero_for_one, 0).unwrap();
+            assert!((998..=1_000).contains(&out));
+        }
+        // 0.25% 手续费
+        let out = get_amount_out(liquidity, sqrt_price_x64, 1_000_000, true, 2_500).unwrap();
+        assert!((997_000..=997_500).contains(&out));
+        assert_eq!(sqrt_price_x64_to_price(sqrt_price_x64, 9, 6), 1_000.0);
+    }
+}
@@ -0,0 +1,153 @@
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+
+const BASIS_POINTS: u128 = 10_000;
+
+/// 模拟成交使用的价格模型，用于模拟盘和回测
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FillModel {
+    /// 按池子中间价成交，不计价差和价格冲击
+    #[default]
+    MidPrice,
+    /// 按中间价加减半个价差成交，买入更贵、卖出更便宜
+    Spread { spread_bps: u64 },
+    /// 按池子储备的恒定乘积曲线成交，计入价格冲击和手续费
+    ReserveImpact { fee_bps: u64 },
+    /// 按回放数据中下一笔真实成交的价格成交
+    NextTrade,
+}
+
+/// 模拟成交时的市场快照
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct MarketSnapshot {
+    /// 池子 SOL 储备（lamports）
+    pub sol_reserves: u64,
+    /// 池子代币储备（最小单位）
+    pub token_reserves: u64,
+    /// 现价，lamports / 代币最小单位；设置时作为中间价，订单簿等没有储备的市场需要设置
+    pub spot_price: Option<f64>,
+    /// 下一笔成交价格，lamports / 代币最小单位，仅 `FillModel::NextTrade` 使用
+    pub next_trade_price: Option<f64>,
+}
+
+impl MarketSnapshot {
+    /// 中间价，lamports / 代币最小单位，优先使用 `spot_price`
+    pub fn mid_price(&self) -> Result<f64> {
+        if let Some(spot_price) = self.spot_price {
+            return Ok(spot_price);
+        }
+        if self.sol_reserves == 0 || self.token_reserves == 0 {
+            return Err(anyhow!("Pool reserves are empty"));
+        }
+        Ok(self.sol_reserves as f64 / self.token_reserves as f64)
+    }
+}
+
+impl FillModel {
+    /// 模拟用 `sol_amount` lamports 买入，返回成交的代币数量
+    pub fn simulate_buy(&self, snapshot: &MarketSnapshot, sol_amount: u64) -> Result<u64> {
+        match self {
+            FillModel::ReserveImpact { fee_bps } => constant_product_out(
+                snapshot.sol_reserves,
+                snapshot.token_reserves,
+                sol_amount,
+                *fee_bps,
+            ),
+            _ => {
+                let price = self.fill_price(snapshot, true)?;
+                Ok((sol_amount as f64 / price) as u64)
+            }
+        }
+    }
+
+    /// 模拟卖出 `token_amount` 个代币，返回成交的 lamports
+    pub fn simulate_sell(&self, snapshot: &MarketSnapshot, token_amount: u64) -> Result<u64> {
+        match self {
+            FillModel::ReserveImpact { fee_bps } => constant_product_out(
+                snapshot.token_reserves,
+                snapshot.sol_reserves,
+                token_amount,
+                *fee_bps,
+            ),
+            _ => {
+                let price = self.fill_price(snapshot, false)?;
+                Ok((token_amount as f64 * price) as u64)
+            }
+        }
+    }
+
+    /// 与数量无关的成交价格，`ReserveImpact` 不适用
+    fn fill_price(&self, snapshot: &MarketSnapshot, is_buy: bool) -> Result<f64> {
+        let price = match self {
+            FillModel::MidPrice => snapshot.mid_price()?,
+            FillModel::Spread { spread_bps } => {
+                let half_spread = *spread_bps as f64 / BASIS_POINTS as f64 / 2.0;
+                let mid_price = snapshot.mid_price()?;
+                if is_buy {
+                    mid_price * (1.0 + half_spread)
+                } else {
+                    mid_price * (1.0 - half_spread)
+                }
+            }
+            FillModel::NextTrade => snapshot
+                .next_trade_price
+                .ok_or_else(|| anyhow!("No next trade price to replay"))?,
+            FillModel::ReserveImpact { .. } => unreachable!(),
+        };
+        if !price.is_finite() || price <= 0.0 {
+            return Err(anyhow!("Invalid fill price: {}", price));
+        }
+        Ok(price)
+    }
+}
+
+/// 恒定乘积曲线的输出数量，手续费从输入中扣除
+fn constant_product_out(
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_in: u64,
+    fee_bps: u64,
+) -> Result<u64> {
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(anyhow!("Pool reserves are empty"));
+    }
+    let amount_in = amount_in as u128 * (BASIS_POINTS - (fee_bps as u128).min(BASIS_POINTS))
+        / BASIS_POINTS;
+    let amount_out = reserve_out as u128 * amount_in / (reserve_in as u128 + amount_in);
+    Ok(amount_out as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SNAPSHOT: MarketSnapshot = MarketSnapshot {
+        sol_reserves: 100_000_000_000,
+        token_reserves: 1_000_000_000_000,
+        spot_price: None,
+        next_trade_price: Some(0.2),
+    };
+
+    #[test]
+    fn test_fill_models() {
+        assert_eq!(FillModel::MidPrice.simulate_buy(&SNAPSHOT, 1_000_000_000).unwrap(), 10_000_000_000);
+        assert_eq!(FillModel::MidPrice.simulate_sell(&SNAPSHOT, 10_000_000_000).unwrap(), 1_000_000_000);
+
+        let spread = FillModel::Spread { spread_bps: 200 };
+        assert!(spread.simulate_buy(&SNAPSHOT, 1_000_000_000).unwrap() < 10_000_000_000);
+        assert_eq!(spread.simulate_sell(&SNAPSHOT, 10_000_000_000).unwrap(), 990_000_000);
+
+        // 1% 的输入带来约 1% 的价格冲击
+        let impact = FillModel::ReserveImpact { fee_bps: 0 };
+        assert_eq!(impact.simulate_buy(&SNAPSHOT, 1_000_000_000).unwrap(), 9_900_990_099);
+
+        assert_eq!(FillModel::NextTrade.simulate_buy(&SNAPSHOT, 1_000_000_000).unwrap(), 5_000_000_000);
+        let no_replay = MarketSnapshot { next_trade_price: None, ..SNAPSHOT };
+        assert!(FillModel::NextTrade.simulate_buy(&no_replay, 1_000_000_000).is_err());
+
+        // 没有储备的市场按现价成交，但无法计算价格冲击
+        let order_book = MarketSnapshot { spot_price: Some(0.5), ..Default::default() };
+        assert_eq!(FillModel::MidPrice.simulate_sell(&order_book, 1_000).unwrap(), 500);
+        assert!(impact.simulate_buy(&order_book, 1_000).is_err());
+    }
+}
@@ -0,0 +1,236 @@
+use anyhow::{anyhow, Result};
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, UiTransactionEncoding, UiTransactionStatusMeta,
+    UiTransactionTokenBalance,
+};
+
+use super::token_program::{get_mint_token_info, MintTokenInfo};
+use crate::common::SolanaRpcClient;
+
+/// 已确认交易的实际成交结果
+///
+/// 买入时数值为正；卖出时 `tokens_received` 和 `sol_spent` 为负
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FillReport {
+    /// 持仓账户代币余额变化（最小单位）
+    pub tokens_received: i128,
+    /// 持仓账户 SOL 余额变化（lamports），不含网络费用，包含小费及账户租金
+    pub sol_spent: i128,
+    /// 成交均价，SOL / 代币
+    pub price_paid: f64,
+    /// 网络费用（lamports），由交易的 fee payer 支付
+    pub fee_lamports: u64,
+    /// 代币精度
+    pub decimals: u8,
+    /// Token-2022 转账手续费（代币最小单位），买入时从到账数量中扣除，卖出时由接收方承担
+    pub transfer_fee: u64,
+    /// 成交时生效的转账手续费率（基点），非 Token-2022 或无手续费时为 0
+    pub transfer_fee_basis_points: u16,
+}
+
+/// 查询已确认的交易，按交易前后余额计算 `owner` 在 `mint` 上的成交结果
+pub async fn get_fill_report(
+    rpc: &SolanaRpcClient,
+    signature: &Signature,
+    owner: &Pubkey,
+    mint: &Pubkey,
+) -> Result<FillReport> {
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(rpc.commitment()),
+        max_supported_transaction_version: Some(0),
+    };
+    let confirmed = rpc.get_transaction_with_config(signature, config).await?;
+    let transaction = confirmed
+        .transaction
+        .transaction
+        .decode()
+        .ok_or_else(|| anyhow!("Failed to decode transaction {}", signature))?;
+    let meta = confirmed
+        .transaction
+        .meta
+        .ok_or_else(|| anyhow!("Transaction {} has no status meta", signature))?;
+    if let Some(err) = &meta.err {
+        return Err(anyhow!("Transaction {} failed: {:?}", signature, err));
+    }
+    let mint_info = get_mint_token_info(rpc, mint).await?;
+    fill_report_from_meta(transaction.message.static_account_keys(), &meta, owner, mint, &mint_info)
+}
+
+fn fill_report_from_meta(
+    account_keys: &[Pubkey],
+    meta: &UiTransactionStatusMeta,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    mint_info: &MintTokenInfo,
+) -> Result<FillReport> {
+    let owner_index = account_keys
+        .iter()
+        .position(|key| key == owner)
+        .ok_or_else(|| anyhow!("{} is not part of the transaction", owner))?;
+    let pre_lamports = *meta.pre_balances.get(owner_index).unwrap_or(&0) as i128;
+    let post_lamports = *meta.post_balances.get(owner_index).unwrap_or(&0) as i128;
+    // 第一个账户为 fee payer，网络费用不计入成交金额
+    let fee_paid_by_owner = if owner_index == 0 { meta.fee as i128 } else { 0 };
+    let sol_spent = pre_lamports - post_lamports - fee_paid_by_owner;
+
+    let (pre_tokens, pre_decimals) = sum_token_balance(&meta.pre_token_balances, owner, mint)?;
+    let (post_tokens, post_decimals) = sum_token_balance(&meta.post_token_balances, owner, mint)?;
+    let decimals = post_decimals
+        .or(pre_decimals)
+        .ok_or_else(|| anyhow!("No {} balance of {} in the transaction", mint, owner))?;
+    let tokens_received = post_tokens - pre_tokens;
+
+    let price_paid = if tokens_received == 0 {
+        0.0
+    } else {
+        (sol_spent as f64 / LAMPORTS_PER_SOL as f64)
+            / (tokens_received as f64 / 10f64.powi(decimals as i32))
+    };
+
+    // 买入时到账的是扣除手续费后的数量；卖出时转出的数量全额计入余额变化
+    let transfer_fee = if tokens_received > 0 {
+        mint_info.inverse_transfer_fee(tokens_received as u64)
+    } else {
+        mint_info.transfer_fee(tokens_received.unsigned_abs() as u64)
+    };
+
+    Ok(FillReport {
+        tokens_received,
+        sol_spent,
+        price_paid,
+        fee_lamports: meta.fee,
+        decimals,
+        transfer_fee,
+        transfer_fee_basis_points: mint_info.transfer_fee_basis_points(),
+    })
+}
+
+/// 汇总 `owner` 持有的 `mint` 代币账户余额及精度
+fn sum_token_balance(
+    balances: &OptionSerializer<Vec<UiTransactionTokenBalance>>,
+    owner: &Pubkey,
+    mint: &Pubkey,
+) -> Result<(i128, Option<u8>)> {
+    let OptionSerializer::Some(balances) = balances else {
+        return Ok((0, None));
+    };
+    let owner = owner.to_string();
+    let mint = mint.to_string();
+    let mut total = 0i128;
+    let mut decimals = None;
+    for balance in balances {
+        let is_owner = matches!(&balance.owner, OptionSerializer::Some(o) if *o == owner);
+        if balance.mint != mint || !is_owner {
+            continue;
+        }
+        total += balance.ui_token_amount.amount.parse::<u64>()? as i128;
+        decimals = Some(balance.ui_token_amount.decimals);
+    }
+    Ok((total, decimals))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use spl_token_2022::extension::transfer_fee::{TransferFee, TransferFeeConfig};
+
+    fn token_balance(owner: &Pubkey, mint: &Pubkey, amount: u64) -> Value {
+        json!({
+            "accountIndex": 1,
+            "mint": mint.to_string(),
+            "uiTokenAmount": {
+                "uiAmount": null,
+                "decimals": 6,
+                "amount": amount.to_string(),
+                "uiAmountString": "",
+            },
+            "owner": owner.to_string(),
+        })
+    }
+
+    fn meta(
+        pre_lamports: u64,
+        post_lamports: u64,
+        pre_tokens: Vec<Value>,
+        post_tokens: Vec<Value>,
+    ) -> UiTransactionStatusMeta {
+        serde_json::from_value(json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5_000,
+            "preBalances": [pre_lamports, 0],
+            "postBalances": [post_lamports, 0],
+            "preTokenBalances": pre_tokens,
+            "postTokenBalances": post_tokens,
+        }))
+        .unwrap()
+    }
+
+    fn token_2022_with_fee() -> MintTokenInfo {
+        let transfer_fee = TransferFee {
+            transfer_fee_basis_points: 100.into(),
+            maximum_fee: u64::MAX.into(),
+            ..Default::default()
+        };
+        MintTokenInfo {
+            token_program: spl_token_2022::ID,
+            transfer_fee_config: Some(TransferFeeConfig {
+                newer_transfer_fee: transfer_fee,
+                ..Default::default()
+            }),
+            epoch: 1,
+        }
+    }
+
+    #[test]
+    fn test_fill_report_from_meta() {
+        let (owner, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let keys = [owner, Pubkey::new_unique()];
+
+        // 买入 1 SOL，到账 2 个代币；首次买入前没有代币账户余额
+        let buy =
+            meta(3_000_000_000, 1_999_995_000, vec![], vec![token_balance(&owner, &mint, 2_000_000)]);
+        let report =
+            fill_report_from_meta(&keys, &buy, &owner, &mint, &MintTokenInfo::spl_token()).unwrap();
+        assert_eq!((report.tokens_received, report.sol_spent), (2_000_000, 1_000_000_000));
+        assert_eq!((report.fee_lamports, report.decimals), (5_000, 6));
+        assert_eq!(report.price_paid, 0.5);
+        assert_eq!((report.transfer_fee, report.transfer_fee_basis_points), (0, 0));
+
+        // Token-2022 买入到账 990，被扣除 10；卖出转出 1000，接收方承担 10
+        let mint_info = token_2022_with_fee();
+        let buy = meta(1_000_000_000, 999_995_000, vec![], vec![token_balance(&owner, &mint, 990)]);
+        let report = fill_report_from_meta(&keys, &buy, &owner, &mint, &mint_info).unwrap();
+        assert_eq!((report.transfer_fee, report.transfer_fee_basis_points), (10, 100));
+        let sell = meta(
+            1_000_000_000,
+            1_000_095_000,
+            vec![token_balance(&owner, &mint, 1_000)],
+            vec![token_balance(&owner, &mint, 0)],
+        );
+        let report = fill_report_from_meta(&keys, &sell, &owner, &mint, &mint_info).unwrap();
+        assert_eq!((report.tokens_received, report.sol_spent), (-1_000, -100_000));
+        assert_eq!(report.transfer_fee, 10);
+    }
+
+    #[test]
+    fn test_fill_report_without_balance() {
+        let (owner, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let keys = [owner, Pubkey::new_unique()];
+        let info = MintTokenInfo::spl_token();
+
+        // 交易中没有 owner 持有该代币的余额
+        let other_owner = meta(1_000, 1_000, vec![], vec![token_balance(&keys[1], &mint, 5)]);
+        assert!(fill_report_from_meta(&keys, &other_owner, &owner, &mint, &info).is_err());
+        let other_mint =
+            meta(1_000, 1_000, vec![], vec![token_balance(&owner, &Pubkey::new_unique(), 5)]);
+        assert!(fill_report_from_meta(&keys, &other_mint, &owner, &mint, &info).is_err());
+        // owner 不在交易中
+        let buy = meta(1_000, 1_000, vec![], vec![token_balance(&owner, &mint, 5)]);
+        assert!(fill_report_from_meta(&keys, &buy, &Pubkey::new_unique(), &mint, &info).is_err());
+    }
+}
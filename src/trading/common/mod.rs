@@ -3,10 +3,25 @@ pub mod transaction_builder;
 pub mod compute_budget_manager;
 pub mod address_lookup_manager;
 pub mod utils;
+pub mod token_program;
+pub mod token_safety;
+pub mod fill_report;
+pub mod fill_model;
+pub mod slippage_manager;
+pub mod tip_escalation;
+pub mod concentrated_liquidity;
 
 // Re-export commonly used functions
 pub use nonce_manager::*;
 pub use transaction_builder::*;
 pub use compute_budget_manager::*;
 pub use address_lookup_manager::*;
-pub use utils::*;
\ No newline at end of file
+pub use utils::*;
+pub use token_program::*;
+pub use fill_report::{get_fill_report, FillReport};
+pub use fill_model::{FillModel, MarketSnapshot};
+pub use slippage_manager::{AdaptiveSlippageConfig, SlippageManager, SlippageStats};
+pub use tip_escalation::{TipEscalationAttempt, TipEscalationConfig};
+pub use token_safety::{
+    check_token_safety, TokenSafetyConfig, TokenSafetyContext, TokenSafetyFailure,
+};
\ No newline at end of file
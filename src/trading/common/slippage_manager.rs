@@ -0,0 +1,179 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use crate::trading::factory::DexType;
+
+/// 自适应滑点配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdaptiveSlippageConfig {
+    /// 每次重试增加的滑点（基点）
+    pub step_basis_points: u64,
+    /// 滑点上限（基点），达到后不再重试
+    pub max_basis_points: u64,
+    /// 因滑点超限失败后的最大重试次数
+    pub max_retries: u32,
+}
+
+impl Default for AdaptiveSlippageConfig {
+    fn default() -> Self {
+        Self { step_basis_points: 500, max_basis_points: 3000, max_retries: 2 }
+    }
+}
+
+/// 单个协议的实际滑点统计（基点）
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SlippageStats {
+    pub samples: u64,
+    pub average_basis_points: f64,
+    pub max_basis_points: u64,
+}
+
+/// 建议滑点参考的最近样本数
+const RECENT_SAMPLES: usize = 50;
+/// 建议滑点取最近样本的百分位
+const SUGGESTED_PERCENTILE: usize = 90;
+
+/// 单个协议的实际滑点：全部样本的统计及最近的样本
+#[derive(Debug, Default)]
+struct Realized {
+    stats: SlippageStats,
+    recent: VecDeque<u64>,
+}
+
+/// 滑点管理器：滑点超限失败后逐步放宽滑点重试，并按协议记录实际滑点
+#[derive(Debug)]
+pub struct SlippageManager {
+    config: AdaptiveSlippageConfig,
+    realized: Mutex<HashMap<DexType, Realized>>,
+}
+
+impl SlippageManager {
+    pub fn new(config: AdaptiveSlippageConfig) -> Self {
+        Self { config, realized: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn config(&self) -> &AdaptiveSlippageConfig {
+        &self.config
+    }
+
+    /// 重试时使用的滑点，已达到上限时返回 None
+    pub fn next_slippage(&self, current_basis_points: u64) -> Option<u64> {
+        if current_basis_points >= self.config.max_basis_points {
+            return None;
+        }
+        Some((current_basis_points + self.config.step_basis_points).min(self.config.max_basis_points))
+    }
+
+    /// 错误是否由协议的滑点检查触发
+    pub fn is_slippage_error(dex_type: &DexType, error: &anyhow::Error) -> bool {
+        let message = format!("{:#}", error);
+        if message.to_lowercase().contains("slippage") {
+            return true;
+        }
+        let Some(code) = parse_custom_program_error(&message) else {
+            return false;
+        };
+        match dex_type {
+            // TooMuchSolRequired / TooLittleSolReceived
+            DexType::PumpFun => code == 6002 || code == 6003,
+            // ExceededSlippage
+            DexType::PumpSwap => code == 6004,
+            DexType::RaydiumCpmm => code == 6005,
+            // SlippageToleranceExceeded
+            DexType::Jupiter => code == 6001,
+            _ => false,
+        }
+    }
+
+    /// 记录一笔成交的实际滑点，`expected_amount` 为报价数量，`actual_amount` 为实际到账数量
+    pub fn record_realized(&self, dex_type: DexType, expected_amount: u64, actual_amount: u64) {
+        if expected_amount == 0 {
+            return;
+        }
+        let slippage = (expected_amount.saturating_sub(actual_amount) as u128 * 10_000
+            / expected_amount as u128) as u64;
+        let mut realized = self.realized.lock().unwrap();
+        let realized = realized.entry(dex_type).or_default();
+        if realized.recent.len() == RECENT_SAMPLES {
+            realized.recent.pop_front();
+        }
+        realized.recent.push_back(slippage);
+        let stats = &mut realized.stats;
+        stats.average_basis_points = (stats.average_basis_points * stats.samples as f64
+            + slippage as f64)
+            / (stats.samples + 1) as f64;
+        stats.samples += 1;
+        stats.max_basis_points = stats.max_basis_points.max(slippage);
+    }
+
+    /// 指定协议的实际滑点统计
+    pub fn stats(&self, dex_type: &DexType) -> Option<SlippageStats> {
+        self.realized.lock().unwrap().get(dex_type).map(|realized| realized.stats)
+    }
+
+    /// 根据实际滑点给出的默认滑点：最近 50 笔实际滑点的 90 分位再加一个步长，不超过上限
+    ///
+    /// 只看最近的样本，行情平稳后不会一直沿用早先的极端滑点
+    pub fn suggested_slippage(&self, dex_type: &DexType) -> Option<u64> {
+        let realized = self.realized.lock().unwrap();
+        let mut recent: Vec<u64> = realized.get(dex_type)?.recent.iter().copied().collect();
+        recent.sort_unstable();
+        let rank = (recent.len() * SUGGESTED_PERCENTILE).div_ceil(100).max(1);
+        Some((recent[rank - 1] + self.config.step_basis_points).min(self.config.max_basis_points))
+    }
+}
+
+/// 从错误信息中解析 "custom program error: 0x1772" 形式的错误码
+fn parse_custom_program_error(message: &str) -> Option<u32> {
+    let hex = message.split("custom program error: 0x").nth(1)?;
+    let hex: String = hex.chars().take_while(|c| c.is_ascii_hexdigit()).collect();
+    u32::from_str_radix(&hex, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slippage_retry_and_stats() {
+        let manager = SlippageManager::new(AdaptiveSlippageConfig::default());
+        assert_eq!(manager.next_slippage(1000), Some(1500));
+        assert_eq!(manager.next_slippage(2800), Some(3000));
+        assert_eq!(manager.next_slippage(3000), None);
+
+        let error = anyhow::anyhow!("Error processing Instruction 2: custom program error: 0x1772");
+        assert!(SlippageManager::is_slippage_error(&DexType::PumpFun, &error));
+        assert!(!SlippageManager::is_slippage_error(&DexType::PumpSwap, &error));
+
+        manager.record_realized(DexType::PumpFun, 1_000, 990);
+        manager.record_realized(DexType::PumpFun, 1_000, 970);
+        let stats = manager.stats(&DexType::PumpFun).unwrap();
+        assert_eq!((stats.samples, stats.max_basis_points), (2, 300));
+        assert_eq!(stats.average_basis_points, 200.0);
+        assert_eq!(manager.suggested_slippage(&DexType::PumpFun), Some(800));
+    }
+
+    #[test]
+    fn test_suggested_slippage_uses_recent_percentile() {
+        let manager = SlippageManager::new(AdaptiveSlippageConfig::default());
+        assert_eq!(manager.suggested_slippage(&DexType::PumpFun), None);
+
+        // 早先的一笔极端滑点被最近的样本挤出窗口
+        manager.record_realized(DexType::PumpFun, 1_000, 800);
+        for _ in 0..RECENT_SAMPLES {
+            manager.record_realized(DexType::PumpFun, 1_000, 990);
+        }
+        assert_eq!(manager.stats(&DexType::PumpFun).unwrap().max_basis_points, 2000);
+        assert_eq!(manager.suggested_slippage(&DexType::PumpFun), Some(600));
+
+        // 少数离群样本不影响 90 分位
+        for _ in 0..3 {
+            manager.record_realized(DexType::PumpFun, 1_000, 700);
+        }
+        assert_eq!(manager.suggested_slippage(&DexType::PumpFun), Some(600));
+        for _ in 0..5 {
+            manager.record_realized(DexType::PumpFun, 1_000, 700);
+        }
+        assert_eq!(manager.suggested_slippage(&DexType::PumpFun), Some(3000));
+    }
+}
@@ -0,0 +1,132 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use solana_hash::Hash;
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature};
+
+use crate::common::{PriorityFee, SolanaRpcClient};
+use crate::swqos::TradeType;
+
+/// 查询签名状态的间隔
+const LANDING_POLL_INTERVAL: Duration = Duration::from_millis(400);
+
+/// 小费递增配置：带小费的交易在 `max_slots` 个 slot 内未上链时，提高小费重新构建并提交
+///
+/// 使用持久 nonce 时每次提交消费同一个 nonce，最多只有一笔能上链；
+/// 未使用 nonce 时，要等到上一次提交的 blockhash 过期（约 150 个 slot）且之前的交易都未上链，
+/// 才会换新的 blockhash 重新提交，避免重复成交
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TipEscalationConfig {
+    /// 等待上链的 slot 数
+    pub max_slots: u64,
+    /// 每次重新提交时小费的倍数
+    pub multiplier: f64,
+    /// 小费上限（SOL），所有小费都达到上限后不再重新提交
+    pub max_tip: f64,
+    /// 最多重新提交的次数
+    pub max_escalations: u32,
+}
+
+impl Default for TipEscalationConfig {
+    fn default() -> Self {
+        Self { max_slots: 10, multiplier: 2.0, max_tip: 0.01, max_escalations: 2 }
+    }
+}
+
+impl TipEscalationConfig {
+    /// 按倍数提高小费，不超过上限
+    pub fn escalate(&self, tip: f64) -> f64 {
+        (tip * self.multiplier).min(self.max_tip).max(tip)
+    }
+
+    /// 提高交易使用的小费，卖出提高 `sell_tip_fee`，其余提高 `buy_tip_fee` 和 `buy_tip_fees`，
+    /// 小费都已达到上限时返回 false
+    pub fn escalate_priority_fee(&self, priority_fee: &mut PriorityFee, trade_type: TradeType) -> bool {
+        let before = tips(priority_fee, trade_type);
+        match trade_type {
+            TradeType::Sell => priority_fee.sell_tip_fee = self.escalate(priority_fee.sell_tip_fee),
+            _ => {
+                priority_fee.buy_tip_fee = self.escalate(priority_fee.buy_tip_fee);
+                for tip in priority_fee.buy_tip_fees.iter_mut() {
+                    *tip = self.escalate(*tip);
+                }
+            }
+        }
+        tips(priority_fee, trade_type) != before
+    }
+}
+
+/// 一次提交使用的小费及是否在等待的 slot 内上链
+#[derive(Debug, Clone, PartialEq)]
+pub struct TipEscalationAttempt {
+    /// 卖出为 `sell_tip_fee`，其余为各 SWQOS 通道的 `buy_tip_fees`
+    pub tips: Vec<f64>,
+    pub signatures: Vec<Signature>,
+    /// 本次或之前某次提交的交易已上链
+    pub landed: bool,
+}
+
+/// 交易使用的小费
+pub fn tips(priority_fee: &PriorityFee, trade_type: TradeType) -> Vec<f64> {
+    match trade_type {
+        TradeType::Sell => vec![priority_fee.sell_tip_fee],
+        _ => priority_fee.buy_tip_fees.clone(),
+    }
+}
+
+/// 等待任一签名上链（包括执行失败），超过 `max_slots` 个 slot 仍未上链时返回 false。
+/// `signatures` 应包含之前所有提交的签名，之前的交易仍可能上链
+pub async fn wait_for_landing(rpc: &SolanaRpcClient, signatures: &[Signature], max_slots: u64) -> Result<bool> {
+    let deadline = rpc.get_slot().await? + max_slots;
+    loop {
+        let statuses = rpc.get_signature_statuses(signatures).await?;
+        if statuses.value.iter().any(|status| status.is_some()) {
+            return Ok(true);
+        }
+        if rpc.get_slot().await? >= deadline {
+            return Ok(false);
+        }
+        tokio::time::sleep(LANDING_POLL_INTERVAL).await;
+    }
+}
+
+/// 等待 `blockhash` 过期，期间任一签名上链时返回 true；过期后再查询一次签名状态，
+/// 两者都未发生时返回 false，此时使用该 blockhash 的交易都不会再上链
+pub async fn wait_for_expiry(rpc: &SolanaRpcClient, signatures: &[Signature], blockhash: &Hash) -> Result<bool> {
+    loop {
+        let statuses = rpc.get_signature_statuses(signatures).await?;
+        if statuses.value.iter().any(|status| status.is_some()) {
+            return Ok(true);
+        }
+        if !rpc.is_blockhash_valid(blockhash, CommitmentConfig::confirmed()).await? {
+            let statuses = rpc.get_signature_statuses(signatures).await?;
+            return Ok(statuses.value.iter().any(|status| status.is_some()));
+        }
+        tokio::time::sleep(LANDING_POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escalate_until_ceiling() {
+        let config = TipEscalationConfig { multiplier: 2.0, max_tip: 0.005, ..Default::default() };
+        let mut priority_fee =
+            PriorityFee { buy_tip_fee: 0.001, buy_tip_fees: vec![0.001, 0.004], ..Default::default() };
+
+        assert!(config.escalate_priority_fee(&mut priority_fee, TradeType::Buy));
+        assert_eq!(priority_fee.buy_tip_fees, vec![0.002, 0.005]);
+        assert!(config.escalate_priority_fee(&mut priority_fee, TradeType::Buy));
+        assert_eq!(priority_fee.buy_tip_fees, vec![0.004, 0.005]);
+        assert!(config.escalate_priority_fee(&mut priority_fee, TradeType::Buy));
+        assert!(!config.escalate_priority_fee(&mut priority_fee, TradeType::Buy));
+        assert_eq!(priority_fee.buy_tip_fee, 0.005);
+
+        // 已超过上限的小费不会被降低
+        let mut priority_fee = PriorityFee { sell_tip_fee: 0.02, ..Default::default() };
+        assert!(!config.escalate_priority_fee(&mut priority_fee, TradeType::Sell));
+        assert_eq!(priority_fee.sell_tip_fee, 0.02);
+    }
+}
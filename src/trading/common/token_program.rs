@@ -0,0 +1,156 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use spl_token_2022::{
+    extension::{transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions},
+    state::Mint,
+};
+
+use crate::common::{AccountCache, SolanaRpcClient};
+
+/// 代币 mint 的程序及转账手续费信息
+#[derive(Debug, Clone)]
+pub struct MintTokenInfo {
+    /// 代币程序，spl_token::ID 或 spl_token_2022::ID
+    pub token_program: Pubkey,
+    /// Token-2022 转账手续费配置
+    pub transfer_fee_config: Option<TransferFeeConfig>,
+    /// 当前 epoch，用于选择生效的手续费
+    pub epoch: u64,
+}
+
+impl MintTokenInfo {
+    /// 普通 SPL Token 代币
+    pub fn spl_token() -> Self {
+        Self { token_program: spl_token::ID, transfer_fee_config: None, epoch: 0 }
+    }
+
+    pub fn is_token_2022(&self) -> bool {
+        self.token_program == spl_token_2022::ID
+    }
+
+    /// 转账 `amount` 时扣除的手续费
+    pub fn transfer_fee(&self, amount: u64) -> u64 {
+        self.transfer_fee_config
+            .as_ref()
+            .and_then(|config| config.calculate_epoch_fee(self.epoch, amount))
+            .unwrap_or(0)
+    }
+
+    /// 转账 `amount` 后实际到账的数量
+    pub fn amount_after_transfer_fee(&self, amount: u64) -> u64 {
+        amount.saturating_sub(self.transfer_fee(amount))
+    }
+
+    /// 实际到账 `amount` 时被扣除的手续费
+    pub fn inverse_transfer_fee(&self, amount: u64) -> u64 {
+        self.transfer_fee_config
+            .as_ref()
+            .and_then(|config| config.calculate_inverse_epoch_fee(self.epoch, amount))
+            .unwrap_or(0)
+    }
+
+    /// 当前 epoch 生效的转账手续费率（基点），不含 maximum_fee 上限，无手续费时为 0
+    pub fn transfer_fee_basis_points(&self) -> u16 {
+        self.transfer_fee_config
+            .as_ref()
+            .map(|config| u16::from(config.get_epoch_fee(self.epoch).transfer_fee_basis_points))
+            .unwrap_or(0)
+    }
+}
+
+/// 查询代币 mint 所属的代币程序及转账手续费配置，mint 数据通过 AccountCache 缓存
+pub async fn get_mint_token_info(rpc: &SolanaRpcClient, mint: &Pubkey) -> Result<MintTokenInfo> {
+    let cached = AccountCache::get_instance()
+        .get_or_fetch(rpc, mint, |account| decode_mint_token_info(mint, account))
+        .await?;
+    let mut info = (*cached.data).clone();
+    if info.transfer_fee_config.is_some() {
+        info.epoch = current_epoch(rpc).await?;
+    }
+    Ok(info)
+}
+
+/// 查询代币 mint 所属的代币程序，WSOL 直接返回 spl_token::ID，其余 mint 通过 AccountCache 缓存
+pub async fn get_mint_token_program(rpc: &SolanaRpcClient, mint: &Pubkey) -> Result<Pubkey> {
+    if *mint == spl_token::native_mint::ID {
+        return Ok(spl_token::ID);
+    }
+    Ok(get_mint_token_info(rpc, mint).await?.token_program)
+}
+
+/// epoch 缓存有效期，epoch 约两天切换一次，短时间内无需重复查询
+const EPOCH_CACHE_TTL: Duration = Duration::from_secs(60);
+
+static EPOCH_CACHE: Mutex<Option<(u64, Instant)>> = Mutex::new(None);
+
+async fn current_epoch(rpc: &SolanaRpcClient) -> Result<u64> {
+    if let Some((epoch, fetched_at)) = *EPOCH_CACHE.lock().unwrap() {
+        if fetched_at.elapsed() < EPOCH_CACHE_TTL {
+            return Ok(epoch);
+        }
+    }
+    let epoch = rpc.get_epoch_info().await?.epoch;
+    *EPOCH_CACHE.lock().unwrap() = Some((epoch, Instant::now()));
+    Ok(epoch)
+}
+
+fn decode_mint_token_info(mint: &Pubkey, account: &Account) -> Result<MintTokenInfo> {
+    if account.owner == spl_token::ID {
+        return Ok(MintTokenInfo::spl_token());
+    }
+    if account.owner != spl_token_2022::ID {
+        return Err(anyhow!("Mint {} is not owned by a token program: {}", mint, account.owner));
+    }
+    let state = StateWithExtensions::<Mint>::unpack(&account.data)?;
+    let transfer_fee_config = state.get_extension::<TransferFeeConfig>().ok().copied();
+    Ok(MintTokenInfo { token_program: spl_token_2022::ID, transfer_fee_config, epoch: 0 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spl_token_2022::extension::transfer_fee::TransferFee;
+
+    #[test]
+    fn test_amount_after_transfer_fee() {
+        let transfer_fee = TransferFee {
+            transfer_fee_basis_points: 100.into(),
+            maximum_fee: 50.into(),
+            ..Default::default()
+        };
+        let info = MintTokenInfo {
+            token_program: spl_token_2022::ID,
+            transfer_fee_config: Some(TransferFeeConfig {
+                newer_transfer_fee: transfer_fee,
+                ..Default::default()
+            }),
+            epoch: 1,
+        };
+        assert_eq!(info.amount_after_transfer_fee(1_000), 990);
+        // 手续费不超过 maximum_fee
+        assert_eq!(info.amount_after_transfer_fee(10_000), 9_950);
+        assert_eq!(info.inverse_transfer_fee(990), 10);
+        assert_eq!(info.transfer_fee_basis_points(), 100);
+        assert_eq!(MintTokenInfo::spl_token().amount_after_transfer_fee(1_000), 1_000);
+        assert_eq!(MintTokenInfo::spl_token().transfer_fee_basis_points(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_mint_token_program_uses_cache() {
+        // RPC 不可达，结果只能来自缓存
+        let rpc = SolanaRpcClient::new("http://127.0.0.1:1".to_string());
+        assert_eq!(
+            get_mint_token_program(&rpc, &spl_token::native_mint::ID).await.unwrap(),
+            spl_token::ID
+        );
+
+        let mint = Pubkey::new_unique();
+        assert!(get_mint_token_program(&rpc, &mint).await.is_err());
+        let info = MintTokenInfo { token_program: spl_token_2022::ID, transfer_fee_config: None, epoch: 0 };
+        AccountCache::get_instance().insert(mint, info, 1);
+        assert_eq!(get_mint_token_program(&rpc, &mint).await.unwrap(), spl_token_2022::ID);
+    }
+}
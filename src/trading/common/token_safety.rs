@@ -0,0 +1,252 @@
+use std::fmt;
+
+use anyhow::{anyhow, Result};
+use solana_sdk::{account::Account, pubkey, pubkey::Pubkey};
+use spl_token_2022::{
+    extension::{
+        transfer_fee::TransferFeeConfig, BaseStateWithExtensions, ExtensionType,
+        StateWithExtensions,
+    },
+    state::{Account as TokenAccount, Mint},
+};
+
+use crate::common::SolanaRpcClient;
+
+/// 销毁地址，发送到这里的 LP 无法取回
+pub const INCINERATOR: Pubkey = pubkey!("1nc1nerator11111111111111111111111111111111");
+
+/// 买入前的代币安全检查配置，未启用的检查不做
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenSafetyConfig {
+    /// 要求 mint authority 已放弃，否则可以增发
+    pub require_mint_authority_renounced: bool,
+    /// 要求没有 freeze authority，否则可以冻结持有者的代币账户
+    pub require_no_freeze_authority: bool,
+    /// 不允许的 Token-2022 扩展
+    pub blocked_extensions: Vec<ExtensionType>,
+    /// Token-2022 转账手续费上限（基点）
+    pub max_transfer_fee_basis_points: Option<u16>,
+    /// 前 `top_holders` 个持有者（不含池子账户）合计占总供应量的上限（基点）
+    pub max_top_holders_basis_points: Option<u64>,
+    pub top_holders: usize,
+    /// 未销毁或未锁定的 LP 占 LP 供应量的上限（基点），只在提供 LP mint 时检查
+    pub max_unlocked_lp_basis_points: Option<u64>,
+    /// 视为已锁定的 LP 持有者，如销毁地址和锁仓程序
+    pub lp_lockers: Vec<Pubkey>,
+}
+
+impl Default for TokenSafetyConfig {
+    fn default() -> Self {
+        Self {
+            require_mint_authority_renounced: true,
+            require_no_freeze_authority: true,
+            blocked_extensions: vec![
+                ExtensionType::PermanentDelegate,
+                ExtensionType::TransferHook,
+                ExtensionType::NonTransferable,
+                ExtensionType::DefaultAccountState,
+                ExtensionType::Pausable,
+            ],
+            max_transfer_fee_basis_points: Some(500),
+            max_top_holders_basis_points: Some(3_000),
+            top_holders: 10,
+            max_unlocked_lp_basis_points: Some(1_000),
+            lp_lockers: vec![INCINERATOR],
+        }
+    }
+}
+
+/// 检查所需的池子信息
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TokenSafetyContext {
+    /// 池子持有代币的账户（如 bonding curve 或池子金库），不计入持有者集中度
+    pub pool_token_accounts: Vec<Pubkey>,
+    /// 池子的 LP mint，bonding curve 等没有 LP 的池子为 None
+    pub lp_mint: Option<Pubkey>,
+}
+
+/// 未通过的检查
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenSafetyFailure {
+    MintAuthority(Pubkey),
+    FreezeAuthority(Pubkey),
+    BlockedExtension(ExtensionType),
+    TransferFee(u16),
+    TopHolderConcentration(u64),
+    UnlockedLp(u64),
+}
+
+impl fmt::Display for TokenSafetyFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MintAuthority(authority) => {
+                write!(f, "mint authority not renounced ({})", authority)
+            }
+            Self::FreezeAuthority(authority) => write!(f, "freeze authority set ({})", authority),
+            Self::BlockedExtension(extension) => write!(f, "blocked extension {:?}", extension),
+            Self::TransferFee(bps) => write!(f, "transfer fee {} bps", bps),
+            Self::TopHolderConcentration(bps) => write!(f, "top holders own {} bps of supply", bps),
+            Self::UnlockedLp(bps) => write!(f, "{} bps of LP not burned or locked", bps),
+        }
+    }
+}
+
+/// 检查 mint 账户：mint / freeze authority、Token-2022 扩展和转账手续费
+pub fn check_mint_account(
+    mint: &Pubkey,
+    account: &Account,
+    epoch: u64,
+    config: &TokenSafetyConfig,
+) -> Result<Vec<TokenSafetyFailure>> {
+    if account.owner != spl_token::ID && account.owner != spl_token_2022::ID {
+        return Err(anyhow!("Mint {} is not owned by a token program: {}", mint, account.owner));
+    }
+    let state = StateWithExtensions::<Mint>::unpack(&account.data)?;
+    let mut failures = vec![];
+    if let Some(authority) = Option::<Pubkey>::from(state.base.mint_authority) {
+        if config.require_mint_authority_renounced {
+            failures.push(TokenSafetyFailure::MintAuthority(authority));
+        }
+    }
+    if let Some(authority) = Option::<Pubkey>::from(state.base.freeze_authority) {
+        if config.require_no_freeze_authority {
+            failures.push(TokenSafetyFailure::FreezeAuthority(authority));
+        }
+    }
+    for extension in state.get_extension_types()? {
+        if config.blocked_extensions.contains(&extension) {
+            failures.push(TokenSafetyFailure::BlockedExtension(extension));
+        }
+    }
+    if let (Some(max), Ok(fee_config)) =
+        (config.max_transfer_fee_basis_points, state.get_extension::<TransferFeeConfig>())
+    {
+        let bps = u16::from(fee_config.get_epoch_fee(epoch).transfer_fee_basis_points);
+        if bps > max {
+            failures.push(TokenSafetyFailure::TransferFee(bps));
+        }
+    }
+    Ok(failures)
+}
+
+/// `amount` 占 `supply` 的比例（基点），供应量为 0 时为 0
+pub fn share_basis_points(amount: u64, supply: u64) -> u64 {
+    if supply == 0 {
+        return 0;
+    }
+    (amount as u128 * 10_000 / supply as u128) as u64
+}
+
+/// 买入前检查代币安全性，返回所有未通过的检查，为空表示通过
+///
+/// 持有者集中度和 LP 检查基于 `getTokenLargestAccounts`，只统计最大的 20 个账户，
+/// 其余 LP 一律视为未锁定
+pub async fn check_token_safety(
+    rpc: &SolanaRpcClient,
+    mint: &Pubkey,
+    config: &TokenSafetyConfig,
+    context: &TokenSafetyContext,
+) -> Result<Vec<TokenSafetyFailure>> {
+    let account = rpc.get_account(mint).await?;
+    let epoch =
+        if config.max_transfer_fee_basis_points.is_some() && account.owner == spl_token_2022::ID {
+            rpc.get_epoch_info().await?.epoch
+        } else {
+            0
+        };
+    let mut failures = check_mint_account(mint, &account, epoch, config)?;
+
+    if let Some(max) = config.max_top_holders_basis_points {
+        let supply = StateWithExtensions::<Mint>::unpack(&account.data)?.base.supply;
+        let held: u64 = largest_accounts(rpc, mint)
+            .await?
+            .into_iter()
+            .filter(|(address, _)| !context.pool_token_accounts.contains(address))
+            .take(config.top_holders)
+            .map(|(_, amount)| amount)
+            .sum();
+        let bps = share_basis_points(held, supply);
+        if bps > max {
+            failures.push(TokenSafetyFailure::TopHolderConcentration(bps));
+        }
+    }
+
+    if let (Some(max), Some(lp_mint)) = (config.max_unlocked_lp_basis_points, context.lp_mint) {
+        let supply = StateWithExtensions::<Mint>::unpack(&rpc.get_account(&lp_mint).await?.data)?
+            .base
+            .supply;
+        let holders = largest_accounts(rpc, &lp_mint).await?;
+        let addresses: Vec<Pubkey> = holders.iter().map(|(address, _)| *address).collect();
+        let accounts = rpc.get_multiple_accounts(&addresses).await?;
+        let locked: u64 = holders
+            .iter()
+            .zip(accounts)
+            .filter(|(_, account)| {
+                account
+                    .as_ref()
+                    .and_then(|account| {
+                        StateWithExtensions::<TokenAccount>::unpack(&account.data).ok()
+                    })
+                    .is_some_and(|state| config.lp_lockers.contains(&state.base.owner))
+            })
+            .map(|((_, amount), _)| *amount)
+            .sum();
+        let bps = share_basis_points(supply.saturating_sub(locked), supply);
+        if bps > max {
+            failures.push(TokenSafetyFailure::UnlockedLp(bps));
+        }
+    }
+    Ok(failures)
+}
+
+/// 持有数量最多的代币账户及其数量
+async fn largest_accounts(rpc: &SolanaRpcClient, mint: &Pubkey) -> Result<Vec<(Pubkey, u64)>> {
+    rpc.get_token_largest_accounts(mint)
+        .await?
+        .into_iter()
+        .map(|balance| Ok((balance.address.parse()?, balance.amount.amount.parse()?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::program_option::COption;
+    use solana_sdk::program_pack::Pack;
+
+    fn mint_account(mint_authority: Option<Pubkey>, freeze_authority: Option<Pubkey>) -> Account {
+        let mut data = vec![0; spl_token::state::Mint::LEN];
+        spl_token::state::Mint {
+            mint_authority: mint_authority.map_or(COption::None, COption::Some),
+            supply: 1_000_000,
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority: freeze_authority.map_or(COption::None, COption::Some),
+        }
+        .pack_into_slice(&mut data);
+        Account { lamports: 1, data, owner: spl_token::ID, executable: false, rent_epoch: 0 }
+    }
+
+    #[test]
+    fn test_check_mint_account() {
+        let (mint, authority) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let config = TokenSafetyConfig::default();
+
+        let safe = mint_account(None, None);
+        assert!(check_mint_account(&mint, &safe, 0, &config).unwrap().is_empty());
+
+        let unsafe_mint = mint_account(Some(authority), Some(authority));
+        assert_eq!(
+            check_mint_account(&mint, &unsafe_mint, 0, &config).unwrap(),
+            vec![
+                TokenSafetyFailure::MintAuthority(authority),
+                TokenSafetyFailure::FreezeAuthority(authority)
+            ]
+        );
+        let relaxed = TokenSafetyConfig { require_no_freeze_authority: false, ..config };
+        assert_eq!(check_mint_account(&mint, &unsafe_mint, 0, &relaxed).unwrap().len(), 1);
+
+        assert_eq!(share_basis_points(250, 1_000), 2_500);
+        assert_eq!(share_basis_points(0, 0), 0);
+    }
+}
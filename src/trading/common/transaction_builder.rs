@@ -12,7 +12,10 @@ use solana_system_interface::instruction::transfer;
 use std::sync::Arc;
 
 use super::{
-    address_lookup_manager::get_address_lookup_table_accounts,
+    address_lookup_manager::{
+        compile_message_with_optimal_lookup_tables, get_address_lookup_table_accounts,
+        get_cached_address_lookup_table_accounts,
+    },
     compute_budget_manager::{
         add_rpc_compute_budget_instructions, add_tip_compute_budget_instructions,
     },
@@ -28,6 +31,7 @@ use crate::{
 /// 构建标准的RPC交易
 pub async fn build_rpc_transaction(
     payer: Arc<Keypair>,
+    fee_payer: Option<Arc<Keypair>>,
     priority_fee: &PriorityFee,
     business_instructions: Vec<Instruction>,
     lookup_table_key: Option<Pubkey>,
@@ -54,12 +58,14 @@ pub async fn build_rpc_transaction(
     let address_lookup_table_accounts = get_address_lookup_table_accounts(lookup_table_key).await;
 
     // 构建交易
-    build_versioned_transaction(payer, instructions, address_lookup_table_accounts, blockhash).await
+    build_versioned_transaction(payer, fee_payer, instructions, address_lookup_table_accounts, blockhash).await
 }
 
 /// 构建带小费的交易
+#[allow(clippy::too_many_arguments)]
 pub async fn build_tip_transaction(
     payer: Arc<Keypair>,
+    fee_payer: Option<Arc<Keypair>>,
     priority_fee: &PriorityFee,
     business_instructions: Vec<Instruction>,
     tip_account: &Pubkey,
@@ -81,9 +87,9 @@ pub async fn build_tip_transaction(
     // 添加业务指令
     instructions.extend(business_instructions);
 
-    // 添加小费转账指令
+    // 添加小费转账指令，由手续费账户支付
     instructions.push(transfer(
-        &payer.pubkey(),
+        &fee_payer.as_ref().unwrap_or(&payer).pubkey(),
         tip_account,
         sol_str_to_lamports(tip_amount.to_string().as_str()).unwrap_or(0),
     ));
@@ -95,32 +101,68 @@ pub async fn build_tip_transaction(
     let address_lookup_table_accounts = get_address_lookup_table_accounts(lookup_table_key).await;
 
     // 构建交易
-    build_versioned_transaction(payer, instructions, address_lookup_table_accounts, blockhash).await
+    build_versioned_transaction(payer, fee_payer, instructions, address_lookup_table_accounts, blockhash).await
 }
 
 /// 构建版本化交易的底层函数
-async fn build_versioned_transaction(
+pub(crate) async fn build_versioned_transaction(
     payer: Arc<Keypair>,
+    fee_payer: Option<Arc<Keypair>>,
     instructions: Vec<Instruction>,
     address_lookup_table_accounts: Vec<solana_sdk::message::AddressLookupTableAccount>,
     blockhash: Hash,
 ) -> Result<VersionedTransaction, anyhow::Error> {
-    let v0_message: v0::Message = v0::Message::try_compile(
-        &payer.pubkey(),
+    build_versioned_transaction_with_signers(
+        payer,
+        fee_payer,
+        &[],
+        instructions,
+        address_lookup_table_accounts,
+        blockhash,
+    )
+    .await
+}
+
+/// 构建版本化交易，`additional_signers` 为 payer 和手续费账户之外需要签名的账户（如新 mint）
+pub(crate) async fn build_versioned_transaction_with_signers(
+    payer: Arc<Keypair>,
+    fee_payer: Option<Arc<Keypair>>,
+    additional_signers: &[Arc<Keypair>],
+    instructions: Vec<Instruction>,
+    address_lookup_table_accounts: Vec<solana_sdk::message::AddressLookupTableAccount>,
+    blockhash: Hash,
+) -> Result<VersionedTransaction, anyhow::Error> {
+    // 指定了独立的手续费账户时，由其支付网络费用并与 payer 共同签名
+    let fee_payer = fee_payer.filter(|fee_payer| fee_payer.pubkey() != payer.pubkey());
+    let message_payer = fee_payer.as_ref().unwrap_or(&payer).pubkey();
+
+    // 在配置的与缓存中的地址表里选择使交易最小的组合
+    let mut candidates = address_lookup_table_accounts;
+    candidates.extend(get_cached_address_lookup_table_accounts());
+    let v0_message: v0::Message = compile_message_with_optimal_lookup_tables(
+        &message_payer,
         &instructions,
-        &address_lookup_table_accounts,
+        &candidates,
         blockhash,
     )?;
 
-    let versioned_message: VersionedMessage = VersionedMessage::V0(v0_message.clone());
-    let transaction = VersionedTransaction::try_new(versioned_message, &[payer.as_ref()])?;
+    let versioned_message: VersionedMessage = VersionedMessage::V0(v0_message);
+    let mut signers: Vec<&Keypair> = vec![];
+    if let Some(fee_payer) = &fee_payer {
+        signers.push(fee_payer.as_ref());
+    }
+    signers.push(payer.as_ref());
+    signers.extend(additional_signers.iter().map(|signer| signer.as_ref()));
+    let transaction = VersionedTransaction::try_new(versioned_message, &signers)?;
 
     Ok(transaction)
 }
 
 /// 构建带小费的交易（使用PriorityFee中的tip_fee）
+#[allow(clippy::too_many_arguments)]
 pub async fn build_tip_transaction_with_priority_fee(
     payer: Arc<Keypair>,
+    fee_payer: Option<Arc<Keypair>>,
     priority_fee: &PriorityFee,
     business_instructions: Vec<Instruction>,
     tip_account: &Pubkey,
@@ -130,6 +172,7 @@ pub async fn build_tip_transaction_with_priority_fee(
 ) -> Result<VersionedTransaction, anyhow::Error> {
     build_tip_transaction(
         payer,
+        fee_payer,
         priority_fee,
         business_instructions,
         tip_account,
@@ -144,6 +187,7 @@ pub async fn build_tip_transaction_with_priority_fee(
 /// 构建标准的RPC交易
 pub async fn build_sell_transaction(
     payer: Arc<Keypair>,
+    fee_payer: Option<Arc<Keypair>>,
     priority_fee: &PriorityFee,
     business_instructions: Vec<Instruction>,
     lookup_table_key: Option<Pubkey>,
@@ -163,6 +207,7 @@ pub async fn build_sell_transaction(
     // 构建交易
     build_versioned_transaction(
         payer,
+        fee_payer,
         instructions,
         address_lookup_table_accounts,
         recent_blockhash,
@@ -170,8 +215,10 @@ pub async fn build_sell_transaction(
     .await
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn build_sell_tip_transaction(
     payer: Arc<Keypair>,
+    fee_payer: Option<Arc<Keypair>>,
     priority_fee: &PriorityFee,
     business_instructions: Vec<Instruction>,
     tip_account: &Pubkey,
@@ -187,9 +234,9 @@ pub async fn build_sell_tip_transaction(
     // 添加业务指令
     instructions.extend(business_instructions);
 
-    // 添加小费转账指令
+    // 添加小费转账指令，由手续费账户支付
     instructions.push(transfer(
-        &payer.pubkey(),
+        &fee_payer.as_ref().unwrap_or(&payer).pubkey(),
         tip_account,
         sol_str_to_lamports(tip_amount.to_string().as_str()).unwrap_or(0),
     ));
@@ -200,6 +247,7 @@ pub async fn build_sell_tip_transaction(
     // 构建交易
     build_versioned_transaction(
         payer,
+        fee_payer,
         instructions,
         address_lookup_table_accounts,
         recent_blockhash,
@@ -209,6 +257,7 @@ pub async fn build_sell_tip_transaction(
 
 pub async fn build_sell_tip_transaction_with_priority_fee(
     payer: Arc<Keypair>,
+    fee_payer: Option<Arc<Keypair>>,
     priority_fee: &PriorityFee,
     business_instructions: Vec<Instruction>,
     tip_account: &Pubkey,
@@ -217,6 +266,7 @@ pub async fn build_sell_tip_transaction_with_priority_fee(
 ) -> Result<VersionedTransaction, anyhow::Error> {
     build_sell_tip_transaction(
         payer,
+        fee_payer,
         priority_fee,
         business_instructions,
         tip_account,
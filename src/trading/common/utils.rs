@@ -1,6 +1,8 @@
 use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer, transaction::Transaction};
 use solana_system_interface::instruction::transfer;
-use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::{
+    get_associated_token_address, get_associated_token_address_with_program_id,
+};
 use spl_token::instruction::close_account;
 
 use crate::common::SolanaRpcClient;
@@ -19,6 +21,21 @@ pub async fn get_token_balance(
     Ok(balance_u64)
 }
 
+/// 查询指定代币程序下的 ATA 余额，Token-2022 代币需使用此方法
+#[inline]
+pub async fn get_token_balance_with_program(
+    rpc: &SolanaRpcClient,
+    payer: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<u64, anyhow::Error> {
+    let ata = get_associated_token_address_with_program_id(payer, mint, token_program);
+    let balance = rpc.get_token_account_balance(&ata).await?;
+    let balance_u64 =
+        balance.amount.parse::<u64>().map_err(|_| anyhow!("Failed to parse token balance"))?;
+    Ok(balance_u64)
+}
+
 #[inline]
 pub async fn get_sol_balance(
     rpc: &SolanaRpcClient,
@@ -1,19 +1,72 @@
 use anyhow::{anyhow, Result};
-use std::sync::Arc;
+use solana_hash::Hash;
+use solana_sdk::{
+    instruction::Instruction, pubkey::Pubkey, signature::Keypair,
+    transaction::VersionedTransaction,
+};
+use std::{sync::Arc, time::Instant};
 
 use super::{
-    parallel::parallel_execute_with_tips,
+    parallel::{parallel_execute_with_tips, SubmissionReport},
     params::{BuyParams, BuyWithTipParams, SellParams, SellWithTipParams},
     timer::TradeTimer,
     traits::{InstructionBuilder, TradeExecutor},
 };
 use crate::{
-    swqos::TradeType,
-    trading::common::{build_rpc_transaction, build_sell_transaction},
+    common::{PriorityFee, SolanaRpcClient},
+    swqos::{SwqosType, TradeType},
+    trading::common::{
+        build_rpc_transaction, build_sell_tip_transaction_with_priority_fee,
+        build_sell_transaction, build_tip_transaction_with_priority_fee,
+        simulate_compute_unit_limit,
+    },
 };
 
 const MAX_LOADED_ACCOUNTS_DATA_SIZE_LIMIT: u32 = 256 * 1024;
 
+/// 开启计算单元模拟时，用模拟结果替换静态的计算单元上限
+///
+/// 模拟失败时保留配置值
+#[allow(clippy::too_many_arguments)]
+async fn apply_simulated_compute_units(
+    rpc: Option<&Arc<SolanaRpcClient>>,
+    payer: &Arc<Keypair>,
+    fee_payer: Option<&Arc<Keypair>>,
+    priority_fee: &mut PriorityFee,
+    instructions: &[Instruction],
+    lookup_table_key: Option<Pubkey>,
+    recent_blockhash: Hash,
+    data_size_limit: u32,
+) {
+    let (Some(margin), Some(rpc)) = (priority_fee.compute_unit_margin, rpc) else {
+        return;
+    };
+    let data_size_limit = if data_size_limit == 0 {
+        MAX_LOADED_ACCOUNTS_DATA_SIZE_LIMIT
+    } else {
+        data_size_limit
+    };
+    match simulate_compute_unit_limit(
+        rpc,
+        payer.clone(),
+        fee_payer.cloned(),
+        priority_fee,
+        instructions.to_vec(),
+        lookup_table_key,
+        recent_blockhash,
+        data_size_limit,
+        margin,
+    )
+    .await
+    {
+        Ok(unit_limit) => {
+            priority_fee.unit_limit = unit_limit;
+            priority_fee.rpc_unit_limit = unit_limit;
+        }
+        Err(e) => println!("计算单元模拟失败，使用配置值: {}", e),
+    }
+}
+
 /// 通用交易执行器实现
 pub struct GenericTradeExecutor {
     instruction_builder: Arc<dyn InstructionBuilder>,
@@ -48,11 +101,23 @@ impl TradeExecutor for GenericTradeExecutor {
             .instruction_builder
             .build_buy_instructions(&params)
             .await?;
+        apply_simulated_compute_units(
+            Some(&rpc),
+            &params.payer,
+            params.fee_payer.as_ref(),
+            &mut params.priority_fee,
+            &instructions,
+            params.lookup_table_key,
+            params.recent_blockhash,
+            params.data_size_limit,
+        )
+        .await;
         timer.stage("构建rpc交易指令");
 
         // 构建交易
         let transaction = build_rpc_transaction(
             params.payer.clone(),
+            params.fee_payer.clone(),
             &params.priority_fee,
             instructions,
             params.lookup_table_key,
@@ -69,7 +134,7 @@ impl TradeExecutor for GenericTradeExecutor {
         Ok(())
     }
 
-    async fn buy_with_tip(&self, mut params: BuyWithTipParams) -> Result<()> {
+    async fn buy_with_tip(&self, mut params: BuyWithTipParams) -> Result<SubmissionReport> {
         if params.data_size_limit == 0 {
             params.data_size_limit = MAX_LOADED_ACCOUNTS_DATA_SIZE_LIMIT;
         }
@@ -78,7 +143,9 @@ impl TradeExecutor for GenericTradeExecutor {
         // 验证参数 - 转换为BuyParams进行验证
         let buy_params = BuyParams {
             rpc: params.rpc,
+            read_rpc: params.read_rpc,
             payer: params.payer.clone(),
+            fee_payer: params.fee_payer.clone(),
             mint: params.mint,
             creator: params.creator,
             sol_amount: params.sol_amount,
@@ -87,6 +154,8 @@ impl TradeExecutor for GenericTradeExecutor {
             lookup_table_key: params.lookup_table_key,
             recent_blockhash: params.recent_blockhash,
             data_size_limit: params.data_size_limit,
+            wsol_policy: params.wsol_policy,
+            swqos_types: None,
             protocol_params: params.protocol_params.clone(),
         };
 
@@ -95,6 +164,17 @@ impl TradeExecutor for GenericTradeExecutor {
             .instruction_builder
             .build_buy_instructions(&buy_params)
             .await?;
+        apply_simulated_compute_units(
+            buy_params.rpc.as_ref(),
+            &params.payer,
+            params.fee_payer.as_ref(),
+            &mut params.priority_fee,
+            &instructions,
+            params.lookup_table_key,
+            params.recent_blockhash,
+            params.data_size_limit,
+        )
+        .await;
 
         timer.finish();
 
@@ -102,19 +182,21 @@ impl TradeExecutor for GenericTradeExecutor {
         parallel_execute_with_tips(
             params.swqos_clients,
             params.payer,
+            params.fee_payer,
             instructions,
             params.priority_fee,
             params.lookup_table_key,
             params.recent_blockhash,
             params.data_size_limit,
             TradeType::Buy,
+            params.race_submissions,
+            params.rpc_fallback,
+            params.on_sent,
         )
-        .await?;
-
-        Ok(())
+        .await
     }
 
-    async fn sell(&self, params: SellParams) -> Result<()> {
+    async fn sell(&self, mut params: SellParams) -> Result<SubmissionReport> {
         if params.rpc.is_none() {
             return Err(anyhow!("RPC is not set"));
         }
@@ -126,11 +208,23 @@ impl TradeExecutor for GenericTradeExecutor {
             .instruction_builder
             .build_sell_instructions(&params)
             .await?;
+        apply_simulated_compute_units(
+            Some(&rpc),
+            &params.payer,
+            params.fee_payer.as_ref(),
+            &mut params.priority_fee,
+            &instructions,
+            params.lookup_table_key,
+            params.recent_blockhash,
+            0,
+        )
+        .await;
         timer.stage("卖出交易指令");
 
         // 构建交易
         let transaction = build_sell_transaction(
             params.payer.clone(),
+            params.fee_payer.clone(),
             &params.priority_fee,
             instructions,
             params.lookup_table_key,
@@ -140,27 +234,37 @@ impl TradeExecutor for GenericTradeExecutor {
         timer.stage("卖出交易签名");
 
         // 发送交易
-        rpc.send_and_confirm_transaction(&transaction).await?;
+        if let Some(on_sent) = &params.on_sent {
+            on_sent.notify(SwqosType::Default, transaction.signatures[0]);
+        }
+        let start = Instant::now();
+        let signature = rpc.send_and_confirm_transaction(&transaction).await?;
         timer.finish();
 
-        Ok(())
+        Ok(SubmissionReport::rpc(signature, start.elapsed()))
     }
 
-    async fn sell_with_tip(&self, params: SellWithTipParams) -> Result<()> {
+    async fn sell_with_tip(&self, mut params: SellWithTipParams) -> Result<SubmissionReport> {
         let timer = TradeTimer::new("构建卖出交易指令");
 
         // 转换为SellParams进行指令构建
         let sell_params = SellParams {
             rpc: params.rpc,
+            read_rpc: params.read_rpc,
             payer: params.payer.clone(),
+            fee_payer: params.fee_payer.clone(),
             mint: params.mint,
             creator: params.creator,
             token_amount: params.token_amount,
             slippage_basis_points: params.slippage_basis_points,
+            min_sol_out: params.min_sol_out,
             priority_fee: params.priority_fee.clone(),
             lookup_table_key: params.lookup_table_key,
             recent_blockhash: params.recent_blockhash,
+            wsol_policy: params.wsol_policy,
+            swqos_types: None,
             protocol_params: params.protocol_params.clone(),
+            on_sent: None,
         };
 
         // 构建指令
@@ -168,6 +272,17 @@ impl TradeExecutor for GenericTradeExecutor {
             .instruction_builder
             .build_sell_instructions(&sell_params)
             .await?;
+        apply_simulated_compute_units(
+            sell_params.rpc.as_ref(),
+            &params.payer,
+            params.fee_payer.as_ref(),
+            &mut params.priority_fee,
+            &instructions,
+            params.lookup_table_key,
+            params.recent_blockhash,
+            0,
+        )
+        .await;
 
         timer.finish();
 
@@ -175,19 +290,149 @@ impl TradeExecutor for GenericTradeExecutor {
         parallel_execute_with_tips(
             params.swqos_clients,
             params.payer,
+            params.fee_payer,
             instructions,
             params.priority_fee,
             params.lookup_table_key,
             params.recent_blockhash,
             0,
             TradeType::Sell,
+            params.race_submissions,
+            params.rpc_fallback,
+            params.on_sent,
         )
-        .await?;
+        .await
+    }
 
-        Ok(())
+    async fn build_buy_transaction(
+        &self,
+        mut params: BuyParams,
+        tip_account: Option<Pubkey>,
+    ) -> Result<VersionedTransaction> {
+        if params.data_size_limit == 0 {
+            params.data_size_limit = MAX_LOADED_ACCOUNTS_DATA_SIZE_LIMIT;
+        }
+        let instructions = self.instruction_builder.build_buy_instructions(&params).await?;
+        apply_simulated_compute_units(
+            params.rpc.as_ref(),
+            &params.payer,
+            params.fee_payer.as_ref(),
+            &mut params.priority_fee,
+            &instructions,
+            params.lookup_table_key,
+            params.recent_blockhash,
+            params.data_size_limit,
+        )
+        .await;
+
+        match tip_account {
+            Some(tip_account) => {
+                build_tip_transaction_with_priority_fee(
+                    params.payer,
+                    params.fee_payer,
+                    &params.priority_fee,
+                    instructions,
+                    &tip_account,
+                    params.lookup_table_key,
+                    params.recent_blockhash,
+                    params.data_size_limit,
+                )
+                .await
+            }
+            None => {
+                build_rpc_transaction(
+                    params.payer,
+                    params.fee_payer,
+                    &params.priority_fee,
+                    instructions,
+                    params.lookup_table_key,
+                    params.recent_blockhash,
+                    params.data_size_limit,
+                )
+                .await
+            }
+        }
+    }
+
+    async fn build_sell_transaction(
+        &self,
+        mut params: SellParams,
+        tip_account: Option<Pubkey>,
+    ) -> Result<VersionedTransaction> {
+        let instructions = self.instruction_builder.build_sell_instructions(&params).await?;
+        apply_simulated_compute_units(
+            params.rpc.as_ref(),
+            &params.payer,
+            params.fee_payer.as_ref(),
+            &mut params.priority_fee,
+            &instructions,
+            params.lookup_table_key,
+            params.recent_blockhash,
+            0,
+        )
+        .await;
+
+        match tip_account {
+            Some(tip_account) => {
+                build_sell_tip_transaction_with_priority_fee(
+                    params.payer,
+                    params.fee_payer,
+                    &params.priority_fee,
+                    instructions,
+                    &tip_account,
+                    params.lookup_table_key,
+                    params.recent_blockhash,
+                )
+                .await
+            }
+            None => {
+                build_sell_transaction(
+                    params.payer,
+                    params.fee_payer,
+                    &params.priority_fee,
+                    instructions,
+                    params.lookup_table_key,
+                    params.recent_blockhash,
+                )
+                .await
+            }
+        }
     }
 
     fn protocol_name(&self) -> &'static str {
         self.protocol_name
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn simulate(
+        rpc: Option<&Arc<SolanaRpcClient>>,
+        payer: &Arc<Keypair>,
+        priority_fee: &mut PriorityFee,
+    ) {
+        apply_simulated_compute_units(rpc, payer, None, priority_fee, &[], None, Hash::default(), 0)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_simulated_compute_units_fall_back_to_config() {
+        // RPC 不可达，模拟失败时保留配置的上限
+        let rpc = Arc::new(SolanaRpcClient::new("http://127.0.0.1:1".to_string()));
+        let payer = Arc::new(Keypair::new());
+        let mut priority_fee = PriorityFee { compute_unit_margin: Some(1.2), ..Default::default() };
+        let configured = (priority_fee.unit_limit, priority_fee.rpc_unit_limit);
+        simulate(Some(&rpc), &payer, &mut priority_fee).await;
+        assert_eq!((priority_fee.unit_limit, priority_fee.rpc_unit_limit), configured);
+
+        // 未开启模拟或没有 RPC 时不做任何处理
+        let mut priority_fee = PriorityFee { unit_limit: 1, rpc_unit_limit: 2, ..Default::default() };
+        simulate(Some(&rpc), &payer, &mut priority_fee).await;
+        assert_eq!((priority_fee.unit_limit, priority_fee.rpc_unit_limit), (1, 2));
+        priority_fee.compute_unit_margin = Some(1.2);
+        simulate(None, &payer, &mut priority_fee).await;
+        assert_eq!((priority_fee.unit_limit, priority_fee.rpc_unit_limit), (1, 2));
+    }
+}
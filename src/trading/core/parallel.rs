@@ -1,41 +1,513 @@
 use anyhow::{anyhow, Result};
 use solana_hash::Hash;
-use solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::Keypair};
-use std::{str::FromStr, sync::Arc};
-use tokio::task::JoinHandle;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::{Keypair, Signature}};
+use std::{
+    collections::HashMap,
+    future::Future,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tokio::sync::oneshot;
+use tokio::task::{Id, JoinSet};
 
 use crate::{
-    common::PriorityFee,
-    swqos::{SwqosType, SwqosClient, TradeType},
+    common::{PriorityFee, SolanaRpcClient},
+    swqos::{solana_rpc::SolRpcClient, SwqosClientTrait, SwqosType, SwqosClient, TradeType},
     trading::core::timer::TradeTimer,
+    trading::common::tip_escalation::TipEscalationAttempt,
     trading::common::{
         build_rpc_transaction, build_sell_tip_transaction_with_priority_fee,
         build_sell_transaction, build_tip_transaction_with_priority_fee,
     },
 };
 
+/// 单个 SWQOS 通道的提交结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum SubmissionStatus {
+    /// 提交（及确认）成功
+    Succeeded,
+    /// 构建或提交失败，附带错误信息
+    Failed(String),
+    /// 竞速模式下其他通道先成功，该通道被取消
+    Cancelled,
+}
+
+/// 单个 SWQOS 通道的耗时，从开始提交算起，包含构建交易
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubmissionTiming {
+    pub swqos_type: SwqosType,
+    pub elapsed: Duration,
+    pub status: SubmissionStatus,
+    /// 提交成功的交易签名
+    pub signature: Option<Signature>,
+    /// 该通道交易支付的小费（SOL），不带小费时为 0
+    pub tip: f64,
+}
+
+/// 一次并行提交中各 SWQOS 通道的结果
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SubmissionReport {
+    /// 按完成先后排序，被取消的通道排在最后，RPC 回退提交排在最后
+    pub timings: Vec<SubmissionTiming>,
+    /// 所有 SWQOS 都失败后改由普通 RPC 提交了不带小费的交易
+    pub rpc_fallback: bool,
+    /// 开启小费递增时每次提交的小费及是否上链，最后一项为本报告对应的提交
+    pub tip_escalations: Vec<TipEscalationAttempt>,
+    /// 同一笔交易所有提交尝试共用的 id，未经 `SolanaTrade` 提交时为 0
+    pub trade_id: u64,
+    /// 本笔交易的所有提交尝试（首次提交、滑点重试、小费递增），按时间先后排列
+    pub attempts: Vec<SubmissionAttempt>,
+}
+
+static NEXT_TRADE_ID: AtomicU64 = AtomicU64::new(1);
+
+/// 分配进程内唯一的交易 id
+pub fn next_trade_id() -> u64 {
+    NEXT_TRADE_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// 一笔交易的一次提交尝试，用于事后区分小费过低、服务商拒绝和提交过慢
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SubmissionAttempt {
+    /// 交易使用的 blockhash，使用持久 nonce 时为 nonce 值
+    pub blockhash: Hash,
+    /// 提交的通道及各自的小费（SOL），提交成功时按 `timings` 记录，包括 RPC 回退
+    pub swqos: Vec<(SwqosType, f64)>,
+    pub slippage_basis_points: Option<u64>,
+    /// 提交成功的交易签名
+    pub signatures: Vec<Signature>,
+    /// 构建或提交失败时的错误信息，包含各通道的错误
+    pub error: Option<String>,
+    /// 开启小费递增时本次或之前的提交是否已上链，未等待上链时为 None
+    pub landed: Option<bool>,
+}
+
+impl SubmissionAttempt {
+    /// 按提交结果记录一次尝试，提交失败时通道及小费取 `planned`
+    pub fn new(
+        blockhash: Hash,
+        planned: Vec<(SwqosType, f64)>,
+        slippage_basis_points: Option<u64>,
+        result: &Result<SubmissionReport>,
+    ) -> Self {
+        match result {
+            Ok(report) if !report.timings.is_empty() => Self {
+                blockhash,
+                swqos: report.timings.iter().map(|timing| (timing.swqos_type.clone(), timing.tip)).collect(),
+                slippage_basis_points,
+                signatures: report.signatures(),
+                error: None,
+                landed: None,
+            },
+            Ok(_) => Self { blockhash, swqos: planned, slippage_basis_points, ..Default::default() },
+            Err(e) => Self {
+                blockhash,
+                swqos: planned,
+                slippage_basis_points,
+                error: Some(e.to_string()),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl SubmissionReport {
+    /// 不带小费、直接通过 RPC 发送并确认的交易，与 RPC 回退一样记为 `SwqosType::Default`
+    pub fn rpc(signature: Signature, elapsed: Duration) -> Self {
+        Self {
+            timings: vec![SubmissionTiming {
+                swqos_type: SwqosType::Default,
+                elapsed,
+                status: SubmissionStatus::Succeeded,
+                signature: Some(signature),
+                tip: 0.0,
+            }],
+            ..Default::default()
+        }
+    }
+
+    /// 最先成功的通道
+    pub fn winner(&self) -> Option<&SubmissionTiming> {
+        self.timings.iter().find(|timing| timing.status == SubmissionStatus::Succeeded)
+    }
+
+    /// 提交成功的交易及其通道和小费
+    pub fn submitted(&self) -> Vec<(SwqosType, Signature, f64)> {
+        self.timings
+            .iter()
+            .filter_map(|timing| timing.signature.map(|signature| (timing.swqos_type.clone(), signature, timing.tip)))
+            .collect()
+    }
+
+    /// 提交成功的交易签名
+    pub fn signatures(&self) -> Vec<Signature> {
+        self.timings.iter().filter_map(|timing| timing.signature).collect()
+    }
+
+    fn errors(&self) -> Vec<String> {
+        self.timings
+            .iter()
+            .filter_map(|timing| match &timing.status {
+                SubmissionStatus::Failed(e) => Some(format!("{:?}: {}", timing.swqos_type, e)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// 把失败但交易已上链的通道标记为成功
+    fn mark_landed(&mut self, swqos_type: &SwqosType, signature: Signature) {
+        if let Some(timing) = self
+            .timings
+            .iter_mut()
+            .find(|timing| &timing.swqos_type == swqos_type && timing.status != SubmissionStatus::Succeeded)
+        {
+            timing.status = SubmissionStatus::Succeeded;
+            timing.signature = Some(signature);
+        }
+    }
+
+    /// `race` 为 false 时任一通道失败即返回错误，为 true 时全部失败才返回错误
+    fn check(self, race: bool) -> Result<Self> {
+        let errors = self.errors();
+        let failed = if race { self.winner().is_none() } else { !errors.is_empty() };
+        if failed {
+            for error in &errors {
+                println!("Task error: {}", error);
+            }
+            return Err(anyhow!("Some tasks failed: {:?}", errors));
+        }
+        Ok(self)
+    }
+}
+
+/// 交易第一次交给 SWQOS（或 RPC）发送时通知一次，`SubmissionMode::FireAndTrack` 据此不等确认即返回签名
+#[derive(Clone)]
+pub struct SentNotifier(Arc<Mutex<Option<SentSender>>>);
+
+type SentSender = oneshot::Sender<(SwqosType, Signature)>;
+
+impl SentNotifier {
+    /// 通知及其接收端；通知的所有副本都被丢弃仍未通知时，接收端返回错误
+    pub fn new() -> (Self, oneshot::Receiver<(SwqosType, Signature)>) {
+        let (sender, receiver) = oneshot::channel();
+        (Self(Arc::new(Mutex::new(Some(sender)))), receiver)
+    }
+
+    /// 只有第一次调用生效
+    pub fn notify(&self, swqos_type: SwqosType, signature: Signature) {
+        if let Some(sender) = self.0.lock().unwrap().take() {
+            let _ = sender.send((swqos_type, signature));
+        }
+    }
+}
+
+/// 已交给 SWQOS 发送的交易，在发送前记录
+#[derive(Clone, Default)]
+pub struct SentLog {
+    sent: Arc<Mutex<Vec<(SwqosType, Signature)>>>,
+    notifier: Option<SentNotifier>,
+}
+
+impl SentLog {
+    /// 记录交给 `swqos_type` 发送的交易，第一笔交易同时通知 `SentNotifier`
+    pub fn record(&self, swqos_type: SwqosType, signature: Signature) {
+        self.sent.lock().unwrap().push((swqos_type.clone(), signature));
+        if let Some(notifier) = &self.notifier {
+            notifier.notify(swqos_type, signature);
+        }
+    }
+
+    fn snapshot(&self) -> Vec<(SwqosType, Signature)> {
+        self.sent.lock().unwrap().clone()
+    }
+}
+
+/// 并行运行的 SWQOS 提交任务
+#[derive(Default)]
+pub struct SubmissionTasks {
+    tasks: JoinSet<(Duration, Result<Signature>)>,
+    swqos_types: HashMap<Id, (SwqosType, f64)>,
+    sent: SentLog,
+}
+
+impl SubmissionTasks {
+    /// 第一笔交易交给 SWQOS 发送时通知 `notifier`
+    pub fn with_notifier(notifier: Option<SentNotifier>) -> Self {
+        Self { sent: SentLog { notifier, ..Default::default() }, ..Default::default() }
+    }
+
+    /// 启动一个提交任务，记录其耗时及小费，任务成功时返回提交的交易签名
+    pub fn spawn<F>(&mut self, swqos_type: SwqosType, tip: f64, future: F)
+    where
+        F: Future<Output = Result<Signature>> + Send + 'static,
+    {
+        let handle = self.tasks.spawn(async move {
+            let start = Instant::now();
+            let result = future.await;
+            (start.elapsed(), result)
+        });
+        self.swqos_types.insert(handle.id(), (swqos_type, tip));
+    }
+
+    /// 已发送的交易，任务在交给 SWQOS 发送前记录；发送报错（如确认超时）的交易仍可能上链
+    pub fn sent(&self) -> SentLog {
+        self.sent.clone()
+    }
+
+    /// 等待提交结果
+    ///
+    /// `race` 为 false 时等待全部通道，任一失败即返回错误；为 true 时第一个
+    /// 成功的通道返回后立即取消其余任务，全部失败才返回错误
+    pub async fn collect(self, race: bool) -> Result<SubmissionReport> {
+        self.join(race).await.check(race)
+    }
+
+    /// 与 `collect` 相同，但没有任何通道成功时运行 `fallback`（通过普通 RPC 提交），
+    /// 回退成功即视为提交成功
+    ///
+    /// 失败的通道可能已把交易发出，只有 `sent` 为空（没有交易交给任何通道）时才回退；
+    /// 否则通过 `fallback` 的 RPC 查询其中的交易，已上链且执行成功的交易视为该通道提交成功。
+    /// 已发出但尚未上链的交易仍可能上链，回退的交易签名不同，两者都上链会重复成交
+    pub async fn collect_or_fallback<F>(
+        self,
+        race: bool,
+        fallback: Option<(Arc<SolanaRpcClient>, F)>,
+    ) -> Result<SubmissionReport>
+    where
+        F: Future<Output = Result<Signature>>,
+    {
+        let sent = self.sent.clone();
+        let self_notifier = sent.notifier.clone();
+        let mut report = self.join(race).await;
+        if let (None, Some((rpc, fallback))) = (report.winner(), fallback) {
+            let sent = sent.snapshot();
+            match landed_submission(&rpc, &sent).await {
+                Ok(None) if sent.is_empty() => {}
+                Ok(None) => {
+                    println!("SWQOS 交易已发出但尚未上链，不回退到 RPC");
+                    return report.check(race);
+                }
+                Ok(Some((swqos_type, signature, true))) => {
+                    println!("SWQOS 交易 {} 已上链，不回退到 RPC", signature);
+                    report.mark_landed(&swqos_type, signature);
+                    return Ok(report);
+                }
+                Ok(Some((_, signature, false))) => {
+                    println!("SWQOS 交易 {} 已上链但执行失败，不回退到 RPC", signature);
+                    return report.check(race);
+                }
+                Err(e) => {
+                    println!("警告: 查询 SWQOS 交易状态失败，不回退到 RPC: {}", e);
+                    return report.check(race);
+                }
+            }
+            println!("所有 SWQOS 提交失败，回退到 RPC 提交");
+            let start = Instant::now();
+            let (status, signature) = match fallback.await {
+                Ok(signature) => {
+                    if let Some(notifier) = &self_notifier {
+                        notifier.notify(SwqosType::Default, signature);
+                    }
+                    (SubmissionStatus::Succeeded, Some(signature))
+                }
+                Err(e) => (SubmissionStatus::Failed(e.to_string()), None),
+            };
+            let succeeded = status == SubmissionStatus::Succeeded;
+            report.timings.push(SubmissionTiming {
+                swqos_type: SwqosType::Default,
+                elapsed: start.elapsed(),
+                status,
+                signature,
+                tip: 0.0,
+            });
+            if succeeded {
+                report.rpc_fallback = true;
+                return Ok(report);
+            }
+        }
+        report.check(race)
+    }
+
+    async fn join(mut self, race: bool) -> SubmissionReport {
+        let start = Instant::now();
+        let mut report = SubmissionReport::default();
+
+        while let Some(joined) = self.tasks.join_next_with_id().await {
+            let (id, elapsed, status, signature) = match joined {
+                Ok((id, (elapsed, Ok(signature)))) => (id, elapsed, SubmissionStatus::Succeeded, Some(signature)),
+                Ok((id, (elapsed, Err(e)))) => (id, elapsed, SubmissionStatus::Failed(e.to_string()), None),
+                Err(e) => {
+                    (e.id(), start.elapsed(), SubmissionStatus::Failed(format!("Join error: {}", e)), None)
+                }
+            };
+            let succeeded = status == SubmissionStatus::Succeeded;
+            if let Some((swqos_type, tip)) = self.swqos_types.remove(&id) {
+                report.timings.push(SubmissionTiming { swqos_type, elapsed, status, signature, tip });
+            }
+            if race && succeeded {
+                break;
+            }
+        }
+
+        // 竞速模式下剩余的任务直接取消
+        self.tasks.abort_all();
+        let elapsed = start.elapsed();
+        for (_, (swqos_type, tip)) in self.swqos_types.drain() {
+            report.timings.push(SubmissionTiming {
+                swqos_type,
+                elapsed,
+                status: SubmissionStatus::Cancelled,
+                signature: None,
+                tip,
+            });
+        }
+
+        report
+    }
+}
+
+/// 查询已发送交易的状态，返回第一笔已上链的交易及其是否执行成功
+async fn landed_submission(
+    rpc: &SolanaRpcClient,
+    sent: &[(SwqosType, Signature)],
+) -> Result<Option<(SwqosType, Signature, bool)>> {
+    if sent.is_empty() {
+        return Ok(None);
+    }
+    let signatures: Vec<Signature> = sent.iter().map(|(_, signature)| *signature).collect();
+    let statuses = rpc.get_signature_statuses(&signatures).await?.value;
+    Ok(sent.iter().zip(statuses).find_map(|((swqos_type, signature), status)| {
+        status.map(|status| (swqos_type.clone(), *signature, status.err.is_none()))
+    }))
+}
+
+/// 各 SWQOS 通道及其小费（SOL），与 `parallel_execute_with_tips` 的计算一致
+pub fn swqos_tips(
+    swqos_clients: &[Arc<SwqosClient>],
+    priority_fee: &PriorityFee,
+    trade_type: TradeType,
+) -> Vec<(SwqosType, f64)> {
+    swqos_clients
+        .iter()
+        .enumerate()
+        .map(|(i, swqos_client)| {
+            let swqos_type = swqos_client.get_swqos_type();
+            let tip = submission_tip(&swqos_type, i, priority_fee, trade_type);
+            (swqos_type, tip)
+        })
+        .collect()
+}
+
+/// 第 `index` 个通道的交易支付的小费（SOL），Default 通道不带小费
+fn submission_tip(
+    swqos_type: &SwqosType,
+    index: usize,
+    priority_fee: &PriorityFee,
+    trade_type: TradeType,
+) -> f64 {
+    match trade_type {
+        _ if *swqos_type == SwqosType::Default => 0.0,
+        TradeType::Sell => priority_fee.sell_tip_fee,
+        _ => priority_fee.buy_tip_fees.get(index).copied().unwrap_or(priority_fee.buy_tip_fee),
+    }
+}
+
+/// 只保留指定类型的 SWQOS 通道，`buy_tip_fees` 同步过滤以保持与通道对应
+///
+/// 没有任何通道匹配时返回错误
+pub fn select_swqos_types(
+    swqos_clients: Vec<Arc<SwqosClient>>,
+    swqos_types: &[SwqosType],
+    priority_fee: &mut PriorityFee,
+) -> Result<Vec<Arc<SwqosClient>>> {
+    let selected: Vec<usize> = (0..swqos_clients.len())
+        .filter(|index| swqos_types.contains(&swqos_clients[*index].get_swqos_type()))
+        .collect();
+    if selected.is_empty() {
+        return Err(anyhow!("No configured SWQOS client matches {:?}", swqos_types));
+    }
+    if !priority_fee.buy_tip_fees.is_empty() {
+        priority_fee.buy_tip_fees = selected
+            .iter()
+            .map(|index| priority_fee.buy_tip_fees.get(*index).copied().unwrap_or(priority_fee.buy_tip_fee))
+            .collect();
+    }
+    Ok(selected.iter().map(|index| swqos_clients[*index].clone()).collect())
+}
+
 /// 并行执行交易的通用函数
+///
+/// `race` 为 true 时第一个通道成功即返回并取消其余通道；设置了 `rpc_fallback` 时，
+/// 所有通道都失败后通过该 RPC 提交不带小费的交易；第一笔交易交给通道发送时通知 `on_sent`
+#[allow(clippy::too_many_arguments)]
 pub async fn parallel_execute_with_tips(
     swqos_clients: Vec<Arc<SwqosClient>>,
     payer: Arc<Keypair>,
+    fee_payer: Option<Arc<Keypair>>,
     instructions: Vec<Instruction>,
     priority_fee: PriorityFee,
     lookup_table_key: Option<Pubkey>,
     recent_blockhash: Hash,
     data_size_limit: u32,
     trade_type: TradeType,
-) -> Result<()> {
+    race: bool,
+    rpc_fallback: Option<Arc<SolRpcClient>>,
+    on_sent: Option<SentNotifier>,
+) -> Result<SubmissionReport> {
     let cores = core_affinity::get_core_ids().unwrap();
-    let mut handles: Vec<JoinHandle<Result<()>>> = vec![];
+    let mut tasks = SubmissionTasks::with_notifier(on_sent);
+
+    // 回退交易不带小费，跳过预检
+    let fallback = rpc_fallback.map(|rpc_client| {
+        let rpc = rpc_client.rpc_client.clone();
+        let payer = payer.clone();
+        let fee_payer = fee_payer.clone();
+        let instructions = instructions.clone();
+        let priority_fee = priority_fee.clone();
+        let fallback = async move {
+            let transaction = if matches!(trade_type, TradeType::Sell) {
+                build_sell_transaction(
+                    payer,
+                    fee_payer,
+                    &priority_fee,
+                    instructions,
+                    lookup_table_key,
+                    recent_blockhash,
+                )
+                .await?
+            } else {
+                build_rpc_transaction(
+                    payer,
+                    fee_payer,
+                    &priority_fee,
+                    instructions,
+                    lookup_table_key,
+                    recent_blockhash,
+                    data_size_limit,
+                )
+                .await?
+            };
+            rpc_client.send_transaction(trade_type, &transaction).await?;
+            Ok(transaction.signatures[0])
+        };
+        (rpc, fallback)
+    });
 
     for i in 0..swqos_clients.len() {
         let swqos_client = swqos_clients[i].clone();
         let payer = payer.clone();
+        let fee_payer = fee_payer.clone();
         let instructions = instructions.clone();
         let mut priority_fee = priority_fee.clone();
         let core_id = cores[i % cores.len()];
+        let sent = tasks.sent();
+        let tip = submission_tip(&swqos_client.get_swqos_type(), i, &priority_fee, trade_type);
 
-        let handle = tokio::spawn(async move {
+        tasks.spawn(swqos_client.get_swqos_type(), tip, async move {
             core_affinity::set_for_current(core_id);
 
             let mut timer = TradeTimer::new(format!("构建交易指令: {:?}", swqos_client.get_swqos_type()));
@@ -45,6 +517,7 @@ pub async fn parallel_execute_with_tips(
             {
                 build_sell_transaction(
                     payer,
+                    fee_payer,
                     &priority_fee,
                     instructions,
                     lookup_table_key,
@@ -58,6 +531,7 @@ pub async fn parallel_execute_with_tips(
                 let tip_account = Arc::new(Pubkey::from_str(&tip_account).map_err(|e| anyhow!(e))?);
                 build_sell_tip_transaction_with_priority_fee(
                     payer,
+                    fee_payer,
                     &priority_fee,
                     instructions,
                     &tip_account,
@@ -68,6 +542,7 @@ pub async fn parallel_execute_with_tips(
             } else if swqos_client.get_swqos_type() == SwqosType::Default {
                 build_rpc_transaction(
                     payer,
+                    fee_payer,
                     &priority_fee,
                     instructions,
                     lookup_table_key,
@@ -78,10 +553,12 @@ pub async fn parallel_execute_with_tips(
             } else {
                 let tip_account = swqos_client.get_tip_account()?;
                 let tip_account = Arc::new(Pubkey::from_str(&tip_account).map_err(|e| anyhow!(e))?);
-                priority_fee.buy_tip_fee = priority_fee.buy_tip_fees[i];
+                priority_fee.buy_tip_fee =
+                    priority_fee.buy_tip_fees.get(i).copied().unwrap_or(priority_fee.buy_tip_fee);
 
                 build_tip_transaction_with_priority_fee(
                     payer,
+                    fee_payer,
                     &priority_fee,
                     instructions,
                     &tip_account,
@@ -93,34 +570,237 @@ pub async fn parallel_execute_with_tips(
             };
 
             timer.stage(format!("提交交易指令: {:?}", swqos_client.get_swqos_type()));
+            sent.record(swqos_client.get_swqos_type(), transaction.signatures[0]);
 
             swqos_client
                 .send_transaction(trade_type, &transaction)
                 .await?;
 
             timer.finish();
-            Ok::<(), anyhow::Error>(())
+            Ok::<Signature, anyhow::Error>(transaction.signatures[0])
         });
+    }
 
-        handles.push(handle);
+    tasks.collect_or_fallback(race, fallback).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spawn_delayed(tasks: &mut SubmissionTasks, swqos_type: SwqosType, millis: u64, ok: bool) {
+        tasks.spawn(swqos_type, 0.0, async move {
+            tokio::time::sleep(Duration::from_millis(millis)).await;
+            if ok { Ok(Signature::default()) } else { Err(anyhow!("rejected")) }
+        });
     }
 
-    // 等待所有任务完成
-    let mut errors = Vec::new();
-    for handle in handles {
-        match handle.await {
-            Ok(Ok(_)) => (),
-            Ok(Err(e)) => errors.push(format!("Task error: {}", e)),
-            Err(e) => errors.push(format!("Join error: {}", e)),
-        }
+    #[test]
+    fn test_rpc_report_carries_signature() {
+        let signature = Signature::from([3u8; 64]);
+        let report = SubmissionReport::rpc(signature, Duration::from_millis(5));
+        assert_eq!(report.winner().map(|timing| timing.swqos_type.clone()), Some(SwqosType::Default));
+        assert_eq!(report.submitted(), vec![(SwqosType::Default, signature, 0.0)]);
+        assert!(!report.rpc_fallback);
     }
 
-    if !errors.is_empty() {
-        for error in &errors {
-            println!("{}", error);
-        }
-        return Err(anyhow!("Some tasks failed: {:?}", errors));
+    #[tokio::test]
+    async fn test_sent_notifier_reports_first_signature_only() {
+        let (notifier, sent) = SentNotifier::new();
+        let first = Signature::from([1u8; 64]);
+        notifier.clone().notify(SwqosType::Jito, first);
+        notifier.notify(SwqosType::Default, Signature::from([2u8; 64]));
+        assert_eq!(sent.await.unwrap(), (SwqosType::Jito, first));
+
+        let (notifier, sent) = SentNotifier::new();
+        drop(notifier);
+        assert!(sent.await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_race_cancels_remaining_submissions() {
+        let mut tasks = SubmissionTasks::default();
+        spawn_delayed(&mut tasks, SwqosType::Jito, 5_000, true);
+        spawn_delayed(&mut tasks, SwqosType::Node1, 1, false);
+        spawn_delayed(&mut tasks, SwqosType::BlockRazor, 20, true);
+
+        let start = Instant::now();
+        let report = tasks.collect(true).await.unwrap();
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        let winner = report.winner().unwrap();
+        assert_eq!(winner.swqos_type, SwqosType::BlockRazor);
+        assert_eq!(report.timings.len(), 3);
+        assert_eq!(report.timings[0].status, SubmissionStatus::Failed("rejected".to_string()));
+        assert_eq!(report.timings[2].swqos_type, SwqosType::Jito);
+        assert_eq!(report.timings[2].status, SubmissionStatus::Cancelled);
     }
 
-    Ok(())
+    #[tokio::test]
+    async fn test_await_all_fails_on_any_error() {
+        let mut tasks = SubmissionTasks::default();
+        spawn_delayed(&mut tasks, SwqosType::Jito, 1, true);
+        spawn_delayed(&mut tasks, SwqosType::Node1, 10, false);
+        assert!(tasks.collect(false).await.is_err());
+
+        let mut tasks = SubmissionTasks::default();
+        spawn_delayed(&mut tasks, SwqosType::Jito, 1, false);
+        spawn_delayed(&mut tasks, SwqosType::Node1, 10, false);
+        assert!(tasks.collect(true).await.is_err());
+
+        let mut tasks = SubmissionTasks::default();
+        spawn_delayed(&mut tasks, SwqosType::Jito, 10, true);
+        spawn_delayed(&mut tasks, SwqosType::Node1, 1, true);
+        let report = tasks.collect(false).await.unwrap();
+        assert_eq!(report.winner().unwrap().swqos_type, SwqosType::Node1);
+        assert!(report.timings.iter().all(|t| t.status == SubmissionStatus::Succeeded));
+    }
+
+    #[tokio::test]
+    async fn test_submission_tips() {
+        let priority_fee =
+            PriorityFee { buy_tip_fees: vec![0.1, 0.2], buy_tip_fee: 0.05, sell_tip_fee: 0.01, ..Default::default() };
+        assert_eq!(submission_tip(&SwqosType::Jito, 1, &priority_fee, TradeType::Buy), 0.2);
+        // 小费列表不足时使用 buy_tip_fee
+        assert_eq!(submission_tip(&SwqosType::Jito, 2, &priority_fee, TradeType::Buy), 0.05);
+        assert_eq!(submission_tip(&SwqosType::Node1, 0, &priority_fee, TradeType::Sell), 0.01);
+        assert_eq!(submission_tip(&SwqosType::Default, 0, &priority_fee, TradeType::Buy), 0.0);
+
+        // 只有提交成功的通道参与胜出统计，并带上各自的小费
+        let mut tasks = SubmissionTasks::default();
+        tasks.spawn(SwqosType::Jito, 0.1, async { Ok(Signature::default()) });
+        tasks.spawn(SwqosType::Node1, 0.2, async { Err(anyhow!("rejected")) });
+        let report = tasks.collect(true).await.unwrap();
+        assert_eq!(report.submitted(), vec![(SwqosType::Jito, Signature::default(), 0.1)]);
+    }
+
+    #[test]
+    fn test_select_swqos_types() {
+        use crate::swqos::{SwqosConfig, SwqosRegion};
+        use solana_sdk::commitment_config::CommitmentConfig;
+
+        let swqos_clients: Vec<Arc<SwqosClient>> = [
+            SwqosConfig::Jito("".to_string(), SwqosRegion::Frankfurt),
+            SwqosConfig::Node1("key".to_string(), SwqosRegion::Frankfurt),
+            SwqosConfig::Jito("".to_string(), SwqosRegion::NewYork),
+        ]
+        .into_iter()
+        .map(|config| {
+            SwqosConfig::get_swqos_client("http://127.0.0.1:8899".to_string(), CommitmentConfig::processed(), config)
+                .unwrap()
+        })
+        .collect();
+
+        let mut priority_fee = PriorityFee { buy_tip_fees: vec![0.1, 0.2, 0.3], ..Default::default() };
+        let selected = select_swqos_types(swqos_clients.clone(), &[SwqosType::Jito], &mut priority_fee).unwrap();
+        assert_eq!(selected.len(), 2);
+        assert_eq!(priority_fee.buy_tip_fees, vec![0.1, 0.3]);
+
+        // 小费少于通道时，缺少的用 buy_tip_fee 补齐
+        let mut priority_fee = PriorityFee { buy_tip_fee: 0.5, buy_tip_fees: vec![0.1], ..Default::default() };
+        select_swqos_types(swqos_clients.clone(), &[SwqosType::Jito], &mut priority_fee).unwrap();
+        assert_eq!(priority_fee.buy_tip_fees, vec![0.1, 0.5]);
+
+        let mut priority_fee = PriorityFee::default();
+        assert!(select_swqos_types(swqos_clients, &[SwqosType::BlockRazor], &mut priority_fee).is_err());
+    }
+
+    // 未发出任何交易时不会查询 RPC，地址不可达也不影响
+    fn unreachable_rpc() -> Arc<SolanaRpcClient> {
+        Arc::new(SolanaRpcClient::new("http://127.0.0.1:1".to_string()))
+    }
+
+    #[tokio::test]
+    async fn test_rpc_fallback_only_when_all_fail() {
+        let mut tasks = SubmissionTasks::default();
+        spawn_delayed(&mut tasks, SwqosType::Jito, 1, false);
+        spawn_delayed(&mut tasks, SwqosType::Node1, 1, false);
+        let fallback = (unreachable_rpc(), async { Ok(Signature::default()) });
+        let report = tasks.collect_or_fallback(false, Some(fallback)).await.unwrap();
+        assert!(report.rpc_fallback);
+        assert_eq!(report.winner().unwrap().swqos_type, SwqosType::Default);
+        assert_eq!(report.timings.len(), 3);
+
+        let mut tasks = SubmissionTasks::default();
+        spawn_delayed(&mut tasks, SwqosType::Jito, 1, false);
+        let fallback = (unreachable_rpc(), async { Err(anyhow!("rpc down")) });
+        assert!(tasks.collect_or_fallback(true, Some(fallback)).await.is_err());
+
+        let mut tasks = SubmissionTasks::default();
+        spawn_delayed(&mut tasks, SwqosType::Jito, 1, true);
+        spawn_delayed(&mut tasks, SwqosType::Node1, 1, false);
+        let fallback = (unreachable_rpc(), async { panic!("fallback must not run") });
+        assert!(tasks.collect_or_fallback(false, Some(fallback)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_no_rpc_fallback_after_transaction_sent() {
+        // 交易已发出但通道报错（如确认超时），无法确认其未上链时不回退
+        let mut tasks = SubmissionTasks::default();
+        let sent = tasks.sent();
+        tasks.spawn(SwqosType::Jito, 0.001, async move {
+            sent.record(SwqosType::Jito, Signature::default());
+            Err(anyhow!("confirmation timeout"))
+        });
+        let fallback = (unreachable_rpc(), async { panic!("fallback must not run") });
+        let report = tasks.collect_or_fallback(true, Some(fallback)).await;
+        assert!(report.is_err());
+    }
+
+    #[test]
+    fn test_submission_attempt_records_channels() {
+        let blockhash = Hash::new_unique();
+        let planned = vec![(SwqosType::Jito, 0.001), (SwqosType::Node1, 0.002)];
+
+        let rejected: Result<SubmissionReport> = Err(anyhow!("Some tasks failed: [Jito: rejected]"));
+        let attempt = SubmissionAttempt::new(blockhash, planned.clone(), Some(300), &rejected);
+        assert_eq!((attempt.blockhash, attempt.swqos.clone()), (blockhash, planned.clone()));
+        assert_eq!(attempt.error.as_deref(), Some("Some tasks failed: [Jito: rejected]"));
+        assert!(attempt.signatures.is_empty());
+
+        // 提交成功时按实际完成的通道记录，包括 RPC 回退
+        let signature = Signature::new_unique();
+        let report = SubmissionReport {
+            timings: vec![
+                SubmissionTiming {
+                    swqos_type: SwqosType::Jito,
+                    elapsed: Duration::ZERO,
+                    status: SubmissionStatus::Failed("rejected".to_string()),
+                    signature: None,
+                    tip: 0.001,
+                },
+                SubmissionTiming {
+                    swqos_type: SwqosType::Default,
+                    elapsed: Duration::ZERO,
+                    status: SubmissionStatus::Succeeded,
+                    signature: Some(signature),
+                    tip: 0.0,
+                },
+            ],
+            rpc_fallback: true,
+            ..Default::default()
+        };
+        let attempt = SubmissionAttempt::new(blockhash, planned, Some(300), &Ok(report));
+        assert_eq!(attempt.swqos, vec![(SwqosType::Jito, 0.001), (SwqosType::Default, 0.0)]);
+        assert_eq!((attempt.signatures, attempt.error, attempt.landed), (vec![signature], None, None));
+        assert_ne!(next_trade_id(), next_trade_id());
+    }
+
+    #[test]
+    fn test_mark_landed() {
+        let mut report = SubmissionReport {
+            timings: vec![SubmissionTiming {
+                swqos_type: SwqosType::Jito,
+                elapsed: Duration::ZERO,
+                status: SubmissionStatus::Failed("confirmation timeout".to_string()),
+                signature: None,
+                tip: 0.001,
+            }],
+            ..Default::default()
+        };
+        let signature = Signature::new_unique();
+        report.mark_landed(&SwqosType::Jito, signature);
+        assert_eq!(report.winner().unwrap().signature, Some(signature));
+        assert!(!report.rpc_fallback);
+    }
 }
@@ -4,18 +4,33 @@ use std::sync::Arc;
 
 use super::traits::ProtocolParams;
 use crate::common::bonding_curve::BondingCurveAccount;
-use crate::common::{PriorityFee, SolanaRpcClient};
+use crate::common::{PriorityFee, SolanaRpcClient, WsolPolicy};
 use crate::constants::bonk::accounts::{PLATFORM_FEE_RATE, PROTOCOL_FEE_RATE, SHARE_FEE_RATE};
+use crate::constants::bonk::launch;
+use crate::constants::jupiter::DEFAULT_API_URL;
 use crate::solana_streamer_sdk::streaming::event_parser::common::EventType;
 use crate::solana_streamer_sdk::streaming::event_parser::protocols::bonk::BonkTradeEvent;
-use crate::swqos::SwqosClient;
+use crate::solana_streamer_sdk::streaming::event_parser::protocols::pumpfun::PumpFunTradeEvent;
+use crate::solana_streamer_sdk::streaming::event_parser::protocols::pumpswap::{
+    PumpSwapBuyEvent, PumpSwapSellEvent,
+};
+use crate::solana_streamer_sdk::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
+use crate::swqos::solana_rpc::SolRpcClient;
+use crate::swqos::{SwqosClient, SwqosType};
+use crate::trading::core::parallel::SentNotifier;
 use crate::trading::bonk::common::{get_amount_in, get_amount_in_net, get_amount_out};
+use crate::trading::boop::curve::BondingCurve as BoopBondingCurve;
+use crate::trading::moonshot::curve::CurveAccount;
 
 /// 通用买入参数
 #[derive(Clone)]
 pub struct BuyParams {
     pub rpc: Option<Arc<SolanaRpcClient>>,
+    /// getProgramAccounts 等重查询使用的 RPC，未设置时使用 rpc
+    pub read_rpc: Option<Arc<SolanaRpcClient>>,
     pub payer: Arc<Keypair>,
+    /// 支付网络费用和小费的账户，未设置时由 payer 支付
+    pub fee_payer: Option<Arc<Keypair>>,
     pub mint: Pubkey,
     pub creator: Pubkey,
     pub sol_amount: u64,
@@ -24,6 +39,10 @@ pub struct BuyParams {
     pub lookup_table_key: Option<Pubkey>,
     pub recent_blockhash: Hash,
     pub data_size_limit: u32,
+    /// 交易前后 wSOL 账户的处理方式
+    pub wsol_policy: WsolPolicy,
+    /// 只提交到这些类型的 SWQOS 通道，None 为所有通道
+    pub swqos_types: Option<Vec<SwqosType>>,
     pub protocol_params: Box<dyn ProtocolParams>,
 }
 
@@ -31,8 +50,12 @@ pub struct BuyParams {
 #[derive(Clone)]
 pub struct BuyWithTipParams {
     pub rpc: Option<Arc<SolanaRpcClient>>,
+    /// getProgramAccounts 等重查询使用的 RPC，未设置时使用 rpc
+    pub read_rpc: Option<Arc<SolanaRpcClient>>,
     pub swqos_clients: Vec<Arc<SwqosClient>>,
     pub payer: Arc<Keypair>,
+    /// 支付网络费用和小费的账户，未设置时由 payer 支付
+    pub fee_payer: Option<Arc<Keypair>>,
     pub mint: Pubkey,
     pub creator: Pubkey,
     pub sol_amount: u64,
@@ -41,38 +64,72 @@ pub struct BuyWithTipParams {
     pub lookup_table_key: Option<Pubkey>,
     pub recent_blockhash: Hash,
     pub data_size_limit: u32,
+    /// 交易前后 wSOL 账户的处理方式
+    pub wsol_policy: WsolPolicy,
+    /// 为 true 时第一个 SWQOS 成功即返回并取消其余提交
+    pub race_submissions: bool,
+    /// 所有 SWQOS 都失败后用于提交不带小费交易的 RPC 通道，未设置时不回退
+    pub rpc_fallback: Option<Arc<SolRpcClient>>,
     pub protocol_params: Box<dyn ProtocolParams>,
+    /// 第一笔交易交给 SWQOS 或 RPC 发送时通知，`SubmissionMode::FireAndTrack` 使用
+    pub on_sent: Option<SentNotifier>,
 }
 
 /// 通用卖出参数
 #[derive(Clone)]
 pub struct SellParams {
     pub rpc: Option<Arc<SolanaRpcClient>>,
+    /// getProgramAccounts 等重查询使用的 RPC，未设置时使用 rpc
+    pub read_rpc: Option<Arc<SolanaRpcClient>>,
     pub payer: Arc<Keypair>,
+    /// 支付网络费用和小费的账户，未设置时由 payer 支付
+    pub fee_payer: Option<Arc<Keypair>>,
     pub mint: Pubkey,
     pub creator: Pubkey,
     pub token_amount: Option<u64>,
     pub slippage_basis_points: Option<u64>,
+    /// 最少获得的 SOL 数量（lamports），设置后替代按滑点计算的下限
+    pub min_sol_out: Option<u64>,
     pub priority_fee: PriorityFee,
     pub lookup_table_key: Option<Pubkey>,
     pub recent_blockhash: Hash,
+    /// 交易前后 wSOL 账户的处理方式
+    pub wsol_policy: WsolPolicy,
+    /// 只提交到这些类型的 SWQOS 通道，None 为所有通道
+    pub swqos_types: Option<Vec<SwqosType>>,
     pub protocol_params: Box<dyn ProtocolParams>,
+    /// 第一笔交易交给 SWQOS 或 RPC 发送时通知，`SubmissionMode::FireAndTrack` 使用
+    pub on_sent: Option<SentNotifier>,
 }
 
 /// 带MEV服务的卖出参数
 #[derive(Clone)]
 pub struct SellWithTipParams {
     pub rpc: Option<Arc<SolanaRpcClient>>,
+    /// getProgramAccounts 等重查询使用的 RPC，未设置时使用 rpc
+    pub read_rpc: Option<Arc<SolanaRpcClient>>,
     pub swqos_clients: Vec<Arc<SwqosClient>>,
     pub payer: Arc<Keypair>,
+    /// 支付网络费用和小费的账户，未设置时由 payer 支付
+    pub fee_payer: Option<Arc<Keypair>>,
     pub mint: Pubkey,
     pub creator: Pubkey,
     pub token_amount: Option<u64>,
     pub slippage_basis_points: Option<u64>,
+    /// 最少获得的 SOL 数量（lamports），设置后替代按滑点计算的下限
+    pub min_sol_out: Option<u64>,
     pub priority_fee: PriorityFee,
     pub lookup_table_key: Option<Pubkey>,
     pub recent_blockhash: Hash,
+    /// 交易前后 wSOL 账户的处理方式
+    pub wsol_policy: WsolPolicy,
+    /// 为 true 时第一个 SWQOS 成功即返回并取消其余提交
+    pub race_submissions: bool,
+    /// 所有 SWQOS 都失败后用于提交不带小费交易的 RPC 通道，未设置时不回退
+    pub rpc_fallback: Option<Arc<SolRpcClient>>,
     pub protocol_params: Box<dyn ProtocolParams>,
+    /// 第一笔交易交给 SWQOS 或 RPC 发送时通知，`SubmissionMode::FireAndTrack` 使用
+    pub on_sent: Option<SentNotifier>,
 }
 
 /// PumpFun协议特定参数
@@ -87,6 +144,13 @@ impl PumpFunParams {
             bonding_curve: None,
         }
     }
+
+    /// 使用交易事件后的 bonding curve 状态，买入时不需要再查询链上
+    pub fn from_trade(trade_info: &PumpFunTradeEvent) -> Self {
+        Self {
+            bonding_curve: Some(Arc::new(BondingCurveAccount::from_trade(trade_info))),
+        }
+    }
 }
 
 impl ProtocolParams for PumpFunParams {
@@ -99,6 +163,37 @@ impl ProtocolParams for PumpFunParams {
     }
 }
 
+/// PumpFun发币参数，元数据由 Metaplex 元数据账户保存
+#[derive(Clone, Debug)]
+pub struct PumpFunCreateParams {
+    pub name: String,
+    pub symbol: String,
+    /// 元数据 JSON 地址（名称、图片、社交链接等）
+    pub uri: String,
+}
+
+/// Bonk（letsbonk.fun）发币参数，元数据由 Metaplex 元数据账户保存
+#[derive(Clone, Debug)]
+pub struct BonkCreateParams {
+    pub name: String,
+    pub symbol: String,
+    /// 元数据 JSON 地址（名称、图片、社交链接等），需事先上传
+    pub uri: String,
+    /// 代币小数位，letsbonk.fun 默认为 6
+    pub decimals: u8,
+}
+
+impl BonkCreateParams {
+    pub fn new(name: impl Into<String>, symbol: impl Into<String>, uri: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            symbol: symbol.into(),
+            uri: uri.into(),
+            decimals: launch::DECIMALS,
+        }
+    }
+}
+
 /// PumpSwap Protocol Specific Parameters
 ///
 /// Parameters for configuring PumpSwap trading protocol, including liquidity pool information,
@@ -145,6 +240,42 @@ impl PumpSwapParams {
             auto_handle_wsol: true,
         }
     }
+
+    /// 使用买入事件中的池子和交易后的储备，LP 手续费留在池子中
+    pub fn from_buy_trade(trade_info: &PumpSwapBuyEvent) -> Self {
+        Self {
+            pool: Some(trade_info.pool),
+            base_mint: Some(trade_info.base_mint),
+            quote_mint: Some(trade_info.quote_mint),
+            pool_base_token_reserves: Some(
+                trade_info.pool_base_token_reserves.saturating_sub(trade_info.base_amount_out),
+            ),
+            pool_quote_token_reserves: Some(
+                trade_info
+                    .pool_quote_token_reserves
+                    .saturating_add(trade_info.quote_amount_in_with_lp_fee),
+            ),
+            auto_handle_wsol: true,
+        }
+    }
+
+    /// 使用卖出事件中的池子和交易后的储备，LP 手续费留在池子中
+    pub fn from_sell_trade(trade_info: &PumpSwapSellEvent) -> Self {
+        Self {
+            pool: Some(trade_info.pool),
+            base_mint: Some(trade_info.base_mint),
+            quote_mint: Some(trade_info.quote_mint),
+            pool_base_token_reserves: Some(
+                trade_info.pool_base_token_reserves.saturating_add(trade_info.base_amount_in),
+            ),
+            pool_quote_token_reserves: Some(
+                trade_info
+                    .pool_quote_token_reserves
+                    .saturating_sub(trade_info.quote_amount_out_without_lp_fee),
+            ),
+            auto_handle_wsol: true,
+        }
+    }
 }
 
 impl ProtocolParams for PumpSwapParams {
@@ -188,8 +319,6 @@ impl BonkParams {
     }
 
     pub fn from_dev_trade(trade_info: BonkTradeEvent) -> Self {
-        const DEFAULT_VIRTUAL_BASE: u128 = 1073025605596382;
-        const DEFAULT_VIRTUAL_QUOTE: u128 = 30000852951;
         let amount_in = if trade_info.metadata.event_type == EventType::BonkBuyExactIn {
             trade_info.amount_in
         } else {
@@ -198,8 +327,8 @@ impl BonkParams {
                 PROTOCOL_FEE_RATE,
                 PLATFORM_FEE_RATE,
                 SHARE_FEE_RATE,
-                DEFAULT_VIRTUAL_BASE,
-                DEFAULT_VIRTUAL_QUOTE,
+                launch::VIRTUAL_BASE,
+                launch::VIRTUAL_QUOTE,
                 0,
                 0,
                 0,
@@ -217,8 +346,8 @@ impl BonkParams {
                 PROTOCOL_FEE_RATE,
                 PLATFORM_FEE_RATE,
                 SHARE_FEE_RATE,
-                DEFAULT_VIRTUAL_BASE,
-                DEFAULT_VIRTUAL_QUOTE,
+                launch::VIRTUAL_BASE,
+                launch::VIRTUAL_QUOTE,
                 0,
                 0,
                 0,
@@ -228,8 +357,8 @@ impl BonkParams {
         };
         let real_base = amount_out;
         Self {
-            virtual_base: Some(DEFAULT_VIRTUAL_BASE),
-            virtual_quote: Some(DEFAULT_VIRTUAL_QUOTE),
+            virtual_base: Some(launch::VIRTUAL_BASE),
+            virtual_quote: Some(launch::VIRTUAL_QUOTE),
             real_base: Some(real_base),
             real_quote: Some(real_quote),
             auto_handle_wsol: true,
@@ -254,6 +383,7 @@ pub struct RaydiumCpmmParams {
     pub pool_state: Option<Pubkey>,
     /// 代币程序ID
     /// 指定代币使用的程序，通常为 spl_token::ID 或 spl_token_2022::ID
+    /// 为 None 时通过 RPC 自动检测，Token-2022 代币会同时读取转账手续费配置
     pub mint_token_program: Option<Pubkey>,
     /// 指定 mint_token 在 pool_state 账户数据中的索引位置
     /// 默认值为1，表示在索引1的位置
@@ -266,12 +396,28 @@ impl RaydiumCpmmParams {
     pub fn default() -> Self {
         Self {
             pool_state: None,
-            mint_token_program: Some(spl_token::ID),
+            mint_token_program: None,
             mint_token_in_pool_state_index: Some(1),
             minimum_amount_out: None,
             auto_handle_wsol: true,
         }
     }
+
+    /// 使用交易事件中的池子，Raydium CPMM 池子的 token0 地址小于 token1，据此确定代币位置
+    pub fn from_trade(trade_info: &RaydiumCpmmSwapEvent, mint: &Pubkey) -> Self {
+        let other = if trade_info.input_token_mint == *mint {
+            trade_info.output_token_mint
+        } else {
+            trade_info.input_token_mint
+        };
+        Self {
+            pool_state: Some(trade_info.pool_state),
+            mint_token_program: None,
+            mint_token_in_pool_state_index: Some(if *mint > other { 1 } else { 0 }),
+            minimum_amount_out: None,
+            auto_handle_wsol: true,
+        }
+    }
 }
 
 impl ProtocolParams for RaydiumCpmmParams {
@@ -284,13 +430,210 @@ impl ProtocolParams for RaydiumCpmmParams {
     }
 }
 
+/// RaydiumAmmV4协议特定参数
+#[derive(Clone)]
+pub struct RaydiumAmmV4Params {
+    /// 池子（AmmInfo）账户地址
+    /// 为 None 时通过 getProgramAccounts 查找，会增加延迟
+    pub amm: Option<Pubkey>,
+    /// 池子中基础代币（coin）的可交易储备
+    /// 与 pc_reserve 同时提供时不再通过 RPC 查询储备
+    pub coin_reserve: Option<u64>,
+    /// 池子中报价代币（pc）的可交易储备
+    pub pc_reserve: Option<u64>,
+    pub auto_handle_wsol: bool,
+}
+
+impl Default for RaydiumAmmV4Params {
+    fn default() -> Self {
+        Self { amm: None, coin_reserve: None, pc_reserve: None, auto_handle_wsol: true }
+    }
+}
+
+impl ProtocolParams for RaydiumAmmV4Params {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn ProtocolParams> {
+        Box::new(self.clone())
+    }
+}
+
+/// RaydiumClmm协议特定参数
+#[derive(Clone)]
+pub struct RaydiumClmmParams {
+    /// 池子（PoolState）账户地址
+    /// 为 None 时通过 getProgramAccounts 查找流动性最大的 WSOL 池子，会增加延迟
+    pub pool: Option<Pubkey>,
+    /// swap 使用的 tick array 账户，按交易方向排列
+    /// 为 None 时根据池子的 tick array 位图自动解析
+    pub tick_arrays: Option<Vec<Pubkey>>,
+    /// 最小输出数量，直接作为指令中的下限
+    /// 为 None 时按当前价格区间的流动性报价并扣除滑点
+    pub minimum_amount_out: Option<u64>,
+    pub auto_handle_wsol: bool,
+}
+
+impl Default for RaydiumClmmParams {
+    fn default() -> Self {
+        Self { pool: None, tick_arrays: None, minimum_amount_out: None, auto_handle_wsol: true }
+    }
+}
+
+impl ProtocolParams for RaydiumClmmParams {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn ProtocolParams> {
+        Box::new(self.clone())
+    }
+}
+
+/// OrcaWhirlpool协议特定参数
+#[derive(Clone)]
+pub struct OrcaWhirlpoolParams {
+    /// Whirlpool 池子账户地址
+    /// 为 None 时通过 getProgramAccounts 查找流动性最大的 WSOL 池子，会增加延迟
+    pub pool: Option<Pubkey>,
+    /// swap 使用的三个 tick array 账户，按交易方向排列
+    /// 为 None 时根据池子当前 tick 计算
+    pub tick_arrays: Option<Vec<Pubkey>>,
+    /// 最小输出数量，直接作为指令中的下限
+    /// 为 None 时按当前价格区间的流动性报价并扣除滑点
+    pub minimum_amount_out: Option<u64>,
+    pub auto_handle_wsol: bool,
+}
+
+impl Default for OrcaWhirlpoolParams {
+    fn default() -> Self {
+        Self { pool: None, tick_arrays: None, minimum_amount_out: None, auto_handle_wsol: true }
+    }
+}
+
+impl ProtocolParams for OrcaWhirlpoolParams {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn ProtocolParams> {
+        Box::new(self.clone())
+    }
+}
+
+/// Moonshot协议特定参数
+#[derive(Clone, Default)]
+pub struct MoonshotParams {
+    /// bonding curve 账户状态，用于报价
+    /// 为 None 时通过 RPC 获取
+    pub curve: Option<CurveAccount>,
+}
+
+impl ProtocolParams for MoonshotParams {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn ProtocolParams> {
+        Box::new(self.clone())
+    }
+}
+
+/// Boop.fun协议特定参数
+#[derive(Clone, Default)]
+pub struct BoopParams {
+    /// bonding curve 账户状态，用于报价
+    /// 为 None 时通过 RPC 获取
+    pub bonding_curve: Option<BoopBondingCurve>,
+}
+
+impl ProtocolParams for BoopParams {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn ProtocolParams> {
+        Box::new(self.clone())
+    }
+}
+
+/// Phoenix协议特定参数
+#[derive(Clone)]
+pub struct PhoenixParams {
+    /// 市场账户地址
+    /// 为 None 时通过 getProgramAccounts 查找卖单深度最大的 WSOL 市场，会增加延迟
+    pub market: Option<Pubkey>,
+    pub auto_handle_wsol: bool,
+}
+
+impl Default for PhoenixParams {
+    fn default() -> Self {
+        Self { market: None, auto_handle_wsol: true }
+    }
+}
+
+impl ProtocolParams for PhoenixParams {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn ProtocolParams> {
+        Box::new(self.clone())
+    }
+}
+
+/// Jupiter聚合器特定参数
+#[derive(Clone)]
+pub struct JupiterParams {
+    /// Jupiter swap API 地址
+    pub api_url: String,
+    /// 付费 API 的 key，通过 x-api-key 请求头发送
+    pub api_key: Option<String>,
+    /// 只使用单跳路由
+    pub only_direct_routes: bool,
+    /// 路由涉及的账户数上限，过大的路由可能超出交易大小限制
+    pub max_accounts: Option<u8>,
+    /// 由 Jupiter 在交易中创建并关闭 wSOL 账户
+    pub auto_handle_wsol: bool,
+}
+
+impl Default for JupiterParams {
+    fn default() -> Self {
+        Self {
+            api_url: DEFAULT_API_URL.to_string(),
+            api_key: None,
+            only_direct_routes: false,
+            max_accounts: None,
+            auto_handle_wsol: true,
+        }
+    }
+}
+
+impl ProtocolParams for JupiterParams {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn ProtocolParams> {
+        Box::new(self.clone())
+    }
+}
+
 impl BuyParams {
     /// 转换为BuyWithTipParams
-    pub fn with_tip(self, swqos_clients: Vec<Arc<SwqosClient>>) -> BuyWithTipParams {
+    pub fn with_tip(
+        self,
+        swqos_clients: Vec<Arc<SwqosClient>>,
+        race_submissions: bool,
+        rpc_fallback: Option<Arc<SolRpcClient>>,
+    ) -> BuyWithTipParams {
         BuyWithTipParams {
             rpc: self.rpc,
+            read_rpc: self.read_rpc,
             swqos_clients,
             payer: self.payer,
+            fee_payer: self.fee_payer,
             mint: self.mint,
             creator: self.creator,
             sol_amount: self.sol_amount,
@@ -299,26 +642,73 @@ impl BuyParams {
             lookup_table_key: self.lookup_table_key,
             recent_blockhash: self.recent_blockhash,
             data_size_limit: self.data_size_limit,
+            wsol_policy: self.wsol_policy,
+            race_submissions,
+            rpc_fallback,
             protocol_params: self.protocol_params,
+            on_sent: None,
         }
     }
 }
 
 impl SellParams {
     /// 转换为SellWithTipParams
-    pub fn with_tip(self, swqos_clients: Vec<Arc<SwqosClient>>) -> SellWithTipParams {
+    pub fn with_tip(
+        self,
+        swqos_clients: Vec<Arc<SwqosClient>>,
+        race_submissions: bool,
+        rpc_fallback: Option<Arc<SolRpcClient>>,
+    ) -> SellWithTipParams {
         SellWithTipParams {
             rpc: self.rpc,
+            read_rpc: self.read_rpc,
             swqos_clients,
             payer: self.payer,
+            fee_payer: self.fee_payer,
             mint: self.mint,
             creator: self.creator,
             token_amount: self.token_amount,
             slippage_basis_points: self.slippage_basis_points,
+            min_sol_out: self.min_sol_out,
             priority_fee: self.priority_fee,
             lookup_table_key: self.lookup_table_key,
             recent_blockhash: self.recent_blockhash,
+            wsol_policy: self.wsol_policy,
+            race_submissions,
+            rpc_fallback,
             protocol_params: self.protocol_params,
+            on_sent: self.on_sent,
+        }
+    }
+}
+
+#[cfg(test)]
+impl SellParams {
+    /// 测试用的卖出参数：通过 `rpc` 卖出 `token_amount` 个 `mint`，其余字段取默认值
+    pub(crate) fn for_test(
+        rpc: SolanaRpcClient,
+        mint: Pubkey,
+        token_amount: u64,
+        min_sol_out: Option<u64>,
+        protocol_params: Box<dyn ProtocolParams>,
+    ) -> Self {
+        Self {
+            rpc: Some(Arc::new(rpc)),
+            read_rpc: None,
+            payer: Arc::new(Keypair::new()),
+            fee_payer: None,
+            mint,
+            creator: Pubkey::default(),
+            token_amount: Some(token_amount),
+            slippage_basis_points: Some(1000),
+            min_sol_out,
+            priority_fee: PriorityFee::default(),
+            lookup_table_key: None,
+            recent_blockhash: Hash::default(),
+            wsol_policy: WsolPolicy::default(),
+            swqos_types: None,
+            protocol_params,
+            on_sent: None,
         }
     }
 }
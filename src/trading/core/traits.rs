@@ -1,5 +1,6 @@
 use anyhow::Result;
-use solana_sdk::instruction::Instruction;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey, transaction::VersionedTransaction};
+use super::parallel::SubmissionReport;
 use super::params::{BuyParams, BuyWithTipParams, SellParams, SellWithTipParams};
 
 /// 交易执行器trait - 定义了所有交易协议都需要实现的核心方法
@@ -9,13 +10,27 @@ pub trait TradeExecutor: Send + Sync {
     async fn buy(&self, params: BuyParams) -> Result<()>;
 
     /// 使用MEV服务执行买入交易
-    async fn buy_with_tip(&self, params: BuyWithTipParams) -> Result<()>;
+    async fn buy_with_tip(&self, params: BuyWithTipParams) -> Result<SubmissionReport>;
 
-    /// 执行卖出交易
-    async fn sell(&self, params: SellParams) -> Result<()>;
+    /// 执行卖出交易，不带小费直接通过 RPC 发送
+    async fn sell(&self, params: SellParams) -> Result<SubmissionReport>;
 
     /// 使用MEV服务执行卖出交易
-    async fn sell_with_tip(&self, params: SellWithTipParams) -> Result<()>;
+    async fn sell_with_tip(&self, params: SellWithTipParams) -> Result<SubmissionReport>;
+
+    /// 构建买入交易但不发送，指定小费账户时附带小费转账
+    async fn build_buy_transaction(
+        &self,
+        params: BuyParams,
+        tip_account: Option<Pubkey>,
+    ) -> Result<VersionedTransaction>;
+
+    /// 构建卖出交易但不发送，指定小费账户时附带小费转账
+    async fn build_sell_transaction(
+        &self,
+        params: SellParams,
+        tip_account: Option<Pubkey>,
+    ) -> Result<VersionedTransaction>;
 
     /// 获取协议名称
     fn protocol_name(&self) -> &'static str;
@@ -45,3 +60,13 @@ impl Clone for Box<dyn ProtocolParams> {
         self.clone_box()
     }
 }
+
+/// 让包含协议参数的结构体可以派生 Debug
+#[derive(Clone)]
+pub(crate) struct ProtocolParamsBox(pub(crate) Box<dyn ProtocolParams>);
+
+impl std::fmt::Debug for ProtocolParamsBox {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ProtocolParams")
+    }
+}
@@ -0,0 +1,77 @@
+use anyhow::{anyhow, Result};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    common::SolanaRpcClient,
+    constants::{bonk, raydium_cpmm},
+    trading::{
+        bonk::{common::get_pool_pda as get_bonk_pool_pda, pool::Pool as BonkPool},
+        boop::curve::BondingCurve as BoopCurve,
+        factory::DexType,
+        moonshot::curve::CurveAccount as MoonshotCurve,
+        pumpfun::common::get_bonding_curve_account_v2,
+        pumpswap::pool::Pool as PumpSwapPool,
+        raydium_amm_v4::pool::Pool as AmmV4Pool,
+        raydium_cpmm::common::get_pool_pda as get_cpmm_pool_pda,
+    },
+};
+
+/// Bonk 池子仍在 bonding curve 阶段（未迁移）时的状态值
+const BONK_POOL_STATUS_TRADING: u8 = 0;
+
+/// 识别代币当前可交易的协议
+///
+/// 按以下顺序检查：未完成的 PumpFun bonding curve、PumpSwap 池子、未迁移的 Bonk 池子、
+/// 未迁移的 Moonshot bonding curve、未毕业的 Boop.fun bonding curve、默认 AMM 配置下的 Raydium CPMM WSOL 池子、
+/// Raydium AMM v4 WSOL 池子。每一步都需要 RPC 查询，对延迟敏感的场景应缓存结果或直接指定协议。
+pub async fn resolve_dex(rpc: &SolanaRpcClient, mint: &Pubkey) -> Result<DexType> {
+    if let Ok((bonding_curve, _)) = get_bonding_curve_account_v2(rpc, mint).await {
+        if !bonding_curve.complete {
+            return Ok(DexType::PumpFun);
+        }
+    }
+
+    if PumpSwapPool::find_by_mint(rpc, mint).await.is_ok() {
+        return Ok(DexType::PumpSwap);
+    }
+
+    if let Some(pool_address) = get_bonk_pool_pda(mint, &bonk::accounts::WSOL_TOKEN_ACCOUNT) {
+        if let Ok(pool) = BonkPool::fetch(rpc, &pool_address).await {
+            if pool.status == BONK_POOL_STATUS_TRADING {
+                return Ok(DexType::Bonk);
+            }
+        }
+    }
+
+    if let Ok(curve) = MoonshotCurve::fetch(rpc, mint).await {
+        if curve.is_trading() {
+            return Ok(DexType::Moonshot);
+        }
+    }
+
+    if let Ok(curve) = BoopCurve::fetch(rpc, mint).await {
+        if curve.is_trading() {
+            return Ok(DexType::Boop);
+        }
+    }
+
+    let wsol = raydium_cpmm::accounts::WSOL_TOKEN_ACCOUNT;
+    for (mint0, mint1) in [(&wsol, mint), (mint, &wsol)] {
+        let Some(pool_address) =
+            get_cpmm_pool_pda(&raydium_cpmm::accounts::AMM_CONFIG, mint0, mint1)
+        else {
+            continue;
+        };
+        if let Ok(account) = rpc.get_account(&pool_address).await {
+            if account.owner == raydium_cpmm::accounts::RAYDIUM_CPMM {
+                return Ok(DexType::RaydiumCpmm);
+            }
+        }
+    }
+
+    if AmmV4Pool::find_by_mint(rpc, mint).await.is_ok() {
+        return Ok(DexType::RaydiumAmmV4);
+    }
+
+    Err(anyhow!("No supported DEX found for mint {}", mint))
+}
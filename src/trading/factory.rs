@@ -1,20 +1,32 @@
 use anyhow::{anyhow, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::sync::Arc;
 
 use crate::instruction::{
-    bonk::BonkInstructionBuilder, pumpfun::PumpFunInstructionBuilder,
-    pumpswap::PumpSwapInstructionBuilder, raydium_cpmm::RaydiumCpmmInstructionBuilder,
+    bonk::BonkInstructionBuilder, boop::BoopInstructionBuilder, moonshot::MoonshotInstructionBuilder,
+    orca_whirlpool::OrcaWhirlpoolInstructionBuilder, phoenix::PhoenixInstructionBuilder,
+    pumpfun::PumpFunInstructionBuilder,
+    pumpswap::PumpSwapInstructionBuilder, raydium_amm_v4::RaydiumAmmV4InstructionBuilder,
+    raydium_clmm::RaydiumClmmInstructionBuilder, raydium_cpmm::RaydiumCpmmInstructionBuilder,
 };
 
 use super::core::{executor::GenericTradeExecutor, traits::TradeExecutor};
+use super::jupiter::executor::JupiterTradeExecutor;
 
 /// 支持的交易协议
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum DexType {
     PumpFun,
     PumpSwap,
     Bonk,
     RaydiumCpmm,
+    RaydiumAmmV4,
+    RaydiumClmm,
+    OrcaWhirlpool,
+    Jupiter,
+    Moonshot,
+    Boop,
+    Phoenix,
 }
 
 impl std::fmt::Display for DexType {
@@ -24,6 +36,13 @@ impl std::fmt::Display for DexType {
             DexType::PumpSwap => write!(f, "PumpSwap"),
             DexType::Bonk => write!(f, "Bonk"),
             DexType::RaydiumCpmm => write!(f, "RaydiumCpmm"),
+            DexType::RaydiumAmmV4 => write!(f, "RaydiumAmmV4"),
+            DexType::RaydiumClmm => write!(f, "RaydiumClmm"),
+            DexType::OrcaWhirlpool => write!(f, "OrcaWhirlpool"),
+            DexType::Jupiter => write!(f, "Jupiter"),
+            DexType::Moonshot => write!(f, "Moonshot"),
+            DexType::Boop => write!(f, "Boop"),
+            DexType::Phoenix => write!(f, "Phoenix"),
         }
     }
 }
@@ -31,17 +50,40 @@ impl std::fmt::Display for DexType {
 impl std::str::FromStr for DexType {
     type Err = anyhow::Error;
 
+    /// 不区分大小写，忽略 `_` 和 `-`，`trade_platform` 中的名称也可解析
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
+        match s.to_lowercase().replace(['_', '-'], "").as_str() {
             "pumpfun" => Ok(DexType::PumpFun),
             "pumpswap" => Ok(DexType::PumpSwap),
             "bonk" => Ok(DexType::Bonk),
             "raydiumcpmm" => Ok(DexType::RaydiumCpmm),
+            "raydiumammv4" => Ok(DexType::RaydiumAmmV4),
+            "raydiumclmm" => Ok(DexType::RaydiumClmm),
+            "orcawhirlpool" => Ok(DexType::OrcaWhirlpool),
+            "jupiter" => Ok(DexType::Jupiter),
+            "moonshot" => Ok(DexType::Moonshot),
+            "boop" => Ok(DexType::Boop),
+            "phoenix" => Ok(DexType::Phoenix),
             _ => Err(anyhow!("Unsupported protocol: {}", s)),
         }
     }
 }
 
+/// 序列化为 `Display` 的名称
+impl Serialize for DexType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// 与 `FromStr` 接受相同的名称，配置文件中的协议名在加载时校验
+impl<'de> Deserialize<'de> for DexType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 /// 交易工厂 - 用于创建不同协议的交易执行器
 pub struct TradeFactory;
 
@@ -68,12 +110,49 @@ impl TradeFactory {
                     "RaydiumCpmm",
                 ))
             }
+            DexType::RaydiumAmmV4 => {
+                let instruction_builder = Arc::new(RaydiumAmmV4InstructionBuilder);
+                Arc::new(GenericTradeExecutor::new(instruction_builder, "RaydiumAmmV4"))
+            }
+            DexType::RaydiumClmm => {
+                let instruction_builder = Arc::new(RaydiumClmmInstructionBuilder);
+                Arc::new(GenericTradeExecutor::new(instruction_builder, "RaydiumClmm"))
+            }
+            DexType::OrcaWhirlpool => {
+                let instruction_builder = Arc::new(OrcaWhirlpoolInstructionBuilder);
+                Arc::new(GenericTradeExecutor::new(instruction_builder, "OrcaWhirlpool"))
+            }
+            DexType::Jupiter => Arc::new(JupiterTradeExecutor),
+            DexType::Moonshot => {
+                let instruction_builder = Arc::new(MoonshotInstructionBuilder);
+                Arc::new(GenericTradeExecutor::new(instruction_builder, "Moonshot"))
+            }
+            DexType::Boop => {
+                let instruction_builder = Arc::new(BoopInstructionBuilder);
+                Arc::new(GenericTradeExecutor::new(instruction_builder, "Boop"))
+            }
+            DexType::Phoenix => {
+                let instruction_builder = Arc::new(PhoenixInstructionBuilder);
+                Arc::new(GenericTradeExecutor::new(instruction_builder, "Phoenix"))
+            }
         }
     }
 
     /// 获取所有支持的协议
     pub fn supported_dex_types() -> Vec<DexType> {
-        vec![DexType::PumpFun, DexType::PumpSwap, DexType::Bonk, DexType::RaydiumCpmm]
+        vec![
+            DexType::PumpFun,
+            DexType::PumpSwap,
+            DexType::Bonk,
+            DexType::RaydiumCpmm,
+            DexType::RaydiumAmmV4,
+            DexType::RaydiumClmm,
+            DexType::OrcaWhirlpool,
+            DexType::Jupiter,
+            DexType::Moonshot,
+            DexType::Boop,
+            DexType::Phoenix,
+        ]
     }
 
     /// 检查协议是否支持
@@ -81,3 +160,22 @@ impl TradeFactory {
         Self::supported_dex_types().contains(dex_type)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::trade_platform;
+
+    #[test]
+    fn test_dex_type_serde() {
+        for dex_type in TradeFactory::supported_dex_types() {
+            let json = serde_json::to_string(&dex_type).unwrap();
+            assert_eq!(serde_json::from_str::<DexType>(&json).unwrap(), dex_type);
+        }
+        assert_eq!(
+            serde_json::from_str::<DexType>(&format!("{:?}", trade_platform::RAYDIUM_AMM_V4)).unwrap(),
+            DexType::RaydiumAmmV4
+        );
+        assert!(serde_json::from_str::<DexType>("\"uniswap\"").is_err());
+    }
+}
@@ -0,0 +1,157 @@
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use reqwest::{Client, RequestBuilder};
+use serde_json::{json, Value};
+use solana_sdk::{pubkey::Pubkey, transaction::VersionedTransaction};
+
+static HTTP_CLIENT: OnceLock<Client> = OnceLock::new();
+
+fn http_client() -> &'static Client {
+    HTTP_CLIENT.get_or_init(|| {
+        Client::builder()
+            .pool_idle_timeout(Duration::from_secs(60))
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap()
+    })
+}
+
+/// Jupiter 报价，swap 时原样回传
+#[derive(Debug, Clone)]
+pub struct JupiterQuote {
+    pub in_amount: u64,
+    pub out_amount: u64,
+    /// 按滑点计算的最少输出，即 swap 指令中的下限
+    pub other_amount_threshold: u64,
+    pub slippage_bps: u64,
+    pub price_impact_pct: f64,
+    raw: Value,
+}
+
+impl JupiterQuote {
+    pub fn from_json(raw: Value) -> Result<Self> {
+        if let Some(error) = raw.get("error") {
+            return Err(anyhow!("Jupiter quote failed: {}", error));
+        }
+        Ok(Self {
+            in_amount: parse_u64(&raw, "inAmount")?,
+            out_amount: parse_u64(&raw, "outAmount")?,
+            other_amount_threshold: parse_u64(&raw, "otherAmountThreshold")?,
+            slippage_bps: raw["slippageBps"].as_u64().unwrap_or_default(),
+            price_impact_pct: raw["priceImpactPct"]
+                .as_str()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or_default(),
+            raw,
+        })
+    }
+}
+
+/// Jupiter swap API 客户端
+pub struct JupiterApi {
+    api_url: String,
+    api_key: Option<String>,
+}
+
+impl JupiterApi {
+    pub fn new(api_url: impl Into<String>, api_key: Option<String>) -> Self {
+        Self { api_url: api_url.into().trim_end_matches('/').to_string(), api_key }
+    }
+
+    /// 获取 `amount` 个 `input_mint` 兑换 `output_mint` 的最优路由
+    pub async fn quote(
+        &self,
+        input_mint: &Pubkey,
+        output_mint: &Pubkey,
+        amount: u64,
+        slippage_bps: u64,
+        only_direct_routes: bool,
+        max_accounts: Option<u8>,
+    ) -> Result<JupiterQuote> {
+        let mut query = vec![
+            ("inputMint", input_mint.to_string()),
+            ("outputMint", output_mint.to_string()),
+            ("amount", amount.to_string()),
+            ("slippageBps", slippage_bps.to_string()),
+            ("onlyDirectRoutes", only_direct_routes.to_string()),
+        ];
+        if let Some(max_accounts) = max_accounts {
+            query.push(("maxAccounts", max_accounts.to_string()));
+        }
+        let request = http_client().get(format!("{}/quote", self.api_url)).query(&query);
+        let raw = self.send(request).await?;
+        JupiterQuote::from_json(raw)
+    }
+
+    /// 获取报价对应的 swap 交易，由 `user` 签名，blockhash 和计算预算由 Jupiter 设置
+    pub async fn swap_transaction(
+        &self,
+        quote: &JupiterQuote,
+        user: &Pubkey,
+        wrap_and_unwrap_sol: bool,
+    ) -> Result<VersionedTransaction> {
+        let body = json!({
+            "quoteResponse": quote.raw,
+            "userPublicKey": user.to_string(),
+            "wrapAndUnwrapSol": wrap_and_unwrap_sol,
+            "dynamicComputeUnitLimit": true,
+        });
+        let request = http_client().post(format!("{}/swap", self.api_url)).json(&body);
+        let response = self.send(request).await?;
+        let swap_transaction = response["swapTransaction"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Jupiter swap failed: {}", response))?;
+        let bytes = STANDARD.decode(swap_transaction)?;
+        Ok(bincode::deserialize(&bytes)?)
+    }
+
+    async fn send(&self, request: RequestBuilder) -> Result<Value> {
+        let request = match &self.api_key {
+            Some(api_key) => request.header("x-api-key", api_key),
+            None => request,
+        };
+        let response = request.send().await?;
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            return Err(anyhow!("Jupiter API returned {}: {}", status, body));
+        }
+        Ok(serde_json::from_str(&body)?)
+    }
+}
+
+/// Jupiter 以字符串返回数量
+fn parse_u64(raw: &Value, field: &str) -> Result<u64> {
+    raw[field]
+        .as_str()
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| anyhow!("Jupiter quote is missing {}", field))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_quote() {
+        let quote = JupiterQuote::from_json(json!({
+            "inAmount": "1000000000",
+            "outAmount": "123456",
+            "otherAmountThreshold": "122000",
+            "slippageBps": 100,
+            "priceImpactPct": "0.0012",
+            "routePlan": [],
+        }))
+        .unwrap();
+        assert_eq!(quote.in_amount, 1_000_000_000);
+        assert_eq!(quote.out_amount, 123_456);
+        assert_eq!(quote.other_amount_threshold, 122_000);
+        assert_eq!(quote.slippage_bps, 100);
+        assert_eq!(quote.raw["routePlan"], json!([]));
+
+        assert!(JupiterQuote::from_json(json!({ "error": "No routes found" })).is_err());
+    }
+}
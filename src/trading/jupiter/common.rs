@@ -0,0 +1,198 @@
+use anyhow::{anyhow, Result};
+use solana_program::address_lookup_table::state::AddressLookupTable;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    message::{AddressLookupTableAccount, VersionedMessage},
+    pubkey::Pubkey,
+    transaction::VersionedTransaction,
+};
+
+use crate::{
+    common::{address_lookup_cache::AddressLookupTableCache, SolanaRpcClient},
+    constants::jupiter::{accounts, SET_COMPUTE_UNIT_LIMIT_TAG},
+};
+
+/// 从 Jupiter swap 交易中解析出的路由
+#[derive(Debug, Clone)]
+pub struct JupiterSwap {
+    /// swap 相关指令，已去除计算预算指令
+    pub instructions: Vec<Instruction>,
+    /// 路由使用的地址查找表
+    pub address_lookup_table_accounts: Vec<AddressLookupTableAccount>,
+    /// Jupiter 模拟得到的计算单元上限
+    pub compute_unit_limit: Option<u32>,
+}
+
+/// 解析 Jupiter 返回的 swap 交易，以便重新设置计算预算、小费并由本地签名
+///
+/// 查找表内容优先从 `AddressLookupTableCache` 读取，缺失时通过 RPC 获取并写入缓存。
+pub async fn decompile_swap_transaction(
+    rpc: &SolanaRpcClient,
+    transaction: &VersionedTransaction,
+) -> Result<JupiterSwap> {
+    let lookup_table_keys: Vec<Pubkey> = transaction
+        .message
+        .address_table_lookups()
+        .map(|lookups| lookups.iter().map(|lookup| lookup.account_key).collect())
+        .unwrap_or_default();
+    let address_lookup_table_accounts = get_lookup_tables(rpc, &lookup_table_keys).await?;
+    let instructions = decompile_instructions(&transaction.message, &address_lookup_table_accounts)?;
+    let (instructions, compute_unit_limit) = split_compute_budget(instructions);
+    Ok(JupiterSwap { instructions, address_lookup_table_accounts, compute_unit_limit })
+}
+
+/// 将编译后的消息还原为指令，v0 消息的查找表账户必须包含在 `address_lookup_table_accounts` 中
+pub fn decompile_instructions(
+    message: &VersionedMessage,
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+) -> Result<Vec<Instruction>> {
+    let header = message.header();
+    let static_keys = message.static_account_keys();
+    let num_signed = header.num_required_signatures as usize;
+    let num_writable_signed = num_signed - header.num_readonly_signed_accounts as usize;
+    let num_writable_unsigned = static_keys.len() - header.num_readonly_unsigned_accounts as usize;
+
+    // 账户顺序：静态账户、各查找表的可写账户、各查找表的只读账户
+    let mut account_metas: Vec<AccountMeta> = static_keys
+        .iter()
+        .enumerate()
+        .map(|(i, key)| {
+            let is_signer = i < num_signed;
+            let is_writable =
+                if is_signer { i < num_writable_signed } else { i < num_writable_unsigned };
+            AccountMeta { pubkey: *key, is_signer, is_writable }
+        })
+        .collect();
+    if let Some(lookups) = message.address_table_lookups() {
+        let mut readonly = vec![];
+        for lookup in lookups {
+            let table = address_lookup_table_accounts
+                .iter()
+                .find(|table| table.key == lookup.account_key)
+                .ok_or_else(|| anyhow!("Missing address lookup table {}", lookup.account_key))?;
+            let resolve = |index: &u8| {
+                table.addresses.get(*index as usize).copied().ok_or_else(|| {
+                    anyhow!("Index {} out of range in lookup table {}", index, table.key)
+                })
+            };
+            for index in &lookup.writable_indexes {
+                account_metas.push(AccountMeta::new(resolve(index)?, false));
+            }
+            for index in &lookup.readonly_indexes {
+                readonly.push(AccountMeta::new_readonly(resolve(index)?, false));
+            }
+        }
+        account_metas.extend(readonly);
+    }
+
+    let account_meta = |index: u8| {
+        account_metas
+            .get(index as usize)
+            .cloned()
+            .ok_or_else(|| anyhow!("Account index {} out of range", index))
+    };
+    message
+        .instructions()
+        .iter()
+        .map(|instruction| {
+            Ok(Instruction {
+                program_id: account_meta(instruction.program_id_index)?.pubkey,
+                accounts: instruction
+                    .accounts
+                    .iter()
+                    .map(|index| account_meta(*index))
+                    .collect::<Result<_>>()?,
+                data: instruction.data.clone(),
+            })
+        })
+        .collect()
+}
+
+/// 去除计算预算指令，返回其中设置的计算单元上限
+pub fn split_compute_budget(instructions: Vec<Instruction>) -> (Vec<Instruction>, Option<u32>) {
+    let mut compute_unit_limit = None;
+    let instructions = instructions
+        .into_iter()
+        .filter(|instruction| {
+            if instruction.program_id != accounts::COMPUTE_BUDGET_PROGRAM {
+                return true;
+            }
+            if let [SET_COMPUTE_UNIT_LIMIT_TAG, a, b, c, d] = instruction.data[..] {
+                compute_unit_limit = Some(u32::from_le_bytes([a, b, c, d]));
+            }
+            false
+        })
+        .collect();
+    (instructions, compute_unit_limit)
+}
+
+async fn get_lookup_tables(
+    rpc: &SolanaRpcClient,
+    lookup_table_keys: &[Pubkey],
+) -> Result<Vec<AddressLookupTableAccount>> {
+    let cache = AddressLookupTableCache::get_instance();
+    let mut tables = Vec::with_capacity(lookup_table_keys.len());
+    for key in lookup_table_keys {
+        let cached = cache.get_table(key).and_then(|info| info.address_lookup_table);
+        let table = match cached {
+            Some(table) if !table.addresses.is_empty() => table,
+            _ => {
+                let account = rpc.get_account(key).await?;
+                let lookup_table = AddressLookupTable::deserialize(&account.data)
+                    .map_err(|e| anyhow!("Invalid address lookup table {}: {}", key, e))?;
+                let table = AddressLookupTableAccount {
+                    key: *key,
+                    addresses: lookup_table.addresses.to_vec(),
+                };
+                cache.add_or_update_table(*key, Some(table.clone()), None);
+                table
+            }
+        };
+        tables.push(table);
+    }
+    Ok(tables)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{
+        compute_budget::ComputeBudgetInstruction, hash::Hash, message::v0,
+    };
+
+    #[test]
+    fn test_decompile_instructions() {
+        let payer = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+        let (writable, readonly) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: vec![readonly, writable],
+        };
+        let instructions = vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(250_000),
+            Instruction::new_with_bytes(
+                program,
+                &[1, 2, 3],
+                vec![
+                    AccountMeta::new(payer, true),
+                    AccountMeta::new(writable, false),
+                    AccountMeta::new_readonly(readonly, false),
+                ],
+            ),
+        ];
+        let message =
+            v0::Message::try_compile(&payer, &instructions, &[table.clone()], Hash::default())
+                .unwrap();
+        let message = VersionedMessage::V0(message);
+        assert_eq!(message.address_table_lookups().unwrap().len(), 1);
+
+        let decompiled = decompile_instructions(&message, &[table]).unwrap();
+        assert_eq!(decompiled, instructions);
+        assert!(decompile_instructions(&message, &[]).is_err());
+
+        let (swap_instructions, compute_unit_limit) = split_compute_budget(decompiled);
+        assert_eq!(swap_instructions, instructions[1..]);
+        assert_eq!(compute_unit_limit, Some(250_000));
+    }
+}
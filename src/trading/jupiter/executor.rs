@@ -0,0 +1,456 @@
+use anyhow::{anyhow, Result};
+use solana_hash::Hash;
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction, native_token::sol_str_to_lamports, pubkey::Pubkey,
+    signature::Keypair, signer::Signer, transaction::VersionedTransaction,
+};
+use solana_system_interface::instruction::transfer;
+use std::{str::FromStr, sync::Arc, time::Instant};
+
+use super::{
+    api::JupiterApi,
+    common::{decompile_swap_transaction, JupiterSwap},
+};
+use crate::{
+    common::{PriorityFee, SolanaRpcClient},
+    constants::{jupiter::accounts, trade::trade::DEFAULT_SLIPPAGE},
+    swqos::{solana_rpc::SolRpcClient, SwqosClient, SwqosClientTrait, SwqosType, TradeType},
+    trading::{
+        common::{
+            add_nonce_instruction, build_versioned_transaction, get_address_lookup_table_accounts,
+            get_mint_token_info, get_token_balance_with_program, get_transaction_blockhash,
+        },
+        core::{
+            parallel::{SentNotifier, SubmissionReport, SubmissionTasks},
+            params::{BuyParams, BuyWithTipParams, JupiterParams, SellParams, SellWithTipParams},
+            timer::TradeTimer,
+            traits::{ProtocolParams, TradeExecutor},
+        },
+    },
+};
+
+/// Jupiter聚合器的交易执行器
+///
+/// 从 Jupiter API 获取路由及 swap 交易，解析出 swap 指令后替换为本地的计算预算和各 SWQOS
+/// 的小费，由本地 payer 重新签名并提交，未直接支持的交易场所也能获得 MEV 保护。
+pub struct JupiterTradeExecutor;
+
+#[async_trait::async_trait]
+impl TradeExecutor for JupiterTradeExecutor {
+    async fn buy(&self, params: BuyParams) -> Result<()> {
+        let rpc = params.rpc.as_ref().ok_or_else(|| anyhow!("RPC is not set"))?;
+        let swap = route_buy(rpc, &params).await?;
+        let transaction = build_transaction(
+            params.payer,
+            params.fee_payer,
+            &params.priority_fee,
+            &swap,
+            TradeType::Buy,
+            None,
+            params.lookup_table_key,
+            params.recent_blockhash,
+        )
+        .await?;
+        rpc.send_and_confirm_transaction(&transaction).await?;
+        Ok(())
+    }
+
+    async fn buy_with_tip(&self, params: BuyWithTipParams) -> Result<SubmissionReport> {
+        let timer = TradeTimer::new("获取Jupiter路由");
+        let buy_params = BuyParams {
+            rpc: params.rpc,
+            read_rpc: params.read_rpc,
+            payer: params.payer.clone(),
+            fee_payer: params.fee_payer.clone(),
+            mint: params.mint,
+            creator: params.creator,
+            sol_amount: params.sol_amount,
+            slippage_basis_points: params.slippage_basis_points,
+            priority_fee: params.priority_fee.clone(),
+            lookup_table_key: params.lookup_table_key,
+            recent_blockhash: params.recent_blockhash,
+            data_size_limit: params.data_size_limit,
+            wsol_policy: params.wsol_policy,
+            swqos_types: None,
+            protocol_params: params.protocol_params.clone(),
+        };
+        let rpc = buy_params.rpc.as_ref().ok_or_else(|| anyhow!("RPC is not set"))?;
+        let swap = route_buy(rpc, &buy_params).await?;
+        timer.finish();
+
+        parallel_submit(
+            params.swqos_clients,
+            params.payer,
+            params.fee_payer,
+            params.priority_fee,
+            swap,
+            TradeType::Buy,
+            params.lookup_table_key,
+            params.recent_blockhash,
+            params.race_submissions,
+            params.rpc_fallback,
+            params.on_sent,
+        )
+        .await
+    }
+
+    async fn sell(&self, params: SellParams) -> Result<SubmissionReport> {
+        let rpc = params.rpc.as_ref().ok_or_else(|| anyhow!("RPC is not set"))?;
+        let swap = route_sell(rpc, &params).await?;
+        let transaction = build_transaction(
+            params.payer,
+            params.fee_payer,
+            &params.priority_fee,
+            &swap,
+            TradeType::Sell,
+            None,
+            params.lookup_table_key,
+            params.recent_blockhash,
+        )
+        .await?;
+        if let Some(on_sent) = &params.on_sent {
+            on_sent.notify(SwqosType::Default, transaction.signatures[0]);
+        }
+        let start = Instant::now();
+        let signature = rpc.send_and_confirm_transaction(&transaction).await?;
+        Ok(SubmissionReport::rpc(signature, start.elapsed()))
+    }
+
+    async fn sell_with_tip(&self, params: SellWithTipParams) -> Result<SubmissionReport> {
+        let timer = TradeTimer::new("获取Jupiter路由");
+        let sell_params = SellParams {
+            rpc: params.rpc,
+            read_rpc: params.read_rpc,
+            payer: params.payer.clone(),
+            fee_payer: params.fee_payer.clone(),
+            mint: params.mint,
+            creator: params.creator,
+            token_amount: params.token_amount,
+            slippage_basis_points: params.slippage_basis_points,
+            min_sol_out: params.min_sol_out,
+            priority_fee: params.priority_fee.clone(),
+            lookup_table_key: params.lookup_table_key,
+            recent_blockhash: params.recent_blockhash,
+            wsol_policy: params.wsol_policy,
+            swqos_types: None,
+            protocol_params: params.protocol_params.clone(),
+            on_sent: None,
+        };
+        let rpc = sell_params.rpc.as_ref().ok_or_else(|| anyhow!("RPC is not set"))?;
+        let swap = route_sell(rpc, &sell_params).await?;
+        timer.finish();
+
+        parallel_submit(
+            params.swqos_clients,
+            params.payer,
+            params.fee_payer,
+            params.priority_fee,
+            swap,
+            TradeType::Sell,
+            params.lookup_table_key,
+            params.recent_blockhash,
+            params.race_submissions,
+            params.rpc_fallback,
+            params.on_sent,
+        )
+        .await
+    }
+
+    async fn build_buy_transaction(
+        &self,
+        params: BuyParams,
+        tip_account: Option<Pubkey>,
+    ) -> Result<VersionedTransaction> {
+        let rpc = params.rpc.as_ref().ok_or_else(|| anyhow!("RPC is not set"))?;
+        let swap = route_buy(rpc, &params).await?;
+        let tip = tip_account.map(|tip_account| (tip_account, params.priority_fee.buy_tip_fee));
+        build_transaction(
+            params.payer,
+            params.fee_payer,
+            &params.priority_fee,
+            &swap,
+            TradeType::Buy,
+            tip,
+            params.lookup_table_key,
+            params.recent_blockhash,
+        )
+        .await
+    }
+
+    async fn build_sell_transaction(
+        &self,
+        params: SellParams,
+        tip_account: Option<Pubkey>,
+    ) -> Result<VersionedTransaction> {
+        let rpc = params.rpc.as_ref().ok_or_else(|| anyhow!("RPC is not set"))?;
+        let swap = route_sell(rpc, &params).await?;
+        let tip = tip_account.map(|tip_account| (tip_account, params.priority_fee.sell_tip_fee));
+        build_transaction(
+            params.payer,
+            params.fee_payer,
+            &params.priority_fee,
+            &swap,
+            TradeType::Sell,
+            tip,
+            params.lookup_table_key,
+            params.recent_blockhash,
+        )
+        .await
+    }
+
+    fn protocol_name(&self) -> &'static str {
+        "Jupiter"
+    }
+}
+
+fn jupiter_params(protocol_params: &dyn ProtocolParams) -> Result<&JupiterParams> {
+    protocol_params
+        .as_any()
+        .downcast_ref::<JupiterParams>()
+        .ok_or_else(|| anyhow!("Invalid protocol params for Jupiter"))
+}
+
+/// 获取用 `sol_amount` 买入 `mint` 的路由
+async fn route_buy(rpc: &SolanaRpcClient, params: &BuyParams) -> Result<JupiterSwap> {
+    if params.sol_amount == 0 {
+        return Err(anyhow!("Amount cannot be zero"));
+    }
+    let jupiter_params = jupiter_params(params.protocol_params.as_ref())?;
+    // 输入为 SOL，由 Jupiter 包装；UseExistingBalance 时直接使用已有的 wSOL
+    let wrap_and_unwrap_sol =
+        params.wsol_policy.resolve(jupiter_params.auto_handle_wsol).wraps_input();
+    route(
+        rpc,
+        &params.payer.pubkey(),
+        jupiter_params,
+        wrap_and_unwrap_sol,
+        &accounts::WSOL_TOKEN_ACCOUNT,
+        &params.mint,
+        params.sol_amount,
+        params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+        None,
+    )
+    .await
+}
+
+/// 获取卖出 `mint` 换回 SOL 的路由，未指定数量时卖出全部余额
+async fn route_sell(rpc: &SolanaRpcClient, params: &SellParams) -> Result<JupiterSwap> {
+    let amount = match params.token_amount {
+        Some(amount) if amount > 0 => amount,
+        _ => {
+            let mint_info = get_mint_token_info(rpc, &params.mint).await?;
+            get_token_balance_with_program(
+                rpc,
+                &params.payer.pubkey(),
+                &params.mint,
+                &mint_info.token_program,
+            )
+            .await?
+        }
+    };
+    if amount == 0 {
+        return Err(anyhow!("Amount cannot be zero"));
+    }
+    let jupiter_params = jupiter_params(params.protocol_params.as_ref())?;
+    // 输出为 wSOL，只有 UnwrapAfterTrade 时由 Jupiter 解包为 SOL
+    let wrap_and_unwrap_sol =
+        params.wsol_policy.resolve(jupiter_params.auto_handle_wsol).unwraps_after_trade();
+    route(
+        rpc,
+        &params.payer.pubkey(),
+        jupiter_params,
+        wrap_and_unwrap_sol,
+        &params.mint,
+        &accounts::WSOL_TOKEN_ACCOUNT,
+        amount,
+        params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+        params.min_sol_out,
+    )
+    .await
+}
+
+/// 报价并获取 swap 交易，指定 `min_amount_out` 时保证指令中的下限不低于该值
+#[allow(clippy::too_many_arguments)]
+async fn route(
+    rpc: &SolanaRpcClient,
+    user: &Pubkey,
+    jupiter_params: &JupiterParams,
+    wrap_and_unwrap_sol: bool,
+    input_mint: &Pubkey,
+    output_mint: &Pubkey,
+    amount: u64,
+    slippage_bps: u64,
+    min_amount_out: Option<u64>,
+) -> Result<JupiterSwap> {
+    let api = JupiterApi::new(&jupiter_params.api_url, jupiter_params.api_key.clone());
+    let quote = |slippage_bps: u64| {
+        api.quote(
+            input_mint,
+            output_mint,
+            amount,
+            slippage_bps,
+            jupiter_params.only_direct_routes,
+            jupiter_params.max_accounts,
+        )
+    };
+    let mut jupiter_quote = quote(slippage_bps).await?;
+    if let Some(min_amount_out) = min_amount_out {
+        if jupiter_quote.other_amount_threshold < min_amount_out {
+            if jupiter_quote.out_amount < min_amount_out {
+                return Err(anyhow!(
+                    "Jupiter quote {} is below the minimum output {}",
+                    jupiter_quote.out_amount,
+                    min_amount_out
+                ));
+            }
+            // 收紧滑点后重新报价
+            let slippage_bps = (jupiter_quote.out_amount - min_amount_out) as u128 * 10_000
+                / jupiter_quote.out_amount as u128;
+            jupiter_quote = quote(slippage_bps as u64).await?;
+            if jupiter_quote.other_amount_threshold < min_amount_out {
+                return Err(anyhow!(
+                    "Jupiter quote moved below the minimum output {}",
+                    min_amount_out
+                ));
+            }
+        }
+    }
+    let transaction =
+        api.swap_transaction(&jupiter_quote, user, wrap_and_unwrap_sol).await?;
+    decompile_swap_transaction(rpc, &transaction).await
+}
+
+/// 使用本地的计算预算和小费重新构建 swap 交易并签名
+///
+/// 计算单元上限取配置值与 Jupiter 模拟值中的较大者。不设置加载账户数据大小上限，
+/// 聚合路由可能加载较大的池子账户。
+#[allow(clippy::too_many_arguments)]
+async fn build_transaction(
+    payer: Arc<Keypair>,
+    fee_payer: Option<Arc<Keypair>>,
+    priority_fee: &PriorityFee,
+    swap: &JupiterSwap,
+    trade_type: TradeType,
+    tip: Option<(Pubkey, f64)>,
+    lookup_table_key: Option<Pubkey>,
+    recent_blockhash: Hash,
+) -> Result<VersionedTransaction> {
+    let mut instructions = vec![];
+    let mut blockhash = recent_blockhash;
+
+    // 与其他协议一致，只有买入使用nonce
+    if matches!(trade_type, TradeType::Buy) {
+        add_nonce_instruction(&mut instructions, payer.as_ref())?;
+        blockhash = get_transaction_blockhash(recent_blockhash);
+    }
+
+    let (unit_price, unit_limit) = if tip.is_some() {
+        (priority_fee.unit_price, priority_fee.unit_limit)
+    } else {
+        (priority_fee.rpc_unit_price, priority_fee.rpc_unit_limit)
+    };
+    let unit_limit = unit_limit.max(swap.compute_unit_limit.unwrap_or_default());
+    instructions.push(ComputeBudgetInstruction::set_compute_unit_price(unit_price));
+    instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(unit_limit));
+
+    instructions.extend(swap.instructions.iter().cloned());
+
+    // 添加小费转账指令，由手续费账户支付
+    if let Some((tip_account, tip_amount)) = tip {
+        instructions.push(transfer(
+            &fee_payer.as_ref().unwrap_or(&payer).pubkey(),
+            &tip_account,
+            sol_str_to_lamports(tip_amount.to_string().as_str()).unwrap_or(0),
+        ));
+    }
+
+    let mut address_lookup_table_accounts = swap.address_lookup_table_accounts.clone();
+    address_lookup_table_accounts.extend(get_address_lookup_table_accounts(lookup_table_key).await);
+
+    build_versioned_transaction(payer, fee_payer, instructions, address_lookup_table_accounts, blockhash)
+        .await
+}
+
+/// 为每个 SWQOS 构建带对应小费的交易并并行提交
+#[allow(clippy::too_many_arguments)]
+async fn parallel_submit(
+    swqos_clients: Vec<Arc<SwqosClient>>,
+    payer: Arc<Keypair>,
+    fee_payer: Option<Arc<Keypair>>,
+    priority_fee: PriorityFee,
+    swap: JupiterSwap,
+    trade_type: TradeType,
+    lookup_table_key: Option<Pubkey>,
+    recent_blockhash: Hash,
+    race: bool,
+    rpc_fallback: Option<Arc<SolRpcClient>>,
+    on_sent: Option<SentNotifier>,
+) -> Result<SubmissionReport> {
+    let swap = Arc::new(swap);
+    let mut tasks = SubmissionTasks::with_notifier(on_sent);
+
+    // 回退交易不带小费
+    let fallback = rpc_fallback.map(|rpc_client| {
+        let rpc = rpc_client.rpc_client.clone();
+        let payer = payer.clone();
+        let fee_payer = fee_payer.clone();
+        let priority_fee = priority_fee.clone();
+        let swap = swap.clone();
+        let fallback = async move {
+            let transaction = build_transaction(
+                payer,
+                fee_payer,
+                &priority_fee,
+                &swap,
+                trade_type,
+                None,
+                lookup_table_key,
+                recent_blockhash,
+            )
+            .await?;
+            rpc_client.send_transaction(trade_type, &transaction).await?;
+            Ok(transaction.signatures[0])
+        };
+        (rpc, fallback)
+    });
+
+    for (i, swqos_client) in swqos_clients.into_iter().enumerate() {
+        let payer = payer.clone();
+        let fee_payer = fee_payer.clone();
+        let priority_fee = priority_fee.clone();
+        let swap = swap.clone();
+        let sent = tasks.sent();
+
+        let tip_amount = match trade_type {
+            _ if swqos_client.get_swqos_type() == SwqosType::Default => 0.0,
+            TradeType::Sell => priority_fee.sell_tip_fee,
+            _ => priority_fee.buy_tip_fees.get(i).copied().unwrap_or(priority_fee.buy_tip_fee),
+        };
+
+        tasks.spawn(swqos_client.get_swqos_type(), tip_amount, async move {
+            let tip = if swqos_client.get_swqos_type() == SwqosType::Default {
+                None
+            } else {
+                let tip_account = Pubkey::from_str(&swqos_client.get_tip_account()?)
+                    .map_err(|e| anyhow!(e))?;
+                Some((tip_account, tip_amount))
+            };
+            let transaction = build_transaction(
+                payer,
+                fee_payer,
+                &priority_fee,
+                &swap,
+                trade_type,
+                tip,
+                lookup_table_key,
+                recent_blockhash,
+            )
+            .await?;
+            sent.record(swqos_client.get_swqos_type(), transaction.signatures[0]);
+            swqos_client.send_transaction(trade_type, &transaction).await?;
+            Ok(transaction.signatures[0])
+        });
+    }
+
+    tasks.collect_or_fallback(race, fallback).await
+}
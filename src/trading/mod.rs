@@ -1,11 +1,29 @@
+pub mod arbitrage;
+pub mod router;
+pub mod twap;
+pub mod paper;
 pub mod common;
 pub mod core;
 pub mod factory;
+pub mod dex_resolver;
 pub mod bonk;
 pub mod pumpfun;
 pub mod pumpswap;
 pub mod raydium_cpmm;
+pub mod raydium_amm_v4;
+pub mod raydium_clmm;
+pub mod orca_whirlpool;
+pub mod jupiter;
+pub mod moonshot;
+pub mod boop;
+pub mod phoenix;
 
+pub use core::parallel::{SubmissionAttempt, SubmissionReport, SubmissionStatus, SubmissionTiming};
 pub use core::params::{BuyParams, BuyWithTipParams, SellParams, SellWithTipParams};
 pub use core::traits::{InstructionBuilder, TradeExecutor};
 pub use factory::TradeFactory;
+pub use arbitrage::{ArbitrageConfig, ArbitrageDetector, ArbitrageSignal, VenueQuote};
+pub use router::{Route, RouteLeg, Router, RouterConfig, Venue};
+pub use dex_resolver::resolve_dex;
+pub use paper::{PaperBroker, PaperFill};
+pub use twap::{plan_twap, TwapConfig, TwapOrder, TwapOrders, TwapSlice, TwapSliceOutcome};
@@ -0,0 +1,99 @@
+use solana_sdk::pubkey::Pubkey;
+
+use crate::constants::moonshot::{
+    accounts, seeds, FEE_BASIS_POINTS, INITIAL_VIRTUAL_COLLATERAL_RESERVES,
+    INITIAL_VIRTUAL_TOKEN_RESERVES,
+};
+
+use super::curve::CurveAccount;
+
+pub fn get_curve_pda(mint: &Pubkey) -> Option<Pubkey> {
+    let seeds: &[&[u8]; 2] = &[seeds::CURVE_SEED, mint.as_ref()];
+    let program_id: &Pubkey = &accounts::MOONSHOT;
+    let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
+    pda.map(|pubkey| pubkey.0)
+}
+
+/// 当前虚拟储备 (代币, SOL)，按已售出的代币数量沿恒定乘积曲线推算
+fn virtual_reserves(total_supply: u64, curve_amount: u64) -> (u128, u128) {
+    let sold = (total_supply as u128).saturating_sub(curve_amount as u128);
+    let virtual_token = INITIAL_VIRTUAL_TOKEN_RESERVES.saturating_sub(sold).max(1);
+    let virtual_collateral =
+        INITIAL_VIRTUAL_TOKEN_RESERVES * INITIAL_VIRTUAL_COLLATERAL_RESERVES / virtual_token;
+    (virtual_token, virtual_collateral)
+}
+
+/// 用 `sol_amount` lamports 可买入的代币数量，手续费从输入中扣除
+pub fn get_buy_token_amount_from_sol_amount(curve: &CurveAccount, sol_amount: u64) -> u64 {
+    let (virtual_token, virtual_collateral) =
+        virtual_reserves(curve.total_supply, curve.curve_amount);
+    let amount_in = sol_amount as u128 * (10_000 - FEE_BASIS_POINTS) / 10_000;
+    let new_virtual_token =
+        virtual_token * virtual_collateral / (virtual_collateral + amount_in);
+    let amount_out = virtual_token - new_virtual_token;
+    amount_out.min(curve.curve_amount as u128) as u64
+}
+
+/// 卖出 `token_amount` 个代币可获得的 lamports，已扣除手续费
+pub fn get_sell_sol_amount_from_token_amount(curve: &CurveAccount, token_amount: u64) -> u64 {
+    let (virtual_token, virtual_collateral) =
+        virtual_reserves(curve.total_supply, curve.curve_amount);
+    let new_virtual_collateral =
+        virtual_token * virtual_collateral / (virtual_token + token_amount as u128);
+    let amount_out = virtual_collateral - new_virtual_collateral;
+    (amount_out * (10_000 - FEE_BASIS_POINTS) / 10_000) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve(sold: u64) -> CurveAccount {
+        let total_supply = 1_000_000_000_000_000_000;
+        CurveAccount {
+            total_supply,
+            curve_amount: 800_000_000_000_000_000 - sold,
+            mint: Pubkey::new_unique(),
+            decimals: 9,
+            collateral_currency: 0,
+            curve_type: 0,
+            marketcap_threshold: 0,
+            marketcap_currency: 0,
+            migration_fee: 0,
+            coef_b: 25,
+            bump: 0,
+            migration_target: 0,
+        }
+    }
+
+    #[test]
+    fn test_buy_then_sell_loses_only_fees() {
+        let curve = curve(0);
+        let tokens = get_buy_token_amount_from_sol_amount(&curve, 1_000_000_000);
+        assert!(tokens > 0);
+
+        let mut after_buy = curve.clone();
+        after_buy.curve_amount -= tokens;
+        let sol_out = get_sell_sol_amount_from_token_amount(&after_buy, tokens);
+        // 两次 1% 手续费
+        assert!(sol_out < 1_000_000_000 * 99 / 100);
+        assert!(sol_out > 1_000_000_000 * 97 / 100);
+    }
+
+    #[test]
+    fn test_price_increases_as_tokens_sell() {
+        let early = get_buy_token_amount_from_sol_amount(&curve(0), 1_000_000_000);
+        let late = get_buy_token_amount_from_sol_amount(&curve(400_000_000_000_000_000), 1_000_000_000);
+        assert!(late < early);
+    }
+
+    #[test]
+    fn test_get_curve_pda() {
+        let mint = Pubkey::new_unique();
+        let (expected, _) = Pubkey::find_program_address(
+            &[seeds::CURVE_SEED, mint.as_ref()],
+            &accounts::MOONSHOT,
+        );
+        assert_eq!(get_curve_pda(&mint), Some(expected));
+    }
+}
@@ -0,0 +1,52 @@
+use crate::{common::SolanaRpcClient, constants::moonshot::accounts};
+use anyhow::anyhow;
+use borsh::BorshDeserialize;
+use solana_sdk::pubkey::Pubkey;
+
+use super::common::get_curve_pda;
+
+/// Moonshot bonding curve 账户
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct CurveAccount {
+    pub total_supply: u64,
+    /// 曲线中剩余可售的代币数量
+    pub curve_amount: u64,
+    pub mint: Pubkey,
+    pub decimals: u8,
+    pub collateral_currency: u8,
+    pub curve_type: u8,
+    pub marketcap_threshold: u64,
+    pub marketcap_currency: u8,
+    pub migration_fee: u64,
+    pub coef_b: u32,
+    pub bump: u8,
+    pub migration_target: u8,
+}
+
+impl CurveAccount {
+    pub fn from_bytes(data: &[u8]) -> Result<Self, anyhow::Error> {
+        if data.len() < 8 {
+            return Err(anyhow!("Curve account data is too short"));
+        }
+        // 账户末尾可能有预留空间，只解析已知字段
+        let curve = CurveAccount::deserialize(&mut &data[8..])?;
+        Ok(curve)
+    }
+
+    pub async fn fetch(rpc: &SolanaRpcClient, mint: &Pubkey) -> Result<Self, anyhow::Error> {
+        let curve_address =
+            get_curve_pda(mint).ok_or_else(|| anyhow!("Failed to derive curve account"))?;
+        let account = rpc.get_account(&curve_address).await?;
+
+        if account.owner != accounts::MOONSHOT {
+            return Err(anyhow!("Account is not owned by Moonshot program"));
+        }
+
+        Self::from_bytes(&account.data)
+    }
+
+    /// 曲线是否仍可交易（未售罄迁移）
+    pub fn is_trading(&self) -> bool {
+        self.curve_amount > 0
+    }
+}
@@ -0,0 +1,172 @@
+use borsh::BorshDeserialize;
+use solana_sdk::{pubkey::Pubkey, transaction::VersionedTransaction};
+
+use crate::constants::moonshot::{
+    accounts, BUY_DISCRIMINATOR, SELL_DISCRIMINATOR, TOKEN_MINT_DISCRIMINATOR,
+};
+
+/// Moonshot 买入 / 卖出，从指令数据解析
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoonshotTradeEvent {
+    pub mint: Pubkey,
+    pub curve_account: Pubkey,
+    pub user: Pubkey,
+    pub is_buy: bool,
+    /// 代币数量，买入时为报价，实际成交受滑点影响
+    pub token_amount: u64,
+    /// SOL 数量（lamports），卖出时为报价，实际成交受滑点影响
+    pub collateral_amount: u64,
+    pub fixed_side: u8,
+    pub slippage_bps: u64,
+    pub signature: String,
+    pub slot: u64,
+}
+
+/// Moonshot 发币
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoonshotTokenMintEvent {
+    pub mint: Pubkey,
+    pub curve_account: Pubkey,
+    pub creator: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub decimals: u8,
+    pub amount: u64,
+    pub signature: String,
+    pub slot: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MoonshotEvent {
+    Trade(MoonshotTradeEvent),
+    TokenMint(MoonshotTokenMintEvent),
+}
+
+#[derive(BorshDeserialize)]
+struct TradeArgs {
+    token_amount: u64,
+    collateral_amount: u64,
+    fixed_side: u8,
+    slippage_bps: u64,
+}
+
+#[derive(BorshDeserialize)]
+struct TokenMintArgs {
+    name: String,
+    symbol: String,
+    uri: String,
+    decimals: u8,
+    _collateral_currency: u8,
+    amount: u64,
+    _curve_type: u8,
+    _migration_target: u8,
+}
+
+/// 解析一条 Moonshot 指令，`accounts` 为指令按顺序引用的账户
+pub fn parse_instruction(
+    accounts: &[Pubkey],
+    data: &[u8],
+    signature: &str,
+    slot: u64,
+) -> Option<MoonshotEvent> {
+    if data.len() < 8 {
+        return None;
+    }
+    let (discriminator, mut args) = data.split_at(8);
+
+    if discriminator == BUY_DISCRIMINATOR || discriminator == SELL_DISCRIMINATOR {
+        // sender, sender_token_account, curve_account, curve_token_account, dex_fee, helio_fee, mint, ...
+        if accounts.len() < 7 {
+            return None;
+        }
+        let trade = TradeArgs::deserialize(&mut args).ok()?;
+        return Some(MoonshotEvent::Trade(MoonshotTradeEvent {
+            mint: accounts[6],
+            curve_account: accounts[2],
+            user: accounts[0],
+            is_buy: discriminator == BUY_DISCRIMINATOR,
+            token_amount: trade.token_amount,
+            collateral_amount: trade.collateral_amount,
+            fixed_side: trade.fixed_side,
+            slippage_bps: trade.slippage_bps,
+            signature: signature.to_string(),
+            slot,
+        }));
+    }
+
+    if discriminator == TOKEN_MINT_DISCRIMINATOR {
+        // sender, backend_authority, curve_account, mint, ...
+        if accounts.len() < 4 {
+            return None;
+        }
+        let token_mint = TokenMintArgs::deserialize(&mut args).ok()?;
+        return Some(MoonshotEvent::TokenMint(MoonshotTokenMintEvent {
+            mint: accounts[3],
+            curve_account: accounts[2],
+            creator: accounts[0],
+            name: token_mint.name,
+            symbol: token_mint.symbol,
+            uri: token_mint.uri,
+            decimals: token_mint.decimals,
+            amount: token_mint.amount,
+            signature: signature.to_string(),
+            slot,
+        }));
+    }
+
+    None
+}
+
+/// 解析交易中所有 Moonshot 顶层指令
+///
+/// 只使用交易的静态账户，引用地址查找表账户的指令会被跳过
+pub fn parse_transaction(transaction: &VersionedTransaction, slot: u64) -> Vec<MoonshotEvent> {
+    let signature = transaction.signatures.first().map(|s| s.to_string()).unwrap_or_default();
+    let account_keys = transaction.message.static_account_keys();
+
+    transaction
+        .message
+        .instructions()
+        .iter()
+        .filter(|ix| account_keys.get(ix.program_id_index as usize) == Some(&accounts::MOONSHOT))
+        .filter_map(|ix| {
+            let accounts: Option<Vec<Pubkey>> = ix
+                .accounts
+                .iter()
+                .map(|index| account_keys.get(*index as usize).copied())
+                .collect();
+            parse_instruction(&accounts?, &ix.data, &signature, slot)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::moonshot::FIXED_SIDE_EXACT_IN;
+
+    #[test]
+    fn test_parse_buy_instruction() {
+        let accounts: Vec<Pubkey> = (0..11).map(|_| Pubkey::new_unique()).collect();
+        let mut data = BUY_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&1_000u64.to_le_bytes());
+        data.extend_from_slice(&500u64.to_le_bytes());
+        data.push(FIXED_SIDE_EXACT_IN);
+        data.extend_from_slice(&100u64.to_le_bytes());
+
+        let Some(MoonshotEvent::Trade(trade)) = parse_instruction(&accounts, &data, "sig", 7)
+        else {
+            panic!("expected a trade event");
+        };
+        assert!(trade.is_buy);
+        assert_eq!(trade.user, accounts[0]);
+        assert_eq!(trade.curve_account, accounts[2]);
+        assert_eq!(trade.mint, accounts[6]);
+        assert_eq!((trade.token_amount, trade.collateral_amount, trade.slippage_bps), (1_000, 500, 100));
+        assert_eq!(trade.slot, 7);
+
+        assert!(parse_instruction(&accounts[..3], &data, "sig", 7).is_none());
+        assert!(parse_instruction(&accounts, &[0; 8], "sig", 7).is_none());
+    }
+}
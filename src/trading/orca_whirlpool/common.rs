@@ -0,0 +1,80 @@
+use crate::{
+    constants::orca_whirlpool::{accounts, seeds, SWAP_TICK_ARRAYS, TICK_ARRAY_SIZE},
+    trading::orca_whirlpool::pool::Pool,
+};
+use solana_sdk::pubkey::Pubkey;
+
+/// tick 上下限
+pub const MIN_TICK_INDEX: i32 = -443636;
+pub const MAX_TICK_INDEX: i32 = 443636;
+
+/// tick array PDA，起始 tick 以十进制字符串作为种子
+pub fn get_tick_array_pda(whirlpool: &Pubkey, start_tick_index: i32) -> Option<Pubkey> {
+    let start_tick_index = start_tick_index.to_string();
+    let seeds: &[&[u8]; 3] =
+        &[seeds::TICK_ARRAY_SEED, whirlpool.as_ref(), start_tick_index.as_bytes()];
+    let pda: Option<(Pubkey, u8)> =
+        Pubkey::try_find_program_address(seeds, &accounts::WHIRLPOOL_PROGRAM);
+    pda.map(|pubkey| pubkey.0)
+}
+
+pub fn get_oracle_pda(whirlpool: &Pubkey) -> Option<Pubkey> {
+    let seeds: &[&[u8]; 2] = &[seeds::ORACLE_SEED, whirlpool.as_ref()];
+    let pda: Option<(Pubkey, u8)> =
+        Pubkey::try_find_program_address(seeds, &accounts::WHIRLPOOL_PROGRAM);
+    pda.map(|pubkey| pubkey.0)
+}
+
+/// 包含 `tick` 的 tick array 之后第 `offset` 个 tick array 的起始 tick，超出 tick 范围时返回 None
+pub fn get_tick_array_start_index(tick: i32, tick_spacing: u16, offset: i32) -> Option<i32> {
+    let ticks_in_array = TICK_ARRAY_SIZE * tick_spacing as i32;
+    let start_index = (tick.div_euclid(ticks_in_array) + offset) * ticks_in_array;
+    let min_start_index = MIN_TICK_INDEX.div_euclid(ticks_in_array) * ticks_in_array;
+    (min_start_index..=MAX_TICK_INDEX).contains(&start_index).then_some(start_index)
+}
+
+/// 按交易方向解析 swap 使用的三个 tick array 起始 tick
+///
+/// `a_to_b` 为 true 时价格下降，依次取当前及更小的 tick array；反之从当前 tick 加一个
+/// tick_spacing 所在的 tick array 开始向上取。靠近 tick 边界不足三个时重复最后一个。
+pub fn get_tick_array_start_indexes(pool: &Pool, a_to_b: bool) -> Vec<i32> {
+    let shift = if a_to_b { 0 } else { pool.tick_spacing as i32 };
+    let step = if a_to_b { -1 } else { 1 };
+    let mut start_indexes = Vec::with_capacity(SWAP_TICK_ARRAYS);
+    for i in 0..SWAP_TICK_ARRAYS as i32 {
+        match get_tick_array_start_index(pool.tick_current_index + shift, pool.tick_spacing, i * step) {
+            Some(start_index) => start_indexes.push(start_index),
+            None => break,
+        }
+    }
+    while let Some(&last) = start_indexes.last() {
+        if start_indexes.len() == SWAP_TICK_ARRAYS {
+            break;
+        }
+        start_indexes.push(last);
+    }
+    start_indexes
+}
+
+/// 解析 swap 使用的 tick array 账户地址
+pub fn get_tick_arrays(whirlpool: &Pubkey, pool: &Pool, a_to_b: bool) -> Vec<Pubkey> {
+    get_tick_array_start_indexes(pool, a_to_b)
+        .into_iter()
+        .filter_map(|start_index| get_tick_array_pda(whirlpool, start_index))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tick_array_start_index() {
+        assert_eq!(get_tick_array_start_index(0, 64, 0), Some(0));
+        assert_eq!(get_tick_array_start_index(5631, 64, 0), Some(0));
+        assert_eq!(get_tick_array_start_index(5632, 64, 0), Some(5632));
+        assert_eq!(get_tick_array_start_index(-1, 64, 0), Some(-5632));
+        assert_eq!(get_tick_array_start_index(-1, 64, -1), Some(-11264));
+        assert_eq!(get_tick_array_start_index(MAX_TICK_INDEX, 64, 1), None);
+    }
+}
@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use crate::{
+    common::{AccountCache, SolanaRpcClient},
+    constants::orca_whirlpool::{accounts, TOKEN_MINT_A_OFFSET, TOKEN_MINT_B_OFFSET},
+};
+use anyhow::anyhow;
+use borsh::BorshDeserialize;
+use solana_account_decoder::UiAccountEncoding;
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct WhirlpoolRewardInfo {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub authority: Pubkey,
+    pub emissions_per_second_x64: u128,
+    pub growth_global_x64: u128,
+}
+
+/// Orca Whirlpool 池子账户
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct Pool {
+    pub whirlpools_config: Pubkey,
+    pub whirlpool_bump: [u8; 1],
+    pub tick_spacing: u16,
+    pub fee_tier_index_seed: [u8; 2],
+    /// 交易费率，单位为百万分之一
+    pub fee_rate: u16,
+    pub protocol_fee_rate: u16,
+    pub liquidity: u128,
+    pub sqrt_price: u128,
+    pub tick_current_index: i32,
+    pub protocol_fee_owed_a: u64,
+    pub protocol_fee_owed_b: u64,
+    pub token_mint_a: Pubkey,
+    pub token_vault_a: Pubkey,
+    pub fee_growth_global_a: u128,
+    pub token_mint_b: Pubkey,
+    pub token_vault_b: Pubkey,
+    pub fee_growth_global_b: u128,
+    pub reward_last_updated_timestamp: u64,
+    pub reward_infos: [WhirlpoolRewardInfo; 3],
+}
+
+impl Pool {
+    pub fn from_bytes(data: &[u8]) -> Result<Self, anyhow::Error> {
+        let pool = Pool::deserialize(&mut data.get(8..).unwrap_or_default())?;
+        Ok(pool)
+    }
+
+    pub async fn fetch(
+        rpc: &SolanaRpcClient,
+        pool_address: &Pubkey,
+    ) -> Result<Self, anyhow::Error> {
+        let account = rpc.get_account(pool_address).await?;
+        decode_owned(&account)
+    }
+
+    /// 通过 AccountCache 获取池子数据，接入订阅流后池子变化时自动失效
+    pub async fn fetch_cached(
+        rpc: &SolanaRpcClient,
+        pool_address: &Pubkey,
+    ) -> Result<Arc<Self>, anyhow::Error> {
+        let cached = AccountCache::get_instance()
+            .get_or_fetch(rpc, pool_address, decode_owned)
+            .await?;
+        Ok(cached.data)
+    }
+
+    /// 查找 `mint` 与 WSOL 的池子，存在多个费率档位时返回流动性最大的池子
+    pub async fn find_by_mint(
+        rpc: &SolanaRpcClient,
+        mint: &Pubkey,
+    ) -> Result<(Pubkey, Self), anyhow::Error> {
+        // 池子中 token_mint_a 总是较小的地址
+        let wsol = accounts::WSOL_TOKEN_ACCOUNT;
+        let (mint_a, mint_b) = if *mint < wsol { (mint, &wsol) } else { (&wsol, mint) };
+        let filters = vec![
+            solana_rpc_client_api::filter::RpcFilterType::Memcmp(
+                solana_client::rpc_filter::Memcmp::new_base58_encoded(
+                    TOKEN_MINT_A_OFFSET,
+                    &mint_a.to_bytes(),
+                ),
+            ),
+            solana_rpc_client_api::filter::RpcFilterType::Memcmp(
+                solana_client::rpc_filter::Memcmp::new_base58_encoded(
+                    TOKEN_MINT_B_OFFSET,
+                    &mint_b.to_bytes(),
+                ),
+            ),
+        ];
+        let config = solana_rpc_client_api::config::RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: solana_rpc_client_api::config::RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                data_slice: None,
+                commitment: None,
+                min_context_slot: None,
+            },
+            with_context: None,
+            sort_results: None,
+        };
+        let accounts = rpc
+            .get_program_accounts_with_config(&accounts::WHIRLPOOL_PROGRAM, config)
+            .await?;
+        accounts
+            .into_iter()
+            .filter_map(|(addr, acc)| Self::from_bytes(&acc.data).map(|pool| (addr, pool)).ok())
+            .max_by_key(|(_, pool)| pool.liquidity)
+            .ok_or_else(|| anyhow!("No pool found for mint {}", mint))
+    }
+}
+
+fn decode_owned(account: &Account) -> Result<Pool, anyhow::Error> {
+    if account.owner != accounts::WHIRLPOOL_PROGRAM {
+        return Err(anyhow!("Account is not owned by Orca Whirlpool program"));
+    }
+    Pool::from_bytes(&account.data)
+}
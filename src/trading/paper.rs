@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signature::Signature};
+
+use crate::swqos::{SwqosType, TradeType};
+use crate::trading::common::{FillModel, FillReport};
+use crate::trading::core::parallel::{SubmissionReport, SubmissionStatus, SubmissionTiming};
+use crate::trading::factory::DexType;
+use crate::trading::router::Venue;
+
+/// 模拟成交的网络费用（lamports），按单签名交易的基础费用估算
+pub const PAPER_FEE_LAMPORTS: u64 = 5_000;
+
+/// 一笔模拟成交
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaperFill {
+    /// 模拟交易签名，可用于 `SolanaTrade::get_fill_report` 查询
+    pub signature: Signature,
+    pub dex_type: DexType,
+    pub mint: Pubkey,
+    pub trade_type: TradeType,
+    pub fill: FillReport,
+    pub filled_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default)]
+struct PaperBook {
+    fills: Vec<PaperFill>,
+    by_signature: HashMap<Signature, usize>,
+    token_balances: HashMap<Pubkey, u64>,
+    /// 所有成交的 SOL 净变化（lamports），含网络费用
+    net_sol: i128,
+}
+
+/// 模拟成交引擎：按实时报价成交，不构建也不提交交易
+///
+/// 设置 `FillModel` 后改按模型和实时的池子储备成交，转账手续费仍按代币配置扣除。
+/// 成交结果与链上成交使用相同的 `FillReport`，返回的 `SubmissionReport` 带有模拟签名，
+/// 后续查询成交、开仓监控和止盈止损都按实盘流程进行。卖出不能超过模拟持仓
+#[derive(Debug, Default)]
+pub struct PaperBroker {
+    book: RwLock<PaperBook>,
+    /// 为 None 时按协议报价成交，含手续费和价格冲击
+    fill_model: Option<FillModel>,
+}
+
+impl PaperBroker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 使用指定的成交模型，`FillModel::NextTrade` 需要回放数据，在模拟盘中无法成交
+    pub fn with_fill_model(mut self, fill_model: Option<FillModel>) -> Self {
+        self.fill_model = fill_model;
+        self
+    }
+
+    /// 按报价模拟买入，到账数量已扣除协议手续费和转账手续费
+    pub fn fill_buy(
+        &self,
+        venue: &Venue,
+        sol_amount: u64,
+        decimals: u8,
+    ) -> Result<(SubmissionReport, FillReport)> {
+        let tokens_received = self.buy_amount_out(venue, sol_amount)?;
+        if tokens_received == 0 {
+            return Err(anyhow!("No paper fill for {} SOL of {}", sol_amount, venue.mint));
+        }
+        let fill = FillReport {
+            tokens_received: tokens_received as i128,
+            sol_spent: sol_amount as i128,
+            price_paid: price(sol_amount, tokens_received, decimals),
+            fee_lamports: PAPER_FEE_LAMPORTS,
+            decimals,
+            transfer_fee: venue.mint_info.inverse_transfer_fee(tokens_received),
+            transfer_fee_basis_points: venue.mint_info.transfer_fee_basis_points(),
+        };
+        Ok(self.record(venue, TradeType::Buy, fill))
+    }
+
+    /// 按报价模拟卖出，所得低于 `min_sol_out` 时与链上滑点保护一样失败
+    pub fn fill_sell(
+        &self,
+        venue: &Venue,
+        token_amount: u64,
+        min_sol_out: Option<u64>,
+        decimals: u8,
+    ) -> Result<(SubmissionReport, FillReport)> {
+        let balance = self.token_balance(&venue.mint);
+        if token_amount == 0 || token_amount > balance {
+            return Err(anyhow!(
+                "Paper balance of {} is {}, cannot sell {}",
+                venue.mint,
+                balance,
+                token_amount
+            ));
+        }
+        let sol_received = self.sell_amount_out(venue, token_amount)?;
+        if let Some(min_sol_out) = min_sol_out {
+            if sol_received < min_sol_out {
+                return Err(anyhow!(
+                    "Paper sell of {} would receive {} lamports, below the minimum {}",
+                    venue.mint,
+                    sol_received,
+                    min_sol_out
+                ));
+            }
+        }
+        let fill = FillReport {
+            tokens_received: -(token_amount as i128),
+            sol_spent: -(sol_received as i128),
+            price_paid: price(sol_received, token_amount, decimals),
+            fee_lamports: PAPER_FEE_LAMPORTS,
+            decimals,
+            transfer_fee: venue.mint_info.transfer_fee(token_amount),
+            transfer_fee_basis_points: venue.mint_info.transfer_fee_basis_points(),
+        };
+        Ok(self.record(venue, TradeType::Sell, fill))
+    }
+
+    /// 模拟签名对应的成交结果
+    pub fn fill(&self, signature: &Signature) -> Option<FillReport> {
+        let book = self.book.read().unwrap();
+        book.by_signature.get(signature).map(|index| book.fills[*index].fill)
+    }
+
+    /// 所有模拟成交，按成交顺序排列
+    pub fn fills(&self) -> Vec<PaperFill> {
+        self.book.read().unwrap().fills.clone()
+    }
+
+    /// 模拟持仓数量（代币最小单位）
+    pub fn token_balance(&self, mint: &Pubkey) -> u64 {
+        self.book.read().unwrap().token_balances.get(mint).copied().unwrap_or_default()
+    }
+
+    /// 所有模拟成交的 SOL 净变化（lamports），含网络费用，为正表示盈利
+    pub fn net_sol(&self) -> i128 {
+        self.book.read().unwrap().net_sol
+    }
+
+    /// 买入到账的代币数量，已扣除转账手续费
+    fn buy_amount_out(&self, venue: &Venue, sol_amount: u64) -> Result<u64> {
+        match &self.fill_model {
+            Some(fill_model) => {
+                let amount_out = fill_model.simulate_buy(&venue.market_snapshot(), sol_amount)?;
+                Ok(venue.mint_info.amount_after_transfer_fee(amount_out))
+            }
+            None => Ok(venue.buy_amount_out(sol_amount)),
+        }
+    }
+
+    /// 卖出所得的 lamports，池子按扣除转账手续费后的数量成交
+    fn sell_amount_out(&self, venue: &Venue, token_amount: u64) -> Result<u64> {
+        match &self.fill_model {
+            Some(fill_model) => fill_model.simulate_sell(
+                &venue.market_snapshot(),
+                venue.mint_info.amount_after_transfer_fee(token_amount),
+            ),
+            None => Ok(venue.sell_amount_out(token_amount)),
+        }
+    }
+
+    fn record(
+        &self,
+        venue: &Venue,
+        trade_type: TradeType,
+        fill: FillReport,
+    ) -> (SubmissionReport, FillReport) {
+        let signature = Signature::new_unique();
+        let mut book = self.book.write().unwrap();
+        let balance = book.token_balances.entry(venue.mint).or_default();
+        *balance = (*balance as i128 + fill.tokens_received) as u64;
+        if *balance == 0 {
+            book.token_balances.remove(&venue.mint);
+        }
+        book.net_sol -= fill.sol_spent + fill.fee_lamports as i128;
+        let index = book.fills.len();
+        book.by_signature.insert(signature, index);
+        book.fills.push(PaperFill {
+            signature,
+            dex_type: venue.dex_type.clone(),
+            mint: venue.mint,
+            trade_type,
+            fill,
+            filled_at: Utc::now(),
+        });
+        let report = SubmissionReport {
+            timings: vec![SubmissionTiming {
+                swqos_type: SwqosType::Default,
+                elapsed: Duration::ZERO,
+                status: SubmissionStatus::Succeeded,
+                signature: Some(signature),
+                tip: 0.0,
+            }],
+            ..Default::default()
+        };
+        (report, fill)
+    }
+}
+
+/// 成交均价，SOL / 代币，与链上成交结果的计算方式一致
+fn price(lamports: u64, token_amount: u64, decimals: u8) -> f64 {
+    (lamports as f64 / LAMPORTS_PER_SOL as f64)
+        / (token_amount as f64 / 10f64.powi(decimals as i32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::common::token_program::MintTokenInfo;
+    use crate::trading::router::Liquidity;
+    use crate::trading::VenueQuote;
+
+    fn pool(mint: Pubkey, sol_reserve: u64, fee_basis_points: u64) -> Venue {
+        Venue {
+            dex_type: DexType::PumpSwap,
+            mint,
+            liquidity: Liquidity::ConstantProduct {
+                quote: VenueQuote {
+                    dex_type: DexType::PumpSwap,
+                    pool: Pubkey::default(),
+                    sol_reserve,
+                    token_reserve: 1_000_000_000_000,
+                    fee_basis_points,
+                    transfer_fee_basis_points: 0,
+                },
+                sol_is_base: false,
+                coin_creator: None,
+            },
+            mint_info: MintTokenInfo::spl_token(),
+        }
+    }
+
+    #[test]
+    fn test_paper_fills_track_balance_and_pnl() {
+        let mint = Pubkey::new_unique();
+        let venue = |sol_reserve: u64| pool(mint, sol_reserve, 0);
+        let broker = PaperBroker::new();
+
+        let (report, fill) = broker.fill_buy(&venue(100_000_000_000), 1_000_000_000, 6).unwrap();
+        let tokens = fill.tokens_received as u64;
+        assert!(tokens > 0);
+        assert_eq!(broker.fill(&report.signatures()[0]), Some(fill));
+        assert_eq!(broker.token_balance(&mint), tokens);
+        assert!(broker.fill_sell(&venue(100_000_000_000), tokens + 1, None, 6).is_err());
+
+        // 池子 SOL 翻倍后卖出一半，最低所得不满足时失败
+        let price_up = venue(200_000_000_000);
+        assert!(broker.fill_sell(&price_up, tokens / 2, Some(10_000_000_000), 6).is_err());
+        let (_, sell) = broker.fill_sell(&price_up, tokens / 2, Some(900_000_000), 6).unwrap();
+        assert!(sell.sol_spent < -900_000_000);
+        assert_eq!(broker.token_balance(&mint), tokens - tokens / 2);
+        assert_eq!(broker.net_sol(), -1_000_000_000 - sell.sol_spent - 10_000);
+        assert_eq!(broker.fills().len(), 2);
+    }
+
+    #[test]
+    fn test_paper_fills_use_fill_model() {
+        let mint = Pubkey::new_unique();
+        let venue = pool(mint, 100_000_000_000, 25);
+        let quoted = PaperBroker::new().fill_buy(&venue, 1_000_000_000, 6).unwrap().1;
+
+        // 按中间价成交时没有手续费和价格冲击，到账数量多于报价
+        let broker = PaperBroker::new().with_fill_model(Some(FillModel::MidPrice));
+        let (_, fill) = broker.fill_buy(&venue, 1_000_000_000, 6).unwrap();
+        assert_eq!(fill.tokens_received, 10_000_000_000);
+        assert!(fill.tokens_received > quoted.tokens_received);
+        let (_, sell) = broker.fill_sell(&venue, 5_000_000_000, None, 6).unwrap();
+        assert_eq!(sell.sol_spent, -500_000_000);
+
+        let broker = PaperBroker::new().with_fill_model(Some(FillModel::NextTrade));
+        assert!(broker.fill_buy(&venue, 1_000_000_000, 6).is_err());
+    }
+}
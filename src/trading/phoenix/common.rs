@@ -0,0 +1,97 @@
+use solana_sdk::pubkey::Pubkey;
+
+use crate::constants::phoenix::{accounts, seeds};
+
+use super::market::Market;
+
+pub fn get_log_authority_pda() -> Option<Pubkey> {
+    let seeds: &[&[u8]; 1] = &[seeds::LOG_AUTHORITY_SEED];
+    let program_id: &Pubkey = &accounts::PHOENIX;
+    let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
+    pda.map(|pubkey| pubkey.0)
+}
+
+/// `num_base_lots` 个 base lot 在 `price_in_ticks` 价格下对应的 quote lot 数量
+fn quote_lots(market: &Market, price_in_ticks: u64, num_base_lots: u64) -> u128 {
+    num_base_lots as u128 * price_in_ticks as u128 * market.tick_size_in_quote_lots_per_base_unit as u128
+        / market.base_lots_per_base_unit.max(1) as u128
+}
+
+/// 用 `num_quote_lots` 个 quote lot 吃卖单可买到的 base lot 数量，taker 手续费从预算中预留
+pub fn get_buy_base_lots(market: &Market, num_quote_lots: u64) -> u64 {
+    let mut budget = num_quote_lots as u128 * 10_000 / (10_000 + market.taker_fee_bps as u128);
+    let mut base_lots = 0u128;
+    for level in &market.asks {
+        let price = level.price_in_ticks as u128 * market.tick_size_in_quote_lots_per_base_unit as u128;
+        if price == 0 {
+            continue;
+        }
+        let affordable = budget * market.base_lots_per_base_unit as u128 / price;
+        let filled = affordable.min(level.num_base_lots as u128);
+        base_lots += filled;
+        budget -= quote_lots(market, level.price_in_ticks, filled as u64).min(budget);
+        if filled < level.num_base_lots as u128 {
+            break;
+        }
+    }
+    base_lots as u64
+}
+
+/// 卖出 `num_base_lots` 个 base lot 吃买单可获得的 quote lot 数量，已扣除 taker 手续费
+pub fn get_sell_quote_lots(market: &Market, num_base_lots: u64) -> u64 {
+    let mut remaining = num_base_lots;
+    let mut received = 0u128;
+    for level in &market.bids {
+        let filled = remaining.min(level.num_base_lots);
+        received += quote_lots(market, level.price_in_ticks, filled);
+        remaining -= filled;
+        if remaining == 0 {
+            break;
+        }
+    }
+    (received * (10_000 - market.taker_fee_bps.min(10_000) as u128) / 10_000) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::phoenix::market::PriceLevel;
+
+    fn market() -> Market {
+        Market {
+            base_mint: Pubkey::new_unique(),
+            base_vault: Pubkey::new_unique(),
+            base_lot_size: 1_000,
+            quote_mint: accounts::WSOL_TOKEN_ACCOUNT,
+            quote_vault: Pubkey::new_unique(),
+            quote_lot_size: 1,
+            base_lots_per_base_unit: 1,
+            tick_size_in_quote_lots_per_base_unit: 1,
+            taker_fee_bps: 0,
+            bids: vec![
+                PriceLevel { price_in_ticks: 100, num_base_lots: 10 },
+                PriceLevel { price_in_ticks: 90, num_base_lots: 10 },
+            ],
+            asks: vec![
+                PriceLevel { price_in_ticks: 110, num_base_lots: 10 },
+                PriceLevel { price_in_ticks: 120, num_base_lots: 10 },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_walk_price_levels() {
+        let market = market();
+        // 第一档 10 lot 花费 1100，剩余 1200 在第二档买到 10 lot
+        assert_eq!(get_buy_base_lots(&market, 2_300), 20);
+        assert_eq!(get_buy_base_lots(&market, 1_700), 15);
+        // 超出深度时只能成交挂单总量
+        assert_eq!(get_buy_base_lots(&market, 1_000_000), 20);
+
+        assert_eq!(get_sell_quote_lots(&market, 15), 1_000 + 450);
+
+        let mut with_fee = market.clone();
+        with_fee.taker_fee_bps = 100;
+        assert_eq!(get_sell_quote_lots(&with_fee, 10), 990);
+    }
+}
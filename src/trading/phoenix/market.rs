@@ -0,0 +1,205 @@
+use crate::{
+    common::SolanaRpcClient,
+    constants::phoenix::{accounts, BASE_MINT_OFFSET, MARKET_HEADER_SIZE, QUOTE_MINT_OFFSET},
+};
+use anyhow::anyhow;
+use solana_account_decoder::UiAccountEncoding;
+use solana_sdk::pubkey::Pubkey;
+
+/// FIFOMarket 中订单簿之前的字段：填充 [u64; 32] 加 6 个 u64
+const BOOK_OFFSET: usize = MARKET_HEADER_SIZE + 256 + 48;
+/// 红黑树头部：root(u32) + 填充 [u32; 3] + 分配器 size(u64) / bump_index(u32) / free_list_head(u32)
+const TREE_HEADER_SIZE: usize = 32;
+/// 树节点：4 个寄存器(u32) + FIFOOrderId(16 字节) + FIFORestingOrder(32 字节)
+const TREE_NODE_SIZE: usize = 64;
+/// 节点寄存器：左子节点、右子节点、父节点、颜色
+const REGISTER_LEFT: usize = 0;
+const REGISTER_RIGHT: usize = 1;
+
+/// 订单簿中的一笔挂单
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriceLevel {
+    pub price_in_ticks: u64,
+    pub num_base_lots: u64,
+}
+
+/// Phoenix 市场账户中报价和下单所需的字段
+#[derive(Debug, Clone)]
+pub struct Market {
+    pub base_mint: Pubkey,
+    pub base_vault: Pubkey,
+    pub base_lot_size: u64,
+    pub quote_mint: Pubkey,
+    pub quote_vault: Pubkey,
+    pub quote_lot_size: u64,
+    pub base_lots_per_base_unit: u64,
+    pub tick_size_in_quote_lots_per_base_unit: u64,
+    pub taker_fee_bps: u64,
+    /// 买单，价格从高到低
+    pub bids: Vec<PriceLevel>,
+    /// 卖单，价格从低到高
+    pub asks: Vec<PriceLevel>,
+}
+
+impl Market {
+    pub fn from_bytes(data: &[u8]) -> Result<Self, anyhow::Error> {
+        let bids_size = read_u64(data, 16)? as usize;
+        let asks_size = read_u64(data, 24)? as usize;
+
+        let mut bids = read_tree(data, BOOK_OFFSET, bids_size)?;
+        let asks_offset = BOOK_OFFSET + TREE_HEADER_SIZE + bids_size * TREE_NODE_SIZE;
+        let mut asks = read_tree(data, asks_offset, asks_size)?;
+        bids.sort_by_key(|level| std::cmp::Reverse(level.price_in_ticks));
+        asks.sort_by_key(|level| level.price_in_ticks);
+
+        Ok(Self {
+            base_mint: read_pubkey(data, BASE_MINT_OFFSET)?,
+            base_vault: read_pubkey(data, BASE_MINT_OFFSET + 32)?,
+            base_lot_size: read_u64(data, 112)?,
+            quote_mint: read_pubkey(data, QUOTE_MINT_OFFSET)?,
+            quote_vault: read_pubkey(data, QUOTE_MINT_OFFSET + 32)?,
+            quote_lot_size: read_u64(data, 192)?,
+            base_lots_per_base_unit: read_u64(data, MARKET_HEADER_SIZE + 256)?,
+            tick_size_in_quote_lots_per_base_unit: read_u64(data, MARKET_HEADER_SIZE + 264)?,
+            taker_fee_bps: read_u64(data, MARKET_HEADER_SIZE + 280)?,
+            bids,
+            asks,
+        })
+    }
+
+    pub async fn fetch(rpc: &SolanaRpcClient, market: &Pubkey) -> Result<Self, anyhow::Error> {
+        let account = rpc.get_account(market).await?;
+
+        if account.owner != accounts::PHOENIX {
+            return Err(anyhow!("Account is not owned by Phoenix program"));
+        }
+
+        Self::from_bytes(&account.data)
+    }
+
+    /// 查找 `mint` 为 base、WSOL 为 quote 的市场，存在多个时返回卖单深度最大的市场
+    pub async fn find_by_mint(
+        rpc: &SolanaRpcClient,
+        mint: &Pubkey,
+    ) -> Result<(Pubkey, Self), anyhow::Error> {
+        let filters = vec![
+            solana_rpc_client_api::filter::RpcFilterType::Memcmp(
+                solana_client::rpc_filter::Memcmp::new_base58_encoded(
+                    BASE_MINT_OFFSET,
+                    &mint.to_bytes(),
+                ),
+            ),
+            solana_rpc_client_api::filter::RpcFilterType::Memcmp(
+                solana_client::rpc_filter::Memcmp::new_base58_encoded(
+                    QUOTE_MINT_OFFSET,
+                    &accounts::WSOL_TOKEN_ACCOUNT.to_bytes(),
+                ),
+            ),
+        ];
+        let config = solana_rpc_client_api::config::RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: solana_rpc_client_api::config::RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                data_slice: None,
+                commitment: None,
+                min_context_slot: None,
+            },
+            with_context: None,
+            sort_results: None,
+        };
+        let accounts = rpc.get_program_accounts_with_config(&accounts::PHOENIX, config).await?;
+        accounts
+            .into_iter()
+            .filter_map(|(addr, acc)| Self::from_bytes(&acc.data).map(|market| (addr, market)).ok())
+            .max_by_key(|(_, market)| market.asks.iter().map(|level| level.num_base_lots).sum::<u64>())
+            .ok_or_else(|| anyhow!("No Phoenix market found for mint {}", mint))
+    }
+}
+
+/// 从根节点遍历红黑树，只访问在树中的节点，已释放的节点不会出现
+fn read_tree(data: &[u8], offset: usize, max_size: usize) -> Result<Vec<PriceLevel>, anyhow::Error> {
+    let end = offset + TREE_HEADER_SIZE + max_size * TREE_NODE_SIZE;
+    if data.len() < end {
+        return Err(anyhow!("Phoenix market account is too short"));
+    }
+    let nodes = offset + TREE_HEADER_SIZE;
+    let node_offset = |index: u32| nodes + (index as usize - 1) * TREE_NODE_SIZE;
+    let register = |index: u32, register: usize| read_u32(data, node_offset(index) + register * 4);
+
+    let mut levels = Vec::new();
+    let mut stack = vec![read_u32(data, offset)?];
+    while let Some(index) = stack.pop() {
+        // 0 为哨兵节点
+        if index == 0 || index as usize > max_size {
+            continue;
+        }
+        if levels.len() >= max_size {
+            return Err(anyhow!("Phoenix order book tree is malformed"));
+        }
+        let node = node_offset(index);
+        levels.push(PriceLevel {
+            price_in_ticks: read_u64(data, node + 16)?,
+            num_base_lots: read_u64(data, node + 40)?,
+        });
+        stack.push(register(index, REGISTER_LEFT)?);
+        stack.push(register(index, REGISTER_RIGHT)?);
+    }
+    Ok(levels)
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64, anyhow::Error> {
+    data.get(offset..offset + 8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or_else(|| anyhow!("Phoenix market account is too short"))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, anyhow::Error> {
+    data.get(offset..offset + 4)
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or_else(|| anyhow!("Phoenix market account is too short"))
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Result<Pubkey, anyhow::Error> {
+    data.get(offset..offset + 32)
+        .map(|bytes| Pubkey::new_from_array(bytes.try_into().unwrap()))
+        .ok_or_else(|| anyhow!("Phoenix market account is too short"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_node(data: &mut [u8], tree: usize, index: u32, left: u32, right: u32, price: u64, lots: u64) {
+        let node = tree + TREE_HEADER_SIZE + (index as usize - 1) * TREE_NODE_SIZE;
+        data[node..node + 4].copy_from_slice(&left.to_le_bytes());
+        data[node + 4..node + 8].copy_from_slice(&right.to_le_bytes());
+        data[node + 16..node + 24].copy_from_slice(&price.to_le_bytes());
+        data[node + 40..node + 48].copy_from_slice(&lots.to_le_bytes());
+    }
+
+    #[test]
+    fn test_decode_order_book() {
+        let (bids_size, asks_size) = (4usize, 4usize);
+        let asks_offset = BOOK_OFFSET + TREE_HEADER_SIZE + bids_size * TREE_NODE_SIZE;
+        let mut data = vec![0u8; asks_offset + TREE_HEADER_SIZE + asks_size * TREE_NODE_SIZE];
+        data[16..24].copy_from_slice(&(bids_size as u64).to_le_bytes());
+        data[24..32].copy_from_slice(&(asks_size as u64).to_le_bytes());
+        data[MARKET_HEADER_SIZE + 256..MARKET_HEADER_SIZE + 264].copy_from_slice(&1_000u64.to_le_bytes());
+
+        // 买单树：根为 2，左子 1，右子 3；节点 4 已释放，不在树中
+        data[BOOK_OFFSET..BOOK_OFFSET + 4].copy_from_slice(&2u32.to_le_bytes());
+        write_node(&mut data, BOOK_OFFSET, 2, 1, 3, 100, 5);
+        write_node(&mut data, BOOK_OFFSET, 1, 0, 0, 99, 7);
+        write_node(&mut data, BOOK_OFFSET, 3, 0, 0, 101, 9);
+        write_node(&mut data, BOOK_OFFSET, 4, 0, 0, 500, 1);
+        // 卖单树只有一个节点
+        data[asks_offset..asks_offset + 4].copy_from_slice(&1u32.to_le_bytes());
+        write_node(&mut data, asks_offset, 1, 0, 0, 102, 3);
+
+        let market = Market::from_bytes(&data).unwrap();
+        let bid_prices: Vec<u64> = market.bids.iter().map(|level| level.price_in_ticks).collect();
+        assert_eq!(bid_prices, vec![101, 100, 99]);
+        assert_eq!(market.asks, vec![PriceLevel { price_in_ticks: 102, num_base_lots: 3 }]);
+        assert_eq!(market.base_lots_per_base_unit, 1_000);
+    }
+}
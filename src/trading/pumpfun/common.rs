@@ -78,6 +78,14 @@ pub fn get_global_volume_accumulator_pda() -> Option<Pubkey> {
     pda.map(|pubkey| pubkey.0)
 }
 
+#[inline]
+pub fn get_pool_authority_pda(mint: &Pubkey) -> Option<Pubkey> {
+    let seeds: &[&[u8]; 2] = &[constants::pumpfun::seeds::POOL_AUTHORITY_SEED, mint.as_ref()];
+    let program_id: &Pubkey = &constants::pumpfun::accounts::PUMPFUN;
+    let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
+    pda.map(|pubkey| pubkey.0)
+}
+
 #[inline]
 pub fn get_metadata_pda(mint: &Pubkey) -> Pubkey {
     Pubkey::find_program_address(
@@ -140,6 +148,25 @@ pub async fn get_bonding_curve_account_v2(
     Ok((Arc::new(bonding_curve), bonding_curve_pda))
 }
 
+/// 查询 bonding curve 并转换为交易使用的 `BondingCurveAccount`，其中包含创建者
+pub async fn fetch_bonding_curve(
+    rpc: &SolanaRpcClient,
+    mint: &Pubkey,
+) -> Result<BondingCurveAccount, anyhow::Error> {
+    let (curve, account) = get_bonding_curve_account_v2(rpc, mint).await?;
+    Ok(BondingCurveAccount {
+        discriminator: curve.discriminator,
+        account,
+        virtual_token_reserves: curve.virtual_token_reserves,
+        virtual_sol_reserves: curve.virtual_sol_reserves,
+        real_token_reserves: curve.real_token_reserves,
+        real_sol_reserves: curve.real_sol_reserves,
+        token_total_supply: curve.token_total_supply,
+        complete: curve.complete,
+        creator: curve.creator,
+    })
+}
+
 #[inline]
 pub fn get_buy_token_amount(
     bonding_curve_account: &BondingCurveAccount,
@@ -222,6 +249,15 @@ pub fn get_token_price(virtual_sol_reserves: u64, virtual_token_reserves: u64) -
     v_sol / v_tokens
 }
 
+/// Bonding curve 现价，lamports / 代币最小单位，与 `PriceMonitor` 和 `Router` 的价格单位一致
+#[inline]
+pub fn get_spot_price(virtual_sol_reserves: u64, virtual_token_reserves: u64) -> f64 {
+    if virtual_token_reserves == 0 {
+        return 0.0;
+    }
+    virtual_sol_reserves as f64 / virtual_token_reserves as f64
+}
+
 #[inline]
 pub fn get_buy_price(amount: u64, trade_info: &PumpFunTradeEvent) -> u64 {
     if amount == 0 {
@@ -0,0 +1,61 @@
+use anyhow::anyhow;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    common::{bonding_curve::BondingCurveAccount, SolanaRpcClient},
+    trading::{
+        core::params::PumpSwapParams,
+        pumpfun::common::get_bonding_curve_account_v2,
+        pumpswap::{common::get_canonical_pool_pda, pool::Pool},
+    },
+};
+
+/// bonding curve 已完成（代币已迁移到 PumpSwap）时返回迁移池子的交易参数和池子的代币创建者，未完成时返回 None
+///
+/// PumpSwap 的创建者费用账户按池子的 `coin_creator` 计算，调用方应以其替换传入的创建者。
+///
+/// 传入 `bonding_curve` 时直接使用其 `complete` 字段，否则通过 RPC 查询，查询失败时返回错误；
+/// 池子地址按迁移规则推导，无需 getProgramAccounts。
+pub async fn migrated_pumpswap_params(
+    rpc: &SolanaRpcClient,
+    mint: &Pubkey,
+    bonding_curve: Option<&BondingCurveAccount>,
+) -> Result<Option<(PumpSwapParams, Pubkey)>, anyhow::Error> {
+    let complete = match bonding_curve {
+        Some(bonding_curve) => bonding_curve.complete,
+        None => get_bonding_curve_account_v2(rpc, mint).await?.0.complete,
+    };
+    if !complete {
+        return Ok(None);
+    }
+
+    let pool_address = get_canonical_pool_pda(mint)
+        .ok_or_else(|| anyhow!("Failed to derive PumpSwap pool for {}", mint))?;
+    let pool = Pool::fetch_cached(rpc, &pool_address).await.map_err(|e| {
+        anyhow!("Bonding curve of {} is complete but PumpSwap pool {} is unavailable: {}", mint, pool_address, e)
+    })?;
+    let (pool_base_token_reserves, pool_quote_token_reserves) = pool.get_token_balances(rpc).await?;
+    let params = PumpSwapParams {
+        pool: Some(pool_address),
+        base_mint: Some(pool.base_mint),
+        quote_mint: Some(pool.quote_mint),
+        pool_base_token_reserves: Some(pool_base_token_reserves),
+        pool_quote_token_reserves: Some(pool_quote_token_reserves),
+        auto_handle_wsol: true,
+    };
+    Ok(Some((params, pool.coin_creator)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_incomplete_curve_is_not_routed() {
+        let rpc = SolanaRpcClient::new("http://127.0.0.1:1".to_string());
+        let mint = Pubkey::new_unique();
+        let bonding_curve = BondingCurveAccount::from_dev_trade(&mint, 0, 0, Pubkey::new_unique());
+        let params = migrated_pumpswap_params(&rpc, &mint, Some(&bonding_curve)).await.unwrap();
+        assert!(params.is_none());
+    }
+}
@@ -1 +1,2 @@
-pub mod common;
\ No newline at end of file
+pub mod common;
+pub mod migration;
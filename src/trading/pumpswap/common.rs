@@ -129,23 +129,31 @@ pub(crate) fn coin_creator_vault_authority(coin_creator: Pubkey) -> Pubkey {
     pump_pool_authority
 }
 
-pub(crate) fn coin_creator_vault_ata(coin_creator: Pubkey, quote_mint: Pubkey) -> Pubkey {
+pub(crate) fn coin_creator_vault_ata(
+    coin_creator: Pubkey,
+    quote_mint: Pubkey,
+    quote_token_program: Pubkey,
+) -> Pubkey {
     let creator_vault_authority = coin_creator_vault_authority(coin_creator);
     let associated_token_creator_vault_authority =
         spl_associated_token_account::get_associated_token_address_with_program_id(
             &creator_vault_authority,
             &quote_mint,
-            &crate::constants::pumpswap::accounts::TOKEN_PROGRAM,
+            &quote_token_program,
         );
     associated_token_creator_vault_authority
 }
 
-pub(crate) fn fee_recipient_ata(fee_recipient: Pubkey, quote_mint: Pubkey) -> Pubkey {
+pub(crate) fn fee_recipient_ata(
+    fee_recipient: Pubkey,
+    quote_mint: Pubkey,
+    quote_token_program: Pubkey,
+) -> Pubkey {
     let associated_token_fee_recipient =
         spl_associated_token_account::get_associated_token_address_with_program_id(
             &fee_recipient,
             &quote_mint,
-            &crate::constants::pumpswap::accounts::TOKEN_PROGRAM,
+            &quote_token_program,
         );
     associated_token_fee_recipient
 }
@@ -166,3 +174,19 @@ pub fn get_global_volume_accumulator_pda() -> Option<Pubkey> {
     let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
     pda.map(|pubkey| pubkey.0)
 }
+
+/// PumpFun bonding curve 完成后迁移创建的 WSOL 池子地址（创建者为 PumpFun pool authority）
+pub fn get_canonical_pool_pda(mint: &Pubkey) -> Option<Pubkey> {
+    let pool_authority = crate::trading::pumpfun::common::get_pool_authority_pda(mint)?;
+    let index = crate::constants::pumpswap::seeds::CANONICAL_POOL_INDEX.to_le_bytes();
+    let seeds: &[&[u8]; 5] = &[
+        crate::constants::pumpswap::seeds::POOL_SEED,
+        &index,
+        pool_authority.as_ref(),
+        mint.as_ref(),
+        crate::constants::pumpswap::accounts::WSOL_TOKEN_ACCOUNT.as_ref(),
+    ];
+    let program_id: &Pubkey = &crate::constants::pumpswap::accounts::AMM_PROGRAM;
+    let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
+    pda.map(|pubkey| pubkey.0)
+}
@@ -1,4 +1,6 @@
-use crate::{common::SolanaRpcClient, constants::pumpswap::accounts};
+use std::sync::Arc;
+
+use crate::{common::{AccountCache, SolanaRpcClient}, constants::pumpswap::accounts};
 use anyhow::anyhow;
 use solana_account_decoder::UiAccountEncoding;
 use solana_sdk::pubkey::Pubkey;
@@ -100,6 +102,22 @@ impl Pool {
         Self::from_bytes(&account.data)
     }
 
+    /// 通过 AccountCache 获取池子数据，接入订阅流后池子变化时自动失效
+    pub async fn fetch_cached(
+        rpc: &SolanaRpcClient,
+        pool_address: &Pubkey,
+    ) -> Result<Arc<Self>, anyhow::Error> {
+        let cached = AccountCache::get_instance()
+            .get_or_fetch(rpc, pool_address, |account| {
+                if account.owner != accounts::AMM_PROGRAM {
+                    return Err(anyhow!("Account is not owned by PumpSwap program"));
+                }
+                Self::from_bytes(&account.data)
+            })
+            .await?;
+        Ok(cached.data)
+    }
+
     pub async fn find_by_base_mint(
         rpc: &SolanaRpcClient,
         base_mint: &Pubkey,
@@ -0,0 +1,70 @@
+use crate::{
+    common::SolanaRpcClient,
+    trading::raydium_amm_v4::pool::Pool,
+};
+use anyhow::anyhow;
+
+/// 获取池子可交易的储备 (coin_reserve, pc_reserve)
+///
+/// 金库余额中包含尚未提取的协议收益，需要扣除
+pub async fn get_pool_reserves(
+    rpc: &SolanaRpcClient,
+    pool: &Pool,
+) -> Result<(u64, u64), anyhow::Error> {
+    let coin_balance = rpc.get_token_account_balance(&pool.coin_vault).await?;
+    let pc_balance = rpc.get_token_account_balance(&pool.pc_vault).await?;
+
+    let coin_amount = coin_balance
+        .amount
+        .parse::<u64>()
+        .map_err(|e| anyhow!("解析 coin 余额失败: {}", e))?;
+    let pc_amount = pc_balance
+        .amount
+        .parse::<u64>()
+        .map_err(|e| anyhow!("解析 pc 余额失败: {}", e))?;
+
+    Ok((
+        coin_amount.saturating_sub(pool.state_data.need_take_pnl_coin),
+        pc_amount.saturating_sub(pool.state_data.need_take_pnl_pc),
+    ))
+}
+
+/// 按恒定乘积公式计算输出数量，输入数量先扣除池子的 swap 手续费
+pub fn get_amount_out(
+    amount_in: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    swap_fee_numerator: u64,
+    swap_fee_denominator: u64,
+) -> Result<u64, anyhow::Error> {
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(anyhow!("池子储备金为零，无法进行交换"));
+    }
+    if swap_fee_denominator == 0 {
+        return Err(anyhow!("手续费分母为零，计算错误"));
+    }
+
+    // 使用 u128 防止溢出
+    let amount_in = amount_in as u128;
+    let fee = (amount_in * swap_fee_numerator as u128).div_ceil(swap_fee_denominator as u128);
+    let amount_in_after_fee = amount_in - fee.min(amount_in);
+
+    // 恒定乘积公式: amount_out = (amount_in * reserve_out) / (reserve_in + amount_in)
+    let amount_out = amount_in_after_fee * reserve_out as u128
+        / (reserve_in as u128 + amount_in_after_fee);
+    Ok(amount_out as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_amount_out() {
+        // 0.25% 手续费：1000 输入扣除 3 后为 997
+        let amount_out = get_amount_out(1_000, 1_000_000, 2_000_000, 25, 10_000).unwrap();
+        assert_eq!(amount_out, 997 * 2_000_000 / 1_000_997);
+        assert_eq!(get_amount_out(1_000, 1_000_000, 2_000_000, 0, 10_000).unwrap(), 1_998);
+        assert!(get_amount_out(1_000, 0, 2_000_000, 25, 10_000).is_err());
+    }
+}
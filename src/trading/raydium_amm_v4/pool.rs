@@ -0,0 +1,188 @@
+use std::sync::Arc;
+
+use crate::{
+    common::{AccountCache, SolanaRpcClient},
+    constants::raydium_amm_v4::{accounts, AMM_INFO_SIZE, COIN_MINT_OFFSET, PC_MINT_OFFSET},
+};
+use anyhow::anyhow;
+use borsh::BorshDeserialize;
+use solana_account_decoder::UiAccountEncoding;
+use solana_sdk::pubkey::Pubkey;
+
+/// 池子手续费配置，比例为 numerator / denominator
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct Fees {
+    pub min_separate_numerator: u64,
+    pub min_separate_denominator: u64,
+    pub trade_fee_numerator: u64,
+    pub trade_fee_denominator: u64,
+    pub pnl_numerator: u64,
+    pub pnl_denominator: u64,
+    pub swap_fee_numerator: u64,
+    pub swap_fee_denominator: u64,
+}
+
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct StateData {
+    pub need_take_pnl_coin: u64,
+    pub need_take_pnl_pc: u64,
+    pub total_pnl_pc: u64,
+    pub total_pnl_coin: u64,
+    pub pool_open_time: u64,
+    pub padding: [u64; 2],
+    pub orderbook_to_init_time: u64,
+    pub swap_coin_in_amount: u128,
+    pub swap_pc_out_amount: u128,
+    pub swap_acc_pc_fee: u64,
+    pub swap_pc_in_amount: u128,
+    pub swap_coin_out_amount: u128,
+    pub swap_acc_coin_fee: u64,
+}
+
+/// Raydium AMM v4 池子账户（AmmInfo），coin 为基础代币，pc 为报价代币
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct Pool {
+    pub status: u64,
+    pub nonce: u64,
+    pub order_num: u64,
+    pub depth: u64,
+    pub coin_decimals: u64,
+    pub pc_decimals: u64,
+    pub state: u64,
+    pub reset_flag: u64,
+    pub min_size: u64,
+    pub vol_max_cut_ratio: u64,
+    pub amount_wave: u64,
+    pub coin_lot_size: u64,
+    pub pc_lot_size: u64,
+    pub min_price_multiplier: u64,
+    pub max_price_multiplier: u64,
+    pub sys_decimal_value: u64,
+    pub fees: Fees,
+    pub state_data: StateData,
+    pub coin_vault: Pubkey,
+    pub pc_vault: Pubkey,
+    pub coin_vault_mint: Pubkey,
+    pub pc_vault_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub open_orders: Pubkey,
+    pub market: Pubkey,
+    pub market_program: Pubkey,
+    pub target_orders: Pubkey,
+    pub padding1: [u64; 8],
+    pub amm_owner: Pubkey,
+    pub lp_amount: u64,
+    pub client_order_id: u64,
+    pub recent_epoch: u64,
+    pub padding2: u64,
+}
+
+impl Pool {
+    pub fn from_bytes(data: &[u8]) -> Result<Self, anyhow::Error> {
+        let pool = Pool::try_from_slice(data)?;
+        Ok(pool)
+    }
+
+    pub async fn fetch(
+        rpc: &SolanaRpcClient,
+        pool_address: &Pubkey,
+    ) -> Result<Self, anyhow::Error> {
+        let account = rpc.get_account(pool_address).await?;
+
+        if account.owner != accounts::RAYDIUM_AMM_V4 {
+            return Err(anyhow!("Account is not owned by Raydium AMM v4 program"));
+        }
+
+        Self::from_bytes(&account.data)
+    }
+
+    /// 通过 AccountCache 获取池子数据，接入订阅流后池子变化时自动失效
+    pub async fn fetch_cached(
+        rpc: &SolanaRpcClient,
+        pool_address: &Pubkey,
+    ) -> Result<Arc<Self>, anyhow::Error> {
+        let cached = AccountCache::get_instance()
+            .get_or_fetch(rpc, pool_address, |account| {
+                if account.owner != accounts::RAYDIUM_AMM_V4 {
+                    return Err(anyhow!("Account is not owned by Raydium AMM v4 program"));
+                }
+                Self::from_bytes(&account.data)
+            })
+            .await?;
+        Ok(cached.data)
+    }
+
+    /// 查找 `mint` 与 WSOL 的池子，存在多个时返回 LP 数量最多的池子
+    pub async fn find_by_mint(
+        rpc: &SolanaRpcClient,
+        mint: &Pubkey,
+    ) -> Result<(Pubkey, Self), anyhow::Error> {
+        let mut pools = Vec::new();
+        for (coin_mint, pc_mint) in
+            [(mint, &accounts::WSOL_TOKEN_ACCOUNT), (&accounts::WSOL_TOKEN_ACCOUNT, mint)]
+        {
+            pools.extend(Self::find_by_mints(rpc, coin_mint, pc_mint).await?);
+        }
+        pools.sort_by_key(|(_, pool)| std::cmp::Reverse(pool.lp_amount));
+        pools.into_iter().next().ok_or_else(|| anyhow!("No pool found for mint {}", mint))
+    }
+
+    async fn find_by_mints(
+        rpc: &SolanaRpcClient,
+        coin_mint: &Pubkey,
+        pc_mint: &Pubkey,
+    ) -> Result<Vec<(Pubkey, Self)>, anyhow::Error> {
+        // 使用getProgramAccounts查找给定mint的池子
+        let filters = vec![
+            solana_rpc_client_api::filter::RpcFilterType::DataSize(AMM_INFO_SIZE),
+            solana_rpc_client_api::filter::RpcFilterType::Memcmp(
+                solana_client::rpc_filter::Memcmp::new_base58_encoded(
+                    COIN_MINT_OFFSET,
+                    &coin_mint.to_bytes(),
+                ),
+            ),
+            solana_rpc_client_api::filter::RpcFilterType::Memcmp(
+                solana_client::rpc_filter::Memcmp::new_base58_encoded(
+                    PC_MINT_OFFSET,
+                    &pc_mint.to_bytes(),
+                ),
+            ),
+        ];
+        let config = solana_rpc_client_api::config::RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: solana_rpc_client_api::config::RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                data_slice: None,
+                commitment: None,
+                min_context_slot: None,
+            },
+            with_context: None,
+            sort_results: None,
+        };
+        let accounts = rpc
+            .get_program_accounts_with_config(&accounts::RAYDIUM_AMM_V4, config)
+            .await?;
+        Ok(accounts
+            .into_iter()
+            .filter_map(|(addr, acc)| Self::from_bytes(&acc.data).map(|pool| (addr, pool)).ok())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_bytes_layout() {
+        let mut data = vec![0u8; AMM_INFO_SIZE as usize];
+        let mint = Pubkey::new_unique();
+        data[COIN_MINT_OFFSET..COIN_MINT_OFFSET + 32].copy_from_slice(mint.as_ref());
+        data[PC_MINT_OFFSET..PC_MINT_OFFSET + 32]
+            .copy_from_slice(accounts::WSOL_TOKEN_ACCOUNT.as_ref());
+        let pool = Pool::from_bytes(&data).unwrap();
+        assert_eq!(pool.coin_vault_mint, mint);
+        assert_eq!(pool.pc_vault_mint, accounts::WSOL_TOKEN_ACCOUNT);
+        assert!(Pool::from_bytes(&data[..AMM_INFO_SIZE as usize - 1]).is_err());
+    }
+}
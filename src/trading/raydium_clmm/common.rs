@@ -0,0 +1,84 @@
+use crate::{
+    constants::raydium_clmm::{
+        accounts, seeds, MAX_TICK_ARRAYS, TICK_ARRAY_BITMAP_SIZE, TICK_ARRAY_SIZE,
+    },
+    trading::raydium_clmm::pool::Pool,
+};
+use anyhow::anyhow;
+use solana_sdk::pubkey::Pubkey;
+
+pub fn get_tick_array_pda(pool_state: &Pubkey, start_tick_index: i32) -> Option<Pubkey> {
+    let seeds: &[&[u8]; 3] = &[
+        seeds::TICK_ARRAY_SEED,
+        pool_state.as_ref(),
+        &start_tick_index.to_be_bytes(),
+    ];
+    let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, &accounts::RAYDIUM_CLMM);
+    pda.map(|pubkey| pubkey.0)
+}
+
+pub fn get_tick_array_bitmap_extension_pda(pool_state: &Pubkey) -> Option<Pubkey> {
+    let seeds: &[&[u8]; 2] = &[seeds::TICK_ARRAY_BITMAP_EXTENSION_SEED, pool_state.as_ref()];
+    let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, &accounts::RAYDIUM_CLMM);
+    pda.map(|pubkey| pubkey.0)
+}
+
+/// 包含 `tick` 的 tick array 的起始 tick
+pub fn get_tick_array_start_index(tick: i32, tick_spacing: u16) -> i32 {
+    let ticks_in_array = TICK_ARRAY_SIZE * tick_spacing as i32;
+    tick.div_euclid(ticks_in_array) * ticks_in_array
+}
+
+/// 根据池子内置位图，按交易方向找出 swap 需要的已初始化 tick array 起始 tick
+///
+/// `zero_for_one` 为 true 时价格下降，从当前 tick array 向更小的 tick 查找。
+/// 超出内置位图范围的 tick array 记录在位图扩展账户中，此时需要通过参数指定 tick array。
+pub fn get_tick_array_start_indexes(pool: &Pool, zero_for_one: bool) -> Vec<i32> {
+    let ticks_in_array = TICK_ARRAY_SIZE * pool.tick_spacing as i32;
+    let current_start = get_tick_array_start_index(pool.tick_current, pool.tick_spacing);
+    let half = TICK_ARRAY_BITMAP_SIZE / 2;
+    let mut position = current_start / ticks_in_array + half;
+    let mut start_indexes = Vec::with_capacity(MAX_TICK_ARRAYS);
+    while (0..TICK_ARRAY_BITMAP_SIZE).contains(&position) && start_indexes.len() < MAX_TICK_ARRAYS {
+        let word = pool.tick_array_bitmap[(position / 64) as usize];
+        if word & (1u64 << (position % 64)) != 0 {
+            start_indexes.push((position - half) * ticks_in_array);
+        }
+        position += if zero_for_one { -1 } else { 1 };
+    }
+    start_indexes
+}
+
+/// 解析 swap 需要的 tick array 账户地址
+pub fn get_tick_arrays(
+    pool_state: &Pubkey,
+    pool: &Pool,
+    zero_for_one: bool,
+) -> Result<Vec<Pubkey>, anyhow::Error> {
+    let tick_arrays: Vec<Pubkey> = get_tick_array_start_indexes(pool, zero_for_one)
+        .into_iter()
+        .filter_map(|start_index| get_tick_array_pda(pool_state, start_index))
+        .collect();
+    if tick_arrays.is_empty() {
+        return Err(anyhow!(
+            "No initialized tick array found for pool {}, pass tick_arrays explicitly",
+            pool_state
+        ));
+    }
+    Ok(tick_arrays)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tick_array_start_index() {
+        assert_eq!(get_tick_array_start_index(0, 10), 0);
+        assert_eq!(get_tick_array_start_index(599, 10), 0);
+        assert_eq!(get_tick_array_start_index(600, 10), 600);
+        assert_eq!(get_tick_array_start_index(-1, 10), -600);
+        assert_eq!(get_tick_array_start_index(-600, 10), -600);
+        assert_eq!(get_tick_array_start_index(-601, 10), -1200);
+    }
+}
@@ -0,0 +1,168 @@
+use std::sync::Arc;
+
+use crate::{
+    common::{AccountCache, SolanaRpcClient},
+    constants::raydium_clmm::{accounts, TOKEN_MINT_0_OFFSET, TOKEN_MINT_1_OFFSET},
+};
+use anyhow::anyhow;
+use borsh::BorshDeserialize;
+use solana_account_decoder::UiAccountEncoding;
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct RewardInfo {
+    pub reward_state: u8,
+    pub open_time: u64,
+    pub end_time: u64,
+    pub last_update_time: u64,
+    pub emissions_per_second_x64: u128,
+    pub reward_total_emissioned: u64,
+    pub reward_claimed: u64,
+    pub token_mint: Pubkey,
+    pub token_vault: Pubkey,
+    pub authority: Pubkey,
+    pub reward_growth_global_x64: u128,
+}
+
+/// Raydium CLMM 池子账户（PoolState），只解析到 tick array 位图为止
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct Pool {
+    pub bump: u8,
+    pub amm_config: Pubkey,
+    pub owner: Pubkey,
+    pub token_mint_0: Pubkey,
+    pub token_mint_1: Pubkey,
+    pub token_vault_0: Pubkey,
+    pub token_vault_1: Pubkey,
+    pub observation_key: Pubkey,
+    pub mint_decimals_0: u8,
+    pub mint_decimals_1: u8,
+    pub tick_spacing: u16,
+    pub liquidity: u128,
+    pub sqrt_price_x64: u128,
+    pub tick_current: i32,
+    pub padding3: u16,
+    pub padding4: u16,
+    pub fee_growth_global_0_x64: u128,
+    pub fee_growth_global_1_x64: u128,
+    pub protocol_fees_token_0: u64,
+    pub protocol_fees_token_1: u64,
+    pub swap_in_amount_token_0: u128,
+    pub swap_out_amount_token_1: u128,
+    pub swap_in_amount_token_1: u128,
+    pub swap_out_amount_token_0: u128,
+    pub status: u8,
+    pub padding: [u8; 7],
+    pub reward_infos: [RewardInfo; 3],
+    /// 以 tick array 为单位的初始化位图，覆盖 [-512, 512) 个 tick array
+    pub tick_array_bitmap: [u64; 16],
+}
+
+/// 池子使用的手续费配置（AmmConfig），费率单位为百万分之一
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct AmmConfig {
+    pub bump: u8,
+    pub index: u16,
+    pub owner: Pubkey,
+    pub protocol_fee_rate: u32,
+    pub trade_fee_rate: u32,
+    pub tick_spacing: u16,
+    pub fund_fee_rate: u32,
+}
+
+impl Pool {
+    pub fn from_bytes(data: &[u8]) -> Result<Self, anyhow::Error> {
+        let pool = Pool::deserialize(&mut data.get(8..).unwrap_or_default())?;
+        Ok(pool)
+    }
+
+    pub async fn fetch(
+        rpc: &SolanaRpcClient,
+        pool_address: &Pubkey,
+    ) -> Result<Self, anyhow::Error> {
+        let account = rpc.get_account(pool_address).await?;
+        decode_owned(&account, Self::from_bytes)
+    }
+
+    /// 通过 AccountCache 获取池子数据，接入订阅流后池子变化时自动失效
+    pub async fn fetch_cached(
+        rpc: &SolanaRpcClient,
+        pool_address: &Pubkey,
+    ) -> Result<Arc<Self>, anyhow::Error> {
+        let cached = AccountCache::get_instance()
+            .get_or_fetch(rpc, pool_address, |account| decode_owned(account, Self::from_bytes))
+            .await?;
+        Ok(cached.data)
+    }
+
+    /// 查找 `mint` 与 WSOL 的池子，存在多个费率档位时返回流动性最大的池子
+    pub async fn find_by_mint(
+        rpc: &SolanaRpcClient,
+        mint: &Pubkey,
+    ) -> Result<(Pubkey, Self), anyhow::Error> {
+        // 池子中 token_mint_0 总是较小的地址
+        let wsol = accounts::WSOL_TOKEN_ACCOUNT;
+        let (mint0, mint1) = if *mint < wsol { (mint, &wsol) } else { (&wsol, mint) };
+        let filters = vec![
+            solana_rpc_client_api::filter::RpcFilterType::Memcmp(
+                solana_client::rpc_filter::Memcmp::new_base58_encoded(
+                    TOKEN_MINT_0_OFFSET,
+                    &mint0.to_bytes(),
+                ),
+            ),
+            solana_rpc_client_api::filter::RpcFilterType::Memcmp(
+                solana_client::rpc_filter::Memcmp::new_base58_encoded(
+                    TOKEN_MINT_1_OFFSET,
+                    &mint1.to_bytes(),
+                ),
+            ),
+        ];
+        let config = solana_rpc_client_api::config::RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: solana_rpc_client_api::config::RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                data_slice: None,
+                commitment: None,
+                min_context_slot: None,
+            },
+            with_context: None,
+            sort_results: None,
+        };
+        let accounts = rpc
+            .get_program_accounts_with_config(&accounts::RAYDIUM_CLMM, config)
+            .await?;
+        accounts
+            .into_iter()
+            .filter_map(|(addr, acc)| Self::from_bytes(&acc.data).map(|pool| (addr, pool)).ok())
+            .max_by_key(|(_, pool)| pool.liquidity)
+            .ok_or_else(|| anyhow!("No pool found for mint {}", mint))
+    }
+}
+
+impl AmmConfig {
+    pub fn from_bytes(data: &[u8]) -> Result<Self, anyhow::Error> {
+        let config = AmmConfig::deserialize(&mut data.get(8..).unwrap_or_default())?;
+        Ok(config)
+    }
+
+    /// 通过 AccountCache 获取手续费配置
+    pub async fn fetch_cached(
+        rpc: &SolanaRpcClient,
+        amm_config: &Pubkey,
+    ) -> Result<Arc<Self>, anyhow::Error> {
+        let cached = AccountCache::get_instance()
+            .get_or_fetch(rpc, amm_config, |account| decode_owned(account, Self::from_bytes))
+            .await?;
+        Ok(cached.data)
+    }
+}
+
+fn decode_owned<T>(
+    account: &Account,
+    decode: impl FnOnce(&[u8]) -> Result<T, anyhow::Error>,
+) -> Result<T, anyhow::Error> {
+    if account.owner != accounts::RAYDIUM_CLMM {
+        return Err(anyhow!("Account is not owned by Raydium CLMM program"));
+    }
+    decode(&account.data)
+}
@@ -1,7 +1,7 @@
 use crate::{
     common::SolanaRpcClient,
     constants::{self, raydium_cpmm::accounts::WSOL_TOKEN_ACCOUNT},
-    trading::raydium_cpmm::pool::Pool,
+    trading::{common::token_program::get_mint_token_info, raydium_cpmm::pool::Pool},
 };
 use anyhow::anyhow;
 use solana_sdk::pubkey::Pubkey;
@@ -39,12 +39,61 @@ pub fn get_observation_state_pda(pool_state: &Pubkey) -> Option<Pubkey> {
     pda.map(|pubkey| pubkey.0)
 }
 
+pub fn get_lp_mint_pda(pool_state: &Pubkey) -> Option<Pubkey> {
+    let seeds: &[&[u8]; 2] = &[
+        constants::raydium_cpmm::seeds::POOL_LP_MINT_SEED,
+        pool_state.as_ref(),
+    ];
+    let program_id: &Pubkey = &constants::raydium_cpmm::accounts::RAYDIUM_CPMM;
+    let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
+    pda.map(|pubkey| pubkey.0)
+}
+
+/// 新建池子涉及的全部地址，token0 / token1 按 mint 地址字节序排列
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CpmmPoolAddresses {
+    pub amm_config: Pubkey,
+    pub pool_state: Pubkey,
+    pub lp_mint: Pubkey,
+    pub token_0_mint: Pubkey,
+    pub token_1_mint: Pubkey,
+    pub token_0_vault: Pubkey,
+    pub token_1_vault: Pubkey,
+    pub observation_state: Pubkey,
+}
+
+impl CpmmPoolAddresses {
+    /// 推导 `mint_a` / `mint_b` 在 `amm_config` 下的池子地址，两个 mint 的顺序不影响结果
+    pub fn derive(amm_config: &Pubkey, mint_a: &Pubkey, mint_b: &Pubkey) -> Result<Self, anyhow::Error> {
+        if mint_a == mint_b {
+            return Err(anyhow!("Pool mints must differ"));
+        }
+        let (token_0_mint, token_1_mint) =
+            if mint_a.to_bytes() < mint_b.to_bytes() { (*mint_a, *mint_b) } else { (*mint_b, *mint_a) };
+        let pool_state = get_pool_pda(amm_config, &token_0_mint, &token_1_mint)
+            .ok_or_else(|| anyhow!("Failed to derive pool state"))?;
+        Ok(Self {
+            amm_config: *amm_config,
+            pool_state,
+            lp_mint: get_lp_mint_pda(&pool_state).ok_or_else(|| anyhow!("Failed to derive LP mint"))?,
+            token_0_mint,
+            token_1_mint,
+            token_0_vault: get_vault_pda(&pool_state, &token_0_mint)
+                .ok_or_else(|| anyhow!("Failed to derive token 0 vault"))?,
+            token_1_vault: get_vault_pda(&pool_state, &token_1_mint)
+                .ok_or_else(|| anyhow!("Failed to derive token 1 vault"))?,
+            observation_state: get_observation_state_pda(&pool_state)
+                .ok_or_else(|| anyhow!("Failed to derive observation state"))?,
+        })
+    }
+}
+
 pub async fn get_buy_token_amount(
     rpc: &SolanaRpcClient,
     pool_state: &Pubkey,
     sol_amount: u64,
 ) -> Result<u64, anyhow::Error> {
-    let pool = Pool::fetch(rpc, pool_state).await?;
+    let pool = Pool::fetch_cached(rpc, pool_state).await?;
     let is_token0_input = if pool.token0_mint == WSOL_TOKEN_ACCOUNT {
         true
     } else {
@@ -85,7 +134,10 @@ pub async fn get_buy_token_amount(
         return Err(anyhow!("输出数量超过池子储备金"));
     }
 
-    Ok(amount_out as u64)
+    // Token-2022 代币扣除转账手续费后才是实际到账数量
+    let token_mint = if is_token0_input { pool.token1_mint } else { pool.token0_mint };
+    let mint_info = get_mint_token_info(rpc, &token_mint).await?;
+    Ok(mint_info.amount_after_transfer_fee(amount_out as u64))
 }
 
 pub async fn get_sell_sol_amount(
@@ -93,7 +145,7 @@ pub async fn get_sell_sol_amount(
     pool_state: &Pubkey,
     token_amount: u64,
 ) -> Result<u64, anyhow::Error> {
-    let pool = Pool::fetch(rpc, pool_state).await?;
+    let pool = Pool::fetch_cached(rpc, pool_state).await?;
     let is_token0_sol = if pool.token0_mint == WSOL_TOKEN_ACCOUNT {
         true
     } else {
@@ -112,6 +164,11 @@ pub async fn get_sell_sol_amount(
         return Err(anyhow!("池子储备金为零，无法进行交换"));
     }
 
+    // 池子实际收到的是扣除 Token-2022 转账手续费后的数量
+    let token_mint = if is_token0_sol { pool.token1_mint } else { pool.token0_mint };
+    let mint_info = get_mint_token_info(rpc, &token_mint).await?;
+    let token_amount = mint_info.amount_after_transfer_fee(token_amount);
+
     // 使用 u128 防止溢出
     let amount_in_128 = token_amount as u128;
     let reserve_in_128 = reserve_in as u128;
@@ -217,4 +274,17 @@ mod tests {
         let result = get_observation_state_pda(&pool_state);
         assert_eq!(result, Some(observation_state_pda));
     }
+
+    #[test]
+    fn test_derive_pool_addresses() {
+        let amm_config = constants::raydium_cpmm::accounts::AMM_CONFIG;
+        let wsol = pubkey!("So11111111111111111111111111111111111111112");
+        let mint = pubkey!("BnwbwoqPm5ZNx7YTJ8g9jR2qCpYeHBC7xxpU8zEtbonk");
+        let addresses = CpmmPoolAddresses::derive(&amm_config, &mint, &wsol).unwrap();
+        // mint 顺序不影响结果，WSOL 的地址字节序较小，为 token0
+        assert_eq!(addresses, CpmmPoolAddresses::derive(&amm_config, &wsol, &mint).unwrap());
+        assert_eq!(addresses.token_0_mint, wsol);
+        assert_eq!(addresses.pool_state, pubkey!("E9rRRpcdsKAseeLFbwC1Ewxd3aYG27meqwTTrMfCTbSG"));
+        assert!(CpmmPoolAddresses::derive(&amm_config, &mint, &mint).is_err());
+    }
 }
@@ -1,4 +1,6 @@
-use crate::{common::SolanaRpcClient, constants::raydium_cpmm::accounts};
+use std::sync::Arc;
+
+use crate::{common::{AccountCache, SolanaRpcClient}, constants::raydium_cpmm::accounts};
 use anyhow::anyhow;
 use borsh::BorshDeserialize;
 use solana_sdk::pubkey::Pubkey;
@@ -48,4 +50,20 @@ impl Pool {
 
         Self::from_bytes(&account.data)
     }
+
+    /// 通过 AccountCache 获取池子数据，接入订阅流后池子变化时自动失效
+    pub async fn fetch_cached(
+        rpc: &SolanaRpcClient,
+        pool_address: &Pubkey,
+    ) -> Result<Arc<Self>, anyhow::Error> {
+        let cached = AccountCache::get_instance()
+            .get_or_fetch(rpc, pool_address, |account| {
+                if account.owner != accounts::RAYDIUM_CPMM {
+                    return Err(anyhow!("Account is not owned by Raydium Cpmm program"));
+                }
+                Self::from_bytes(&account.data)
+            })
+            .await?;
+        Ok(cached.data)
+    }
 }
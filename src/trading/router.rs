@@ -0,0 +1,510 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    common::{bonding_curve::BondingCurveAccount, SolanaRpcClient},
+    constants::{pumpfun, pumpswap},
+    trading::{
+        arbitrage::{quote_raydium_cpmm, VenueQuote, PUMPSWAP_FEE_BASIS_POINTS},
+        common::{
+            token_program::{get_mint_token_info, MintTokenInfo},
+            MarketSnapshot,
+        },
+        core::{
+            params::{PhoenixParams, PumpFunParams, PumpSwapParams, RaydiumCpmmParams},
+            traits::ProtocolParams,
+        },
+        factory::DexType,
+        phoenix::{
+            common::{get_buy_base_lots, get_sell_quote_lots},
+            market::Market as PhoenixMarket,
+        },
+        pumpfun::common::{fetch_bonding_curve, get_spot_price},
+        pumpswap::pool::Pool as PumpSwapPool,
+    },
+};
+
+/// 路由配置
+#[derive(Debug, Clone)]
+pub struct RouterConfig {
+    /// 参与报价的协议，目前支持 PumpFun、PumpSwap、Raydium CPMM 和 Phoenix
+    pub venues: Vec<DexType>,
+    /// 单笔交易最多拆分到的协议数，1 表示不拆单
+    pub max_splits: usize,
+    /// 拆单时将数量均分为多少份，逐份分配给边际输出最高的协议
+    pub split_steps: u64,
+}
+
+impl Default for RouterConfig {
+    fn default() -> Self {
+        Self {
+            venues: vec![DexType::PumpFun, DexType::PumpSwap, DexType::RaydiumCpmm, DexType::Phoenix],
+            max_splits: 1,
+            split_steps: 10,
+        }
+    }
+}
+
+/// 报价所需的流动性状态，报价在本地计算，不再发起 RPC 请求
+#[derive(Debug, Clone)]
+pub enum Liquidity {
+    /// PumpFun bonding curve
+    BondingCurve(Arc<BondingCurveAccount>),
+    /// 恒定乘积池子，`sol_is_base` 表示 WSOL 为池子的 base 代币，
+    /// `coin_creator` 为 PumpSwap 池子的 coin creator，其他池子为 None
+    ConstantProduct { quote: VenueQuote, sol_is_base: bool, coin_creator: Option<Pubkey> },
+    /// Phoenix 订单簿
+    OrderBook { market: Pubkey, state: Arc<PhoenixMarket> },
+}
+
+/// 单个协议上的可交易流动性
+#[derive(Debug, Clone)]
+pub struct Venue {
+    pub dex_type: DexType,
+    pub mint: Pubkey,
+    pub liquidity: Liquidity,
+    /// 代币程序及 Token-2022 转账手续费，报价按实际到账数量计算
+    pub mint_info: MintTokenInfo,
+}
+
+impl Venue {
+    /// 花费 `sol_amount` lamports 可获得的代币数量，已扣除手续费和转账手续费
+    pub fn buy_amount_out(&self, sol_amount: u64) -> u64 {
+        let amount_out = match &self.liquidity {
+            Liquidity::BondingCurve(curve) => {
+                let sol_after_fee = sol_amount as u128 * 10_000
+                    / (10_000 + pumpfun::global_constants::FEE_BASIS_POINTS as u128);
+                curve.get_buy_price(sol_after_fee as u64).unwrap_or(0)
+            }
+            Liquidity::ConstantProduct { quote, .. } => constant_product_out(
+                sol_amount,
+                quote.sol_reserve,
+                quote.token_reserve,
+                quote.fee_basis_points,
+            ),
+            Liquidity::OrderBook { state, .. } => {
+                get_buy_base_lots(state, sol_amount / state.quote_lot_size.max(1))
+                    .saturating_mul(state.base_lot_size)
+            }
+        };
+        self.mint_info.amount_after_transfer_fee(amount_out)
+    }
+
+    /// 卖出 `token_amount` 可获得的 SOL（lamports），已扣除手续费，
+    /// 池子按扣除转账手续费后实际收到的代币数量报价
+    pub fn sell_amount_out(&self, token_amount: u64) -> u64 {
+        let token_amount = self.mint_info.amount_after_transfer_fee(token_amount);
+        match &self.liquidity {
+            Liquidity::BondingCurve(curve) => curve
+                .get_sell_price(token_amount, pumpfun::global_constants::FEE_BASIS_POINTS)
+                .unwrap_or(0),
+            Liquidity::ConstantProduct { quote, .. } => constant_product_out(
+                token_amount,
+                quote.token_reserve,
+                quote.sol_reserve,
+                quote.fee_basis_points,
+            ),
+            Liquidity::OrderBook { state, .. } => {
+                get_sell_quote_lots(state, token_amount / state.base_lot_size.max(1))
+                    .saturating_mul(state.quote_lot_size)
+            }
+        }
+    }
+
+    /// 现价，lamports / 代币最小单位，不含手续费；订单簿为卖出一手的成交价
+    pub fn price(&self) -> f64 {
+        match &self.liquidity {
+            Liquidity::BondingCurve(curve) => {
+                get_spot_price(curve.virtual_sol_reserves, curve.virtual_token_reserves)
+            }
+            Liquidity::ConstantProduct { quote, .. } => quote.price(),
+            Liquidity::OrderBook { state, .. } => {
+                get_sell_quote_lots(state, 1) as f64 * state.quote_lot_size as f64
+                    / state.base_lot_size.max(1) as f64
+            }
+        }
+    }
+
+    /// 供 `FillModel` 模拟成交的市场快照，订单簿没有储备，按现价成交
+    pub fn market_snapshot(&self) -> MarketSnapshot {
+        let (sol_reserves, token_reserves) = match &self.liquidity {
+            Liquidity::BondingCurve(curve) => {
+                (curve.virtual_sol_reserves, curve.virtual_token_reserves)
+            }
+            Liquidity::ConstantProduct { quote, .. } => (quote.sol_reserve, quote.token_reserve),
+            Liquidity::OrderBook { .. } => (0, 0),
+        };
+        let spot_price =
+            matches!(self.liquidity, Liquidity::OrderBook { .. }).then(|| self.price());
+        MarketSnapshot { sol_reserves, token_reserves, spot_price, next_trade_price: None }
+    }
+
+    /// PumpFun 卖出指令及 PumpSwap 指令需要的创作者地址
+    pub fn creator(&self) -> Option<Pubkey> {
+        match &self.liquidity {
+            Liquidity::BondingCurve(curve) => Some(curve.creator),
+            Liquidity::ConstantProduct { coin_creator, .. } => *coin_creator,
+            Liquidity::OrderBook { .. } => None,
+        }
+    }
+
+    /// 在该协议上执行时使用的交易参数，携带已获取的账户以省去重复查询
+    pub fn protocol_params(&self) -> Box<dyn ProtocolParams> {
+        match &self.liquidity {
+            Liquidity::BondingCurve(curve) => {
+                Box::new(PumpFunParams { bonding_curve: Some(curve.clone()) })
+            }
+            Liquidity::ConstantProduct { quote, sol_is_base, .. } => match self.dex_type {
+                DexType::PumpSwap => {
+                    let wsol = pumpswap::accounts::WSOL_TOKEN_ACCOUNT;
+                    let (base_mint, quote_mint, base_reserve, quote_reserve) = if *sol_is_base {
+                        (wsol, self.mint, quote.sol_reserve, quote.token_reserve)
+                    } else {
+                        (self.mint, wsol, quote.token_reserve, quote.sol_reserve)
+                    };
+                    Box::new(PumpSwapParams {
+                        pool: Some(quote.pool),
+                        base_mint: Some(base_mint),
+                        quote_mint: Some(quote_mint),
+                        pool_base_token_reserves: Some(base_reserve),
+                        pool_quote_token_reserves: Some(quote_reserve),
+                        auto_handle_wsol: true,
+                    })
+                }
+                _ => Box::new(RaydiumCpmmParams {
+                    pool_state: Some(quote.pool),
+                    ..RaydiumCpmmParams::default()
+                }),
+            },
+            Liquidity::OrderBook { market, .. } => {
+                Box::new(PhoenixParams { market: Some(*market), ..Default::default() })
+            }
+        }
+    }
+}
+
+/// 路由中的一段：在 `venue` 上交易 `amount_in`
+#[derive(Debug, Clone)]
+pub struct RouteLeg {
+    pub venue: Venue,
+    /// 买入时为 SOL（lamports），卖出时为代币数量
+    pub amount_in: u64,
+    /// 按报价计算的输出，未扣除滑点
+    pub expected_out: u64,
+}
+
+/// 路由结果，按输入数量从大到小排列
+#[derive(Debug, Clone)]
+pub struct Route {
+    pub mint: Pubkey,
+    pub is_buy: bool,
+    pub legs: Vec<RouteLeg>,
+    /// 代币的 Token-2022 转账手续费率（基点），`expected_out` 已扣除该手续费
+    pub transfer_fee_basis_points: u16,
+}
+
+impl Route {
+    pub fn expected_out(&self) -> u64 {
+        self.legs.iter().map(|leg| leg.expected_out).sum()
+    }
+}
+
+/// 跨协议路由：查询代币在各协议上的流动性，选择扣除手续费和价格冲击后输出最高的协议
+///
+/// 开启拆单时按份贪心分配，每份交给当前边际输出最高的协议，
+/// 各段分别提交交易，不保证原子性。
+#[derive(Debug, Clone, Default)]
+pub struct Router {
+    config: RouterConfig,
+}
+
+impl Router {
+    pub fn new(config: RouterConfig) -> Self {
+        Self { config }
+    }
+
+    /// 查询 `mint` 在已配置协议上的流动性，没有流动性或查询失败的协议会被跳过
+    pub async fn venues(&self, rpc: &SolanaRpcClient, mint: &Pubkey) -> Vec<Venue> {
+        let mint_info = match get_mint_token_info(rpc, mint).await {
+            Ok(mint_info) => mint_info,
+            Err(e) => {
+                println!("警告: 查询 {} 的代币信息失败: {}", mint, e);
+                return Vec::new();
+            }
+        };
+        let mut venues = Vec::new();
+        for dex_type in &self.config.venues {
+            match fetch_venue(rpc, mint, dex_type, &mint_info).await {
+                Ok(Some(venue)) => venues.push(venue),
+                Ok(None) => {}
+                Err(e) => println!("警告: 查询 {} 在 {} 的流动性失败: {}", mint, dex_type, e),
+            }
+        }
+        venues
+    }
+
+    /// 查询 `mint` 在单个协议上的流动性，该协议没有流动性时返回 None
+    pub async fn venue(
+        rpc: &SolanaRpcClient,
+        mint: &Pubkey,
+        dex_type: &DexType,
+    ) -> Result<Option<Venue>> {
+        let mint_info = get_mint_token_info(rpc, mint).await?;
+        fetch_venue(rpc, mint, dex_type, &mint_info).await
+    }
+
+    /// 查询流动性并计算买入路由
+    pub async fn route_buy(&self, rpc: &SolanaRpcClient, mint: &Pubkey, sol_amount: u64) -> Result<Route> {
+        let venues = self.venues(rpc, mint).await;
+        self.plan(mint, &venues, sol_amount, true)
+    }
+
+    /// 查询流动性并计算卖出路由
+    pub async fn route_sell(&self, rpc: &SolanaRpcClient, mint: &Pubkey, token_amount: u64) -> Result<Route> {
+        let venues = self.venues(rpc, mint).await;
+        self.plan(mint, &venues, token_amount, false)
+    }
+
+    /// 根据已获取的流动性分配数量
+    pub fn plan(&self, mint: &Pubkey, venues: &[Venue], amount_in: u64, is_buy: bool) -> Result<Route> {
+        let amount_out = |venue: &Venue, amount: u64| {
+            if is_buy {
+                venue.buy_amount_out(amount)
+            } else {
+                venue.sell_amount_out(amount)
+            }
+        };
+        let best = venues
+            .iter()
+            .enumerate()
+            .map(|(index, venue)| (index, amount_out(venue, amount_in)))
+            .max_by_key(|(_, out)| *out)
+            .filter(|(_, out)| *out > 0)
+            .ok_or_else(|| anyhow!("No venue with liquidity for mint {}", mint))?;
+
+        let mut allocation = vec![0u64; venues.len()];
+        if self.config.max_splits <= 1 || venues.len() == 1 {
+            allocation[best.0] = amount_in;
+        } else {
+            let steps = self.config.split_steps.clamp(1, amount_in.max(1));
+            let step = amount_in / steps;
+            for i in 0..steps {
+                // 最后一份包含除不尽的余数
+                let chunk = if i + 1 == steps { amount_in - step * (steps - 1) } else { step };
+                let used = allocation.iter().filter(|amount| **amount > 0).count();
+                let (index, _) = venues
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| allocation[*index] > 0 || used < self.config.max_splits)
+                    .map(|(index, venue)| {
+                        let before = amount_out(venue, allocation[index]);
+                        (index, amount_out(venue, allocation[index] + chunk).saturating_sub(before))
+                    })
+                    .max_by_key(|(_, marginal)| *marginal)
+                    .unwrap_or(best);
+                allocation[index] += chunk;
+            }
+            // 贪心分配不优于单一协议时不拆单
+            let split_out: u64 =
+                allocation.iter().enumerate().map(|(index, amount)| amount_out(&venues[index], *amount)).sum();
+            if split_out <= best.1 {
+                allocation = vec![0; venues.len()];
+                allocation[best.0] = amount_in;
+            }
+        }
+
+        let mut legs: Vec<RouteLeg> = allocation
+            .into_iter()
+            .enumerate()
+            .filter(|(_, amount)| *amount > 0)
+            .map(|(index, amount)| RouteLeg {
+                venue: venues[index].clone(),
+                amount_in: amount,
+                expected_out: amount_out(&venues[index], amount),
+            })
+            .collect();
+        legs.sort_by_key(|leg| std::cmp::Reverse(leg.amount_in));
+        let transfer_fee_basis_points = venues[best.0].mint_info.transfer_fee_basis_points();
+        Ok(Route { mint: *mint, is_buy, legs, transfer_fee_basis_points })
+    }
+}
+
+/// 恒定乘积池子的输出数量，手续费从输入中扣除
+fn constant_product_out(amount_in: u64, reserve_in: u64, reserve_out: u64, fee_basis_points: u64) -> u64 {
+    let amount_in = amount_in as u128 * (10_000 - fee_basis_points.min(10_000)) as u128 / 10_000;
+    let denominator = reserve_in as u128 + amount_in;
+    if denominator == 0 {
+        return 0;
+    }
+    (reserve_out as u128 * amount_in / denominator) as u64
+}
+
+async fn fetch_venue(
+    rpc: &SolanaRpcClient,
+    mint: &Pubkey,
+    dex_type: &DexType,
+    mint_info: &MintTokenInfo,
+) -> Result<Option<Venue>> {
+    let liquidity = match dex_type {
+        DexType::PumpFun => match fetch_bonding_curve(rpc, mint).await {
+            Ok(curve) if !curve.complete => Liquidity::BondingCurve(Arc::new(curve)),
+            _ => return Ok(None),
+        },
+        DexType::PumpSwap => {
+            let Ok((pool_address, pool)) = PumpSwapPool::find_by_mint(rpc, mint).await else {
+                return Ok(None);
+            };
+            let (base_amount, quote_amount) = pool.get_token_balances(rpc).await?;
+            let sol_is_base = pool.base_mint == pumpswap::accounts::WSOL_TOKEN_ACCOUNT;
+            if !sol_is_base && pool.quote_mint != pumpswap::accounts::WSOL_TOKEN_ACCOUNT {
+                return Ok(None);
+            }
+            let (sol_reserve, token_reserve) =
+                if sol_is_base { (base_amount, quote_amount) } else { (quote_amount, base_amount) };
+            let quote = VenueQuote {
+                dex_type: DexType::PumpSwap,
+                pool: pool_address,
+                sol_reserve,
+                token_reserve,
+                fee_basis_points: PUMPSWAP_FEE_BASIS_POINTS,
+                transfer_fee_basis_points: mint_info.transfer_fee_basis_points(),
+            };
+            // 没有 coin creator 的池子不收取创作者手续费
+            let coin_creator =
+                Some(pool.coin_creator).filter(|creator| *creator != Pubkey::default());
+            Liquidity::ConstantProduct { quote, sol_is_base, coin_creator }
+        }
+        DexType::RaydiumCpmm => match quote_raydium_cpmm(rpc, mint).await {
+            Ok(quote) => Liquidity::ConstantProduct { quote, sol_is_base: false, coin_creator: None },
+            Err(_) => return Ok(None),
+        },
+        DexType::Phoenix => match PhoenixMarket::find_by_mint(rpc, mint).await {
+            Ok((market, state)) => Liquidity::OrderBook { market, state: Arc::new(state) },
+            Err(_) => return Ok(None),
+        },
+        _ => return Err(anyhow!("Router does not support {}", dex_type)),
+    };
+    Ok(Some(Venue {
+        dex_type: dex_type.clone(),
+        mint: *mint,
+        liquidity,
+        mint_info: mint_info.clone(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(dex_type: DexType, sol_reserve: u64, token_reserve: u64) -> Venue {
+        Venue {
+            dex_type: dex_type.clone(),
+            mint: Pubkey::new_unique(),
+            liquidity: Liquidity::ConstantProduct {
+                quote: VenueQuote {
+                    dex_type,
+                    pool: Pubkey::new_unique(),
+                    sol_reserve,
+                    token_reserve,
+                    fee_basis_points: 30,
+                    transfer_fee_basis_points: 0,
+                },
+                sol_is_base: false,
+                coin_creator: None,
+            },
+            mint_info: MintTokenInfo::spl_token(),
+        }
+    }
+
+    #[test]
+    fn test_plan_picks_best_venue() {
+        let mint = Pubkey::new_unique();
+        // Raydium CPMM 价格更低，买入时输出更多
+        let venues = [
+            pool(DexType::PumpSwap, 100_000_000, 1_000_000_000),
+            pool(DexType::RaydiumCpmm, 90_000_000, 1_000_000_000),
+        ];
+        let router = Router::default();
+        let route = router.plan(&mint, &venues, 1_000_000, true).unwrap();
+        assert_eq!(route.legs.len(), 1);
+        assert_eq!(route.legs[0].venue.dex_type, DexType::RaydiumCpmm);
+
+        // 卖出时在价格更高的 PumpSwap 成交
+        let route = router.plan(&mint, &venues, 10_000_000, false).unwrap();
+        assert_eq!(route.legs[0].venue.dex_type, DexType::PumpSwap);
+
+        assert!(router.plan(&mint, &[], 1_000_000, true).is_err());
+        assert_eq!(venues[0].price(), 0.1);
+    }
+
+    #[test]
+    fn test_pumpswap_leg_carries_coin_creator() {
+        let mint = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+        let mut pumpswap = pool(DexType::PumpSwap, 100_000_000, 1_000_000_000);
+        if let Liquidity::ConstantProduct { coin_creator, .. } = &mut pumpswap.liquidity {
+            *coin_creator = Some(creator);
+        }
+        let raydium = pool(DexType::RaydiumCpmm, 100_000_000, 2_000_000_000);
+
+        // PumpSwap 报价更优，路由的这一段需要带上池子的 coin creator
+        let route = Router::default().plan(&mint, &[pumpswap, raydium], 10_000_000, false).unwrap();
+        assert_eq!(route.legs[0].venue.dex_type, DexType::PumpSwap);
+        assert_eq!(route.legs[0].venue.creator(), Some(creator));
+
+        let raydium = pool(DexType::RaydiumCpmm, 100_000_000, 1_000_000_000);
+        let route = Router::default().plan(&mint, &[raydium], 1_000_000, true).unwrap();
+        assert_eq!(route.legs[0].venue.creator(), None);
+    }
+
+    #[test]
+    fn test_plan_splits_large_orders() {
+        let mint = Pubkey::new_unique();
+        let venues = [
+            pool(DexType::PumpSwap, 100_000_000, 1_000_000_000),
+            pool(DexType::RaydiumCpmm, 100_000_000, 1_000_000_000),
+        ];
+        let router = Router::new(RouterConfig { max_splits: 2, ..Default::default() });
+        // 相对储备较大的订单拆到两个池子可降低价格冲击
+        let route = router.plan(&mint, &venues, 50_000_000, true).unwrap();
+        assert_eq!(route.legs.len(), 2);
+        assert_eq!(route.legs.iter().map(|leg| leg.amount_in).sum::<u64>(), 50_000_000);
+        let single = Router::default().plan(&mint, &venues, 50_000_000, true).unwrap();
+        assert!(route.expected_out() > single.expected_out());
+    }
+
+    #[test]
+    fn test_quotes_net_of_transfer_fee() {
+        use spl_token_2022::extension::transfer_fee::{TransferFee, TransferFeeConfig};
+
+        let mint = Pubkey::new_unique();
+        let spl = pool(DexType::RaydiumCpmm, 100_000_000, 1_000_000_000);
+        let mut token_2022 = spl.clone();
+        token_2022.mint_info = MintTokenInfo {
+            token_program: spl_token_2022::ID,
+            transfer_fee_config: Some(TransferFeeConfig {
+                newer_transfer_fee: TransferFee {
+                    transfer_fee_basis_points: 100.into(),
+                    maximum_fee: u64::MAX.into(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            epoch: 1,
+        };
+
+        // 买入到账数量扣除 1% 转账手续费
+        let amount_out = spl.buy_amount_out(1_000_000);
+        assert_eq!(token_2022.buy_amount_out(1_000_000), amount_out - amount_out.div_ceil(100));
+        // 卖出时池子只收到扣费后的代币
+        assert_eq!(token_2022.sell_amount_out(10_000_000), spl.sell_amount_out(9_900_000));
+
+        let route = Router::default().plan(&mint, &[token_2022], 1_000_000, true).unwrap();
+        assert_eq!(route.transfer_fee_basis_points, 100);
+        assert_eq!(route.expected_out(), amount_out - amount_out.div_ceil(100));
+        let route = Router::default().plan(&mint, &[spl], 1_000_000, true).unwrap();
+        assert_eq!(route.transfer_fee_basis_points, 0);
+    }
+}
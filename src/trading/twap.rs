@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::oneshot;
+
+use crate::swqos::TradeType;
+use crate::trading::factory::DexType;
+use crate::trading::SubmissionReport;
+
+/// 时间加权拆单配置：将一笔大额买入或卖出在 `duration_ms` 内拆成 `slices` 份依次执行
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TwapConfig {
+    pub slices: u32,
+    /// 第一份到最后一份之间的时间（毫秒）
+    pub duration_ms: u64,
+    /// 每份执行时间的随机偏移，占间隔的基点，最大 5000（半个间隔），避免被识别出固定节奏
+    pub jitter_basis_points: u64,
+    /// 每份的滑点上限（基点），None 时使用默认滑点
+    pub slice_slippage_basis_points: Option<u64>,
+    /// 某一份失败后是否放弃剩余份数，否则继续执行
+    pub stop_on_failure: bool,
+}
+
+impl Default for TwapConfig {
+    fn default() -> Self {
+        Self {
+            slices: 5,
+            duration_ms: 60_000,
+            jitter_basis_points: 2_000,
+            slice_slippage_basis_points: Some(300),
+            stop_on_failure: false,
+        }
+    }
+}
+
+/// 拆出的一份订单
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TwapSlice {
+    pub index: u32,
+    /// 买入为 SOL 数量（lamports），卖出为代币数量
+    pub amount: u64,
+    /// 相对开始时间的执行时间（毫秒）
+    pub delay_ms: u64,
+}
+
+/// 一份订单的执行结果
+#[derive(Debug, Clone)]
+pub struct TwapSliceOutcome {
+    pub slice: TwapSlice,
+    pub submission: SubmissionReport,
+    /// 执行失败时的错误，未执行（之前失败且 `stop_on_failure`）的份数不在结果中
+    pub error: Option<String>,
+}
+
+/// 拆分订单：数量均分，余数计入最后一份；执行时间按间隔均匀分布并加上随机偏移
+pub fn plan_twap(total_amount: u64, config: &TwapConfig) -> Result<Vec<TwapSlice>> {
+    plan_twap_with_rng(total_amount, config, &mut rand::rng())
+}
+
+/// 使用指定的随机数生成器拆分订单，便于复现
+pub fn plan_twap_with_rng(
+    total_amount: u64,
+    config: &TwapConfig,
+    rng: &mut impl Rng,
+) -> Result<Vec<TwapSlice>> {
+    if config.slices == 0 {
+        return Err(anyhow!("TWAP needs at least one slice"));
+    }
+    let slices = config.slices as u64;
+    if total_amount < slices {
+        return Err(anyhow!(
+            "Amount {} is too small to split into {} slices",
+            total_amount,
+            slices
+        ));
+    }
+    let slice_amount = total_amount / slices;
+    let interval = if slices > 1 { config.duration_ms / (slices - 1) } else { 0 };
+    let max_jitter = interval * config.jitter_basis_points.min(5_000) / 10_000;
+    Ok((0..slices)
+        .map(|index| {
+            let scheduled = index * interval;
+            let jitter = if max_jitter > 0 { rng.random_range(0..=2 * max_jitter) } else { 0 };
+            TwapSlice {
+                index: index as u32,
+                amount: if index == slices - 1 {
+                    total_amount - slice_amount * (slices - 1)
+                } else {
+                    slice_amount
+                },
+                // 第一份立即执行，之后的份数在计划时间前后偏移
+                delay_ms: if index == 0 {
+                    0
+                } else {
+                    (scheduled + jitter).saturating_sub(max_jitter)
+                },
+            }
+        })
+        .collect())
+}
+
+/// 执行中的 TWAP 订单
+#[derive(Debug, Clone, PartialEq)]
+pub struct TwapOrder {
+    pub id: u64,
+    pub dex_type: DexType,
+    pub mint: Pubkey,
+    pub trade_type: TradeType,
+    /// 买入为 SOL 数量（lamports），卖出为代币数量
+    pub total_amount: u64,
+    /// 尚未执行的数量
+    pub remaining_amount: u64,
+    pub slices: u32,
+    /// 已执行（含失败）的份数
+    pub executed_slices: u32,
+    pub started_at: DateTime<Utc>,
+}
+
+/// 执行中的 TWAP 订单登记，取消后剩余份数不再执行，正在执行的一份不受影响
+#[derive(Debug, Default)]
+pub struct TwapOrders {
+    next_id: AtomicU64,
+    orders: Mutex<HashMap<u64, (TwapOrder, oneshot::Sender<()>)>>,
+}
+
+impl TwapOrders {
+    /// 登记一笔订单，返回订单 id 和取消通知，订单被取消时接收端完成
+    pub fn start(
+        &self,
+        dex_type: DexType,
+        mint: Pubkey,
+        trade_type: TradeType,
+        slices: &[TwapSlice],
+    ) -> (u64, oneshot::Receiver<()>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let total_amount = slices.iter().map(|slice| slice.amount).sum();
+        let order = TwapOrder {
+            id,
+            dex_type,
+            mint,
+            trade_type,
+            total_amount,
+            remaining_amount: total_amount,
+            slices: slices.len() as u32,
+            executed_slices: 0,
+            started_at: Utc::now(),
+        };
+        let (sender, cancelled) = oneshot::channel();
+        self.orders.lock().unwrap().insert(id, (order, sender));
+        (id, cancelled)
+    }
+
+    /// 记录已执行的一份
+    pub fn record_slice(&self, id: u64, slice: &TwapSlice) {
+        if let Some((order, _)) = self.orders.lock().unwrap().get_mut(&id) {
+            order.executed_slices += 1;
+            order.remaining_amount = order.remaining_amount.saturating_sub(slice.amount);
+        }
+    }
+
+    /// 订单执行结束后移除
+    pub fn finish(&self, id: u64) {
+        self.orders.lock().unwrap().remove(&id);
+    }
+
+    /// 取消订单，订单不存在（已结束或已取消）时返回 None
+    pub fn cancel(&self, id: u64) -> Option<TwapOrder> {
+        // 丢弃发送端即通知执行循环
+        self.orders.lock().unwrap().remove(&id).map(|(order, _)| order)
+    }
+
+    /// 按 id 排序的执行中订单
+    pub fn list(&self) -> Vec<TwapOrder> {
+        let mut orders: Vec<TwapOrder> =
+            self.orders.lock().unwrap().values().map(|(order, _)| order.clone()).collect();
+        orders.sort_by_key(|order| order.id);
+        orders
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[tokio::test]
+    async fn test_twap_orders_cancel() {
+        let orders = TwapOrders::default();
+        let slices = plan_twap(1_000, &TwapConfig { slices: 4, ..Default::default() }).unwrap();
+        let mint = Pubkey::new_unique();
+        let (id, mut cancelled) = orders.start(DexType::PumpSwap, mint, TradeType::Buy, &slices);
+
+        orders.record_slice(id, &slices[0]);
+        let listed = orders.list();
+        assert_eq!(listed.len(), 1);
+        assert_eq!((listed[0].executed_slices, listed[0].remaining_amount), (1, 750));
+        assert_eq!(cancelled.try_recv(), Err(oneshot::error::TryRecvError::Empty));
+
+        assert_eq!(orders.cancel(id).unwrap().mint, mint);
+        assert!(cancelled.await.is_err());
+        assert!(orders.list().is_empty());
+        assert!(orders.cancel(id).is_none());
+    }
+
+    #[test]
+    fn test_plan_twap_splits_amount_and_time() {
+        let config = TwapConfig { slices: 4, duration_ms: 3_000, ..Default::default() };
+        let plan = plan_twap_with_rng(1_003, &config, &mut StdRng::seed_from_u64(7)).unwrap();
+
+        assert_eq!(plan.iter().map(|slice| slice.amount).collect::<Vec<_>>(), [250, 250, 250, 253]);
+        assert_eq!(plan[0].delay_ms, 0);
+        for (slice, scheduled) in plan.iter().zip([0u64, 1_000, 2_000, 3_000]).skip(1) {
+            // 间隔 1000 毫秒，偏移最多 ±200 毫秒
+            assert!(slice.delay_ms.abs_diff(scheduled) <= 200, "{:?}", slice);
+        }
+        assert!(plan.windows(2).all(|pair| pair[0].delay_ms < pair[1].delay_ms));
+
+        assert!(plan_twap(3, &config).is_err());
+        assert!(plan_twap(100, &TwapConfig { slices: 0, ..config }).is_err());
+        assert_eq!(plan_twap(100, &TwapConfig { slices: 1, ..config }).unwrap()[0].amount, 100);
+    }
+}